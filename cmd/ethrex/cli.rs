@@ -13,7 +13,10 @@ use ethrex_blockchain::{
     BlockchainOptions, BlockchainType, L2Config,
     error::{ChainError, InvalidBlockError},
 };
-use ethrex_common::types::{Block, DEFAULT_BUILDER_GAS_CEIL, Genesis, validate_block_body};
+use ethrex_common::{
+    Address, H256,
+    types::{Block, DEFAULT_BUILDER_GAS_CEIL, Genesis, validate_block_body},
+};
 use ethrex_p2p::{
     discv4::{peer_table::TARGET_PEERS, server::INITIAL_LOOKUP_INTERVAL_MS},
     sync::SyncMode,
@@ -21,7 +24,9 @@ use ethrex_p2p::{
     types::Node,
 };
 use ethrex_rlp::encode::RLPEncode;
+use ethrex_rpc::{DEFAULT_HTTP_API, RpcLimits, RpcNamespace};
 use ethrex_storage::error::StoreError;
+use reqwest::Url;
 use tokio_util::sync::CancellationToken;
 use tracing::{Level, error, info, warn};
 
@@ -138,6 +143,24 @@ pub struct Options {
         help_heading = "Node options"
     )]
     pub mempool_max_size: usize,
+    #[arg(
+        long = "parallel-execution",
+        action = ArgAction::SetTrue,
+        help = "Execute the transactions of each block optimistically in parallel",
+        long_help = "Transactions are executed speculatively in parallel and the ones that conflict with previous transactions of the block are re-executed, so the results are the same as with serial execution. Only affects L1 blocks.",
+        help_heading = "Node options",
+        env = "ETHREX_PARALLEL_EXECUTION"
+    )]
+    pub parallel_execution: bool,
+    #[arg(
+        long = "index-addresses",
+        action = ArgAction::SetTrue,
+        help = "Index the transactions each address took part in",
+        long_help = "Indexes the sender, recipient and every address called, created or self destructed by each transaction of the blocks executed, as the ots_searchTransactionsBefore, ots_searchTransactionsAfter, ots_getTransactionBySenderAndNonce and ots_getContractCreator endpoints need. Blocks stored while disabled aren't indexed.",
+        help_heading = "Node options",
+        env = "ETHREX_INDEX_ADDRESSES"
+    )]
+    pub index_addresses: bool,
     #[arg(
         long = "http.addr",
         default_value = "0.0.0.0",
@@ -156,6 +179,27 @@ pub struct Options {
         env = "ETHREX_HTTP_PORT"
     )]
     pub http_port: String,
+    #[arg(
+        long = "http.api",
+        default_value = "eth,net,web3,debug,txpool,trace",
+        value_name = "NAMESPACE_LIST",
+        value_delimiter = ',',
+        value_parser = utils::parse_rpc_namespace,
+        help = "Comma separated list of rpc namespaces served by the http rpc server.",
+        long_help = "Possible values: eth, net, web3, debug, txpool, trace, ots, admin. The admin namespace is served by the admin rpc server instead, only include it here if the http server isn't reachable from the outside. In L2 mode, the ethrex namespace is served along with eth.",
+        help_heading = "RPC options",
+        env = "ETHREX_HTTP_API"
+    )]
+    pub http_api: Vec<RpcNamespace>,
+    #[arg(
+        long = "http.deny-methods",
+        value_name = "METHOD_LIST",
+        value_delimiter = ',',
+        help = "Comma separated list of rpc methods disabled in the http rpc server, even if their namespace is enabled.",
+        help_heading = "RPC options",
+        env = "ETHREX_HTTP_DENY_METHODS"
+    )]
+    pub http_deny_methods: Vec<String>,
     #[arg(
         long = "ws.enabled",
         default_value = "false",
@@ -184,6 +228,124 @@ pub struct Options {
         env = "ETHREX_WS_PORT"
     )]
     pub ws_port: String,
+    #[arg(
+        long = "ws.api",
+        default_value = "eth,net,web3,debug,txpool,trace",
+        value_name = "NAMESPACE_LIST",
+        value_delimiter = ',',
+        value_parser = utils::parse_rpc_namespace,
+        requires = "ws_enabled",
+        help = "Comma separated list of rpc namespaces served by the websocket rpc server.",
+        long_help = "Possible values: eth, net, web3, debug, txpool, trace, ots, admin. The admin namespace is served by the admin rpc server instead, only include it here if the websocket server isn't reachable from the outside. In L2 mode, the ethrex namespace is served along with eth.",
+        help_heading = "RPC options",
+        env = "ETHREX_WS_API"
+    )]
+    pub ws_api: Vec<RpcNamespace>,
+    #[arg(
+        long = "ws.deny-methods",
+        value_name = "METHOD_LIST",
+        value_delimiter = ',',
+        requires = "ws_enabled",
+        help = "Comma separated list of rpc methods disabled in the websocket rpc server, even if their namespace is enabled.",
+        help_heading = "RPC options",
+        env = "ETHREX_WS_DENY_METHODS"
+    )]
+    pub ws_deny_methods: Vec<String>,
+    #[arg(
+        long = "admin.enabled",
+        default_value = "false",
+        help = "Enable the admin rpc server, which serves the admin namespace on the loopback interface only. Disabled by default.",
+        help_heading = "RPC options",
+        env = "ETHREX_ENABLE_ADMIN"
+    )]
+    pub admin_enabled: bool,
+    #[arg(
+        long = "admin.port",
+        default_value_t = 8550,
+        value_name = "PORT",
+        requires = "admin_enabled",
+        help = "Listening port for the admin rpc server.",
+        help_heading = "RPC options",
+        env = "ETHREX_ADMIN_PORT"
+    )]
+    pub admin_port: u16,
+    #[arg(
+        long = "ipc.path",
+        value_name = "IPC_PATH",
+        help = "Path of the unix socket of the IPC rpc server. Disabled by default.",
+        long_help = "The IPC rpc server serves every namespace but the engine one, admin included, so the socket should only be accessible to trusted users. Not supported in L2 mode.",
+        help_heading = "RPC options",
+        env = "ETHREX_IPC_PATH"
+    )]
+    pub ipc_path: Option<PathBuf>,
+    #[arg(
+        long = "rpc.batch-limit",
+        default_value_t = 1000,
+        value_name = "MAX_REQUESTS",
+        help = "Maximum amount of requests in a batch.",
+        help_heading = "RPC options"
+    )]
+    pub rpc_batch_limit: usize,
+    #[arg(
+        long = "rpc.response-size-limit",
+        default_value_t = 25 * 1024 * 1024,
+        value_name = "BYTES",
+        help = "Maximum size of a response, adding up every response of a batch.",
+        help_heading = "RPC options"
+    )]
+    pub rpc_response_size_limit: usize,
+    #[arg(
+        long = "rpc.rate-limit",
+        value_name = "TOKENS_PER_SECOND",
+        help = "Rate limit of each client of the http and websocket rpc servers. Disabled by default.",
+        long_help = "Each client gets a bucket of tokens refilled at this rate, every request takes the cost of its method from it. Most methods cost 1 token, see `--rpc.method-costs`.",
+        help_heading = "RPC options",
+        env = "ETHREX_RPC_RATE_LIMIT"
+    )]
+    pub rpc_rate_limit: Option<u32>,
+    #[arg(
+        long = "rpc.rate-limit-burst",
+        default_value_t = 200,
+        value_name = "TOKENS",
+        requires = "rpc_rate_limit",
+        help = "Maximum amount of tokens a client can accumulate, which caps the cost of the methods it can call.",
+        help_heading = "RPC options"
+    )]
+    pub rpc_rate_limit_burst: u32,
+    #[arg(
+        long = "rpc.method-costs",
+        value_name = "METHOD=COST_LIST",
+        value_delimiter = ',',
+        value_parser = utils::parse_method_cost,
+        help = "Comma separated list of rate limit costs of rpc methods, overriding the default ones.",
+        long_help = "Methods costing 20 tokens or more are considered expensive and count towards `--rpc.expensive-requests-limit`.",
+        help_heading = "RPC options"
+    )]
+    pub rpc_method_costs: Vec<(String, u32)>,
+    #[arg(
+        long = "rpc.logs-block-range-limit",
+        default_value_t = 10_000,
+        value_name = "BLOCKS",
+        help = "Maximum amount of blocks queried by eth_getLogs and log filters.",
+        help_heading = "RPC options"
+    )]
+    pub rpc_logs_block_range_limit: u64,
+    #[arg(
+        long = "rpc.logs-limit",
+        default_value_t = 10_000,
+        value_name = "MAX_LOGS",
+        help = "Maximum amount of logs returned by eth_getLogs and log filters.",
+        help_heading = "RPC options"
+    )]
+    pub rpc_logs_limit: usize,
+    #[arg(
+        long = "rpc.expensive-requests-limit",
+        default_value_t = 16,
+        value_name = "MAX_REQUESTS",
+        help = "Maximum amount of expensive requests, such as traces and logs queries, processed at the same time.",
+        help_heading = "RPC options"
+    )]
+    pub rpc_expensive_requests_limit: usize,
     #[arg(
         long = "authrpc.addr",
         default_value = "127.0.0.1",
@@ -208,6 +370,15 @@ pub struct Options {
         help_heading = "RPC options"
     )]
     pub authrpc_jwtsecret: String,
+    #[arg(
+        long = "authrpc.ipc-path",
+        value_name = "IPC_PATH",
+        help = "Path of the unix socket of the authenticated IPC rpc server. Disabled by default.",
+        long_help = "Serves the same methods as the authenticated rpc server. Requests sent through the socket aren't authenticated with the jwt secret, instead the socket is only accessible to the user running the node. Not supported in L2 mode, as the L2 doesn't serve the engine API.",
+        help_heading = "RPC options",
+        env = "ETHREX_AUTHRPC_IPC_PATH"
+    )]
+    pub authrpc_ipc_path: Option<PathBuf>,
     #[arg(long = "p2p.disabled", default_value = "false", value_name = "P2P_DISABLED", action = ArgAction::SetTrue, help_heading = "P2P options")]
     pub p2p_disabled: bool,
     #[arg(
@@ -273,6 +444,53 @@ pub struct Options {
         help_heading = "Block building options"
     )]
     pub gas_limit: u64,
+    #[arg(
+        long = "builder.relay-url",
+        value_name = "URL",
+        help = "URL of the relay to submit the blocks built to. Disabled by default.",
+        long_help = "Bids every block built for a slot whose proposer is registered to the relay, rebuilding it with the fee recipient and gas limit of the proposer's registration when they differ. Requires the consensus client to send payload attributes every slot.",
+        help_heading = "Block building options",
+        requires = "builder_secret_key",
+        env = "ETHREX_BUILDER_RELAY_URL"
+    )]
+    pub builder_relay_url: Option<Url>,
+    #[arg(
+        long = "builder.secret-key",
+        value_name = "SECRET_KEY",
+        value_parser = utils::parse_bls_secret_key,
+        help = "BLS secret key signing the bids submitted to the relay.",
+        help_heading = "Block building options",
+        env = "ETHREX_BUILDER_SECRET_KEY"
+    )]
+    pub builder_secret_key: Option<H256>,
+    #[arg(
+        long = "builder.genesis-fork-version",
+        value_name = "FORK_VERSION",
+        value_parser = utils::parse_fork_version,
+        help = "Genesis fork version of the beacon chain, used in the bids signing domain.",
+        long_help = "Only needed for networks other than mainnet, sepolia, holesky and hoodi.",
+        help_heading = "Block building options"
+    )]
+    pub builder_genesis_fork_version: Option<[u8; 4]>,
+    #[arg(
+        long = "builder.genesis-time",
+        value_name = "TIMESTAMP",
+        help = "Genesis time of the beacon chain, used to compute the slot of the blocks built.",
+        long_help = "Only needed for networks other than mainnet, sepolia, holesky and hoodi.",
+        help_heading = "Block building options"
+    )]
+    pub builder_genesis_time: Option<u64>,
+    #[arg(
+        long = "builder.bundle-signers",
+        value_name = "ADDRESS_LIST",
+        value_delimiter = ',',
+        help = "Comma separated list of the searchers allowed to send bundles. Any searcher is allowed by default.",
+        long_help = "Bundles are only accepted through eth_sendBundle and eth_callBundle when submitting blocks to a relay, and their http requests must be signed in the X-Flashbots-Signature header. When set, only requests signed by these addresses are accepted.",
+        help_heading = "Block building options",
+        requires = "builder_relay_url",
+        env = "ETHREX_BUILDER_BUNDLE_SIGNERS"
+    )]
+    pub builder_bundle_signers: Vec<Address>,
 }
 
 impl Options {
@@ -317,17 +535,34 @@ impl Options {
 
 impl Default for Options {
     fn default() -> Self {
+        let limits = RpcLimits::default();
         Self {
             http_addr: Default::default(),
             http_port: Default::default(),
+            http_api: DEFAULT_HTTP_API.to_vec(),
+            http_deny_methods: Default::default(),
             ws_enabled: false,
             ws_addr: Default::default(),
             ws_port: Default::default(),
+            ws_api: DEFAULT_HTTP_API.to_vec(),
+            ws_deny_methods: Default::default(),
+            admin_enabled: false,
+            admin_port: 8550,
+            ipc_path: None,
+            rpc_batch_limit: limits.max_batch_size,
+            rpc_response_size_limit: limits.max_response_size,
+            rpc_rate_limit: None,
+            rpc_rate_limit_burst: 200,
+            rpc_method_costs: Vec::new(),
+            rpc_logs_block_range_limit: limits.logs.max_block_range,
+            rpc_logs_limit: limits.logs.max_logs,
+            rpc_expensive_requests_limit: limits.max_expensive_in_flight,
             log_level: Level::INFO,
             log_color: Default::default(),
             authrpc_addr: Default::default(),
             authrpc_port: Default::default(),
             authrpc_jwtsecret: Default::default(),
+            authrpc_ipc_path: None,
             p2p_disabled: Default::default(),
             p2p_addr: None,
             p2p_port: Default::default(),
@@ -342,11 +577,18 @@ impl Default for Options {
             dev: Default::default(),
             force: false,
             mempool_max_size: Default::default(),
+            parallel_execution: false,
+            index_addresses: false,
             tx_broadcasting_time_interval: Default::default(),
             target_peers: Default::default(),
             lookup_interval: Default::default(),
             extra_data: get_minimal_client_version(),
             gas_limit: DEFAULT_BUILDER_GAS_CEIL,
+            builder_relay_url: None,
+            builder_secret_key: None,
+            builder_genesis_fork_version: None,
+            builder_genesis_time: None,
+            builder_bundle_signers: Vec::new(),
         }
     }
 }
@@ -465,6 +707,8 @@ impl Subcommand {
                     BlockchainOptions {
                         max_mempool_size: opts.mempool_max_size,
                         r#type: blockchain_type,
+                        parallel_execution: opts.parallel_execution,
+                        index_addresses: opts.index_addresses,
                         ..Default::default()
                     },
                 )
@@ -490,6 +734,7 @@ impl Subcommand {
                     BlockchainOptions {
                         r#type: blockchain_type,
                         perf_logs_enabled: true,
+                        parallel_execution: opts.parallel_execution,
                         ..Default::default()
                     },
                 )
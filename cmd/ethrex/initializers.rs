@@ -5,7 +5,7 @@ use crate::{
         read_jwtsecret_file, read_node_config_file,
     },
 };
-use ethrex_blockchain::{Blockchain, BlockchainOptions, BlockchainType};
+use ethrex_blockchain::{Blockchain, BlockchainOptions, BlockchainType, bundle::BundlePoolOptions};
 use ethrex_common::fd_limit::raise_fd_limit;
 use ethrex_common::types::Genesis;
 use ethrex_config::networks::Network;
@@ -22,9 +22,13 @@ use ethrex_p2p::{
     types::{Node, NodeRecord},
     utils::public_key_from_signing_key,
 };
+use ethrex_rpc::{
+    BlockBuilderConfig, LogsLimits, RateLimit, RpcApiFilter, RpcLimits, RpcNamespace,
+};
 use ethrex_storage::{EngineType, Store, error::StoreError};
 use local_ip_address::{local_ip, local_ipv6};
 use rand::rngs::OsRng;
+use reqwest::Url;
 use secp256k1::SecretKey;
 #[cfg(feature = "sync-test")]
 use std::env;
@@ -164,10 +168,26 @@ pub async fn init_rpc_api(
         None
     };
 
+    let http_socket_addr = get_http_socket_addr(opts);
+    if opts.http_api.contains(&RpcNamespace::Admin) && !http_socket_addr.ip().is_loopback() {
+        warn!(
+            "The admin namespace is served by the http rpc server at {http_socket_addr}, consider using the admin rpc server instead"
+        );
+    }
+    let http_api = RpcApiFilter::new(opts.http_api.clone(), opts.http_deny_methods.clone());
+    let ws_api = RpcApiFilter::new(opts.ws_api.clone(), opts.ws_deny_methods.clone());
+    let admin_port = opts.admin_enabled.then_some(opts.admin_port);
+
     let rpc_api = ethrex_rpc::start_api(
-        get_http_socket_addr(opts),
+        http_socket_addr,
+        http_api,
         ws_socket_opts,
+        ws_api,
+        admin_port,
+        opts.ipc_path.clone(),
+        get_rpc_limits(opts),
         get_authrpc_socket_addr(opts),
+        opts.authrpc_ipc_path.clone(),
         store,
         blockchain,
         read_jwtsecret_file(&opts.authrpc_jwtsecret),
@@ -244,6 +264,45 @@ pub async fn init_dev_network(opts: &Options, store: &Store, tracker: TaskTracke
     tracker.spawn(block_producer_engine);
 }
 
+pub fn init_block_builder(
+    opts: &Options,
+    network: &Network,
+    relay_url: Url,
+    blockchain: Arc<Blockchain>,
+    store: Store,
+    tracker: TaskTracker,
+) -> eyre::Result<()> {
+    let public_network = match network {
+        Network::PublicNetwork(public_network) => Some(*public_network),
+        _ => None,
+    };
+    let genesis_fork_version = opts
+        .builder_genesis_fork_version
+        .or(public_network.map(|network| network.genesis_fork_version()))
+        .ok_or_else(|| eyre::eyre!("--builder.genesis-fork-version is required for {network}"))?;
+    let genesis_time = opts
+        .builder_genesis_time
+        .or(public_network.map(|network| network.beacon_genesis_time()))
+        .ok_or_else(|| eyre::eyre!("--builder.genesis-time is required for {network}"))?;
+    let secret_key = opts
+        .builder_secret_key
+        .ok_or_else(|| eyre::eyre!("--builder.secret-key is required to submit blocks"))?;
+
+    info!("Submitting blocks built to relay {relay_url}");
+    let block_builder = ethrex_rpc::start_block_builder(
+        blockchain,
+        store,
+        BlockBuilderConfig {
+            relay_url,
+            secret_key,
+            genesis_fork_version,
+            genesis_time,
+        },
+    )?;
+    tracker.spawn(block_builder);
+    Ok(())
+}
+
 pub fn get_network(opts: &Options) -> Network {
     let default = if opts.dev {
         Network::LocalDevnet
@@ -351,6 +410,23 @@ pub fn get_http_socket_addr(opts: &Options) -> SocketAddr {
         .expect("Failed to parse http address and port")
 }
 
+pub fn get_rpc_limits(opts: &Options) -> RpcLimits {
+    RpcLimits {
+        max_batch_size: opts.rpc_batch_limit,
+        max_response_size: opts.rpc_response_size_limit,
+        rate_limit: opts.rpc_rate_limit.map(|tokens_per_second| RateLimit {
+            tokens_per_second,
+            burst: opts.rpc_rate_limit_burst,
+        }),
+        method_costs: opts.rpc_method_costs.iter().cloned().collect(),
+        logs: LogsLimits {
+            max_block_range: opts.rpc_logs_block_range_limit,
+            max_logs: opts.rpc_logs_limit,
+        },
+        max_expensive_in_flight: opts.rpc_expensive_requests_limit,
+    }
+}
+
 pub fn get_ws_socket_addr(opts: &Options) -> SocketAddr {
     parse_socket_addr(&opts.ws_addr, &opts.ws_port)
         .expect("Failed to parse websocket address and port")
@@ -418,6 +494,12 @@ pub async fn init_l1(
             max_mempool_size: opts.mempool_max_size,
             perf_logs_enabled: true,
             r#type: BlockchainType::L1,
+            parallel_execution: opts.parallel_execution,
+            index_addresses: opts.index_addresses,
+            // Bundles are only worth simulating when the payloads built are bid to a relay
+            bundles: opts.builder_relay_url.is_some().then(|| BundlePoolOptions {
+                allowed_signers: opts.builder_bundle_signers.clone(),
+            }),
         },
     );
 
@@ -471,6 +553,17 @@ pub async fn init_l1(
         init_metrics(&opts, tracker.clone());
     }
 
+    if let Some(relay_url) = &opts.builder_relay_url {
+        init_block_builder(
+            &opts,
+            &network,
+            relay_url.clone(),
+            blockchain.clone(),
+            store.clone(),
+            tracker.clone(),
+        )?;
+    }
+
     if opts.dev {
         #[cfg(feature = "dev")]
         init_dev_network(&opts, &store, tracker.clone()).await;
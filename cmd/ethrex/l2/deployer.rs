@@ -372,6 +372,34 @@ pub struct DeployerOptions {
         help = "This address will be registered as an initial fee token"
     )]
     pub initial_fee_token: Option<Address>,
+    #[arg(
+        long = "proof-policy.min-proofs",
+        default_value = "0",
+        value_name = "AMOUNT",
+        env = "ETHREX_DEPLOYER_PROOF_POLICY_MIN_PROOFS",
+        help_heading = "Deployer options",
+        help = "Amount of the required proof systems a batch has to be verified with, any combination of them unless restricted with --proof-policy.immediate-proofs. The remaining required proofs can be submitted later, within the upgrade window. Defaults to every required proof system."
+    )]
+    pub proof_policy_min_proofs: u8,
+    #[arg(
+        long = "proof-policy.immediate-proofs",
+        value_name = "PROVER_TYPES",
+        value_delimiter = ',',
+        value_parser = parse_immediate_proof_type,
+        env = "ETHREX_DEPLOYER_PROOF_POLICY_IMMEDIATE_PROOFS",
+        help_heading = "Deployer options",
+        help = "Comma separated list of the proof systems (risc0, sp1, tdx) that have to be among the proofs a batch is verified with, counting towards --proof-policy.min-proofs. The remaining required proofs can be submitted later, within the upgrade window."
+    )]
+    pub proof_policy_immediate_proofs: Vec<ProverType>,
+    #[arg(
+        long = "proof-policy.upgrade-window",
+        default_value = "0",
+        value_name = "SECONDS",
+        env = "ETHREX_DEPLOYER_PROOF_POLICY_UPGRADE_WINDOW",
+        help_heading = "Deployer options",
+        help = "Time, in seconds, the remaining proofs of a batch verified with only its immediate proofs can take. Zero means they're never needed."
+    )]
+    pub proof_policy_upgrade_window: u64,
 }
 
 impl Default for DeployerOptions {
@@ -453,6 +481,9 @@ impl Default for DeployerOptions {
             router: None,
             deploy_router: false,
             initial_fee_token: None,
+            proof_policy_min_proofs: 0,
+            proof_policy_immediate_proofs: vec![],
+            proof_policy_upgrade_window: 0,
         }
     }
 }
@@ -461,6 +492,17 @@ pub fn parse_private_key(s: &str) -> eyre::Result<SecretKey> {
     Ok(SecretKey::from_slice(&parse_hex(s)?)?)
 }
 
+pub fn parse_immediate_proof_type(s: &str) -> eyre::Result<ProverType> {
+    match s.to_lowercase().as_str() {
+        "risc0" => Ok(ProverType::RISC0),
+        "sp1" => Ok(ProverType::SP1),
+        "tdx" => Ok(ProverType::TDX),
+        _ => Err(eyre::eyre!(
+            "Invalid proof system {s}, expected one of: risc0, sp1, tdx"
+        )),
+    }
+}
+
 pub fn parse_hex(s: &str) -> eyre::Result<Bytes, FromHexError> {
     match s.strip_prefix("0x") {
         Some(s) => hex::decode(s).map(Into::into),
@@ -544,6 +586,7 @@ const INITIALIZE_ON_CHAIN_PROPOSER_SIGNATURE_BASED: &str = "initialize(bool,addr
 const INITIALIZE_ON_CHAIN_PROPOSER_SIGNATURE: &str = "initialize(bool,address,bool,bool,bool,bool,address,address,address,address,bytes32,bytes32,bytes32,bytes32,address[],uint256)";
 
 const INITIALIZE_BRIDGE_ADDRESS_SIGNATURE: &str = "initializeBridgeAddress(address)";
+const SET_PROOF_POLICY_SIGNATURE: &str = "setProofPolicy(uint8,uint8,uint256)";
 const TRANSFER_OWNERSHIP_SIGNATURE: &str = "transferOwnership(address)";
 const ACCEPT_OWNERSHIP_SIGNATURE: &str = "acceptOwnership()";
 const BRIDGE_INITIALIZER_SIGNATURE: &str = "initialize(address,address,uint256,address, uint256)";
@@ -1002,6 +1045,71 @@ fn read_vk(path: &str) -> Result<Bytes, DeployerError> {
     Ok(Bytes::from(decoded))
 }
 
+/// Sets how many, and which, proof systems a batch has to be verified with before its remaining proofs.
+/// Has to be sent by the OnChainProposer owner, before any batch is verified optimistically.
+async fn set_proof_policy(
+    contract_addresses: &ContractAddresses,
+    eth_client: &EthClient,
+    opts: &DeployerOptions,
+    initializer: &Signer,
+    gas_price: u64,
+) -> Result<H256, DeployerError> {
+    let required_proofs = [opts.risc0, opts.sp1, opts.tdx]
+        .into_iter()
+        .filter(|required| *required)
+        .count();
+    if usize::from(opts.proof_policy_min_proofs) > required_proofs {
+        return Err(DeployerError::InternalError(format!(
+            "The proof policy requires {} proofs, but the OnChainProposer only requires {required_proofs} proof systems",
+            opts.proof_policy_min_proofs
+        )));
+    }
+
+    let mut immediate_proofs = 0u8;
+    for prover_type in &opts.proof_policy_immediate_proofs {
+        let required = match prover_type {
+            ProverType::RISC0 => opts.risc0,
+            ProverType::SP1 => opts.sp1,
+            ProverType::TDX => opts.tdx,
+            ProverType::Exec => false,
+        };
+        let bit = prover_type.proof_bit().filter(|_| required).ok_or_else(|| {
+            DeployerError::InternalError(format!(
+                "The proof policy requires {prover_type} proofs, which are not required by the OnChainProposer"
+            ))
+        })?;
+        immediate_proofs |= bit;
+    }
+
+    let initializer_nonce = eth_client
+        .get_nonce(
+            initializer.address(),
+            BlockIdentifier::Tag(BlockTag::Pending),
+        )
+        .await?;
+    let calldata_values = vec![
+        Value::Uint(opts.proof_policy_min_proofs.into()),
+        Value::Uint(immediate_proofs.into()),
+        Value::Uint(opts.proof_policy_upgrade_window.into()),
+    ];
+    let set_proof_policy_calldata = encode_calldata(SET_PROOF_POLICY_SIGNATURE, &calldata_values)?;
+
+    Ok(initialize_contract_no_wait(
+        contract_addresses.on_chain_proposer_address,
+        set_proof_policy_calldata,
+        initializer,
+        eth_client,
+        Overrides {
+            nonce: Some(initializer_nonce),
+            gas_limit: Some(TRANSACTION_GAS_LIMIT),
+            max_fee_per_gas: Some(gas_price),
+            max_priority_fee_per_gas: Some(gas_price),
+            ..Default::default()
+        },
+    )
+    .await?)
+}
+
 async fn initialize_contracts(
     contract_addresses: ContractAddresses,
     eth_client: &EthClient,
@@ -1230,6 +1338,31 @@ async fn initialize_contracts(
 
     tx_hashes.push(initialize_bridge_address_tx_hash);
 
+    if opts.proof_policy_min_proofs != 0
+        || !opts.proof_policy_immediate_proofs.is_empty()
+        || opts.proof_policy_upgrade_window != 0
+    {
+        if opts.deploy_based_contracts {
+            warn!(
+                "The based OnChainProposer has no proof policy, ignoring the proof policy options"
+            );
+        } else {
+            let set_proof_policy_tx_hash = set_proof_policy(
+                &contract_addresses,
+                eth_client,
+                opts,
+                initializer,
+                gas_price,
+            )
+            .await?;
+            info!(
+                tx_hash = %format!("{set_proof_policy_tx_hash:#x}"),
+                "OnChainProposer proof policy set"
+            );
+            tx_hashes.push(set_proof_policy_tx_hash);
+        }
+    }
+
     if opts.on_chain_proposer_owner != initializer.address() {
         let initializer_nonce = eth_client
             .get_nonce(
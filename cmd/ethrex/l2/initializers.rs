@@ -1,8 +1,10 @@
 use crate::cli::Options as L1Options;
 use crate::initializers::{
     self, get_authrpc_socket_addr, get_http_socket_addr, get_local_node_record, get_local_p2p_node,
-    get_network, get_signer, init_blockchain, init_network, init_store,
+    get_network, get_rpc_limits, get_signer, get_ws_socket_addr, init_blockchain, init_network,
+    init_store,
 };
+use crate::l2::options::parse_signer;
 use crate::l2::{L2Options, SequencerOptions};
 use crate::utils::{
     NodeConfigFile, get_client_version, init_datadir, read_jwtsecret_file, store_node_config_file,
@@ -14,18 +16,22 @@ use ethrex_common::{Address, types::DEFAULT_BUILDER_GAS_CEIL};
 use ethrex_l2::sequencer::block_producer;
 use ethrex_l2::sequencer::l1_committer;
 use ethrex_l2::sequencer::l1_committer::regenerate_head_state;
+use ethrex_l2_rpc::l2::preconfirmations::PreconfirmationFeed;
 use ethrex_p2p::{
     discv4::peer_table::PeerTable,
     network::P2PContext,
     peer_handler::PeerHandler,
-    rlpx::{initiator::RLPxInitiator, l2::l2_connection::P2PBasedContext},
+    rlpx::{
+        initiator::RLPxInitiator,
+        l2::l2_connection::{LeadSequencer, P2PBasedContext},
+    },
     sync_manager::SyncManager,
     types::{Node, NodeRecord},
 };
+use ethrex_rpc::{RpcApiFilter, RpcNamespace};
 use ethrex_storage::Store;
 use ethrex_storage_rollup::{EngineTypeRollup, StoreRollup};
 use eyre::OptionExt;
-use secp256k1::SecretKey;
 use spawned_concurrency::tasks::GenServerHandle;
 use std::{fs::read_to_string, path::Path, sync::Arc, time::Duration};
 use tokio::task::JoinSet;
@@ -49,11 +55,32 @@ fn init_rpc_api(
     rollup_store: StoreRollup,
     log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
     gas_ceil: Option<u64>,
+    preconfirmation_feed: Option<PreconfirmationFeed>,
 ) {
     init_datadir(&opts.datadir);
 
+    let ws_socket_opts = if opts.ws_enabled {
+        Some(get_ws_socket_addr(opts))
+    } else {
+        None
+    };
+
+    let http_socket_addr = get_http_socket_addr(opts);
+    if opts.http_api.contains(&RpcNamespace::Admin) && !http_socket_addr.ip().is_loopback() {
+        warn!(
+            "The admin namespace is served by the http rpc server at {http_socket_addr}, consider using the admin rpc server instead"
+        );
+    }
+    let http_api = RpcApiFilter::new(opts.http_api.clone(), opts.http_deny_methods.clone());
+    let ws_api = RpcApiFilter::new(opts.ws_api.clone(), opts.ws_deny_methods.clone());
+    let admin_port = opts.admin_enabled.then_some(opts.admin_port);
+
     let rpc_api = ethrex_l2_rpc::start_api(
-        get_http_socket_addr(opts),
+        http_socket_addr,
+        http_api,
+        ws_socket_opts,
+        ws_api,
+        admin_port,
         get_authrpc_socket_addr(opts),
         store,
         blockchain,
@@ -68,6 +95,8 @@ fn init_rpc_api(
         rollup_store,
         log_filter_handler,
         gas_ceil.unwrap_or(DEFAULT_BUILDER_GAS_CEIL),
+        preconfirmation_feed,
+        get_rpc_limits(opts),
     );
 
     tracker.spawn(rpc_api);
@@ -162,6 +191,15 @@ pub async fn init_l2(
     opts: L2Options,
     log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
 ) -> eyre::Result<()> {
+    // The L2 rpc server is only served over http and websockets
+    if opts.node_opts.ipc_path.is_some() {
+        return Err(eyre::eyre!("--ipc.path is not supported in L2 mode"));
+    }
+    if opts.node_opts.authrpc_ipc_path.is_some() {
+        return Err(eyre::eyre!(
+            "--authrpc.ipc-path is not supported in L2 mode"
+        ));
+    }
     raise_fd_limit()?;
     let datadir = opts.node_opts.datadir.clone();
     init_datadir(&opts.node_opts.datadir);
@@ -199,6 +237,9 @@ pub async fn init_l2(
         max_mempool_size: opts.node_opts.mempool_max_size,
         r#type: BlockchainType::L2(l2_config),
         perf_logs_enabled: true,
+        parallel_execution: opts.node_opts.parallel_execution,
+        bundles: None,
+        index_addresses: opts.node_opts.index_addresses,
     };
 
     let blockchain = init_blockchain(store.clone(), blockchain_opts.clone());
@@ -217,9 +258,13 @@ pub async fn init_l2(
 
     let cancel_token = tokio_util::sync::CancellationToken::new();
 
-    let based = opts.sequencer_opts.based;
+    // Based sequencers and high availability standbys follow the lead sequencer's blocks over p2p
+    let p2p_enabled =
+        opts.sequencer_opts.based || opts.sequencer_opts.high_availability_opts.ha_enabled;
+
+    let lead_sequencer = get_lead_sequencer(&opts.sequencer_opts)?;
 
-    let (peer_handler, syncer) = if based {
+    let (peer_handler, syncer) = if p2p_enabled {
         let peer_table = PeerTable::spawn(opts.node_opts.target_peers);
         let p2p_context = P2PContext::new(
             local_p2p_node.clone(),
@@ -232,19 +277,7 @@ pub async fn init_l2(
             #[cfg(feature = "l2")]
             Some(P2PBasedContext {
                 store_rollup: rollup_store.clone(),
-                // TODO: The Web3Signer refactor introduced a limitation where the committer key cannot be accessed directly because the signer could be either Local or Remote.
-                // The Signer enum cannot be used in the P2PBasedContext struct due to cyclic dependencies between the l2-rpc and p2p crates.
-                // As a temporary solution, a dummy committer key is used until a proper mechanism to utilize the Signer enum is implemented.
-                // This should be replaced with the Signer enum once the refactor is complete.
-                committer_key: Arc::new(
-                    SecretKey::from_slice(
-                        &hex::decode(
-                            "385c546456b6a603a1cfcaa9ec9494ba4832da08dd6bcf4de9a71e4a01b74924",
-                        )
-                        .expect("Invalid committer key"),
-                    )
-                    .expect("Failed to create committer key"),
-                ),
+                lead_sequencer: lead_sequencer.clone(),
             }),
             opts.node_opts.tx_broadcasting_time_interval,
             opts.node_opts.lookup_interval,
@@ -283,6 +316,8 @@ pub async fn init_l2(
         (None, None)
     };
 
+    let preconfirmation_feed = get_preconfirmation_feed(&opts.sequencer_opts)?;
+
     init_rpc_api(
         &opts.node_opts,
         &opts,
@@ -296,6 +331,7 @@ pub async fn init_l2(
         rollup_store.clone(),
         log_filter_handler,
         Some(opts.sequencer_opts.block_producer_opts.block_gas_limit),
+        preconfirmation_feed.clone(),
     );
 
     // Initialize metrics if enabled
@@ -318,6 +354,8 @@ pub async fn init_l2(
         l2_url,
         genesis,
         checkpoints_dir,
+        preconfirmation_feed,
+        lead_sequencer,
     )
     .await?;
     join_set.spawn(l2_sequencer);
@@ -338,7 +376,7 @@ pub async fn init_l2(
     let node_config_path = datadir.join("node_config.json");
     info!(path = %node_config_path.display(), "Storing node config");
     cancel_token.cancel();
-    if based {
+    if p2p_enabled {
         let peer_handler = peer_handler.ok_or_eyre("Peer handler not initialized")?;
         let node_config = NodeConfigFile::new(peer_handler.peer_table, local_node_record).await;
         store_node_config_file(node_config, node_config_path);
@@ -348,6 +386,38 @@ pub async fn init_l2(
     Ok(())
 }
 
+/// Preconfirmations are signed with the committer key, which identifies the sequencer on L1.
+fn get_preconfirmation_feed(
+    sequencer_opts: &SequencerOptions,
+) -> eyre::Result<Option<PreconfirmationFeed>> {
+    if !sequencer_opts.block_producer_opts.preconfirmations {
+        return Ok(None);
+    }
+    let committer_opts = &sequencer_opts.committer_opts;
+    let signer = parse_signer(
+        committer_opts.committer_l1_private_key,
+        committer_opts.committer_remote_signer_url.clone(),
+        committer_opts.committer_remote_signer_public_key,
+    )?;
+    Ok(Some(PreconfirmationFeed::new(signer.address())))
+}
+
+/// In high availability mode every sequencer of the cluster commits with the
+/// same signer, so it is the lead sequencer from the start. Based sequencers
+/// learn it from the sequencer registry once the `StateUpdater` starts.
+fn get_lead_sequencer(sequencer_opts: &SequencerOptions) -> eyre::Result<LeadSequencer> {
+    if !sequencer_opts.high_availability_opts.ha_enabled {
+        return Ok(LeadSequencer::default());
+    }
+    let committer_opts = &sequencer_opts.committer_opts;
+    let signer = parse_signer(
+        committer_opts.committer_l1_private_key,
+        committer_opts.committer_remote_signer_url.clone(),
+        committer_opts.committer_remote_signer_public_key,
+    )?;
+    Ok(LeadSequencer::new(Some(signer.address())))
+}
+
 pub fn get_l1_fee_config(sequencer_opts: &SequencerOptions) -> Option<L1FeeConfig> {
     if sequencer_opts.based {
         // If based is enabled, skip L1 fee configuration
@@ -8,7 +8,7 @@ use ethrex_l2::{
     BasedConfig, BlockFetcherConfig, BlockProducerConfig, CommitterConfig, EthConfig,
     L1WatcherConfig, ProofCoordinatorConfig, SequencerConfig, StateUpdaterConfig,
     sequencer::{
-        configs::{AdminConfig, AlignedConfig, MonitorConfig},
+        configs::{AdminConfig, AlignedConfig, HighAvailabilityConfig, MonitorConfig},
         utils::resolve_aligned_network,
     },
 };
@@ -21,6 +21,7 @@ use reqwest::Url;
 use secp256k1::{PublicKey, SecretKey};
 use std::{
     net::{IpAddr, Ipv4Addr},
+    path::PathBuf,
     str::FromStr,
 };
 use tracing::Level;
@@ -80,6 +81,8 @@ pub struct SequencerOptions {
     pub monitor_opts: MonitorOptions,
     #[command(flatten)]
     pub admin_opts: AdminOptions,
+    #[command(flatten)]
+    pub high_availability_opts: HighAvailabilityOptions,
     #[arg(
         long = "validium",
         default_value = "false",
@@ -137,6 +140,10 @@ pub enum SequencerOptionsError {
     NoOnChainProposerAddress,
     #[error("No bridge address was provided")]
     NoBridgeAddress,
+    #[error("No node id was provided for high availability mode")]
+    NoHighAvailabilityNodeId,
+    #[error("No lease file was provided for high availability mode")]
+    NoHighAvailabilityLeaseFile,
 }
 
 impl TryFrom<SequencerOptions> for SequencerConfig {
@@ -166,6 +173,7 @@ impl TryFrom<SequencerOptions> for SequencerConfig {
                 operator_fee_vault_address: opts.block_producer_opts.operator_fee_vault_address,
                 elasticity_multiplier: opts.block_producer_opts.elasticity_multiplier,
                 block_gas_limit: opts.block_producer_opts.block_gas_limit,
+                flashblock_interval_ms: opts.block_producer_opts.flashblock_interval_ms,
             },
             l1_committer: CommitterConfig {
                 on_chain_proposer_address: opts
@@ -214,6 +222,9 @@ impl TryFrom<SequencerOptions> for SequencerConfig {
                     .proof_coordinator_tdx_private_key,
                 qpl_tool_path: opts.proof_coordinator_opts.proof_coordinator_qpl_tool_path,
                 validium: opts.validium,
+                aggregation: opts.proof_coordinator_opts.aggregation,
+                aggregation_size: opts.proof_coordinator_opts.aggregation_size,
+                aggregation_timeout_ms: opts.proof_coordinator_opts.aggregation_timeout_ms,
             },
             based: BasedConfig {
                 enabled: opts.based,
@@ -248,6 +259,26 @@ impl TryFrom<SequencerOptions> for SequencerConfig {
                 listen_ip: opts.admin_opts.admin_listen_ip,
                 listen_port: opts.admin_opts.admin_listen_port,
             },
+            high_availability: HighAvailabilityConfig {
+                enabled: opts.high_availability_opts.ha_enabled,
+                node_id: if opts.high_availability_opts.ha_enabled {
+                    opts.high_availability_opts
+                        .ha_node_id
+                        .ok_or(SequencerOptionsError::NoHighAvailabilityNodeId)?
+                } else {
+                    opts.high_availability_opts.ha_node_id.unwrap_or_default()
+                },
+                lease_file: if opts.high_availability_opts.ha_enabled {
+                    opts.high_availability_opts
+                        .ha_lease_file
+                        .ok_or(SequencerOptionsError::NoHighAvailabilityLeaseFile)?
+                } else {
+                    opts.high_availability_opts
+                        .ha_lease_file
+                        .unwrap_or_default()
+                },
+                lease_ttl_ms: opts.high_availability_opts.ha_lease_ttl_ms,
+            },
         })
     }
 }
@@ -281,6 +312,8 @@ impl SequencerOptions {
         self.monitor_opts
             .populate_with_defaults(&defaults.monitor_opts);
         // admin_opts contains only non-optional fields.
+        self.high_availability_opts
+            .populate_with_defaults(&defaults.high_availability_opts);
     }
 }
 
@@ -535,6 +568,24 @@ pub struct BlockProducerOptions {
         help_heading = "Block producer options"
     )]
     pub block_gas_limit: u64,
+    #[arg(
+        long = "block-producer.preconfirmations",
+        default_value = "false",
+        value_name = "BOOLEAN",
+        env = "ETHREX_BLOCK_PRODUCER_PRECONFIRMATIONS",
+        help = "Sign a preconfirmation for every transaction included by the block producer and stream flashblocks over the websocket RPC.",
+        help_heading = "Block producer options"
+    )]
+    pub preconfirmations: bool,
+    #[arg(
+        long = "block-producer.flashblock-interval",
+        default_value = "250",
+        value_name = "UINT64",
+        env = "ETHREX_BLOCK_PRODUCER_FLASHBLOCK_INTERVAL",
+        help = "Max time in milliseconds between two flashblocks of the block being built.",
+        help_heading = "Block producer options"
+    )]
+    pub flashblock_interval_ms: u64,
 }
 
 impl Default for BlockProducerOptions {
@@ -552,6 +603,8 @@ impl Default for BlockProducerOptions {
             l1_fee_vault_address: None,
             elasticity_multiplier: 2,
             block_gas_limit: DEFAULT_BUILDER_GAS_CEIL,
+            preconfirmations: false,
+            flashblock_interval_ms: 250,
         }
     }
 }
@@ -762,6 +815,34 @@ pub struct ProofCoordinatorOptions {
         help_heading = "Proof coordinator options"
     )]
     pub proof_send_interval_ms: u64,
+    #[arg(
+        long = "proof-coordinator.aggregation",
+        action = clap::ArgAction::SetTrue,
+        default_value = "false",
+        env = "ETHREX_PROOF_COORDINATOR_AGGREGATION",
+        help = "Aggregate the proofs of consecutive batches and verify them with a single L1 transaction. Incompatible with aligned mode.",
+        help_heading = "Proof coordinator options",
+        conflicts_with = "aligned"
+    )]
+    pub aggregation: bool,
+    #[arg(
+        long = "proof-coordinator.aggregation-size",
+        default_value = "10",
+        value_name = "UINT64",
+        env = "ETHREX_PROOF_COORDINATOR_AGGREGATION_SIZE",
+        help = "Maximum amount of batches covered by a single aggregated proof.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub aggregation_size: u64,
+    #[arg(
+        long = "proof-coordinator.aggregation-timeout",
+        default_value = "600000",
+        value_name = "UINT64",
+        env = "ETHREX_PROOF_COORDINATOR_AGGREGATION_TIMEOUT",
+        help = "How long in milliseconds to wait for a range to reach the aggregation size before aggregating the batches already proven.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub aggregation_timeout_ms: u64,
 }
 
 impl Default for ProofCoordinatorOptions {
@@ -777,6 +858,9 @@ impl Default for ProofCoordinatorOptions {
             listen_ip: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
             listen_port: 3900,
             proof_send_interval_ms: 5000,
+            aggregation: false,
+            aggregation_size: 10,
+            aggregation_timeout_ms: 600000,
             proof_coordinator_tdx_private_key: None,
             proof_coordinator_qpl_tool_path: Some(
                 DEFAULT_PROOF_COORDINATOR_QPL_TOOL_PATH.to_string(),
@@ -1015,6 +1099,68 @@ impl Default for AdminOptions {
     }
 }
 
+#[derive(Parser, Debug)]
+pub struct HighAvailabilityOptions {
+    #[arg(
+        long = "ha.enabled",
+        default_value = "false",
+        value_name = "BOOLEAN",
+        env = "ETHREX_HA_ENABLED",
+        conflicts_with = "based",
+        help = "Run the sequencer in active/passive mode. Only the holder of the leader lease produces and commits blocks, the others follow it over p2p.",
+        help_heading = "High availability options"
+    )]
+    pub ha_enabled: bool,
+    #[arg(
+        long = "ha.node-id",
+        value_name = "NODE_ID",
+        env = "ETHREX_HA_NODE_ID",
+        required_if_eq("ha_enabled", "true"),
+        help = "Unique identifier of this sequencer among the ones sharing the leader lease.",
+        help_heading = "High availability options"
+    )]
+    pub ha_node_id: Option<String>,
+    #[arg(
+        long = "ha.lease-file",
+        value_name = "PATH",
+        env = "ETHREX_HA_LEASE_FILE",
+        required_if_eq("ha_enabled", "true"),
+        help = "Path to the leader lease file. Every sequencer of the cluster must use the same file.",
+        help_heading = "High availability options"
+    )]
+    pub ha_lease_file: Option<PathBuf>,
+    #[arg(
+        long = "ha.lease-ttl",
+        default_value = "3000",
+        value_name = "UINT64",
+        env = "ETHREX_HA_LEASE_TTL",
+        help = "Time in milliseconds a standby waits since the lease last changed before taking over from a leader that stopped renewing it.",
+        help_heading = "High availability options"
+    )]
+    pub ha_lease_ttl_ms: u64,
+}
+
+impl Default for HighAvailabilityOptions {
+    fn default() -> Self {
+        Self {
+            ha_enabled: false,
+            ha_node_id: None,
+            ha_lease_file: None,
+            ha_lease_ttl_ms: 3000,
+        }
+    }
+}
+
+impl HighAvailabilityOptions {
+    fn populate_with_defaults(&mut self, defaults: &Self) {
+        self.ha_node_id = self.ha_node_id.clone().or(defaults.ha_node_id.clone());
+        self.ha_lease_file = self
+            .ha_lease_file
+            .clone()
+            .or(defaults.ha_lease_file.clone());
+    }
+}
+
 #[derive(Parser)]
 pub struct ProverClientOptions {
     #[arg(
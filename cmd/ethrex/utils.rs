@@ -1,12 +1,16 @@
 use crate::decode;
 use bytes::Bytes;
 use directories::ProjectDirs;
-use ethrex_common::types::{Block, Genesis};
+use ethrex_common::{
+    H256,
+    types::{Block, Genesis},
+};
 use ethrex_p2p::{
     discv4::peer_table::PeerTable,
     sync::SyncMode,
     types::{Node, NodeRecord},
 };
+use ethrex_rpc::utils::{RpcNamespace, resolve_namespace};
 use hex::FromHexError;
 use secp256k1::{PublicKey, SecretKey};
 use serde::{Deserialize, Serialize};
@@ -74,6 +78,25 @@ pub fn parse_sync_mode(s: &str) -> eyre::Result<SyncMode> {
     }
 }
 
+pub fn parse_rpc_namespace(s: &str) -> eyre::Result<RpcNamespace> {
+    match resolve_namespace(s, s.to_owned()) {
+        Ok(RpcNamespace::Engine) => Err(eyre::eyre!(
+            "The engine namespace is only served by the authenticated rpc server",
+        )),
+        Ok(namespace) => Ok(namespace),
+        Err(_) => Err(eyre::eyre!(
+            "Invalid rpc namespace {s:?} expected one of eth, net, web3, debug, txpool, trace, ots or admin",
+        )),
+    }
+}
+
+pub fn parse_method_cost(s: &str) -> eyre::Result<(String, u32)> {
+    let (method, cost) = s
+        .split_once('=')
+        .ok_or_else(|| eyre::eyre!("Invalid method cost {s:?} expected METHOD=COST"))?;
+    Ok((method.to_owned(), cost.parse()?))
+}
+
 pub fn parse_socket_addr(addr: &str, port: &str) -> io::Result<SocketAddr> {
     // NOTE: this blocks until hostname can be resolved
     format!("{addr}:{port}")
@@ -145,6 +168,25 @@ pub fn parse_public_key(s: &str) -> eyre::Result<PublicKey> {
     Ok(PublicKey::from_slice(&parse_hex(s)?)?)
 }
 
+pub fn parse_bls_secret_key(s: &str) -> eyre::Result<H256> {
+    let bytes = parse_hex(s)?;
+    if bytes.len() != 32 {
+        return Err(eyre::eyre!(
+            "Invalid BLS secret key, expected 32 bytes and got {}",
+            bytes.len()
+        ));
+    }
+    Ok(H256::from_slice(&bytes))
+}
+
+pub fn parse_fork_version(s: &str) -> eyre::Result<[u8; 4]> {
+    let bytes = parse_hex(s)?;
+    bytes
+        .as_ref()
+        .try_into()
+        .map_err(|_| eyre::eyre!("Invalid fork version {s:?} expected 4 bytes"))
+}
+
 pub fn parse_hex(s: &str) -> eyre::Result<Bytes, FromHexError> {
     match s.strip_prefix("0x") {
         Some(s) => hex::decode(s).map(Into::into),
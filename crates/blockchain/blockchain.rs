@@ -1,26 +1,31 @@
+pub mod bundle;
 pub mod constants;
 pub mod error;
 pub mod fork_choice;
 pub mod mempool;
 pub mod payload;
+mod prefetch;
 mod smoke_test;
 pub mod tracing;
 pub mod vm;
 
 use ::tracing::{debug, info, instrument, trace};
+use bundle::{BundlePool, BundlePoolOptions};
 use constants::{MAX_INITCODE_SIZE, MAX_TRANSACTION_DATA_SIZE, POST_OSAKA_GAS_LIMIT_CAP};
 use error::MempoolError;
 use error::{ChainError, InvalidBlockError};
 use ethrex_common::constants::{
     EMPTY_TRIE_HASH, GAS_PER_BLOB, MAX_RLP_BLOCK_SIZE, MIN_BASE_FEE_PER_BLOB_GAS,
 };
+use ethrex_common::types::block_access_list::BlockAccessList;
 use ethrex_common::types::block_execution_witness::ExecutionWitness;
 use ethrex_common::types::fee_config::FeeConfig;
 use ethrex_common::types::requests::{EncodedRequests, Requests, compute_requests_hash};
 use ethrex_common::types::{
     AccountState, AccountUpdate, Block, BlockHash, BlockHeader, BlockNumber, ChainConfig, Code,
-    EIP4844Transaction, Receipt, Transaction, WrappedEIP4844Transaction, compute_receipts_root,
-    validate_block_header, validate_cancun_header_fields, validate_prague_header_fields,
+    CodeAnalysisCache, CodeAnalysisCacheStats, EIP4844Transaction, Receipt, Transaction,
+    TransactionAddresses, WrappedEIP4844Transaction, compute_receipts_root, validate_block_header,
+    validate_cancun_header_fields, validate_prague_header_fields,
     validate_pre_cancun_header_fields,
 };
 use ethrex_common::types::{ELASTICITY_MULTIPLIER, P2PTransaction};
@@ -37,9 +42,10 @@ use ethrex_storage::{
 use ethrex_trie::node::{BranchNode, ExtensionNode};
 use ethrex_trie::{Nibbles, Node, NodeRef, Trie};
 use ethrex_vm::backends::levm::db::DatabaseLogger;
+use ethrex_vm::tracing::TracerRegistry;
 use ethrex_vm::{BlockExecutionResult, DynVmDatabase, Evm, EvmError};
 use mempool::Mempool;
-use payload::PayloadOrTask;
+use payload::{PayloadBuildResult, PayloadOrTask};
 use rustc_hash::FxHashMap;
 use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, HashMap};
@@ -49,7 +55,7 @@ use std::sync::{
     mpsc::{Receiver, channel},
 };
 use std::time::Instant;
-use tokio::sync::Mutex as TokioMutex;
+use tokio::sync::{Mutex as TokioMutex, broadcast};
 use tokio_util::sync::CancellationToken;
 
 use vm::StoreVmDatabase;
@@ -62,6 +68,8 @@ use ethrex_common::types::BlobsBundle;
 
 const MAX_PAYLOADS: usize = 10;
 const MAX_MEMPOOL_SIZE_DEFAULT: usize = 10_000;
+/// Payloads kept for subscribers lagging behind, older ones are dropped
+const BUILT_PAYLOADS_CAPACITY: usize = 16;
 
 type StoreUpdatesMap = FxHashMap<H256, (Result<Trie, StoreError>, FxHashMap<Nibbles, Vec<u8>>)>;
 //TODO: Implement a struct Chain or BlockChain to encapsulate
@@ -84,6 +92,8 @@ pub struct L2Config {
 pub struct Blockchain {
     storage: Store,
     pub mempool: Mempool,
+    /// Bundles to be included at the top of the blocks built, only kept when building for relays
+    pub bundles: Option<BundlePool>,
     /// Whether the node's chain is in or out of sync with the current chain
     /// This will be set to true once the initial sync has taken place and wont be set to false after
     /// This does not reflect whether there is an ongoing sync process
@@ -92,6 +102,14 @@ pub struct Blockchain {
     /// Mapping from a payload id to either a complete payload or a payload build task
     /// We need to keep completed payloads around in case consensus requests them twice
     pub payloads: Arc<TokioMutex<Vec<(u64, PayloadOrTask)>>>,
+    /// Notifies every payload built that's more valuable than the previous one built for the
+    /// same slot, so they can be submitted to relays
+    built_payloads: broadcast::Sender<Arc<PayloadBuildResult>>,
+    /// Jumpdest analysis of the bytecodes executed so far, shared with the store so it's kept
+    /// across blocks
+    code_analysis_cache: Arc<CodeAnalysisCache>,
+    /// Native tracers that can be requested by name when tracing transactions
+    pub tracers: TracerRegistry,
 }
 
 #[derive(Debug, Clone)]
@@ -100,6 +118,13 @@ pub struct BlockchainOptions {
     /// Whether performance logs should be emitted
     pub perf_logs_enabled: bool,
     pub r#type: BlockchainType,
+    /// Whether the transactions of a block should be executed optimistically in parallel
+    pub parallel_execution: bool,
+    /// Accepts bundles and includes them in the payloads built if set
+    pub bundles: Option<BundlePoolOptions>,
+    /// Whether the addresses each transaction took part in are indexed, as the `ots_*` endpoints
+    /// searching transactions need
+    pub index_addresses: bool,
 }
 
 impl Default for BlockchainOptions {
@@ -108,6 +133,9 @@ impl Default for BlockchainOptions {
             max_mempool_size: MAX_MEMPOOL_SIZE_DEFAULT,
             perf_logs_enabled: false,
             r#type: BlockchainType::default(),
+            parallel_execution: false,
+            bundles: None,
+            index_addresses: false,
         }
     }
 }
@@ -139,24 +167,38 @@ fn log_batch_progress(batch_size: u32, current_block: u32) {
 impl Blockchain {
     pub fn new(store: Store, blockchain_opts: BlockchainOptions) -> Self {
         Self {
+            code_analysis_cache: store.code_analysis_cache(),
             storage: store,
             mempool: Mempool::new(blockchain_opts.max_mempool_size),
+            bundles: blockchain_opts.bundles.clone().map(BundlePool::new),
             is_synced: AtomicBool::new(false),
             payloads: Arc::new(TokioMutex::new(Vec::new())),
+            built_payloads: broadcast::channel(BUILT_PAYLOADS_CAPACITY).0,
             options: blockchain_opts,
+            tracers: TracerRegistry::default(),
         }
     }
 
     pub fn default_with_store(store: Store) -> Self {
         Self {
+            code_analysis_cache: store.code_analysis_cache(),
             storage: store,
             mempool: Mempool::new(MAX_MEMPOOL_SIZE_DEFAULT),
+            bundles: None,
             is_synced: AtomicBool::new(false),
             payloads: Arc::new(TokioMutex::new(Vec::new())),
+            built_payloads: broadcast::channel(BUILT_PAYLOADS_CAPACITY).0,
             options: BlockchainOptions::default(),
+            tracers: TracerRegistry::default(),
         }
     }
 
+    /// Subscribes to the payloads built, each one more valuable than the previous one built for
+    /// the same slot
+    pub fn subscribe_built_payloads(&self) -> broadcast::Receiver<Arc<PayloadBuildResult>> {
+        self.built_payloads.subscribe()
+    }
+
     /// Executes a block withing a new vm instance and state
     fn execute_block(
         &self,
@@ -175,15 +217,24 @@ impl Blockchain {
         validate_block(block, &parent_header, &chain_config, ELASTICITY_MULTIPLIER)?;
 
         let vm_db = StoreVmDatabase::new(self.storage.clone(), parent_header)?;
-        let mut vm = self.new_evm(vm_db)?;
+        let mut vm = self.new_indexing_evm(vm_db)?;
 
-        let execution_result = vm.execute_block(block)?;
+        let execution_result = if self.options.parallel_execution {
+            vm.execute_block_parallel(block)?
+        } else {
+            vm.execute_block(block)?
+        };
         let account_updates = vm.get_state_transitions()?;
 
         // Validate execution went alright
         validate_gas_used(&execution_result.receipts, &block.header)?;
         validate_receipts_root(&block.header, &execution_result.receipts)?;
         validate_requests_hash(&block.header, &chain_config, &execution_result.requests)?;
+        validate_block_access_list_hash(
+            &block.header,
+            &chain_config,
+            execution_result.block_access_list.as_ref(),
+        )?;
 
         Ok((execution_result, account_updates))
     }
@@ -199,7 +250,7 @@ impl Blockchain {
         &self,
         block: &Block,
         parent_header: &BlockHeader,
-        mut vm: Evm,
+        vm_db: StoreVmDatabase,
     ) -> Result<
         (
             BlockExecutionResult,
@@ -218,18 +269,51 @@ impl Blockchain {
         validate_block(block, parent_header, &chain_config, ELASTICITY_MULTIPLIER)?;
         let block_validated_instant = Instant::now();
 
+        // Recovered once for the prefetch stages and the execution
+        let transactions = block.body.get_transactions_with_sender().map_err(|error| {
+            EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+        })?;
+        prefetch::prefetch_block_state(block, &transactions, &vm_db);
+        let mut vm = self.new_indexing_evm(vm_db.clone())?;
+
         let exec_merkle_start = Instant::now();
         let queue_length = AtomicUsize::new(0);
         let queue_length_ref = &queue_length;
         let mut max_queue_length = 0;
+        let execution_done = AtomicBool::new(false);
+        let execution_done_ref = &execution_done;
         let (execution_result, account_updates_list) = std::thread::scope(|s| {
             let max_queue_length_ref = &mut max_queue_length;
             let (tx, rx) = channel();
+            let vm_db_ref = &vm_db;
+            let transactions_ref = &transactions;
+            std::thread::Builder::new()
+                .name("block_executor_prefetcher".to_string())
+                .spawn_scoped(s, move || {
+                    prefetch::pre_execute_block(
+                        block,
+                        transactions_ref,
+                        vm_db_ref,
+                        &self.options.r#type,
+                        execution_done_ref,
+                    );
+                })
+                .expect("Failed to spawn block_executor prefetcher thread");
             let execution_handle = std::thread::Builder::new()
                 .name("block_executor_execution".to_string())
                 .spawn_scoped(s, move || -> Result<_, ChainError> {
-                    let execution_result =
-                        vm.execute_block_pipeline(block, tx, queue_length_ref)?;
+                    let execution_result = if self.options.parallel_execution {
+                        vm.execute_block_pipeline_parallel(
+                            block,
+                            transactions_ref,
+                            tx,
+                            queue_length_ref,
+                        )
+                    } else {
+                        vm.execute_block_pipeline(block, transactions_ref, tx, queue_length_ref)
+                    };
+                    execution_done_ref.store(true, Ordering::Relaxed);
+                    let execution_result = execution_result?;
 
                     // Validate execution went alright
                     validate_gas_used(&execution_result.receipts, &block.header)?;
@@ -239,6 +323,11 @@ impl Blockchain {
                         &chain_config,
                         &execution_result.requests,
                     )?;
+                    validate_block_access_list_hash(
+                        &block.header,
+                        &chain_config,
+                        execution_result.block_access_list.as_ref(),
+                    )?;
 
                     let exec_end_instant = Instant::now();
                     Ok((execution_result, exec_end_instant))
@@ -667,11 +756,20 @@ impl Blockchain {
     ) -> Result<BlockExecutionResult, ChainError> {
         // Validate the block pre-execution
         validate_block(block, parent_header, chain_config, ELASTICITY_MULTIPLIER)?;
-        let execution_result = vm.execute_block(block)?;
+        let execution_result = if self.options.parallel_execution {
+            vm.execute_block_parallel(block)?
+        } else {
+            vm.execute_block(block)?
+        };
         // Validate execution went alright
         validate_gas_used(&execution_result.receipts, &block.header)?;
         validate_receipts_root(&block.header, &execution_result.receipts)?;
         validate_requests_hash(&block.header, chain_config, &execution_result.requests)?;
+        validate_block_access_list_hash(
+            &block.header,
+            chain_config,
+            execution_result.block_access_list.as_ref(),
+        )?;
 
         Ok(execution_result)
     }
@@ -1033,17 +1131,32 @@ impl Blockchain {
         // Check state root matches the one in block header
         validate_state_root(&block.header, account_updates_list.state_trie_hash)?;
 
+        let block_hash = block.hash();
         let update_batch = UpdateBatch {
             account_updates: account_updates_list.state_updates,
             storage_updates: account_updates_list.storage_updates,
-            receipts: vec![(block.hash(), execution_result.receipts)],
+            receipts: vec![(block_hash, execution_result.receipts)],
+            transaction_addresses: execution_result
+                .transaction_addresses
+                .map(|addresses| (block_hash, addresses))
+                .into_iter()
+                .collect(),
             blocks: vec![block],
             code_updates: account_updates_list.code_updates,
         };
 
         self.storage
             .store_block_updates(update_batch)
-            .map_err(|e| e.into())
+            .map_err(ChainError::from)?;
+
+        metrics!(METRICS_BLOCKS.set_code_analysis_cache_stats(self.code_analysis_cache_stats()));
+
+        Ok(())
+    }
+
+    /// Hits, misses and size of the bytecode analysis cache.
+    pub fn code_analysis_cache_stats(&self) -> CodeAnalysisCacheStats {
+        self.code_analysis_cache.stats()
     }
 
     pub fn add_block(&self, block: Block) -> Result<(), ChainError> {
@@ -1091,11 +1204,11 @@ impl Blockchain {
             return Err(ChainError::ParentNotFound);
         };
 
-        let vm_db = StoreVmDatabase::new(self.storage.clone(), parent_header.clone())?;
-        let vm = self.new_evm(vm_db)?;
+        let vm_db =
+            StoreVmDatabase::new(self.storage.clone(), parent_header.clone())?.with_state_cache();
 
         let (res, account_updates_list, merkle_queue_length, instants) =
-            self.execute_block_pipeline(&block, &parent_header, vm)?;
+            self.execute_block_pipeline(&block, &parent_header, vm_db)?;
 
         let (gas_used, gas_limit, block_number, transactions_count) = (
             block.header.gas_used,
@@ -1226,8 +1339,9 @@ impl Blockchain {
             };
             let extra_log = if as_gigas > 0.0 {
                 format!(
-                    " block validation: {}% | exec(w/merkle): {}% | merkle-only: {}% (max_queue_length: {merkle_queue_length}) | store: {}%",
+                    " block validation: {}% | prefetch: {}% | exec(w/merkle): {}% | merkle-only: {}% (max_queue_length: {merkle_queue_length}) | store: {}%",
                     percentage(start_instant, block_validated_instant),
+                    percentage(block_validated_instant, exec_merkle_start),
                     percentage(exec_merkle_start, exec_end_instant),
                     percentage(exec_end_instant, exec_merkle_end_instant),
                     percentage(exec_merkle_end_instant, stored_instant),
@@ -1273,10 +1387,11 @@ impl Blockchain {
             block_hash_cache,
         )
         .map_err(|e| (ChainError::EvmError(e), None))?;
-        let mut vm = self.new_evm(vm_db).map_err(|e| (e.into(), None))?;
+        let mut vm = self.new_indexing_evm(vm_db).map_err(|e| (e.into(), None))?;
 
         let blocks_len = blocks.len();
         let mut all_receipts: Vec<(BlockHash, Vec<Receipt>)> = Vec::with_capacity(blocks_len);
+        let mut all_transaction_addresses: Vec<(BlockHash, Vec<TransactionAddresses>)> = Vec::new();
         let mut total_gas_used = 0;
         let mut transactions_count = 0;
 
@@ -1302,7 +1417,11 @@ impl Blockchain {
                 blocks[i - 1].header.clone()
             };
 
-            let BlockExecutionResult { receipts, .. } = self
+            let BlockExecutionResult {
+                receipts,
+                transaction_addresses,
+                ..
+            } = self
                 .execute_block_from_state(&parent_header, block, &chain_config, &mut vm)
                 .map_err(|err| {
                     (
@@ -1318,6 +1437,9 @@ impl Blockchain {
             total_gas_used += block.header.gas_used;
             transactions_count += block.body.transactions.len();
             all_receipts.push((block.hash(), receipts));
+            if let Some(addresses) = transaction_addresses {
+                all_transaction_addresses.push((block.hash(), addresses));
+            }
 
             // Conversion is safe because EXECUTE_BATCH_SIZE=1024
             log_batch_progress(blocks_len as u32, i as u32);
@@ -1355,6 +1477,7 @@ impl Blockchain {
             storage_updates: accounts_updates,
             blocks,
             receipts: all_receipts,
+            transaction_addresses: all_transaction_addresses,
             code_updates,
         };
 
@@ -1654,6 +1777,16 @@ impl Blockchain {
         new_evm(&self.options.r#type, vm_db)
     }
 
+    /// Like [`Blockchain::new_evm`], but recording the addresses each transaction takes part in
+    /// if they are indexed, for executing the blocks that get stored.
+    pub fn new_indexing_evm(&self, vm_db: StoreVmDatabase) -> Result<Evm, EvmError> {
+        let mut vm = self.new_evm(vm_db)?;
+        if self.options.index_addresses {
+            vm.db.enable_address_index();
+        }
+        Ok(vm)
+    }
+
     /// Get the current fork of the chain, based on the latest block's timestamp
     pub async fn current_fork(&self) -> Result<Fork, StoreError> {
         let chain_config = self.storage.get_chain_config();
@@ -1706,6 +1839,30 @@ pub fn validate_requests_hash(
     Ok(())
 }
 
+/// Validates the header commits to the access list recorded while executing the block (EIP-7928).
+pub fn validate_block_access_list_hash(
+    header: &BlockHeader,
+    chain_config: &ChainConfig,
+    block_access_list: Option<&BlockAccessList>,
+) -> Result<(), ChainError> {
+    if !chain_config.is_amsterdam_activated(header.timestamp) {
+        return Ok(());
+    }
+
+    let valid = header
+        .block_access_list_hash
+        .zip(block_access_list)
+        .is_some_and(|(hash, block_access_list)| hash == block_access_list.hash());
+
+    if !valid {
+        return Err(ChainError::InvalidBlock(
+            InvalidBlockError::BlockAccessListHashMismatch,
+        ));
+    }
+
+    Ok(())
+}
+
 /// Performs post-execution checks
 pub fn validate_state_root(
     block_header: &BlockHeader,
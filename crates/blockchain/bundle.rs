@@ -0,0 +1,214 @@
+use std::{collections::HashMap, sync::RwLock, time::Duration};
+
+use ethrex_common::{
+    Address, H256, U256,
+    types::{BlockNumber, Transaction, TxKind},
+    utils::keccak,
+};
+use ethrex_storage::error::StoreError;
+
+use crate::error::MempoolError;
+
+/// Max amount of bundles kept in the pool, across all target blocks
+const MAX_BUNDLES: usize = 10_000;
+/// Max amount of bundles kept in the pool for the same target block, as every one of them is
+/// simulated when building it
+pub const MAX_BUNDLES_PER_BLOCK: usize = 200;
+/// Time spent simulating the bundles of a payload, or the ones sent to `eth_callBundle`, after
+/// which the remaining ones are skipped
+pub const BUNDLE_SIMULATION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Settings of the bundle pool, only kept by nodes building blocks for relays
+#[derive(Debug, Clone, Default)]
+pub struct BundlePoolOptions {
+    /// Searchers allowed to send bundles. Any searcher signing its requests is allowed if empty.
+    pub allowed_signers: Vec<Address>,
+}
+
+/// Transactions to be included together, in order, at the top of a given block, or not at all
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    /// Transactions of the bundle along with their senders
+    pub transactions: Vec<(Transaction, Address)>,
+    /// The only block the bundle can be included in
+    pub block_number: BlockNumber,
+    /// Earliest timestamp of the block the bundle can be included in
+    pub min_timestamp: Option<u64>,
+    /// Latest timestamp of the block the bundle can be included in
+    pub max_timestamp: Option<u64>,
+    /// Transactions of the bundle allowed to revert without discarding the whole bundle
+    pub reverting_tx_hashes: Vec<H256>,
+}
+
+impl Bundle {
+    /// Hash of the concatenated hashes of the bundle transactions
+    pub fn hash(&self) -> H256 {
+        let tx_hashes: Vec<u8> = self
+            .transactions
+            .iter()
+            .flat_map(|(tx, _)| tx.hash().0)
+            .collect();
+        keccak(tx_hashes)
+    }
+
+    /// Whether the bundle can be included in a block with the given number and timestamp
+    pub fn is_eligible(&self, block_number: BlockNumber, timestamp: u64) -> bool {
+        self.block_number == block_number
+            && self.min_timestamp.is_none_or(|min| timestamp >= min)
+            && self.max_timestamp.is_none_or(|max| timestamp <= max)
+    }
+
+    /// Whether the transaction with the given hash may revert without discarding the bundle
+    pub fn may_revert(&self, tx_hash: &H256) -> bool {
+        self.reverting_tx_hashes.contains(tx_hash)
+    }
+}
+
+/// Outcome of executing a bundle on top of a block, without including it
+#[derive(Debug, Clone)]
+pub struct BundleSimulation {
+    /// Increase of the fee recipient's balance after executing the whole bundle
+    pub coinbase_diff: U256,
+    pub gas_used: u64,
+    pub results: Vec<BundleTransactionResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleTransactionResult {
+    pub tx_hash: H256,
+    pub sender: Address,
+    pub to: TxKind,
+    pub gas_used: u64,
+    /// Increase of the fee recipient's balance after executing the transaction
+    pub coinbase_diff: U256,
+    /// The error of the transaction if it couldn't be executed, or `None` if it was executed,
+    /// even if it reverted
+    pub error: Option<String>,
+    pub succeeded: bool,
+}
+
+/// Bundles received through `eth_sendBundle`, waiting for their target block to be built
+#[derive(Debug, Default)]
+pub struct BundlePool {
+    options: BundlePoolOptions,
+    bundles: RwLock<HashMap<H256, Bundle>>,
+}
+
+impl BundlePool {
+    pub fn new(options: BundlePoolOptions) -> Self {
+        Self {
+            options,
+            bundles: Default::default(),
+        }
+    }
+
+    /// Whether the searcher that signed a request is allowed to send bundles
+    pub fn is_signer_allowed(&self, signer: &Address) -> bool {
+        self.options.allowed_signers.is_empty() || self.options.allowed_signers.contains(signer)
+    }
+
+    /// Adds a bundle to the pool, returning its hash. Adding the same bundle twice is a no-op.
+    pub fn add_bundle(&self, bundle: Bundle) -> Result<H256, MempoolError> {
+        let hash = bundle.hash();
+        let mut bundles = self
+            .bundles
+            .write()
+            .map_err(|error| StoreError::MempoolWriteLock(error.to_string()))?;
+        if bundles.contains_key(&hash) {
+            return Ok(hash);
+        }
+        if bundles.len() >= MAX_BUNDLES {
+            return Err(MempoolError::BundlePoolFull);
+        }
+        let same_block = bundles
+            .values()
+            .filter(|pooled| pooled.block_number == bundle.block_number)
+            .count();
+        if same_block >= MAX_BUNDLES_PER_BLOCK {
+            return Err(MempoolError::BundleBlockFull(bundle.block_number));
+        }
+        bundles.insert(hash, bundle);
+        Ok(hash)
+    }
+
+    /// Returns the bundles that can be included in a block with the given number and timestamp,
+    /// dropping the ones targeting earlier blocks as they can't be included anymore
+    pub fn bundles_for_block(
+        &self,
+        block_number: BlockNumber,
+        timestamp: u64,
+    ) -> Result<Vec<Bundle>, StoreError> {
+        let mut bundles = self
+            .bundles
+            .write()
+            .map_err(|error| StoreError::MempoolWriteLock(error.to_string()))?;
+        bundles.retain(|_, bundle| bundle.block_number >= block_number);
+        Ok(bundles
+            .values()
+            .filter(|bundle| bundle.is_eligible(block_number, timestamp))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_common::types::LegacyTransaction;
+
+    fn bundle(block_number: BlockNumber, nonce: u64) -> Bundle {
+        let tx = Transaction::LegacyTransaction(LegacyTransaction {
+            nonce,
+            ..Default::default()
+        });
+        Bundle {
+            transactions: vec![(tx, Address::zero())],
+            block_number,
+            min_timestamp: Some(10),
+            max_timestamp: Some(20),
+            reverting_tx_hashes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn bundles_are_returned_for_their_target_block_only() {
+        let pool = BundlePool::default();
+        let hash = pool.add_bundle(bundle(2, 0)).unwrap();
+        pool.add_bundle(bundle(1, 1)).unwrap();
+        pool.add_bundle(bundle(3, 2)).unwrap();
+
+        // Out of the timestamp range
+        assert!(pool.bundles_for_block(2, 21).unwrap().is_empty());
+        let bundles = pool.bundles_for_block(2, 15).unwrap();
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].hash(), hash);
+        // The bundle targeting block 1 was dropped
+        assert_eq!(pool.bundles.read().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn bundles_are_capped_per_target_block() {
+        let pool = BundlePool::default();
+        for nonce in 0..MAX_BUNDLES_PER_BLOCK as u64 {
+            pool.add_bundle(bundle(2, nonce)).unwrap();
+        }
+        assert!(matches!(
+            pool.add_bundle(bundle(2, MAX_BUNDLES_PER_BLOCK as u64)),
+            Err(MempoolError::BundleBlockFull(2))
+        ));
+        // Resending a pooled bundle or targeting another block is still possible
+        pool.add_bundle(bundle(2, 0)).unwrap();
+        pool.add_bundle(bundle(3, 0)).unwrap();
+    }
+
+    #[test]
+    fn only_allowed_signers_can_send_bundles() {
+        let searcher = Address::repeat_byte(1);
+        assert!(BundlePool::default().is_signer_allowed(&searcher));
+        let pool = BundlePool::new(BundlePoolOptions {
+            allowed_signers: vec![searcher],
+        });
+        assert!(pool.is_signer_allowed(&searcher));
+        assert!(!pool.is_signer_allowed(&Address::repeat_byte(2)));
+    }
+}
@@ -72,6 +72,8 @@ impl ChainError {
 pub enum InvalidBlockError {
     #[error("Requests hash does not match the one in the header after executing")]
     RequestsHashMismatch,
+    #[error("Block access list hash does not match the one in the header after executing")]
+    BlockAccessListHashMismatch,
     #[error("World State Root does not match the one in the header after executing")]
     StateRootMismatch,
     #[error("Receipts Root does not match the one in the header after executing")]
@@ -142,6 +144,10 @@ pub enum MempoolError {
     InvalidTxSender(#[from] ethrex_common::EcdsaError),
     #[error("Attempted to replace a pooled transaction with an underpriced transaction")]
     UnderpricedReplacement,
+    #[error("Bundle pool is full")]
+    BundlePoolFull,
+    #[error("Too many bundles target block {0}")]
+    BundleBlockFull(u64),
 }
 
 #[derive(Debug)]
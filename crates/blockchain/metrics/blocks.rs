@@ -1,3 +1,4 @@
+use ethrex_common::types::CodeAnalysisCacheStats;
 use prometheus::Histogram;
 use prometheus::HistogramOpts;
 use prometheus::{Encoder, Gauge, IntGauge, Registry, TextEncoder};
@@ -24,6 +25,10 @@ pub struct MetricsBlocks {
     store_ms: IntGauge,
     /// Keeps track of the head block number
     head_height: IntGauge,
+    code_analysis_cache_hits: IntGauge,
+    code_analysis_cache_misses: IntGauge,
+    code_analysis_cache_entries: IntGauge,
+    code_analysis_cache_size: IntGauge,
 }
 
 impl Default for MetricsBlocks {
@@ -114,6 +119,26 @@ impl MetricsBlocks {
                 "Keeps track of transaction count in a block",
             )
             .unwrap(),
+            code_analysis_cache_hits: IntGauge::new(
+                "code_analysis_cache_hits",
+                "Keeps track of the bytecodes whose jumpdest analysis was found in the cache",
+            )
+            .unwrap(),
+            code_analysis_cache_misses: IntGauge::new(
+                "code_analysis_cache_misses",
+                "Keeps track of the bytecodes that had to be analysed because they weren't cached",
+            )
+            .unwrap(),
+            code_analysis_cache_entries: IntGauge::new(
+                "code_analysis_cache_entries",
+                "Keeps track of the number of bytecode analyses in the cache",
+            )
+            .unwrap(),
+            code_analysis_cache_size: IntGauge::new(
+                "code_analysis_cache_size_bytes",
+                "Keeps track of the estimated size of the bytecode analysis cache in bytes",
+            )
+            .unwrap(),
         }
     }
 
@@ -166,6 +191,17 @@ impl MetricsBlocks {
         self.gas_used.set(gas_used);
     }
 
+    pub fn set_code_analysis_cache_stats(&self, stats: CodeAnalysisCacheStats) {
+        self.code_analysis_cache_hits
+            .set(stats.hits.try_into().unwrap_or(i64::MAX));
+        self.code_analysis_cache_misses
+            .set(stats.misses.try_into().unwrap_or(i64::MAX));
+        self.code_analysis_cache_entries
+            .set(stats.entries.try_into().unwrap_or(i64::MAX));
+        self.code_analysis_cache_size
+            .set(stats.size.try_into().unwrap_or(i64::MAX));
+    }
+
     pub fn gather_metrics(&self) -> Result<String, MetricsError> {
         if self.block_number.get() <= 0 {
             return Ok(String::new());
@@ -199,6 +235,14 @@ impl MetricsBlocks {
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
         r.register(Box::new(self.transaction_count.clone()))
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.code_analysis_cache_hits.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.code_analysis_cache_misses.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.code_analysis_cache_entries.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.code_analysis_cache_size.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
 
         let encoder = TextEncoder::new();
         let metric_families = r.gather();
@@ -11,14 +11,18 @@ use ethrex_common::{
     constants::{DEFAULT_OMMERS_HASH, DEFAULT_REQUESTS_HASH, GAS_PER_BLOB, MAX_RLP_BLOCK_SIZE},
     types::{
         AccountUpdate, BlobsBundle, Block, BlockBody, BlockHash, BlockHeader, BlockNumber,
-        ChainConfig, MempoolTransaction, Receipt, Transaction, TxType, Withdrawal, bloom_from_logs,
-        calc_excess_blob_gas, calculate_base_fee_per_blob_gas, calculate_base_fee_per_gas,
-        compute_receipts_root, compute_transactions_root, compute_withdrawals_root,
+        ChainConfig, ELASTICITY_MULTIPLIER, MempoolTransaction, Receipt, Transaction,
+        TransactionAddresses, TxType, Withdrawal,
+        block_access_list::BlockAccessList,
+        bloom_from_logs, calc_excess_blob_gas, calculate_base_fee_per_blob_gas,
+        calculate_base_fee_per_gas, compute_receipts_root, compute_transactions_root,
+        compute_withdrawals_root,
         requests::{EncodedRequests, compute_requests_hash},
     },
 };
 
 use ethrex_crypto::keccak::Keccak256;
+use ethrex_vm::backends::levm::block_access_index;
 use ethrex_vm::{Evm, EvmError};
 
 use ethrex_rlp::encode::RLPEncode;
@@ -34,6 +38,7 @@ use tokio_util::sync::CancellationToken;
 
 use crate::{
     Blockchain, BlockchainType, MAX_PAYLOADS,
+    bundle::{BUNDLE_SIMULATION_TIMEOUT, Bundle, BundleSimulation, BundleTransactionResult},
     constants::{GAS_LIMIT_BOUND_DIVISOR, MIN_GAS_LIMIT, TX_GAS_COST},
     error::{ChainError, InvalidBlockError},
     mempool::PendingTxFilter,
@@ -170,6 +175,9 @@ pub fn create_payload(
         requests_hash: chain_config
             .is_prague_activated(args.timestamp)
             .then_some(*DEFAULT_REQUESTS_HASH),
+        block_access_list_hash: chain_config
+            .is_amsterdam_activated(args.timestamp)
+            .then(|| BlockAccessList::default().hash()),
         ..Default::default()
     };
 
@@ -217,6 +225,7 @@ pub struct PayloadBuildContext {
     pub vm: Evm,
     pub account_updates: Vec<AccountUpdate>,
     pub payload_size: u64,
+    pub block_access_list: Option<BlockAccessList>,
 }
 
 impl PayloadBuildContext {
@@ -239,7 +248,10 @@ impl PayloadBuildContext {
             .map_err(|e| EvmError::DB(e.to_string()))?
             .ok_or_else(|| EvmError::DB("parent header not found".to_string()))?;
         let vm_db = StoreVmDatabase::new(storage.clone(), parent_header)?;
-        let vm = new_evm(blockchain_type, vm_db)?;
+        let mut vm = new_evm(blockchain_type, vm_db)?;
+        if config.is_amsterdam_activated(payload.header.timestamp) {
+            vm.db.enable_block_access_list();
+        }
 
         let payload_size = payload.length() as u64;
         Ok(PayloadBuildContext {
@@ -256,6 +268,7 @@ impl PayloadBuildContext {
             vm,
             account_updates: Vec::new(),
             payload_size,
+            block_access_list: None,
         })
     }
 
@@ -290,6 +303,9 @@ pub struct PayloadBuildResult {
     pub requests: Vec<EncodedRequests>,
     pub account_updates: Vec<AccountUpdate>,
     pub payload: Block,
+    pub block_access_list: Option<BlockAccessList>,
+    /// Only recorded if the context's VM was set to index them
+    pub transaction_addresses: Option<Vec<TransactionAddresses>>,
 }
 
 impl From<PayloadBuildContext> for PayloadBuildResult {
@@ -301,6 +317,8 @@ impl From<PayloadBuildContext> for PayloadBuildResult {
             receipts,
             account_updates,
             payload,
+            block_access_list,
+            mut vm,
             ..
         } = value;
 
@@ -311,6 +329,8 @@ impl From<PayloadBuildContext> for PayloadBuildResult {
             receipts,
             account_updates,
             payload,
+            block_access_list,
+            transaction_addresses: vm.db.transaction_addresses.take(),
         }
     }
 }
@@ -371,6 +391,7 @@ impl Blockchain {
         // Attempt to rebuild the payload as many times within the given timeframe to maximize fee revenue
         // TODO(#4997): start with an empty block
         let mut res = self.build_payload(payload.clone())?;
+        let _ = self.built_payloads.send(Arc::new(res.clone()));
         while start.elapsed() < SECONDS_PER_SLOT && !cancel_token.is_cancelled() {
             let payload = payload.clone();
             let self_clone = self.clone();
@@ -381,7 +402,11 @@ impl Blockchain {
             //   which wastes CPU resources.
             match cancel_token.run_until_cancelled(building_task).await {
                 Some(Ok(current_res)) => {
-                    res = current_res?;
+                    let current_res = current_res?;
+                    if current_res.block_value > res.block_value {
+                        let _ = self.built_payloads.send(Arc::new(current_res.clone()));
+                    }
+                    res = current_res;
                 }
                 Some(Err(err)) => {
                     warn!(%err, "Payload-building task panicked");
@@ -404,8 +429,10 @@ impl Blockchain {
         if let BlockchainType::L1 = self.options.r#type {
             self.apply_system_operations(&mut context)?;
         }
-        self.apply_withdrawals(&mut context)?;
+        context.vm.db.checkpoint_block_access_list(0);
         self.fill_transactions(&mut context)?;
+        // Withdrawals are processed after the transactions, as when executing the block
+        self.apply_withdrawals(&mut context)?;
         self.extract_requests(&mut context)?;
         self.finalize_payload(&mut context)?;
 
@@ -497,6 +524,8 @@ impl Blockchain {
             .map(|schedule| schedule.max)
             .unwrap_or_default() as usize;
 
+        self.fill_bundles(context)?;
+
         debug!("Fetching transactions from mempool");
         // Fetch mempool transactions
         let (mut plain_txs, mut blob_txs) = self.fetch_mempool_transactions(context)?;
@@ -565,6 +594,12 @@ impl Blockchain {
             // Execute tx
             let receipt = match self.apply_transaction(&head_tx, context) {
                 Ok(receipt) => {
+                    context
+                        .vm
+                        .db
+                        .checkpoint_block_access_list(block_access_index(
+                            context.payload.body.transactions.len() + 1,
+                        )?);
                     txs.shift()?;
                     metrics!(METRICS_TX.inc_tx_with_type(MetricsTxType(head_tx.tx_type())));
                     receipt
@@ -573,6 +608,7 @@ impl Blockchain {
                 Err(e) => {
                     debug!("Failed to execute transaction: {tx_hash:x}, {e}");
                     metrics!(METRICS_TX.inc_tx_errors(e.to_metric()));
+                    context.vm.db.discard_pending_block_access_list();
                     txs.pop();
                     continue;
                 }
@@ -586,6 +622,114 @@ impl Blockchain {
         Ok(())
     }
 
+    /// Includes the bundles targeting the payload at its top, the most profitable first.
+    /// Each bundle is simulated on its own first, and then included only if it still executes as
+    /// expected after the bundles included before it. Bundles left once `BUNDLE_SIMULATION_TIMEOUT`
+    /// is spent are skipped. Does nothing unless the bundle pool is enabled.
+    pub fn fill_bundles(&self, context: &mut PayloadBuildContext) -> Result<(), ChainError> {
+        let Some(pool) = &self.bundles else {
+            return Ok(());
+        };
+        let bundles =
+            pool.bundles_for_block(context.block_number(), context.payload.header.timestamp)?;
+        if bundles.is_empty() {
+            return Ok(());
+        }
+
+        debug!("Simulating {} bundles", bundles.len());
+        let deadline = Instant::now() + BUNDLE_SIMULATION_TIMEOUT;
+        let mut profitable_bundles = Vec::with_capacity(bundles.len());
+        for bundle in bundles {
+            if Instant::now() >= deadline {
+                debug!("Bundle simulation timed out, skipping the remaining bundles");
+                break;
+            }
+            let mut simulation = context.clone();
+            if let Some(profit) = apply_bundle(&bundle, &mut simulation)? {
+                profitable_bundles.push((profit, bundle));
+            }
+        }
+        profitable_bundles.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        for (_, bundle) in profitable_bundles {
+            if Instant::now() >= deadline {
+                break;
+            }
+            let mut candidate = context.clone();
+            if apply_bundle(&bundle, &mut candidate)?.is_some() {
+                debug!("Adding bundle: {:#x} to payload", bundle.hash());
+                *context = candidate;
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes a bundle on top of the given block as if it was included at the top of the next
+    /// one, with the given timestamp and fee recipient. Every transaction is executed, even after
+    /// one fails, unless the simulation takes longer than `BUNDLE_SIMULATION_TIMEOUT`.
+    pub fn simulate_bundle(
+        &self,
+        bundle: &Bundle,
+        parent: &BlockHeader,
+        timestamp: u64,
+        coinbase: Address,
+    ) -> Result<BundleSimulation, ChainError> {
+        let chain_config = self.storage.get_chain_config();
+        let args = BuildPayloadArgs {
+            parent: parent.hash(),
+            timestamp,
+            fee_recipient: coinbase,
+            random: H256::zero(),
+            withdrawals: chain_config.is_shanghai_activated(timestamp).then(Vec::new),
+            beacon_root: chain_config
+                .is_cancun_activated(timestamp)
+                .then_some(H256::zero()),
+            version: 0,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            gas_ceil: parent.gas_limit,
+        };
+        let payload = create_payload(&args, &self.storage, Bytes::new())?;
+        let mut context = PayloadBuildContext::new(payload, &self.storage, &self.options.r#type)?;
+        if let BlockchainType::L1 = self.options.r#type {
+            self.apply_system_operations(&mut context)?;
+        }
+
+        let deadline = Instant::now() + BUNDLE_SIMULATION_TIMEOUT;
+        let initial_balance = coinbase_balance(&mut context)?;
+        let mut results = Vec::with_capacity(bundle.transactions.len());
+        for (tx, sender) in &bundle.transactions {
+            if Instant::now() >= deadline {
+                return Err(ChainError::Custom("Bundle simulation timed out".to_owned()));
+            }
+            let balance_before = coinbase_balance(&mut context)?;
+            let gas_before = context.remaining_gas;
+            let head = HeadTransaction {
+                tip: tx
+                    .effective_gas_tip(context.base_fee_per_gas())
+                    .unwrap_or_default(),
+                tx: MempoolTransaction::new(tx.clone(), *sender),
+            };
+            let (succeeded, error) = match apply_plain_transaction(&head, &mut context) {
+                Ok(receipt) => (receipt.succeeded, None),
+                Err(error) => (false, Some(error.to_string())),
+            };
+            results.push(BundleTransactionResult {
+                tx_hash: tx.hash(),
+                sender: *sender,
+                to: tx.to(),
+                gas_used: gas_before - context.remaining_gas,
+                coinbase_diff: coinbase_balance(&mut context)?.saturating_sub(balance_before),
+                error,
+                succeeded,
+            });
+        }
+        Ok(BundleSimulation {
+            coinbase_diff: coinbase_balance(&mut context)?.saturating_sub(initial_balance),
+            gas_used: context.gas_used(),
+            results,
+        })
+    }
+
     /// Executes the transaction, updates gas-related context values & return the receipt
     /// The payload build context should have enough remaining gas to cover the transaction's gas_limit
     fn apply_transaction(
@@ -650,6 +794,18 @@ impl Blockchain {
     }
 
     pub fn finalize_payload(&self, context: &mut PayloadBuildContext) -> Result<(), ChainError> {
+        context
+            .vm
+            .db
+            .checkpoint_block_access_list(block_access_index(
+                context.payload.body.transactions.len() + 1,
+            )?);
+        context.block_access_list = context
+            .vm
+            .db
+            .block_access_list
+            .take()
+            .map(|recorder| recorder.build());
         let account_updates = context.vm.get_state_transitions()?;
 
         let ret_acount_updates_list = self
@@ -667,6 +823,10 @@ impl Blockchain {
             .requests
             .as_ref()
             .map(|requests| compute_requests_hash(requests));
+        context.payload.header.block_access_list_hash = context
+            .block_access_list
+            .as_ref()
+            .map(BlockAccessList::hash);
         context.payload.header.gas_used = context.payload.header.gas_limit - context.remaining_gas;
         context.account_updates = account_updates;
 
@@ -697,6 +857,75 @@ pub fn apply_plain_transaction(
     Ok(report)
 }
 
+/// Executes the transactions of a bundle and adds them to the payload.
+/// Returns the profit of the bundle, the increase of the fee recipient's balance, or `None` if
+/// the bundle can't be included because any of its transactions failed or reverted without being
+/// allowed to, in which case the context must be discarded.
+fn apply_bundle(
+    bundle: &Bundle,
+    context: &mut PayloadBuildContext,
+) -> Result<Option<U256>, ChainError> {
+    let value_before = context.block_value;
+    let balance_before = coinbase_balance(context)?;
+    for (tx, sender) in &bundle.transactions {
+        let tx_hash = tx.hash();
+        // Blob transactions aren't accepted in bundles, as their blobs aren't sent along
+        if context.remaining_gas < tx.gas_limit() || tx.tx_type() == TxType::EIP4844 {
+            return Ok(None);
+        }
+        let potential_rlp_block_size =
+            context.payload_size + tx.encode_canonical_to_vec().len() as u64;
+        if context
+            .chain_config()
+            .is_osaka_activated(context.payload.header.timestamp)
+            && potential_rlp_block_size > MAX_RLP_BLOCK_SIZE
+        {
+            return Ok(None);
+        }
+        let Some(tip) = tx.effective_gas_tip(context.base_fee_per_gas()) else {
+            return Ok(None);
+        };
+        let head = HeadTransaction {
+            tip,
+            tx: MempoolTransaction::new(tx.clone(), *sender),
+        };
+        let receipt = match apply_plain_transaction(&head, context) {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                debug!("Failed to execute bundle transaction: {tx_hash:x}, {e}");
+                return Ok(None);
+            }
+        };
+        if !receipt.succeeded && !bundle.may_revert(&tx_hash) {
+            debug!("Bundle transaction reverted: {tx_hash:x}");
+            return Ok(None);
+        }
+        context
+            .vm
+            .db
+            .checkpoint_block_access_list(block_access_index(
+                context.payload.body.transactions.len() + 1,
+            )?);
+        context.payload_size = potential_rlp_block_size;
+        context.payload.body.transactions.push(tx.clone());
+        context.receipts.push(receipt);
+    }
+    // Bundles usually pay the fee recipient directly on top of the priority fees
+    let profit = coinbase_balance(context)?.saturating_sub(balance_before);
+    context.block_value = value_before + profit;
+    Ok(Some(profit))
+}
+
+fn coinbase_balance(context: &mut PayloadBuildContext) -> Result<U256, ChainError> {
+    let coinbase = context.payload.header.coinbase;
+    let account = context
+        .vm
+        .db
+        .get_account(coinbase)
+        .map_err(EvmError::from)?;
+    Ok(account.info.balance)
+}
+
 /// A struct representing suitable mempool transactions waiting to be included in a block
 // TODO: Consider using VecDequeue instead of Vec
 pub struct TransactionQueue {
@@ -0,0 +1,85 @@
+//! Warming of the state read by a block before (and while) executing it.
+//!
+//! Execution loads accounts and storage slots lazily, so most of its time importing blocks is spent
+//! waiting on random reads to the store. Here we load what the block is likely to touch into the
+//! [`StoreVmDatabase`] state cache using several threads:
+//! - Senders, recipients and access lists of the transactions, before execution starts.
+//! - Whatever each transaction reads when executed on its own against the parent state, on a side
+//!   thread that runs along the actual execution.
+//!
+//! Prefetching is best effort: any error is ignored, as execution will hit it again and report it.
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use ethrex_common::Address;
+use ethrex_common::types::{Block, Transaction, TxKind};
+use ethrex_vm::VmDatabase;
+
+use crate::vm::StoreVmDatabase;
+use crate::{BlockchainType, new_evm};
+
+/// Loads the accounts and storage slots the block's transactions declare they'll touch.
+/// `transactions` are the ones of the block with their recovered senders.
+pub fn prefetch_block_state(
+    block: &Block,
+    transactions: &[(&Transaction, Address)],
+    vm_db: &StoreVmDatabase,
+) {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(transactions.len());
+    let next_tx = AtomicUsize::new(0);
+
+    std::thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| {
+                while let Some((tx, sender)) =
+                    transactions.get(next_tx.fetch_add(1, Ordering::Relaxed))
+                {
+                    prefetch_transaction_state(tx, *sender, vm_db);
+                }
+            });
+        }
+
+        let _ = vm_db.get_account_state(block.header.coinbase);
+        for withdrawal in block.body.withdrawals.iter().flatten() {
+            let _ = vm_db.get_account_state(withdrawal.address);
+        }
+    });
+}
+
+fn prefetch_transaction_state(tx: &Transaction, sender: Address, vm_db: &StoreVmDatabase) {
+    let _ = vm_db.get_account_state(sender);
+    if let TxKind::Call(to) = tx.to() {
+        let _ = vm_db.get_account_state(to);
+    }
+    for (address, keys) in tx.access_list() {
+        let _ = vm_db.get_account_state(*address);
+        for key in keys {
+            let _ = vm_db.get_storage_slot(*address, *key);
+        }
+    }
+}
+
+/// Executes every transaction of the block on its own against the parent state, so the state they
+/// read is already cached when the actual execution gets to them. Stops as soon as `done` is set.
+pub fn pre_execute_block(
+    block: &Block,
+    transactions: &[(&Transaction, Address)],
+    vm_db: &StoreVmDatabase,
+    blockchain_type: &BlockchainType,
+    done: &AtomicBool,
+) {
+    for (tx, sender) in transactions {
+        if done.load(Ordering::Relaxed) {
+            return;
+        }
+        let Ok(mut vm) = new_evm(blockchain_type, vm_db.clone()) else {
+            return;
+        };
+        let mut remaining_gas = block.header.gas_limit;
+        // Transactions don't see the changes of the previous ones here, so failures are expected
+        let _ = vm.execute_tx(tx, &block.header, &mut remaining_gas, *sender);
+    }
+}
@@ -12,8 +12,11 @@ mod blockchain_integration_test {
 
     use bytes::Bytes;
     use ethrex_common::{
-        H160, H256,
-        types::{Block, BlockHeader, DEFAULT_BUILDER_GAS_CEIL, ELASTICITY_MULTIPLIER},
+        Address, H160, H256, U256,
+        types::{
+            Block, BlockHeader, DEFAULT_BUILDER_GAS_CEIL, ELASTICITY_MULTIPLIER, Genesis,
+            Withdrawal,
+        },
     };
     use ethrex_storage::{EngineType, Store};
 
@@ -287,6 +290,55 @@ mod blockchain_integration_test {
         assert_eq!(latest_canonical_block_hash(&store).await.unwrap(), hash_b);
     }
 
+    #[tokio::test]
+    async fn built_block_access_list_passes_validation() {
+        let mut genesis = test_genesis();
+        genesis.config.amsterdam_time = Some(0);
+        let store = store_with_genesis(genesis).await;
+        let genesis_header = store.get_block_header(0).unwrap().unwrap();
+        let blockchain = Blockchain::default_with_store(store.clone());
+
+        let withdrawal_address = Address::from_low_u64_be(0xaa);
+        let args = BuildPayloadArgs {
+            parent: genesis_header.hash(),
+            timestamp: genesis_header.timestamp + 12,
+            fee_recipient: H160::random(),
+            random: H256::random(),
+            withdrawals: Some(vec![Withdrawal {
+                index: 0,
+                validator_index: 0,
+                address: withdrawal_address,
+                amount: 1,
+            }]),
+            beacon_root: Some(H256::random()),
+            version: 1,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
+        };
+        let payload = create_payload(&args, &store, Bytes::new()).unwrap();
+        let result = blockchain.build_payload(payload).unwrap();
+
+        // The withdrawal is processed after the (no) transactions
+        let block_access_list = result.block_access_list.clone().unwrap();
+        let withdrawal_changes = block_access_list
+            .accounts
+            .iter()
+            .find(|account| account.address == withdrawal_address)
+            .unwrap();
+        assert_eq!(withdrawal_changes.balance_changes[0].block_access_index, 1);
+        assert_eq!(
+            withdrawal_changes.balance_changes[0].post_balance,
+            U256::from(1_000_000_000)
+        );
+        assert_eq!(
+            result.payload.header.block_access_list_hash,
+            Some(block_access_list.hash())
+        );
+
+        // Executing the block records the same access list
+        blockchain.add_block(result.payload).unwrap();
+    }
+
     async fn new_block(store: &Store, parent: &BlockHeader) -> Block {
         let args = BuildPayloadArgs {
             parent: parent.hash(),
@@ -308,13 +360,18 @@ mod blockchain_integration_test {
         result.payload
     }
 
-    async fn test_store() -> Store {
-        // Get genesis
+    fn test_genesis() -> Genesis {
         let file = File::open("../../fixtures/genesis/execution-api.json")
             .expect("Failed to open genesis file");
         let reader = BufReader::new(file);
-        let genesis = serde_json::from_reader(reader).expect("Failed to deserialize genesis file");
+        serde_json::from_reader(reader).expect("Failed to deserialize genesis file")
+    }
+
+    async fn test_store() -> Store {
+        store_with_genesis(test_genesis()).await
+    }
 
+    async fn store_with_genesis(genesis: Genesis) -> Store {
         // Build store with genesis
         let mut store =
             Store::new("store.db", EngineType::InMemory).expect("Failed to build DB for testing");
@@ -3,9 +3,17 @@ use std::{
     time::Duration,
 };
 
-use ethrex_common::{H256, tracing::CallTrace, types::Block};
+use ethrex_common::{
+    H256,
+    tracing::{CallTrace, InternalOperation},
+    types::{Block, BlockHeader, GenericTransaction, StateOverride},
+};
 use ethrex_storage::Store;
-use ethrex_vm::{Evm, EvmError};
+use ethrex_vm::{
+    Evm, EvmError,
+    tracing::{ReplayTrace, ReplayTraceConfig},
+};
+use serde_json::Value;
 
 use crate::{Blockchain, error::ChainError, vm::StoreVmDatabase};
 
@@ -43,6 +51,38 @@ impl Blockchain {
         .await
     }
 
+    /// Outputs the value transfers, contract creations and self destructs made by the given transaction besides
+    /// its top call
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
+    pub async fn trace_transaction_internal_operations(
+        &self,
+        tx_hash: H256,
+        reexec: u32,
+        timeout: Duration,
+    ) -> Result<Vec<InternalOperation>, ChainError> {
+        // Fetch the transaction's location and the block it is contained in
+        let Some((_, block_hash, tx_index)) =
+            self.storage.get_transaction_location(tx_hash).await?
+        else {
+            return Err(ChainError::Custom("Transaction not Found".to_string()));
+        };
+        let tx_index = tx_index as usize;
+        let Some(block) = self.storage.get_block_by_hash(block_hash).await? else {
+            return Err(ChainError::Custom("Block not Found".to_string()));
+        };
+        // Obtain the block's parent state
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        // Run the block until the transaction we want to trace
+        vm.rerun_block(&block, Some(tx_index))?;
+        // Trace the transaction
+        timeout_trace_operation(timeout, move || {
+            vm.trace_tx_internal_operations(&block, tx_index)
+        })
+        .await
+    }
+
     /// Outputs the call trace for each transaction in the block along with the transaction's hash
     /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
     /// Returns transaction call traces from oldest to newest
@@ -82,6 +122,211 @@ impl Blockchain {
         Ok(call_traces)
     }
 
+    /// Outputs the result of the native tracer registered under `tracer_name` for the given transaction
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
+    pub async fn trace_transaction_with(
+        &self,
+        tx_hash: H256,
+        reexec: u32,
+        timeout: Duration,
+        tracer_name: String,
+        tracer_config: Option<Value>,
+    ) -> Result<Value, ChainError> {
+        // Fetch the transaction's location and the block it is contained in
+        let Some((_, block_hash, tx_index)) =
+            self.storage.get_transaction_location(tx_hash).await?
+        else {
+            return Err(ChainError::Custom("Transaction not Found".to_string()));
+        };
+        let tx_index = tx_index as usize;
+        let Some(block) = self.storage.get_block_by_hash(block_hash).await? else {
+            return Err(ChainError::Custom("Block not Found".to_string()));
+        };
+        // Obtain the block's parent state
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        // Run the block until the transaction we want to trace
+        vm.rerun_block(&block, Some(tx_index))?;
+        // Trace the transaction
+        let tracers = self.tracers.clone();
+        timeout_trace_operation(timeout, move || {
+            vm.trace_tx(&block, tx_index, &tracers, &tracer_name, tracer_config)
+        })
+        .await
+    }
+
+    /// Outputs the result of the native tracer registered under `tracer_name` for each transaction in the block
+    /// along with the transaction's hash. A new tracer is built for each transaction.
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
+    /// Returns transaction traces from oldest to newest
+    pub async fn trace_block_with(
+        &self,
+        block: Block,
+        reexec: u32,
+        timeout: Duration,
+        tracer_name: String,
+        tracer_config: Option<Value>,
+    ) -> Result<Vec<(H256, Value)>, ChainError> {
+        // Obtain the block's parent state
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        // Run anything necessary before executing the block's transactions (system calls, etc)
+        vm.rerun_block(&block, Some(0))?;
+        let vm = Arc::new(Mutex::new(vm));
+        let block = Arc::new(block);
+        let tracer_name = Arc::new(tracer_name);
+        let mut traces = vec![];
+        for index in 0..block.body.transactions.len() {
+            let block = block.clone();
+            let vm = vm.clone();
+            let tracers = self.tracers.clone();
+            let tracer_name = tracer_name.clone();
+            let tracer_config = tracer_config.clone();
+            let tx_hash = block.as_ref().body.transactions[index].hash();
+            let trace = timeout_trace_operation(timeout, move || {
+                vm.lock()
+                    .map_err(|_| EvmError::Custom("Unexpected Runtime Error".to_string()))?
+                    .trace_tx(block.as_ref(), index, &tracers, &tracer_name, tracer_config)
+            })
+            .await?;
+            traces.push((tx_hash, trace));
+        }
+        Ok(traces)
+    }
+
+    /// Outputs the call trace of a transaction simulated on top of the state after the given block, with the
+    /// given state overrides applied first
+    /// May need to re-execute blocks in order to rebuild the block's state, up to the amount given by `reexec`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn trace_call_calls(
+        &self,
+        tx: GenericTransaction,
+        block_hash: H256,
+        state_override: StateOverride,
+        reexec: u32,
+        timeout: Duration,
+        only_top_call: bool,
+        with_log: bool,
+    ) -> Result<CallTrace, ChainError> {
+        let (mut vm, header) = self
+            .rebuild_call_state(block_hash, &state_override, reexec)
+            .await?;
+        timeout_trace_operation(timeout, move || {
+            vm.trace_generic_tx_calls(&tx, &header, only_top_call, with_log)
+        })
+        .await
+    }
+
+    /// Outputs the result of the native tracer registered under `tracer_name` for a transaction simulated on top
+    /// of the state after the given block, with the given state overrides applied first
+    /// May need to re-execute blocks in order to rebuild the block's state, up to the amount given by `reexec`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn trace_call_with(
+        &self,
+        tx: GenericTransaction,
+        block_hash: H256,
+        state_override: StateOverride,
+        reexec: u32,
+        timeout: Duration,
+        tracer_name: String,
+        tracer_config: Option<Value>,
+    ) -> Result<Value, ChainError> {
+        let (mut vm, header) = self
+            .rebuild_call_state(block_hash, &state_override, reexec)
+            .await?;
+        let tracers = self.tracers.clone();
+        timeout_trace_operation(timeout, move || {
+            vm.trace_generic_tx(&tx, &header, &tracers, &tracer_name, tracer_config)
+        })
+        .await
+    }
+
+    /// Rebuilds the state after the given block with the state overrides applied, returning it along with
+    /// the block's header, which calls are simulated in
+    async fn rebuild_call_state(
+        &self,
+        block_hash: H256,
+        state_override: &StateOverride,
+        reexec: u32,
+    ) -> Result<(Evm, BlockHeader), ChainError> {
+        let header = self
+            .storage
+            .get_block_header_by_hash(block_hash)?
+            .ok_or(ChainError::Custom("Block not Found".to_string()))?;
+        let mut vm = self.rebuild_parent_state(block_hash, reexec).await?;
+        vm.apply_state_override(state_override)?;
+        Ok((vm, header))
+    }
+
+    /// Replays the given transaction, outputting its call trace and the traces enabled in `config`
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
+    pub async fn trace_transaction_replay(
+        &self,
+        tx_hash: H256,
+        reexec: u32,
+        timeout: Duration,
+        config: ReplayTraceConfig,
+    ) -> Result<ReplayTrace, ChainError> {
+        // Fetch the transaction's location and the block it is contained in
+        let Some((_, block_hash, tx_index)) =
+            self.storage.get_transaction_location(tx_hash).await?
+        else {
+            return Err(ChainError::Custom("Transaction not Found".to_string()));
+        };
+        let tx_index = tx_index as usize;
+        let Some(block) = self.storage.get_block_by_hash(block_hash).await? else {
+            return Err(ChainError::Custom("Block not Found".to_string()));
+        };
+        // Obtain the block's parent state
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        // Run the block until the transaction we want to trace
+        vm.rerun_block(&block, Some(tx_index))?;
+        // Trace the transaction
+        timeout_trace_operation(timeout, move || {
+            vm.trace_tx_replay(&block, tx_index, config)
+        })
+        .await
+    }
+
+    /// Replays every transaction in the block, outputting their call traces and the traces enabled in `config`
+    /// along with the transaction's hash.
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
+    /// Returns transaction traces from oldest to newest
+    pub async fn trace_block_replay(
+        &self,
+        block: Block,
+        reexec: u32,
+        timeout: Duration,
+        config: ReplayTraceConfig,
+    ) -> Result<Vec<(H256, ReplayTrace)>, ChainError> {
+        // Obtain the block's parent state
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        // Run anything necessary before executing the block's transactions (system calls, etc)
+        vm.rerun_block(&block, Some(0))?;
+        let vm = Arc::new(Mutex::new(vm));
+        let block = Arc::new(block);
+        let mut traces = vec![];
+        for index in 0..block.body.transactions.len() {
+            let block = block.clone();
+            let vm = vm.clone();
+            let tx_hash = block.as_ref().body.transactions[index].hash();
+            let trace = timeout_trace_operation(timeout, move || {
+                vm.lock()
+                    .map_err(|_| EvmError::Custom("Unexpected Runtime Error".to_string()))?
+                    .trace_tx_replay(block.as_ref(), index, config)
+            })
+            .await?;
+            traces.push((tx_hash, trace));
+        }
+        Ok(traces)
+    }
+
     /// Rebuild the parent state for a block given its parent hash, returning an `Evm` instance with all changes cached
     /// Will re-execute all ancestor block's which's state is not stored up to a maximum given by `reexec`
     async fn rebuild_parent_state(
@@ -1,14 +1,17 @@
 use ethrex_common::{
     Address, H256, U256,
     constants::EMPTY_KECCACK_HASH,
-    types::{AccountState, BlockHash, BlockHeader, BlockNumber, ChainConfig, Code},
+    types::{
+        AccountState, BlockHash, BlockHeader, BlockNumber, ChainConfig, Code, CodeAnalysisCache,
+    },
 };
 use ethrex_storage::Store;
 use ethrex_vm::{EvmError, VmDatabase};
+use rustc_hash::FxHashMap;
 use std::{
     cmp::Ordering,
     collections::BTreeMap,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, RwLock},
 };
 use tracing::instrument;
 
@@ -21,6 +24,16 @@ pub struct StoreVmDatabase {
     // and may need to access hashes of blocks previously executed in the batch
     pub block_hash_cache: Arc<Mutex<BTreeMap<BlockNumber, BlockHash>>>,
     pub state_root: H256,
+    // Used to keep the accounts and storage slots read from the store when prefetching the state of a block
+    // ahead of its execution. Shared by every clone of the database so it can be warmed from other threads.
+    pub state_cache: Option<Arc<StateCache>>,
+}
+
+/// Accounts and storage slots read from the store at a given state root.
+#[derive(Default)]
+pub struct StateCache {
+    accounts: RwLock<FxHashMap<Address, Option<AccountState>>>,
+    storage: RwLock<FxHashMap<(Address, H256), Option<U256>>>,
 }
 
 impl StoreVmDatabase {
@@ -40,6 +53,7 @@ impl StoreVmDatabase {
             block_hash: block_header.hash(),
             block_hash_cache: Arc::new(Mutex::new(BTreeMap::new())),
             state_root: block_header.state_root,
+            state_cache: None,
         })
     }
 
@@ -60,8 +74,15 @@ impl StoreVmDatabase {
             block_hash: block_header.hash(),
             block_hash_cache: Arc::new(Mutex::new(block_hash_cache)),
             state_root: block_header.state_root,
+            state_cache: None,
         })
     }
+
+    /// Enables caching the state read from the store, so that it can be prefetched before execution.
+    pub fn with_state_cache(mut self) -> Self {
+        self.state_cache = Some(Arc::new(StateCache::default()));
+        self
+    }
 }
 
 impl VmDatabase for StoreVmDatabase {
@@ -72,9 +93,30 @@ impl VmDatabase for StoreVmDatabase {
         fields(namespace = "block_execution")
     )]
     fn get_account_state(&self, address: Address) -> Result<Option<AccountState>, EvmError> {
-        self.store
+        let Some(state_cache) = &self.state_cache else {
+            return self
+                .store
+                .get_account_state_by_root(self.state_root, address)
+                .map_err(|e| EvmError::DB(e.to_string()));
+        };
+        if let Some(account_state) = state_cache
+            .accounts
+            .read()
+            .map_err(|_| EvmError::Custom("LockError".to_string()))?
+            .get(&address)
+        {
+            return Ok(account_state.clone());
+        }
+        let account_state = self
+            .store
             .get_account_state_by_root(self.state_root, address)
-            .map_err(|e| EvmError::DB(e.to_string()))
+            .map_err(|e| EvmError::DB(e.to_string()))?;
+        state_cache
+            .accounts
+            .write()
+            .map_err(|_| EvmError::Custom("LockError".to_string()))?
+            .insert(address, account_state.clone());
+        Ok(account_state)
     }
 
     #[instrument(
@@ -84,9 +126,30 @@ impl VmDatabase for StoreVmDatabase {
         fields(namespace = "block_execution")
     )]
     fn get_storage_slot(&self, address: Address, key: H256) -> Result<Option<U256>, EvmError> {
-        self.store
+        let Some(state_cache) = &self.state_cache else {
+            return self
+                .store
+                .get_storage_at_root(self.state_root, address, key)
+                .map_err(|e| EvmError::DB(e.to_string()));
+        };
+        if let Some(value) = state_cache
+            .storage
+            .read()
+            .map_err(|_| EvmError::Custom("LockError".to_string()))?
+            .get(&(address, key))
+        {
+            return Ok(*value);
+        }
+        let value = self
+            .store
             .get_storage_at_root(self.state_root, address, key)
-            .map_err(|e| EvmError::DB(e.to_string()))
+            .map_err(|e| EvmError::DB(e.to_string()))?;
+        state_cache
+            .storage
+            .write()
+            .map_err(|_| EvmError::Custom("LockError".to_string()))?
+            .insert((address, key), value);
+        Ok(value)
     }
 
     #[instrument(
@@ -168,4 +231,126 @@ impl VmDatabase for StoreVmDatabase {
             Err(e) => Err(EvmError::DB(e.to_string())),
         }
     }
+
+    fn code_analysis_cache(&self) -> Option<Arc<CodeAnalysisCache>> {
+        Some(self.store.code_analysis_cache())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ethrex_common::types::{Genesis, GenesisAccount};
+    use ethrex_storage::EngineType;
+
+    use super::*;
+
+    const ACCOUNT: Address = Address::repeat_byte(0xaa);
+    const MISSING_ACCOUNT: Address = Address::repeat_byte(0xbb);
+
+    /// Database at the state of a genesis with a single account, that has slot 1 set to 7.
+    async fn genesis_vm_db() -> StoreVmDatabase {
+        let genesis = Genesis {
+            alloc: BTreeMap::from([(
+                ACCOUNT,
+                GenesisAccount {
+                    code: Default::default(),
+                    storage: HashMap::from([(U256::one(), U256::from(7))]),
+                    balance: U256::from(100),
+                    nonce: 1,
+                },
+            )]),
+            ..Default::default()
+        };
+        let header = genesis.get_block().header;
+        let mut store = Store::new("test", EngineType::InMemory).unwrap();
+        store.add_initial_state(genesis).await.unwrap();
+        StoreVmDatabase::new(store, header).unwrap()
+    }
+
+    fn slot(key: u64) -> H256 {
+        H256::from_low_u64_be(key)
+    }
+
+    #[tokio::test]
+    async fn reads_are_cached_and_shared_by_clones() {
+        let vm_db = genesis_vm_db().await.with_state_cache();
+        let prefetcher = vm_db.clone();
+
+        let account = prefetcher.get_account_state(ACCOUNT).unwrap().unwrap();
+        assert_eq!((account.balance, account.nonce), (U256::from(100), 1));
+        assert_eq!(
+            prefetcher.get_storage_slot(ACCOUNT, slot(1)).unwrap(),
+            Some(U256::from(7))
+        );
+
+        let state_cache = vm_db.state_cache.as_ref().unwrap();
+        assert!(Arc::ptr_eq(
+            state_cache,
+            prefetcher.state_cache.as_ref().unwrap()
+        ));
+        assert_eq!(
+            state_cache.accounts.read().unwrap().get(&ACCOUNT),
+            Some(&Some(account))
+        );
+        assert_eq!(
+            state_cache.storage.read().unwrap().get(&(ACCOUNT, slot(1))),
+            Some(&Some(U256::from(7)))
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_state_is_read_instead_of_the_store() {
+        let vm_db = genesis_vm_db().await.with_state_cache();
+        let state_cache = vm_db.state_cache.clone().unwrap();
+        state_cache.accounts.write().unwrap().insert(ACCOUNT, None);
+        state_cache
+            .storage
+            .write()
+            .unwrap()
+            .insert((ACCOUNT, slot(1)), Some(U256::from(8)));
+
+        assert_eq!(vm_db.get_account_state(ACCOUNT).unwrap(), None);
+        assert_eq!(
+            vm_db.get_storage_slot(ACCOUNT, slot(1)).unwrap(),
+            Some(U256::from(8))
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_state_is_cached_too() {
+        let vm_db = genesis_vm_db().await.with_state_cache();
+
+        assert_eq!(vm_db.get_account_state(MISSING_ACCOUNT).unwrap(), None);
+        assert_eq!(vm_db.get_storage_slot(ACCOUNT, slot(2)).unwrap(), None);
+
+        let state_cache = vm_db.state_cache.as_ref().unwrap();
+        assert_eq!(
+            state_cache.accounts.read().unwrap().get(&MISSING_ACCOUNT),
+            Some(&None)
+        );
+        assert_eq!(
+            state_cache.storage.read().unwrap().get(&(ACCOUNT, slot(2))),
+            Some(&None)
+        );
+    }
+
+    #[tokio::test]
+    async fn state_is_not_cached_unless_enabled() {
+        let vm_db = genesis_vm_db().await;
+
+        assert!(vm_db.state_cache.is_none());
+        assert_eq!(
+            vm_db
+                .get_account_state(ACCOUNT)
+                .unwrap()
+                .map(|account| account.balance),
+            Some(U256::from(100))
+        );
+        assert_eq!(
+            vm_db.get_storage_slot(ACCOUNT, slot(1)).unwrap(),
+            Some(U256::from(7))
+        );
+    }
 }
@@ -141,6 +141,28 @@ impl Network {
     }
 }
 
+impl PublicNetwork {
+    /// Genesis time of the network's beacon chain
+    pub fn beacon_genesis_time(&self) -> u64 {
+        match self {
+            PublicNetwork::Holesky => 1695902400,
+            PublicNetwork::Hoodi => 1742213400,
+            PublicNetwork::Mainnet => 1606824023,
+            PublicNetwork::Sepolia => 1655733600,
+        }
+    }
+
+    /// Fork version of the network's beacon chain at genesis
+    pub fn genesis_fork_version(&self) -> [u8; 4] {
+        match self {
+            PublicNetwork::Holesky => [0x01, 0x01, 0x70, 0x00],
+            PublicNetwork::Hoodi => [0x10, 0x00, 0x09, 0x10],
+            PublicNetwork::Mainnet => [0x00, 0x00, 0x00, 0x00],
+            PublicNetwork::Sepolia => [0x90, 0x00, 0x00, 0x69],
+        }
+    }
+}
+
 fn get_genesis_contents(network: PublicNetwork) -> &'static str {
     match network {
         PublicNetwork::Holesky => HOLESKY_GENESIS_CONTENTS,
@@ -272,6 +272,24 @@ pub mod u64 {
             .map_err(|_| D::Error::custom("Failed to deserialize u64 value"))
     }
 
+    pub mod dec_str {
+        use super::*;
+
+        pub fn deserialize<'de, D>(d: D) -> Result<u64, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            super::deser_dec_str(d)
+        }
+
+        pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&value.to_string())
+        }
+    }
+
     pub fn deser_hex_or_dec_str<'de, D>(d: D) -> Result<u64, D::Error>
     where
         D: Deserializer<'de>,
@@ -383,6 +401,32 @@ pub mod bytes {
             serialize_vec_of_hex_encodables(value, serializer)
         }
     }
+
+    pub mod opt {
+        use serde::Serialize;
+
+        use super::*;
+
+        pub fn deserialize<'de, D>(d: D) -> Result<Option<Bytes>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Option::<String>::deserialize(d)?
+                .map(|value| {
+                    hex::decode(value.trim_start_matches("0x"))
+                        .map(Bytes::from)
+                        .map_err(|e| D::Error::custom(e.to_string()))
+                })
+                .transpose()
+        }
+
+        pub fn serialize<S>(value: &Option<Bytes>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            Option::<String>::serialize(&value.as_ref().map(|v| format!("0x{v:x}")), serializer)
+        }
+    }
 }
 
 /// Serializes to and deserializes from 0x prefixed hex string
@@ -417,6 +461,18 @@ pub mod bytes48 {
         serializer.serialize_str(&format!("0x{}", hex::encode(value)))
     }
 
+    pub fn deserialize<'de, D>(d: D) -> Result<[u8; 48], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(d)?;
+        let bytes = hex::decode(value.trim_start_matches("0x"))
+            .map_err(|e| D::Error::custom(e.to_string()))?;
+        bytes.try_into().map_err(|bytes: Vec<u8>| {
+            D::Error::custom(format!("Expected 48 bytes, got {}", bytes.len()))
+        })
+    }
+
     pub mod vec {
         use super::*;
 
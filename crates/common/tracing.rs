@@ -2,6 +2,7 @@ use bytes::Bytes;
 use ethereum_types::H256;
 use ethereum_types::{Address, U256};
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Collection of traces of each call frame as defined in geth's `callTracer` output
 /// https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#call-tracer
@@ -65,3 +66,117 @@ pub struct CallLog {
     pub data: Bytes,
     pub position: u64,
 }
+
+/// Changes made by a transaction to each account, as defined in Parity's `stateDiff` trace
+/// https://openethereum.github.io/JSONRPC-trace-module#statediff
+pub type StateDiff = BTreeMap<Address, AccountDiff>;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AccountDiff {
+    pub balance: Delta<U256>,
+    pub nonce: Delta<U256>,
+    pub code: Delta<HexBytes>,
+    pub storage: BTreeMap<H256, Delta<H256>>,
+}
+
+/// Change of a single value. Accounts that are created or destroyed have every value `Born` or
+/// `Died` respectively.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub enum Delta<T> {
+    #[serde(rename = "=")]
+    Unchanged,
+    #[serde(rename = "+")]
+    Born(T),
+    #[serde(rename = "-")]
+    Died(T),
+    #[serde(rename = "*")]
+    Changed { from: T, to: T },
+}
+
+impl<T: PartialEq> Delta<T> {
+    pub fn new(from: T, to: T) -> Self {
+        if from == to {
+            Delta::Unchanged
+        } else {
+            Delta::Changed { from, to }
+        }
+    }
+}
+
+/// Bytes serialized as a hex string.
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(transparent)]
+pub struct HexBytes(#[serde(with = "crate::serde_utils::bytes")] pub Bytes);
+
+/// Trace of every opcode executed, as defined in Parity's `vmTrace` trace
+/// https://openethereum.github.io/JSONRPC-trace-module#vmtrace
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct VmTrace {
+    /// Code executed by the call frame
+    #[serde(with = "crate::serde_utils::bytes")]
+    pub code: Bytes,
+    pub ops: Vec<VmOperation>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct VmOperation {
+    pub pc: usize,
+    /// Gas consumed by the operation, including the gas used by the call frames it created
+    pub cost: u64,
+    /// Result of the operation, missing if it halted
+    pub ex: Option<VmExecutedOperation>,
+    /// Trace of the call frame created by CALL and CREATE like operations
+    pub sub: Option<VmTrace>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct VmExecutedOperation {
+    /// Gas remaining after the operation
+    pub used: u64,
+    /// Values pushed to the stack
+    pub push: Vec<U256>,
+    /// Memory written by the operation
+    pub mem: Option<MemoryDiff>,
+    /// Storage slot written by the operation
+    pub store: Option<StorageDiff>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct MemoryDiff {
+    pub off: usize,
+    #[serde(with = "crate::serde_utils::bytes")]
+    pub data: Bytes,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct StorageDiff {
+    pub key: U256,
+    pub val: U256,
+}
+
+/// Value transfer, contract creation or self destruct made by a transaction besides its top call,
+/// as defined by Otterscan's `ots_getInternalOperations`
+/// https://github.com/otterscan/otterscan/blob/develop/docs/custom-jsonrpc.md#ots_getinternaloperations
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct InternalOperation {
+    #[serde(rename = "type")]
+    pub operation_type: InternalOperationType,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+}
+
+/// Serialized as its number, as Otterscan expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InternalOperationType {
+    Transfer = 0,
+    SelfDestruct = 1,
+    Create = 2,
+    Create2 = 3,
+}
+
+impl Serialize for InternalOperationType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(*self as u8)
+    }
+}
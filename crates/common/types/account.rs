@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use bytes::Bytes;
 use ethereum_types::{H256, U256};
@@ -14,7 +14,7 @@ use ethrex_rlp::{
     structs::{Decoder, Encoder},
 };
 
-use super::GenesisAccount;
+use super::{CodeAnalysis, CodeAnalysisCache, GenesisAccount};
 use crate::{
     constants::{EMPTY_KECCACK_HASH, EMPTY_TRIE_HASH},
     utils::keccak,
@@ -29,11 +29,9 @@ pub struct Code {
     // endpoints to access that hash, saving one expensive Keccak hash.
     pub hash: H256,
     pub bytecode: Bytes,
-    // TODO: Consider using Arc<[u32]> (needs to enable serde rc feature)
-    // The valid addresses are 32-bit because, despite EIP-3860 restricting initcode size,
-    // this does not apply to previous forks. This is tested in the EEST tests, which would
-    // panic in debug mode.
-    pub jump_targets: Vec<u32>,
+    // Shared between clones so loading the code into a call frame doesn't copy it. Persisted
+    // codes get it from the `CodeAnalysisCache`, so hot contracts are only analysed once.
+    pub analysis: Arc<CodeAnalysis>,
 }
 
 impl Code {
@@ -41,44 +39,32 @@ impl Code {
     // the real code hash (i.e. it was precomputed and we're reusing)
     // or never be read (e.g. for initcode).
     pub fn from_bytecode_unchecked(code: Bytes, hash: H256) -> Self {
-        let jump_targets = Self::compute_jump_targets(&code);
+        let analysis = Arc::new(CodeAnalysis::analyze(&code));
         Self {
             hash,
             bytecode: code,
-            jump_targets,
+            analysis,
         }
     }
 
     pub fn from_bytecode(code: Bytes) -> Self {
-        let jump_targets = Self::compute_jump_targets(&code);
+        let analysis = Arc::new(CodeAnalysis::analyze(&code));
         Self {
             hash: keccak(code.as_ref()),
             bytecode: code,
-            jump_targets,
+            analysis,
         }
     }
 
-    fn compute_jump_targets(code: &[u8]) -> Vec<u32> {
-        debug_assert!(code.len() <= u32::MAX as usize);
-        let mut targets = Vec::new();
-        let mut i = 0;
-        while i < code.len() {
-            // TODO: we don't use the constants from the vm module to avoid a circular dependency
-            match code[i] {
-                // OP_JUMPDEST
-                0x5B => {
-                    targets.push(i as u32);
-                }
-                // OP_PUSH1..32
-                c @ 0x60..0x80 => {
-                    // OP_PUSH0
-                    i += (c - 0x5F) as usize;
-                }
-                _ => (),
-            }
-            i += 1;
+    // SAFETY: same as `from_bytecode_unchecked`, and the hash is also used as the cache key, so
+    // it must never be the bogus one used for initcodes.
+    pub fn from_bytecode_cached(code: Bytes, hash: H256, cache: &CodeAnalysisCache) -> Self {
+        let analysis = cache.get_or_analyze(hash, &code);
+        Self {
+            hash,
+            bytecode: code,
+            analysis,
         }
-        targets
     }
 
     /// Estimates the size of the Code struct in bytes
@@ -92,8 +78,7 @@ impl Code {
     pub fn size(&self) -> usize {
         let hash_size = size_of::<H256>();
         let bytes_size = size_of::<Bytes>();
-        let vec_size = size_of::<Vec<u32>>() + self.jump_targets.len() * size_of::<u32>();
-        hash_size + bytes_size + vec_size
+        hash_size + bytes_size + self.analysis.size()
     }
 }
 
@@ -151,7 +136,7 @@ impl Default for Code {
         Self {
             bytecode: Bytes::new(),
             hash: *EMPTY_KECCACK_HASH,
-            jump_targets: Vec::new(),
+            analysis: Arc::default(),
         }
     }
 }
@@ -0,0 +1,450 @@
+//! Block-level access lists as defined in [EIP-7928](https://eips.ethereum.org/EIPS/eip-7928).
+//!
+//! A block access list contains every account and storage slot accessed while executing a block,
+//! together with the values they were left with after each transaction that changed them.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+use ethrex_rlp::{
+    decode::RLPDecode,
+    encode::RLPEncode,
+    error::RLPDecodeError,
+    structs::{Decoder, Encoder},
+};
+
+use crate::utils::keccak;
+
+/// Position in the block of the execution step that made a change:
+/// 0 for the system calls made before the transactions, `i + 1` for the transaction at index `i`
+/// and `n + 1` for the withdrawals and requests processed after the `n` transactions.
+pub type BlockAccessIndex = u16;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockAccessList {
+    /// Sorted by address.
+    pub accounts: Vec<AccountChanges>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountChanges {
+    pub address: Address,
+    /// Sorted by slot.
+    pub storage_changes: Vec<SlotChanges>,
+    /// Slots read but never written, sorted.
+    pub storage_reads: Vec<U256>,
+    pub balance_changes: Vec<BalanceChange>,
+    pub nonce_changes: Vec<NonceChange>,
+    pub code_changes: Vec<CodeChange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlotChanges {
+    pub slot: U256,
+    /// Sorted by block access index.
+    pub changes: Vec<StorageChange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageChange {
+    pub block_access_index: BlockAccessIndex,
+    pub post_value: U256,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub block_access_index: BlockAccessIndex,
+    pub post_balance: U256,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NonceChange {
+    pub block_access_index: BlockAccessIndex,
+    pub post_nonce: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeChange {
+    pub block_access_index: BlockAccessIndex,
+    pub new_code: Bytes,
+}
+
+impl BlockAccessList {
+    /// Hash committed to in the `block_access_list_hash` field of the block header.
+    pub fn hash(&self) -> H256 {
+        keccak(self.encode_to_vec())
+    }
+}
+
+/// Collects the accesses and changes made while executing a block, in any order,
+/// and sorts them into its [`BlockAccessList`].
+#[derive(Debug, Clone, Default)]
+pub struct BlockAccessListBuilder {
+    accounts: BTreeMap<Address, AccountChangesBuilder>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct AccountChangesBuilder {
+    storage_changes: BTreeMap<U256, BTreeMap<BlockAccessIndex, U256>>,
+    storage_reads: BTreeSet<U256>,
+    balance_changes: BTreeMap<BlockAccessIndex, U256>,
+    nonce_changes: BTreeMap<BlockAccessIndex, u64>,
+    code_changes: BTreeMap<BlockAccessIndex, Bytes>,
+}
+
+impl BlockAccessListBuilder {
+    /// Includes the account in the access list, even if it doesn't change.
+    pub fn touch_account(&mut self, address: Address) {
+        self.accounts.entry(address).or_default();
+    }
+
+    pub fn record_storage_read(&mut self, address: Address, slot: H256) {
+        self.accounts
+            .entry(address)
+            .or_default()
+            .storage_reads
+            .insert(U256::from_big_endian(slot.as_bytes()));
+    }
+
+    pub fn record_storage_change(
+        &mut self,
+        address: Address,
+        slot: H256,
+        index: BlockAccessIndex,
+        post_value: U256,
+    ) {
+        self.accounts
+            .entry(address)
+            .or_default()
+            .storage_changes
+            .entry(U256::from_big_endian(slot.as_bytes()))
+            .or_default()
+            .insert(index, post_value);
+    }
+
+    pub fn record_balance_change(
+        &mut self,
+        address: Address,
+        index: BlockAccessIndex,
+        post_balance: U256,
+    ) {
+        self.accounts
+            .entry(address)
+            .or_default()
+            .balance_changes
+            .insert(index, post_balance);
+    }
+
+    pub fn record_nonce_change(
+        &mut self,
+        address: Address,
+        index: BlockAccessIndex,
+        post_nonce: u64,
+    ) {
+        self.accounts
+            .entry(address)
+            .or_default()
+            .nonce_changes
+            .insert(index, post_nonce);
+    }
+
+    pub fn record_code_change(&mut self, address: Address, index: BlockAccessIndex, code: Bytes) {
+        self.accounts
+            .entry(address)
+            .or_default()
+            .code_changes
+            .insert(index, code);
+    }
+
+    pub fn build(self) -> BlockAccessList {
+        let accounts = self
+            .accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let storage_reads = account
+                    .storage_reads
+                    .into_iter()
+                    // A slot that was written at some point of the block is only listed in its changes
+                    .filter(|slot| !account.storage_changes.contains_key(slot))
+                    .collect();
+                AccountChanges {
+                    address,
+                    storage_changes: account
+                        .storage_changes
+                        .into_iter()
+                        .map(|(slot, changes)| SlotChanges {
+                            slot,
+                            changes: changes
+                                .into_iter()
+                                .map(|(block_access_index, post_value)| StorageChange {
+                                    block_access_index,
+                                    post_value,
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                    storage_reads,
+                    balance_changes: account
+                        .balance_changes
+                        .into_iter()
+                        .map(|(block_access_index, post_balance)| BalanceChange {
+                            block_access_index,
+                            post_balance,
+                        })
+                        .collect(),
+                    nonce_changes: account
+                        .nonce_changes
+                        .into_iter()
+                        .map(|(block_access_index, post_nonce)| NonceChange {
+                            block_access_index,
+                            post_nonce,
+                        })
+                        .collect(),
+                    code_changes: account
+                        .code_changes
+                        .into_iter()
+                        .map(|(block_access_index, new_code)| CodeChange {
+                            block_access_index,
+                            new_code,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        BlockAccessList { accounts }
+    }
+}
+
+impl RLPEncode for BlockAccessList {
+    fn encode(&self, buf: &mut dyn bytes::BufMut) {
+        self.accounts.encode(buf)
+    }
+
+    fn length(&self) -> usize {
+        self.accounts.length()
+    }
+}
+
+impl RLPDecode for BlockAccessList {
+    fn decode_unfinished(rlp: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
+        let (accounts, rest) = Vec::<AccountChanges>::decode_unfinished(rlp)?;
+        Ok((BlockAccessList { accounts }, rest))
+    }
+}
+
+impl RLPEncode for AccountChanges {
+    fn encode(&self, buf: &mut dyn bytes::BufMut) {
+        Encoder::new(buf)
+            .encode_field(&self.address)
+            .encode_field(&self.storage_changes)
+            .encode_field(&self.storage_reads)
+            .encode_field(&self.balance_changes)
+            .encode_field(&self.nonce_changes)
+            .encode_field(&self.code_changes)
+            .finish();
+    }
+}
+
+impl RLPDecode for AccountChanges {
+    fn decode_unfinished(rlp: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
+        let decoder = Decoder::new(rlp)?;
+        let (address, decoder) = decoder.decode_field("address")?;
+        let (storage_changes, decoder) = decoder.decode_field("storage_changes")?;
+        let (storage_reads, decoder) = decoder.decode_field("storage_reads")?;
+        let (balance_changes, decoder) = decoder.decode_field("balance_changes")?;
+        let (nonce_changes, decoder) = decoder.decode_field("nonce_changes")?;
+        let (code_changes, decoder) = decoder.decode_field("code_changes")?;
+        Ok((
+            AccountChanges {
+                address,
+                storage_changes,
+                storage_reads,
+                balance_changes,
+                nonce_changes,
+                code_changes,
+            },
+            decoder.finish()?,
+        ))
+    }
+}
+
+impl RLPEncode for SlotChanges {
+    fn encode(&self, buf: &mut dyn bytes::BufMut) {
+        Encoder::new(buf)
+            .encode_field(&self.slot)
+            .encode_field(&self.changes)
+            .finish();
+    }
+}
+
+impl RLPDecode for SlotChanges {
+    fn decode_unfinished(rlp: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
+        let decoder = Decoder::new(rlp)?;
+        let (slot, decoder) = decoder.decode_field("slot")?;
+        let (changes, decoder) = decoder.decode_field("changes")?;
+        Ok((SlotChanges { slot, changes }, decoder.finish()?))
+    }
+}
+
+impl RLPEncode for StorageChange {
+    fn encode(&self, buf: &mut dyn bytes::BufMut) {
+        Encoder::new(buf)
+            .encode_field(&self.block_access_index)
+            .encode_field(&self.post_value)
+            .finish();
+    }
+}
+
+impl RLPDecode for StorageChange {
+    fn decode_unfinished(rlp: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
+        let decoder = Decoder::new(rlp)?;
+        let (block_access_index, decoder) = decoder.decode_field("block_access_index")?;
+        let (post_value, decoder) = decoder.decode_field("post_value")?;
+        Ok((
+            StorageChange {
+                block_access_index,
+                post_value,
+            },
+            decoder.finish()?,
+        ))
+    }
+}
+
+impl RLPEncode for BalanceChange {
+    fn encode(&self, buf: &mut dyn bytes::BufMut) {
+        Encoder::new(buf)
+            .encode_field(&self.block_access_index)
+            .encode_field(&self.post_balance)
+            .finish();
+    }
+}
+
+impl RLPDecode for BalanceChange {
+    fn decode_unfinished(rlp: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
+        let decoder = Decoder::new(rlp)?;
+        let (block_access_index, decoder) = decoder.decode_field("block_access_index")?;
+        let (post_balance, decoder) = decoder.decode_field("post_balance")?;
+        Ok((
+            BalanceChange {
+                block_access_index,
+                post_balance,
+            },
+            decoder.finish()?,
+        ))
+    }
+}
+
+impl RLPEncode for NonceChange {
+    fn encode(&self, buf: &mut dyn bytes::BufMut) {
+        Encoder::new(buf)
+            .encode_field(&self.block_access_index)
+            .encode_field(&self.post_nonce)
+            .finish();
+    }
+}
+
+impl RLPDecode for NonceChange {
+    fn decode_unfinished(rlp: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
+        let decoder = Decoder::new(rlp)?;
+        let (block_access_index, decoder) = decoder.decode_field("block_access_index")?;
+        let (post_nonce, decoder) = decoder.decode_field("post_nonce")?;
+        Ok((
+            NonceChange {
+                block_access_index,
+                post_nonce,
+            },
+            decoder.finish()?,
+        ))
+    }
+}
+
+impl RLPEncode for CodeChange {
+    fn encode(&self, buf: &mut dyn bytes::BufMut) {
+        Encoder::new(buf)
+            .encode_field(&self.block_access_index)
+            .encode_field(&self.new_code)
+            .finish();
+    }
+}
+
+impl RLPDecode for CodeChange {
+    fn decode_unfinished(rlp: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
+        let decoder = Decoder::new(rlp)?;
+        let (block_access_index, decoder) = decoder.decode_field("block_access_index")?;
+        let (new_code, decoder) = decoder.decode_field("new_code")?;
+        Ok((
+            CodeChange {
+                block_access_index,
+                new_code,
+            },
+            decoder.finish()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::DEFAULT_OMMERS_HASH;
+
+    #[test]
+    fn empty_block_access_list_hash_is_empty_list_hash() {
+        assert_eq!(BlockAccessList::default().hash(), *DEFAULT_OMMERS_HASH);
+    }
+
+    #[test]
+    fn builder_sorts_entries_and_drops_written_reads() {
+        let first = Address::from_low_u64_be(1);
+        let second = Address::from_low_u64_be(2);
+        let mut builder = BlockAccessListBuilder::default();
+
+        builder.record_balance_change(second, 2, U256::from(5));
+        builder.record_balance_change(second, 1, U256::from(7));
+        builder.record_storage_read(first, H256::from_low_u64_be(3));
+        builder.record_storage_read(first, H256::from_low_u64_be(4));
+        builder.record_storage_change(first, H256::from_low_u64_be(4), 1, U256::from(9));
+        builder.touch_account(second);
+
+        let block_access_list = builder.build();
+
+        assert_eq!(
+            block_access_list
+                .accounts
+                .iter()
+                .map(|account| account.address)
+                .collect::<Vec<_>>(),
+            vec![first, second]
+        );
+        assert_eq!(
+            block_access_list.accounts[0].storage_reads,
+            vec![U256::from(3)]
+        );
+        assert_eq!(block_access_list.accounts[0].storage_changes.len(), 1);
+        assert_eq!(
+            block_access_list.accounts[1]
+                .balance_changes
+                .iter()
+                .map(|change| change.block_access_index)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn block_access_list_rlp_roundtrip() {
+        let address = Address::from_low_u64_be(0xaa);
+        let mut builder = BlockAccessListBuilder::default();
+        builder.record_storage_change(address, H256::from_low_u64_be(1), 1, U256::from(2));
+        builder.record_storage_read(address, H256::from_low_u64_be(5));
+        builder.record_balance_change(address, 0, U256::from(100));
+        builder.record_nonce_change(address, 1, 1);
+        builder.record_code_change(address, 1, Bytes::from_static(&[0x60, 0x00]));
+        let block_access_list = builder.build();
+
+        let encoded = block_access_list.encode_to_vec();
+        let decoded = BlockAccessList::decode(&encoded).unwrap();
+
+        assert_eq!(decoded, block_access_list);
+    }
+}
@@ -0,0 +1,238 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+
+use ethereum_types::H256;
+use lru::LruCache;
+use rustc_hash::FxBuildHasher;
+use serde::{Deserialize, Serialize};
+
+// 32mb
+pub const CODE_ANALYSIS_CACHE_DEFAULT_MAX_SIZE: usize = 32 * 1024 * 1024;
+
+// TODO: we don't use the constants from the vm module to avoid a circular dependency
+const OP_JUMPDEST: u8 = 0x5B;
+const OP_PUSH1: u8 = 0x60;
+const OP_PUSH32: u8 = 0x7F;
+
+/// Result of analysing a bytecode once, so it can be shared by every call frame running it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CodeAnalysis {
+    /// Bitmap of the offsets holding a JUMPDEST that isn't push data.
+    jumpdests: Vec<u64>,
+    /// Bitmap of the offsets that are immediates of a PUSH1..PUSH32.
+    push_data: Vec<u64>,
+}
+
+impl CodeAnalysis {
+    pub fn analyze(code: &[u8]) -> Self {
+        let words = code.len().div_ceil(64);
+        let mut jumpdests = vec![0u64; words];
+        let mut push_data = vec![0u64; words];
+
+        let mut i = 0;
+        while i < code.len() {
+            match code[i] {
+                OP_JUMPDEST => set_bit(&mut jumpdests, i),
+                opcode @ OP_PUSH1..=OP_PUSH32 => {
+                    let push_len = (opcode - OP_PUSH1 + 1) as usize;
+                    let end = (i + push_len).min(code.len() - 1);
+                    for offset in i + 1..=end {
+                        set_bit(&mut push_data, offset);
+                    }
+                    i = end;
+                }
+                _ => (),
+            }
+            i += 1;
+        }
+
+        Self {
+            jumpdests,
+            push_data,
+        }
+    }
+
+    /// Whether `pc` is a valid jump destination.
+    #[inline]
+    pub fn is_jumpdest(&self, pc: usize) -> bool {
+        get_bit(&self.jumpdests, pc)
+    }
+
+    /// Whether `pc` is part of the immediate of a push, instead of an opcode.
+    #[inline]
+    pub fn is_push_data(&self, pc: usize) -> bool {
+        get_bit(&self.push_data, pc)
+    }
+
+    /// Valid jump destinations in ascending order.
+    pub fn jump_targets(&self) -> Vec<u32> {
+        let mut targets = Vec::new();
+        for (word_index, word) in self.jumpdests.iter().enumerate() {
+            let mut word = *word;
+            while word != 0 {
+                let bit = word.trailing_zeros() as usize;
+                targets.push((word_index * 64 + bit) as u32);
+                word &= word - 1;
+            }
+        }
+        targets
+    }
+
+    /// Estimates the size of the analysis in bytes.
+    pub fn size(&self) -> usize {
+        size_of::<Self>() + (self.jumpdests.len() + self.push_data.len()) * size_of::<u64>()
+    }
+}
+
+fn set_bit(bitmap: &mut [u64], index: usize) {
+    bitmap[index / 64] |= 1u64 << (index % 64);
+}
+
+fn get_bit(bitmap: &[u64], index: usize) -> bool {
+    bitmap
+        .get(index / 64)
+        .is_some_and(|word| word & (1u64 << (index % 64)) != 0)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CodeAnalysisCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    /// Estimated size of the cached analyses in bytes.
+    pub size: usize,
+}
+
+/// Size-bounded LRU cache of bytecode analyses, keyed by code hash.
+///
+/// It's shared by every block executed by a node, so hot contracts are only analysed once
+/// instead of every time their code is loaded.
+#[derive(Debug)]
+pub struct CodeAnalysisCache {
+    inner: Mutex<CodeAnalysisCacheInner>,
+    max_size: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug)]
+struct CodeAnalysisCacheInner {
+    cache: LruCache<H256, Arc<CodeAnalysis>, FxBuildHasher>,
+    size: usize,
+}
+
+impl Default for CodeAnalysisCache {
+    fn default() -> Self {
+        Self::new(CODE_ANALYSIS_CACHE_DEFAULT_MAX_SIZE)
+    }
+}
+
+impl CodeAnalysisCache {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            inner: Mutex::new(CodeAnalysisCacheInner {
+                cache: LruCache::unbounded_with_hasher(FxBuildHasher),
+                size: 0,
+            }),
+            max_size,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cached analysis of the code with the given hash, analysing `bytecode` on a
+    /// miss. `code_hash` must be the real hash of `bytecode`.
+    pub fn get_or_analyze(&self, code_hash: H256, bytecode: &[u8]) -> Arc<CodeAnalysis> {
+        // A poisoned lock only means we can't cache, the analysis is still valid.
+        let Ok(mut inner) = self.inner.lock() else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return Arc::new(CodeAnalysis::analyze(bytecode));
+        };
+
+        if let Some(analysis) = inner.cache.get(&code_hash) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return analysis.clone();
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let analysis = Arc::new(CodeAnalysis::analyze(bytecode));
+        let analysis_size = analysis.size();
+        if analysis_size > self.max_size {
+            return analysis;
+        }
+
+        inner.size += analysis_size;
+        while inner.size > self.max_size {
+            let Some((_, evicted)) = inner.cache.pop_lru() else {
+                break;
+            };
+            inner.size -= evicted.size();
+        }
+        inner.cache.put(code_hash, analysis.clone());
+
+        analysis
+    }
+
+    pub fn stats(&self) -> CodeAnalysisCacheStats {
+        let (entries, size) = self
+            .inner
+            .lock()
+            .map(|inner| (inner.cache.len(), inner.size))
+            .unwrap_or_default();
+        CodeAnalysisCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries,
+            size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_data_is_not_a_jumpdest() {
+        // PUSH2 0x5B5B, JUMPDEST, PUSH1 (truncated)
+        let analysis = CodeAnalysis::analyze(&[0x61, 0x5B, 0x5B, 0x5B, 0x60]);
+        assert!(!analysis.is_jumpdest(1));
+        assert!(!analysis.is_jumpdest(2));
+        assert!(analysis.is_jumpdest(3));
+        assert!(analysis.is_push_data(1) && analysis.is_push_data(2));
+        assert!(!analysis.is_push_data(3) && !analysis.is_push_data(4));
+        assert!(!analysis.is_jumpdest(1000));
+        assert_eq!(analysis.jump_targets(), vec![3]);
+    }
+
+    #[test]
+    fn jump_targets_span_words() {
+        let mut code = vec![0x00; 130];
+        code[0] = 0x5B;
+        code[64] = 0x5B;
+        code[129] = 0x5B;
+        assert_eq!(
+            CodeAnalysis::analyze(&code).jump_targets(),
+            vec![0, 64, 129]
+        );
+    }
+
+    #[test]
+    fn cache_counts_hits_and_evicts() {
+        let code = [0x5B; 64];
+        let size = CodeAnalysis::analyze(&code).size();
+        let cache = CodeAnalysisCache::new(size * 2);
+
+        for i in 0..3 {
+            cache.get_or_analyze(H256::from_low_u64_be(i), &code);
+        }
+        cache.get_or_analyze(H256::from_low_u64_be(2), &code);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.size, size * 2);
+    }
+}
@@ -14,7 +14,8 @@ use tracing::warn;
 
 use super::{
     AccountState, Block, BlockBody, BlockHeader, BlockNumber, INITIAL_BASE_FEE,
-    compute_receipts_root, compute_transactions_root, compute_withdrawals_root,
+    block_access_list::BlockAccessList, compute_receipts_root, compute_transactions_root,
+    compute_withdrawals_root,
 };
 use crate::{
     constants::{DEFAULT_OMMERS_HASH, DEFAULT_REQUESTS_HASH},
@@ -61,6 +62,10 @@ pub enum GenesisError {
     InvalidFork(),
     #[error("Failed to open genesis file: {0}")]
     File(#[from] Error),
+    #[error("L2 precompile address {0:#x} is reserved for a standard precompile")]
+    ReservedL2PrecompileAddress(Address),
+    #[error("L2 precompile address {0:#x} is used by more than one precompile")]
+    DuplicatedL2PrecompileAddress(Address),
 }
 
 impl TryFrom<&Path> for Genesis {
@@ -92,6 +97,8 @@ impl TryFrom<&Path> for Genesis {
             warn!("BPO time set but no BPO BlobSchedule found in ChainConfig")
         }
 
+        genesis.config.l2_precompiles.validate()?;
+
         Ok(genesis)
     }
 }
@@ -194,6 +201,94 @@ fn default_bpo2_schedule() -> ForkBlobSchedule {
         base_fee_update_fraction: 11684671,
     }
 }
+/// Precompiles that L2 chains can enable on top of the ones of their fork, each at the address
+/// and with the gas schedule set in the genesis.
+#[allow(unused)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    RSerialize,
+    RDeserialize,
+    Archive,
+    Default,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct L2Precompiles {
+    /// Poseidon hash of the 32 byte Stark252 field elements of the input.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poseidon: Option<L2PrecompileConfig>,
+    /// Ed25519 verification of a 32 byte public key, a 64 byte signature and the signed message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ed25519_verify: Option<L2PrecompileConfig>,
+    /// Hash of an L1 block, read from the contract where the sequencer stores them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_block_hash: Option<L1BlockHashPrecompileConfig>,
+}
+
+/// Addresses up to this one are kept for the precompiles of Ethereum forks, P256VERIFY being the
+/// highest one so far.
+const LAST_RESERVED_PRECOMPILE_ADDRESS: u64 = 0x100;
+
+impl L2Precompiles {
+    /// Addresses of the enabled precompiles.
+    pub fn addresses(&self) -> impl Iterator<Item = Address> {
+        self.poseidon
+            .iter()
+            .chain(self.ed25519_verify.iter())
+            .chain(self.l1_block_hash.iter().map(|config| &config.precompile))
+            .map(|config| config.address)
+    }
+
+    /// Checks that the precompiles don't shadow a standard precompile nor each other.
+    pub fn validate(&self) -> Result<(), GenesisError> {
+        let reserved = Address::from_low_u64_be(LAST_RESERVED_PRECOMPILE_ADDRESS);
+        let mut seen = Vec::new();
+        for address in self.addresses() {
+            if address <= reserved {
+                return Err(GenesisError::ReservedL2PrecompileAddress(address));
+            }
+            if seen.contains(&address) {
+                return Err(GenesisError::DuplicatedL2PrecompileAddress(address));
+            }
+            seen.push(address);
+        }
+        Ok(())
+    }
+}
+
+#[allow(unused)]
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PartialEq, RSerialize, RDeserialize, Archive,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct L2PrecompileConfig {
+    #[rkyv(with = rkyv_utils::H160Wrapper)]
+    pub address: Address,
+    /// Gas charged on every call.
+    pub base_gas: u64,
+    /// Gas charged for each 32 byte word of the input, rounded up.
+    #[serde(default)]
+    pub word_gas: u64,
+}
+
+#[allow(unused)]
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PartialEq, RSerialize, RDeserialize, Archive,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BlockHashPrecompileConfig {
+    #[serde(flatten)]
+    pub precompile: L2PrecompileConfig,
+    /// Contract keeping the L1 block hashes in a `mapping(uint256 => bytes32)` at slot 0,
+    /// updated through privileged transactions.
+    #[rkyv(with = rkyv_utils::H160Wrapper)]
+    pub source: Address,
+}
+
 /// Blockchain settings defined per block
 #[allow(unused)]
 #[derive(
@@ -251,6 +346,12 @@ pub struct ChainConfig {
     pub bpo4_time: Option<u64>,
     pub bpo5_time: Option<u64>,
 
+    pub amsterdam_time: Option<u64>,
+
+    /// Timestamp at which the EVM Object Format (EOF, EIP-7692) is activated.
+    /// EOF isn't scheduled for any fork, so it's enabled independently of them.
+    pub eof_time: Option<u64>,
+
     /// Amount of total difficulty reached by the network that triggers the consensus upgrade.
     pub terminal_total_difficulty: Option<u128>,
     /// Network has already passed the terminal total difficult
@@ -258,6 +359,9 @@ pub struct ChainConfig {
     pub terminal_total_difficulty_passed: bool,
     #[serde(default)]
     pub blob_schedule: BlobSchedule,
+    /// Extra precompiles enabled by L2 chains, ignored by L1 execution.
+    #[serde(default)]
+    pub l2_precompiles: L2Precompiles,
     #[rkyv(with = rkyv_utils::H160Wrapper)]
     // Deposits system contract address
     pub deposit_contract_address: Address,
@@ -308,6 +412,7 @@ pub enum Fork {
     BPO3 = 22,
     BPO4 = 23,
     BPO5 = 24,
+    Amsterdam = 25,
 }
 
 impl From<Fork> for &str {
@@ -338,6 +443,7 @@ impl From<Fork> for &str {
             Fork::BPO3 => "BPO3",
             Fork::BPO4 => "BPO4",
             Fork::BPO5 => "BPO5",
+            Fork::Amsterdam => "Amsterdam",
         }
     }
 }
@@ -363,6 +469,15 @@ impl ChainConfig {
         self.bpo5_time.is_some_and(|time| time <= block_timestamp)
     }
 
+    pub fn is_amsterdam_activated(&self, block_timestamp: u64) -> bool {
+        self.amsterdam_time
+            .is_some_and(|time| time <= block_timestamp)
+    }
+
+    pub fn is_eof_activated(&self, block_timestamp: u64) -> bool {
+        self.eof_time.is_some_and(|time| time <= block_timestamp)
+    }
+
     pub fn is_osaka_activated(&self, block_timestamp: u64) -> bool {
         self.osaka_time.is_some_and(|time| time <= block_timestamp)
     }
@@ -402,6 +517,7 @@ impl ChainConfig {
             ("Prague", self.prague_time),
             ("Verkle", self.verkle_time),
             ("Osaka", self.osaka_time),
+            ("Amsterdam", self.amsterdam_time),
         ];
 
         let active_forks: Vec<_> = post_merge_forks
@@ -421,7 +537,9 @@ impl ChainConfig {
     }
 
     pub fn get_fork(&self, block_timestamp: u64) -> Fork {
-        if self.is_bpo5_activated(block_timestamp) {
+        if self.is_amsterdam_activated(block_timestamp) {
+            Fork::Amsterdam
+        } else if self.is_bpo5_activated(block_timestamp) {
             Fork::BPO5
         } else if self.is_bpo4_activated(block_timestamp) {
             Fork::BPO4
@@ -492,6 +610,11 @@ impl ChainConfig {
         } else {
             None
         };
+        // Amsterdam is scheduled after whichever BPO forks the network has
+        let next = next.or_else(|| {
+            (self.is_osaka_activated(block_timestamp) && self.amsterdam_time.is_some())
+                .then_some(Fork::Amsterdam)
+        });
         match next {
             Some(fork) if fork > self.fork(block_timestamp) => next,
             _ => None,
@@ -499,7 +622,9 @@ impl ChainConfig {
     }
 
     pub fn get_last_scheduled_fork(&self) -> Fork {
-        if self.bpo5_time.is_some() {
+        if self.amsterdam_time.is_some() {
+            Fork::Amsterdam
+        } else if self.bpo5_time.is_some() {
             Fork::BPO5
         } else if self.bpo4_time.is_some() {
             Fork::BPO4
@@ -530,6 +655,7 @@ impl ChainConfig {
             Fork::BPO3 => self.bpo3_time,
             Fork::BPO4 => self.bpo4_time,
             Fork::BPO5 => self.bpo5_time,
+            Fork::Amsterdam => self.amsterdam_time,
             Fork::Homestead => self.homestead_block,
             Fork::DaoFork => self.dao_fork_block,
             Fork::Byzantium => self.byzantium_block,
@@ -557,6 +683,10 @@ impl ChainConfig {
             Fork::BPO3 => self.blob_schedule.bpo3,
             Fork::BPO4 => self.blob_schedule.bpo4,
             Fork::BPO5 => self.blob_schedule.bpo5,
+            // Amsterdam keeps the blob parameters of the last fork activated before it
+            Fork::Amsterdam => self
+                .amsterdam_time
+                .and_then(|time| self.get_fork_blob_schedule(time)),
             _ => None,
         }
     }
@@ -601,6 +731,8 @@ impl ChainConfig {
             self.bpo3_time,
             self.bpo4_time,
             self.bpo5_time,
+            self.amsterdam_time,
+            self.eof_time,
             self.verkle_time,
         ]
         .into_iter()
@@ -668,6 +800,11 @@ impl Genesis {
             .is_prague_activated(self.timestamp)
             .then_some(self.requests_hash.unwrap_or(*DEFAULT_REQUESTS_HASH));
 
+        let block_access_list_hash = self
+            .config
+            .is_amsterdam_activated(self.timestamp)
+            .then(|| BlockAccessList::default().hash());
+
         BlockHeader {
             parent_hash: H256::zero(),
             ommers_hash: *DEFAULT_OMMERS_HASH,
@@ -690,6 +827,7 @@ impl Genesis {
             excess_blob_gas,
             parent_beacon_block_root,
             requests_hash,
+            block_access_list_hash,
             ..Default::default()
         }
     }
@@ -983,6 +1121,83 @@ mod tests {
         assert_eq!(&config, &expected_chain_config);
     }
 
+    #[test]
+    fn deserialize_chain_config_l2_precompiles() {
+        let json = r#"
+            {
+                "chainId": 65536999,
+                "depositContractAddress": "0x4242424242424242424242424242424242424242",
+                "l2Precompiles": {
+                    "poseidon": {
+                        "address": "0x0000000000000000000000000000000000000a01",
+                        "baseGas": 200,
+                        "wordGas": 20
+                    },
+                    "l1BlockHash": {
+                        "address": "0x0000000000000000000000000000000000000a03",
+                        "baseGas": 2100,
+                        "source": "0x000000000000000000000000000000000000fff0"
+                    }
+                }
+            }
+            "#;
+
+        let config: ChainConfig =
+            serde_json::from_str(json).expect("Failed to deserialize ChainConfig");
+        let expected_l2_precompiles = L2Precompiles {
+            poseidon: Some(L2PrecompileConfig {
+                address: H160::from_low_u64_be(0xa01),
+                base_gas: 200,
+                word_gas: 20,
+            }),
+            ed25519_verify: None,
+            l1_block_hash: Some(L1BlockHashPrecompileConfig {
+                precompile: L2PrecompileConfig {
+                    address: H160::from_low_u64_be(0xa03),
+                    base_gas: 2100,
+                    word_gas: 0,
+                },
+                source: H160::from_low_u64_be(0xfff0),
+            }),
+        };
+        assert_eq!(config.l2_precompiles, expected_l2_precompiles);
+        assert!(config.l2_precompiles.validate().is_ok());
+    }
+
+    #[test]
+    fn l2_precompiles_cant_shadow_other_precompiles() {
+        let precompile = |address| L2PrecompileConfig {
+            address: H160::from_low_u64_be(address),
+            base_gas: 200,
+            word_gas: 0,
+        };
+        let shadowing_ecrecover = L2Precompiles {
+            poseidon: Some(precompile(0x01)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            shadowing_ecrecover.validate(),
+            Err(GenesisError::ReservedL2PrecompileAddress(_))
+        ));
+        let shadowing_p256verify = L2Precompiles {
+            ed25519_verify: Some(precompile(0x100)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            shadowing_p256verify.validate(),
+            Err(GenesisError::ReservedL2PrecompileAddress(_))
+        ));
+        let duplicated = L2Precompiles {
+            poseidon: Some(precompile(0xa01)),
+            ed25519_verify: Some(precompile(0xa01)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            duplicated.validate(),
+            Err(GenesisError::DuplicatedL2PrecompileAddress(_))
+        ));
+    }
+
     #[test]
     fn deserialize_chain_config_missing_cancun_blob_schedule() {
         let json = r#"
@@ -2,7 +2,9 @@ mod account;
 mod account_update;
 pub mod blobs_bundle;
 mod block;
+pub mod block_access_list;
 pub mod block_execution_witness;
+pub mod code_analysis;
 mod constants;
 mod fork_id;
 mod genesis;
@@ -17,6 +19,7 @@ pub use account::*;
 pub use account_update::*;
 pub use blobs_bundle::*;
 pub use block::*;
+pub use code_analysis::{CodeAnalysis, CodeAnalysisCache, CodeAnalysisCacheStats};
 pub use constants::*;
 pub use fork_id::*;
 pub use genesis::*;
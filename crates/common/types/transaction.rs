@@ -1,4 +1,4 @@
-use std::{cmp::min, fmt::Display};
+use std::{cmp::min, collections::BTreeSet, fmt::Display};
 
 use crate::{errors::EcdsaError, utils::keccak};
 use bytes::Bytes;
@@ -8,7 +8,8 @@ pub use mempool::MempoolTransaction;
 use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
 use serde::{Serialize, ser::SerializeStruct};
 pub use serde_impl::{
-    AccessListEntry, AuthorizationTupleEntry, GenericTransaction, GenericTransactionError,
+    AccessListEntry, AccountOverride, AuthorizationTupleEntry, GenericTransaction,
+    GenericTransactionError, StateOverride,
 };
 
 /// The serialized length of a default eip1559 transaction
@@ -479,6 +480,14 @@ impl RLPDecode for Transaction {
     }
 }
 
+/// Sender of a transaction and every address taking part in its calls, including contracts
+/// created and self destruct beneficiaries, as recorded while executing it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionAddresses {
+    pub sender: Address,
+    pub addresses: BTreeSet<Address>,
+}
+
 /// The transaction's kind: call or create.
 #[derive(Clone, Debug, PartialEq, Eq, Default, RSerialize, RDeserialize, Archive)]
 pub enum TxKind {
@@ -2529,6 +2538,28 @@ mod serde_impl {
         Ok(Bytes::from(bytes))
     }
 
+    /// Account fields to replace before simulating a transaction, as accepted by `eth_call` like
+    /// endpoints in geth.
+    #[derive(Deserialize, Debug, PartialEq, Clone, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct AccountOverride {
+        #[serde(default)]
+        pub balance: Option<U256>,
+        #[serde(default, with = "crate::serde_utils::u64::hex_str_opt")]
+        pub nonce: Option<u64>,
+        #[serde(default, with = "crate::serde_utils::bytes::opt")]
+        pub code: Option<Bytes>,
+        /// Replaces the whole storage of the account
+        #[serde(default)]
+        pub state: Option<HashMap<H256, H256>>,
+        /// Replaces only the given storage slots
+        #[serde(default)]
+        pub state_diff: Option<HashMap<H256, H256>>,
+    }
+
+    /// Overrides of each account, applied on top of the state a transaction is simulated on.
+    pub type StateOverride = HashMap<Address, AccountOverride>;
+
     impl From<EIP1559Transaction> for GenericTransaction {
         fn from(value: EIP1559Transaction) -> Self {
             Self {
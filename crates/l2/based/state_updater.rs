@@ -3,6 +3,7 @@ use std::{sync::Arc, time::Duration};
 use ethrex_blockchain::Blockchain;
 use ethrex_common::{Address, types::Block};
 use ethrex_l2_sdk::{calldata::encode_calldata, get_last_committed_batch};
+use ethrex_p2p::rlpx::l2::l2_connection::LeadSequencer;
 use ethrex_rpc::{EthClient, clients::Overrides};
 use ethrex_storage::Store;
 use ethrex_storage_rollup::{RollupStoreError, StoreRollup};
@@ -60,6 +61,8 @@ pub struct StateUpdater {
     check_interval_ms: u64,
     sequencer_state: SequencerState,
     blockchain: Arc<Blockchain>,
+    /// Shared with the p2p layer, which only accepts blocks signed by the lead sequencer.
+    lead_sequencer: LeadSequencer,
 }
 
 impl StateUpdater {
@@ -69,6 +72,7 @@ impl StateUpdater {
         blockchain: Arc<Blockchain>,
         store: Store,
         rollup_store: StoreRollup,
+        lead_sequencer: LeadSequencer,
     ) -> Result<Self, StateUpdaterError> {
         Ok(Self {
             on_chain_proposer_address: sequencer_cfg.l1_committer.on_chain_proposer_address,
@@ -82,6 +86,7 @@ impl StateUpdater {
             check_interval_ms: sequencer_cfg.based.state_updater.check_interval_ms,
             sequencer_state,
             blockchain,
+            lead_sequencer,
         })
     }
 
@@ -91,6 +96,7 @@ impl StateUpdater {
         blockchain: Arc<Blockchain>,
         store: Store,
         rollup_store: StoreRollup,
+        lead_sequencer: LeadSequencer,
     ) -> Result<(), StateUpdaterError> {
         let mut state_updater = Self::new(
             sequencer_cfg,
@@ -98,6 +104,7 @@ impl StateUpdater {
             blockchain,
             store,
             rollup_store,
+            lead_sequencer,
         )?
         .start();
         state_updater
@@ -131,6 +138,9 @@ impl StateUpdater {
         .await?;
 
         let current_state = self.sequencer_state.status().await;
+        let previous_lead_sequencer = self.lead_sequencer.address();
+        let lead_sequencer_changed =
+            previous_lead_sequencer.is_some_and(|previous| previous != lead_sequencer);
 
         let new_status = determine_new_status(
             current_state,
@@ -138,21 +148,22 @@ impl StateUpdater {
             lead_sequencer == self.sequencer_address,
         );
 
-        if current_state != new_status {
-            info!("State transition: {:?} -> {:?}", current_state, new_status);
-
+        if must_revert_uncommitted_state(current_state, new_status, lead_sequencer_changed) {
             if current_state == SequencerStatus::Sequencing {
                 info!("Stopping sequencing.");
-                self.revert_uncommitted_state().await?;
-            }
-
-            if new_status == SequencerStatus::Sequencing {
+            } else if new_status == SequencerStatus::Sequencing {
                 info!("Starting sequencing as lead sequencer.");
-                self.revert_uncommitted_state().await?;
+            } else {
+                info!("Lead sequencer changed to {lead_sequencer:#x}.");
             }
+            info!("Reverting uncommitted state.");
+            self.revert_uncommitted_state().await?;
+        }
+
+        if current_state != new_status {
+            info!("State transition: {:?} -> {:?}", current_state, new_status);
 
             match new_status {
-                // This case is handled above, it is redundant here.
                 SequencerStatus::Sequencing => {
                     info!("Node is now the lead sequencer.");
                 }
@@ -167,6 +178,11 @@ impl StateUpdater {
             }
         }
 
+        if previous_lead_sequencer != Some(lead_sequencer) {
+            let handoff_block = self.store.get_latest_block_number().await?;
+            self.lead_sequencer.hand_off(lead_sequencer, handoff_block);
+        }
+
         // Update the state
         self.sequencer_state.new_status(new_status).await;
 
@@ -264,6 +280,21 @@ impl GenServer for StateUpdater {
     }
 }
 
+/// Whether the blocks after the last committed batch have to be reverted. That is
+/// the case when the node starts or stops sequencing, and when it follows a new
+/// lead sequencer, which builds on top of the last committed batch, so the blocks
+/// the previous lead didn't commit are reorged away.
+fn must_revert_uncommitted_state(
+    current_state: SequencerStatus,
+    new_status: SequencerStatus,
+    lead_sequencer_changed: bool,
+) -> bool {
+    let sequencing_changed = current_state != new_status
+        && (current_state == SequencerStatus::Sequencing
+            || new_status == SequencerStatus::Sequencing);
+    sequencing_changed || (lead_sequencer_changed && new_status == SequencerStatus::Following)
+}
+
 fn determine_new_status(
     current_state: SequencerStatus,
     node_is_up_to_date: bool,
@@ -292,3 +323,38 @@ fn determine_new_status(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use SequencerStatus::{Following, Sequencing, Syncing};
+
+    #[test]
+    fn reverts_when_sequencing_starts_or_stops() {
+        assert!(must_revert_uncommitted_state(Following, Sequencing, false));
+        assert!(must_revert_uncommitted_state(Sequencing, Following, true));
+        assert!(must_revert_uncommitted_state(Sequencing, Syncing, false));
+        assert!(!must_revert_uncommitted_state(
+            Sequencing, Sequencing, false
+        ));
+        assert!(!must_revert_uncommitted_state(Syncing, Following, false));
+    }
+
+    #[test]
+    fn reverts_when_following_a_new_lead_sequencer() {
+        assert!(must_revert_uncommitted_state(Following, Following, true));
+        assert!(must_revert_uncommitted_state(Syncing, Following, true));
+        assert!(!must_revert_uncommitted_state(Following, Following, false));
+        // A syncing node has no uncommitted state to revert
+        assert!(!must_revert_uncommitted_state(Syncing, Syncing, true));
+    }
+
+    #[test]
+    fn lead_sequencer_status() {
+        assert_eq!(determine_new_status(Following, true, true), Sequencing);
+        assert_eq!(determine_new_status(Syncing, true, true), Following);
+        assert_eq!(determine_new_status(Sequencing, true, false), Following);
+        assert_eq!(determine_new_status(Sequencing, false, true), Syncing);
+    }
+}
@@ -1,6 +1,7 @@
 pub mod calldata;
 pub mod merkle_tree;
 pub mod messages;
+pub mod preconfirmations;
 pub mod privileged_transactions;
 pub mod prover;
 pub mod utils;
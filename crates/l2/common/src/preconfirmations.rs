@@ -0,0 +1,183 @@
+use bytes::Bytes;
+use ethereum_types::{Address, H256, Signature};
+use ethrex_common::types::recover_address_from_message;
+use ethrex_common::{EcdsaError, utils::keccak};
+use serde::{Deserialize, Serialize};
+
+/// Domain separator prepended to every preconfirmation before signing, so the
+/// signature can't be replayed as a signature over any other sequencer message.
+pub const PRECONFIRMATION_DOMAIN: &[u8] = b"ethrex-l2-preconfirmation-v1";
+/// Same as `PRECONFIRMATION_DOMAIN`, for block revocations.
+pub const BLOCK_REVOCATION_DOMAIN: &[u8] = b"ethrex-l2-block-revocation-v1";
+
+/// A commitment from the sequencer to include a transaction at a given
+/// position of a given block.
+///
+/// Preconfirmations are published while the block is still being built, so
+/// they're conditional on that block being sealed: if the sequencer discards
+/// it instead (e.g. because it lost the leader lease), it publishes a signed
+/// `BlockRevocation` which voids every preconfirmation for the block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Preconfirmation {
+    pub chain_id: u64,
+    pub block_number: u64,
+    pub parent_hash: H256,
+    pub tx_index: u64,
+    pub tx_hash: H256,
+}
+
+impl Preconfirmation {
+    /// Returns the bytes the sequencer signs over.
+    /// The signer hashes this payload with keccak before signing.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(PRECONFIRMATION_DOMAIN.len() + 112);
+        encoded.extend_from_slice(PRECONFIRMATION_DOMAIN);
+        encoded.extend_from_slice(&self.chain_id.to_be_bytes());
+        encoded.extend_from_slice(&self.block_number.to_be_bytes());
+        encoded.extend_from_slice(self.parent_hash.as_bytes());
+        encoded.extend_from_slice(&self.tx_index.to_be_bytes());
+        encoded.extend_from_slice(self.tx_hash.as_bytes());
+        encoded
+    }
+
+    pub fn hash(&self) -> H256 {
+        keccak(self.encode())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedPreconfirmation {
+    #[serde(flatten)]
+    pub preconfirmation: Preconfirmation,
+    pub signature: Signature,
+}
+
+impl SignedPreconfirmation {
+    /// Recovers the address that signed this preconfirmation.
+    pub fn recover_signer(&self) -> Result<Address, PreconfirmationError> {
+        recover_address_from_message(self.signature, &Bytes::from(self.preconfirmation.encode()))
+            .map_err(PreconfirmationError::from)
+    }
+
+    /// Checks that the preconfirmation was signed by `sequencer`.
+    pub fn verify(&self, sequencer: Address) -> Result<(), PreconfirmationError> {
+        check_signer(self.recover_signer()?, sequencer)
+    }
+}
+
+/// Notice from the sequencer that a block it published flashblocks for was
+/// discarded before being sealed, so none of its preconfirmations hold.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockRevocation {
+    pub chain_id: u64,
+    pub block_number: u64,
+    pub parent_hash: H256,
+}
+
+impl BlockRevocation {
+    /// Returns the bytes the sequencer signs over.
+    /// The signer hashes this payload with keccak before signing.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut encoded = Vec::with_capacity(BLOCK_REVOCATION_DOMAIN.len() + 48);
+        encoded.extend_from_slice(BLOCK_REVOCATION_DOMAIN);
+        encoded.extend_from_slice(&self.chain_id.to_be_bytes());
+        encoded.extend_from_slice(&self.block_number.to_be_bytes());
+        encoded.extend_from_slice(self.parent_hash.as_bytes());
+        encoded
+    }
+
+    /// Whether this revocation voids `preconfirmation`.
+    pub fn revokes(&self, preconfirmation: &Preconfirmation) -> bool {
+        self.chain_id == preconfirmation.chain_id
+            && self.block_number == preconfirmation.block_number
+            && self.parent_hash == preconfirmation.parent_hash
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedBlockRevocation {
+    #[serde(flatten)]
+    pub revocation: BlockRevocation,
+    pub signature: Signature,
+}
+
+impl SignedBlockRevocation {
+    /// Recovers the address that signed this revocation.
+    pub fn recover_signer(&self) -> Result<Address, PreconfirmationError> {
+        recover_address_from_message(self.signature, &Bytes::from(self.revocation.encode()))
+            .map_err(PreconfirmationError::from)
+    }
+
+    /// Checks that the revocation was signed by `sequencer`.
+    pub fn verify(&self, sequencer: Address) -> Result<(), PreconfirmationError> {
+        check_signer(self.recover_signer()?, sequencer)
+    }
+}
+
+fn check_signer(signer: Address, sequencer: Address) -> Result<(), PreconfirmationError> {
+    if signer != sequencer {
+        return Err(PreconfirmationError::InvalidSigner {
+            expected: sequencer,
+            recovered: signer,
+        });
+    }
+    Ok(())
+}
+
+/// A partial payload of a block that is still being built.
+/// Flashblocks are numbered from zero within each block and every one carries
+/// only the transactions appended since the previous flashblock.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Flashblock {
+    pub block_number: u64,
+    pub parent_hash: H256,
+    pub index: u64,
+    /// Canonical encoding of the transactions added in this flashblock.
+    pub transactions: Vec<Bytes>,
+    pub preconfirmations: Vec<SignedPreconfirmation>,
+    /// Gas used by the block so far, including this flashblock.
+    pub cumulative_gas_used: u64,
+}
+
+impl Flashblock {
+    /// Checks every preconfirmation in the flashblock was signed by `sequencer`
+    /// and commits to this flashblock's block.
+    pub fn verify(&self, sequencer: Address) -> Result<(), PreconfirmationError> {
+        for signed in &self.preconfirmations {
+            let preconfirmation = &signed.preconfirmation;
+            if preconfirmation.block_number != self.block_number
+                || preconfirmation.parent_hash != self.parent_hash
+            {
+                return Err(PreconfirmationError::BlockMismatch(preconfirmation.tx_hash));
+            }
+            signed.verify(sequencer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Message streamed to flashblocks subscribers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FlashblocksMessage {
+    Flashblock(Flashblock),
+    Revocation(SignedBlockRevocation),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PreconfirmationError {
+    #[error("Failed to recover preconfirmation signer: {0}")]
+    Recovery(#[from] EcdsaError),
+    #[error("Preconfirmation signed by {recovered:#x}, expected {expected:#x}")]
+    InvalidSigner {
+        expected: Address,
+        recovered: Address,
+    },
+    #[error("Preconfirmation for {0:#x} does not match the flashblock's block")]
+    BlockMismatch(H256),
+}
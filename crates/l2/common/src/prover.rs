@@ -70,6 +70,17 @@ impl ProverType {
         }
     }
 
+    /// Bit identifying the proving system in the proof bitmaps of the OnChainProposer contract
+    pub fn proof_bit(&self) -> Option<u8> {
+        // These values have to match with the OnChainProposer.sol contract
+        match self {
+            Self::RISC0 => Some(1),
+            Self::SP1 => Some(2),
+            Self::TDX => Some(4),
+            Self::Exec => None,
+        }
+    }
+
     /// Used to call a getter for the REQUIRE_*_PROOF boolean in the OnChainProposer contract
     pub fn verifier_getter(&self) -> Option<String> {
         // These values have to match with the OnChainProposer.sol contract
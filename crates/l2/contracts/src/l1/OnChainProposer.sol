@@ -47,6 +47,13 @@ contract OnChainProposer is
 
     uint8 internal constant SP1_VERIFIER_ID = 1;
     uint8 internal constant RISC0_VERIFIER_ID = 2;
+    uint8 internal constant SP1_AGGREGATION_VERIFIER_ID = 3;
+    uint8 internal constant RISC0_AGGREGATION_VERIFIER_ID = 4;
+
+    /// @dev Bits of each proof system in the proof bitmaps.
+    uint8 internal constant RISC0_PROOF = 1;
+    uint8 internal constant SP1_PROOF = 2;
+    uint8 internal constant TDX_PROOF = 4;
 
     /// @notice The commitments of the committed batches.
     /// @dev If a batch is committed, the commitment is stored here.
@@ -112,6 +119,32 @@ contract OnChainProposer is
     mapping(bytes32 commitHash => mapping(uint8 verifierId => bytes32 vk))
         public verificationKeys;
 
+    /// @notice Amount of the required proof systems a batch has to be verified with
+    /// before its remaining proofs.
+    /// @dev Zero, along with a zero `IMMEDIATE_PROOFS`, means every required proof system,
+    /// which is the default.
+    uint8 public MIN_PROOFS_TO_VERIFY;
+
+    /// @notice Bitmap of the required proof systems that have to be among the proofs
+    /// a batch is verified with before its remaining proofs.
+    /// @dev Zero means any of the required proof systems count towards `MIN_PROOFS_TO_VERIFY`.
+    uint8 public IMMEDIATE_PROOFS;
+
+    /// @notice Time, in seconds, the remaining proofs of an optimistically verified batch can take.
+    /// @dev Zero means the remaining proofs are never needed.
+    /// @dev While they are overdue, no new batches can be verified.
+    uint256 public PROOF_UPGRADE_WINDOW;
+
+    /// @notice The first verified batch that is still missing proofs, or zero if there is none.
+    uint256 public firstOptimisticBatch;
+
+    /// @notice Bitmap of the proof systems that verified each batch being tracked
+    /// since `firstOptimisticBatch`.
+    mapping(uint256 batchNumber => uint8 proofs) public batchProofs;
+
+    /// @notice Timestamp at which each batch being tracked since `firstOptimisticBatch` was verified.
+    mapping(uint256 batchNumber => uint256 timestamp) public batchVerifiedAt;
+
     modifier onlySequencer() {
         require(
             authorizedSequencerAddresses[msg.sender],
@@ -238,6 +271,32 @@ contract OnChainProposer is
         emit VerificationKeyUpgraded("RISC0", commit_hash, new_vk);
     }
 
+    /// @inheritdoc IOnChainProposer
+    function upgradeSP1AggregationVerificationKey(
+        bytes32 commit_hash,
+        bytes32 new_vk
+    ) public onlyOwner {
+        require(
+            commit_hash != bytes32(0),
+            "OnChainProposer: commit hash is zero"
+        );
+        verificationKeys[commit_hash][SP1_AGGREGATION_VERIFIER_ID] = new_vk;
+        emit VerificationKeyUpgraded("SP1 aggregation", commit_hash, new_vk);
+    }
+
+    /// @inheritdoc IOnChainProposer
+    function upgradeRISC0AggregationVerificationKey(
+        bytes32 commit_hash,
+        bytes32 new_vk
+    ) public onlyOwner {
+        require(
+            commit_hash != bytes32(0),
+            "OnChainProposer: commit hash is zero"
+        );
+        verificationKeys[commit_hash][RISC0_AGGREGATION_VERIFIER_ID] = new_vk;
+        emit VerificationKeyUpgraded("RISC0 aggregation", commit_hash, new_vk);
+    }
+
     /// @inheritdoc IOnChainProposer
     function commitBatch(
         uint256 batchNumber,
@@ -341,7 +400,7 @@ contract OnChainProposer is
     }
 
     /// @inheritdoc IOnChainProposer
-    /// @notice The first `require` checks that the batch number is the subsequent block.
+    /// @notice The first `require` checks that the batch is the subsequent one, or a verified batch still missing proofs.
     /// @notice The second `require` checks if the batch has been committed.
     /// @notice The order of these `require` statements is important.
     /// Ordering Reason: After the verification process, we delete the `batchCommitments` for `batchNumber - 1`. This means that when checking the batch,
@@ -364,7 +423,8 @@ contract OnChainProposer is
         );
 
         require(
-            batchNumber == lastVerifiedBatch + 1,
+            batchNumber <= lastVerifiedBatch + 1 &&
+                batchNumber > lastFullyVerifiedBatch(),
             "009" // OnChainProposer: batch already verified
         );
         require(
@@ -372,133 +432,261 @@ contract OnChainProposer is
             "00a" // OnChainProposer: cannot verify an uncommitted batch
         );
 
-        // The first 2 bytes are the number of privileged transactions.
-        uint16 privileged_transaction_count = uint16(
-            bytes2(
-                batchCommitments[batchNumber]
-                    .processedPrivilegedTransactionsRollingHash
-            )
-        );
-        if (privileged_transaction_count > 0) {
-            ICommonBridge(BRIDGE).removePendingTransactionHashes(
-                privileged_transaction_count
-            );
-        }
+        // Proofs that were already submitted for an optimistically verified batch
+        uint8 proofs = batchProofs[batchNumber];
 
-        ICommonBridge.L2MessageRollingHash[]
-            memory batchL2InRollingHashes = batchCommitments[batchNumber]
-                .l2InMessageRollingHashes;
-        for (uint256 i = 0; i < batchL2InRollingHashes.length; i++) {
-            uint16 l2_messages_count = uint16(
-                bytes2(batchL2InRollingHashes[i].rollingHash)
-            );
-            ICommonBridge(BRIDGE).removePendingL2Messages(
-                batchL2InRollingHashes[i].chainId,
-                l2_messages_count
-            );
+        // A proof system is skipped if its proof is empty, so a batch can be
+        // verified with a subset of them as allowed by the proof policy.
+        if (
+            REQUIRE_RISC0_PROOF &&
+            risc0BlockProof.length != 0 &&
+            (proofs & RISC0_PROOF) == 0
+        ) {
+            _verifyRisc0Proof(batchNumber, risc0BlockProof, risc0Journal);
+            proofs |= RISC0_PROOF;
         }
 
         if (
-            ICommonBridge(BRIDGE).hasExpiredPrivilegedTransactions() &&
-            batchCommitments[batchNumber].nonPrivilegedTransactions != 0
+            REQUIRE_SP1_PROOF &&
+            sp1ProofBytes.length != 0 &&
+            (proofs & SP1_PROOF) == 0
         ) {
-            revert("00v"); // exceeded privileged transaction inclusion deadline, can't include non-privileged transactions
+            _verifySp1Proof(batchNumber, sp1PublicValues, sp1ProofBytes);
+            proofs |= SP1_PROOF;
         }
 
-        if (REQUIRE_RISC0_PROOF) {
-            // If the verification fails, it will revert.
-            string memory reason = _verifyPublicData(batchNumber, risc0Journal);
-            if (bytes(reason).length != 0) {
-                revert(
-                    string.concat(
-                        "00b", // OnChainProposer: Invalid RISC0 proof:
-                        reason
-                    )
-                );
-            }
-            bytes32 batchCommitHash = batchCommitments[batchNumber].commitHash;
-            bytes32 risc0Vk = verificationKeys[batchCommitHash][
-                RISC0_VERIFIER_ID
-            ];
-            try
-                IRiscZeroVerifier(RISC0_VERIFIER_ADDRESS).verify(
-                    risc0BlockProof,
-                    // we use the same vk as the one set for the commit of the batch
-                    risc0Vk,
-                    sha256(risc0Journal)
-                )
-            {} catch {
-                revert(
-                    "00c" // OnChainProposer: Invalid RISC0 proof failed proof verification
-                );
-            }
+        if (
+            REQUIRE_TDX_PROOF &&
+            tdxSignature.length != 0 &&
+            (proofs & TDX_PROOF) == 0
+        ) {
+            _verifyTdxProof(batchNumber, tdxPublicValues, tdxSignature);
+            proofs |= TDX_PROOF;
         }
 
-        if (REQUIRE_SP1_PROOF) {
-            // If the verification fails, it will revert.
-            string memory reason = _verifyPublicData(
-                batchNumber,
-                sp1PublicValues
+        if (batchNumber <= lastVerifiedBatch) {
+            // The batch was optimistically verified, these are its remaining proofs
+            require(
+                proofs != batchProofs[batchNumber],
+                "01c" // OnChainProposer: no new proofs for an optimistically verified batch
             );
-            if (bytes(reason).length != 0) {
-                revert(
-                    string.concat(
-                        "00d", // OnChainProposer: Invalid SP1 proof:
-                        reason
-                    )
-                );
+            batchProofs[batchNumber] = proofs;
+            _advanceFullyVerifiedBatches();
+            emit BatchProofsUpgraded(batchNumber, proofs);
+            return;
+        }
+
+        _requireNoOverdueProofs();
+        require(
+            _countProofs(proofs) >= _minProofsToVerify() &&
+                (proofs & IMMEDIATE_PROOFS) == IMMEDIATE_PROOFS,
+            "01d" // OnChainProposer: not enough proofs, or missing proofs needed, to verify the batch
+        );
+
+        _consumeBatchMessages(batchNumber);
+
+        ICommonBridge(BRIDGE).publishL2Messages(
+            batchCommitments[batchNumber].balanceDiffs
+        );
+
+        lastVerifiedBatch = batchNumber;
+
+        // Without an upgrade window, the remaining proofs are never needed
+        bool fullyVerified = proofs == _requiredProofs() ||
+            PROOF_UPGRADE_WINDOW == 0;
+        if (fullyVerified && firstOptimisticBatch == 0) {
+            // Remove previous batch commitment as it is no longer needed.
+            delete batchCommitments[batchNumber - 1];
+        } else {
+            // Keep track of the batch until every batch before it has all its proofs
+            batchProofs[batchNumber] = fullyVerified
+                ? _requiredProofs()
+                : proofs;
+            batchVerifiedAt[batchNumber] = block.timestamp;
+            if (firstOptimisticBatch == 0) {
+                firstOptimisticBatch = batchNumber;
             }
-            bytes32 batchCommitHash = batchCommitments[batchNumber].commitHash;
-            bytes32 sp1Vk = verificationKeys[batchCommitHash][SP1_VERIFIER_ID];
-            try
-                ISP1Verifier(SP1_VERIFIER_ADDRESS).verifyProof(
-                    sp1Vk,
-                    sp1PublicValues,
-                    sp1ProofBytes
+        }
+
+        emit BatchVerified(lastVerifiedBatch);
+    }
+
+    function _verifyRisc0Proof(
+        uint256 batchNumber,
+        bytes memory risc0BlockProof,
+        bytes calldata risc0Journal
+    ) internal view {
+        // If the verification fails, it will revert.
+        string memory reason = _verifyPublicData(batchNumber, risc0Journal);
+        if (bytes(reason).length != 0) {
+            revert(
+                string.concat(
+                    "00b", // OnChainProposer: Invalid RISC0 proof:
+                    reason
                 )
-            {} catch {
-                revert(
-                    "00e" // OnChainProposer: Invalid SP1 proof failed proof verification
-                );
-            }
+            );
+        }
+        bytes32 batchCommitHash = batchCommitments[batchNumber].commitHash;
+        bytes32 risc0Vk = verificationKeys[batchCommitHash][RISC0_VERIFIER_ID];
+        try
+            IRiscZeroVerifier(RISC0_VERIFIER_ADDRESS).verify(
+                risc0BlockProof,
+                // we use the same vk as the one set for the commit of the batch
+                risc0Vk,
+                sha256(risc0Journal)
+            )
+        {} catch {
+            revert(
+                "00c" // OnChainProposer: Invalid RISC0 proof failed proof verification
+            );
         }
+    }
 
-        if (REQUIRE_TDX_PROOF) {
-            // If the verification fails, it will revert.
-            string memory reason = _verifyPublicData(
-                batchNumber,
-                tdxPublicValues
+    function _verifySp1Proof(
+        uint256 batchNumber,
+        bytes calldata sp1PublicValues,
+        bytes memory sp1ProofBytes
+    ) internal view {
+        // If the verification fails, it will revert.
+        string memory reason = _verifyPublicData(batchNumber, sp1PublicValues);
+        if (bytes(reason).length != 0) {
+            revert(
+                string.concat(
+                    "00d", // OnChainProposer: Invalid SP1 proof:
+                    reason
+                )
             );
-            if (bytes(reason).length != 0) {
-                revert(
-                    string.concat(
-                        "00f", // OnChainProposer: Invalid TDX proof:
-                        reason
-                    )
-                );
-            }
-            try
-                ITDXVerifier(TDX_VERIFIER_ADDRESS).verify(
-                    tdxPublicValues,
-                    tdxSignature
+        }
+        bytes32 batchCommitHash = batchCommitments[batchNumber].commitHash;
+        bytes32 sp1Vk = verificationKeys[batchCommitHash][SP1_VERIFIER_ID];
+        try
+            ISP1Verifier(SP1_VERIFIER_ADDRESS).verifyProof(
+                sp1Vk,
+                sp1PublicValues,
+                sp1ProofBytes
+            )
+        {} catch {
+            revert(
+                "00e" // OnChainProposer: Invalid SP1 proof failed proof verification
+            );
+        }
+    }
+
+    function _verifyTdxProof(
+        uint256 batchNumber,
+        bytes calldata tdxPublicValues,
+        bytes memory tdxSignature
+    ) internal {
+        // If the verification fails, it will revert.
+        string memory reason = _verifyPublicData(batchNumber, tdxPublicValues);
+        if (bytes(reason).length != 0) {
+            revert(
+                string.concat(
+                    "00f", // OnChainProposer: Invalid TDX proof:
+                    reason
                 )
-            {} catch {
-                revert(
-                    "00g" // OnChainProposer: Invalid TDX proof failed proof verification
-                );
-            }
+            );
         }
+        try
+            ITDXVerifier(TDX_VERIFIER_ADDRESS).verify(
+                tdxPublicValues,
+                tdxSignature
+            )
+        {} catch {
+            revert(
+                "00g" // OnChainProposer: Invalid TDX proof failed proof verification
+            );
+        }
+    }
 
-        ICommonBridge(BRIDGE).publishL2Messages(
-            batchCommitments[batchNumber].balanceDiffs
+    /// @inheritdoc IOnChainProposer
+    function lastFullyVerifiedBatch() public view returns (uint256) {
+        return
+            firstOptimisticBatch == 0
+                ? lastVerifiedBatch
+                : firstOptimisticBatch - 1;
+    }
+
+    /// @inheritdoc IOnChainProposer
+    function setProofPolicy(
+        uint8 minProofs,
+        uint8 immediateProofs,
+        uint256 upgradeWindow
+    ) public onlyOwner {
+        require(
+            minProofs <= _countProofs(_requiredProofs()),
+            "01f" // OnChainProposer: more proofs than required proof systems
+        );
+        require(
+            (immediateProofs & ~_requiredProofs()) == 0,
+            "01a" // OnChainProposer: immediate proofs include a proof system that is not required
+        );
+        require(
+            firstOptimisticBatch == 0,
+            "01b" // OnChainProposer: there are verified batches missing proofs
         );
+        MIN_PROOFS_TO_VERIFY = minProofs;
+        IMMEDIATE_PROOFS = immediateProofs;
+        PROOF_UPGRADE_WINDOW = upgradeWindow;
+        emit ProofPolicyUpdated(minProofs, immediateProofs, upgradeWindow);
+    }
 
-        lastVerifiedBatch = batchNumber;
+    /// @notice Returns the bitmap of the proof systems required to verify a batch.
+    function _requiredProofs() internal view returns (uint8 proofs) {
+        if (REQUIRE_RISC0_PROOF) {
+            proofs |= RISC0_PROOF;
+        }
+        if (REQUIRE_SP1_PROOF) {
+            proofs |= SP1_PROOF;
+        }
+        if (REQUIRE_TDX_PROOF) {
+            proofs |= TDX_PROOF;
+        }
+    }
 
-        // Remove previous batch commitment as it is no longer needed.
-        delete batchCommitments[batchNumber - 1];
+    function _countProofs(uint8 proofs) internal pure returns (uint8 count) {
+        while (proofs != 0) {
+            count += proofs & 1;
+            proofs >>= 1;
+        }
+    }
 
-        emit BatchVerified(lastVerifiedBatch);
+    /// @notice Returns the amount of proofs a batch has to be verified with.
+    function _minProofsToVerify() internal view returns (uint8) {
+        return
+            MIN_PROOFS_TO_VERIFY == 0 && IMMEDIATE_PROOFS == 0
+                ? _countProofs(_requiredProofs())
+                : MIN_PROOFS_TO_VERIFY;
+    }
+
+    /// @notice New batches can't be verified while the remaining proofs of an
+    /// optimistically verified batch are overdue.
+    function _requireNoOverdueProofs() internal view {
+        require(
+            firstOptimisticBatch == 0 ||
+                block.timestamp <=
+                batchVerifiedAt[firstOptimisticBatch] + PROOF_UPGRADE_WINDOW,
+            "01e" // OnChainProposer: the remaining proofs of an optimistically verified batch are overdue
+        );
+    }
+
+    /// @notice Moves past the optimistically verified batches that got all
+    /// their proofs, removing the commitments no longer needed.
+    function _advanceFullyVerifiedBatches() internal {
+        uint8 requiredProofs = _requiredProofs();
+        uint256 batchNumber = firstOptimisticBatch;
+        while (
+            batchNumber <= lastVerifiedBatch &&
+            batchProofs[batchNumber] == requiredProofs
+        ) {
+            delete batchProofs[batchNumber];
+            delete batchVerifiedAt[batchNumber];
+            delete batchCommitments[batchNumber - 1];
+            batchNumber++;
+        }
+        firstOptimisticBatch = batchNumber > lastVerifiedBatch
+            ? 0
+            : batchNumber;
     }
 
     /// @inheritdoc IOnChainProposer
@@ -516,6 +704,10 @@ contract OnChainProposer is
             firstBatchNumber == lastVerifiedBatch + 1,
             "00i" // OnChainProposer: incorrect first batch number
         );
+        require(
+            firstOptimisticBatch == 0,
+            "01b" // OnChainProposer: there are verified batches missing proofs
+        );
 
         if (REQUIRE_SP1_PROOF) {
             require(
@@ -595,6 +787,175 @@ contract OnChainProposer is
         emit BatchVerified(lastVerifiedBatch);
     }
 
+    /// @inheritdoc IOnChainProposer
+    function verifyBatches(
+        uint256 firstBatchNumber,
+        bytes[] calldata publicInputsList,
+        bytes memory risc0AggregatedSeal,
+        bytes memory sp1AggregatedProof,
+        bytes[] calldata tdxSignatures
+    ) external override onlySequencer whenNotPaused {
+        require(
+            !ALIGNED_MODE,
+            "008" // Batch verification should be done via Aligned Layer. Call verifyBatchesAligned() instead.
+        );
+        require(
+            firstBatchNumber == lastVerifiedBatch + 1,
+            "00i" // OnChainProposer: incorrect first batch number
+        );
+        require(
+            publicInputsList.length > 0,
+            "014" // OnChainProposer: no batches to verify
+        );
+        require(
+            firstOptimisticBatch == 0,
+            "01b" // OnChainProposer: there are verified batches missing proofs
+        );
+        if (REQUIRE_TDX_PROOF) {
+            require(
+                publicInputsList.length == tdxSignatures.length,
+                "015" // OnChainProposer: TDX input/signature array length mismatch
+            );
+        }
+
+        // All the aggregated proofs were generated by the same program version.
+        bytes32 commitHash = batchCommitments[firstBatchNumber].commitHash;
+        bytes32[] memory publicInputsHashes = new bytes32[](
+            publicInputsList.length
+        );
+
+        uint256 batchNumber = firstBatchNumber;
+        for (uint256 i = 0; i < publicInputsList.length; i++) {
+            require(
+                batchCommitments[batchNumber].newStateRoot != bytes32(0),
+                "00l" // OnChainProposer: cannot verify an uncommitted batch
+            );
+            require(
+                batchCommitments[batchNumber].commitHash == commitHash,
+                "016" // OnChainProposer: aggregated batches have different commit hashes
+            );
+
+            _consumeBatchMessages(batchNumber);
+
+            string memory reason = _verifyPublicData(
+                batchNumber,
+                publicInputsList[i]
+            );
+            if (bytes(reason).length != 0) {
+                revert(
+                    string.concat(
+                        "017", // OnChainProposer: Invalid aggregated proof:
+                        reason
+                    )
+                );
+            }
+
+            if (REQUIRE_TDX_PROOF) {
+                try
+                    ITDXVerifier(TDX_VERIFIER_ADDRESS).verify(
+                        publicInputsList[i],
+                        tdxSignatures[i]
+                    )
+                {} catch {
+                    revert(
+                        "00g" // OnChainProposer: Invalid TDX proof failed proof verification
+                    );
+                }
+            }
+
+            publicInputsHashes[i] = keccak256(publicInputsList[i]);
+
+            ICommonBridge(BRIDGE).publishL2Messages(
+                batchCommitments[batchNumber].balanceDiffs
+            );
+
+            // Remove previous batch commitment as it is no longer needed.
+            delete batchCommitments[batchNumber - 1];
+
+            lastVerifiedBatch = batchNumber;
+            batchNumber++;
+        }
+
+        // The aggregation programs commit to the hashes of the public inputs of every batch
+        bytes32 publicInputsCommitment = keccak256(
+            abi.encodePacked(publicInputsHashes)
+        );
+
+        if (REQUIRE_RISC0_PROOF) {
+            // The RISC0 aggregation program also commits to the image id of the batch
+            // program, as it takes it as an input.
+            bytes memory journal = abi.encodePacked(
+                verificationKeys[commitHash][RISC0_VERIFIER_ID],
+                publicInputsCommitment
+            );
+            try
+                IRiscZeroVerifier(RISC0_VERIFIER_ADDRESS).verify(
+                    risc0AggregatedSeal,
+                    verificationKeys[commitHash][RISC0_AGGREGATION_VERIFIER_ID],
+                    sha256(journal)
+                )
+            {} catch {
+                revert(
+                    "018" // OnChainProposer: Invalid RISC0 aggregated proof failed proof verification
+                );
+            }
+        }
+
+        if (REQUIRE_SP1_PROOF) {
+            // The SP1 aggregation program has the batch program vk built in.
+            try
+                ISP1Verifier(SP1_VERIFIER_ADDRESS).verifyProof(
+                    verificationKeys[commitHash][SP1_AGGREGATION_VERIFIER_ID],
+                    abi.encodePacked(publicInputsCommitment),
+                    sp1AggregatedProof
+                )
+            {} catch {
+                revert(
+                    "019" // OnChainProposer: Invalid SP1 aggregated proof failed proof verification
+                );
+            }
+        }
+
+        emit BatchVerified(lastVerifiedBatch);
+    }
+
+    /// @notice Removes the privileged transactions and L2 messages processed
+    /// by a batch from the pending queues of the bridge.
+    function _consumeBatchMessages(uint256 batchNumber) internal {
+        // The first 2 bytes are the number of privileged transactions.
+        uint16 privileged_transaction_count = uint16(
+            bytes2(
+                batchCommitments[batchNumber]
+                    .processedPrivilegedTransactionsRollingHash
+            )
+        );
+        if (privileged_transaction_count > 0) {
+            ICommonBridge(BRIDGE).removePendingTransactionHashes(
+                privileged_transaction_count
+            );
+        }
+
+        ICommonBridge.L2MessageRollingHash[]
+            memory batchL2InRollingHashes = batchCommitments[batchNumber]
+                .l2InMessageRollingHashes;
+        for (uint256 i = 0; i < batchL2InRollingHashes.length; i++) {
+            uint16 l2_messages_count = uint16(
+                bytes2(batchL2InRollingHashes[i].rollingHash)
+            );
+            ICommonBridge(BRIDGE).removePendingL2Messages(
+                batchL2InRollingHashes[i].chainId,
+                l2_messages_count
+            );
+        }
+
+        if (
+            ICommonBridge(BRIDGE).hasExpiredPrivilegedTransactions() &&
+            batchCommitments[batchNumber].nonPrivilegedTransactions != 0
+        ) {
+            revert("00v"); // exceeded privileged transaction inclusion deadline, can't include non-privileged transactions
+        }
+    }
+
     function _verifyPublicData(
         uint256 batchNumber,
         bytes calldata publicData
@@ -624,7 +985,7 @@ contract OnChainProposer is
         }
         bytes32 initialStateRoot = bytes32(publicData[0:32]);
         if (
-            batchCommitments[lastVerifiedBatch].newStateRoot != initialStateRoot
+            batchCommitments[batchNumber - 1].newStateRoot != initialStateRoot
         ) {
             return "00o"; // initial state root public inputs don't match with initial state root
         }
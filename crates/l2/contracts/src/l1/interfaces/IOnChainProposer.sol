@@ -16,6 +16,26 @@ interface IOnChainProposer {
     /// @return The latest verified batch number as a uint256.
     function lastVerifiedBatch() external view returns (uint256);
 
+    /// @notice The latest batch verified by every required proof system.
+    /// @dev Batches after it and up to `lastVerifiedBatch` were verified optimistically.
+    /// @return The latest fully verified batch number as a uint256.
+    function lastFullyVerifiedBatch() external view returns (uint256);
+
+    /// @notice Sets how many, and which, of the required proof systems a batch has to be verified with.
+    /// @dev Batches verified without every required proof are verified optimistically,
+    /// and their remaining proofs have to be submitted within `upgradeWindow` seconds
+    /// through `verifyBatch()`, or no new batches can be verified.
+    /// @param minProofs Amount of proofs needed, zero along with a zero `immediateProofs`
+    /// meaning every required proof system.
+    /// @param immediateProofs Bitmap of the proof systems that have to be among them
+    /// (RISC0 = 1, SP1 = 2, TDX = 4), zero meaning any of them.
+    /// @param upgradeWindow Time the remaining proofs can take, zero meaning they're never needed.
+    function setProofPolicy(
+        uint8 minProofs,
+        uint8 immediateProofs,
+        uint256 upgradeWindow
+    ) external;
+
     /// @notice A batch has been committed.
     /// @dev Event emitted when a batch is committed.
     /// @param newStateRoot The new state root of the batch that was committed.
@@ -25,6 +45,21 @@ interface IOnChainProposer {
     /// @dev Event emitted when a batch is verified.
     event BatchVerified(uint256 indexed lastVerifiedBatch);
 
+    /// @notice Proofs of an optimistically verified batch have been submitted.
+    /// @param batchNumber The batch the proofs were submitted for.
+    /// @param proofs Bitmap of the proof systems that verified the batch so far.
+    event BatchProofsUpgraded(uint256 indexed batchNumber, uint8 proofs);
+
+    /// @notice The proof policy has been updated.
+    /// @param minProofs Amount of the required proof systems a batch has to be verified with.
+    /// @param immediateProofs Bitmap of the proof systems that have to be among them.
+    /// @param upgradeWindow Time, in seconds, the remaining proofs can take.
+    event ProofPolicyUpdated(
+        uint8 minProofs,
+        uint8 immediateProofs,
+        uint256 upgradeWindow
+    );
+
     /// @notice A batch has been reverted.
     /// @dev Event emitted when a batch is reverted.
     event BatchReverted(bytes32 indexed newStateRoot);
@@ -63,6 +98,22 @@ interface IOnChainProposer {
         bytes32 new_vk
     ) external;
 
+    /// @notice Upgrades the verification key of the SP1 program that aggregates batch proofs.
+    /// @param new_vk new verification key for the SP1 aggregation program
+    /// @param commit_hash git commit hash that produced the new verification key
+    function upgradeSP1AggregationVerificationKey(
+        bytes32 commit_hash,
+        bytes32 new_vk
+    ) external;
+
+    /// @notice Upgrades the image id of the RISC0 program that aggregates batch proofs.
+    /// @param new_vk new image id for the RISC0 aggregation program
+    /// @param commit_hash git commit hash that produced the new image id
+    function upgradeRISC0AggregationVerificationKey(
+        bytes32 commit_hash,
+        bytes32 new_vk
+    ) external;
+
     /// @notice Commits to a batch of L2 blocks.
     /// @dev Committing to an L2 batch means to store the batch's commitment
     /// and to publish withdrawals if any.
@@ -131,6 +182,22 @@ interface IOnChainProposer {
         bytes32[][] calldata risc0MerkleProofsList
     ) external;
 
+    /// @notice Method used to verify a sequence of L2 batches with a single aggregated proof
+    /// per proving system, starting from `firstBatchNumber`.
+    /// @dev The aggregated proofs commit to `keccak256(abi.encodePacked(keccak256(publicInputsList[i])...))`.
+    /// @param firstBatchNumber The number of the first batch to verify. Must be `lastVerifiedBatch + 1`.
+    /// @param publicInputsList An array of public input bytes, one per batch.
+    /// @param risc0AggregatedSeal The seal of the RISC0 aggregated proof.
+    /// @param sp1AggregatedProof The SP1 aggregated proof bytes.
+    /// @param tdxSignatures An array of TDX signatures, one per batch.
+    function verifyBatches(
+        uint256 firstBatchNumber,
+        bytes[] calldata publicInputsList,
+        bytes memory risc0AggregatedSeal,
+        bytes memory sp1AggregatedProof,
+        bytes[] calldata tdxSignatures
+    ) external;
+
     /// @notice Allows unverified batches to be reverted
     function revertBatch(uint256 batchNumber) external;
 
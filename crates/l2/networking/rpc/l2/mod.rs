@@ -2,4 +2,5 @@ pub mod batch;
 pub mod execution_witness;
 pub mod fees;
 pub mod messages;
+pub mod preconfirmations;
 pub mod transaction;
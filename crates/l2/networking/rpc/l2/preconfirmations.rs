@@ -0,0 +1,197 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{State, WebSocketUpgrade, ws::WebSocket},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use ethrex_common::{Address, H256};
+use ethrex_l2_common::preconfirmations::{
+    Flashblock, FlashblocksMessage, SignedBlockRevocation, SignedPreconfirmation,
+};
+use serde_json::Value;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    utils::RpcErr,
+};
+
+/// Amount of flashblocks buffered for each websocket subscriber before it starts lagging.
+const FLASHBLOCK_CHANNEL_CAPACITY: usize = 1024;
+/// Amount of preconfirmations kept around to answer `ethrex_getPreconfirmation`.
+const MAX_RECENT_PRECONFIRMATIONS: usize = 100_000;
+
+/// Shared channel between the block producer, which publishes flashblocks
+/// while building a block and revokes them if the block is discarded, and
+/// the RPC, which serves them to clients.
+#[derive(Debug, Clone)]
+pub struct PreconfirmationFeed {
+    signer_address: Address,
+    sender: broadcast::Sender<FlashblocksMessage>,
+    recent: Arc<Mutex<RecentPreconfirmations>>,
+}
+
+#[derive(Debug, Default)]
+struct RecentPreconfirmations {
+    by_hash: HashMap<H256, SignedPreconfirmation>,
+    order: VecDeque<H256>,
+}
+
+impl PreconfirmationFeed {
+    pub fn new(signer_address: Address) -> Self {
+        let (sender, _) = broadcast::channel(FLASHBLOCK_CHANNEL_CAPACITY);
+        Self {
+            signer_address,
+            sender,
+            recent: Arc::new(Mutex::new(RecentPreconfirmations::default())),
+        }
+    }
+
+    /// Address that signs every preconfirmation published through this feed.
+    pub fn signer_address(&self) -> Address {
+        self.signer_address
+    }
+
+    pub fn publish(&self, flashblock: Flashblock) {
+        if let Ok(mut recent) = self.recent.lock() {
+            for signed in &flashblock.preconfirmations {
+                let tx_hash = signed.preconfirmation.tx_hash;
+                if recent.by_hash.insert(tx_hash, signed.clone()).is_none() {
+                    recent.order.push_back(tx_hash);
+                }
+            }
+            while recent.order.len() > MAX_RECENT_PRECONFIRMATIONS {
+                if let Some(oldest) = recent.order.pop_front() {
+                    recent.by_hash.remove(&oldest);
+                }
+            }
+        } else {
+            warn!("Preconfirmations lock was poisoned");
+        }
+        // An error only means there are no subscribers right now
+        let _ = self.sender.send(FlashblocksMessage::Flashblock(flashblock));
+    }
+
+    /// Forgets the preconfirmations of the revoked block and lets subscribers know.
+    pub fn revoke(&self, revocation: SignedBlockRevocation) {
+        if let Ok(mut recent) = self.recent.lock() {
+            let RecentPreconfirmations { by_hash, order } = &mut *recent;
+            by_hash.retain(|_, signed| !revocation.revocation.revokes(&signed.preconfirmation));
+            order.retain(|tx_hash| by_hash.contains_key(tx_hash));
+        } else {
+            warn!("Preconfirmations lock was poisoned");
+        }
+        let _ = self.sender.send(FlashblocksMessage::Revocation(revocation));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<FlashblocksMessage> {
+        self.sender.subscribe()
+    }
+
+    pub fn get(&self, tx_hash: &H256) -> Option<SignedPreconfirmation> {
+        self.recent
+            .lock()
+            .ok()
+            .and_then(|recent| recent.by_hash.get(tx_hash).cloned())
+    }
+}
+
+/// Upgrades requests to the `/flashblocks` path of the websocket server, which
+/// only exists when the sequencer publishes preconfirmations.
+pub async fn handle_flashblocks_upgrade(
+    ws: WebSocketUpgrade,
+    State(context): State<RpcApiContext>,
+) -> Response {
+    match context.preconfirmation_feed {
+        Some(feed) => ws
+            .on_upgrade(|socket| handle_flashblocks_websocket(socket, feed))
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Streams every flashblock and revocation published by the block producer to the websocket client.
+async fn handle_flashblocks_websocket(mut socket: WebSocket, feed: PreconfirmationFeed) {
+    let mut receiver = feed.subscribe();
+    loop {
+        let message = match receiver.recv().await {
+            Ok(message) => message,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!("Flashblocks subscriber lagged behind, skipped {skipped} flashblocks");
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        let Ok(body) = serde_json::to_string(&message) else {
+            return;
+        };
+        if socket.send(body.into()).await.is_err() {
+            debug!("Flashblocks subscriber disconnected");
+            return;
+        }
+    }
+}
+
+pub struct GetPreconfirmation {
+    pub transaction_hash: H256,
+}
+
+impl RpcHandler for GetPreconfirmation {
+    fn parse(params: &Option<Vec<Value>>) -> Result<GetPreconfirmation, RpcErr> {
+        let params = params.as_ref().ok_or(ethrex_rpc::RpcErr::BadParams(
+            "No params provided".to_owned(),
+        ))?;
+        if params.len() != 1 {
+            return Err(ethrex_rpc::RpcErr::BadParams("Expected 1 param".to_owned()))?;
+        };
+        Ok(GetPreconfirmation {
+            transaction_hash: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        debug!(
+            "Requested preconfirmation for transaction {:#x}",
+            self.transaction_hash
+        );
+        let feed = preconfirmation_feed(context, "ethrex_getPreconfirmation")?;
+        let Some(preconfirmation) = feed.get(&self.transaction_hash) else {
+            return Ok(Value::Null);
+        };
+        serde_json::to_value(preconfirmation).map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+pub struct GetPreconfirmationSigner;
+
+impl RpcHandler for GetPreconfirmationSigner {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        if params.as_ref().is_some_and(|params| !params.is_empty()) {
+            return Err(ethrex_rpc::RpcErr::BadParams(
+                "Expected 0 params".to_owned(),
+            ))?;
+        };
+        Ok(GetPreconfirmationSigner)
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let feed = preconfirmation_feed(context, "ethrex_preconfirmationSigner")?;
+        serde_json::to_value(format!("{:#x}", feed.signer_address()))
+            .map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+/// The preconfirmation methods only exist when the sequencer publishes preconfirmations.
+fn preconfirmation_feed(
+    context: RpcApiContext,
+    method: &str,
+) -> Result<PreconfirmationFeed, RpcErr> {
+    context
+        .preconfirmation_feed
+        .ok_or_else(|| ethrex_rpc::RpcErr::MethodNotFound(method.to_owned()).into())
+}
@@ -7,8 +7,12 @@ use crate::l2::fees::{
     GetOperatorFeeVaultAddress,
 };
 use crate::l2::messages::GetL1MessageProof;
+use crate::l2::preconfirmations::{
+    GetPreconfirmation, GetPreconfirmationSigner, PreconfirmationFeed, handle_flashblocks_upgrade,
+};
 use crate::utils::{RpcErr, RpcNamespace, resolve_namespace};
-use axum::extract::State;
+use axum::extract::ws::WebSocket;
+use axum::extract::{ConnectInfo, State, WebSocketUpgrade};
 use axum::{Json, Router, http::StatusCode, routing::post};
 use bytes::Bytes;
 use ethrex_blockchain::Blockchain;
@@ -20,7 +24,8 @@ use ethrex_p2p::types::NodeRecord;
 use ethrex_rpc::RpcHandler as L1RpcHandler;
 use ethrex_rpc::debug::execution_witness::ExecutionWitnessRequest;
 use ethrex_rpc::{
-    GasTipEstimator, NodeData, RpcRequestWrapper,
+    GasTipEstimator, NodeData, ResponseBudget, RpcApiFilter, RpcLimiter, RpcLimits,
+    RpcRequestWrapper, batch_error_response, serve_optional,
     types::transaction::SendRawTransactionRequest,
     utils::{RpcRequest, RpcRequestId},
 };
@@ -29,7 +34,7 @@ use serde_json::Value;
 use std::{
     collections::HashMap,
     future::IntoFuture,
-    net::SocketAddr,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     sync::{Arc, Mutex},
     time::Duration,
 };
@@ -49,6 +54,9 @@ pub struct RpcApiContext {
     pub valid_delegation_addresses: Vec<Address>,
     pub sponsor_pk: SecretKey,
     pub rollup_store: StoreRollup,
+    pub preconfirmation_feed: Option<PreconfirmationFeed>,
+    /// Namespaces and methods served by the listener the request came from
+    pub api: Arc<RpcApiFilter>,
 }
 
 pub trait RpcHandler: Sized {
@@ -73,6 +81,10 @@ pub const FILTER_DURATION: Duration = {
 #[expect(clippy::too_many_arguments)]
 pub async fn start_api(
     http_addr: SocketAddr,
+    http_api: RpcApiFilter,
+    ws_addr: Option<SocketAddr>,
+    ws_api: RpcApiFilter,
+    admin_port: Option<u16>,
     authrpc_addr: SocketAddr,
     storage: Store,
     blockchain: Arc<Blockchain>,
@@ -87,6 +99,8 @@ pub async fn start_api(
     rollup_store: StoreRollup,
     log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
     gas_ceil: u64,
+    preconfirmation_feed: Option<PreconfirmationFeed>,
+    limits: RpcLimits,
 ) -> Result<(), RpcErr> {
     // TODO: Refactor how filters are handled,
     // filters are used by the filters endpoints (eth_newFilter, eth_getFilterChanges, ...etc)
@@ -110,10 +124,14 @@ pub async fn start_api(
             log_filter_handler,
             gas_ceil,
             block_worker_channel,
+            limiter: Arc::new(RpcLimiter::new(limits)),
+            bundle_signer: None,
         },
         valid_delegation_addresses,
         sponsor_pk,
         rollup_store,
+        preconfirmation_feed,
+        api: Arc::new(http_api),
     };
 
     // Periodically clean up the active filters for the filters endpoints.
@@ -136,40 +154,146 @@ pub async fn start_api(
 
     let http_router = Router::new()
         .route("/", post(handle_http_request))
-        .layer(cors)
+        .layer(cors.clone())
         .with_state(service_context.clone());
     let http_listener = TcpListener::bind(http_addr)
         .await
         .map_err(|error| RpcErr::Internal(error.to_string()))?;
-    let http_server = axum::serve(http_listener, http_router)
-        .with_graceful_shutdown(ethrex_rpc::shutdown_signal())
-        .into_future();
+    let http_server = axum::serve(
+        http_listener,
+        http_router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(ethrex_rpc::shutdown_signal())
+    .into_future();
     info!("Starting HTTP server at {http_addr}");
 
     info!("Not starting Auth-RPC server. The address passed as argument is {authrpc_addr}");
 
-    let _ =
-        tokio::try_join!(http_server).inspect_err(|e| info!("Error shutting down servers: {e:?}"));
+    let ws_server = if let Some(address) = ws_addr {
+        let ws_handler = |ws: WebSocketUpgrade, ctx, client| async {
+            ws.on_upgrade(|socket| handle_websocket(socket, ctx, client))
+        };
+        let ws_router = Router::new()
+            .route("/", axum::routing::any(ws_handler))
+            .route(
+                "/flashblocks",
+                axum::routing::any(handle_flashblocks_upgrade),
+            )
+            .layer(cors)
+            .with_state(RpcApiContext {
+                api: Arc::new(ws_api),
+                ..service_context.clone()
+            });
+        let ws_listener = TcpListener::bind(address)
+            .await
+            .map_err(|error| RpcErr::Internal(error.to_string()))?;
+        info!("Starting WS server at {address}");
+        Some(
+            axum::serve(
+                ws_listener,
+                ws_router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(ethrex_rpc::shutdown_signal())
+            .into_future(),
+        )
+    } else {
+        None
+    };
+
+    // The admin namespace can change the node's configuration, so it's only served on the loopback interface
+    let admin_server = if let Some(port) = admin_port {
+        let admin_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let admin_router = Router::new()
+            .route("/", post(handle_http_request))
+            .with_state(RpcApiContext {
+                api: Arc::new(RpcApiFilter::namespaces([ethrex_rpc::RpcNamespace::Admin])),
+                ..service_context
+            });
+        let admin_listener = TcpListener::bind(admin_addr)
+            .await
+            .map_err(|error| RpcErr::Internal(error.to_string()))?;
+        info!("Starting admin RPC server at {admin_addr}");
+        Some(
+            axum::serve(
+                admin_listener,
+                admin_router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(ethrex_rpc::shutdown_signal())
+            .into_future(),
+        )
+    } else {
+        None
+    };
+
+    let _ = tokio::try_join!(
+        http_server,
+        serve_optional(ws_server),
+        serve_optional(admin_server)
+    )
+    .inspect_err(|e| info!("Error shutting down servers: {e:?}"));
 
     Ok(())
 }
 
+async fn handle_websocket(
+    mut socket: WebSocket,
+    state: State<RpcApiContext>,
+    client: ConnectInfo<SocketAddr>,
+) {
+    while let Some(message) = socket.recv().await {
+        let Ok(body) = message
+            .and_then(|msg| msg.into_text())
+            .map(|msg| msg.to_string())
+        else {
+            return;
+        };
+
+        // ok-clone: increase arc reference count
+        let Ok(response) = handle_http_request(state.clone(), client, body)
+            .await
+            .map(|res| res.to_string())
+        else {
+            return;
+        };
+
+        if socket.send(response.into()).await.is_err() {
+            return;
+        }
+    }
+}
+
 async fn handle_http_request(
     State(service_context): State<RpcApiContext>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
     body: String,
 ) -> Result<Json<Value>, StatusCode> {
+    let limiter = service_context.l1_ctx.limiter.clone();
+    let limits = limiter.limits();
+    let mut budget = ResponseBudget::new(limits.max_response_size);
     let res = match serde_json::from_str::<RpcRequestWrapper>(&body) {
         Ok(RpcRequestWrapper::Single(request)) => {
-            let res = map_http_requests(&request, service_context).await;
-            ethrex_rpc::rpc_response(request.id, res).map_err(|_| StatusCode::BAD_REQUEST)?
+            let res = map_limited_requests(&request, client.ip(), service_context).await;
+            budget
+                .response(&request, res)
+                .map_err(|_| StatusCode::BAD_REQUEST)?
         }
         Ok(RpcRequestWrapper::Multiple(requests)) => {
+            if let Err(error) = limits.check_batch_size(requests.len()) {
+                return Ok(Json(
+                    batch_error_response(error).map_err(|_| StatusCode::BAD_REQUEST)?,
+                ));
+            }
             let mut responses = Vec::new();
             for req in requests {
-                let res = map_http_requests(&req, service_context.clone()).await;
-                responses.push(
-                    ethrex_rpc::rpc_response(req.id, res).map_err(|_| StatusCode::BAD_REQUEST)?,
-                );
+                // The rest of the batch isn't processed once its responses are too large
+                let response = if budget.is_spent() {
+                    budget.too_large(&req)
+                } else {
+                    let res =
+                        map_limited_requests(&req, client.ip(), service_context.clone()).await;
+                    budget.response(&req, res)
+                };
+                responses.push(response.map_err(|_| StatusCode::BAD_REQUEST)?);
             }
             serde_json::to_value(responses).map_err(|_| StatusCode::BAD_REQUEST)?
         }
@@ -184,8 +308,20 @@ async fn handle_http_request(
     Ok(Json(res))
 }
 
+/// Handle requests from clients, rejecting the ones exceeding the client's limits
+async fn map_limited_requests(
+    req: &RpcRequest,
+    client: IpAddr,
+    context: RpcApiContext,
+) -> Result<Value, RpcErr> {
+    // Held until the request is handled
+    let _permit = context.l1_ctx.limiter.admit(Some(client), &req.method)?;
+    map_http_requests(req, context).await
+}
+
 /// Handle requests that can come from either clients or other users
 pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+    check_api(req, &context.api)?;
     match resolve_namespace(&req.method) {
         Ok(RpcNamespace::L1RpcNamespace(ethrex_rpc::RpcNamespace::Eth)) => {
             map_eth_requests(req, context).await
@@ -197,6 +333,20 @@ pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Resu
     }
 }
 
+/// Returns `MethodNotFound` if the request's method is disabled in the listener.
+/// The ethrex namespace has no entry of its own in the namespace lists, so it's served along with eth.
+fn check_api(req: &RpcRequest, api: &RpcApiFilter) -> Result<(), RpcErr> {
+    match resolve_namespace(&req.method)? {
+        RpcNamespace::EthrexL2 => {
+            if !api.allows(ethrex_rpc::RpcNamespace::Eth) || api.denies(&req.method) {
+                return Err(ethrex_rpc::RpcErr::MethodNotFound(req.method.clone()).into());
+            }
+            Ok(())
+        }
+        RpcNamespace::L1RpcNamespace(_) => Ok(api.check(req)?),
+    }
+}
+
 pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.method.as_str() {
         "eth_sendRawTransaction" => {
@@ -238,6 +388,8 @@ pub async fn map_l2_requests(req: &RpcRequest, context: RpcApiContext) -> Result
         "ethrex_getOperatorFee" => GetOperatorFee::call(req, context).await,
         "ethrex_getL1FeeVaultAddress" => GetL1FeeVaultAddress::call(req, context).await,
         "ethrex_getL1BlobBaseFee" => GetL1BlobBaseFeeRequest::call(req, context).await,
+        "ethrex_getPreconfirmation" => GetPreconfirmation::call(req, context).await,
+        "ethrex_preconfirmationSigner" => GetPreconfirmationSigner::call(req, context).await,
         unknown_ethrex_l2_method => {
             Err(ethrex_rpc::RpcErr::MethodNotFound(unknown_ethrex_l2_method.to_owned()).into())
         }
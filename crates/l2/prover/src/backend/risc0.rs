@@ -4,7 +4,7 @@ use ethrex_l2_common::{
 };
 use guest_program::{
     input::ProgramInput,
-    methods::{ZKVM_RISC0_PROGRAM_ELF, ZKVM_RISC0_PROGRAM_ID},
+    methods::{ZKVM_RISC0_AGGREGATION_PROGRAM_ELF, ZKVM_RISC0_PROGRAM_ELF, ZKVM_RISC0_PROGRAM_ID},
 };
 use risc0_zkp::verify::VerificationError;
 use risc0_zkvm::{
@@ -21,6 +21,8 @@ pub enum Error {
     EncodeNonGroth16Seal,
     #[error("failed to get seal selector")]
     NoSealSelector,
+    #[error("can only aggregate compressed proofs")]
+    AggregateNonCompressedProof,
     #[error("verification failed: {0}")]
     VerificationFailed(#[from] VerificationError),
     #[error("decode failed: {0}")]
@@ -114,6 +116,39 @@ pub fn verify(receipt: &Receipt) -> Result<(), Error> {
     Ok(())
 }
 
+/// Generates a single Groth16 receipt attesting the compressed receipts of
+/// consecutive batches, in order.
+pub fn aggregate(proofs: Vec<BatchProof>) -> Result<BatchProof, Box<dyn std::error::Error>> {
+    let mut journals = Vec::with_capacity(proofs.len());
+    let mut receipts = Vec::with_capacity(proofs.len());
+    for proof in proofs {
+        let Some(inner) = proof.compressed() else {
+            return Err(Error::AggregateNonCompressedProof.into());
+        };
+        let inner: InnerReceipt = bincode::deserialize(&inner)?;
+        let journal = proof.public_values();
+        receipts.push(Receipt::new(inner, journal.clone()));
+        journals.push(journal);
+    }
+
+    info!("Aggregating {} RISC0 receipts", journals.len());
+    let mut builder = ExecutorEnv::builder();
+    builder.write(&(ZKVM_RISC0_PROGRAM_ID, journals))?;
+    for receipt in receipts {
+        builder.add_assumption(receipt);
+    }
+    let env = builder.build()?;
+
+    let prover = default_prover();
+    let prove_info = prover.prove_with_opts(
+        env,
+        ZKVM_RISC0_AGGREGATION_PROGRAM_ELF,
+        &ProverOpts::groth16(),
+    )?;
+
+    Ok(BatchProof::ProofCalldata(to_calldata(prove_info.receipt)?))
+}
+
 pub fn to_batch_proof(
     receipt: Receipt,
     format: ProofFormat,
@@ -2,7 +2,7 @@ use ethrex_l2_common::{
     calldata::Value,
     prover::{BatchProof, ProofBytes, ProofCalldata, ProofFormat, ProverType},
 };
-use guest_program::{ZKVM_SP1_PROGRAM_ELF, input::ProgramInput};
+use guest_program::{ZKVM_SP1_AGGREGATION_PROGRAM_ELF, ZKVM_SP1_PROGRAM_ELF, input::ProgramInput};
 use rkyv::rancor::Error;
 use sp1_prover::components::CpuProverComponents;
 #[cfg(not(feature = "gpu"))]
@@ -10,7 +10,7 @@ use sp1_sdk::CpuProver;
 #[cfg(feature = "gpu")]
 use sp1_sdk::cuda::builder::CudaProverBuilder;
 use sp1_sdk::{
-    HashableKey, Prover, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin,
+    HashableKey, Prover, SP1Proof, SP1ProofMode, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin,
     SP1VerifyingKey,
 };
 use std::{
@@ -29,6 +29,9 @@ pub struct ProverSetup {
 
 pub static PROVER_SETUP: OnceLock<ProverSetup> = OnceLock::new();
 
+/// Proving and verifying keys of the aggregation program, set up on the first aggregation.
+static AGGREGATION_SETUP: OnceLock<(SP1ProvingKey, SP1VerifyingKey)> = OnceLock::new();
+
 pub fn init_prover_setup(_endpoint: Option<Url>) -> ProverSetup {
     #[cfg(feature = "gpu")]
     let client = {
@@ -156,6 +159,44 @@ pub fn verify(output: &ProveOutput) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Generates a single Groth16 proof attesting the compressed proofs of
+/// consecutive batches, in order.
+pub fn aggregate(proofs: Vec<BatchProof>) -> Result<BatchProof, Box<dyn std::error::Error>> {
+    let setup = PROVER_SETUP.get_or_init(|| init_prover_setup(None));
+    let (aggregation_pk, aggregation_vk) =
+        AGGREGATION_SETUP.get_or_init(|| setup.client.setup(ZKVM_SP1_AGGREGATION_PROGRAM_ELF));
+
+    let mut public_inputs_list = Vec::with_capacity(proofs.len());
+    let mut reduce_proofs = Vec::with_capacity(proofs.len());
+    for proof in proofs {
+        let Some(proof) = proof.compressed() else {
+            return Err("can only aggregate compressed proofs".into());
+        };
+        let proof: SP1ProofWithPublicValues = bincode::deserialize(&proof)?;
+        let SP1Proof::Compressed(reduce_proof) = proof.proof else {
+            return Err("can only aggregate compressed proofs".into());
+        };
+        public_inputs_list.push(proof.public_values.to_vec());
+        reduce_proofs.push(reduce_proof);
+    }
+
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&public_inputs_list);
+    for reduce_proof in reduce_proofs {
+        stdin.write_proof(*reduce_proof, setup.vk.vk.clone());
+    }
+
+    info!("Aggregating {} SP1 proofs", public_inputs_list.len());
+    let proof = setup
+        .client
+        .prove(aggregation_pk, &stdin, SP1ProofMode::Groth16)?;
+
+    Ok(BatchProof::ProofCalldata(to_calldata(ProveOutput::new(
+        proof,
+        aggregation_vk.clone(),
+    ))))
+}
+
 pub fn to_batch_proof(
     proof: ProveOutput,
     format: ProofFormat,
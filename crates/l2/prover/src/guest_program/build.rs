@@ -25,41 +25,54 @@ fn build_risc0_program() {
         vec![]
     };
 
-    let guest_options = if option_env!("PROVER_REPRODUCIBLE_BUILD").is_some() {
-        let docker_options = DockerOptionsBuilder::default()
-            .root_dir(format!("{}/../../../../../", env!("CARGO_MANIFEST_DIR")))
-            .build()
-            .unwrap();
-        GuestOptionsBuilder::default()
-            .features(features)
-            .use_docker(docker_options)
-            .build()
-            .unwrap()
-    } else {
-        GuestOptionsBuilder::default()
-            .features(features)
-            .build()
-            .unwrap()
+    let guest_options = |features: Vec<String>| {
+        if option_env!("PROVER_REPRODUCIBLE_BUILD").is_some() {
+            let docker_options = DockerOptionsBuilder::default()
+                .root_dir(format!("{}/../../../../../", env!("CARGO_MANIFEST_DIR")))
+                .build()
+                .unwrap();
+            GuestOptionsBuilder::default()
+                .features(features)
+                .use_docker(docker_options)
+                .build()
+                .unwrap()
+        } else {
+            GuestOptionsBuilder::default()
+                .features(features)
+                .build()
+                .unwrap()
+        }
     };
 
-    let built_guests = embed_methods_with_options(std::collections::HashMap::from([(
-        "zkvm-risc0-program",
-        guest_options,
-    )]));
-    let elf = built_guests[0].elf.clone();
-    let image_id = built_guests[0].image_id;
-
-    // this errs if the dir already exists, so we don't handle an error.
-    let _ = std::fs::create_dir("./src/risc0/out");
-
-    std::fs::write("./src/risc0/out/riscv32im-risc0-elf", &elf)
-        .expect("could not write Risc0 elf to file");
-
-    std::fs::write(
-        "./src/risc0/out/riscv32im-risc0-vk",
-        format!("0x{}\n", hex::encode(image_id.as_bytes())),
-    )
-    .expect("could not write Risc0 vk to file");
+    let built_guests = embed_methods_with_options(std::collections::HashMap::from([
+        ("zkvm-risc0-program", guest_options(features)),
+        ("zkvm-risc0-aggregation-program", guest_options(vec![])),
+    ]));
+
+    for (name, out_dir) in [
+        ("zkvm-risc0-program", "./src/risc0/out"),
+        (
+            "zkvm-risc0-aggregation-program",
+            "./src/risc0_aggregation/out",
+        ),
+    ] {
+        let guest = built_guests
+            .iter()
+            .find(|guest| guest.name == name)
+            .expect("risc0 guest was not built");
+
+        // this errs if the dir already exists, so we don't handle an error.
+        let _ = std::fs::create_dir(out_dir);
+
+        std::fs::write(format!("{out_dir}/riscv32im-risc0-elf"), &guest.elf)
+            .expect("could not write Risc0 elf to file");
+
+        std::fs::write(
+            format!("{out_dir}/riscv32im-risc0-vk"),
+            format!("0x{}\n", hex::encode(guest.image_id.as_bytes())),
+        )
+        .expect("could not write Risc0 vk to file");
+    }
 }
 
 #[cfg(all(not(clippy), feature = "sp1"))]
@@ -103,6 +116,40 @@ fn build_sp1_program() {
         format!("0x{}\n", hex::encode(vk.vk.hash_bytes())),
     )
     .expect("could not write SP1 vk-u32 to file");
+
+    // The aggregation program only accepts proofs of the batch program built above,
+    // so its verification key has to be known before building it.
+    let _ = std::fs::create_dir("./src/sp1_aggregation/out");
+    std::fs::write(
+        "./src/sp1_aggregation/out/batch_program_vk.rs",
+        format!(
+            "pub const BATCH_PROGRAM_VK: [u32; 8] = {:?};\n",
+            vk.vk.hash_u32()
+        ),
+    )
+    .expect("could not write SP1 batch program vk to file");
+
+    sp1_build::build_program_with_args(
+        "./src/sp1_aggregation",
+        sp1_build::BuildArgs {
+            output_directory: Some("./src/sp1_aggregation/out".to_string()),
+            elf_name: Some("riscv32im-succinct-zkvm-elf".to_string()),
+            docker: option_env!("PROVER_REPRODUCIBLE_BUILD").is_some(),
+            tag: "v5.0.8".to_string(),
+            workspace_directory: Some(format!("{}/../../../../../", env!("CARGO_MANIFEST_DIR"))),
+            ..Default::default()
+        },
+    );
+
+    let elf = std::fs::read("./src/sp1_aggregation/out/riscv32im-succinct-zkvm-elf")
+        .expect("could not read SP1 aggregation elf file");
+    let (_, vk) = prover.setup(&elf);
+
+    std::fs::write(
+        "./src/sp1_aggregation/out/riscv32im-succinct-zkvm-vk-bn254",
+        format!("{}\n", vk.vk.bytes32()),
+    )
+    .expect("could not write SP1 aggregation vk-bn254 to file");
 }
 
 #[cfg(all(not(clippy), feature = "zisk"))]
@@ -0,0 +1,60 @@
+use ethrex_crypto::keccak::keccak_hash;
+
+/// Commitment to the public inputs of a range of consecutive batches, exposed
+/// as the public output of an aggregated proof.
+///
+/// It has to match the one computed by `OnChainProposer.verifyBatches()`:
+/// `keccak256(abi.encodePacked(keccak256(publicInputs[0]), ..., keccak256(publicInputs[n])))`
+pub fn public_inputs_commitment<T: AsRef<[u8]>>(public_inputs: &[T]) -> [u8; 32] {
+    let hashes: Vec<u8> = public_inputs
+        .iter()
+        .flat_map(|public_inputs| keccak_hash(public_inputs))
+        .collect();
+    keccak_hash(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ethrex_common::H256;
+
+    use super::*;
+
+    fn h256(hex: &str) -> [u8; 32] {
+        H256::from_str(hex).unwrap().0
+    }
+
+    #[test]
+    fn commitment_matches_verify_batches_encoding() {
+        let public_inputs: [&[u8]; 2] = [&[0x01], &[0x02, 0x03]];
+
+        // keccak256(abi.encodePacked(publicInputsHashes)), with publicInputsHashes a bytes32[]
+        // holding keccak256(0x01) and keccak256(0x0203)
+        let mut packed = Vec::new();
+        packed.extend(h256(
+            "5fe7f977e71dba2ea1a68e21057beebb9be2ac30c6410aa38d4f3fbe41dcffd2",
+        ));
+        packed.extend(h256(
+            "57ca2fe04d5cba0d4d4219560d4b2e77c3f4f8c7214a1b99ee8c3a7fa01184fe",
+        ));
+        assert_eq!(
+            public_inputs_commitment(&public_inputs),
+            keccak_hash(packed)
+        );
+        assert_eq!(
+            public_inputs_commitment(&public_inputs),
+            h256("47be2b3008e354e2bb93865926f6df17f2e1f5fa96ae815db29d5663bc79701a")
+        );
+    }
+
+    #[test]
+    fn commitment_depends_on_the_order_of_the_batches() {
+        let public_inputs: [&[u8]; 2] = [&[0x01], &[0x02, 0x03]];
+        let reversed: [&[u8]; 2] = [&[0x02, 0x03], &[0x01]];
+        assert_ne!(
+            public_inputs_commitment(&public_inputs),
+            public_inputs_commitment(&reversed)
+        );
+    }
+}
@@ -4,8 +4,8 @@ use crate::report_cycles;
 
 use ethrex_blockchain::error::ChainError;
 use ethrex_blockchain::{
-    validate_block, validate_gas_used, validate_receipts_root, validate_requests_hash,
-    validate_state_root,
+    validate_block, validate_block_access_list_hash, validate_gas_used, validate_receipts_root,
+    validate_requests_hash, validate_state_root,
 };
 use ethrex_common::types::AccountUpdate;
 use ethrex_common::types::block_execution_witness::ExecutionWitness;
@@ -41,6 +41,8 @@ pub enum StatelessExecutionError {
     GasValidationError(ChainError),
     #[error("L1Message validation error: {0}")]
     RequestsRootValidationError(ChainError),
+    #[error("Block access list validation error: {0}")]
+    BlockAccessListValidationError(ChainError),
     #[error("Receipts validation error: {0}")]
     ReceiptsRootValidationError(ChainError),
     #[error("EVM error: {0}")]
@@ -240,6 +242,15 @@ pub fn stateless_validation_l1(
                 .map_err(StatelessExecutionError::RequestsRootValidationError)
         })?;
 
+        report_cycles("validate_block_access_list_hash", || {
+            validate_block_access_list_hash(
+                &block.header,
+                &chain_config,
+                result.block_access_list.as_ref(),
+            )
+            .map_err(StatelessExecutionError::BlockAccessListValidationError)
+        })?;
+
         non_privileged_count += block.body.transactions.len();
         parent_block_header = &block.header;
     }
@@ -459,6 +470,12 @@ fn execute_stateless(
         // validate_requests_hash doesn't do anything for l2 blocks as this verifies l1 requests (messages, privileged transactions and consolidations)
         validate_requests_hash(&block.header, &chain_config, &result.requests)
             .map_err(StatelessExecutionError::RequestsRootValidationError)?;
+        validate_block_access_list_hash(
+            &block.header,
+            &chain_config,
+            result.block_access_list.as_ref(),
+        )
+        .map_err(StatelessExecutionError::BlockAccessListValidationError)?;
         acc_receipts.push(receipts);
 
         parent_block_header = &block.header;
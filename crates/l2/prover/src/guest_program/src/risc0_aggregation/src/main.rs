@@ -0,0 +1,17 @@
+use guest_program::aggregation::public_inputs_commitment;
+use risc0_zkvm::{guest::env, sha::Digest};
+
+fn main() {
+    let (image_id, journals): ([u32; 8], Vec<Vec<u8>>) = env::read();
+
+    for journal in &journals {
+        // The receipts themselves are added by the host as assumptions,
+        // in the same order as their journals.
+        env::verify(image_id, journal).unwrap();
+    }
+
+    // The image id is committed so the verifier can check the batch proofs
+    // were generated by the program registered for the batches' commit hash.
+    env::commit_slice(Digest::from(image_id).as_bytes());
+    env::commit_slice(&public_inputs_commitment(&journals));
+}
@@ -0,0 +1,24 @@
+#![no_main]
+
+use guest_program::aggregation::public_inputs_commitment;
+use sha2::{Digest, Sha256};
+
+sp1_zkvm::entrypoint!(main);
+
+// Verification key of the batch execution program, written by the build script
+// right after building it, so an aggregated proof can only attest batch proofs
+// of the program built alongside it.
+include!("../out/batch_program_vk.rs");
+
+pub fn main() {
+    let public_inputs_list = sp1_zkvm::io::read::<Vec<Vec<u8>>>();
+
+    for public_inputs in &public_inputs_list {
+        let public_inputs_digest: [u8; 32] = Sha256::digest(public_inputs).into();
+        // The proofs themselves are passed by the host through `SP1Stdin::write_proof`,
+        // in the same order as their public inputs.
+        sp1_zkvm::lib::verify::verify_sp1_proof(&BATCH_PROGRAM_VK, &public_inputs_digest);
+    }
+
+    sp1_zkvm::io::commit_slice(&public_inputs_commitment(&public_inputs_list));
+}
@@ -3,7 +3,7 @@ pub mod prover;
 
 pub mod config;
 use config::ProverConfig;
-use ethrex_l2_common::prover::{BatchProof, ProofFormat};
+use ethrex_l2_common::prover::{BatchProof, ProofFormat, ProverType};
 use guest_program::input::ProgramInput;
 use std::time::Duration;
 use tracing::warn;
@@ -107,3 +107,44 @@ pub fn to_batch_proof(
         ProveOutput::OpenVM(proof) => backend::openvm::to_batch_proof(proof, format),
     }
 }
+
+/// Prover type of the proofs generated by the specified backend, if the
+/// proof coordinator can require them.
+pub fn backend_prover_type(backend: Backend) -> Option<ProverType> {
+    match backend {
+        Backend::Exec => Some(ProverType::Exec),
+        #[cfg(feature = "sp1")]
+        Backend::SP1 => Some(ProverType::SP1),
+        #[cfg(feature = "risc0")]
+        Backend::RISC0 => Some(ProverType::RISC0),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Prover type of the aggregated proofs generated by the specified backend,
+/// if it supports proof aggregation.
+pub fn aggregation_prover_type(backend: Backend) -> Option<ProverType> {
+    match backend {
+        #[cfg(feature = "sp1")]
+        Backend::SP1 => Some(ProverType::SP1),
+        #[cfg(feature = "risc0")]
+        Backend::RISC0 => Some(ProverType::RISC0),
+        _ => None,
+    }
+}
+
+/// Aggregate the compressed proofs of consecutive batches into a single proof
+/// using the specified backend.
+pub fn aggregate(
+    backend: Backend,
+    proofs: Vec<BatchProof>,
+) -> Result<BatchProof, Box<dyn std::error::Error>> {
+    match backend {
+        #[cfg(feature = "sp1")]
+        Backend::SP1 => backend::sp1::aggregate(proofs),
+        #[cfg(feature = "risc0")]
+        Backend::RISC0 => backend::risc0::aggregate(proofs),
+        _ => Err(format!("{backend:?} backend doesn't support proof aggregation").into()),
+    }
+}
@@ -1,6 +1,9 @@
-use crate::{backend::Backend, config::ProverConfig, prove, to_batch_proof};
+use crate::{
+    aggregate, aggregation_prover_type, backend::Backend, backend_prover_type,
+    config::ProverConfig, prove, to_batch_proof,
+};
 use ethrex_l2::sequencer::{proof_coordinator::ProofData, utils::get_git_commit_hash};
-use ethrex_l2_common::prover::{BatchProof, ProofFormat};
+use ethrex_l2_common::prover::{BatchProof, ProofFormat, ProverType};
 use guest_program::input::ProgramInput;
 use std::time::Duration;
 use tokio::{
@@ -22,6 +25,12 @@ struct ProverData {
     format: ProofFormat,
 }
 
+struct AggregationData {
+    first_batch: u64,
+    last_batch: u64,
+    proofs: Vec<BatchProof>,
+}
+
 struct Prover {
     backend: Backend,
     proof_coordinator_endpoints: Vec<Url>,
@@ -86,12 +95,98 @@ impl Prover {
                     // TODO: Retry?
                     warn!(%endpoint, "Failed to submit proof: {e}"));
             }
+
+            if let Some(prover_type) = aggregation_prover_type(self.backend) {
+                for endpoint in &self.proof_coordinator_endpoints {
+                    self.aggregate_proofs(endpoint, prover_type).await;
+                }
+            }
         }
     }
 
+    /// Aggregates the proofs of the next range of batches the proof coordinator
+    /// has ready, if any, and submits the aggregated proof.
+    async fn aggregate_proofs(&self, endpoint: &Url, prover_type: ProverType) {
+        let Ok(Some(aggregation_data)) = self
+            .request_aggregation(endpoint, prover_type)
+            .await
+            .inspect_err(|e| error!(%endpoint, "Failed to request proofs to aggregate: {e}"))
+        else {
+            return;
+        };
+
+        let Ok(aggregated_proof) = aggregate(self.backend, aggregation_data.proofs)
+            .inspect_err(|e| error!("Failed to aggregate proofs: {e}"))
+        else {
+            return;
+        };
+
+        let _ = self
+            .submit_aggregated_proof(
+                endpoint,
+                aggregation_data.first_batch,
+                aggregation_data.last_batch,
+                aggregated_proof,
+            )
+            .await
+            .inspect_err(|e| warn!(%endpoint, "Failed to submit aggregated proof: {e}"));
+    }
+
+    async fn request_aggregation(
+        &self,
+        endpoint: &Url,
+        prover_type: ProverType,
+    ) -> Result<Option<AggregationData>, String> {
+        let request = ProofData::aggregation_request(self.commit_hash.clone(), prover_type);
+        let ProofData::AggregationResponse {
+            first_batch,
+            last_batch,
+            proofs,
+        } = connect_to_prover_server_wr(endpoint, &request)
+            .await
+            .map_err(|e| format!("Failed to get Response: {e}"))?
+        else {
+            return Err("Expecting ProofData::AggregationResponse".to_owned());
+        };
+
+        let (Some(first_batch), Some(last_batch)) = (first_batch, last_batch) else {
+            debug!(%endpoint, "No batches ready to be aggregated");
+            return Ok(None);
+        };
+
+        info!(%endpoint, "Received proofs to aggregate for batches {first_batch} to {last_batch}");
+        Ok(Some(AggregationData {
+            first_batch,
+            last_batch,
+            proofs,
+        }))
+    }
+
+    async fn submit_aggregated_proof(
+        &self,
+        endpoint: &Url,
+        first_batch: u64,
+        last_batch: u64,
+        aggregated_proof: BatchProof,
+    ) -> Result<(), String> {
+        let submit = ProofData::aggregated_proof_submit(first_batch, last_batch, aggregated_proof);
+
+        let ProofData::AggregatedProofSubmitACK { first_batch } =
+            connect_to_prover_server_wr(endpoint, &submit)
+                .await
+                .map_err(|e| format!("Failed to get AggregatedProofSubmitACK: {e}"))?
+        else {
+            return Err("Expecting ProofData::AggregatedProofSubmitACK".to_owned());
+        };
+
+        info!(%endpoint, "Received aggregated proof submit ack for first_batch: {first_batch}");
+        Ok(())
+    }
+
     async fn request_new_input(&self, endpoint: &Url) -> Result<Option<ProverData>, String> {
         // Request the input with the correct batch_number
-        let request = ProofData::batch_request(self.commit_hash.clone());
+        let request =
+            ProofData::batch_request(self.commit_hash.clone(), backend_prover_type(self.backend));
         let response = connect_to_prover_server_wr(endpoint, &request)
             .await
             .map_err(|e| format!("Failed to get Response: {e}"))?;
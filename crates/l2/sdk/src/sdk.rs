@@ -1125,6 +1125,39 @@ pub async fn get_last_verified_batch(
     _call_u64_variable(client, b"lastVerifiedBatch()", on_chain_proposer_address).await
 }
 
+pub async fn get_last_fully_verified_batch(
+    client: &EthClient,
+    on_chain_proposer_address: Address,
+) -> Result<u64, EthClientError> {
+    _call_u64_variable(
+        client,
+        b"lastFullyVerifiedBatch()",
+        on_chain_proposer_address,
+    )
+    .await
+}
+
+pub async fn get_min_proofs_to_verify(
+    client: &EthClient,
+    on_chain_proposer_address: Address,
+) -> Result<u64, EthClientError> {
+    _call_u64_variable(client, b"MIN_PROOFS_TO_VERIFY()", on_chain_proposer_address).await
+}
+
+pub async fn get_immediate_proofs(
+    client: &EthClient,
+    on_chain_proposer_address: Address,
+) -> Result<u64, EthClientError> {
+    _call_u64_variable(client, b"IMMEDIATE_PROOFS()", on_chain_proposer_address).await
+}
+
+pub async fn get_proof_upgrade_window(
+    client: &EthClient,
+    on_chain_proposer_address: Address,
+) -> Result<u64, EthClientError> {
+    _call_u64_variable(client, b"PROOF_UPGRADE_WINDOW()", on_chain_proposer_address).await
+}
+
 pub async fn get_sp1_vk(
     client: &EthClient,
     on_chain_proposer_address: Address,
@@ -1,4 +1,5 @@
 mod payload_builder;
+mod preconfirmations;
 use std::{
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -13,8 +14,13 @@ use ethrex_blockchain::{
     validate_block,
 };
 use ethrex_common::H256;
-use ethrex_common::{Address, U256};
+use ethrex_common::{
+    Address, U256,
+    types::{AccountUpdate, Block, BlockHeader, fee_config::FeeConfig},
+};
+use ethrex_l2_rpc::{l2::preconfirmations::PreconfirmationFeed, signer::Signer};
 use ethrex_l2_sdk::calldata::encode_calldata;
+use ethrex_p2p::rlpx::l2::messages::block_signing_hash;
 use ethrex_rpc::{
     EthClient,
     clients::{EthClientError, Overrides},
@@ -23,6 +29,7 @@ use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
 use ethrex_vm::BlockExecutionResult;
 pub use payload_builder::build_payload;
+pub use preconfirmations::PreconfirmationPublisher;
 use reqwest::Url;
 use serde::Serialize;
 use spawned_concurrency::tasks::{
@@ -37,6 +44,7 @@ use crate::{
 use std::str::FromStr;
 
 use super::errors::BlockProducerError;
+use super::leader_election::LeaseFence;
 
 use ethrex_metrics::metrics;
 #[cfg(feature = "metrics")]
@@ -72,6 +80,11 @@ pub struct BlockProducer {
     block_gas_limit: u64,
     eth_client: EthClient,
     router_address: Address,
+    preconfirmations: Option<PreconfirmationPublisher>,
+    /// Signs produced blocks so the other sequencers accept them over p2p.
+    block_signer: Option<Signer>,
+    /// Fences produced blocks with the leader lease when running in high availability mode.
+    lease_fence: Option<LeaseFence>,
 }
 
 #[derive(Clone, Serialize)]
@@ -83,6 +96,7 @@ pub struct BlockProducerHealth {
 }
 
 impl BlockProducer {
+    #[expect(clippy::too_many_arguments)]
     pub fn new(
         config: &BlockProducerConfig,
         l1_rpc_url: Vec<Url>,
@@ -91,6 +105,10 @@ impl BlockProducer {
         blockchain: Arc<Blockchain>,
         sequencer_state: SequencerState,
         router_address: Address,
+        preconfirmation_feed: Option<PreconfirmationFeed>,
+        signer: Signer,
+        sign_blocks: bool,
+        lease_fence: Option<LeaseFence>,
     ) -> Result<Self, EthClientError> {
         let BlockProducerConfig {
             block_time_ms,
@@ -99,6 +117,7 @@ impl BlockProducer {
             operator_fee_vault_address,
             elasticity_multiplier,
             block_gas_limit,
+            flashblock_interval_ms,
         } = config;
 
         let eth_client = EthClient::new_with_multiple_urls(l1_rpc_url)?;
@@ -117,6 +136,16 @@ impl BlockProducer {
             );
         }
 
+        let block_signer = sign_blocks.then(|| signer.clone());
+        let preconfirmations = preconfirmation_feed.map(|feed| {
+            PreconfirmationPublisher::new(
+                feed,
+                signer,
+                store.get_chain_config().chain_id,
+                Duration::from_millis(*flashblock_interval_ms),
+            )
+        });
+
         Ok(Self {
             store,
             blockchain,
@@ -130,9 +159,13 @@ impl BlockProducer {
             block_gas_limit: *block_gas_limit,
             eth_client,
             router_address,
+            preconfirmations,
+            block_signer,
+            lease_fence,
         })
     }
 
+    #[expect(clippy::too_many_arguments)]
     pub async fn spawn(
         store: Store,
         rollup_store: StoreRollup,
@@ -140,6 +173,8 @@ impl BlockProducer {
         cfg: SequencerConfig,
         sequencer_state: SequencerState,
         router_address: Address,
+        preconfirmation_feed: Option<PreconfirmationFeed>,
+        lease_fence: Option<LeaseFence>,
     ) -> Result<GenServerHandle<BlockProducer>, BlockProducerError> {
         let mut block_producer = Self::new(
             &cfg.block_producer,
@@ -149,6 +184,11 @@ impl BlockProducer {
             blockchain,
             sequencer_state,
             router_address,
+            preconfirmation_feed,
+            // Preconfirmations and blocks are signed with the same key the sequencer commits batches with
+            cfg.l1_committer.signer,
+            cfg.based.enabled || cfg.high_availability.enabled,
+            lease_fence,
         )?
         .start_blocking();
         block_producer
@@ -199,6 +239,7 @@ impl BlockProducer {
             &mut self.privileged_nonces,
             self.block_gas_limit,
             registered_chains,
+            self.preconfirmations.as_mut(),
         )
         .await?;
         info!(
@@ -208,32 +249,30 @@ impl BlockProducer {
 
         // Blockchain stores block
         let block = payload_build_result.payload;
-        let chain_config = self.store.get_chain_config();
-        validate_block(
-            &block,
-            &head_header,
-            &chain_config,
-            self.elasticity_multiplier,
-        )?;
-
+        let transactions_count = block.body.transactions.len();
+        let block_number = block.header.number;
+        let block_hash = block.hash();
         let account_updates = payload_build_result.account_updates;
-
         let execution_result = BlockExecutionResult {
             receipts: payload_build_result.receipts,
             requests: Vec::new(),
+            block_access_list: None,
+            transaction_addresses: payload_build_result.transaction_addresses,
         };
-
-        let account_updates_list = self
-            .store
-            .apply_account_updates_batch(block.header.parent_hash, &account_updates)?
-            .ok_or(ChainError::ParentStateNotFound)?;
-
-        let transactions_count = block.body.transactions.len();
-        let block_number = block.header.number;
-        let block_hash = block.hash();
-        self.store_fee_config_by_block(block.header.number).await?;
-        self.blockchain
-            .store_block(block, account_updates_list, execution_result)?;
+        let stored = self
+            .store_built_block(block, &head_header, &account_updates, execution_result)
+            .await;
+        // The block's preconfirmations were published while building it, so
+        // subscribers must learn that it won't be sealed
+        if !matches!(stored, Ok(true))
+            && let Some(publisher) = self.preconfirmations.as_mut()
+            && let Err(e) = publisher.revoke_block().await
+        {
+            error!("Failed to revoke the preconfirmations of block {block_number}: {e}");
+        }
+        if !stored? {
+            return Ok(());
+        }
         info!(
             "Stored new block {:x}, transaction_count {}",
             block_hash, transactions_count
@@ -256,7 +295,62 @@ impl BlockProducer {
 
         Ok(())
     }
-    async fn store_fee_config_by_block(&self, block_number: u64) -> Result<(), BlockProducerError> {
+    /// Validates and stores a block built by this sequencer. Returns whether it
+    /// was stored, as it's discarded if the leader lease was lost meanwhile.
+    async fn store_built_block(
+        &mut self,
+        block: Block,
+        head_header: &BlockHeader,
+        account_updates: &[AccountUpdate],
+        execution_result: BlockExecutionResult,
+    ) -> Result<bool, BlockProducerError> {
+        let chain_config = self.store.get_chain_config();
+        validate_block(
+            &block,
+            head_header,
+            &chain_config,
+            self.elasticity_multiplier,
+        )?;
+
+        let account_updates_list = self
+            .store
+            .apply_account_updates_batch(block.header.parent_hash, account_updates)?
+            .ok_or(ChainError::ParentStateNotFound)?;
+
+        let block_number = block.header.number;
+        let block_hash = block.hash();
+        // Standbys only take over once they have every block recorded, and a leader that lost
+        // the lease can't record any more
+        if let Some(fence) = &self.lease_fence
+            && !fence.record_block(block_number)?
+        {
+            warn!("Leader lease was lost, discarding block {block_number}");
+            self.sequencer_state
+                .new_status(SequencerStatus::Following)
+                .await;
+            return Ok(false);
+        }
+        let fee_config = self.store_fee_config_by_block(block_number).await?;
+        // The signature must be stored before the block so it is never gossiped without it
+        if let Some(signer) = &self.block_signer {
+            let signing_hash = block_signing_hash(block_hash, Some(&fee_config));
+            let signature = signer
+                .sign(Bytes::copy_from_slice(signing_hash.as_bytes()))
+                .await?;
+            self.rollup_store
+                .store_signature_by_block(block_hash, signature)
+                .await?;
+        }
+        self.blockchain
+            .store_block(block, account_updates_list, execution_result)?;
+        Ok(true)
+    }
+
+    /// Stores the fee config the block was built with and returns it.
+    async fn store_fee_config_by_block(
+        &self,
+        block_number: u64,
+    ) -> Result<FeeConfig, BlockProducerError> {
         let BlockchainType::L2(l2_config) = &self.blockchain.options.r#type else {
             error!("Invalid blockchain type. Expected L2.");
             return Err(BlockProducerError::Custom("Invalid blockchain type".into()));
@@ -270,7 +364,7 @@ impl BlockProducer {
         self.rollup_store
             .store_fee_config_by_block(block_number, fee_config)
             .await?;
-        Ok(())
+        Ok(fee_config)
     }
 
     async fn get_registered_l2_chain_ids(&self) -> Result<Vec<U256>, BlockProducerError> {
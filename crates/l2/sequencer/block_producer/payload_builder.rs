@@ -1,4 +1,6 @@
-use crate::sequencer::errors::BlockProducerError;
+use crate::sequencer::{
+    block_producer::preconfirmations::PreconfirmationPublisher, errors::BlockProducerError,
+};
 use ethrex_blockchain::{
     Blockchain,
     constants::TX_GAS_COST,
@@ -20,6 +22,7 @@ use ethrex_metrics::{
 };
 use ethrex_rlp::encode::RLPEncode;
 use ethrex_storage::Store;
+use ethrex_vm::backends::levm::block_access_index;
 use std::sync::Arc;
 use std::{collections::HashMap, ops::Div};
 use tokio::time::Instant;
@@ -35,12 +38,17 @@ pub async fn build_payload(
     privileged_nonces: &mut HashMap<u64, Option<u64>>,
     block_gas_limit: u64,
     registered_chains: Vec<U256>,
+    preconfirmations: Option<&mut PreconfirmationPublisher>,
 ) -> Result<PayloadBuildResult, BlockProducerError> {
     let since = Instant::now();
     let gas_limit = payload.header.gas_limit;
 
     debug!("Building payload");
     let mut context = PayloadBuildContext::new(payload, store, &blockchain.options.r#type)?;
+    context.vm.db.checkpoint_block_access_list(0);
+    if blockchain.options.index_addresses {
+        context.vm.db.enable_address_index();
+    }
 
     fill_transactions(
         blockchain.clone(),
@@ -49,6 +57,7 @@ pub async fn build_payload(
         privileged_nonces,
         block_gas_limit,
         registered_chains,
+        preconfirmations,
     )
     .await?;
     blockchain.finalize_payload(&mut context)?;
@@ -93,6 +102,8 @@ pub async fn build_payload(
 /// does not exceed `SAFE_BYTES_PER_BLOB`.
 /// Also, uses a configured `block_gas_limit` to limit the gas used in the block,
 /// which can be lower than the block gas limit specified in the payload header.
+/// If a `PreconfirmationPublisher` is given, every included transaction is preconfirmed, and
+/// the caller must revoke them if the block isn't sealed.
 pub async fn fill_transactions(
     blockchain: Arc<Blockchain>,
     context: &mut PayloadBuildContext,
@@ -100,6 +111,7 @@ pub async fn fill_transactions(
     privileged_nonces: &mut HashMap<u64, Option<u64>>,
     configured_block_gas_limit: u64,
     registered_chains: Vec<U256>,
+    mut preconfirmations: Option<&mut PreconfirmationPublisher>,
 ) -> Result<(), BlockProducerError> {
     let mut privileged_tx_count = 0;
     let VMType::L2(fee_config) = context.vm.vm_type else {
@@ -115,6 +127,10 @@ pub async fn fill_transactions(
     let latest_block_number = store.get_latest_block_number().await?;
     let mut txs = fetch_mempool_transactions(blockchain.as_ref(), context)?;
 
+    if let Some(publisher) = preconfirmations.as_deref_mut() {
+        publisher.start_block(context.block_number(), context.payload.header.parent_hash);
+    }
+
     // Execute and add transactions to payload (if suitable)
     loop {
         // Check if we have enough gas to run more transactions
@@ -217,6 +233,7 @@ pub async fn fill_transactions(
             Err(e) => {
                 debug!("Failed to execute transaction: {}, {e}", tx_hash);
                 metrics!(METRICS_TX.inc_tx_errors(e.to_metric()));
+                context.vm.db.discard_pending_block_access_list();
                 // Ignore following txs from sender
                 txs.pop();
                 continue;
@@ -229,6 +246,7 @@ pub async fn fill_transactions(
             if !registered_chains.contains(&msg.dest_chain_id) {
                 txs.pop();
                 context.vm.undo_last_tx()?;
+                context.vm.db.discard_pending_block_access_list();
                 context.remaining_gas = previous_remaining_gas;
                 context.block_value = previous_block_value;
                 found_invalid_message = true;
@@ -253,13 +271,30 @@ pub async fn fill_transactions(
         // Pull transaction from the mempool
         blockchain.remove_transaction_from_pool(&head_tx.tx.hash())?;
 
+        if let Some(publisher) = preconfirmations.as_deref_mut() {
+            let tx_index = u64::try_from(context.payload.body.transactions.len())?;
+            publisher
+                .on_transaction_included(&tx, tx_index, context.gas_used())
+                .await;
+        }
+
         // Add transaction to block
         context.payload.body.transactions.push(tx);
+        context
+            .vm
+            .db
+            .checkpoint_block_access_list(block_access_index(
+                context.payload.body.transactions.len(),
+            )?);
 
         // Save receipt for hash calculation
         context.receipts.push(receipt);
     } // end loop
 
+    if let Some(publisher) = preconfirmations {
+        publisher.flush(context.gas_used()).await;
+    }
+
     metrics!(
         context
             .payload
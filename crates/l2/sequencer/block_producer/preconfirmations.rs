@@ -0,0 +1,318 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+use ethrex_common::{H256, types::Transaction};
+use ethrex_l2_common::preconfirmations::{
+    BlockRevocation, Flashblock, Preconfirmation, SignedBlockRevocation, SignedPreconfirmation,
+};
+use ethrex_l2_rpc::{l2::preconfirmations::PreconfirmationFeed, signer::Signer};
+use futures::future::join_all;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::sequencer::errors::BlockProducerError;
+
+/// Signs a preconfirmation for every transaction the payload builder includes
+/// and publishes them, grouped in flashblocks, through the `PreconfirmationFeed`.
+///
+/// Flashblocks are published before the block is sealed, so if the block
+/// producer discards it afterwards it must call `revoke_block` to void them.
+pub struct PreconfirmationPublisher {
+    feed: PreconfirmationFeed,
+    signer: Signer,
+    chain_id: u64,
+    flashblock_interval: Duration,
+    // State of the block being built
+    block_number: u64,
+    parent_hash: H256,
+    next_index: u64,
+    transactions: Vec<Bytes>,
+    pending: Vec<Preconfirmation>,
+    last_flush: Instant,
+}
+
+impl PreconfirmationPublisher {
+    pub fn new(
+        feed: PreconfirmationFeed,
+        signer: Signer,
+        chain_id: u64,
+        flashblock_interval: Duration,
+    ) -> Self {
+        Self {
+            feed,
+            signer,
+            chain_id,
+            flashblock_interval,
+            block_number: 0,
+            parent_hash: H256::zero(),
+            next_index: 0,
+            transactions: Vec::new(),
+            pending: Vec::new(),
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Resets the publisher state for a new block built on top of `parent_hash`.
+    pub fn start_block(&mut self, block_number: u64, parent_hash: H256) {
+        self.block_number = block_number;
+        self.parent_hash = parent_hash;
+        self.next_index = 0;
+        self.transactions.clear();
+        self.pending.clear();
+        self.last_flush = Instant::now();
+    }
+
+    /// Records a commitment to include `tx` at `tx_index` and publishes a
+    /// flashblock if the flashblock interval has elapsed.
+    pub async fn on_transaction_included(
+        &mut self,
+        tx: &Transaction,
+        tx_index: u64,
+        cumulative_gas_used: u64,
+    ) {
+        self.pending.push(Preconfirmation {
+            chain_id: self.chain_id,
+            block_number: self.block_number,
+            parent_hash: self.parent_hash,
+            tx_index,
+            tx_hash: tx.hash(),
+        });
+        self.transactions
+            .push(Bytes::from(tx.encode_canonical_to_vec()));
+
+        if self.last_flush.elapsed() >= self.flashblock_interval {
+            self.flush(cumulative_gas_used).await;
+        }
+    }
+
+    /// Signs the preconfirmations recorded since the last flashblock, all at
+    /// once so a remote signer isn't waited on once per transaction, and
+    /// publishes them, if any.
+    /// The transactions are already in the block, so one that fails to be
+    /// signed is published without its preconfirmation.
+    pub async fn flush(&mut self, cumulative_gas_used: u64) {
+        self.last_flush = Instant::now();
+        if self.pending.is_empty() {
+            return;
+        }
+        let pending = std::mem::take(&mut self.pending);
+        let signatures = join_all(
+            pending
+                .iter()
+                .map(|preconfirmation| self.signer.sign(Bytes::from(preconfirmation.encode()))),
+        )
+        .await;
+        let preconfirmations = pending
+            .into_iter()
+            .zip(signatures)
+            .filter_map(|(preconfirmation, signature)| match signature {
+                Ok(signature) => Some(SignedPreconfirmation {
+                    preconfirmation,
+                    signature,
+                }),
+                Err(e) => {
+                    warn!(
+                        "Failed to preconfirm transaction {:#x}: {e}",
+                        preconfirmation.tx_hash
+                    );
+                    None
+                }
+            })
+            .collect();
+
+        let flashblock = Flashblock {
+            block_number: self.block_number,
+            parent_hash: self.parent_hash,
+            index: self.next_index,
+            transactions: std::mem::take(&mut self.transactions),
+            preconfirmations,
+            cumulative_gas_used,
+        };
+        debug!(
+            "Publishing flashblock {} of block {} with {} transactions",
+            flashblock.index,
+            flashblock.block_number,
+            flashblock.transactions.len()
+        );
+        self.next_index += 1;
+        self.feed.publish(flashblock);
+    }
+
+    /// Voids the flashblocks published for the block being built, which won't be sealed.
+    pub async fn revoke_block(&mut self) -> Result<(), BlockProducerError> {
+        self.transactions.clear();
+        self.pending.clear();
+        if self.next_index == 0 {
+            return Ok(());
+        }
+        self.next_index = 0;
+        let revocation = BlockRevocation {
+            chain_id: self.chain_id,
+            block_number: self.block_number,
+            parent_hash: self.parent_hash,
+        };
+        let signature = self.signer.sign(Bytes::from(revocation.encode())).await?;
+        warn!(
+            "Revoking the preconfirmations of discarded block {}",
+            self.block_number
+        );
+        self.feed.revoke(SignedBlockRevocation {
+            revocation,
+            signature,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, clippy::indexing_slicing)]
+mod tests {
+    use ethrex_common::{
+        Address,
+        types::{EIP1559Transaction, TxKind},
+    };
+    use ethrex_l2_common::preconfirmations::{FlashblocksMessage, PreconfirmationError};
+    use ethrex_l2_rpc::signer::LocalSigner;
+    use secp256k1::SecretKey;
+    use tokio::sync::broadcast::Receiver;
+
+    use super::*;
+
+    const CHAIN_ID: u64 = 65536999;
+
+    fn signer(key: u8) -> Signer {
+        Signer::Local(LocalSigner::new(SecretKey::from_slice(&[key; 32]).unwrap()))
+    }
+
+    fn publisher(signer: Signer) -> (PreconfirmationPublisher, Receiver<FlashblocksMessage>) {
+        let feed = PreconfirmationFeed::new(signer.address());
+        let receiver = feed.subscribe();
+        let publisher =
+            PreconfirmationPublisher::new(feed, signer, CHAIN_ID, Duration::from_secs(3600));
+        (publisher, receiver)
+    }
+
+    fn transaction(nonce: u64) -> Transaction {
+        Transaction::EIP1559Transaction(EIP1559Transaction {
+            chain_id: CHAIN_ID,
+            nonce,
+            to: TxKind::Call(Address::repeat_byte(1)),
+            ..Default::default()
+        })
+    }
+
+    async fn publish_block(publisher: &mut PreconfirmationPublisher, txs: &[Transaction]) {
+        publisher.start_block(7, H256::repeat_byte(2));
+        let mut gas_used = 0;
+        for (index, tx) in (0..).zip(txs) {
+            gas_used += 21_000;
+            publisher.on_transaction_included(tx, index, gas_used).await;
+        }
+        publisher.flush(gas_used).await;
+    }
+
+    fn flashblock(receiver: &mut Receiver<FlashblocksMessage>) -> Flashblock {
+        match receiver.try_recv().unwrap() {
+            FlashblocksMessage::Flashblock(flashblock) => flashblock,
+            other => panic!("expected a flashblock, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn flashblock_preconfirmations_are_signed_by_the_sequencer() {
+        let sequencer = signer(1);
+        let sequencer_address = sequencer.address();
+        let (mut publisher, mut receiver) = publisher(sequencer);
+        let txs = [transaction(0), transaction(1)];
+        publish_block(&mut publisher, &txs).await;
+
+        let flashblock = flashblock(&mut receiver);
+        assert_eq!(flashblock.index, 0);
+        assert_eq!(flashblock.transactions.len(), 2);
+        assert_eq!(flashblock.cumulative_gas_used, 42_000);
+        flashblock.verify(sequencer_address).unwrap();
+        for (signed, tx) in flashblock.preconfirmations.iter().zip(&txs) {
+            assert_eq!(signed.preconfirmation.tx_hash, tx.hash());
+            assert_eq!(signed.recover_signer().unwrap(), sequencer_address);
+        }
+        assert_eq!(
+            publisher.feed.get(&txs[1].hash()),
+            Some(flashblock.preconfirmations[1].clone())
+        );
+    }
+
+    #[tokio::test]
+    async fn preconfirmations_from_other_signers_are_rejected() {
+        let (mut publisher, mut receiver) = publisher(signer(1));
+        publish_block(&mut publisher, &[transaction(0)]).await;
+
+        let flashblock = flashblock(&mut receiver);
+        let impostor = signer(2).address();
+        assert!(matches!(
+            flashblock.verify(impostor),
+            Err(PreconfirmationError::InvalidSigner { expected, .. }) if expected == impostor
+        ));
+    }
+
+    #[tokio::test]
+    async fn tampered_preconfirmations_are_rejected() {
+        let sequencer = signer(1);
+        let sequencer_address = sequencer.address();
+        let (mut publisher, mut receiver) = publisher(sequencer);
+        publish_block(&mut publisher, &[transaction(0)]).await;
+
+        let mut flashblock = flashblock(&mut receiver);
+        let mut signed = flashblock.preconfirmations[0].clone();
+        signed.preconfirmation.tx_index += 1;
+        assert!(signed.verify(sequencer_address).is_err());
+
+        // A valid preconfirmation for another block doesn't belong in the flashblock
+        flashblock.block_number += 1;
+        assert!(matches!(
+            flashblock.verify(sequencer_address),
+            Err(PreconfirmationError::BlockMismatch(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn discarded_blocks_are_revoked() {
+        let sequencer = signer(1);
+        let sequencer_address = sequencer.address();
+        let (mut publisher, mut receiver) = publisher(sequencer);
+        let tx = transaction(0);
+        publish_block(&mut publisher, std::slice::from_ref(&tx)).await;
+        let flashblock = flashblock(&mut receiver);
+
+        publisher.revoke_block().await.unwrap();
+
+        let FlashblocksMessage::Revocation(revocation) = receiver.try_recv().unwrap() else {
+            panic!("expected a revocation");
+        };
+        revocation.verify(sequencer_address).unwrap();
+        assert!(revocation.verify(signer(2).address()).is_err());
+        assert!(
+            revocation
+                .revocation
+                .revokes(&flashblock.preconfirmations[0].preconfirmation)
+        );
+        assert_eq!(publisher.feed.get(&tx.hash()), None);
+
+        // Nothing is left to revoke
+        publisher.revoke_block().await.unwrap();
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn unpublished_blocks_are_not_revoked() {
+        let (mut publisher, mut receiver) = publisher(signer(1));
+        publisher.start_block(7, H256::repeat_byte(2));
+        publisher
+            .on_transaction_included(&transaction(0), 0, 21_000)
+            .await;
+
+        publisher.revoke_block().await.unwrap();
+        publisher.flush(21_000).await;
+
+        assert!(receiver.try_recv().is_err());
+    }
+}
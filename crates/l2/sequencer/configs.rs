@@ -3,7 +3,7 @@ use ethrex_common::{Address, U256};
 use ethrex_l2_rpc::signer::Signer;
 use reqwest::Url;
 use secp256k1::SecretKey;
-use std::net::IpAddr;
+use std::{net::IpAddr, path::PathBuf};
 
 #[derive(Clone, Debug)]
 pub struct SequencerConfig {
@@ -16,6 +16,7 @@ pub struct SequencerConfig {
     pub aligned: AlignedConfig,
     pub monitor: MonitorConfig,
     pub admin_server: AdminConfig,
+    pub high_availability: HighAvailabilityConfig,
 }
 
 // TODO: Move to blockchain/dev
@@ -27,6 +28,8 @@ pub struct BlockProducerConfig {
     pub operator_fee_vault_address: Option<Address>,
     pub elasticity_multiplier: u64,
     pub block_gas_limit: u64,
+    /// Max time in ms between two flashblocks of the block being built.
+    pub flashblock_interval_ms: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -73,6 +76,12 @@ pub struct ProofCoordinatorConfig {
     pub validium: bool,
     pub tdx_private_key: Option<SecretKey>,
     pub qpl_tool_path: Option<String>,
+    /// Whether batch proofs are aggregated and verified together with `verifyBatches`.
+    pub aggregation: bool,
+    /// Maximum amount of batches covered by a single aggregated proof.
+    pub aggregation_size: u64,
+    /// How long a range shorter than `aggregation_size` waits for more batches before being aggregated.
+    pub aggregation_timeout_ms: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -117,3 +126,14 @@ pub struct AdminConfig {
     pub listen_ip: IpAddr,
     pub listen_port: u16,
 }
+
+#[derive(Clone, Debug)]
+pub struct HighAvailabilityConfig {
+    pub enabled: bool,
+    /// Identifies this sequencer among the ones sharing the lease.
+    pub node_id: String,
+    /// File holding the leader lease, shared by every sequencer of the cluster.
+    pub lease_file: PathBuf,
+    /// Time in ms a standby waits since the lease last changed before taking it over.
+    pub lease_ttl_ms: u64,
+}
@@ -1,6 +1,7 @@
 use crate::based::block_fetcher::BlockFetcherError;
 use crate::based::state_updater::StateUpdaterError;
 use crate::sequencer::admin_server::AdminError;
+use crate::sequencer::leader_election::LeaderElectionError;
 use crate::utils::error::UtilsError;
 use aligned_sdk::common::errors::SubmitError;
 use ethereum_types::FromStrRadixErr;
@@ -117,6 +118,8 @@ pub enum ProofCoordinatorError {
     MissingBlob(u64),
     #[error("Missing TDX private key")]
     MissingTDXPrivateKey,
+    #[error("Aggregated proof for batches {0} to {1} doesn't match the range handed out")]
+    UnexpectedAggregatedRange(u64, u64),
     #[error("Metrics error")]
     Metrics(#[from] MetricsError),
     #[error("Missing prover input for batch {0} (version {1})")]
@@ -228,6 +231,10 @@ pub enum BlockProducerError {
     EthClientError(#[from] EthClientError),
     #[error("Failed to encode calldata: {0}")]
     CalldataEncodeError(#[from] CalldataEncodeError),
+    #[error("Block Producer failed to sign: {0}")]
+    SignerError(#[from] SignerError),
+    #[error("Block Producer failed to record the block in the leader lease: {0}")]
+    LeaderElection(#[from] LeaderElectionError),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -43,6 +43,7 @@ use ethrex_l2_sdk::{
 #[cfg(feature = "metrics")]
 use ethrex_metrics::l2::metrics::{METRICS, MetricsBlockType};
 use ethrex_metrics::metrics;
+use ethrex_p2p::rlpx::l2::messages::batch_hash;
 use ethrex_rlp::encode::RLPEncode;
 use ethrex_rpc::{
     clients::eth::{EthClient, Overrides},
@@ -110,6 +111,8 @@ pub struct L1Committer {
     validium: bool,
     signer: Signer,
     based: bool,
+    /// Whether sealed batches are signed so they can be gossiped to the other sequencers.
+    sign_batches: bool,
     sequencer_state: SequencerState,
     /// Time to wait before checking if it should send a new batch
     committer_wake_up_ms: u64,
@@ -164,6 +167,7 @@ impl L1Committer {
         store: Store,
         rollup_store: StoreRollup,
         based: bool,
+        sign_batches: bool,
         sequencer_state: SequencerState,
         genesis: Genesis,
         checkpoints_dir: PathBuf,
@@ -201,6 +205,7 @@ impl L1Committer {
             validium: committer_config.validium,
             signer: committer_config.signer.clone(),
             based,
+            sign_batches,
             sequencer_state,
             committer_wake_up_ms: committer_config
                 .commit_time_ms
@@ -234,6 +239,7 @@ impl L1Committer {
             store.clone(),
             rollup_store.clone(),
             cfg.based.enabled,
+            cfg.based.enabled || cfg.high_availability.enabled,
             sequencer_state,
             genesis,
             checkpoints_dir,
@@ -568,6 +574,16 @@ impl L1Committer {
             .seal_batch_with_prover_input(batch.clone(), &self.git_commit_hash, batch_prover_input)
             .await?;
 
+        if self.sign_batches {
+            let signature = self
+                .signer
+                .sign(Bytes::copy_from_slice(batch_hash(&batch).as_bytes()))
+                .await?;
+            self.rollup_store
+                .store_signature_by_batch(batch.number, signature)
+                .await?;
+        }
+
         // Create the next checkpoint from the one-time checkpoint used
         let new_checkpoint_path = self
             .checkpoints_dir
@@ -736,6 +752,8 @@ impl L1Committer {
                     BlockExecutionResult {
                         receipts,
                         requests: vec![],
+                        block_access_list: None,
+                        transaction_addresses: None,
                     },
                 )?;
             } else {
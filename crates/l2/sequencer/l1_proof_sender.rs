@@ -10,7 +10,10 @@ use ethrex_l2_common::{
     prover::{BatchProof, ProverType},
 };
 use ethrex_l2_rpc::signer::{Signer, SignerHealth};
-use ethrex_l2_sdk::{calldata::encode_calldata, get_last_committed_batch, get_last_verified_batch};
+use ethrex_l2_sdk::{
+    calldata::encode_calldata, get_last_committed_batch, get_last_fully_verified_batch,
+    get_last_verified_batch,
+};
 #[cfg(feature = "metrics")]
 use ethrex_metrics::l2::metrics::METRICS;
 use ethrex_metrics::metrics;
@@ -24,11 +27,11 @@ use serde::Serialize;
 use spawned_concurrency::tasks::{
     CallResponse, CastResponse, GenServer, GenServerHandle, send_after,
 };
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 use super::{
     configs::AlignedConfig,
-    utils::{random_duration, send_verify_tx},
+    utils::{get_proof_policy, random_duration, send_verify_tx},
 };
 
 use crate::{
@@ -47,6 +50,8 @@ use aligned_sdk::{
 use ethers::signers::{Signer as EthersSigner, Wallet};
 
 const VERIFY_FUNCTION_SIGNATURE: &str = "verifyBatch(uint256,bytes,bytes,bytes,bytes,bytes,bytes)";
+const VERIFY_AGGREGATED_FUNCTION_SIGNATURE: &str =
+    "verifyBatches(uint256,bytes[],bytes,bytes,bytes[])";
 
 #[derive(Clone)]
 pub enum InMessage {
@@ -78,6 +83,7 @@ pub struct L1ProofSender {
     /// Directory where checkpoints are stored.
     checkpoints_dir: PathBuf,
     aligned_mode: bool,
+    aggregation: bool,
 }
 
 #[derive(Clone, Serialize)]
@@ -133,6 +139,7 @@ impl L1ProofSender {
             fee_estimate,
             checkpoints_dir,
             aligned_mode: aligned_cfg.aligned_mode,
+            aggregation: cfg.aggregation,
         })
     }
 
@@ -166,19 +173,64 @@ impl L1ProofSender {
         let last_verified_batch =
             get_last_verified_batch(&self.eth_client, self.on_chain_proposer_address).await?;
         let latest_sent_batch_db = self.rollup_store.get_latest_sent_batch_proof().await?;
-        let batch_to_send = if self.aligned_mode {
-            std::cmp::max(latest_sent_batch_db, last_verified_batch) + 1
-        } else {
+
+        if self.aligned_mode {
+            let batch_to_send = std::cmp::max(latest_sent_batch_db, last_verified_batch) + 1;
+            return self.send_all_proofs(batch_to_send).await;
+        }
+
+        if self.aggregation {
             if latest_sent_batch_db < last_verified_batch {
-                // hotfix: in case the latest sent batch in DB is less than the last verified on-chain,
-                // we update the db to avoid stalling the proof_coordinator.
                 self.rollup_store
                     .set_latest_sent_batch_proof(last_verified_batch)
                     .await?;
             }
-            last_verified_batch + 1
+            let last_committed_batch =
+                get_last_committed_batch(&self.eth_client, self.on_chain_proposer_address).await?;
+            if last_committed_batch <= last_verified_batch {
+                info!(
+                    "Next batch to send ({}) is not yet committed",
+                    last_verified_batch + 1
+                );
+                return Ok(());
+            }
+            return self
+                .send_aggregated_proofs(last_verified_batch + 1, last_committed_batch)
+                .await;
+        }
+
+        let policy = get_proof_policy(
+            &self.eth_client,
+            self.on_chain_proposer_address,
+            &self.needed_proof_types,
+        )
+        .await;
+
+        // Batches after the last fully verified one were verified with only some of their proofs.
+        let last_fully_verified_batch = if policy.is_optimistic(&self.needed_proof_types) {
+            get_last_fully_verified_batch(&self.eth_client, self.on_chain_proposer_address).await?
+        } else {
+            last_verified_batch
         };
 
+        if latest_sent_batch_db < last_fully_verified_batch {
+            // The latest sent batch in the DB is the last one that got all of its proofs on-chain.
+            // We update it to avoid stalling the proof_coordinator, and remove the checkpoints
+            // that are no longer needed to generate proofs.
+            self.rollup_store
+                .set_latest_sent_batch_proof(last_fully_verified_batch)
+                .await?;
+            for batch_number in latest_sent_batch_db..last_fully_verified_batch {
+                self.remove_checkpoint(batch_number);
+            }
+        }
+
+        if last_fully_verified_batch < last_verified_batch {
+            self.send_remaining_proofs(last_fully_verified_batch + 1)
+                .await?;
+        }
+
+        let batch_to_send = last_verified_batch + 1;
         let last_committed_batch =
             get_last_committed_batch(&self.eth_client, self.on_chain_proposer_address).await?;
 
@@ -187,56 +239,309 @@ impl L1ProofSender {
             return Ok(());
         }
 
+        let (proofs, missing_proof_types) = self.get_batch_proofs(batch_to_send).await?;
+
+        if !policy.can_verify(proofs.keys()) {
+            info!(
+                ?missing_proof_types,
+                ?batch_to_send,
+                "Missing batch proof(s), will not send",
+            );
+            return Ok(());
+        }
+
+        let sent_proof_types: Vec<ProverType> = proofs.keys().copied().collect();
+        self.send_proof_to_contract(batch_to_send, proofs).await?;
+        self.rollup_store
+            .store_sent_proof_types_by_batch(batch_to_send, sent_proof_types)
+            .await?;
+
+        // Without an upgrade window the remaining proofs are never needed.
+        let fully_verified = missing_proof_types.is_empty() || policy.upgrade_window == 0;
+        if fully_verified && last_fully_verified_batch == last_verified_batch {
+            self.rollup_store
+                .set_latest_sent_batch_proof(batch_to_send)
+                .await?;
+
+            // Remove checkpoint from batch sent - 1.
+            // That checkpoint was needed to generate the proof for the batch we just sent.
+            // The checkpoint for the batch we have just sent is needed for the next batch.
+            self.remove_checkpoint(batch_to_send - 1);
+        } else {
+            info!(
+                ?missing_proof_types,
+                ?batch_to_send,
+                "Batch verified optimistically, the missing proof(s) will be sent when ready",
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Sends the batch proofs to Aligned once all the needed ones are ready.
+    async fn send_all_proofs(&self, batch_to_send: u64) -> Result<(), ProofSenderError> {
+        let last_committed_batch =
+            get_last_committed_batch(&self.eth_client, self.on_chain_proposer_address).await?;
+
+        if last_committed_batch < batch_to_send {
+            info!("Next batch to send ({batch_to_send}) is not yet committed");
+            return Ok(());
+        }
+
+        let (proofs, missing_proof_types) = self.get_batch_proofs(batch_to_send).await?;
+
+        if missing_proof_types.is_empty() {
+            self.send_proof_to_aligned(batch_to_send, proofs.values())
+                .await?;
+            self.rollup_store
+                .set_latest_sent_batch_proof(batch_to_send)
+                .await?;
+
+            // Remove checkpoint from batch sent - 1.
+            // That checkpoint was needed to generate the proof for the batch we just sent.
+            // The checkpoint for the batch we have just sent is needed for the next batch.
+            self.remove_checkpoint(batch_to_send - 1);
+        } else {
+            info!(
+                ?missing_proof_types,
+                ?batch_to_send,
+                "Missing batch proof(s), will not send",
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Sends the proofs that became available for an optimistically verified batch.
+    async fn send_remaining_proofs(&self, batch_number: u64) -> Result<(), ProofSenderError> {
+        let mut sent_proof_types = self
+            .rollup_store
+            .get_sent_proof_types_by_batch(batch_number)
+            .await?;
+        let (mut proofs, _) = self.get_batch_proofs(batch_number).await?;
+        proofs.retain(|proof_type, _| !sent_proof_types.contains(proof_type));
+
+        if proofs.is_empty() {
+            debug!(
+                ?batch_number,
+                "No new proofs for optimistically verified batch"
+            );
+            return Ok(());
+        }
+
+        info!(
+            ?batch_number,
+            new_proof_types = ?proofs.keys().collect::<Vec<_>>(),
+            "Sending remaining proof(s) of optimistically verified batch"
+        );
+        sent_proof_types.extend(proofs.keys().copied());
+        self.send_proof_to_contract(batch_number, proofs).await?;
+        self.rollup_store
+            .store_sent_proof_types_by_batch(batch_number, sent_proof_types)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the available proofs of a batch along with the types of the missing ones.
+    async fn get_batch_proofs(
+        &self,
+        batch_number: u64,
+    ) -> Result<(HashMap<ProverType, BatchProof>, Vec<String>), ProofSenderError> {
         let mut proofs = HashMap::new();
         let mut missing_proof_types = Vec::new();
         for proof_type in &self.needed_proof_types {
             if let Some(proof) = self
                 .rollup_store
-                .get_proof_by_batch_and_type(batch_to_send, *proof_type)
+                .get_proof_by_batch_and_type(batch_number, *proof_type)
                 .await?
             {
                 proofs.insert(*proof_type, proof);
             } else {
-                missing_proof_types.push(proof_type);
+                missing_proof_types.push(format!("{proof_type:?}"));
             }
         }
+        Ok((proofs, missing_proof_types))
+    }
 
-        if missing_proof_types.is_empty() {
-            if self.aligned_mode {
-                self.send_proof_to_aligned(batch_to_send, proofs.values())
-                    .await?;
+    fn remove_checkpoint(&self, batch_number: u64) {
+        let checkpoint_path = self
+            .checkpoints_dir
+            .join(batch_checkpoint_name(batch_number));
+        if checkpoint_path.exists() {
+            let _ = remove_dir_all(&checkpoint_path).inspect_err(|e| {
+                error!(
+                    "Failed to remove checkpoint directory at path {checkpoint_path:?}. Should be removed manually. Error: {e}"
+                )
+            });
+        }
+    }
+
+    /// Verifies the batches covered by the aggregated proofs starting at
+    /// `first_batch` with a single `verifyBatches` transaction.
+    async fn send_aggregated_proofs(
+        &self,
+        first_batch: u64,
+        last_committed_batch: u64,
+    ) -> Result<(), ProofSenderError> {
+        let mut aggregated_proofs = HashMap::new();
+        let mut last_batch = None;
+        for proof_type in &self.needed_proof_types {
+            // TDX attestations are cheap to verify and are sent per batch
+            if *proof_type == ProverType::TDX {
+                continue;
+            }
+            let Some((proof_last_batch, proof)) = self
+                .rollup_store
+                .get_aggregated_proof(first_batch, *proof_type)
+                .await?
+            else {
+                info!(
+                    ?proof_type,
+                    ?first_batch,
+                    "Missing aggregated proof, will not send"
+                );
+                return Ok(());
+            };
+            if last_batch.is_some_and(|last_batch| last_batch != proof_last_batch) {
+                // The proof coordinator aligns the ranges, so this should only happen
+                // if the needed proof types changed in between. Aggregate them again.
+                warn!(
+                    ?first_batch,
+                    "Aggregated proofs cover different batch ranges, deleting them"
+                );
+                for proof_type in &self.needed_proof_types {
+                    self.rollup_store
+                        .delete_aggregated_proof(first_batch, *proof_type)
+                        .await?;
+                }
+                return Ok(());
+            }
+            last_batch = Some(proof_last_batch);
+            aggregated_proofs.insert(*proof_type, proof);
+        }
+
+        let Some(last_batch) = last_batch else {
+            return Err(ProofSenderError::UnexpectedError(
+                "Proof aggregation needs RISC0 or SP1 proofs".to_string(),
+            ));
+        };
+
+        if last_committed_batch < last_batch {
+            info!("Last batch of the aggregated proofs ({last_batch}) is not yet committed");
+            return Ok(());
+        }
+
+        let mut public_inputs_list = Vec::new();
+        let mut tdx_signatures = Vec::new();
+        for batch_number in first_batch..=last_batch {
+            let mut public_inputs = None;
+            for proof_type in &self.needed_proof_types {
+                let Some(proof) = self
+                    .rollup_store
+                    .get_proof_by_batch_and_type(batch_number, *proof_type)
+                    .await?
+                else {
+                    info!(
+                        ?proof_type,
+                        ?batch_number,
+                        "Missing batch proof, will not send aggregated proofs"
+                    );
+                    return Ok(());
+                };
+                if *proof_type == ProverType::TDX {
+                    // bytes calldata publicValues,
+                    // bytes memory signature
+                    let signature = proof.calldata().get(1).cloned().ok_or(
+                        ProofSenderError::UnexpectedError(format!(
+                            "Missing TDX signature for batch {batch_number}"
+                        )),
+                    )?;
+                    tdx_signatures.push(signature);
+                } else if public_inputs.is_none() {
+                    public_inputs = Some(Value::Bytes(proof.public_values().into()));
+                }
+            }
+            public_inputs_list.push(public_inputs.ok_or(ProofSenderError::UnexpectedError(
+                format!("Missing public inputs for batch {batch_number}"),
+            ))?);
+        }
+
+        info!(
+            ?first_batch,
+            ?last_batch,
+            "Sending aggregated batch verification transaction to L1"
+        );
+
+        // bytes memory risc0AggregatedSeal
+        let risc0_proof = aggregated_proofs
+            .get(&ProverType::RISC0)
+            .and_then(|proof| proof.calldata().first().cloned())
+            .unwrap_or(Value::Bytes(vec![].into()));
+        // bytes memory sp1AggregatedProof
+        let sp1_proof = aggregated_proofs
+            .get(&ProverType::SP1)
+            .and_then(|proof| proof.calldata().get(1).cloned())
+            .unwrap_or(Value::Bytes(vec![].into()));
+
+        let calldata_values = vec![
+            Value::Uint(U256::from(first_batch)),
+            Value::Array(public_inputs_list),
+            risc0_proof,
+            sp1_proof,
+            Value::Array(tdx_signatures),
+        ];
+        let calldata = encode_calldata(VERIFY_AGGREGATED_FUNCTION_SIGNATURE, &calldata_values)?;
+
+        let send_verify_tx_result = send_verify_tx(
+            calldata,
+            &self.eth_client,
+            self.on_chain_proposer_address,
+            &self.signer,
+        )
+        .await;
+
+        if let Err(EthClientError::EstimateGasError(EstimateGasError::RPCError(error))) =
+            send_verify_tx_result.as_ref()
+        {
+            let invalid_proof_type = if error.contains("018") {
+                Some(ProverType::RISC0)
+            } else if error.contains("019") {
+                Some(ProverType::SP1)
             } else {
-                self.send_proof_to_contract(batch_to_send, proofs).await?;
+                None
+            };
+            if let Some(proof_type) = invalid_proof_type {
+                warn!("Deleting invalid aggregated {proof_type} proof");
+                self.rollup_store
+                    .delete_aggregated_proof(first_batch, proof_type)
+                    .await?;
             }
+        }
+
+        let verify_tx_hash = send_verify_tx_result?;
+
+        for batch_number in first_batch..=last_batch {
             self.rollup_store
-                .set_latest_sent_batch_proof(batch_to_send)
+                .store_verify_tx_by_batch(batch_number, verify_tx_hash)
                 .await?;
+        }
+        self.rollup_store
+            .set_latest_sent_batch_proof(last_batch)
+            .await?;
 
-            // Remove checkpoint from batch sent - 1.
-            // That checkpoint was needed to generate the proof for the batch we just sent.
-            // The checkpoint for the batch we have just sent is needed for the next batch.
-            let checkpoint_path = self
-                .checkpoints_dir
-                .join(batch_checkpoint_name(batch_to_send - 1));
-            if checkpoint_path.exists() {
-                let _ = remove_dir_all(&checkpoint_path).inspect_err(|e| {
-                    error!(
-                        "Failed to remove checkpoint directory at path {checkpoint_path:?}. Should be removed manually. Error: {e}"
-                    )
-                });
-            }
-        } else {
-            let missing_proof_types: Vec<String> = missing_proof_types
-                .iter()
-                .map(|proof_type| format!("{proof_type:?}"))
-                .collect();
-            info!(
-                ?missing_proof_types,
-                ?batch_to_send,
-                "Missing batch proof(s), will not send",
-            );
+        // The checkpoint of the last batch is needed to prove the next one.
+        for batch_number in first_batch.saturating_sub(1)..last_batch {
+            self.remove_checkpoint(batch_number);
         }
 
+        info!(
+            ?first_batch,
+            ?last_batch,
+            ?verify_tx_hash,
+            "Sent aggregated batch verification transaction to L1"
+        );
+
         Ok(())
     }
 
@@ -0,0 +1,712 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use ethrex_blockchain::Blockchain;
+use ethrex_common::{Address, types::BlockNumber};
+use ethrex_rpc::EthClient;
+use ethrex_storage::{Store, error::StoreError};
+use ethrex_storage_rollup::{RollupStoreError, StoreRollup};
+use serde::{Deserialize, Serialize};
+use spawned_concurrency::{
+    error::GenServerError,
+    messages::Unused,
+    tasks::{CastResponse, GenServer, GenServerHandle, send_after},
+};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    SequencerConfig,
+    based::sequencer_state::{SequencerState, SequencerStatus},
+    sequencer::utils::node_is_up_to_date,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LeaderElectionError {
+    #[error("Leader Elector failed to access the lease file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Leader Elector failed to (de)serialize the lease: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Leader Elector lease store lock was poisoned")]
+    PoisonedLock,
+    #[error("Leader Elector failed due to a Store error: {0}")]
+    StoreError(#[from] StoreError),
+    #[error("Leader Elector failed to convert values: {0}")]
+    TryIntoError(#[from] std::num::TryFromIntError),
+    #[error("Leader Elector failed due to an EthClient error: {0}")]
+    EthClientError(#[from] ethrex_rpc::clients::EthClientError),
+    #[error("Leader Elector failed due to a RollupStore error: {0}")]
+    RollupStoreError(#[from] RollupStoreError),
+    #[error("Internal Error: {0}")]
+    InternalError(#[from] GenServerError),
+}
+
+/// The right to sequence the chain, held until the holder stops renewing it.
+///
+/// Leases carry no wall-clock deadline, as clocks of different hosts can't be compared: `version`
+/// increases on every write, and a standby considers the lease expired once it has seen the same
+/// version for a whole TTL on its own monotonic clock. `term` increases every time the lease
+/// changes hands, and fences the blocks of the holder: only the holder of the current term can
+/// record them, so a deposed leader can't produce blocks once another node took over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderLease {
+    pub holder: String,
+    pub term: u64,
+    pub version: u64,
+    /// Last block produced by the holder, which a standby must have before taking over
+    pub last_block: BlockNumber,
+}
+
+/// Version of the lease a standby saw last, and since when on its own monotonic clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeaseObservation {
+    pub version: u64,
+    pub since_ms: u64,
+}
+
+/// Result of an atomic update of the lease.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeaseUpdate {
+    /// The lease was updated to this one
+    Written(LeaderLease),
+    /// The lease was left as it was
+    Kept(Option<LeaderLease>),
+}
+
+/// Computes the lease `node_id` gets if it tries to acquire it with `head` as its latest block,
+/// or `None` if another node holds a lease that hasn't expired yet, or this node hasn't caught up
+/// with the last block of the expired one.
+pub fn next_lease(
+    current: Option<&LeaderLease>,
+    node_id: &str,
+    head: BlockNumber,
+    observed: Option<&LeaseObservation>,
+    now_ms: u64,
+    ttl_ms: u64,
+) -> Option<LeaderLease> {
+    let Some(lease) = current else {
+        return Some(LeaderLease {
+            holder: node_id.to_owned(),
+            term: 1,
+            version: 1,
+            last_block: head,
+        });
+    };
+    let term = if lease.holder == node_id {
+        lease.term
+    } else {
+        let expired = observed.is_some_and(|observed| {
+            observed.version == lease.version && now_ms.saturating_sub(observed.since_ms) >= ttl_ms
+        });
+        if !expired || head < lease.last_block {
+            return None;
+        }
+        lease.term + 1
+    };
+    Some(LeaderLease {
+        holder: node_id.to_owned(),
+        term,
+        version: lease.version + 1,
+        last_block: head.max(lease.last_block),
+    })
+}
+
+/// Computes the lease after `node_id` produces `block_number`, or `None` if it no longer holds
+/// the lease of `term`.
+pub fn lease_with_block(
+    current: Option<&LeaderLease>,
+    node_id: &str,
+    term: u64,
+    block_number: BlockNumber,
+) -> Option<LeaderLease> {
+    let lease = current.filter(|lease| lease.holder == node_id && lease.term == term)?;
+    Some(LeaderLease {
+        version: lease.version + 1,
+        last_block: block_number.max(lease.last_block),
+        ..lease.clone()
+    })
+}
+
+/// Backend holding the leader lease shared by all the sequencers of a cluster.
+pub trait LeaseStore: Send + Sync + std::fmt::Debug {
+    /// Atomically replaces the lease with the one `next` computes from the current one, if any.
+    fn update(
+        &self,
+        next: &dyn Fn(Option<&LeaderLease>) -> Option<LeaderLease>,
+    ) -> Result<LeaseUpdate, LeaderElectionError>;
+
+    /// Acquires or renews the lease for `node_id`, see [`next_lease`].
+    fn try_acquire(
+        &self,
+        node_id: &str,
+        head: BlockNumber,
+        observed: Option<&LeaseObservation>,
+        now_ms: u64,
+        ttl_ms: u64,
+    ) -> Result<LeaseUpdate, LeaderElectionError> {
+        self.update(&|current| next_lease(current, node_id, head, observed, now_ms, ttl_ms))
+    }
+
+    /// Records a block produced by the holder of the lease of `term`, see [`lease_with_block`].
+    fn record_block(
+        &self,
+        node_id: &str,
+        term: u64,
+        block_number: BlockNumber,
+    ) -> Result<LeaseUpdate, LeaderElectionError> {
+        self.update(&|current| lease_with_block(current, node_id, term, block_number))
+    }
+}
+
+/// Lease store living in memory, shared by clones.
+/// Useful to run several sequencers in the same process, e.g. in tests.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryLeaseStore(Arc<Mutex<Option<LeaderLease>>>);
+
+impl LeaseStore for InMemoryLeaseStore {
+    fn update(
+        &self,
+        next: &dyn Fn(Option<&LeaderLease>) -> Option<LeaderLease>,
+    ) -> Result<LeaseUpdate, LeaderElectionError> {
+        let mut current = self
+            .0
+            .lock()
+            .map_err(|_| LeaderElectionError::PoisonedLock)?;
+        match next(current.as_ref()) {
+            Some(lease) => {
+                *current = Some(lease.clone());
+                Ok(LeaseUpdate::Written(lease))
+            }
+            None => Ok(LeaseUpdate::Kept(current.clone())),
+        }
+    }
+}
+
+/// Lease store backed by a JSON file guarded by an exclusive file lock.
+/// Every sequencer of the cluster must point to the same file, e.g. on a shared volume.
+#[derive(Debug, Clone)]
+pub struct FileLeaseStore {
+    path: PathBuf,
+}
+
+impl FileLeaseStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn read_lease(file: &mut File) -> Result<Option<LeaderLease>, LeaderElectionError> {
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        if contents.trim().is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&contents)?))
+    }
+
+    fn write_lease(file: &mut File, lease: &LeaderLease) -> Result<(), LeaderElectionError> {
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&serde_json::to_vec(lease)?)?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+impl LeaseStore for FileLeaseStore {
+    fn update(
+        &self,
+        next: &dyn Fn(Option<&LeaderLease>) -> Option<LeaderLease>,
+    ) -> Result<LeaseUpdate, LeaderElectionError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)?;
+        // The lock is released when the file is closed
+        file.lock()?;
+        let current = Self::read_lease(&mut file)?;
+        match next(current.as_ref()) {
+            Some(lease) => {
+                Self::write_lease(&mut file, &lease)?;
+                Ok(LeaseUpdate::Written(lease))
+            }
+            None => Ok(LeaseUpdate::Kept(current)),
+        }
+    }
+}
+
+/// Fences the blocks of the leader with the term of its lease: the block producer records each
+/// block in the lease before storing it, which fails once the lease changed hands.
+#[derive(Debug, Clone)]
+pub struct LeaseFence {
+    node_id: String,
+    lease_store: Arc<dyn LeaseStore>,
+    /// Term of the lease held by this node, zero if it doesn't hold it
+    term: Arc<AtomicU64>,
+}
+
+impl LeaseFence {
+    pub fn new(node_id: String, lease_store: Arc<dyn LeaseStore>) -> Self {
+        Self {
+            node_id,
+            lease_store,
+            term: Arc::default(),
+        }
+    }
+
+    fn set_term(&self, term: Option<u64>) {
+        self.term.store(term.unwrap_or_default(), Ordering::Release);
+    }
+
+    /// Records `block_number` as the last block of the lease, returning whether this node still
+    /// holds it and so can store the block.
+    pub fn record_block(&self, block_number: BlockNumber) -> Result<bool, LeaderElectionError> {
+        let term = self.term.load(Ordering::Acquire);
+        if term == 0 {
+            return Ok(false);
+        }
+        match self
+            .lease_store
+            .record_block(&self.node_id, term, block_number)?
+        {
+            LeaseUpdate::Written(_) => Ok(true),
+            LeaseUpdate::Kept(_) => {
+                self.set_term(None);
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// Keeps track of the lease from the point of view of a single node, on its own monotonic clock.
+#[derive(Debug)]
+pub struct LeaseTracker {
+    node_id: String,
+    lease_ttl_ms: u64,
+    lease_store: Arc<dyn LeaseStore>,
+    fence: LeaseFence,
+    /// Version of the lease held by another node, as last seen
+    observed: Option<LeaseObservation>,
+    /// When this node last acquired or renewed the lease, if it holds it
+    renewed_at_ms: Option<u64>,
+}
+
+impl LeaseTracker {
+    pub fn new(fence: LeaseFence, lease_ttl_ms: u64) -> Self {
+        Self {
+            node_id: fence.node_id.clone(),
+            lease_ttl_ms,
+            lease_store: fence.lease_store.clone(),
+            fence,
+            observed: None,
+            renewed_at_ms: None,
+        }
+    }
+
+    /// The lease is renewed three times per TTL, so a leader that fails to renew it has time to
+    /// step down before any other node can take over, as long as the clocks of both run at about
+    /// the same rate.
+    pub fn renew_interval_ms(&self) -> u64 {
+        self.lease_ttl_ms / 3
+    }
+
+    /// Tries to acquire or renew the lease at `now_ms`, with `head` as the latest block of the
+    /// node, and returns whether the node leads.
+    /// A leader that can't reach the lease store keeps leading only while its lease is sure to
+    /// outlive the next renewal, as standbys may take over a TTL after its last write.
+    pub fn poll(&mut self, head: BlockNumber, now_ms: u64) -> Result<bool, LeaderElectionError> {
+        let update = match self.lease_store.try_acquire(
+            &self.node_id,
+            head,
+            self.observed.as_ref(),
+            now_ms,
+            self.lease_ttl_ms,
+        ) {
+            Ok(update) => update,
+            Err(err) => {
+                let lease_outlives_next_renewal = self.renewed_at_ms.is_some_and(|renewed_at| {
+                    renewed_at.saturating_add(self.lease_ttl_ms)
+                        > now_ms.saturating_add(self.renew_interval_ms())
+                });
+                if !lease_outlives_next_renewal {
+                    self.step_down();
+                }
+                return Err(err);
+            }
+        };
+        match update {
+            LeaseUpdate::Written(lease) => {
+                self.renewed_at_ms = Some(now_ms);
+                self.observed = None;
+                self.fence.set_term(Some(lease.term));
+                Ok(true)
+            }
+            LeaseUpdate::Kept(lease) => {
+                self.step_down();
+                if let Some(lease) = lease {
+                    if self
+                        .observed
+                        .is_none_or(|observed| observed.version != lease.version)
+                    {
+                        self.observed = Some(LeaseObservation {
+                            version: lease.version,
+                            since_ms: now_ms,
+                        });
+                    } else if head < lease.last_block {
+                        debug!(
+                            "Leader lease expired, catching up with block {} before taking over",
+                            lease.last_block
+                        );
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    fn step_down(&mut self) {
+        self.renewed_at_ms = None;
+        self.fence.set_term(None);
+    }
+
+    /// Term of the lease held by this node, if any
+    pub fn term(&self) -> Option<u64> {
+        Some(self.fence.term.load(Ordering::Acquire)).filter(|term| *term != 0)
+    }
+}
+
+#[derive(Clone)]
+pub enum InMessage {
+    Elect,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum OutMessage {
+    Done,
+}
+
+/// Drives the `SequencerState` of a sequencer running in high availability mode.
+/// The lease holder is `Sequencing`, every other node is `Following` the
+/// leader's blocks over the L2 p2p network and ready to take over once the
+/// lease expires.
+pub struct LeaderElector {
+    tracker: LeaseTracker,
+    /// Origin of the monotonic clock the lease is tracked with
+    started: Instant,
+    sequencer_state: SequencerState,
+    store: Store,
+    blockchain: Arc<Blockchain>,
+    rollup_store: StoreRollup,
+    eth_client: EthClient,
+    on_chain_proposer_address: Address,
+}
+
+impl LeaderElector {
+    pub fn new(
+        sequencer_cfg: &SequencerConfig,
+        fence: LeaseFence,
+        sequencer_state: SequencerState,
+        store: Store,
+        blockchain: Arc<Blockchain>,
+        rollup_store: StoreRollup,
+    ) -> Result<Self, LeaderElectionError> {
+        Ok(Self {
+            tracker: LeaseTracker::new(fence, sequencer_cfg.high_availability.lease_ttl_ms),
+            started: Instant::now(),
+            sequencer_state,
+            store,
+            blockchain,
+            rollup_store,
+            eth_client: EthClient::new_with_multiple_urls(sequencer_cfg.eth.rpc_url.clone())?,
+            on_chain_proposer_address: sequencer_cfg.l1_committer.on_chain_proposer_address,
+        })
+    }
+
+    pub async fn spawn(
+        sequencer_cfg: &SequencerConfig,
+        fence: LeaseFence,
+        sequencer_state: SequencerState,
+        store: Store,
+        blockchain: Arc<Blockchain>,
+        rollup_store: StoreRollup,
+    ) -> Result<(), LeaderElectionError> {
+        let mut leader_elector = Self::new(
+            sequencer_cfg,
+            fence,
+            sequencer_state,
+            store,
+            blockchain,
+            rollup_store,
+        )?
+        .start();
+        leader_elector
+            .cast(InMessage::Elect)
+            .await
+            .map_err(LeaderElectionError::InternalError)
+    }
+
+    pub async fn elect(&mut self) -> Result<(), LeaderElectionError> {
+        let now_ms = u64::try_from(self.started.elapsed().as_millis())?;
+        let current_status = self.sequencer_state.status().await;
+
+        if current_status != SequencerStatus::Sequencing {
+            // Taking over without every committed batch would mean building on a stale head
+            let node_is_up_to_date = node_is_up_to_date::<LeaderElectionError>(
+                &self.eth_client,
+                self.on_chain_proposer_address,
+                &self.rollup_store,
+            )
+            .await?;
+            if !node_is_up_to_date {
+                debug!("Node is not up to date, not trying to acquire the leader lease");
+                self.set_status(current_status, SequencerStatus::Following)
+                    .await;
+                return Ok(());
+            }
+        }
+
+        let head = self.store.get_latest_block_number().await?;
+        let result = self.tracker.poll(head, now_ms);
+        let term = self.tracker.term();
+        let is_sequencing = current_status == SequencerStatus::Sequencing;
+        match (&result, term) {
+            (_, Some(term)) if !is_sequencing => {
+                info!("Acquired leader lease for term {term}, taking over as sequencer");
+            }
+            (Err(_), None) if is_sequencing => {
+                warn!("Could not renew the leader lease, stepping down");
+            }
+            (Ok(_), None) if is_sequencing => {
+                warn!("Leader lease was taken by another node, stepping down");
+            }
+            _ => {}
+        }
+        let new_status = if term.is_some() {
+            SequencerStatus::Sequencing
+        } else {
+            SequencerStatus::Following
+        };
+        self.set_status(current_status, new_status).await;
+        result.map(|_| ())
+    }
+
+    async fn set_status(&self, current_status: SequencerStatus, new_status: SequencerStatus) {
+        if current_status == new_status {
+            return;
+        }
+        info!("State transition: {current_status:?} -> {new_status:?}");
+        // Both the leader and its standbys must accept blocks gossiped over p2p
+        self.blockchain.set_synced();
+        self.sequencer_state.new_status(new_status).await;
+    }
+}
+
+impl GenServer for LeaderElector {
+    type CallMsg = Unused;
+    type CastMsg = InMessage;
+    type OutMsg = OutMessage;
+    type Error = LeaderElectionError;
+
+    async fn handle_cast(
+        &mut self,
+        _message: Self::CastMsg,
+        handle: &GenServerHandle<Self>,
+    ) -> CastResponse {
+        let _ = self
+            .elect()
+            .await
+            .inspect_err(|err| error!("Leader Elector Error: {err}"));
+        send_after(
+            Duration::from_millis(self.tracker.renew_interval_ms()),
+            handle.clone(),
+            Self::CastMsg::Elect,
+        );
+        CastResponse::NoReply
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic)]
+mod tests {
+    use std::{
+        sync::atomic::AtomicBool,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::*;
+
+    const TTL_MS: u64 = 3_000;
+
+    fn written(update: LeaseUpdate) -> LeaderLease {
+        match update {
+            LeaseUpdate::Written(lease) => lease,
+            LeaseUpdate::Kept(lease) => panic!("Expected the lease to be written, kept {lease:?}"),
+        }
+    }
+
+    fn tracker(node_id: &str, store: Arc<dyn LeaseStore>) -> LeaseTracker {
+        LeaseTracker::new(LeaseFence::new(node_id.to_owned(), store), TTL_MS)
+    }
+
+    /// Lease store that can be made unreachable
+    #[derive(Debug, Default)]
+    struct FlakyLeaseStore {
+        inner: InMemoryLeaseStore,
+        unreachable: AtomicBool,
+    }
+
+    impl LeaseStore for FlakyLeaseStore {
+        fn update(
+            &self,
+            next: &dyn Fn(Option<&LeaderLease>) -> Option<LeaderLease>,
+        ) -> Result<LeaseUpdate, LeaderElectionError> {
+            if self.unreachable.load(Ordering::Relaxed) {
+                return Err(std::io::Error::other("unreachable").into());
+            }
+            self.inner.update(next)
+        }
+    }
+
+    #[test]
+    fn first_node_acquires_lease() {
+        let store = InMemoryLeaseStore::default();
+        let lease = written(store.try_acquire("a", 7, None, 0, TTL_MS).unwrap());
+        assert_eq!(lease.holder, "a");
+        assert_eq!(lease.term, 1);
+        assert_eq!(lease.version, 1);
+        assert_eq!(lease.last_block, 7);
+    }
+
+    #[test]
+    fn holder_renews_lease_keeping_term() {
+        let store = InMemoryLeaseStore::default();
+        store.try_acquire("a", 0, None, 0, TTL_MS).unwrap();
+        let lease = written(store.try_acquire("a", 3, None, 1_000, TTL_MS).unwrap());
+        assert_eq!(lease.term, 1);
+        assert_eq!(lease.version, 2);
+        assert_eq!(lease.last_block, 3);
+    }
+
+    #[test]
+    fn lease_expires_after_a_ttl_without_new_versions() {
+        let store = InMemoryLeaseStore::default();
+        let lease = written(store.try_acquire("a", 0, None, 0, TTL_MS).unwrap());
+        // Clocks of different nodes aren't compared, only the time the standby saw the version for
+        let observed = LeaseObservation {
+            version: lease.version,
+            since_ms: 1_000_000,
+        };
+        let acquire = |now_ms| store.try_acquire("b", 0, Some(&observed), now_ms, TTL_MS);
+        assert!(matches!(
+            acquire(1_000_000 + TTL_MS - 1).unwrap(),
+            LeaseUpdate::Kept(Some(_))
+        ));
+        // A renewal in between makes the observation stale
+        store.try_acquire("a", 0, None, 0, TTL_MS).unwrap();
+        assert!(matches!(
+            acquire(1_000_000 + TTL_MS).unwrap(),
+            LeaseUpdate::Kept(Some(_))
+        ));
+    }
+
+    #[test]
+    fn only_the_holder_of_the_term_records_blocks() {
+        let store = InMemoryLeaseStore::default();
+        store.try_acquire("a", 0, None, 0, TTL_MS).unwrap();
+        let lease = written(store.record_block("a", 1, 5).unwrap());
+        assert_eq!(lease.last_block, 5);
+        assert_eq!(lease.version, 2);
+        assert!(matches!(
+            store.record_block("a", 2, 6).unwrap(),
+            LeaseUpdate::Kept(_)
+        ));
+        assert!(matches!(
+            store.record_block("b", 1, 6).unwrap(),
+            LeaseUpdate::Kept(_)
+        ));
+    }
+
+    #[test]
+    fn standby_takes_over_once_caught_up_with_the_last_block() {
+        let store: Arc<dyn LeaseStore> = Arc::new(InMemoryLeaseStore::default());
+        let mut leader = tracker("a", store.clone());
+        let mut standby = tracker("b", store.clone());
+
+        assert!(leader.poll(0, 0).unwrap());
+        assert!(leader.fence.record_block(10).unwrap());
+        assert!(!standby.poll(8, 0).unwrap());
+        // The leader dies, the standby sees the same version for a whole TTL but lacks blocks
+        assert!(!standby.poll(8, TTL_MS).unwrap());
+        assert!(!standby.poll(9, TTL_MS + 1_000).unwrap());
+        assert!(standby.poll(10, TTL_MS + 2_000).unwrap());
+        assert_eq!(standby.term(), Some(2));
+
+        // The previous leader can neither store blocks nor renew once it lost the lease
+        assert!(!leader.fence.record_block(11).unwrap());
+        assert_eq!(leader.term(), None);
+        assert!(!leader.poll(10, TTL_MS + 3_000).unwrap());
+        assert!(standby.fence.record_block(11).unwrap());
+    }
+
+    #[test]
+    fn leader_steps_down_when_it_cannot_renew_before_the_lease_expires() {
+        let store = Arc::new(FlakyLeaseStore::default());
+        let mut leader = tracker("a", store.clone());
+        let interval = leader.renew_interval_ms();
+
+        assert!(leader.poll(0, 0).unwrap());
+        store.unreachable.store(true, Ordering::Relaxed);
+        // The lease outlives the next renewal, so the leader keeps sequencing
+        assert!(leader.poll(0, interval).is_err());
+        assert_eq!(leader.term(), Some(1));
+        // It wouldn't at the next one, standbys may take over before it
+        assert!(leader.poll(0, 2 * interval).is_err());
+        assert_eq!(leader.term(), None);
+        assert!(!leader.fence.record_block(1).unwrap());
+
+        // Once reachable again it renews the lease nobody took over
+        store.unreachable.store(false, Ordering::Relaxed);
+        assert!(leader.poll(0, 3 * interval).unwrap());
+        assert_eq!(leader.term(), Some(1));
+    }
+
+    #[test]
+    fn file_lease_store_is_shared_between_instances() {
+        let path = std::env::temp_dir().join(format!(
+            "ethrex_leader_lease_{}_{}.json",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let leader = FileLeaseStore::new(path.clone());
+        let standby = FileLeaseStore::new(path.clone());
+
+        let lease = written(leader.try_acquire("a", 0, None, 0, TTL_MS).unwrap());
+        assert_eq!(
+            standby.try_acquire("b", 0, None, 0, TTL_MS).unwrap(),
+            LeaseUpdate::Kept(Some(lease.clone()))
+        );
+        let observed = LeaseObservation {
+            version: lease.version,
+            since_ms: 0,
+        };
+        let lease = written(
+            standby
+                .try_acquire("b", 0, Some(&observed), TTL_MS, TTL_MS)
+                .unwrap(),
+        );
+        assert_eq!(lease.term, 2);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
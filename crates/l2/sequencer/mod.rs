@@ -11,11 +11,14 @@ use block_producer::BlockProducer;
 use ethrex_blockchain::Blockchain;
 use ethrex_common::types::Genesis;
 use ethrex_l2_common::prover::ProverType;
+use ethrex_l2_rpc::l2::preconfirmations::PreconfirmationFeed;
+use ethrex_p2p::rlpx::l2::l2_connection::LeadSequencer;
 use ethrex_storage::Store;
 use ethrex_storage_rollup::StoreRollup;
 use l1_committer::L1Committer;
 use l1_proof_sender::L1ProofSender;
 use l1_watcher::L1Watcher;
+use leader_election::{FileLeaseStore, LeaderElector, LeaseFence};
 #[cfg(feature = "metrics")]
 use metrics::MetricsGatherer;
 use proof_coordinator::ProofCoordinator;
@@ -32,6 +35,7 @@ pub mod l1_committer;
 pub mod l1_proof_sender;
 pub mod l1_proof_verifier;
 pub mod l1_watcher;
+pub mod leader_election;
 #[cfg(feature = "metrics")]
 pub mod metrics;
 pub mod proof_coordinator;
@@ -51,6 +55,8 @@ pub async fn start_l2(
     l2_url: Url,
     genesis: Genesis,
     checkpoints_dir: PathBuf,
+    preconfirmation_feed: Option<PreconfirmationFeed>,
+    lead_sequencer: LeadSequencer,
 ) -> Result<
     (
         Option<GenServerHandle<L1Committer>>,
@@ -61,6 +67,9 @@ pub async fn start_l2(
 > {
     let initial_status = if cfg.based.enabled {
         SequencerStatus::default()
+    } else if cfg.high_availability.enabled {
+        // Every node starts as a standby until it acquires the leader lease
+        SequencerStatus::Following
     } else {
         SequencerStatus::Sequencing
     };
@@ -148,6 +157,14 @@ pub async fn start_l2(
     .inspect_err(|err| {
         error!("Error starting L1 Proof Sender: {err}");
     });
+    let lease_fence = cfg.high_availability.enabled.then(|| {
+        LeaseFence::new(
+            cfg.high_availability.node_id.clone(),
+            Arc::new(FileLeaseStore::new(
+                cfg.high_availability.lease_file.clone(),
+            )),
+        )
+    });
     let block_producer = BlockProducer::spawn(
         store.clone(),
         rollup_store.clone(),
@@ -155,6 +172,8 @@ pub async fn start_l2(
         cfg.clone(),
         shared_state.clone(),
         cfg.l1_watcher.router_address,
+        preconfirmation_feed,
+        lease_fence.clone(),
     )
     .await
     .inspect_err(|err| {
@@ -176,6 +195,21 @@ pub async fn start_l2(
             needed_proof_types.clone(),
         )));
     }
+    if let Some(lease_fence) = lease_fence {
+        let _ = LeaderElector::spawn(
+            &cfg,
+            lease_fence,
+            shared_state.clone(),
+            store.clone(),
+            blockchain.clone(),
+            rollup_store.clone(),
+        )
+        .await
+        .inspect_err(|err| {
+            error!("Error starting Leader Elector: {err}");
+        });
+    }
+
     if cfg.based.enabled {
         let _ = StateUpdater::spawn(
             cfg.clone(),
@@ -183,6 +217,7 @@ pub async fn start_l2(
             blockchain.clone(),
             store.clone(),
             rollup_store.clone(),
+            lead_sequencer,
         )
         .await
         .inspect_err(|err| {
@@ -12,20 +12,22 @@ use secp256k1::SecretKey;
 use serde::{Deserialize, Serialize};
 use spawned_concurrency::messages::Unused;
 use spawned_concurrency::tasks::{CastResponse, GenServer, GenServerHandle};
+use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     net::{TcpListener, TcpStream},
+    sync::Mutex,
+    time::Instant,
 };
 use tracing::{debug, error, info, warn};
 
 #[cfg(feature = "metrics")]
 use ethrex_metrics::l2::metrics::METRICS;
 #[cfg(feature = "metrics")]
-use std::{collections::HashMap, time::SystemTime};
-#[cfg(feature = "metrics")]
-use tokio::sync::Mutex;
+use std::time::SystemTime;
 
 /// Enum for the ProverServer <--> ProverClient Communication Protocol.
 #[allow(clippy::large_enum_variant)]
@@ -47,7 +49,12 @@ pub enum ProofData {
     /// The Client initiates the connection with a BatchRequest.
     /// Asking for the ProverInputData the prover_server considers/needs.
     /// The commit hash is used to ensure the client and server are compatible.
-    BatchRequest { commit_hash: String },
+    /// The prover type, if any, lets the server skip the batches that already have
+    /// a proof of that type, as a batch can be verified before all its proofs are ready.
+    BatchRequest {
+        commit_hash: String,
+        prover_type: Option<ProverType>,
+    },
 
     /// 4.
     /// The Server responds with a NoBatchForVersion if the code version is not the same as the one
@@ -75,6 +82,37 @@ pub enum ProofData {
     /// 7.
     /// The Server acknowledges the receipt of the proof and updates its state,
     ProofSubmitACK { batch_number: u64 },
+
+    /// 8.
+    /// The Client asks for the proofs of a range of consecutive batches to
+    /// aggregate them into a single proof of the given type.
+    AggregationRequest {
+        commit_hash: String,
+        prover_type: ProverType,
+    },
+
+    /// 9.
+    /// The Server responds with the compressed proofs of the batches
+    /// `first_batch..=last_batch`, in order.
+    /// If the AggregationResponse is empty, there's no range ready to be aggregated.
+    AggregationResponse {
+        first_batch: Option<u64>,
+        last_batch: Option<u64>,
+        proofs: Vec<BatchProof>,
+    },
+
+    /// 10.
+    /// The Client submits the proof aggregating the proofs of the batches
+    /// `first_batch..=last_batch`.
+    AggregatedProofSubmit {
+        first_batch: u64,
+        last_batch: u64,
+        aggregated_proof: BatchProof,
+    },
+
+    /// 11.
+    /// The Server acknowledges the receipt of the aggregated proof.
+    AggregatedProofSubmitACK { first_batch: u64 },
 }
 
 impl ProofData {
@@ -92,8 +130,11 @@ impl ProofData {
     }
 
     /// Builder function for creating a BatchRequest
-    pub fn batch_request(commit_hash: String) -> Self {
-        ProofData::BatchRequest { commit_hash }
+    pub fn batch_request(commit_hash: String, prover_type: Option<ProverType>) -> Self {
+        ProofData::BatchRequest {
+            commit_hash,
+            prover_type,
+        }
     }
 
     /// Builder function for creating a NoBatchForVersion
@@ -130,6 +171,53 @@ impl ProofData {
     pub fn proof_submit_ack(batch_number: u64) -> Self {
         ProofData::ProofSubmitACK { batch_number }
     }
+
+    /// Builder function for creating an AggregationRequest
+    pub fn aggregation_request(commit_hash: String, prover_type: ProverType) -> Self {
+        ProofData::AggregationRequest {
+            commit_hash,
+            prover_type,
+        }
+    }
+
+    /// Builder function for creating an AggregationResponse
+    pub fn aggregation_response(
+        first_batch: u64,
+        last_batch: u64,
+        proofs: Vec<BatchProof>,
+    ) -> Self {
+        ProofData::AggregationResponse {
+            first_batch: Some(first_batch),
+            last_batch: Some(last_batch),
+            proofs,
+        }
+    }
+
+    pub fn empty_aggregation_response() -> Self {
+        ProofData::AggregationResponse {
+            first_batch: None,
+            last_batch: None,
+            proofs: Vec::new(),
+        }
+    }
+
+    /// Builder function for creating an AggregatedProofSubmit
+    pub fn aggregated_proof_submit(
+        first_batch: u64,
+        last_batch: u64,
+        aggregated_proof: BatchProof,
+    ) -> Self {
+        ProofData::AggregatedProofSubmit {
+            first_batch,
+            last_batch,
+            aggregated_proof,
+        }
+    }
+
+    /// Builder function for creating an AggregatedProofSubmitACK
+    pub fn aggregated_proof_submit_ack(first_batch: u64) -> Self {
+        ProofData::AggregatedProofSubmitACK { first_batch }
+    }
 }
 
 #[derive(Clone)]
@@ -153,6 +241,13 @@ pub struct ProofCoordinator {
     tdx_private_key: Option<SecretKey>,
     needed_proof_types: Vec<ProverType>,
     aligned: bool,
+    aggregation: bool,
+    aggregation_size: u64,
+    aggregation_timeout: Duration,
+    /// First batch of the range waiting to be aggregated and since when it waits.
+    aggregation_wait: Arc<Mutex<Option<(u64, Instant)>>>,
+    /// Last range handed out to be aggregated, for each prover type.
+    aggregation_ranges: Arc<Mutex<HashMap<ProverType, (u64, u64)>>>,
     git_commit_hash: String,
     #[cfg(feature = "metrics")]
     request_timestamp: Arc<Mutex<HashMap<u64, SystemTime>>>,
@@ -196,6 +291,13 @@ impl ProofCoordinator {
             needed_proof_types,
             git_commit_hash: get_git_commit_hash(),
             aligned: config.aligned.aligned_mode,
+            aggregation: config.proof_coordinator.aggregation,
+            aggregation_size: config.proof_coordinator.aggregation_size,
+            aggregation_timeout: Duration::from_millis(
+                config.proof_coordinator.aggregation_timeout_ms,
+            ),
+            aggregation_wait: Arc::new(Mutex::new(None)),
+            aggregation_ranges: Arc::new(Mutex::new(HashMap::new())),
             #[cfg(feature = "metrics")]
             request_timestamp: Arc::new(Mutex::new(HashMap::new())),
             qpl_tool_path: config.proof_coordinator.qpl_tool_path.clone(),
@@ -243,18 +345,22 @@ impl ProofCoordinator {
         }
     }
 
+    /// Returns the first batch after the latest fully verified one that is
+    /// missing any of `proof_types` and can be proven by the given version.
     async fn next_batch_to_prove_for_version(
         &self,
         commit_hash: &str,
+        proof_types: &[ProverType],
     ) -> Result<u64, ProofCoordinatorError> {
         let mut batch_to_prove = 1 + self.rollup_store.get_latest_sent_batch_proof().await?;
 
-        while self
-            .rollup_store
-            .get_prover_input_by_batch_and_version(batch_to_prove, commit_hash)
-            .await?
-            .is_none()
-            && self.rollup_store.contains_batch(&batch_to_prove).await?
+        while self.rollup_store.contains_batch(&batch_to_prove).await?
+            && (self
+                .rollup_store
+                .get_prover_input_by_batch_and_version(batch_to_prove, commit_hash)
+                .await?
+                .is_none()
+                || self.all_proofs_exist(batch_to_prove, proof_types).await?)
         {
             batch_to_prove += 1;
         }
@@ -262,13 +368,42 @@ impl ProofCoordinator {
         Ok(batch_to_prove)
     }
 
+    async fn all_proofs_exist(
+        &self,
+        batch_number: u64,
+        proof_types: &[ProverType],
+    ) -> Result<bool, ProofCoordinatorError> {
+        for proof_type in proof_types {
+            if self
+                .rollup_store
+                .get_proof_by_batch_and_type(batch_number, *proof_type)
+                .await?
+                .is_none()
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     async fn handle_request(
         &self,
         stream: &mut TcpStream,
         commit_hash: String,
+        prover_type: Option<ProverType>,
     ) -> Result<(), ProofCoordinatorError> {
         info!("BatchRequest received");
-        let batch_to_prove = self.next_batch_to_prove_for_version(&commit_hash).await?;
+        // Provers of a needed type only get the batches missing their proof, so
+        // faster provers don't wait for the slower ones to move forward.
+        let proof_types = match prover_type {
+            Some(prover_type) if self.needed_proof_types.contains(&prover_type) => {
+                vec![prover_type]
+            }
+            _ => self.needed_proof_types.clone(),
+        };
+        let batch_to_prove = self
+            .next_batch_to_prove_for_version(&commit_hash, &proof_types)
+            .await?;
 
         if commit_hash != self.git_commit_hash {
             debug!(
@@ -277,53 +412,41 @@ impl ProofCoordinator {
             );
         }
 
-        let mut all_proofs_exist = true;
-        for proof_type in &self.needed_proof_types {
-            if self
+        let response = if !self.rollup_store.contains_batch(&batch_to_prove).await? {
+            debug!("Sending empty BatchResponse");
+            ProofData::empty_batch_response()
+        } else {
+            let Some(input) = self
                 .rollup_store
-                .get_proof_by_batch_and_type(batch_to_prove, *proof_type)
+                .get_prover_input_by_batch_and_version(batch_to_prove, &commit_hash)
                 .await?
-                .is_none()
-            {
-                all_proofs_exist = false;
-                break;
-            }
-        }
-
-        let response =
-            if all_proofs_exist || !self.rollup_store.contains_batch(&batch_to_prove).await? {
-                debug!("Sending empty BatchResponse");
-                ProofData::empty_batch_response()
+            else {
+                let response = ProofData::no_batch_for_version(commit_hash);
+                send_response(stream, &response).await?;
+                info!("No batch for version sent");
+                return Ok(());
+            };
+            debug!("Sending BatchResponse for block_number: {batch_to_prove}");
+            // Aggregation recursively verifies the batch proofs, so they
+            // are requested in their compressed form, as for Aligned.
+            let format = if self.aligned || self.aggregation {
+                ProofFormat::Compressed
             } else {
-                let Some(input) = self
-                    .rollup_store
-                    .get_prover_input_by_batch_and_version(batch_to_prove, &commit_hash)
-                    .await?
-                else {
-                    let response = ProofData::no_batch_for_version(commit_hash);
-                    send_response(stream, &response).await?;
-                    info!("No batch for version sent");
-                    return Ok(());
-                };
-                debug!("Sending BatchResponse for block_number: {batch_to_prove}");
-                let format = if self.aligned {
-                    ProofFormat::Compressed
-                } else {
-                    ProofFormat::Groth16
-                };
-                metrics!(
-                    // First request starts a timer until a proof is received. The elapsed time will be
-                    // the estimated proving time.
-                    // This should be used for development only and runs on the assumption that:
-                    //   1. There's a single prover
-                    //   2. Communication does not fail
-                    //   3. Communication adds negligible overhead in comparison with proving time
-                    let mut lock = self.request_timestamp.lock().await;
-                    lock.entry(batch_to_prove).or_insert(SystemTime::now());
-                );
-                debug!("Sending BatchResponse for block_number: {batch_to_prove}");
-                ProofData::batch_response(batch_to_prove, input, format)
+                ProofFormat::Groth16
             };
+            metrics!(
+                // First request starts a timer until a proof is received. The elapsed time will be
+                // the estimated proving time.
+                // This should be used for development only and runs on the assumption that:
+                //   1. There's a single prover
+                //   2. Communication does not fail
+                //   3. Communication adds negligible overhead in comparison with proving time
+                let mut lock = self.request_timestamp.lock().await;
+                lock.entry(batch_to_prove).or_insert(SystemTime::now());
+            );
+            debug!("Sending BatchResponse for block_number: {batch_to_prove}");
+            ProofData::batch_response(batch_to_prove, input, format)
+        };
 
         send_response(stream, &response).await?;
         info!("BatchResponse sent for batch number: {batch_to_prove}");
@@ -379,6 +502,174 @@ impl ProofCoordinator {
         Ok(())
     }
 
+    /// Returns the range of batches, starting right after the latest sent one,
+    /// whose proofs of type `prover_type` should be aggregated next, as decided
+    /// by [`aggregation_range_is_ready`].
+    /// The range stops at the first batch that can't be proven by this version.
+    async fn next_batches_to_aggregate(
+        &self,
+        commit_hash: &str,
+        prover_type: ProverType,
+    ) -> Result<Option<(u64, u64, Vec<BatchProof>)>, ProofCoordinatorError> {
+        let first_batch = 1 + self.rollup_store.get_latest_sent_batch_proof().await?;
+
+        if self
+            .rollup_store
+            .get_aggregated_proof(first_batch, prover_type)
+            .await?
+            .is_some()
+        {
+            return Ok(None);
+        }
+
+        let mut target_last_batch = None;
+        for other_type in &self.needed_proof_types {
+            if *other_type == prover_type {
+                continue;
+            }
+            if let Some((last_batch, _)) = self
+                .rollup_store
+                .get_aggregated_proof(first_batch, *other_type)
+                .await?
+            {
+                target_last_batch = Some(last_batch);
+                break;
+            }
+        }
+        let max_last_batch = target_last_batch
+            .unwrap_or(first_batch.saturating_add(self.aggregation_size.saturating_sub(1)));
+
+        let mut proofs = Vec::new();
+        let mut next_batch = first_batch;
+        while next_batch <= max_last_batch && self.rollup_store.contains_batch(&next_batch).await? {
+            if self
+                .rollup_store
+                .get_prover_input_by_batch_and_version(next_batch, commit_hash)
+                .await?
+                .is_none()
+            {
+                break;
+            }
+            let Some(proof) = self
+                .rollup_store
+                .get_proof_by_batch_and_type(next_batch, prover_type)
+                .await?
+            else {
+                // This batch is still being proven, so the range isn't ready yet
+                return Ok(None);
+            };
+            proofs.push(proof);
+            next_batch += 1;
+        }
+
+        let Some(last_batch) = next_batch
+            .checked_sub(1)
+            .filter(|last| *last >= first_batch)
+        else {
+            return Ok(None);
+        };
+
+        let waiting = {
+            let mut aggregation_wait = self.aggregation_wait.lock().await;
+            match *aggregation_wait {
+                Some((batch, since)) if batch == first_batch => since.elapsed(),
+                _ => {
+                    *aggregation_wait = Some((first_batch, Instant::now()));
+                    Duration::ZERO
+                }
+            }
+        };
+        if !aggregation_range_is_ready(
+            first_batch,
+            last_batch,
+            target_last_batch,
+            self.aggregation_size,
+            waiting,
+            self.aggregation_timeout,
+        ) {
+            return Ok(None);
+        }
+
+        self.aggregation_ranges
+            .lock()
+            .await
+            .insert(prover_type, (first_batch, last_batch));
+        Ok(Some((first_batch, last_batch, proofs)))
+    }
+
+    async fn handle_aggregation_request(
+        &self,
+        stream: &mut TcpStream,
+        commit_hash: String,
+        prover_type: ProverType,
+    ) -> Result<(), ProofCoordinatorError> {
+        info!("AggregationRequest received for {prover_type}");
+
+        let response = if !self.aggregation || !self.needed_proof_types.contains(&prover_type) {
+            ProofData::empty_aggregation_response()
+        } else if let Some((first_batch, last_batch, proofs)) = self
+            .next_batches_to_aggregate(&commit_hash, prover_type)
+            .await?
+        {
+            debug!("Sending AggregationResponse for batches {first_batch} to {last_batch}");
+            ProofData::aggregation_response(first_batch, last_batch, proofs)
+        } else {
+            debug!("Sending empty AggregationResponse");
+            ProofData::empty_aggregation_response()
+        };
+
+        send_response(stream, &response).await?;
+        info!("AggregationResponse sent");
+        Ok(())
+    }
+
+    async fn handle_aggregated_submit(
+        &self,
+        stream: &mut TcpStream,
+        first_batch: u64,
+        last_batch: u64,
+        aggregated_proof: BatchProof,
+    ) -> Result<(), ProofCoordinatorError> {
+        info!("AggregatedProofSubmit received for batches {first_batch} to {last_batch}");
+
+        let prover_type = aggregated_proof.prover_type();
+        let expected_first_batch = 1 + self.rollup_store.get_latest_sent_batch_proof().await?;
+        let handed_out = self
+            .aggregation_ranges
+            .lock()
+            .await
+            .get(&prover_type)
+            .copied();
+        if first_batch != expected_first_batch || handed_out != Some((first_batch, last_batch)) {
+            return Err(ProofCoordinatorError::UnexpectedAggregatedRange(
+                first_batch,
+                last_batch,
+            ));
+        }
+
+        if self
+            .rollup_store
+            .get_aggregated_proof(first_batch, prover_type)
+            .await?
+            .is_some()
+        {
+            info!(
+                ?first_batch,
+                ?prover_type,
+                "An aggregated proof was received for a range and type that is already stored"
+            );
+        } else {
+            self.rollup_store
+                .store_aggregated_proof(first_batch, last_batch, prover_type, aggregated_proof)
+                .await?;
+        }
+
+        let response = ProofData::aggregated_proof_submit_ack(first_batch);
+        send_response(stream, &response).await?;
+        info!("AggregatedProofSubmit ACK sent");
+        Ok(())
+    }
+
     async fn handle_setup(
         &self,
         stream: &mut TcpStream,
@@ -449,6 +740,26 @@ impl GenServer for ProofCoordinator {
     }
 }
 
+/// Whether the proven batches `first_batch..=last_batch` should be aggregated now.
+///
+/// A range has to match the one already aggregated for another proof type, so
+/// both proofs can be verified together. Otherwise it has to hold
+/// `aggregation_size` batches, unless its first batch has been waiting for
+/// `timeout`, so the latest batches of a quiet chain don't wait indefinitely.
+fn aggregation_range_is_ready(
+    first_batch: u64,
+    last_batch: u64,
+    target_last_batch: Option<u64>,
+    aggregation_size: u64,
+    waiting: Duration,
+    timeout: Duration,
+) -> bool {
+    match target_last_batch {
+        Some(target) => target == last_batch,
+        None => last_batch - first_batch + 1 >= aggregation_size || waiting >= timeout,
+    }
+}
+
 #[derive(Clone)]
 struct ConnectionHandler {
     proof_coordinator: ProofCoordinator,
@@ -486,10 +797,13 @@ impl ConnectionHandler {
 
             let data: Result<ProofData, _> = serde_json::from_slice(&buffer);
             match data {
-                Ok(ProofData::BatchRequest { commit_hash }) => {
+                Ok(ProofData::BatchRequest {
+                    commit_hash,
+                    prover_type,
+                }) => {
                     if let Err(e) = self
                         .proof_coordinator
-                        .handle_request(&mut stream, commit_hash)
+                        .handle_request(&mut stream, commit_hash, prover_type)
                         .await
                     {
                         error!("Failed to handle BatchRequest: {e}");
@@ -507,6 +821,36 @@ impl ConnectionHandler {
                         error!("Failed to handle ProofSubmit: {e}");
                     }
                 }
+                Ok(ProofData::AggregationRequest {
+                    commit_hash,
+                    prover_type,
+                }) => {
+                    if let Err(e) = self
+                        .proof_coordinator
+                        .handle_aggregation_request(&mut stream, commit_hash, prover_type)
+                        .await
+                    {
+                        error!("Failed to handle AggregationRequest: {e}");
+                    }
+                }
+                Ok(ProofData::AggregatedProofSubmit {
+                    first_batch,
+                    last_batch,
+                    aggregated_proof,
+                }) => {
+                    if let Err(e) = self
+                        .proof_coordinator
+                        .handle_aggregated_submit(
+                            &mut stream,
+                            first_batch,
+                            last_batch,
+                            aggregated_proof,
+                        )
+                        .await
+                    {
+                        error!("Failed to handle AggregatedProofSubmit: {e}");
+                    }
+                }
                 Ok(ProofData::ProverSetup {
                     prover_type,
                     payload,
@@ -582,3 +926,59 @@ async fn send_response(
         .map_err(ProofCoordinatorError::ConnectionError)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: u64 = 4;
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn full_ranges_are_aggregated_right_away() {
+        assert!(aggregation_range_is_ready(
+            5,
+            8,
+            None,
+            SIZE,
+            Duration::ZERO,
+            TIMEOUT
+        ));
+    }
+
+    #[test]
+    fn partial_ranges_wait_for_the_timeout() {
+        assert!(!aggregation_range_is_ready(
+            5,
+            6,
+            None,
+            SIZE,
+            Duration::from_secs(59),
+            TIMEOUT
+        ));
+        assert!(aggregation_range_is_ready(
+            5, 6, None, SIZE, TIMEOUT, TIMEOUT
+        ));
+    }
+
+    #[test]
+    fn ranges_match_the_one_aggregated_for_another_type() {
+        // Even if full or waiting for long, as both proofs are verified together
+        assert!(!aggregation_range_is_ready(
+            5,
+            8,
+            Some(6),
+            SIZE,
+            TIMEOUT,
+            TIMEOUT
+        ));
+        assert!(aggregation_range_is_ready(
+            5,
+            6,
+            Some(6),
+            SIZE,
+            Duration::ZERO,
+            TIMEOUT
+        ));
+    }
+}
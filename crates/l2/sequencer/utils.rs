@@ -7,7 +7,8 @@ use ethrex_common::{Address, H256, types::TxType};
 use ethrex_l2_common::prover::ProverType;
 use ethrex_l2_rpc::signer::Signer;
 use ethrex_l2_sdk::{
-    build_generic_tx, get_last_committed_batch, send_tx_bump_gas_exponential_backoff,
+    build_generic_tx, get_immediate_proofs, get_last_committed_batch, get_min_proofs_to_verify,
+    get_proof_upgrade_window, send_tx_bump_gas_exponential_backoff,
 };
 use ethrex_rpc::{
     EthClient,
@@ -20,7 +21,7 @@ use rand::Rng;
 use reqwest::Url;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::time::sleep;
-use tracing::info;
+use tracing::{debug, info};
 
 pub async fn sleep_random(sleep_amount: u64) {
     sleep(random_duration(sleep_amount)).await;
@@ -114,6 +115,105 @@ pub async fn get_needed_proof_types(
     Ok(needed_proof_types)
 }
 
+/// Proof policy set in the OnChainProposer with `setProofPolicy`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofPolicy {
+    /// Amount of the needed proof types a batch has to be verified with before its remaining proofs.
+    pub min_proofs: usize,
+    /// Needed proof types that have to be among them.
+    pub immediate_proof_types: Vec<ProverType>,
+    /// Time, in seconds, the remaining proofs of an optimistically verified
+    /// batch can take. Zero means they're never needed.
+    pub upgrade_window: u64,
+}
+
+impl ProofPolicy {
+    /// Policy that requires every needed proof type to verify a batch.
+    pub fn all_proofs(needed_proof_types: &[ProverType]) -> Self {
+        Self {
+            min_proofs: needed_proof_types.len(),
+            immediate_proof_types: needed_proof_types.to_vec(),
+            upgrade_window: 0,
+        }
+    }
+
+    /// Builds the policy from the `MIN_PROOFS_TO_VERIFY` amount and the `IMMEDIATE_PROOFS`
+    /// bitmap of the OnChainProposer, where both being zero means every needed proof type.
+    pub fn from_contract(
+        min_proofs: u64,
+        immediate_proofs: u64,
+        upgrade_window: u64,
+        needed_proof_types: &[ProverType],
+    ) -> Self {
+        let immediate_proof_types: Vec<ProverType> = needed_proof_types
+            .iter()
+            .copied()
+            .filter(|prover_type| {
+                prover_type
+                    .proof_bit()
+                    .is_some_and(|bit| immediate_proofs & u64::from(bit) != 0)
+            })
+            .collect();
+
+        // The immediate proofs count towards the minimum
+        let min_proofs = usize::try_from(min_proofs)
+            .unwrap_or(usize::MAX)
+            .max(immediate_proof_types.len());
+        if min_proofs == 0 || min_proofs > needed_proof_types.len() {
+            return Self::all_proofs(needed_proof_types);
+        }
+
+        Self {
+            min_proofs,
+            immediate_proof_types,
+            upgrade_window,
+        }
+    }
+
+    /// Whether batches can be verified before all their proofs are ready.
+    pub fn is_optimistic(&self, needed_proof_types: &[ProverType]) -> bool {
+        self.min_proofs < needed_proof_types.len()
+    }
+
+    /// Whether a batch can be verified with the given ready proof types.
+    pub fn can_verify<'a>(
+        &self,
+        ready_proof_types: impl IntoIterator<Item = &'a ProverType>,
+    ) -> bool {
+        let ready_proof_types: Vec<&ProverType> = ready_proof_types.into_iter().collect();
+        ready_proof_types.len() >= self.min_proofs
+            && self
+                .immediate_proof_types
+                .iter()
+                .all(|prover_type| ready_proof_types.contains(&prover_type))
+    }
+}
+
+/// Reads the proof policy from the OnChainProposer. Contracts without a proof
+/// policy require every needed proof type.
+pub async fn get_proof_policy(
+    eth_client: &EthClient,
+    on_chain_proposer_address: Address,
+    needed_proof_types: &[ProverType],
+) -> ProofPolicy {
+    match (
+        get_min_proofs_to_verify(eth_client, on_chain_proposer_address).await,
+        get_immediate_proofs(eth_client, on_chain_proposer_address).await,
+        get_proof_upgrade_window(eth_client, on_chain_proposer_address).await,
+    ) {
+        (Ok(min_proofs), Ok(immediate_proofs), Ok(upgrade_window)) => ProofPolicy::from_contract(
+            min_proofs,
+            immediate_proofs,
+            upgrade_window,
+            needed_proof_types,
+        ),
+        (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => {
+            debug!("Failed to get the proof policy, requiring every proof: {err}");
+            ProofPolicy::all_proofs(needed_proof_types)
+        }
+    }
+}
+
 pub fn resolve_aligned_network(network: &str) -> Network {
     match network {
         "devnet" => Network::Devnet,
@@ -192,3 +292,83 @@ pub fn get_git_commit_hash() -> String {
 pub fn batch_checkpoint_name(batch_number: u64) -> String {
     format!("checkpoint_batch_{batch_number}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEEDED: [ProverType; 3] = [ProverType::RISC0, ProverType::SP1, ProverType::TDX];
+
+    #[test]
+    fn zero_policy_requires_every_proof() {
+        let policy = ProofPolicy::from_contract(0, 0, 86400, &NEEDED);
+        assert_eq!(policy, ProofPolicy::all_proofs(&NEEDED));
+        assert!(!policy.is_optimistic(&NEEDED));
+        assert!(!policy.can_verify(&[ProverType::RISC0, ProverType::SP1]));
+        assert!(policy.can_verify(&NEEDED));
+    }
+
+    #[test]
+    fn two_of_three_accepts_any_combination() {
+        let policy = ProofPolicy::from_contract(2, 0, 86400, &NEEDED);
+        assert_eq!(policy.min_proofs, 2);
+        assert!(policy.immediate_proof_types.is_empty());
+        assert!(policy.is_optimistic(&NEEDED));
+        assert!(policy.can_verify(&[ProverType::RISC0, ProverType::SP1]));
+        assert!(policy.can_verify(&[ProverType::RISC0, ProverType::TDX]));
+        assert!(policy.can_verify(&[ProverType::SP1, ProverType::TDX]));
+        assert!(policy.can_verify(&NEEDED));
+        for prover_type in NEEDED {
+            assert!(!policy.can_verify(&[prover_type]));
+        }
+    }
+
+    #[test]
+    fn bitmap_requires_specific_provers() {
+        // TDX immediately, the ZK proofs within the upgrade window
+        let policy = ProofPolicy::from_contract(0, 4, 86400, &NEEDED);
+        assert_eq!(policy.min_proofs, 1);
+        assert_eq!(policy.immediate_proof_types, vec![ProverType::TDX]);
+        assert_eq!(policy.upgrade_window, 86400);
+        assert!(policy.is_optimistic(&NEEDED));
+        assert!(policy.can_verify(&[ProverType::TDX]));
+        // Having as many proofs isn't enough without the required prover
+        assert!(!policy.can_verify(&[ProverType::RISC0]));
+        assert!(!policy.can_verify(&[ProverType::RISC0, ProverType::SP1]));
+    }
+
+    #[test]
+    fn minimum_and_bitmap_are_both_enforced() {
+        // Two proofs, one of them TDX
+        let policy = ProofPolicy::from_contract(2, 4, 86400, &NEEDED);
+        assert!(policy.can_verify(&[ProverType::TDX, ProverType::SP1]));
+        assert!(policy.can_verify(&[ProverType::RISC0, ProverType::TDX]));
+        assert!(!policy.can_verify(&[ProverType::TDX]));
+        assert!(!policy.can_verify(&[ProverType::RISC0, ProverType::SP1]));
+    }
+
+    #[test]
+    fn policy_ignores_proof_types_that_are_not_needed() {
+        let needed = [ProverType::RISC0, ProverType::TDX];
+        let policy = ProofPolicy::from_contract(0, 1 | 2, 3600, &needed);
+        assert_eq!(policy.immediate_proof_types, vec![ProverType::RISC0]);
+        assert!(policy.can_verify(&[ProverType::RISC0]));
+
+        // Only proof types that aren't needed means every needed one
+        let policy = ProofPolicy::from_contract(0, 2, 3600, &needed);
+        assert_eq!(policy, ProofPolicy::all_proofs(&needed));
+
+        // More proofs than needed proof types means every needed one
+        let policy = ProofPolicy::from_contract(3, 0, 3600, &needed);
+        assert_eq!(policy, ProofPolicy::all_proofs(&needed));
+    }
+
+    #[test]
+    fn exec_only_policy_is_not_optimistic() {
+        let needed = [ProverType::Exec];
+        let policy = ProofPolicy::from_contract(0, 4, 3600, &needed);
+        assert_eq!(policy, ProofPolicy::all_proofs(&needed));
+        assert!(!policy.is_optimistic(&needed));
+        assert!(policy.can_verify(&[ProverType::Exec]));
+    }
+}
@@ -171,6 +171,42 @@ pub trait StoreEngineRollup: Debug + Send + Sync {
         proof_type: ProverType,
     ) -> Result<(), RollupStoreError>;
 
+    /// Stores a proof of the batches `first_batch..=last_batch` produced by
+    /// aggregating their individual proofs.
+    async fn store_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        proof_type: ProverType,
+        proof: BatchProof,
+    ) -> Result<(), RollupStoreError>;
+
+    /// Returns the last batch covered and the aggregated proof starting at `first_batch`.
+    async fn get_aggregated_proof(
+        &self,
+        first_batch: u64,
+        proof_type: ProverType,
+    ) -> Result<Option<(u64, BatchProof)>, RollupStoreError>;
+
+    async fn delete_aggregated_proof(
+        &self,
+        first_batch: u64,
+        proof_type: ProverType,
+    ) -> Result<(), RollupStoreError>;
+
+    /// Stores the types of the proofs of a batch that were sent to the L1.
+    async fn store_sent_proof_types_by_batch(
+        &self,
+        batch_number: u64,
+        proof_types: Vec<ProverType>,
+    ) -> Result<(), RollupStoreError>;
+
+    /// Returns the types of the proofs of a batch that were sent to the L1.
+    async fn get_sent_proof_types_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<Vec<ProverType>, RollupStoreError>;
+
     async fn revert_to_batch(&self, batch_number: u64) -> Result<(), RollupStoreError>;
 
     async fn store_prover_input_by_batch_and_version(
@@ -411,6 +411,57 @@ impl Store {
             .await
     }
 
+    pub async fn store_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        proof_type: ProverType,
+        proof: BatchProof,
+    ) -> Result<(), RollupStoreError> {
+        self.engine
+            .store_aggregated_proof(first_batch, last_batch, proof_type, proof)
+            .await
+    }
+
+    pub async fn get_aggregated_proof(
+        &self,
+        first_batch: u64,
+        proof_type: ProverType,
+    ) -> Result<Option<(u64, BatchProof)>, RollupStoreError> {
+        self.engine
+            .get_aggregated_proof(first_batch, proof_type)
+            .await
+    }
+
+    pub async fn delete_aggregated_proof(
+        &self,
+        first_batch: u64,
+        proof_type: ProverType,
+    ) -> Result<(), RollupStoreError> {
+        self.engine
+            .delete_aggregated_proof(first_batch, proof_type)
+            .await
+    }
+
+    pub async fn store_sent_proof_types_by_batch(
+        &self,
+        batch_number: u64,
+        proof_types: Vec<ProverType>,
+    ) -> Result<(), RollupStoreError> {
+        self.engine
+            .store_sent_proof_types_by_batch(batch_number, proof_types)
+            .await
+    }
+
+    pub async fn get_sent_proof_types_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<Vec<ProverType>, RollupStoreError> {
+        self.engine
+            .get_sent_proof_types_by_batch(batch_number)
+            .await
+    }
+
     /// Reverts to a previous batch, discarding operations in them
     pub async fn revert_to_batch(&self, batch_number: u64) -> Result<(), RollupStoreError> {
         self.engine.revert_to_batch(batch_number).await
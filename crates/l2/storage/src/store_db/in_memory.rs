@@ -51,6 +51,10 @@ struct StoreInner {
     account_updates_by_block_number: HashMap<BlockNumber, Vec<AccountUpdate>>,
     /// Map of (ProverType, batch_number) to batch proof data
     batch_proofs: HashMap<(ProverType, u64), BatchProof>,
+    /// Map of (ProverType, first_batch) to the last batch covered and the aggregated proof
+    aggregated_proofs: HashMap<(ProverType, u64), (u64, BatchProof)>,
+    /// Map of batch number to the types of its proofs sent to the L1
+    sent_proof_types: HashMap<u64, Vec<ProverType>>,
     /// Map of batch number to commit transaction hash
     commit_txs: HashMap<u64, H256>,
     /// Map of batch number to verify transaction hash
@@ -303,6 +307,65 @@ impl StoreEngineRollup for Store {
             .cloned())
     }
 
+    async fn store_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        proof_type: ProverType,
+        proof: BatchProof,
+    ) -> Result<(), RollupStoreError> {
+        self.inner()?
+            .aggregated_proofs
+            .insert((proof_type, first_batch), (last_batch, proof));
+        Ok(())
+    }
+
+    async fn get_aggregated_proof(
+        &self,
+        first_batch: u64,
+        proof_type: ProverType,
+    ) -> Result<Option<(u64, BatchProof)>, RollupStoreError> {
+        Ok(self
+            .inner()?
+            .aggregated_proofs
+            .get(&(proof_type, first_batch))
+            .cloned())
+    }
+
+    async fn delete_aggregated_proof(
+        &self,
+        first_batch: u64,
+        proof_type: ProverType,
+    ) -> Result<(), RollupStoreError> {
+        self.inner()?
+            .aggregated_proofs
+            .remove(&(proof_type, first_batch));
+        Ok(())
+    }
+
+    async fn store_sent_proof_types_by_batch(
+        &self,
+        batch_number: u64,
+        proof_types: Vec<ProverType>,
+    ) -> Result<(), RollupStoreError> {
+        self.inner()?
+            .sent_proof_types
+            .insert(batch_number, proof_types);
+        Ok(())
+    }
+
+    async fn get_sent_proof_types_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<Vec<ProverType>, RollupStoreError> {
+        Ok(self
+            .inner()?
+            .sent_proof_types
+            .get(&batch_number)
+            .cloned()
+            .unwrap_or_default())
+    }
+
     async fn get_non_privileged_transactions_by_batch(
         &self,
         batch_number: u64,
@@ -333,6 +396,12 @@ impl StoreEngineRollup for Store {
         store
             .batch_prover_input
             .retain(|(batch, _), _| *batch <= batch_number);
+        store
+            .aggregated_proofs
+            .retain(|_, (last_batch, _)| *last_batch <= batch_number);
+        store
+            .sent_proof_types
+            .retain(|batch, _| *batch <= batch_number);
         Ok(())
     }
 
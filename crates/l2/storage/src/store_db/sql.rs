@@ -32,7 +32,7 @@ impl Debug for SQLStore {
     }
 }
 
-const DB_SCHEMA: [&str; 20] = [
+const DB_SCHEMA: [&str; 22] = [
     "CREATE TABLE IF NOT EXISTS blocks (block_number INT PRIMARY KEY, batch INT)",
     "CREATE TABLE IF NOT EXISTS l1_messages (batch INT, idx INT, message_hash BLOB, PRIMARY KEY (batch, idx))",
     "CREATE TABLE IF NOT EXISTS l2_rolling_hashes (batch INT PRIMARY KEY, value BLOB)",
@@ -49,6 +49,8 @@ const DB_SCHEMA: [&str; 20] = [
     "CREATE TABLE IF NOT EXISTS latest_sent (_id INT PRIMARY KEY, batch INT)",
     "INSERT INTO latest_sent VALUES (0, 0) ON CONFLICT(_id) DO NOTHING",
     "CREATE TABLE IF NOT EXISTS batch_proofs (batch INT, prover_type INT, proof BLOB, PRIMARY KEY (batch, prover_type))",
+    "CREATE TABLE IF NOT EXISTS aggregated_proofs (first_batch INT, prover_type INT, last_batch INT, proof BLOB, PRIMARY KEY (first_batch, prover_type))",
+    "CREATE TABLE IF NOT EXISTS sent_proof_types (batch INT PRIMARY KEY, prover_types BLOB)",
     "CREATE TABLE IF NOT EXISTS block_signatures (block_hash BLOB PRIMARY KEY, signature BLOB)",
     "CREATE TABLE IF NOT EXISTS batch_signatures (batch INT PRIMARY KEY, signature BLOB)",
     "CREATE TABLE IF NOT EXISTS batch_prover_input (batch INT, prover_version TEXT, prover_input BLOB, PRIMARY KEY (batch, prover_version))",
@@ -813,6 +815,14 @@ impl StoreEngineRollup for SQLStore {
                 "DELETE FROM batch_prover_input WHERE batch > ?1",
                 [batch_number].into_params()?,
             ),
+            (
+                "DELETE FROM aggregated_proofs WHERE last_batch > ?1",
+                [batch_number].into_params()?,
+            ),
+            (
+                "DELETE FROM sent_proof_types WHERE batch > ?1",
+                [batch_number].into_params()?,
+            ),
         ];
         self.execute_in_tx(queries, None).await
     }
@@ -861,6 +871,102 @@ impl StoreEngineRollup for SQLStore {
         Ok(None)
     }
 
+    async fn store_aggregated_proof(
+        &self,
+        first_batch: u64,
+        last_batch: u64,
+        prover_type: ProverType,
+        proof: BatchProof,
+    ) -> Result<(), RollupStoreError> {
+        let serialized_proof = bincode::serialize(&proof)?;
+        let prover_type: u32 = prover_type.into();
+        self.execute_in_tx(
+            vec![
+                (
+                    "DELETE FROM aggregated_proofs WHERE first_batch = ?1 AND prover_type = ?2",
+                    (first_batch, prover_type).into_params()?,
+                ),
+                (
+                    "INSERT INTO aggregated_proofs VALUES (?1, ?2, ?3, ?4)",
+                    (first_batch, prover_type, last_batch, serialized_proof).into_params()?,
+                ),
+            ],
+            None,
+        )
+        .await
+    }
+
+    async fn get_aggregated_proof(
+        &self,
+        first_batch: u64,
+        prover_type: ProverType,
+    ) -> Result<Option<(u64, BatchProof)>, RollupStoreError> {
+        let prover_type: u32 = prover_type.into();
+        let mut rows = self
+            .query(
+                "SELECT last_batch, proof from aggregated_proofs WHERE first_batch = ?1 AND prover_type = ?2",
+                (first_batch, prover_type),
+            )
+            .await?;
+
+        if let Some(row) = rows.next().await? {
+            let last_batch = read_from_row_int(&row, 0)?;
+            let vec = read_from_row_blob(&row, 1)?;
+            return Ok(Some((last_batch, bincode::deserialize(&vec)?)));
+        }
+        Ok(None)
+    }
+
+    async fn delete_aggregated_proof(
+        &self,
+        first_batch: u64,
+        prover_type: ProverType,
+    ) -> Result<(), RollupStoreError> {
+        let prover_type: u32 = prover_type.into();
+        self.execute_in_tx(
+            vec![(
+                "DELETE FROM aggregated_proofs WHERE first_batch = ?1 AND prover_type = ?2",
+                (first_batch, prover_type).into_params()?,
+            )],
+            None,
+        )
+        .await
+    }
+
+    async fn store_sent_proof_types_by_batch(
+        &self,
+        batch_number: u64,
+        proof_types: Vec<ProverType>,
+    ) -> Result<(), RollupStoreError> {
+        let serialized_proof_types = bincode::serialize(&proof_types)?;
+        self.execute_in_tx(
+            vec![(
+                "INSERT OR REPLACE INTO sent_proof_types VALUES (?1, ?2)",
+                (batch_number, serialized_proof_types).into_params()?,
+            )],
+            None,
+        )
+        .await
+    }
+
+    async fn get_sent_proof_types_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<Vec<ProverType>, RollupStoreError> {
+        let mut rows = self
+            .query(
+                "SELECT prover_types FROM sent_proof_types WHERE batch = ?1",
+                vec![batch_number],
+            )
+            .await?;
+
+        if let Some(row) = rows.next().await? {
+            let vec = read_from_row_blob(&row, 0)?;
+            return Ok(bincode::deserialize(&vec)?);
+        }
+        Ok(Vec::new())
+    }
+
     async fn seal_batch(&self, batch: Batch) -> Result<(), RollupStoreError> {
         let conn = self.write_conn.lock().await;
         let transaction = conn.transaction().await?;
@@ -1096,6 +1202,8 @@ mod tests {
             "operation_count",
             "latest_sent",
             "batch_proofs",
+            "aggregated_proofs",
+            "sent_proof_types",
             "block_signatures",
             "batch_signatures",
             "batch_prover_input",
@@ -1139,6 +1247,12 @@ mod tests {
                 ("batch_proofs", "batch") => "INT",
                 ("batch_proofs", "prover_type") => "INT",
                 ("batch_proofs", "proof") => "BLOB",
+                ("aggregated_proofs", "first_batch") => "INT",
+                ("aggregated_proofs", "prover_type") => "INT",
+                ("aggregated_proofs", "last_batch") => "INT",
+                ("aggregated_proofs", "proof") => "BLOB",
+                ("sent_proof_types", "batch") => "INT",
+                ("sent_proof_types", "prover_types") => "BLOB",
                 ("block_signatures", "block_hash") => "BLOB",
                 ("block_signatures", "signature") => "BLOB",
                 ("batch_signatures", "batch") => "INT",
@@ -1,19 +1,20 @@
 use crate::rlpx::connection::server::send;
 use crate::rlpx::l2::messages::{BatchSealed, L2Message, NewBlock};
 use crate::rlpx::{connection::server::Established, error::PeerConnectionError, message::Message};
-use ethereum_types::Address;
-use ethereum_types::Signature;
+use bytes::Bytes;
+use ethereum_types::{Address, H256, Signature};
 use ethrex_blockchain::error::ChainError;
 use ethrex_blockchain::fork_choice::apply_fork_choice;
-use ethrex_common::types::{Block, recover_address};
+use ethrex_blockchain::{Blockchain, BlockchainType};
+use ethrex_common::errors::EcdsaError;
+use ethrex_common::types::{Block, fee_config::FeeConfig, recover_address_from_message};
 use ethrex_storage_rollup::StoreRollup;
-use secp256k1::{Message as SecpMessage, SecretKey};
 use std::collections::BTreeMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tokio::time::Instant;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info};
 
-use super::messages::batch_hash;
+use super::messages::{batch_hash, block_signing_hash};
 use super::{PERIODIC_BATCH_BROADCAST_INTERVAL, PERIODIC_BLOCK_BROADCAST_INTERVAL};
 
 #[derive(Debug, Clone)]
@@ -23,7 +24,9 @@ pub struct L2ConnectedState {
     pub latest_batch_sent: u64,
     pub blocks_on_queue: BTreeMap<u64, Arc<Block>>,
     pub store_rollup: StoreRollup,
-    pub committer_key: Arc<SecretKey>,
+    pub lead_sequencer: LeadSequencer,
+    /// Lead sequencer term this connection's block tracking belongs to.
+    pub lead_sequencer_term: u64,
     pub next_block_broadcast: Instant,
     pub next_batch_broadcast: Instant,
 }
@@ -31,7 +34,60 @@ pub struct L2ConnectedState {
 #[derive(Debug, Clone)]
 pub struct P2PBasedContext {
     pub store_rollup: StoreRollup,
-    pub committer_key: Arc<SecretKey>,
+    pub lead_sequencer: LeadSequencer,
+}
+
+/// Lead sequencer this node follows, shared between the sequencer, which keeps
+/// it up to date, and every L2 connection, which only accepts blocks and
+/// batches signed by it.
+#[derive(Debug, Clone, Default)]
+pub struct LeadSequencer(Arc<RwLock<LeadSequencerState>>);
+
+#[derive(Debug, Clone, Copy, Default)]
+struct LeadSequencerState {
+    address: Option<Address>,
+    /// Increased on every handoff so connections notice the lead changed.
+    term: u64,
+    /// Head of the chain right after the last handoff. Blocks after it are
+    /// expected from the new lead sequencer.
+    handoff_block: u64,
+}
+
+impl LeadSequencer {
+    pub fn new(address: Option<Address>) -> Self {
+        Self(Arc::new(RwLock::new(LeadSequencerState {
+            address,
+            ..Default::default()
+        })))
+    }
+
+    pub fn address(&self) -> Option<Address> {
+        self.state().address
+    }
+
+    /// Whether `signer` is the current lead sequencer.
+    pub fn is_lead(&self, signer: Address) -> bool {
+        self.address() == Some(signer)
+    }
+
+    /// Switches to a new lead sequencer. `handoff_block` is the block the chain
+    /// was reverted to, the blocks after it built by the previous lead are discarded.
+    pub fn hand_off(&self, address: Address, handoff_block: u64) {
+        let mut state = self
+            .0
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.address = Some(address);
+        state.term += 1;
+        state.handoff_block = handoff_block;
+    }
+
+    fn state(&self) -> LeadSequencerState {
+        *self
+            .0
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -97,7 +153,8 @@ impl L2ConnState {
                     blocks_on_queue: BTreeMap::new(),
                     latest_batch_sent: 0,
                     store_rollup: ctxt.store_rollup.clone(),
-                    committer_key: ctxt.committer_key.clone(),
+                    lead_sequencer_term: ctxt.lead_sequencer.state().term,
+                    lead_sequencer: ctxt.lead_sequencer.clone(),
                     next_block_broadcast: Instant::now() + PERIODIC_BLOCK_BROADCAST_INTERVAL,
                     next_batch_broadcast: Instant::now() + PERIODIC_BATCH_BROADCAST_INTERVAL,
                 };
@@ -109,9 +166,60 @@ impl L2ConnState {
     }
 }
 
-fn validate_signature(_recovered_lead_sequencer: Address) -> bool {
-    // Until the RPC module can be included in the P2P crate, we skip the validation
-    true
+/// Recovers the signer of `hash`. Blocks and batches are signed with the
+/// sequencer's committer signer, which hashes the signed data before signing it.
+fn recover_signer(signature: Signature, hash: H256) -> Result<Address, EcdsaError> {
+    recover_address_from_message(signature, &Bytes::copy_from_slice(hash.as_bytes()))
+}
+
+/// Recovers the signer of `hash` and checks it is the current lead sequencer.
+async fn validate_signature(
+    established: &Established,
+    signature: Signature,
+    hash: H256,
+) -> Result<bool, PeerConnectionError> {
+    let recovered_lead_sequencer =
+        tokio::task::spawn_blocking(move || recover_signer(signature, hash))
+            .await
+            .map_err(|_| {
+                PeerConnectionError::InternalError("Recover Address task failed".to_string())
+            })?
+            .map_err(|e| {
+                error!(
+                    peer=%established.node,
+                    error=%e,
+                    "Failed to recover lead sequencer",
+                );
+                PeerConnectionError::CryptographyError(e.to_string())
+            })?;
+
+    let lead_sequencer = &established.l2_state.connection_state()?.lead_sequencer;
+    if !lead_sequencer.is_lead(recovered_lead_sequencer) {
+        debug!(
+            peer=%established.node,
+            signer=?recovered_lead_sequencer,
+            lead_sequencer=?lead_sequencer.address(),
+            "Ignoring message not signed by the lead sequencer",
+        );
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// After a lead sequencer handoff the chain was reverted to the last committed
+/// block, so the blocks queued or sent from the previous lead are discarded and
+/// tracking restarts from the handoff block.
+fn sync_lead_sequencer_term(l2_state: &mut L2ConnectedState) {
+    let lead_sequencer = l2_state.lead_sequencer.state();
+    if lead_sequencer.term == l2_state.lead_sequencer_term {
+        return;
+    }
+    l2_state.lead_sequencer_term = lead_sequencer.term;
+    l2_state.blocks_on_queue.clear();
+    l2_state.latest_block_added = l2_state
+        .latest_block_added
+        .min(lead_sequencer.handoff_block);
+    l2_state.latest_block_sent = l2_state.latest_block_sent.min(lead_sequencer.handoff_block);
 }
 
 pub(crate) async fn handle_based_capability_message(
@@ -203,10 +311,11 @@ pub(crate) async fn send_new_block(
     established: &mut Established,
 ) -> Result<(), PeerConnectionError> {
     let latest_block_number = established.storage.get_latest_block_number().await?;
-    let latest_block_sent = established
-        .l2_state
-        .connection_state_mut()?
-        .latest_block_sent;
+    let latest_block_sent = {
+        let l2_state = established.l2_state.connection_state_mut()?;
+        sync_lead_sequencer_term(l2_state);
+        l2_state.latest_block_sent
+    };
     for block_number in latest_block_sent + 1..=latest_block_number {
         let new_block_msg = {
             let l2_state = established.l2_state.connection_state_mut()?;
@@ -231,39 +340,25 @@ pub(crate) async fn send_new_block(
                 header: new_block_header,
                 body: new_block_body,
             };
-            let signature = match l2_state
+            // Only blocks signed by a lead sequencer are gossiped, the ones
+            // fetched from L1 without a signature are synced from L1 by every node.
+            let Some(signature) = l2_state
                 .store_rollup
                 .get_signature_by_block(new_block.hash())
                 .await?
-            {
-                Some(sig) => sig,
-                None => {
-                    let (recovery_id, signature) = secp256k1::SECP256K1
-                        .sign_ecdsa_recoverable(
-                            &SecpMessage::from_digest(new_block.hash().to_fixed_bytes()),
-                            &l2_state.committer_key,
-                        )
-                        .serialize_compact();
-                    let recovery_id: u8 =
-                        Into::<i32>::into(recovery_id).try_into().map_err(|e| {
-                            PeerConnectionError::InternalError(format!(
-                                "Failed to convert recovery id to u8: {e}. This is a bug."
-                            ))
-                        })?;
-                    let mut sig = [0u8; 65];
-                    sig[..64].copy_from_slice(&signature);
-                    sig[64] = recovery_id;
-                    let signature = Signature::from_slice(&sig);
-                    l2_state
-                        .store_rollup
-                        .store_signature_by_block(new_block.hash(), signature)
-                        .await?;
-                    signature
-                }
+            else {
+                debug!("Block {block_number} has no lead sequencer signature, not broadcasting it");
+                l2_state.latest_block_sent = block_number;
+                continue;
             };
+            let fee_config = l2_state
+                .store_rollup
+                .get_fee_config_by_block(block_number)
+                .await?;
             NewBlock {
                 block: new_block.into(),
                 signature,
+                fee_config,
             }
         };
 
@@ -286,6 +381,7 @@ async fn should_process_new_block(
         debug!("Not processing new block, blockchain is not synced");
         return Ok(false);
     }
+    sync_lead_sequencer_term(l2_state);
     if l2_state.latest_block_added >= msg.block.header.number
         || l2_state
             .blocks_on_queue
@@ -299,30 +395,23 @@ async fn should_process_new_block(
     }
 
     let block_hash = msg.block.hash();
+    // The fee config is only stored if the lead sequencer signed it along with the block
+    let signing_hash = block_signing_hash(block_hash, msg.fee_config.as_ref());
 
-    let msg_signature = msg.signature;
-    let recovered_lead_sequencer =
-        tokio::task::spawn_blocking(move || recover_address(msg_signature, block_hash))
-            .await
-            .map_err(|_| {
-                PeerConnectionError::InternalError("Recover Address task failed".to_string())
-            })?
-            .map_err(|e| {
-                error!(
-                    peer=%established.node,
-                    error=%e,
-                    "Failed to recover lead sequencer",
-                );
-                PeerConnectionError::CryptographyError(e.to_string())
-            })?;
-
-    if !validate_signature(recovered_lead_sequencer) {
+    if !validate_signature(established, msg.signature, signing_hash).await? {
         return Ok(false);
     }
+    let l2_state = established.l2_state.connection_state_mut()?;
     l2_state
         .store_rollup
         .store_signature_by_block(block_hash, msg.signature)
         .await?;
+    if let Some(fee_config) = msg.fee_config {
+        l2_state
+            .store_rollup
+            .store_fee_config_by_block(msg.block.header.number, fee_config)
+            .await?;
+    }
     Ok(true)
 }
 
@@ -357,18 +446,10 @@ async fn should_process_batch_sealed(
 
     let hash = batch_hash(&msg.batch);
 
-    let recovered_lead_sequencer = recover_address(msg.signature, hash).map_err(|e| {
-        error!(
-            peer=%established.node,
-            error=%e,
-            "Failed to recover lead sequencer",
-        );
-        PeerConnectionError::CryptographyError(e.to_string())
-    })?;
-
-    if !validate_signature(recovered_lead_sequencer) {
+    if !validate_signature(established, msg.signature, hash).await? {
         return Ok(false);
     }
+    let l2_state = established.l2_state.connection_state_mut()?;
     l2_state
         .store_rollup
         .store_signature_by_batch(msg.batch.number, msg.signature)
@@ -400,6 +481,14 @@ async fn process_new_block(
         let block = Arc::<Block>::try_unwrap(block).map_err(|_| {
             PeerConnectionError::InternalError("Failed to take ownership of block".to_string())
         })?;
+        // The block must be executed with the same fee config the lead sequencer built it with
+        if let Some(fee_config) = l2_state
+            .store_rollup
+            .get_fee_config_by_block(block_number)
+            .await?
+        {
+            set_fee_config(&established.blockchain, fee_config)?;
+        }
         established
             .blockchain
             .add_block_pipeline(block)
@@ -431,6 +520,20 @@ async fn process_new_block(
     Ok(())
 }
 
+fn set_fee_config(
+    blockchain: &Blockchain,
+    fee_config: FeeConfig,
+) -> Result<(), PeerConnectionError> {
+    let BlockchainType::L2(l2_config) = &blockchain.options.r#type else {
+        return Ok(());
+    };
+    let mut fee_config_guard = l2_config.fee_config.write().map_err(|_| {
+        PeerConnectionError::InternalError("Fee config lock was poisoned".to_string())
+    })?;
+    *fee_config_guard = fee_config;
+    Ok(())
+}
+
 pub(crate) async fn send_sealed_batch(
     established: &mut Established,
 ) -> Result<(), PeerConnectionError> {
@@ -452,29 +555,19 @@ pub(crate) async fn send_sealed_batch(
         else {
             return Ok(());
         };
-        match l2_state
+        // As with blocks, only batches sealed by a lead sequencer are gossiped
+        let Some(signature) = l2_state
             .store_rollup
             .get_signature_by_batch(next_batch_to_send)
-            .await
-            .inspect_err(|err| {
-                warn!(
-                    "Fetching signature from store returned an error, \
-             defaulting to signing with committer key: {err}"
-                )
-            }) {
-            Ok(Some(recovered_sig)) => BatchSealed::new(batch, recovered_sig),
-            Ok(None) | Err(_) => {
-                let msg = BatchSealed::from_batch_and_key(
-                    batch,
-                    l2_state.committer_key.clone().as_ref(),
-                )?;
-                l2_state
-                    .store_rollup
-                    .store_signature_by_batch(msg.batch.number, msg.signature)
-                    .await?;
-                msg
-            }
-        }
+            .await?
+        else {
+            debug!(
+                "Batch {next_batch_to_send} has no lead sequencer signature, not broadcasting it"
+            );
+            l2_state.latest_batch_sent = next_batch_to_send;
+            return Ok(());
+        };
+        BatchSealed::new(batch, signature)
     };
     let batch_sealed_msg: Message = batch_sealed_msg.into();
     send(established, batch_sealed_msg).await?;
@@ -498,9 +591,156 @@ async fn process_batch_sealed(
     Ok(())
 }
 
-// These tests are disabled because they previously assumed
-// the connection used the old struct RLPxConnection, but
-// the new GenServer approach changes a lot of things,
-// this will be eventually addressed (#3563)
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use ethrex_common::utils::keccak;
+    use secp256k1::{Message as SecpMessage, SECP256K1, SecretKey};
+
+    use super::*;
+
+    fn secret_key(byte: u8) -> SecretKey {
+        SecretKey::from_slice(&[byte; 32]).expect("valid secret key")
+    }
+
+    fn address_of(secret_key: &SecretKey) -> Address {
+        Address::from(keccak(
+            &secret_key.public_key(SECP256K1).serialize_uncompressed()[1..],
+        ))
+    }
+
+    /// Signs `hash` the way the sequencer's local signer does.
+    fn sign(secret_key: &SecretKey, hash: H256) -> Signature {
+        let digest = keccak(hash.as_bytes());
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&SecpMessage::from_digest(digest.0), secret_key)
+            .serialize_compact();
+        let mut bytes = signature.to_vec();
+        bytes.push(u8::try_from(i32::from(recovery_id)).expect("recovery id fits in a byte"));
+        Signature::from_slice(&bytes)
+    }
+
+    fn connected_state(lead_sequencer: LeadSequencer) -> L2ConnectedState {
+        L2ConnectedState {
+            latest_block_sent: 0,
+            latest_block_added: 0,
+            latest_batch_sent: 0,
+            blocks_on_queue: BTreeMap::new(),
+            store_rollup: StoreRollup::default(),
+            lead_sequencer_term: lead_sequencer.state().term,
+            lead_sequencer,
+            next_block_broadcast: Instant::now(),
+            next_batch_broadcast: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn accepts_messages_signed_by_the_lead_sequencer() {
+        let lead = secret_key(1);
+        let lead_sequencer = LeadSequencer::new(Some(address_of(&lead)));
+        let hash = H256::repeat_byte(0xab);
+
+        let signer = recover_signer(sign(&lead, hash), hash).expect("recoverable signature");
+
+        assert_eq!(signer, address_of(&lead));
+        assert!(lead_sequencer.is_lead(signer));
+    }
+
+    #[test]
+    fn rejects_messages_signed_by_other_sequencers() {
+        let lead = secret_key(1);
+        let other = secret_key(2);
+        let lead_sequencer = LeadSequencer::new(Some(address_of(&lead)));
+        let hash = H256::repeat_byte(0xab);
+
+        let signer = recover_signer(sign(&other, hash), hash).expect("recoverable signature");
+        assert!(!lead_sequencer.is_lead(signer));
+
+        // A signature over other data doesn't recover the lead sequencer either
+        let signer = recover_signer(sign(&lead, H256::repeat_byte(0xcd)), hash)
+            .expect("recoverable signature");
+        assert!(!lead_sequencer.is_lead(signer));
+
+        // Nothing is accepted until the lead sequencer is known
+        let signer = recover_signer(sign(&lead, hash), hash).expect("recoverable signature");
+        assert!(!LeadSequencer::default().is_lead(signer));
+    }
+
+    #[test]
+    fn fee_configs_are_only_accepted_with_the_signature_of_the_block() {
+        let lead = secret_key(1);
+        let lead_sequencer = LeadSequencer::new(Some(address_of(&lead)));
+        let block_hash = H256::repeat_byte(0xab);
+        let fee_config = FeeConfig {
+            base_fee_vault: Some(Address::repeat_byte(0xfe)),
+            ..Default::default()
+        };
+        let signature = sign(&lead, block_signing_hash(block_hash, Some(&fee_config)));
+
+        let signer = recover_signer(signature, block_signing_hash(block_hash, Some(&fee_config)))
+            .expect("recoverable signature");
+        assert!(lead_sequencer.is_lead(signer));
+
+        // A peer relaying the signed block with another fee config
+        let forged_fee_config = FeeConfig {
+            base_fee_vault: Some(Address::repeat_byte(0x66)),
+            ..Default::default()
+        };
+        let signer = recover_signer(
+            signature,
+            block_signing_hash(block_hash, Some(&forged_fee_config)),
+        )
+        .expect("recoverable signature");
+        assert!(!lead_sequencer.is_lead(signer));
+
+        // Or without it
+        let signer = recover_signer(signature, block_signing_hash(block_hash, None))
+            .expect("recoverable signature");
+        assert!(!lead_sequencer.is_lead(signer));
+    }
+
+    #[test]
+    fn handoff_switches_the_accepted_signer() {
+        let previous = address_of(&secret_key(1));
+        let next = address_of(&secret_key(2));
+        let lead_sequencer = LeadSequencer::new(Some(previous));
+
+        lead_sequencer.hand_off(next, 10);
+
+        assert_eq!(lead_sequencer.address(), Some(next));
+        assert!(lead_sequencer.is_lead(next));
+        assert!(!lead_sequencer.is_lead(previous));
+    }
+
+    #[test]
+    fn handoff_discards_blocks_from_the_previous_lead() {
+        let lead_sequencer = LeadSequencer::new(Some(Address::repeat_byte(1)));
+        let mut l2_state = connected_state(lead_sequencer.clone());
+        l2_state.latest_block_added = 15;
+        l2_state.latest_block_sent = 14;
+        l2_state
+            .blocks_on_queue
+            .insert(17, Arc::new(Block::default()));
+
+        // Without a handoff nothing changes
+        sync_lead_sequencer_term(&mut l2_state);
+        assert_eq!(l2_state.latest_block_added, 15);
+        assert_eq!(l2_state.blocks_on_queue.len(), 1);
+
+        // The chain was reverted to block 12 when the lead changed
+        lead_sequencer.hand_off(Address::repeat_byte(2), 12);
+        sync_lead_sequencer_term(&mut l2_state);
+
+        assert_eq!(l2_state.latest_block_added, 12);
+        assert_eq!(l2_state.latest_block_sent, 12);
+        assert!(l2_state.blocks_on_queue.is_empty());
+        assert_eq!(l2_state.lead_sequencer_term, 1);
+
+        // Tracking behind the handoff block is kept
+        l2_state.latest_block_added = 13;
+        l2_state.latest_block_sent = 10;
+        lead_sequencer.hand_off(Address::repeat_byte(3), 20);
+        sync_lead_sequencer_term(&mut l2_state);
+        assert_eq!(l2_state.latest_block_added, 13);
+        assert_eq!(l2_state.latest_block_sent, 10);
+    }
+}
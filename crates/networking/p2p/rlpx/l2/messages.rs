@@ -1,17 +1,15 @@
 use crate::rlpx::{
-    error::PeerConnectionError,
     message::{Message, RLPxMessage},
     utils::{snappy_compress, snappy_decompress},
 };
-use bytes::BufMut;
+use bytes::{BufMut, Bytes};
 use ethrex_common::utils::keccak;
 use ethrex_common::{
     H256, Signature,
-    types::{Block, batch::Batch},
+    types::{Block, batch::Batch, fee_config::FeeConfig},
 };
 use ethrex_rlp::error::{RLPDecodeError, RLPEncodeError};
 use ethrex_rlp::structs::{Decoder, Encoder};
-use secp256k1::{Message as SecpMessage, SecretKey};
 use std::{ops::Deref as _, sync::Arc};
 
 #[derive(Debug, Clone)]
@@ -24,6 +22,9 @@ pub struct NewBlock {
     // when broadcasting this message.
     pub block: Arc<Block>,
     pub signature: Signature,
+    /// Fee config the block was built with, needed by followers to re-execute it.
+    /// Optional at the end of the message to stay compatible with peers that don't send it.
+    pub fee_config: Option<FeeConfig>,
 }
 
 impl RLPxMessage for NewBlock {
@@ -34,6 +35,11 @@ impl RLPxMessage for NewBlock {
         Encoder::new(&mut encoded_data)
             .encode_field(&self.block.deref().clone())
             .encode_field(&self.signature)
+            .encode_optional_field(
+                &self
+                    .fee_config
+                    .map(|fee_config| Bytes::from(fee_config.to_vec())),
+            )
             .finish();
         let msg_data = snappy_compress(encoded_data)?;
         buf.put_slice(&msg_data);
@@ -45,10 +51,17 @@ impl RLPxMessage for NewBlock {
         let decoder = Decoder::new(&decompressed_data)?;
         let (block, decoder) = decoder.decode_field("block")?;
         let (signature, decoder) = decoder.decode_field("signature")?;
+        let (fee_config, decoder) = decoder.decode_optional_field::<Bytes>();
         decoder.finish()?;
+        let fee_config = fee_config
+            .map(|fee_config| FeeConfig::decode(&fee_config))
+            .transpose()
+            .map_err(|e| RLPDecodeError::Custom(format!("Invalid fee config: {e}")))?
+            .map(|(_, fee_config)| fee_config);
         Ok(NewBlock {
             block: Arc::new(block),
             signature,
+            fee_config,
         })
     }
 }
@@ -60,28 +73,6 @@ pub struct BatchSealed {
 }
 
 impl BatchSealed {
-    pub fn from_batch_and_key(
-        batch: Batch,
-        secret_key: &SecretKey,
-    ) -> Result<Self, PeerConnectionError> {
-        let hash = batch_hash(&batch);
-        let (recovery_id, signature) = secp256k1::SECP256K1
-            .sign_ecdsa_recoverable(&SecpMessage::from_digest(hash.into()), secret_key)
-            .serialize_compact();
-        let recovery_id: u8 = Into::<i32>::into(recovery_id).try_into().map_err(|e| {
-            PeerConnectionError::InternalError(format!(
-                "Failed to convert recovery id to u8: {e}. This is a bug."
-            ))
-        })?;
-        let mut sig = [0u8; 65];
-        sig[..64].copy_from_slice(&signature);
-        sig[64] = recovery_id;
-        let signature = Signature::from_slice(&sig);
-        Ok(Self {
-            batch: Box::new(batch),
-            signature,
-        })
-    }
     pub fn new(batch: Batch, signature: Signature) -> Self {
         Self {
             batch: Box::new(batch),
@@ -90,6 +81,15 @@ impl BatchSealed {
     }
 }
 
+/// Digest the lead sequencer signs for a block, covering the fee config it was built with,
+/// since followers re-execute the block with it.
+pub fn block_signing_hash(block_hash: H256, fee_config: Option<&FeeConfig>) -> H256 {
+    match fee_config {
+        Some(fee_config) => keccak([block_hash.as_bytes(), &fee_config.to_vec()].concat()),
+        None => block_hash,
+    }
+}
+
 pub fn batch_hash(sealed_batch: &Batch) -> H256 {
     let input = [
         sealed_batch.first_block.to_be_bytes(),
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+
+use crate::utils::{RpcErr, RpcNamespace, RpcRequest};
+
+/// Namespaces served by the http server unless configured otherwise.
+/// The admin namespace is left out as it is served by its own local-only listener.
+pub const DEFAULT_HTTP_API: [RpcNamespace; 6] = [
+    RpcNamespace::Eth,
+    RpcNamespace::Net,
+    RpcNamespace::Web3,
+    RpcNamespace::Debug,
+    RpcNamespace::Mempool,
+    RpcNamespace::Trace,
+];
+
+/// Namespaces and methods served by one of the rpc listeners.
+/// Requests to any other method are answered as if the method didn't exist.
+#[derive(Debug, Clone)]
+pub struct RpcApiFilter {
+    namespaces: HashSet<RpcNamespace>,
+    denied_methods: HashSet<String>,
+}
+
+impl RpcApiFilter {
+    pub fn new(
+        namespaces: impl IntoIterator<Item = RpcNamespace>,
+        denied_methods: impl IntoIterator<Item = String>,
+    ) -> Self {
+        Self {
+            namespaces: namespaces.into_iter().collect(),
+            denied_methods: denied_methods.into_iter().collect(),
+        }
+    }
+
+    /// Serves every method of the given namespaces
+    pub fn namespaces(namespaces: impl IntoIterator<Item = RpcNamespace>) -> Self {
+        Self::new(namespaces, [])
+    }
+
+    /// Serves every namespace served to clients, admin included, for listeners that can only be
+    /// reached from the node's host
+    pub fn local() -> Self {
+        Self::namespaces(DEFAULT_HTTP_API.into_iter().chain([RpcNamespace::Admin]))
+    }
+
+    pub fn allows(&self, namespace: RpcNamespace) -> bool {
+        self.namespaces.contains(&namespace)
+    }
+
+    /// Whether the method is disabled on its own by the deny list
+    pub fn denies(&self, method: &str) -> bool {
+        self.denied_methods.contains(method)
+    }
+
+    /// Returns `MethodNotFound` if the request's method is disabled in this listener
+    pub fn check(&self, req: &RpcRequest) -> Result<(), RpcErr> {
+        let namespace = req.namespace()?;
+        if !self.allows(namespace) || self.denies(&req.method) {
+            return Err(RpcErr::MethodNotFound(req.method.clone()));
+        }
+        Ok(())
+    }
+}
+
+impl Default for RpcApiFilter {
+    fn default() -> Self {
+        Self::namespaces(DEFAULT_HTTP_API)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_namespaces_and_methods_are_not_found() {
+        let filter = RpcApiFilter::new(
+            [RpcNamespace::Eth, RpcNamespace::Debug],
+            ["debug_traceCall".to_string()],
+        );
+        assert!(filter.check(&RpcRequest::new("eth_chainId", None)).is_ok());
+        assert!(filter.denies("debug_traceCall"));
+        assert!(!filter.denies("debug_traceTransaction"));
+        assert!(
+            filter
+                .check(&RpcRequest::new("debug_traceTransaction", None))
+                .is_ok()
+        );
+        assert!(matches!(
+            filter.check(&RpcRequest::new("debug_traceCall", None)),
+            Err(RpcErr::MethodNotFound(method)) if method == "debug_traceCall"
+        ));
+        assert!(matches!(
+            filter.check(&RpcRequest::new("admin_addPeer", None)),
+            Err(RpcErr::MethodNotFound(method)) if method == "admin_addPeer"
+        ));
+    }
+
+    #[test]
+    fn admin_is_not_served_by_default() {
+        let filter = RpcApiFilter::default();
+        assert!(!filter.allows(RpcNamespace::Admin));
+        assert!(!filter.allows(RpcNamespace::Engine));
+        assert!(filter.allows(RpcNamespace::Eth));
+    }
+}
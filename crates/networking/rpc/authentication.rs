@@ -13,6 +13,7 @@ pub enum AuthenticationError {
     InvalidIssuedAtClaim,
     TokenDecodingError,
     MissingAuthentication,
+    InvalidBundleSignature,
 }
 
 pub fn authenticate(
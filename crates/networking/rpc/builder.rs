@@ -0,0 +1,370 @@
+//! Submission of the payloads built to a relay, acting as a block builder for the proposers
+//! registered to it.
+//!
+//! The consensus client has to send payload attributes every slot, not only when its validators
+//! are proposing. Payloads built for a slot whose proposer is registered to the relay are rebuilt
+//! with the fee recipient and gas limit of its registration when they don't match them, so the
+//! proposer is paid through the block rewards. Every payload more valuable than the last one bid
+//! for the same slot is bid.
+
+use std::sync::Arc;
+
+use ethrex_blockchain::{
+    Blockchain,
+    payload::{BuildPayloadArgs, PayloadBuildResult, create_payload},
+};
+use ethrex_common::{H256, U256, types::ELASTICITY_MULTIPLIER};
+use ethrex_storage::Store;
+use reqwest::Url;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use crate::clients::relay::{
+    RelayClient,
+    errors::RelayClientError,
+    signing::BuilderSigner,
+    types::{
+        BidTrace, ExecutionPayload, ExecutionRequests, ProposerDuty, SignedBidSubmission,
+        ValidatorRegistration,
+    },
+};
+
+const SECONDS_PER_SLOT: u64 = 12;
+const SLOTS_PER_EPOCH: u64 = 32;
+
+#[derive(Debug, Clone)]
+pub struct BlockBuilderConfig {
+    pub relay_url: Url,
+    /// BLS secret key of the builder, big-endian
+    pub secret_key: H256,
+    pub genesis_fork_version: [u8; 4],
+    /// Genesis time of the beacon chain
+    pub genesis_time: u64,
+}
+
+/// Returns a task submitting the payloads built to the relay, until the blockchain is dropped
+pub fn start_block_builder(
+    blockchain: Arc<Blockchain>,
+    storage: Store,
+    config: BlockBuilderConfig,
+) -> Result<impl Future<Output = ()>, RelayClientError> {
+    let mut payloads = blockchain.subscribe_built_payloads();
+    let mut builder = BlockBuilder::new(blockchain, storage, config)?;
+    Ok(async move {
+        loop {
+            match payloads.recv().await {
+                Ok(payload) => {
+                    if let Err(error) = builder.submit_payload(&payload).await {
+                        warn!(%error, "Failed to submit block to relay");
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!("Block builder skipped {skipped} payloads");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+struct BlockBuilder {
+    blockchain: Arc<Blockchain>,
+    storage: Store,
+    relay: RelayClient,
+    signer: BuilderSigner,
+    genesis_time: u64,
+    /// Proposers of the current and next epoch of `duties_epoch`, fetched once per epoch
+    duties: Vec<ProposerDuty>,
+    duties_epoch: Option<u64>,
+    /// Slot and value of the last bid
+    last_bid: Option<(u64, U256)>,
+}
+
+impl BlockBuilder {
+    fn new(
+        blockchain: Arc<Blockchain>,
+        storage: Store,
+        config: BlockBuilderConfig,
+    ) -> Result<Self, RelayClientError> {
+        Ok(Self {
+            blockchain,
+            storage,
+            relay: RelayClient::new(config.relay_url),
+            signer: BuilderSigner::new(config.secret_key, config.genesis_fork_version)?,
+            genesis_time: config.genesis_time,
+            duties: Vec::new(),
+            duties_epoch: None,
+            last_bid: None,
+        })
+    }
+
+    /// Returns the proposer of the slot, fetching the duties from the relay the first time a slot
+    /// of a new epoch is seen
+    async fn proposer_duty(&mut self, slot: u64) -> Result<Option<ProposerDuty>, RelayClientError> {
+        let epoch = slot / SLOTS_PER_EPOCH;
+        if self.duties_epoch != Some(epoch) {
+            self.duties = self.relay.get_proposer_duties().await?;
+            self.duties_epoch = Some(epoch);
+        }
+        Ok(self.duties.iter().find(|duty| duty.slot == slot).cloned())
+    }
+
+    /// Bids the payload to the relay if the proposer of its slot is registered to it, rebuilding
+    /// it first if it doesn't follow the proposer's registration
+    async fn submit_payload(
+        &mut self,
+        payload: &Arc<PayloadBuildResult>,
+    ) -> Result<(), RelayClientError> {
+        let header = &payload.payload.header;
+        let Some(slot) = header
+            .timestamp
+            .checked_sub(self.genesis_time)
+            .map(|elapsed| elapsed / SECONDS_PER_SLOT)
+        else {
+            return Err(RelayClientError::Custom(format!(
+                "Payload timestamp {} is before genesis",
+                header.timestamp
+            )));
+        };
+        let Some(duty) = self.proposer_duty(slot).await? else {
+            debug!("No proposer registered to the relay for slot {slot}");
+            return Ok(());
+        };
+        let proposer = &duty.entry.message;
+        let payload = self.follow_registration(payload, proposer).await?;
+        let header = &payload.payload.header;
+        if let Some((last_slot, last_value)) = self.last_bid
+            && last_slot == slot
+            && last_value >= payload.block_value
+        {
+            debug!(
+                "Not bidding for slot {slot}, the payload isn't more valuable than the last bid"
+            );
+            return Ok(());
+        }
+
+        let execution_payload = ExecutionPayload::from(&payload.payload);
+        let message = BidTrace {
+            slot,
+            parent_hash: header.parent_hash,
+            block_hash: execution_payload.block_hash,
+            builder_pubkey: self.signer.pubkey(),
+            proposer_pubkey: proposer.pubkey,
+            proposer_fee_recipient: proposer.fee_recipient,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            value: payload.block_value,
+        };
+        let signature = self.signer.sign(&message).to_vec().into();
+        let execution_requests = header
+            .requests_hash
+            .map(|_| ExecutionRequests::decode(&payload.requests))
+            .transpose()?;
+        let submission = SignedBidSubmission {
+            message,
+            execution_payload,
+            blobs_bundle: payload.blobs_bundle.clone(),
+            execution_requests,
+            signature,
+        };
+        self.relay.submit_block(&submission).await?;
+        self.last_bid = Some((slot, payload.block_value));
+        info!(
+            "Submitted block {:#x} for slot {slot} with value {}",
+            submission.message.block_hash, submission.message.value
+        );
+        Ok(())
+    }
+
+    /// Returns the payload built with the fee recipient and towards the gas limit of the
+    /// proposer's registration, reusing the given one if it already does
+    async fn follow_registration(
+        &self,
+        payload: &Arc<PayloadBuildResult>,
+        proposer: &ValidatorRegistration,
+    ) -> Result<Arc<PayloadBuildResult>, RelayClientError> {
+        let block = &payload.payload;
+        let args = BuildPayloadArgs {
+            parent: block.header.parent_hash,
+            timestamp: block.header.timestamp,
+            fee_recipient: proposer.fee_recipient,
+            random: block.header.prev_randao,
+            withdrawals: block.body.withdrawals.clone(),
+            beacon_root: block.header.parent_beacon_block_root,
+            version: 0,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            gas_ceil: proposer.gas_limit,
+        };
+        let rebuilt = create_payload(&args, &self.storage, block.header.extra_data.clone())
+            .map_err(|error| RelayClientError::Custom(error.to_string()))?;
+        if rebuilt.header.coinbase == block.header.coinbase
+            && rebuilt.header.gas_limit == block.header.gas_limit
+        {
+            return Ok(payload.clone());
+        }
+
+        debug!(
+            "Rebuilding payload {:#x} for fee recipient {:#x} and gas limit {}",
+            block.hash(),
+            proposer.fee_recipient,
+            rebuilt.header.gas_limit
+        );
+        let blockchain = self.blockchain.clone();
+        let rebuilt = tokio::task::spawn_blocking(move || blockchain.build_payload(rebuilt))
+            .await
+            .map_err(|error| RelayClientError::Custom(error.to_string()))?
+            .map_err(|error| RelayClientError::Custom(error.to_string()))?;
+        Ok(Arc::new(rebuilt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use axum::{Json, Router, extract::State, routing::get, routing::post};
+    use bytes::Bytes;
+    use ethrex_common::{Address, types::DEFAULT_BUILDER_GAS_CEIL};
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::test_utils::setup_store;
+
+    #[derive(Clone, Default)]
+    struct Relay {
+        duties_requests: Arc<AtomicUsize>,
+        submissions: Arc<Mutex<Vec<Value>>>,
+    }
+
+    async fn proposer_duties(State(relay): State<Relay>) -> Json<Value> {
+        relay.duties_requests.fetch_add(1, Ordering::SeqCst);
+        Json(json!([{
+            "slot": "5",
+            "validator_index": "7",
+            "entry": {
+                "message": {
+                    "fee_recipient": format!("{:#x}", Address::repeat_byte(1)),
+                    "gas_limit": "30000000",
+                    "timestamp": "1000",
+                    "pubkey": format!("0x{}", hex::encode([2; 48])),
+                },
+                "signature": format!("0x{}", hex::encode([0; 96])),
+            },
+        }]))
+    }
+
+    async fn submit_block(State(relay): State<Relay>, Json(body): Json<Value>) {
+        relay.submissions.lock().unwrap().push(body);
+    }
+
+    /// Builds an empty payload on top of genesis, as the consensus client would ask for it
+    fn payload(
+        blockchain: &Blockchain,
+        storage: &Store,
+        genesis_time: u64,
+        slot: u64,
+        fee_recipient: Address,
+    ) -> Arc<PayloadBuildResult> {
+        let args = BuildPayloadArgs {
+            parent: storage.get_block_header(0).unwrap().unwrap().hash(),
+            timestamp: genesis_time + slot * SECONDS_PER_SLOT,
+            fee_recipient,
+            random: H256::zero(),
+            withdrawals: Some(Vec::new()),
+            beacon_root: Some(H256::zero()),
+            version: 3,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
+        };
+        let block = create_payload(&args, storage, Bytes::new()).unwrap();
+        Arc::new(blockchain.build_payload(block).unwrap())
+    }
+
+    #[tokio::test]
+    async fn payloads_are_bid_to_the_relay_for_registered_proposers() {
+        let relay = Relay::default();
+        let router = Router::new()
+            .route("/relay/v1/builder/validators", get(proposer_duties))
+            .route("/relay/v1/builder/blocks", post(submit_block))
+            .with_state(relay.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let relay_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let storage = setup_store().await;
+        let blockchain = Arc::new(Blockchain::default_with_store(storage.clone()));
+        let genesis_time = storage.get_block_header(0).unwrap().unwrap().timestamp;
+        let mut builder = BlockBuilder::new(
+            blockchain.clone(),
+            storage.clone(),
+            BlockBuilderConfig {
+                relay_url: relay_url.parse().unwrap(),
+                secret_key: H256::from_low_u64_be(42),
+                genesis_fork_version: [0; 4],
+                genesis_time,
+            },
+        )
+        .unwrap();
+        let builder_coinbase = Address::repeat_byte(3);
+
+        // No proposer registered for the slot
+        builder
+            .submit_payload(&payload(
+                &blockchain,
+                &storage,
+                genesis_time,
+                6,
+                builder_coinbase,
+            ))
+            .await
+            .unwrap();
+        assert!(relay.submissions.lock().unwrap().is_empty());
+
+        // Built for the builder, so it's rebuilt to pay the proposer
+        let built = payload(&blockchain, &storage, genesis_time, 5, builder_coinbase);
+        builder.submit_payload(&built).await.unwrap();
+        {
+            let submissions = relay.submissions.lock().unwrap();
+            assert_eq!(submissions.len(), 1);
+            let message = &submissions[0]["message"];
+            assert_eq!(message["slot"], "5");
+            assert_eq!(
+                message["proposer_pubkey"],
+                format!("0x{}", hex::encode([2; 48]))
+            );
+            let execution_payload = &submissions[0]["execution_payload"];
+            assert_eq!(
+                execution_payload["fee_recipient"],
+                format!("{:#x}", Address::repeat_byte(1))
+            );
+            assert_eq!(execution_payload["block_number"], "1");
+            assert_eq!(message["block_hash"], execution_payload["block_hash"]);
+            assert_ne!(
+                message["block_hash"],
+                format!("{:#x}", built.payload.hash())
+            );
+        }
+
+        // Not more valuable than the last bid for the slot
+        builder.submit_payload(&built).await.unwrap();
+        assert_eq!(relay.submissions.lock().unwrap().len(), 1);
+
+        // Duties are fetched once per epoch
+        assert_eq!(relay.duties_requests.load(Ordering::SeqCst), 1);
+        builder
+            .submit_payload(&payload(
+                &blockchain,
+                &storage,
+                genesis_time,
+                SLOTS_PER_EPOCH + 5,
+                builder_coinbase,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(relay.duties_requests.load(Ordering::SeqCst), 2);
+        assert_eq!(relay.submissions.lock().unwrap().len(), 1);
+    }
+}
@@ -1,6 +1,7 @@
 pub mod auth;
 pub mod beacon;
 pub mod eth;
+pub mod relay;
 
 pub use auth::{EngineClient, errors::EngineClientError};
 pub use eth::{EthClient, Overrides, errors::EthClientError};
@@ -0,0 +1,15 @@
+#[derive(Debug, thiserror::Error)]
+pub enum RelayClientError {
+    #[error("reqwest error: {0}")]
+    ReqwestError(#[from] reqwest::Error),
+    #[error("Relay error (code: {0}): {1}")]
+    RelayError(u64, String),
+    #[error("Response deserialization error: {0}")]
+    DeserializeError(#[from] serde_json::Error),
+    #[error("Failed to set url endpoint: {0}")]
+    FailedToSetURLEndpointError(String),
+    #[error("Invalid builder secret key")]
+    InvalidSecretKey,
+    #[error("Error: {0}")]
+    Custom(String),
+}
@@ -0,0 +1,77 @@
+use errors::RelayClientError;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use types::{ProposerDuty, SignedBidSubmission};
+
+pub mod errors;
+pub mod signing;
+pub mod types;
+
+#[derive(Deserialize, Debug)]
+pub struct RelayResponseError {
+    code: u64,
+    message: String,
+}
+
+/// Client of the relay API of the builder-specs
+pub struct RelayClient {
+    client: Client,
+    url: Url,
+}
+
+impl RelayClient {
+    pub fn new(url: Url) -> Self {
+        Self {
+            client: Client::new(),
+            url,
+        }
+    }
+
+    fn endpoint(&self, endpoint: &str) -> Result<Url, RelayClientError> {
+        self.url
+            .join(endpoint)
+            .map_err(|error| RelayClientError::FailedToSetURLEndpointError(error.to_string()))
+    }
+
+    async fn check_response(
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response, RelayClientError> {
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status();
+        let error = match response.json::<RelayResponseError>().await {
+            Ok(error) => RelayClientError::RelayError(error.code, error.message),
+            Err(_) => RelayClientError::RelayError(status.as_u16().into(), status.to_string()),
+        };
+        Err(error)
+    }
+
+    /// Returns the validators proposing in the current and next epoch, along with their
+    /// registrations
+    pub async fn get_proposer_duties(&self) -> Result<Vec<ProposerDuty>, RelayClientError> {
+        let response = self
+            .client
+            .get(self.endpoint("/relay/v1/builder/validators")?)
+            .header("accept", "application/json")
+            .send()
+            .await?;
+        Ok(Self::check_response(response).await?.json().await?)
+    }
+
+    /// Submits a signed bid, replacing the previous bid of the builder for the same slot
+    pub async fn submit_block(
+        &self,
+        submission: &SignedBidSubmission,
+    ) -> Result<(), RelayClientError> {
+        let response = self
+            .client
+            .post(self.endpoint("/relay/v1/builder/blocks")?)
+            .header("content-type", "application/json")
+            .json(submission)
+            .send()
+            .await?;
+        Self::check_response(response).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+//! BLS signing of the bids submitted to relays, as specified by the builder-specs.
+//!
+//! Bids are signed over the SSZ hash tree root of the [`BidTrace`] in the application builder
+//! domain, which only depends on the genesis fork version of the network.
+
+use bls12_381::{
+    G1Affine, G2Affine, G2Projective, Scalar,
+    hash_to_curve::{ExpandMsgXmd, HashToCurve},
+};
+use ethrex_common::H256;
+use sha2::{Digest, Sha256};
+
+use super::{errors::RelayClientError, types::BidTrace};
+
+const DOMAIN_APPLICATION_BUILDER: [u8; 4] = [0x00, 0x00, 0x00, 0x01];
+/// Domain separation tag of the proof of possession scheme used by the consensus layer
+const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+type Chunk = [u8; 32];
+
+/// Signs bids with the builder's BLS key
+pub struct BuilderSigner {
+    secret_key: Scalar,
+    pubkey: [u8; 48],
+    domain: Chunk,
+}
+
+impl BuilderSigner {
+    /// Creates a signer from a big-endian secret key, for the network with the given genesis
+    /// fork version
+    pub fn new(secret_key: H256, genesis_fork_version: [u8; 4]) -> Result<Self, RelayClientError> {
+        let mut little_endian = secret_key.0;
+        little_endian.reverse();
+        let secret_key = Option::<Scalar>::from(Scalar::from_bytes(&little_endian))
+            .filter(|secret_key| *secret_key != Scalar::zero())
+            .ok_or(RelayClientError::InvalidSecretKey)?;
+        Ok(Self {
+            secret_key,
+            pubkey: G1Affine::from(G1Affine::generator() * secret_key).to_compressed(),
+            domain: builder_domain(genesis_fork_version),
+        })
+    }
+
+    /// Compressed public key of the builder
+    pub fn pubkey(&self) -> [u8; 48] {
+        self.pubkey
+    }
+
+    /// Returns the compressed signature of the bid
+    pub fn sign(&self, bid: &BidTrace) -> [u8; 96] {
+        let message = signing_root(bid_trace_root(bid), self.domain);
+        let point = <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(
+            message,
+            SIGNATURE_DST,
+        );
+        G2Affine::from(point * self.secret_key).to_compressed()
+    }
+}
+
+/// `compute_domain` of the consensus specs, with an empty genesis validators root as the builder
+/// domain isn't bound to a specific chain
+fn builder_domain(genesis_fork_version: [u8; 4]) -> Chunk {
+    let mut version = [0; 32];
+    version[..4].copy_from_slice(&genesis_fork_version);
+    let fork_data_root = hash_pair(&version, &[0; 32]);
+
+    let mut domain = [0; 32];
+    domain[..4].copy_from_slice(&DOMAIN_APPLICATION_BUILDER);
+    domain[4..].copy_from_slice(&fork_data_root[..28]);
+    domain
+}
+
+fn signing_root(object_root: Chunk, domain: Chunk) -> Chunk {
+    hash_pair(&object_root, &domain)
+}
+
+fn bid_trace_root(bid: &BidTrace) -> Chunk {
+    merkleize(&[
+        u64_chunk(bid.slot),
+        bid.parent_hash.0,
+        bid.block_hash.0,
+        pubkey_root(&bid.builder_pubkey),
+        pubkey_root(&bid.proposer_pubkey),
+        left_aligned_chunk(bid.proposer_fee_recipient.as_bytes()),
+        u64_chunk(bid.gas_limit),
+        u64_chunk(bid.gas_used),
+        bid.value.to_little_endian(),
+    ])
+}
+
+/// Root of a 48 bytes vector, which spans two chunks
+fn pubkey_root(pubkey: &[u8; 48]) -> Chunk {
+    hash_pair(
+        &left_aligned_chunk(&pubkey[..32]),
+        &left_aligned_chunk(&pubkey[32..]),
+    )
+}
+
+fn u64_chunk(value: u64) -> Chunk {
+    left_aligned_chunk(&value.to_le_bytes())
+}
+
+fn left_aligned_chunk(bytes: &[u8]) -> Chunk {
+    let mut chunk = [0; 32];
+    chunk[..bytes.len()].copy_from_slice(bytes);
+    chunk
+}
+
+/// Root of the chunks, padded with zeroed chunks to the next power of two
+fn merkleize(chunks: &[Chunk]) -> Chunk {
+    let mut layer = chunks.to_vec();
+    layer.resize(chunks.len().next_power_of_two(), [0; 32]);
+    while layer.len() > 1 {
+        layer = layer
+            .chunks_exact(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+    }
+    layer.first().copied().unwrap_or_default()
+}
+
+fn hash_pair(left: &Chunk, right: &Chunk) -> Chunk {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::pairing;
+    use ethrex_common::{Address, U256};
+    use hex_literal::hex;
+
+    use super::*;
+
+    #[test]
+    fn mainnet_builder_domain() {
+        assert_eq!(
+            builder_domain([0; 4]),
+            hex!("00000001f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a9")
+        );
+    }
+
+    #[test]
+    fn signed_bids_verify_against_the_builder_pubkey() {
+        let signer = BuilderSigner::new(H256::from_low_u64_be(42), [0; 4]).unwrap();
+        let bid = BidTrace {
+            slot: 1,
+            parent_hash: H256::repeat_byte(1),
+            block_hash: H256::repeat_byte(2),
+            builder_pubkey: signer.pubkey(),
+            proposer_pubkey: [3; 48],
+            proposer_fee_recipient: Address::repeat_byte(4),
+            gas_limit: 30_000_000,
+            gas_used: 21_000,
+            value: U256::from(1_000),
+        };
+        let signature = G2Affine::from_compressed(&signer.sign(&bid)).unwrap();
+        let pubkey = G1Affine::from_compressed(&signer.pubkey()).unwrap();
+        let message = G2Affine::from(
+            <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(
+                signing_root(bid_trace_root(&bid), signer.domain),
+                SIGNATURE_DST,
+            ),
+        );
+        assert_eq!(
+            pairing(&pubkey, &message),
+            pairing(&G1Affine::generator(), &signature)
+        );
+        assert!(BuilderSigner::new(H256::zero(), [0; 4]).is_err());
+    }
+}
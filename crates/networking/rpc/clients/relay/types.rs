@@ -0,0 +1,274 @@
+use bytes::Bytes;
+use ethrex_common::{
+    Address, Bloom, H256, U256,
+    serde_utils::{self, u64::dec_str},
+    types::{BlobsBundle, Block, Withdrawal, requests::EncodedRequests},
+};
+use serde::{Deserialize, Serialize};
+
+use super::errors::RelayClientError;
+
+/// Each element of the `/relay/v1/builder/validators` endpoint's response, a validator proposing
+/// in the current or next epoch
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProposerDuty {
+    #[serde(with = "dec_str")]
+    pub slot: u64,
+    #[serde(with = "dec_str")]
+    pub validator_index: u64,
+    pub entry: SignedValidatorRegistration,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SignedValidatorRegistration {
+    pub message: ValidatorRegistration,
+    // 96 bytes hex string
+    #[serde(rename = "signature", with = "serde_utils::bytes")]
+    _signature: Bytes,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ValidatorRegistration {
+    pub fee_recipient: Address,
+    #[serde(with = "dec_str")]
+    pub gas_limit: u64,
+    #[serde(with = "dec_str")]
+    pub timestamp: u64,
+    #[serde(with = "serde_utils::bytes48")]
+    pub pubkey: [u8; 48],
+}
+
+/// Bid of a block for a slot, the message signed by the builder
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BidTrace {
+    #[serde(with = "dec_str")]
+    pub slot: u64,
+    pub parent_hash: H256,
+    pub block_hash: H256,
+    #[serde(with = "serde_utils::bytes48")]
+    pub builder_pubkey: [u8; 48],
+    #[serde(with = "serde_utils::bytes48")]
+    pub proposer_pubkey: [u8; 48],
+    pub proposer_fee_recipient: Address,
+    #[serde(with = "dec_str")]
+    pub gas_limit: u64,
+    #[serde(with = "dec_str")]
+    pub gas_used: u64,
+    #[serde(with = "serde_utils::u256::dec_str")]
+    pub value: U256,
+}
+
+/// Body of the `/relay/v1/builder/blocks` endpoint's request
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SignedBidSubmission {
+    pub message: BidTrace,
+    pub execution_payload: ExecutionPayload,
+    pub blobs_bundle: BlobsBundle,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub execution_requests: Option<ExecutionRequests>,
+    // 96 bytes hex string
+    #[serde(with = "serde_utils::bytes")]
+    pub signature: Bytes,
+}
+
+/// Execution payload as encoded by the beacon and builder APIs
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExecutionPayload {
+    pub parent_hash: H256,
+    pub fee_recipient: Address,
+    pub state_root: H256,
+    pub receipts_root: H256,
+    pub logs_bloom: Bloom,
+    pub prev_randao: H256,
+    #[serde(with = "dec_str")]
+    pub block_number: u64,
+    #[serde(with = "dec_str")]
+    pub gas_limit: u64,
+    #[serde(with = "dec_str")]
+    pub gas_used: u64,
+    #[serde(with = "dec_str")]
+    pub timestamp: u64,
+    #[serde(with = "serde_utils::bytes")]
+    pub extra_data: Bytes,
+    #[serde(with = "serde_utils::u256::dec_str")]
+    pub base_fee_per_gas: U256,
+    pub block_hash: H256,
+    #[serde(with = "serde_utils::bytes::vec")]
+    pub transactions: Vec<Bytes>,
+    pub withdrawals: Vec<BeaconWithdrawal>,
+    #[serde(with = "dec_str")]
+    pub blob_gas_used: u64,
+    #[serde(with = "dec_str")]
+    pub excess_blob_gas: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BeaconWithdrawal {
+    #[serde(with = "dec_str")]
+    pub index: u64,
+    #[serde(with = "dec_str")]
+    pub validator_index: u64,
+    pub address: Address,
+    #[serde(with = "dec_str")]
+    pub amount: u64,
+}
+
+/// Execution layer requests of a payload, decoded as the beacon and builder APIs expect them
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ExecutionRequests {
+    pub deposits: Vec<DepositRequest>,
+    pub withdrawals: Vec<WithdrawalRequest>,
+    pub consolidations: Vec<ConsolidationRequest>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DepositRequest {
+    #[serde(with = "serde_utils::bytes48")]
+    pub pubkey: [u8; 48],
+    pub withdrawal_credentials: H256,
+    #[serde(with = "dec_str")]
+    pub amount: u64,
+    #[serde(with = "serde_utils::bytes")]
+    pub signature: Bytes,
+    #[serde(with = "dec_str")]
+    pub index: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WithdrawalRequest {
+    pub source_address: Address,
+    #[serde(with = "serde_utils::bytes48")]
+    pub validator_pubkey: [u8; 48],
+    #[serde(with = "dec_str")]
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConsolidationRequest {
+    pub source_address: Address,
+    #[serde(with = "serde_utils::bytes48")]
+    pub source_pubkey: [u8; 48],
+    #[serde(with = "serde_utils::bytes48")]
+    pub target_pubkey: [u8; 48],
+}
+
+const DEPOSIT_REQUEST_TYPE: u8 = 0x00;
+const WITHDRAWAL_REQUEST_TYPE: u8 = 0x01;
+const CONSOLIDATION_REQUEST_TYPE: u8 = 0x02;
+const DEPOSIT_REQUEST_SIZE: usize = 192;
+const WITHDRAWAL_REQUEST_SIZE: usize = 76;
+const CONSOLIDATION_REQUEST_SIZE: usize = 116;
+
+impl From<&Block> for ExecutionPayload {
+    fn from(block: &Block) -> Self {
+        let header = &block.header;
+        ExecutionPayload {
+            parent_hash: header.parent_hash,
+            fee_recipient: header.coinbase,
+            state_root: header.state_root,
+            receipts_root: header.receipts_root,
+            logs_bloom: header.logs_bloom,
+            prev_randao: header.prev_randao,
+            block_number: header.number,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            timestamp: header.timestamp,
+            extra_data: header.extra_data.clone(),
+            base_fee_per_gas: header.base_fee_per_gas.unwrap_or_default().into(),
+            block_hash: block.hash(),
+            transactions: block
+                .body
+                .transactions
+                .iter()
+                .map(|tx| tx.encode_canonical_to_vec().into())
+                .collect(),
+            withdrawals: block
+                .body
+                .withdrawals
+                .iter()
+                .flatten()
+                .map(BeaconWithdrawal::from)
+                .collect(),
+            blob_gas_used: header.blob_gas_used.unwrap_or_default(),
+            excess_blob_gas: header.excess_blob_gas.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&Withdrawal> for BeaconWithdrawal {
+    fn from(withdrawal: &Withdrawal) -> Self {
+        BeaconWithdrawal {
+            index: withdrawal.index,
+            validator_index: withdrawal.validator_index,
+            address: withdrawal.address,
+            amount: withdrawal.amount,
+        }
+    }
+}
+
+impl ExecutionRequests {
+    /// Decodes the requests of a payload, each one being the request type followed by the
+    /// concatenated requests of that type
+    pub fn decode(requests: &[EncodedRequests]) -> Result<Self, RelayClientError> {
+        let mut decoded = ExecutionRequests::default();
+        for request in requests {
+            let Some((request_type, data)) = request.0.split_first() else {
+                continue;
+            };
+            match *request_type {
+                DEPOSIT_REQUEST_TYPE => {
+                    for deposit in chunks(data, DEPOSIT_REQUEST_SIZE)? {
+                        decoded.deposits.push(DepositRequest {
+                            pubkey: fixed(&deposit[..48]),
+                            withdrawal_credentials: H256::from_slice(&deposit[48..80]),
+                            amount: u64::from_le_bytes(fixed(&deposit[80..88])),
+                            signature: Bytes::copy_from_slice(&deposit[88..184]),
+                            index: u64::from_le_bytes(fixed(&deposit[184..192])),
+                        });
+                    }
+                }
+                WITHDRAWAL_REQUEST_TYPE => {
+                    for withdrawal in chunks(data, WITHDRAWAL_REQUEST_SIZE)? {
+                        decoded.withdrawals.push(WithdrawalRequest {
+                            source_address: Address::from_slice(&withdrawal[..20]),
+                            validator_pubkey: fixed(&withdrawal[20..68]),
+                            amount: u64::from_le_bytes(fixed(&withdrawal[68..76])),
+                        });
+                    }
+                }
+                CONSOLIDATION_REQUEST_TYPE => {
+                    for consolidation in chunks(data, CONSOLIDATION_REQUEST_SIZE)? {
+                        decoded.consolidations.push(ConsolidationRequest {
+                            source_address: Address::from_slice(&consolidation[..20]),
+                            source_pubkey: fixed(&consolidation[20..68]),
+                            target_pubkey: fixed(&consolidation[68..116]),
+                        });
+                    }
+                }
+                request_type => {
+                    return Err(RelayClientError::Custom(format!(
+                        "Unknown request type: {request_type}"
+                    )));
+                }
+            }
+        }
+        Ok(decoded)
+    }
+}
+
+fn chunks(data: &[u8], size: usize) -> Result<std::slice::ChunksExact<'_, u8>, RelayClientError> {
+    if data.len() % size != 0 {
+        return Err(RelayClientError::Custom(format!(
+            "Requests of {} bytes aren't a multiple of {size} bytes",
+            data.len()
+        )));
+    }
+    Ok(data.chunks_exact(size))
+}
+
+/// Copies a slice into an array of its length
+fn fixed<const N: usize>(slice: &[u8]) -> [u8; N] {
+    let mut array = [0; N];
+    array.copy_from_slice(slice);
+    array
+}
@@ -4,36 +4,15 @@ pub mod fork_choice;
 pub mod payload;
 
 use crate::{
-    rpc::{RpcApiContext, RpcHandler},
+    rpc::{ENGINE_CAPABILITIES, RpcApiContext, RpcHandler},
     utils::RpcErr,
     utils::RpcRequest,
 };
 use serde_json::{Value, json};
 
-pub type ExchangeCapabilitiesRequest = Vec<String>;
-
-/// List of capabilities that the execution layer client supports. Add new capabilities here.
+/// The response lists the engine methods dispatched by the node, see `ENGINE_CAPABILITIES`.
 /// More info: https://github.com/ethereum/execution-apis/blob/main/src/engine/common.md#engine_exchangecapabilities
-pub const CAPABILITIES: [&str; 18] = [
-    "engine_forkchoiceUpdatedV1",
-    "engine_forkchoiceUpdatedV2",
-    "engine_forkchoiceUpdatedV3",
-    "engine_newPayloadV1",
-    "engine_newPayloadV2",
-    "engine_newPayloadV3",
-    "engine_newPayloadV4",
-    "engine_getPayloadV1",
-    "engine_getPayloadV2",
-    "engine_getPayloadV3",
-    "engine_getPayloadV4",
-    "engine_getPayloadV5",
-    "engine_exchangeTransitionConfigurationV1",
-    "engine_getPayloadBodiesByHashV1",
-    "engine_getPayloadBodiesByRangeV1",
-    "engine_getBlobsV1",
-    "engine_getBlobsV2",
-    "engine_getBlobsV3",
-];
+pub type ExchangeCapabilitiesRequest = Vec<String>;
 
 impl From<ExchangeCapabilitiesRequest> for RpcRequest {
     fn from(val: ExchangeCapabilitiesRequest) -> Self {
@@ -59,6 +38,6 @@ impl RpcHandler for ExchangeCapabilitiesRequest {
     }
 
     async fn handle(&self, _context: RpcApiContext) -> Result<Value, RpcErr> {
-        Ok(json!(CAPABILITIES))
+        Ok(json!(ENGINE_CAPABILITIES))
     }
 }
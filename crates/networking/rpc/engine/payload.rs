@@ -723,7 +723,8 @@ async fn try_execute_payload(
     // Execute and store the block
     info!(%block_hash, %block_number, "Executing payload");
 
-    match add_block(context, block).await {
+    // The block is kept to debug it if it turns out to be invalid
+    match add_block(context, block.clone()).await {
         Err(ChainError::ParentNotFound) => {
             // Start sync
             syncer.sync_to_head(block_hash);
@@ -742,7 +743,7 @@ async fn try_execute_payload(
             warn!("Error executing block: {error}");
             context
                 .storage
-                .set_latest_valid_ancestor(block_hash, latest_valid_hash)
+                .add_bad_block(block, latest_valid_hash)
                 .await?;
             Ok(PayloadStatus::invalid_with(
                 latest_valid_hash,
@@ -753,7 +754,7 @@ async fn try_execute_payload(
             warn!("Error executing block: {error}");
             context
                 .storage
-                .set_latest_valid_ancestor(block_hash, latest_valid_hash)
+                .add_bad_block(block, latest_valid_hash)
                 .await?;
             Ok(PayloadStatus::invalid_with(
                 latest_valid_hash,
@@ -1,11 +1,20 @@
+use std::collections::BTreeMap;
+
+use ethrex_storage::Store;
 use serde_json::Value;
 use tracing::debug;
 
 use crate::rpc::{RpcApiContext, RpcHandler};
-use crate::types::account_proof::{AccountProof, StorageProof};
+use crate::types::account_proof::{AccountProof, RpcAccount, StorageProof};
 use crate::types::block_identifier::{BlockIdentifierOrHash, BlockTag};
 use crate::utils::RpcErr;
-use ethrex_common::{Address, BigEndianHash, H256, U256, serde_utils};
+use ethrex_common::{
+    Address, BigEndianHash, H256, U256, serde_utils,
+    types::{AccountState, BlockNumber},
+};
+
+/// Max amount of accounts plus slots read by a single `eth_getStorageValues` request
+pub const MAX_STORAGE_VALUES_SLOTS: usize = 1024;
 
 pub struct GetBalanceRequest {
     pub address: Address,
@@ -34,6 +43,16 @@ pub struct GetProofRequest {
     pub block: BlockIdentifierOrHash,
 }
 
+pub struct GetAccountRequest {
+    pub address: Address,
+    pub block: BlockIdentifierOrHash,
+}
+
+pub struct GetStorageValuesRequest {
+    pub storage_keys: BTreeMap<Address, Vec<H256>>,
+    pub block: BlockIdentifierOrHash,
+}
+
 impl RpcHandler for GetBalanceRequest {
     fn parse(params: &Option<Vec<Value>>) -> Result<GetBalanceRequest, RpcErr> {
         let params = params
@@ -226,7 +245,7 @@ impl RpcHandler for GetProofRequest {
             .get_account_proof(header.state_root, self.address, &self.storage_keys)
             .await?
         else {
-            return Err(RpcErr::Internal("Could not get account proof".to_owned()));
+            return Err(state_not_available(block_number));
         };
         let storage_proof = account_proof
             .storage_proof
@@ -251,9 +270,125 @@ impl RpcHandler for GetProofRequest {
     }
 }
 
+impl RpcHandler for GetAccountRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 2 {
+            return Err(RpcErr::BadParams("Expected 2 params".to_owned()));
+        };
+        Ok(GetAccountRequest {
+            address: serde_json::from_value(params[0].clone())?,
+            block: BlockIdentifierOrHash::parse(params[1].clone(), 1)?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        debug!("Requested account {} at block {}", self.address, self.block);
+        let Some(accounts) =
+            read_accounts(&context.storage, &self.block, &[(self.address, Vec::new())]).await?
+        else {
+            return Ok(Value::Null);
+        };
+        // Accounts that don't exist are empty
+        let account = accounts
+            .into_iter()
+            .next()
+            .and_then(|(account, _)| account)
+            .unwrap_or_default();
+        let account = RpcAccount {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            storage_root: account.storage_root,
+        };
+        serde_json::to_value(account).map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+impl RpcHandler for GetStorageValuesRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 2 {
+            return Err(RpcErr::BadParams("Expected 2 params".to_owned()));
+        };
+        let storage_keys: BTreeMap<Address, Vec<U256>> = serde_json::from_value(params[0].clone())?;
+        // Each account is read too, even without slots
+        let reads = storage_keys.len() + storage_keys.values().map(Vec::len).sum::<usize>();
+        if reads > MAX_STORAGE_VALUES_SLOTS {
+            return Err(RpcErr::LimitExceeded(format!(
+                "requested {reads} accounts and slots, the limit is {MAX_STORAGE_VALUES_SLOTS}"
+            )));
+        }
+        let storage_keys = storage_keys
+            .into_iter()
+            .map(|(address, keys)| (address, keys.iter().map(H256::from_uint).collect()))
+            .collect();
+        Ok(GetStorageValuesRequest {
+            storage_keys,
+            block: BlockIdentifierOrHash::parse(params[1].clone(), 1)?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        debug!(
+            "Requested storage values of {} accounts at block {}",
+            self.storage_keys.len(),
+            self.block
+        );
+        let accounts: Vec<_> = self
+            .storage_keys
+            .iter()
+            .map(|(address, keys)| (*address, keys.clone()))
+            .collect();
+        let Some(results) = read_accounts(&context.storage, &self.block, &accounts).await? else {
+            return Ok(Value::Null);
+        };
+        let values: BTreeMap<Address, Vec<H256>> = accounts
+            .iter()
+            .zip(results)
+            .map(|((address, _), (_, values))| {
+                (*address, values.iter().map(H256::from_uint).collect())
+            })
+            .collect();
+        serde_json::to_value(values).map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+/// Reads the given accounts and slots at a block, `None` if the block is unknown
+async fn read_accounts(
+    storage: &Store,
+    block: &BlockIdentifierOrHash,
+    accounts: &[(Address, Vec<H256>)],
+) -> Result<Option<Vec<(Option<AccountState>, Vec<U256>)>>, RpcErr> {
+    let Some(block_number) = block.resolve_block_number(storage).await? else {
+        return Ok(None);
+    };
+    let Some(header) = storage.get_block_header(block_number)? else {
+        return Ok(None);
+    };
+    let Some(results) = storage.get_accounts_storage_at_root(header.state_root, accounts)? else {
+        return Err(state_not_available(block_number));
+    };
+    Ok(Some(results))
+}
+
+fn state_not_available(block_number: BlockNumber) -> RpcErr {
+    RpcErr::Internal(format!("State of block {block_number} is not available"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        rpc::map_http_requests,
+        test_utils::{default_context_with_storage, setup_store},
+        utils::RpcRequest,
+    };
+    use ethrex_common::constants::{EMPTY_KECCACK_HASH, EMPTY_TRIE_HASH};
     use serde_json::json;
 
     #[test]
@@ -291,4 +426,71 @@ mod tests {
         assert_eq!(request.storage_slot, H256::from_uint(&U256::from(1u64)));
         assert_eq!(request.block, BlockTag::Latest);
     }
+
+    #[test]
+    fn test_get_storage_values_request_parse() {
+        let address: Address = "0xdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef"
+            .parse()
+            .unwrap();
+        let params = Some(vec![
+            json!({ "0xdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef": ["0x1", "0x2"] }),
+            json!("latest"),
+        ]);
+        let request = GetStorageValuesRequest::parse(&params).unwrap();
+
+        assert_eq!(
+            request.storage_keys.get(&address).unwrap(),
+            &vec![
+                H256::from_uint(&U256::from(1u64)),
+                H256::from_uint(&U256::from(2u64))
+            ]
+        );
+        assert_eq!(request.block, BlockTag::Latest);
+
+        let slots = vec![json!("0x1"); MAX_STORAGE_VALUES_SLOTS + 1];
+        let params = Some(vec![
+            json!({ "0xdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef": slots }),
+            json!("latest"),
+        ]);
+        assert!(matches!(
+            GetStorageValuesRequest::parse(&params),
+            Err(RpcErr::LimitExceeded(_))
+        ));
+
+        // Accounts count towards the limit even without slots
+        let accounts: serde_json::Map<_, _> = (0..MAX_STORAGE_VALUES_SLOTS as u64 + 1)
+            .map(|i| (format!("{:#x}", Address::from_low_u64_be(i)), json!([])))
+            .collect();
+        let params = Some(vec![Value::Object(accounts), json!("latest")]);
+        assert!(matches!(
+            GetStorageValuesRequest::parse(&params),
+            Err(RpcErr::LimitExceeded(_))
+        ));
+        let slots = vec![json!("0x1"); MAX_STORAGE_VALUES_SLOTS - 1];
+        let params = Some(vec![
+            json!({ "0xdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef": slots }),
+            json!("latest"),
+        ]);
+        assert!(GetStorageValuesRequest::parse(&params).is_ok());
+    }
+
+    #[tokio::test]
+    async fn get_account_returns_the_empty_account_for_missing_accounts() {
+        let context = default_context_with_storage(setup_store().await).await;
+        let request = RpcRequest::new(
+            "eth_getAccount",
+            Some(vec![json!(Address::repeat_byte(0x42)), json!("latest")]),
+        );
+
+        let account = map_http_requests(&request, context).await.unwrap();
+        assert_eq!(
+            account,
+            json!({
+                "balance": "0x0",
+                "nonce": "0x0",
+                "codeHash": *EMPTY_KECCACK_HASH,
+                "storageRoot": *EMPTY_TRIE_HASH,
+            })
+        );
+    }
 }
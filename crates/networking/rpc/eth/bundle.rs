@@ -0,0 +1,374 @@
+use std::str::FromStr;
+
+use bytes::Bytes;
+use ethereum_types::Signature;
+use ethrex_blockchain::bundle::{Bundle, BundlePool, BundleSimulation};
+use ethrex_common::{
+    Address, H256, U256, serde_utils,
+    types::{BlockNumber, Transaction, TxKind, TxType, recover_address_from_message},
+    utils::keccak,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{
+    authentication::AuthenticationError,
+    rpc::{RpcApiContext, RpcHandler},
+    types::block_identifier::BlockIdentifier,
+    utils::RpcErr,
+};
+
+/// Max amount of transactions of a single bundle
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 100;
+/// Header authenticating the searcher sending a bundle, as `<address>:<signature>`. The signature
+/// is the EIP-191 signature of the hex encoded keccak of the request body, like Flashbots expects.
+pub const FLASHBOTS_SIGNATURE_HEADER: &str = "x-flashbots-signature";
+
+/// Bundle as sent to `eth_sendBundle`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcBundle {
+    #[serde(with = "serde_utils::bytes::vec")]
+    txs: Vec<Bytes>,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    block_number: BlockNumber,
+    min_timestamp: Option<u64>,
+    max_timestamp: Option<u64>,
+    #[serde(default)]
+    reverting_tx_hashes: Vec<H256>,
+}
+
+/// Bundle as sent to `eth_callBundle`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcCallBundle {
+    #[serde(with = "serde_utils::bytes::vec")]
+    txs: Vec<Bytes>,
+    state_block_number: Value,
+    timestamp: Option<u64>,
+    coinbase: Option<Address>,
+}
+
+pub struct SendBundleRequest {
+    bundle: Bundle,
+}
+
+/// Executes a bundle on top of `stateBlockNumber` as if it was included at the top of the next
+/// block, without adding it to the pool
+pub struct CallBundleRequest {
+    transactions: Vec<(Transaction, Address)>,
+    state_block: BlockIdentifier,
+    timestamp: Option<u64>,
+    coinbase: Option<Address>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallBundleResponse {
+    bundle_hash: H256,
+    #[serde(with = "serde_utils::u256::dec_str")]
+    coinbase_diff: U256,
+    total_gas_used: u64,
+    state_block_number: BlockNumber,
+    results: Vec<CallBundleTransactionResponse>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallBundleTransactionResponse {
+    tx_hash: H256,
+    gas_used: u64,
+    from_address: Address,
+    to_address: Option<Address>,
+    #[serde(with = "serde_utils::u256::dec_str")]
+    coinbase_diff: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl RpcHandler for SendBundleRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let bundle: RpcBundle = parse_bundle_param(params)?;
+        Ok(SendBundleRequest {
+            bundle: Bundle {
+                transactions: decode_transactions(&bundle.txs)?,
+                block_number: bundle.block_number,
+                min_timestamp: bundle.min_timestamp,
+                max_timestamp: bundle.max_timestamp,
+                reverting_tx_hashes: bundle.reverting_tx_hashes,
+            },
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let pool = authorized_pool(&context)?;
+        let latest_block_number = context.storage.get_latest_block_number().await?;
+        if self.bundle.block_number <= latest_block_number {
+            return Err(RpcErr::BadParams(format!(
+                "Bundle targets block {} but the latest block is {latest_block_number}",
+                self.bundle.block_number
+            )));
+        }
+        let bundle_hash = pool.add_bundle(self.bundle.clone())?;
+        debug!("Added bundle {bundle_hash:#x}");
+        Ok(serde_json::json!({ "bundleHash": bundle_hash }))
+    }
+}
+
+impl RpcHandler for CallBundleRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let bundle: RpcCallBundle = parse_bundle_param(params)?;
+        Ok(CallBundleRequest {
+            transactions: decode_transactions(&bundle.txs)?,
+            state_block: BlockIdentifier::parse(bundle.state_block_number, 0)?,
+            timestamp: bundle.timestamp,
+            coinbase: bundle.coinbase,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        authorized_pool(&context)?;
+        let Some(parent) = self
+            .state_block
+            .resolve_block_header(&context.storage)
+            .await?
+        else {
+            return Err(RpcErr::BadParams("State block not found".to_owned()));
+        };
+        let bundle = Bundle {
+            transactions: self.transactions.clone(),
+            block_number: parent.number + 1,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: Vec::new(),
+        };
+        // Simulate the next slot by default
+        let timestamp = self.timestamp.unwrap_or(parent.timestamp + 12);
+        let coinbase = self.coinbase.unwrap_or(parent.coinbase);
+        let bundle_hash = bundle.hash();
+        let state_block_number = parent.number;
+        let blockchain = context.blockchain.clone();
+        let BundleSimulation {
+            coinbase_diff,
+            gas_used,
+            results,
+        } = tokio::task::spawn_blocking(move || {
+            blockchain.simulate_bundle(&bundle, &parent, timestamp, coinbase)
+        })
+        .await
+        .map_err(|error| RpcErr::Internal(error.to_string()))?
+        .map_err(|error| RpcErr::Internal(error.to_string()))?;
+
+        let results = results
+            .into_iter()
+            .map(|result| CallBundleTransactionResponse {
+                tx_hash: result.tx_hash,
+                gas_used: result.gas_used,
+                from_address: result.sender,
+                to_address: match result.to {
+                    TxKind::Call(to) => Some(to),
+                    TxKind::Create => None,
+                },
+                coinbase_diff: result.coinbase_diff,
+                error: result
+                    .error
+                    .or_else(|| (!result.succeeded).then(|| "execution reverted".to_owned())),
+            })
+            .collect();
+        serde_json::to_value(CallBundleResponse {
+            bundle_hash,
+            coinbase_diff,
+            total_gas_used: gas_used,
+            state_block_number,
+            results,
+        })
+        .map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+/// Returns the searcher that signed the request body, as claimed by the
+/// [`FLASHBOTS_SIGNATURE_HEADER`], or `None` if the signature doesn't match
+pub fn recover_bundle_signer(header: &str, body: &[u8]) -> Option<Address> {
+    let (address, signature) = header.split_once(':')?;
+    let address = Address::from_str(address.trim()).ok()?;
+    let signature = signature.trim();
+    let mut signature = hex::decode(signature.strip_prefix("0x").unwrap_or(signature)).ok()?;
+    if signature.len() != 65 {
+        return None;
+    }
+    // Recovery ids are usually encoded as 27 and 28
+    if signature[64] >= 27 {
+        signature[64] -= 27;
+    }
+    let body_hash = format!("{:#x}", keccak(body));
+    let message = format!(
+        "\x19Ethereum Signed Message:\n{}{body_hash}",
+        body_hash.len()
+    );
+    let signer =
+        recover_address_from_message(Signature::from_slice(&signature), &Bytes::from(message))
+            .ok()?;
+    (signer == address).then_some(signer)
+}
+
+/// Returns the bundle pool if the searcher that signed the request is allowed to send bundles
+fn authorized_pool(context: &RpcApiContext) -> Result<&BundlePool, RpcErr> {
+    let Some(pool) = &context.blockchain.bundles else {
+        return Err(RpcErr::MethodNotFound("bundles are disabled".to_owned()));
+    };
+    match context.bundle_signer {
+        Some(signer) if pool.is_signer_allowed(&signer) => Ok(pool),
+        _ => Err(RpcErr::AuthenticationError(
+            AuthenticationError::InvalidBundleSignature,
+        )),
+    }
+}
+
+fn parse_bundle_param<T: for<'de> Deserialize<'de>>(
+    params: &Option<Vec<Value>>,
+) -> Result<T, RpcErr> {
+    let params = params
+        .as_ref()
+        .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+    if params.len() != 1 {
+        return Err(RpcErr::BadParams(format!(
+            "Expected one param and {} were provided",
+            params.len()
+        )));
+    };
+    Ok(serde_json::from_value(params[0].clone())?)
+}
+
+/// Decodes the raw transactions of a bundle and recovers their senders
+fn decode_transactions(txs: &[Bytes]) -> Result<Vec<(Transaction, Address)>, RpcErr> {
+    if txs.is_empty() {
+        return Err(RpcErr::BadParams("Bundle has no transactions".to_owned()));
+    }
+    if txs.len() > MAX_BUNDLE_TRANSACTIONS {
+        return Err(RpcErr::LimitExceeded(format!(
+            "bundle has {} transactions, the limit is {MAX_BUNDLE_TRANSACTIONS}",
+            txs.len()
+        )));
+    }
+    txs.iter()
+        .map(|tx| {
+            let tx = Transaction::decode_canonical(tx)
+                .map_err(|error| RpcErr::BadParams(error.to_string()))?;
+            // Bundles can't carry the blobs of blob transactions
+            if matches!(tx.tx_type(), TxType::EIP4844 | TxType::Privileged) {
+                return Err(RpcErr::BadParams("Invalid transaction type".to_owned()));
+            }
+            let sender = tx.sender()?;
+            Ok((tx, sender))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::map_http_requests, test_utils::default_context_with_storage, utils::RpcRequest,
+    };
+    use ethrex_blockchain::{Blockchain, BlockchainOptions, bundle::BundlePoolOptions};
+    use ethrex_storage::{EngineType, Store};
+    use secp256k1::{Message, SECP256K1, SecretKey};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    /// Signs the body like Flashbots searchers do, returning the header value
+    fn sign_body(secret_key: &SecretKey, body: &[u8]) -> String {
+        let body_hash = format!("{:#x}", keccak(body));
+        let message = format!(
+            "\x19Ethereum Signed Message:\n{}{body_hash}",
+            body_hash.len()
+        );
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(
+                &Message::from_digest(keccak(message.as_bytes()).0),
+                secret_key,
+            )
+            .serialize_compact();
+        let address = Address::from_slice(
+            &keccak(&secret_key.public_key(SECP256K1).serialize_uncompressed()[1..])[12..],
+        );
+        format!(
+            "{address:#x}:0x{}{:02x}",
+            hex::encode(signature),
+            i32::from(recovery_id) + 27
+        )
+    }
+
+    #[test]
+    fn bundle_signer_is_recovered_from_the_signature_header() {
+        let secret_key = SecretKey::from_slice(&[1; 32]).unwrap();
+        let body = br#"{"jsonrpc":"2.0","id":1,"method":"eth_sendBundle","params":[]}"#;
+        let header = sign_body(&secret_key, body);
+        let (address, signature) = header.split_once(':').unwrap();
+
+        assert_eq!(
+            recover_bundle_signer(&header, body),
+            Some(Address::from_str(address).unwrap())
+        );
+        // Another body
+        assert_eq!(recover_bundle_signer(&header, b"{}"), None);
+        // Claiming to be someone else
+        let impersonating = format!("{:#x}:{signature}", Address::repeat_byte(1));
+        assert_eq!(recover_bundle_signer(&impersonating, body), None);
+        assert_eq!(recover_bundle_signer(address, body), None);
+    }
+
+    #[test]
+    fn parse_send_bundle_rejects_undecodable_transactions() {
+        let params = Some(vec![json!({
+            "txs": ["0x1234"],
+            "blockNumber": "0x10",
+        })]);
+        assert!(matches!(
+            SendBundleRequest::parse(&params),
+            Err(RpcErr::BadParams(_))
+        ));
+        let params = Some(vec![json!({ "txs": [], "blockNumber": "0x10" })]);
+        assert!(matches!(
+            SendBundleRequest::parse(&params),
+            Err(RpcErr::BadParams(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn bundles_are_only_accepted_from_allowed_signers_of_builders() {
+        let storage = Store::new("", EngineType::InMemory).unwrap();
+        let mut context = default_context_with_storage(storage.clone()).await;
+        let request = RpcRequest::new(
+            "eth_sendBundle",
+            Some(vec![json!({ "txs": ["0x1234"], "blockNumber": "0x10" })]),
+        );
+        assert!(matches!(
+            map_http_requests(&request, context.clone()).await,
+            Err(RpcErr::MethodNotFound(_))
+        ));
+
+        let searcher = Address::repeat_byte(1);
+        context.blockchain = Arc::new(Blockchain::new(
+            storage,
+            BlockchainOptions {
+                bundles: Some(BundlePoolOptions {
+                    allowed_signers: vec![searcher],
+                }),
+                ..Default::default()
+            },
+        ));
+        for signer in [None, Some(Address::repeat_byte(2))] {
+            context.bundle_signer = signer;
+            assert!(matches!(
+                authorized_pool(&context),
+                Err(RpcErr::AuthenticationError(
+                    AuthenticationError::InvalidBundleSignature
+                ))
+            ));
+        }
+        context.bundle_signer = Some(searcher);
+        assert!(authorized_pool(&context).is_ok());
+    }
+}
@@ -3,6 +3,7 @@
 // - Go-Ethereum, specifically: https://github.com/ethereum/go-ethereum/blob/368e16f39d6c7e5cce72a92ec289adbfbaed4854/eth/filters/filter.go
 // - Ethereum's reference: https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_newfilter
 use crate::{
+    limits::LogsLimits,
     rpc::{RpcApiContext, RpcHandler},
     types::{
         block_identifier::{BlockIdentifier, BlockTag},
@@ -11,7 +12,7 @@ use crate::{
     utils::RpcErr,
 };
 use ethrex_common::{H160, H256};
-use ethrex_storage::Store;
+use ethrex_storage::{LogIndexQuery, Store};
 use serde::Deserialize;
 use serde_json::Value;
 use std::collections::HashSet;
@@ -102,7 +103,8 @@ impl RpcHandler for LogsFilter {
         }
     }
     async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
-        let filtered_logs = fetch_logs_with_filter(self, context.storage).await?;
+        let limits = context.limiter.limits().logs;
+        let filtered_logs = fetch_logs_with_filter(self, context.storage, limits).await?;
         serde_json::to_value(filtered_logs).map_err(|error| {
             tracing::error!("Log filtering request failed with: {error}");
             RpcErr::Internal("Failed to filter logs".to_string())
@@ -122,6 +124,7 @@ impl RpcHandler for LogsFilter {
 pub(crate) async fn fetch_logs_with_filter(
     filter: &LogsFilter,
     storage: Store,
+    limits: LogsLimits,
 ) -> Result<Vec<RpcLog>, RpcErr> {
     let from = filter
         .from_block
@@ -136,18 +139,24 @@ pub(crate) async fn fetch_logs_with_filter(
     if (from..=to).is_empty() {
         return Err(RpcErr::BadParams("Empty range".to_string()));
     }
+    limits.check_block_range(from, to)?;
     let address_filter: HashSet<_> = match &filter.address_filters {
         Some(AddressFilter::Single(address)) => std::iter::once(address).collect(),
         Some(AddressFilter::Many(addresses)) => addresses.iter().collect(),
         None => HashSet::new(),
     };
 
+    let query = LogIndexQuery {
+        addresses: address_filter.iter().map(|address| **address).collect(),
+        topics: filter.topics.iter().map(indexed_topics).collect(),
+    };
+
     let mut logs: Vec<RpcLog> = Vec::new();
-    // The idea here is to fetch every log and filter by address, if given.
-    // For that, we'll need each block in range, and its transactions,
-    // and for each transaction, we'll need its receipts, which
+    // The idea here is to fetch every log and filter by address and topics, if given.
+    // For that, we'll need each block that may have matching logs according to the log index,
+    // and its transactions, and for each transaction, we'll need its receipts, which
     // contain the actual logs we want.
-    for block_num in from..=to {
+    for block_num in storage.get_log_candidate_blocks(from..=to, query).await? {
         // Take the header of the block, we
         // will use it to access the transactions.
         let block_body = storage
@@ -176,7 +185,9 @@ pub(crate) async fn fetch_logs_with_filter(
 
             if receipt.succeeded {
                 for log in &receipt.logs {
-                    if address_filter.is_empty() || address_filter.contains(&log.address) {
+                    if (address_filter.is_empty() || address_filter.contains(&log.address))
+                        && matches_topics(&filter.topics, &log.topics)
+                    {
                         // Some extra data is needed when
                         // forming the RPC response.
                         logs.push(RpcLog {
@@ -188,47 +199,51 @@ pub(crate) async fn fetch_logs_with_filter(
                             block_hash,
                             removed: false,
                         });
+                        limits.check_logs(logs.len())?;
                     }
                     block_log_index += 1;
                 }
             }
         }
     }
-    // Now that we have the logs filtered by address,
-    // we still need to filter by topics if it was a given parameter.
+    Ok(logs)
+}
 
-    let filtered_logs = if filter.topics.is_empty() {
-        logs
-    } else {
-        logs.into_iter()
-            .filter(|rpc_log| {
-                if filter.topics.len() > rpc_log.log.topics.len() {
+/// Topics to look up in the log index for a position, empty if it matches any topic
+fn indexed_topics(topic_filter: &TopicFilter) -> Vec<H256> {
+    match topic_filter {
+        TopicFilter::Topic(topic) => topic.iter().copied().collect(),
+        TopicFilter::Topics(sub_topics) => sub_topics
+            .iter()
+            .copied()
+            .collect::<Option<_>>()
+            .unwrap_or_default(),
+    }
+}
+
+fn matches_topics(topic_filters: &[TopicFilter], topics: &[H256]) -> bool {
+    if topic_filters.len() > topics.len() {
+        return false;
+    }
+    for (i, topic_filter) in topic_filters.iter().enumerate() {
+        match topic_filter {
+            TopicFilter::Topic(topic) => {
+                if topic.is_some_and(|topic| topics[i] != topic) {
                     return false;
                 }
-                for (i, topic_filter) in filter.topics.iter().enumerate() {
-                    match topic_filter {
-                        TopicFilter::Topic(topic) => {
-                            if topic.is_some_and(|topic| rpc_log.log.topics[i] != topic) {
-                                return false;
-                            }
-                        }
-                        TopicFilter::Topics(sub_topics) => {
-                            if !sub_topics.is_empty()
-                                && !sub_topics
-                                    .iter()
-                                    .any(|st| st.is_none_or(|t| rpc_log.log.topics[i] == t))
-                            {
-                                return false;
-                            }
-                        }
-                    }
+            }
+            TopicFilter::Topics(sub_topics) => {
+                if !sub_topics.is_empty()
+                    && !sub_topics
+                        .iter()
+                        .any(|st| st.is_none_or(|t| topics[i] == t))
+                {
+                    return false;
                 }
-                true
-            })
-            .collect::<Vec<RpcLog>>()
-    };
-
-    Ok(filtered_logs)
+            }
+        }
+    }
+    true
 }
 
 #[cfg(test)]
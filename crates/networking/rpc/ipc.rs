@@ -0,0 +1,240 @@
+use std::{
+    fs, io,
+    os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::de::IgnoredAny;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{UnixListener, UnixStream, unix::OwnedWriteHalf},
+    signal::unix::{SignalKind, signal},
+    sync::watch,
+};
+use tracing::{debug, info, warn};
+
+use crate::{
+    api_filter::RpcApiFilter,
+    rpc::{RpcApiContext, handle_authrpc_body, handle_rpc_body, rpc_response, shutdown_signal},
+    utils::{RpcErr, RpcRequestId},
+};
+
+/// Methods served through an IPC socket
+#[derive(Clone)]
+pub(crate) enum IpcApi {
+    /// The namespaces enabled in the filter, for local clients
+    Local(Arc<RpcApiFilter>),
+    /// The methods of the authenticated rpc server, for the consensus client, notifying each
+    /// request through the heartbeat channel
+    Engine(Arc<watch::Sender<()>>),
+}
+
+impl IpcApi {
+    async fn handle(&self, context: RpcApiContext, body: &[u8]) -> Result<Value, RpcErr> {
+        match self {
+            IpcApi::Local(api) => handle_rpc_body(context, api, None, body).await,
+            IpcApi::Engine(heartbeat) => {
+                let _ = heartbeat.send(());
+                handle_authrpc_body(context, body).await
+            }
+        }
+    }
+}
+
+/// Binds the IPC socket, replacing the one left behind by a previous run, if any
+pub(crate) fn bind_ipc(path: &Path) -> io::Result<UnixListener> {
+    if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+        fs::remove_file(path)?;
+    }
+    UnixListener::bind(path)
+}
+
+/// Binds the IPC socket so that only the owner of the node's process can connect to it.
+///
+/// The socket is bound inside a private directory next to `path`, and only moved into place once
+/// its permissions are restricted, so no one else can connect to it in between.
+pub(crate) fn bind_private_ipc(path: &Path) -> io::Result<UnixListener> {
+    if fs::symlink_metadata(path).is_ok_and(|metadata| !metadata.file_type().is_socket()) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists and isn't a socket", path.display()),
+        ));
+    }
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "IPC path has no file name"))?;
+    let mut private_dir = path.to_path_buf();
+    private_dir.set_file_name(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    fs::DirBuilder::new().mode(0o700).create(&private_dir)?;
+
+    let private_path = private_dir.join(file_name);
+    let listener = UnixListener::bind(&private_path).and_then(|listener| {
+        fs::set_permissions(&private_path, fs::Permissions::from_mode(0o600))?;
+        // Replaces the socket left behind by a previous run, if any
+        fs::rename(&private_path, path)?;
+        Ok(listener)
+    });
+    let _ = fs::remove_dir_all(&private_dir);
+    listener
+}
+
+/// Serves JSON-RPC requests through the IPC socket until the node shuts down, removing the socket afterwards
+pub(crate) async fn serve_ipc(
+    listener: UnixListener,
+    path: PathBuf,
+    context: RpcApiContext,
+    api: IpcApi,
+) -> io::Result<()> {
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    // The node is also stopped with SIGTERM, which must not leave the socket behind either
+    let mut terminate = signal(SignalKind::terminate())?;
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = terminate.recv() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(handle_ipc_connection(stream, context.clone(), api.clone()));
+                }
+                Err(error) => warn!("Failed to accept IPC connection: {error}"),
+            },
+        }
+    }
+    drop(listener);
+    info!("Removing IPC socket at {}", path.display());
+    fs::remove_file(path)
+}
+
+/// Requests aren't delimited in IPC, so they are read as a stream of JSON values, answering each
+/// one as soon as it's complete
+async fn handle_ipc_connection(stream: UnixStream, context: RpcApiContext, api: IpcApi) {
+    let (mut reader, mut writer) = stream.into_split();
+    let mut buffer = Vec::new();
+    loop {
+        match reader.read_buf(&mut buffer).await {
+            Ok(0) => return,
+            Ok(_) => {}
+            Err(error) => {
+                debug!("Failed to read from IPC connection: {error}");
+                return;
+            }
+        }
+
+        let mut consumed = 0;
+        let mut bodies = serde_json::Deserializer::from_slice(&buffer).into_iter::<IgnoredAny>();
+        loop {
+            let response = match bodies.next() {
+                Some(Ok(_)) => {
+                    let end = bodies.byte_offset();
+                    let Some(body) = buffer.get(consumed..end) else {
+                        return;
+                    };
+                    consumed = end;
+                    match api.handle(context.clone(), body).await {
+                        Ok(response) => response,
+                        Err(_) => return,
+                    }
+                }
+                // The rest of the request hasn't arrived yet
+                Some(Err(error)) if error.is_eof() => break,
+                // There's no way of telling where the next request starts after a malformed one
+                Some(Err(_)) => {
+                    let Ok(response) = rpc_response(
+                        RpcRequestId::String("".to_string()),
+                        Err(RpcErr::BadParams("Invalid request body".to_string())),
+                    ) else {
+                        return;
+                    };
+                    let _ = write_response(&mut writer, &response).await;
+                    return;
+                }
+                None => break,
+            };
+            if write_response(&mut writer, &response).await.is_err() {
+                return;
+            }
+        }
+        buffer.drain(..consumed);
+    }
+}
+
+async fn write_response(writer: &mut OwnedWriteHalf, response: &Value) -> io::Result<()> {
+    let mut response = serde_json::to_vec(response)?;
+    response.push(b'\n');
+    writer.write_all(&response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::default_context_with_storage;
+    use ethrex_storage::{EngineType, Store};
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    #[tokio::test]
+    async fn private_sockets_are_only_accessible_by_the_owner() {
+        let dir = std::env::temp_dir().join(format!("ethrex-ipc-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("engine.ipc");
+        // Left behind by a previous run
+        drop(bind_ipc(&path).unwrap());
+
+        let _listener = bind_private_ipc(&path).unwrap();
+        let metadata = fs::symlink_metadata(&path).unwrap();
+        assert!(metadata.file_type().is_socket());
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        assert!(UnixStream::connect(&path).await.is_ok());
+        // Only the socket is left in the directory
+        let entries: Vec<_> = fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        fs::remove_file(&path).unwrap();
+        fs::write(&path, b"not a socket").unwrap();
+        assert!(bind_private_ipc(&path).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn requests_split_across_reads_are_answered_in_order() {
+        let storage = Store::new("", EngineType::InMemory).expect("Failed to create test DB");
+        let context = default_context_with_storage(storage).await;
+        let (client, server) = UnixStream::pair().unwrap();
+        tokio::spawn(handle_ipc_connection(
+            server,
+            context,
+            IpcApi::Local(Arc::new(RpcApiFilter::local())),
+        ));
+
+        let (reader, mut writer) = client.into_split();
+        let requests = concat!(
+            r#"{"jsonrpc":"2.0","method":"web3_clientVersion","params":[],"id":1}"#,
+            r#"[{"jsonrpc":"2.0","method":"web3_clientVersion","params":[],"id":2},"#,
+            r#"{"jsonrpc":"2.0","method":"engine_getPayloadV1","params":[],"id":3}]"#,
+        );
+        let (first, second) = requests.split_at(90);
+        writer.write_all(first.as_bytes()).await.unwrap();
+        writer.flush().await.unwrap();
+        writer.write_all(second.as_bytes()).await.unwrap();
+
+        let mut lines = BufReader::new(reader).lines();
+        let response: Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"], "ethrex/test");
+
+        let response: Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(response[0]["id"], 2);
+        assert_eq!(response[0]["result"], "ethrex/test");
+        // The engine namespace is only served by the authenticated rpc server
+        assert_eq!(response[1]["id"], 3);
+        assert_eq!(response[1]["error"]["code"], -32601);
+    }
+}
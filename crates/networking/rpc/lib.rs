@@ -2,13 +2,19 @@
 #![recursion_limit = "400"]
 
 mod admin;
+mod api_filter;
 mod authentication;
+mod builder;
 pub mod debug;
 mod engine;
 mod eth;
+mod ipc;
+mod limits;
 mod mempool;
 mod net;
+mod ots;
 mod rpc;
+mod trace;
 mod tracing;
 
 pub mod clients;
@@ -16,6 +22,11 @@ pub mod types;
 pub mod utils;
 pub use clients::{EngineClient, EthClient};
 
+pub use api_filter::{DEFAULT_HTTP_API, RpcApiFilter};
+pub use builder::{BlockBuilderConfig, start_block_builder};
+pub use limits::{
+    LogsLimits, RateLimit, ResponseBudget, RpcLimiter, RpcLimits, batch_error_response,
+};
 pub use rpc::{start_api, start_block_executor};
 
 #[cfg(test)]
@@ -31,6 +42,7 @@ pub use eth::{
 };
 pub use rpc::{
     NodeData, RpcApiContext, RpcHandler, RpcRequestWrapper, map_debug_requests, map_eth_requests,
-    map_http_requests, rpc_response, shutdown_signal,
+    map_http_requests, map_ots_requests, map_trace_requests, rpc_response, serve_optional,
+    shutdown_signal,
 };
 pub use utils::{RpcErr, RpcErrorMetadata, RpcNamespace};
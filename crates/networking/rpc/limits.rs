@@ -0,0 +1,437 @@
+use std::{
+    collections::HashMap,
+    io,
+    net::IpAddr,
+    sync::{Arc, Mutex, PoisonError},
+    time::Instant,
+};
+
+use ethrex_metrics::rpc::{RpcOutcome, record_rpc_outcome};
+use serde_json::Value;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{
+    rpc::{get_error_kind, rpc_response},
+    utils::{RpcErr, RpcErrorMetadata, RpcRequest, RpcRequestId},
+};
+
+/// Cost in rate limit tokens of the methods without a cost of their own
+pub const DEFAULT_METHOD_COST: u32 = 1;
+/// Methods costing at least this much count towards the limit of expensive requests in flight
+pub const EXPENSIVE_METHOD_COST: u32 = 20;
+
+/// Amount of clients whose rate limit is tracked before forgetting the idle ones
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+const DEFAULT_METHOD_COSTS: [(&str, u32); 27] = [
+    ("eth_call", 5),
+    ("eth_estimateGas", 5),
+    ("eth_createAccessList", 5),
+    ("eth_getProof", 10),
+    ("eth_getAccount", 10),
+    ("eth_getStorageValues", 10),
+    ("eth_getLogs", 20),
+    ("eth_getFilterChanges", 20),
+    ("eth_callBundle", 20),
+    ("debug_executionWitness", 100),
+    ("debug_traceTransaction", 50),
+    ("debug_traceCall", 50),
+    ("debug_traceBlockByNumber", 100),
+    ("debug_traceBlockByHash", 100),
+    ("debug_traceBadBlock", 100),
+    ("trace_transaction", 50),
+    ("trace_get", 50),
+    ("trace_call", 50),
+    ("trace_block", 100),
+    ("trace_replayBlockTransactions", 100),
+    ("trace_filter", 200),
+    ("txpool_content", 20),
+    ("ots_traceTransaction", 50),
+    ("ots_getInternalOperations", 50),
+    ("ots_getContractCreator", 20),
+    ("ots_searchTransactionsBefore", 20),
+    ("ots_searchTransactionsAfter", 20),
+];
+
+/// Limits protecting the node from clients of the public rpc servers
+#[derive(Debug, Clone)]
+pub struct RpcLimits {
+    /// Max amount of requests in a batch
+    pub max_batch_size: usize,
+    /// Max size in bytes of a response, adding up every response of a batch
+    pub max_response_size: usize,
+    /// Per client rate limit, disabled if `None`
+    pub rate_limit: Option<RateLimit>,
+    /// Cost in rate limit tokens of each method, on top of the default ones
+    pub method_costs: HashMap<String, u32>,
+    pub logs: LogsLimits,
+    /// Max amount of expensive requests being processed at the same time, across all clients
+    pub max_expensive_in_flight: usize,
+}
+
+/// Token bucket refilled at `tokens_per_second` up to `burst` tokens, from which requests take
+/// their method's cost
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub tokens_per_second: u32,
+    pub burst: u32,
+}
+
+/// Caps of `eth_getLogs` and log filters
+#[derive(Debug, Clone, Copy)]
+pub struct LogsLimits {
+    pub max_block_range: u64,
+    pub max_logs: usize,
+}
+
+impl Default for RpcLimits {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 1000,
+            max_response_size: 25 * 1024 * 1024,
+            rate_limit: None,
+            method_costs: HashMap::new(),
+            logs: LogsLimits::default(),
+            max_expensive_in_flight: 16,
+        }
+    }
+}
+
+impl Default for LogsLimits {
+    fn default() -> Self {
+        Self {
+            max_block_range: 10_000,
+            max_logs: 10_000,
+        }
+    }
+}
+
+impl RpcLimits {
+    pub fn check_batch_size(&self, batch_size: usize) -> Result<(), RpcErr> {
+        if batch_size > self.max_batch_size {
+            return Err(RpcErr::LimitExceeded(format!(
+                "batch of {batch_size} requests exceeds the limit of {}",
+                self.max_batch_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl LogsLimits {
+    pub fn check_block_range(&self, from: u64, to: u64) -> Result<(), RpcErr> {
+        if to.saturating_sub(from) >= self.max_block_range {
+            return Err(RpcErr::LimitExceeded(format!(
+                "block range {from}..={to} exceeds the limit of {} blocks",
+                self.max_block_range
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn check_logs(&self, logs: usize) -> Result<(), RpcErr> {
+        if logs > self.max_logs {
+            return Err(RpcErr::LimitExceeded(format!(
+                "query returned more than {} logs",
+                self.max_logs
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Enforces the `RpcLimits` on the requests of every client
+#[derive(Debug)]
+pub struct RpcLimiter {
+    limits: RpcLimits,
+    buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+    expensive_requests: Arc<Semaphore>,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RpcLimiter {
+    pub fn new(limits: RpcLimits) -> Self {
+        let expensive_requests = Arc::new(Semaphore::new(limits.max_expensive_in_flight));
+        Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+            expensive_requests,
+        }
+    }
+
+    pub fn limits(&self) -> &RpcLimits {
+        &self.limits
+    }
+
+    pub fn method_cost(&self, method: &str) -> u32 {
+        self.limits
+            .method_costs
+            .get(method)
+            .copied()
+            .or_else(|| {
+                DEFAULT_METHOD_COSTS
+                    .iter()
+                    .find_map(|(name, cost)| (*name == method).then_some(*cost))
+            })
+            .unwrap_or(DEFAULT_METHOD_COST)
+    }
+
+    /// Takes the cost of the method from the client's rate limit, if it has an address, and, for
+    /// expensive methods, a slot among the expensive requests in flight, which is held until the
+    /// returned permit is dropped
+    pub fn admit(
+        &self,
+        client: Option<IpAddr>,
+        method: &str,
+    ) -> Result<Option<OwnedSemaphorePermit>, RpcErr> {
+        self.try_admit(client, method).inspect_err(|error| {
+            record_rpc_outcome("rpc", method, RpcOutcome::Error(get_error_kind(error)))
+        })
+    }
+
+    fn try_admit(
+        &self,
+        client: Option<IpAddr>,
+        method: &str,
+    ) -> Result<Option<OwnedSemaphorePermit>, RpcErr> {
+        let cost = self.method_cost(method);
+        if let (Some(rate_limit), Some(client)) = (&self.limits.rate_limit, client) {
+            self.take_tokens(client, rate_limit, cost)?;
+        }
+        if cost < EXPENSIVE_METHOD_COST {
+            return Ok(None);
+        }
+        self.expensive_requests
+            .clone()
+            .try_acquire_owned()
+            .map(Some)
+            .map_err(|_| {
+                RpcErr::LimitExceeded(format!(
+                    "too many expensive requests in flight, the limit is {}",
+                    self.limits.max_expensive_in_flight
+                ))
+            })
+    }
+
+    fn take_tokens(&self, client: IpAddr, rate_limit: &RateLimit, cost: u32) -> Result<(), RpcErr> {
+        let now = Instant::now();
+        let burst = f64::from(rate_limit.burst);
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&client) {
+            // Clients whose bucket got full again are indistinguishable from new ones
+            buckets.retain(|_, bucket| {
+                bucket.refill(rate_limit, now);
+                bucket.tokens < burst
+            });
+        }
+        let bucket = buckets.entry(client).or_insert(TokenBucket {
+            tokens: burst,
+            last_refill: now,
+        });
+        bucket.refill(rate_limit, now);
+        let cost = f64::from(cost);
+        if bucket.tokens < cost {
+            return Err(RpcErr::LimitExceeded(format!(
+                "rate limit of {} requests per second exceeded",
+                rate_limit.tokens_per_second
+            )));
+        }
+        bucket.tokens -= cost;
+        Ok(())
+    }
+}
+
+impl Default for RpcLimiter {
+    fn default() -> Self {
+        Self::new(RpcLimits::default())
+    }
+}
+
+/// Keeps the responses to a request, or to every request of a batch, within the response size limit
+#[derive(Debug)]
+pub struct ResponseBudget {
+    max_size: usize,
+    remaining: usize,
+}
+
+impl ResponseBudget {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            remaining: max_size,
+        }
+    }
+
+    /// Whether the budget is spent, after which the rest of the batch isn't processed
+    pub fn is_spent(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Builds the response to the request, or an error response if it doesn't fit in the remaining
+    /// budget, which spends it
+    pub fn response<E>(&mut self, req: &RpcRequest, res: Result<Value, E>) -> Result<Value, RpcErr>
+    where
+        E: Into<RpcErrorMetadata>,
+    {
+        let response = rpc_response(req.id.clone(), res)?;
+        let size = json_size(&response)?;
+        if size > self.remaining {
+            self.remaining = 0;
+            return self.too_large(req);
+        }
+        self.remaining -= size;
+        Ok(response)
+    }
+
+    /// Error response to a request that doesn't fit in the budget
+    pub fn too_large(&self, req: &RpcRequest) -> Result<Value, RpcErr> {
+        let error = RpcErr::ResponseTooLarge(self.max_size);
+        record_rpc_outcome(
+            "rpc",
+            &req.method,
+            RpcOutcome::Error(get_error_kind(&error)),
+        );
+        rpc_response(req.id.clone(), Err(error))
+    }
+}
+
+/// Error response to a batch rejected as a whole, which has no request id to reply to
+pub fn batch_error_response(error: RpcErr) -> Result<Value, RpcErr> {
+    record_rpc_outcome("rpc", "batch", RpcOutcome::Error(get_error_kind(&error)));
+    rpc_response(RpcRequestId::Null, Err(error))
+}
+
+/// Size of the value once serialized, without allocating it
+fn json_size(value: &Value) -> Result<usize, RpcErr> {
+    struct ByteCounter(usize);
+
+    impl io::Write for ByteCounter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, value)?;
+    Ok(counter.0)
+}
+
+impl TokenBucket {
+    fn refill(&mut self, rate_limit: &RateLimit, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * f64::from(rate_limit.tokens_per_second))
+            .min(f64::from(rate_limit.burst));
+        self.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const CLIENT: Option<IpAddr> = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+    #[test]
+    fn rate_limit_takes_method_costs() {
+        let limiter = RpcLimiter::new(RpcLimits {
+            // Slow enough for the bucket not to refill during the test
+            rate_limit: Some(RateLimit {
+                tokens_per_second: 1,
+                burst: 60,
+            }),
+            method_costs: HashMap::from([("eth_chainId".to_string(), 2)]),
+            ..Default::default()
+        });
+        assert_eq!(limiter.method_cost("eth_chainId"), 2);
+        assert_eq!(limiter.method_cost("eth_blockNumber"), DEFAULT_METHOD_COST);
+        assert_eq!(limiter.method_cost("eth_getAccount"), 10);
+
+        // 50 tokens
+        assert!(limiter.admit(CLIENT, "debug_traceCall").is_ok());
+        // 10 tokens
+        assert!(limiter.admit(CLIENT, "eth_getProof").is_ok());
+        assert!(matches!(
+            limiter.admit(CLIENT, "eth_chainId"),
+            Err(RpcErr::LimitExceeded(_))
+        ));
+        // Other clients have their own bucket
+        let other_client = Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        assert!(limiter.admit(other_client, "eth_chainId").is_ok());
+    }
+
+    fn request(id: u64) -> RpcRequest {
+        serde_json::from_value(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "eth_chainId",
+            "params": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn response_budget_is_spent_by_the_first_response_not_fitting() {
+        let result = serde_json::json!("0x".to_string() + &"ab".repeat(20));
+        let response =
+            rpc_response(RpcRequestId::Number(1), Ok::<_, RpcErr>(result.clone())).unwrap();
+        let size = json_size(&response).unwrap();
+        let mut budget = ResponseBudget::new(size + size / 2);
+
+        assert_eq!(
+            budget
+                .response(&request(1), Ok::<_, RpcErr>(result.clone()))
+                .unwrap(),
+            response
+        );
+        assert!(!budget.is_spent());
+        let too_large = budget
+            .response(&request(2), Ok::<_, RpcErr>(result))
+            .unwrap();
+        assert_eq!(too_large["id"], 2);
+        assert_eq!(too_large["error"]["code"], -32003);
+        assert!(budget.is_spent());
+    }
+
+    #[test]
+    fn rejected_batches_have_a_null_id() {
+        let limits = RpcLimits {
+            max_batch_size: 2,
+            ..Default::default()
+        };
+        assert!(limits.check_batch_size(2).is_ok());
+        let response = batch_error_response(limits.check_batch_size(3).unwrap_err()).unwrap();
+        assert!(response["id"].is_null());
+        assert_eq!(response["error"]["code"], -32005);
+    }
+
+    #[test]
+    fn expensive_requests_in_flight_are_capped() {
+        let limiter = RpcLimiter::new(RpcLimits {
+            max_expensive_in_flight: 1,
+            ..Default::default()
+        });
+        let permit = limiter.admit(CLIENT, "trace_filter").unwrap();
+        assert!(permit.is_some());
+        assert!(matches!(
+            limiter.admit(CLIENT, "debug_traceTransaction"),
+            Err(RpcErr::LimitExceeded(_))
+        ));
+        // Cheap methods aren't affected
+        assert!(limiter.admit(CLIENT, "eth_blockNumber").unwrap().is_none());
+        drop(permit);
+        assert!(limiter.admit(CLIENT, "debug_traceTransaction").is_ok());
+    }
+}
@@ -0,0 +1,498 @@
+//! Otterscan's `ots_*` namespace, used by the Otterscan block explorer.
+//! https://github.com/otterscan/otterscan/blob/develop/docs/custom-jsonrpc.md
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    evm::calculate_create_address,
+    serde_utils,
+    tracing::{CallTraceFrame, CallType, InternalOperationType},
+    types::{BlockNumber, TxKind},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::ops::RangeInclusive;
+
+use crate::{
+    eth::block::get_all_block_rpc_receipts,
+    rpc::{RpcApiContext, RpcHandler},
+    tracing::{DEFAULT_REEXEC, DEFAULT_TIMEOUT},
+    types::{block_identifier::BlockIdentifierOrHash, transaction::RpcTransaction},
+    utils::{RpcErr, expect_params, flatten_call_trace},
+};
+
+/// Version of the Otterscan api implemented, checked by Otterscan on startup
+const API_LEVEL: u64 = 8;
+/// Max amount of transactions returned by a page of `ots_searchTransactions*`, whole blocks are
+/// returned so pages may have a few more
+const MAX_SEARCH_PAGE_SIZE: usize = 100;
+
+pub struct GetApiLevelRequest;
+
+pub struct HasCodeRequest {
+    address: Address,
+    block: BlockIdentifierOrHash,
+}
+
+pub struct GetInternalOperationsRequest {
+    tx_hash: H256,
+}
+
+pub struct TraceTransactionRequest {
+    tx_hash: H256,
+}
+
+pub struct GetTransactionBySenderAndNonceRequest {
+    sender: Address,
+    nonce: u64,
+}
+
+pub struct GetContractCreatorRequest {
+    address: Address,
+}
+
+pub struct SearchTransactionsBeforeRequest {
+    search: TransactionSearch,
+}
+
+pub struct SearchTransactionsAfterRequest {
+    search: TransactionSearch,
+}
+
+struct TransactionSearch {
+    address: Address,
+    /// Block to search from, excluding it, `0` to search from the latest or the first block
+    block_number: BlockNumber,
+    page_size: usize,
+}
+
+/// Call frame of `ots_traceTransaction`, listed in depth-first order.
+#[derive(Serialize, Debug)]
+struct TraceEntry {
+    #[serde(rename = "type")]
+    entry_type: &'static str,
+    depth: usize,
+    from: Address,
+    to: Address,
+    /// Missing for calls that can't transfer value
+    value: Option<U256>,
+    #[serde(with = "serde_utils::bytes")]
+    input: Bytes,
+    #[serde(with = "serde_utils::bytes")]
+    output: Bytes,
+}
+
+#[derive(Serialize, Debug)]
+struct ContractCreator {
+    hash: H256,
+    creator: Address,
+}
+
+/// Page of `ots_searchTransactions*`, from newest to oldest.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct TransactionsPage {
+    txs: Vec<RpcTransaction>,
+    /// Receipts of `txs`, along with the timestamp of their block
+    receipts: Vec<Value>,
+    /// Whether the page has the newest transactions
+    first_page: bool,
+    /// Whether the page has the oldest transactions
+    last_page: bool,
+}
+
+fn trace_entries(call_trace: CallTraceFrame) -> Vec<TraceEntry> {
+    flatten_call_trace(call_trace, |frame, trace_address, _| {
+        trace_entry(frame, trace_address.len())
+    })
+}
+
+fn trace_entry(frame: CallTraceFrame, depth: usize) -> TraceEntry {
+    let (entry_type, value) = match frame.call_type {
+        CallType::CALL => ("CALL", Some(frame.value)),
+        CallType::CALLCODE => ("CALLCODE", Some(frame.value)),
+        CallType::STATICCALL => ("STATICCALL", None),
+        CallType::DELEGATECALL => ("DELEGATECALL", None),
+        CallType::CREATE => ("CREATE", Some(frame.value)),
+        CallType::CREATE2 => ("CREATE2", Some(frame.value)),
+        CallType::SELFDESTRUCT => ("SELFDESTRUCT", Some(frame.value)),
+    };
+    TraceEntry {
+        entry_type,
+        depth,
+        from: frame.from,
+        to: frame.to,
+        value,
+        input: frame.input,
+        output: frame.output,
+    }
+}
+
+/// Parses a quantity given either as a number, as Otterscan does, or as a string.
+fn parse_quantity(value: &Value, name: &str) -> Result<u64, RpcErr> {
+    value
+        .as_u64()
+        .map(Ok)
+        .unwrap_or_else(|| serde_utils::u64::deser_hex_or_dec_str(value.clone()))
+        .map_err(|_| RpcErr::WrongParam(name.to_string()))
+}
+
+/// The transactions each address took part in are only indexed if enabled
+fn expect_address_index(context: &RpcApiContext) -> Result<(), RpcErr> {
+    if context.blockchain.options.index_addresses {
+        Ok(())
+    } else {
+        Err(RpcErr::Internal(
+            "Addresses aren't indexed, the node must be started with --index-addresses".to_string(),
+        ))
+    }
+}
+
+/// Blocks searched by a page of `ots_searchTransactions*` starting at `block_number`, with `0`
+/// meaning the newest block when searching backwards and the oldest when searching forward, and
+/// whether the search starts at that edge of the chain.
+fn search_range(
+    before: bool,
+    block_number: BlockNumber,
+    latest: BlockNumber,
+) -> (RangeInclusive<BlockNumber>, bool) {
+    match (before, block_number) {
+        (true, 0) => (0..=latest, true),
+        (true, number) => (0..=number - 1, false),
+        (false, 0) => (1..=latest, true),
+        (false, number) => (number.saturating_add(1)..=latest, false),
+    }
+}
+
+/// Whether a page has the newest and the oldest transactions of the address, given whether its
+/// search started at the edge of the chain and whether there are more transactions past it.
+fn page_edges(before: bool, from_edge: bool, more: bool) -> (bool, bool) {
+    if before {
+        (from_edge, !more)
+    } else {
+        (!more, from_edge)
+    }
+}
+
+impl TransactionSearch {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 3, 3)?;
+        let page_size = parse_quantity(&params[2], "pageSize")?;
+        Ok(TransactionSearch {
+            address: serde_json::from_value(params[0].clone())?,
+            block_number: parse_quantity(&params[1], "blockNumber")?,
+            page_size: usize::try_from(page_size)
+                .unwrap_or(usize::MAX)
+                .clamp(1, MAX_SEARCH_PAGE_SIZE),
+        })
+    }
+
+    async fn search(&self, before: bool, context: &RpcApiContext) -> Result<Value, RpcErr> {
+        expect_address_index(context)?;
+        let storage = &context.storage;
+        let latest = storage.get_latest_block_number().await?;
+        let (blocks, from_edge) = search_range(before, self.block_number, latest);
+        let (mut locations, more) = storage
+            .get_address_transactions(self.address, blocks, before, self.page_size)
+            .await?;
+        if !before {
+            locations.reverse();
+        }
+
+        let mut txs = Vec::with_capacity(locations.len());
+        let mut receipts = Vec::with_capacity(locations.len());
+        let mut block_receipts = None;
+        for (block_number, block_hash, index) in locations {
+            let block = storage
+                .get_block_by_hash(block_hash)
+                .await?
+                .ok_or(RpcErr::Internal(format!("Block {block_number} not found")))?;
+            let tx = block
+                .body
+                .transactions
+                .get(index as usize)
+                .cloned()
+                .ok_or(RpcErr::Internal("Transaction not found".to_string()))?;
+            txs.push(RpcTransaction::build(
+                tx,
+                Some(block_number),
+                Some(block_hash),
+                Some(index as usize),
+            )?);
+
+            let timestamp = block.header.timestamp;
+            let receipts_of_block = match block_receipts.take() {
+                Some((number, receipts)) if number == block_number => receipts,
+                _ => {
+                    get_all_block_rpc_receipts(block_number, block.header, block.body, storage)
+                        .await?
+                }
+            };
+            let mut receipt = serde_json::to_value(receipts_of_block.get(index as usize))?;
+            if let Some(receipt) = receipt.as_object_mut() {
+                receipt.insert("timestamp".to_string(), timestamp.into());
+            }
+            receipts.push(receipt);
+            block_receipts = Some((block_number, receipts_of_block));
+        }
+
+        let (first_page, last_page) = page_edges(before, from_edge, more);
+        serde_json::to_value(TransactionsPage {
+            txs,
+            receipts,
+            first_page,
+            last_page,
+        })
+        .map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+impl RpcHandler for GetApiLevelRequest {
+    fn parse(_params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        Ok(GetApiLevelRequest)
+    }
+
+    async fn handle(&self, _context: RpcApiContext) -> Result<Value, RpcErr> {
+        Ok(API_LEVEL.into())
+    }
+}
+
+impl RpcHandler for HasCodeRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 2, 2)?;
+        Ok(HasCodeRequest {
+            address: serde_json::from_value(params[0].clone())?,
+            block: BlockIdentifierOrHash::parse(params[1].clone(), 1)?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block_number = self
+            .block
+            .resolve_block_number(&context.storage)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        let has_code = context
+            .storage
+            .get_code_by_account_address(block_number, self.address)
+            .await?
+            .is_some_and(|code| !code.bytecode.is_empty());
+        Ok(has_code.into())
+    }
+}
+
+impl RpcHandler for GetInternalOperationsRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 1, 1)?;
+        Ok(GetInternalOperationsRequest {
+            tx_hash: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let operations = context
+            .blockchain
+            .trace_transaction_internal_operations(self.tx_hash, DEFAULT_REEXEC, DEFAULT_TIMEOUT)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        serde_json::to_value(operations).map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+impl RpcHandler for TraceTransactionRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 1, 1)?;
+        Ok(TraceTransactionRequest {
+            tx_hash: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let call_trace = context
+            .blockchain
+            .trace_transaction_calls(self.tx_hash, DEFAULT_REEXEC, DEFAULT_TIMEOUT, false, false)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        let entries: Vec<_> = call_trace.into_iter().flat_map(trace_entries).collect();
+        serde_json::to_value(entries).map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+impl RpcHandler for GetTransactionBySenderAndNonceRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 2, 2)?;
+        Ok(GetTransactionBySenderAndNonceRequest {
+            sender: serde_json::from_value(params[0].clone())?,
+            nonce: parse_quantity(&params[1], "nonce")?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        expect_address_index(&context)?;
+        let tx_hash = context
+            .storage
+            .get_transaction_by_sender_and_nonce(self.sender, self.nonce)
+            .await?;
+        serde_json::to_value(tx_hash).map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+impl RpcHandler for GetContractCreatorRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 1, 1)?;
+        Ok(GetContractCreatorRequest {
+            address: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        expect_address_index(&context)?;
+        let storage = &context.storage;
+        let latest = storage.get_latest_block_number().await?;
+        let has_code = storage
+            .get_code_by_account_address(latest, self.address)
+            .await?
+            .is_some_and(|code| !code.bytecode.is_empty());
+        if !has_code {
+            return Ok(Value::Null);
+        }
+        // The creation transaction is the first one the contract took part in, unless value was
+        // sent to its address before, so the whole first block is looked into
+        let (locations, _) = storage
+            .get_address_transactions(self.address, 0..=latest, false, 1)
+            .await?;
+        for (_, block_hash, index) in locations {
+            let Some(tx) = storage
+                .get_transaction_by_location(block_hash, index)
+                .await?
+            else {
+                continue;
+            };
+            if tx.to() == TxKind::Create {
+                let creator = tx.sender()?;
+                if calculate_create_address(creator, tx.nonce()) == self.address {
+                    return contract_creator(tx.hash(), creator);
+                }
+            }
+            // Contracts deployed by other contracts are found among the internal operations
+            let operations = context
+                .blockchain
+                .trace_transaction_internal_operations(tx.hash(), DEFAULT_REEXEC, DEFAULT_TIMEOUT)
+                .await
+                .map_err(|err| RpcErr::Internal(err.to_string()))?;
+            if let Some(operation) = operations.iter().find(|operation| {
+                matches!(
+                    operation.operation_type,
+                    InternalOperationType::Create | InternalOperationType::Create2
+                ) && operation.to == self.address
+            }) {
+                return contract_creator(tx.hash(), operation.from);
+            }
+        }
+        Ok(Value::Null)
+    }
+}
+
+fn contract_creator(hash: H256, creator: Address) -> Result<Value, RpcErr> {
+    serde_json::to_value(ContractCreator { hash, creator })
+        .map_err(|error| RpcErr::Internal(error.to_string()))
+}
+
+impl RpcHandler for SearchTransactionsBeforeRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        Ok(SearchTransactionsBeforeRequest {
+            search: TransactionSearch::parse(params)?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        self.search.search(true, &context).await
+    }
+}
+
+impl RpcHandler for SearchTransactionsAfterRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        Ok(SearchTransactionsAfterRequest {
+            search: TransactionSearch::parse(params)?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        self.search.search(false, &context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn searches_start_at_the_edges_of_the_chain_or_next_to_the_block_given() {
+        assert_eq!(search_range(true, 0, 10), (0..=10, true));
+        assert_eq!(search_range(true, 5, 10), (0..=4, false));
+        assert_eq!(search_range(false, 0, 10), (1..=10, true));
+        assert_eq!(search_range(false, 5, 10), (6..=10, false));
+        // Searching past the newest block finds nothing
+        assert!(search_range(false, 10, 10).0.is_empty());
+    }
+
+    #[test]
+    fn pages_tell_whether_they_have_the_newest_or_oldest_transactions() {
+        // Backwards from the newest block, with older transactions left
+        assert_eq!(page_edges(true, true, true), (true, false));
+        // Backwards from a block, reaching the oldest transaction
+        assert_eq!(page_edges(true, false, false), (false, true));
+        // Forward from the oldest block, with newer transactions left
+        assert_eq!(page_edges(false, true, true), (false, true));
+        // Forward from a block, reaching the newest transaction
+        assert_eq!(page_edges(false, false, false), (true, false));
+        // A single page with every transaction of the address
+        assert_eq!(page_edges(true, true, false), (true, true));
+        assert_eq!(page_edges(false, true, false), (true, true));
+    }
+
+    #[test]
+    fn call_trace_is_flattened_depth_first() {
+        let frame = CallTraceFrame {
+            call_type: CallType::CALL,
+            calls: vec![
+                CallTraceFrame {
+                    call_type: CallType::DELEGATECALL,
+                    calls: vec![CallTraceFrame {
+                        call_type: CallType::CREATE2,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                CallTraceFrame {
+                    call_type: CallType::STATICCALL,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let entries: Vec<_> = trace_entries(frame)
+            .iter()
+            .map(|entry| (entry.entry_type, entry.depth, entry.value.is_some()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("CALL", 0, true),
+                ("DELEGATECALL", 1, false),
+                ("CREATE2", 2, true),
+                ("STATICCALL", 1, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn quantities_are_parsed_from_numbers_and_strings() {
+        assert_eq!(parse_quantity(&Value::from(25), "pageSize").unwrap(), 25);
+        assert_eq!(
+            parse_quantity(&Value::from("0x19"), "pageSize").unwrap(),
+            25
+        );
+        assert!(parse_quantity(&Value::from(-1), "pageSize").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::api_filter::RpcApiFilter;
 use crate::authentication::authenticate;
 use crate::debug::execution_witness::ExecutionWitnessRequest;
 use crate::engine::blobs::{BlobsV2Request, BlobsV3Request};
@@ -16,14 +17,15 @@ use crate::engine::{
 use crate::eth::client::Config;
 use crate::eth::{
     account::{
-        GetBalanceRequest, GetCodeRequest, GetProofRequest, GetStorageAtRequest,
-        GetTransactionCountRequest,
+        GetAccountRequest, GetBalanceRequest, GetCodeRequest, GetProofRequest, GetStorageAtRequest,
+        GetStorageValuesRequest, GetTransactionCountRequest,
     },
     block::{
         BlockNumberRequest, GetBlobBaseFee, GetBlockByHashRequest, GetBlockByNumberRequest,
         GetBlockReceiptsRequest, GetBlockTransactionCountRequest, GetRawBlockRequest,
         GetRawHeaderRequest, GetRawReceipts,
     },
+    bundle::{self, CallBundleRequest, SendBundleRequest},
     client::{ChainId, Syncing},
     fee_market::FeeHistoryRequest,
     filter::{self, ActiveFilters, DeleteFilterRequest, FilterChangesRequest, NewFilterRequest},
@@ -36,7 +38,21 @@ use crate::eth::{
         GetTransactionByHashRequest, GetTransactionReceiptRequest,
     },
 };
-use crate::tracing::{TraceBlockByNumberRequest, TraceTransactionRequest};
+use crate::ipc::{IpcApi, bind_ipc, bind_private_ipc, serve_ipc};
+use crate::limits::{ResponseBudget, RpcLimiter, RpcLimits, batch_error_response};
+use crate::ots::{
+    GetApiLevelRequest, GetContractCreatorRequest, GetInternalOperationsRequest,
+    GetTransactionBySenderAndNonceRequest, HasCodeRequest, SearchTransactionsAfterRequest,
+    SearchTransactionsBeforeRequest, TraceTransactionRequest,
+};
+use crate::trace::{
+    BlockTracesRequest, CallTracesRequest, FilterTracesRequest, GetTraceRequest,
+    ReplayBlockTransactionsRequest, TransactionTracesRequest,
+};
+use crate::tracing::{
+    GetBadBlocksRequest, TraceBadBlockRequest, TraceBlockByHashRequest, TraceBlockByNumberRequest,
+    TraceCallRequest, TraceTransactionRequest,
+};
 use crate::types::transaction::SendRawTransactionRequest;
 use crate::utils::{
     RpcErr, RpcErrorMetadata, RpcErrorResponse, RpcNamespace, RpcRequest, RpcRequestId,
@@ -44,9 +60,14 @@ use crate::utils::{
 };
 use crate::{admin, net};
 use crate::{eth, mempool};
+use axum::Extension;
 use axum::extract::ws::WebSocket;
-use axum::extract::{DefaultBodyLimit, State, WebSocketUpgrade};
-use axum::{Json, Router, http::StatusCode, routing::post};
+use axum::extract::{ConnectInfo, DefaultBodyLimit, State, WebSocketUpgrade};
+use axum::{
+    Json, Router,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+};
 use axum_extra::{
     TypedHeader,
     headers::{Authorization, authorization::Bearer},
@@ -54,7 +75,7 @@ use axum_extra::{
 use bytes::Bytes;
 use ethrex_blockchain::Blockchain;
 use ethrex_blockchain::error::ChainError;
-use ethrex_common::types::Block;
+use ethrex_common::{Address, types::Block};
 use ethrex_metrics::rpc::{RpcOutcome, record_async_duration, record_rpc_outcome};
 use ethrex_p2p::peer_handler::PeerHandler;
 use ethrex_p2p::sync_manager::SyncManager;
@@ -66,7 +87,9 @@ use serde_json::Value;
 use std::{
     collections::HashMap,
     future::IntoFuture,
-    net::SocketAddr,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
     sync::{Arc, Mutex},
     time::Duration,
 };
@@ -74,7 +97,7 @@ use tokio::net::TcpListener;
 use tokio::sync::{
     Mutex as TokioMutex,
     mpsc::{UnboundedSender, unbounded_channel},
-    oneshot,
+    oneshot, watch,
 };
 use tokio::time::timeout;
 use tower_http::cors::CorsLayer;
@@ -176,6 +199,10 @@ pub struct RpcApiContext {
     pub log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
     pub gas_ceil: u64,
     pub block_worker_channel: UnboundedSender<(oneshot::Sender<Result<(), ChainError>>, Block)>,
+    pub limiter: Arc<RpcLimiter>,
+    /// Searcher that signed the body of the http request being served, as required by the bundle
+    /// methods. See [`bundle::FLASHBOTS_SIGNATURE_HEADER`].
+    pub bundle_signer: Option<Address>,
 }
 
 #[derive(Debug, Clone)]
@@ -219,7 +246,7 @@ pub trait RpcHandler: Sized {
     async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr>;
 }
 
-fn get_error_kind(err: &RpcErr) -> &'static str {
+pub(crate) fn get_error_kind(err: &RpcErr) -> &'static str {
     match err {
         RpcErr::MethodNotFound(_) => "MethodNotFound",
         RpcErr::WrongParam(_) => "WrongParam",
@@ -236,6 +263,8 @@ fn get_error_kind(err: &RpcErr) -> &'static str {
         RpcErr::InvalidForkChoiceState(_) => "InvalidForkChoiceState",
         RpcErr::InvalidPayloadAttributes(_) => "InvalidPayloadAttributes",
         RpcErr::UnknownPayload(_) => "UnknownPayload",
+        RpcErr::LimitExceeded(_) => "LimitExceeded",
+        RpcErr::ResponseTooLarge(_) => "ResponseTooLarge",
     }
 }
 
@@ -268,8 +297,14 @@ pub fn start_block_executor(
 #[allow(clippy::too_many_arguments)]
 pub async fn start_api(
     http_addr: SocketAddr,
+    http_api: RpcApiFilter,
     ws_addr: Option<SocketAddr>,
+    ws_api: RpcApiFilter,
+    admin_port: Option<u16>,
+    ipc_path: Option<PathBuf>,
+    limits: RpcLimits,
     authrpc_addr: SocketAddr,
+    authrpc_ipc_path: Option<PathBuf>,
     storage: Store,
     blockchain: Arc<Blockchain>,
     jwt_secret: Bytes,
@@ -303,6 +338,8 @@ pub async fn start_api(
         log_filter_handler,
         gas_ceil,
         block_worker_channel,
+        limiter: Arc::new(RpcLimiter::new(limits)),
+        bundle_signer: None,
     };
 
     // Periodically clean up the active filters for the filters endpoints.
@@ -331,16 +368,21 @@ pub async fn start_api(
         )
         .route("/", post(handle_http_request))
         .layer(cors.clone())
+        .layer(Extension(Arc::new(http_api)))
         .with_state(service_context.clone());
     let http_listener = TcpListener::bind(http_addr)
         .await
         .map_err(|error| RpcErr::Internal(error.to_string()))?;
-    let http_server = axum::serve(http_listener, http_router)
-        .with_graceful_shutdown(shutdown_signal())
-        .into_future();
+    let http_server = axum::serve(
+        http_listener,
+        http_router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .into_future();
     info!("Starting HTTP server at {http_addr}");
 
-    let (timer_sender, mut timer_receiver) = tokio::sync::watch::channel(());
+    let (timer_sender, mut timer_receiver) = watch::channel(());
+    let heartbeat = Arc::new(timer_sender);
 
     tokio::spawn(async move {
         loop {
@@ -351,18 +393,7 @@ pub async fn start_api(
         }
     });
 
-    let authrpc_handler = move |ctx, auth, body| async move {
-        let _ = timer_sender.send(());
-        handle_authrpc_request(ctx, auth, body).await
-    };
-
-    let authrpc_router = Router::new()
-        .route("/", post(authrpc_handler))
-        .with_state(service_context.clone())
-        // Bump the body limit for the engine API to 256MB
-        // This is needed to receive payloads bigger than the default limit of 2MB
-        .layer(DefaultBodyLimit::max(256 * 1024 * 1024));
-
+    let authrpc_router = authrpc_router(service_context.clone(), heartbeat.clone());
     let authrpc_listener = TcpListener::bind(authrpc_addr)
         .await
         .map_err(|error| RpcErr::Internal(error.to_string()))?;
@@ -371,32 +402,104 @@ pub async fn start_api(
         .into_future();
     info!("Starting Auth-RPC server at {authrpc_addr}");
 
-    if let Some(address) = ws_addr {
-        let ws_handler = |ws: WebSocketUpgrade, ctx| async {
-            ws.on_upgrade(|socket| handle_websocket(socket, ctx))
+    let ws_server = if let Some(address) = ws_addr {
+        let ws_handler = |ws: WebSocketUpgrade, ctx, api, client| async {
+            ws.on_upgrade(|socket| handle_websocket(socket, ctx, api, client))
         };
         let ws_router = Router::new()
             .route("/", axum::routing::any(ws_handler))
             .layer(cors)
-            .with_state(service_context);
+            .layer(Extension(Arc::new(ws_api)))
+            .with_state(service_context.clone());
         let ws_listener = TcpListener::bind(address)
             .await
             .map_err(|error| RpcErr::Internal(error.to_string()))?;
-        let ws_server = axum::serve(ws_listener, ws_router)
-            .with_graceful_shutdown(shutdown_signal())
-            .into_future();
         info!("Starting WS server at {address}");
+        Some(
+            axum::serve(
+                ws_listener,
+                ws_router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(shutdown_signal())
+            .into_future(),
+        )
+    } else {
+        None
+    };
 
-        let _ = tokio::try_join!(authrpc_server, http_server, ws_server)
-            .inspect_err(|e| error!("Error shutting down servers: {e:?}"));
+    // The admin namespace can change the node's configuration, so it's only served on the loopback interface
+    let admin_server = if let Some(port) = admin_port {
+        let admin_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let admin_router = Router::new()
+            .route("/", post(handle_http_request))
+            .layer(Extension(Arc::new(RpcApiFilter::namespaces([
+                RpcNamespace::Admin,
+            ]))))
+            .with_state(service_context.clone());
+        let admin_listener = TcpListener::bind(admin_addr)
+            .await
+            .map_err(|error| RpcErr::Internal(error.to_string()))?;
+        info!("Starting admin RPC server at {admin_addr}");
+        Some(
+            axum::serve(
+                admin_listener,
+                admin_router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(shutdown_signal())
+            .into_future(),
+        )
     } else {
-        let _ = tokio::try_join!(authrpc_server, http_server)
-            .inspect_err(|e| error!("Error shutting down servers: {e:?}"));
-    }
+        None
+    };
+
+    let ipc_server = if let Some(path) = ipc_path {
+        let ipc_listener = bind_ipc(&path).map_err(|error| RpcErr::Internal(error.to_string()))?;
+        info!("Starting IPC server at {}", path.display());
+        let api = IpcApi::Local(Arc::new(RpcApiFilter::local()));
+        Some(serve_ipc(ipc_listener, path, service_context.clone(), api))
+    } else {
+        None
+    };
+
+    // There are no headers to carry a JWT through a unix socket, so only the owner of the node's
+    // process is allowed to connect to it instead
+    let authrpc_ipc_server = if let Some(path) = authrpc_ipc_path {
+        let ipc_listener =
+            bind_private_ipc(&path).map_err(|error| RpcErr::Internal(error.to_string()))?;
+        info!("Starting Auth-RPC IPC server at {}", path.display());
+        Some(serve_ipc(
+            ipc_listener,
+            path,
+            service_context,
+            IpcApi::Engine(heartbeat),
+        ))
+    } else {
+        None
+    };
+
+    let _ = tokio::try_join!(
+        authrpc_server,
+        http_server,
+        serve_optional(ws_server),
+        serve_optional(admin_server),
+        serve_optional(ipc_server),
+        serve_optional(authrpc_ipc_server)
+    )
+    .inspect_err(|e| error!("Error shutting down servers: {e:?}"));
 
     Ok(())
 }
 
+/// Runs the server if it was enabled
+pub async fn serve_optional<F: Future<Output = io::Result<()>>>(
+    server: Option<F>,
+) -> io::Result<()> {
+    match server {
+        Some(server) => server.await,
+        None => Ok(()),
+    }
+}
+
 pub async fn shutdown_signal() {
     tokio::signal::ctrl_c()
         .await
@@ -404,29 +507,87 @@ pub async fn shutdown_signal() {
 }
 
 async fn handle_http_request(
-    State(service_context): State<RpcApiContext>,
+    State(mut service_context): State<RpcApiContext>,
+    Extension(api): Extension<Arc<RpcApiFilter>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     body: String,
 ) -> Result<Json<Value>, StatusCode> {
-    let res = match serde_json::from_str::<RpcRequestWrapper>(&body) {
+    service_context.bundle_signer = headers
+        .get(bundle::FLASHBOTS_SIGNATURE_HEADER)
+        .and_then(|signature| signature.to_str().ok())
+        .and_then(|signature| bundle::recover_bundle_signer(signature, body.as_bytes()));
+    let res = handle_rpc_body(service_context, &api, Some(client.ip()), body.as_bytes())
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(Json(res))
+}
+
+/// Handles a single request or batch of requests received by any of the client transports.
+/// Clients without an address, such as the ones connected through IPC, aren't rate limited.
+pub(crate) async fn handle_rpc_body(
+    service_context: RpcApiContext,
+    api: &RpcApiFilter,
+    client: Option<IpAddr>,
+    body: &[u8],
+) -> Result<Value, RpcErr> {
+    let limiter = service_context.limiter.clone();
+    let limits = limiter.limits();
+    let mut budget = ResponseBudget::new(limits.max_response_size);
+    let res = match serde_json::from_slice::<RpcRequestWrapper>(body) {
         Ok(RpcRequestWrapper::Single(request)) => {
-            let res = map_http_requests(&request, service_context).await;
-            rpc_response(request.id, res).map_err(|_| StatusCode::BAD_REQUEST)?
+            let res = map_enabled_requests(&request, api, client, service_context).await;
+            budget.response(&request, res)?
         }
         Ok(RpcRequestWrapper::Multiple(requests)) => {
+            if let Err(error) = limits.check_batch_size(requests.len()) {
+                return batch_error_response(error);
+            }
             let mut responses = Vec::new();
             for req in requests {
-                let res = map_http_requests(&req, service_context.clone()).await;
-                responses.push(rpc_response(req.id, res).map_err(|_| StatusCode::BAD_REQUEST)?);
+                if budget.is_spent() {
+                    responses.push(budget.too_large(&req)?);
+                    continue;
+                }
+                let res = map_enabled_requests(&req, api, client, service_context.clone()).await;
+                responses.push(budget.response(&req, res)?);
             }
-            serde_json::to_value(responses).map_err(|_| StatusCode::BAD_REQUEST)?
+            serde_json::to_value(responses)?
         }
         Err(_) => rpc_response(
             RpcRequestId::String("".to_string()),
             Err(RpcErr::BadParams("Invalid request body".to_string())),
-        )
-        .map_err(|_| StatusCode::BAD_REQUEST)?,
+        )?,
     };
-    Ok(Json(res))
+    Ok(res)
+}
+
+/// Serves the authenticated rpc API over HTTP, and over WebSocket once the upgrade request is
+/// authenticated, notifying each request through the heartbeat channel
+fn authrpc_router(context: RpcApiContext, heartbeat: Arc<watch::Sender<()>>) -> Router {
+    let authrpc_handler = {
+        let heartbeat = heartbeat.clone();
+        move |ctx, auth, body| async move {
+            let _ = heartbeat.send(());
+            handle_authrpc_request(ctx, auth, body).await
+        }
+    };
+    let authrpc_ws_handler =
+        move |ws: WebSocketUpgrade,
+              State(ctx): State<RpcApiContext>,
+              auth: Option<TypedHeader<Authorization<Bearer>>>| async move {
+            authenticate(&ctx.node_data.jwt_secret, auth).map_err(|_| StatusCode::UNAUTHORIZED)?;
+            Ok::<_, StatusCode>(
+                ws.on_upgrade(|socket| handle_authrpc_websocket(socket, ctx, heartbeat)),
+            )
+        };
+
+    Router::new()
+        .route("/", post(authrpc_handler).get(authrpc_ws_handler))
+        .with_state(context)
+        // Bump the body limit for the engine API to 256MB
+        // This is needed to receive payloads bigger than the default limit of 2MB
+        .layer(DefaultBodyLimit::max(256 * 1024 * 1024))
 }
 
 pub async fn handle_authrpc_request(
@@ -434,33 +595,74 @@ pub async fn handle_authrpc_request(
     auth_header: Option<TypedHeader<Authorization<Bearer>>>,
     body: String,
 ) -> Result<Json<Value>, StatusCode> {
-    let req: RpcRequest = match serde_json::from_str(&body) {
-        Ok(req) => req,
-        Err(_) => {
-            return Ok(Json(
-                rpc_response(
-                    RpcRequestId::String("".to_string()),
-                    Err(RpcErr::BadParams("Invalid request body".to_string())),
-                )
-                .map_err(|_| StatusCode::BAD_REQUEST)?,
-            ));
-        }
-    };
-    match authenticate(&service_context.node_data.jwt_secret, auth_header) {
-        Err(error) => Ok(Json(
-            rpc_response(req.id, Err(error)).map_err(|_| StatusCode::BAD_REQUEST)?,
-        )),
-        Ok(()) => {
-            // Proceed with the request
+    if let Err(error) = authenticate(&service_context.node_data.jwt_secret, auth_header) {
+        let id = serde_json::from_str::<RpcRequest>(&body)
+            .map(|req| req.id)
+            .unwrap_or(RpcRequestId::String("".to_string()));
+        return Ok(Json(
+            rpc_response(id, Err(error)).map_err(|_| StatusCode::BAD_REQUEST)?,
+        ));
+    }
+    let res = handle_authrpc_body(service_context, body.as_bytes())
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(Json(res))
+}
+
+/// Handles a request from the consensus client received by any of the authenticated transports,
+/// once the client is authenticated
+pub(crate) async fn handle_authrpc_body(
+    service_context: RpcApiContext,
+    body: &[u8],
+) -> Result<Value, RpcErr> {
+    match serde_json::from_slice::<RpcRequest>(body) {
+        Ok(req) => {
             let res = map_authrpc_requests(&req, service_context).await;
-            Ok(Json(
-                rpc_response(req.id, res).map_err(|_| StatusCode::BAD_REQUEST)?,
-            ))
+            rpc_response(req.id, res)
+        }
+        Err(_) => rpc_response(
+            RpcRequestId::String("".to_string()),
+            Err(RpcErr::BadParams("Invalid request body".to_string())),
+        ),
+    }
+}
+
+/// Serves a WebSocket connection of the consensus client. The client is authenticated when opening
+/// the connection, with the same JWT checks as the http requests, so its messages aren't
+/// authenticated again
+async fn handle_authrpc_websocket(
+    mut socket: WebSocket,
+    service_context: RpcApiContext,
+    heartbeat: Arc<watch::Sender<()>>,
+) {
+    while let Some(message) = socket.recv().await {
+        let Ok(body) = message
+            .and_then(|msg| msg.into_text())
+            .map(|msg| msg.to_string())
+        else {
+            return;
+        };
+        let _ = heartbeat.send(());
+
+        let Ok(response) = handle_authrpc_body(service_context.clone(), body.as_bytes())
+            .await
+            .map(|res| res.to_string())
+        else {
+            return;
+        };
+
+        if socket.send(response.into()).await.is_err() {
+            return;
         }
     }
 }
 
-async fn handle_websocket(mut socket: WebSocket, state: State<RpcApiContext>) {
+async fn handle_websocket(
+    mut socket: WebSocket,
+    state: State<RpcApiContext>,
+    api: Extension<Arc<RpcApiFilter>>,
+    client: ConnectInfo<SocketAddr>,
+) {
     while let Some(message) = socket.recv().await {
         let Ok(body) = message
             .and_then(|msg| msg.into_text())
@@ -470,7 +672,7 @@ async fn handle_websocket(mut socket: WebSocket, state: State<RpcApiContext>) {
         };
 
         // ok-clone: increase arc reference count
-        let Ok(response) = handle_http_request(state.clone(), body)
+        let Ok(response) = handle_http_request(state.clone(), api.clone(), client, body)
             .await
             .map(|res| res.to_string())
         else {
@@ -483,6 +685,20 @@ async fn handle_websocket(mut socket: WebSocket, state: State<RpcApiContext>) {
     }
 }
 
+/// Handle requests from clients, rejecting the ones to methods disabled in the listener they arrived at
+/// and the ones exceeding the client's limits
+async fn map_enabled_requests(
+    req: &RpcRequest,
+    api: &RpcApiFilter,
+    client: Option<IpAddr>,
+    context: RpcApiContext,
+) -> Result<Value, RpcErr> {
+    api.check(req)?;
+    // Held until the request is handled
+    let _permit = context.limiter.admit(client, &req.method)?;
+    map_http_requests(req, context).await
+}
+
 /// Handle requests that can come from either clients or other users
 pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.namespace() {
@@ -492,6 +708,8 @@ pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Resu
         Ok(RpcNamespace::Web3) => map_web3_requests(req, context),
         Ok(RpcNamespace::Net) => map_net_requests(req, context).await,
         Ok(RpcNamespace::Mempool) => map_mempool_requests(req, context),
+        Ok(RpcNamespace::Trace) => map_trace_requests(req, context).await,
+        Ok(RpcNamespace::Ots) => map_ots_requests(req, context).await,
         Ok(RpcNamespace::Engine) => Err(RpcErr::Internal(
             "Engine namespace not allowed in map_http_requests".to_owned(),
         )),
@@ -550,10 +768,25 @@ pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Resul
             DeleteFilterRequest::stateful_call(req, context.storage, context.active_filters)
         }
         "eth_getFilterChanges" => {
-            FilterChangesRequest::stateful_call(req, context.storage, context.active_filters).await
+            FilterChangesRequest::stateful_call(
+                req,
+                context.storage,
+                context.active_filters,
+                context.limiter.limits().logs,
+            )
+            .await
         }
         "eth_sendRawTransaction" => SendRawTransactionRequest::call(req, context).await,
         "eth_getProof" => GetProofRequest::call(req, context).await,
+        "eth_getAccount" => GetAccountRequest::call(req, context).await,
+        "eth_getStorageValues" => GetStorageValuesRequest::call(req, context).await,
+        // Bundles are only served by nodes building blocks for relays
+        "eth_sendBundle" if context.blockchain.bundles.is_some() => {
+            SendBundleRequest::call(req, context).await
+        }
+        "eth_callBundle" if context.blockchain.bundles.is_some() => {
+            CallBundleRequest::call(req, context).await
+        }
         "eth_gasPrice" => GasPrice::call(req, context).await,
         "eth_maxPriorityFeePerGas" => {
             eth::max_priority_fee::MaxPriorityFee::call(req, context).await
@@ -572,44 +805,88 @@ pub async fn map_debug_requests(req: &RpcRequest, context: RpcApiContext) -> Res
         "debug_executionWitness" => ExecutionWitnessRequest::call(req, context).await,
         "debug_traceTransaction" => TraceTransactionRequest::call(req, context).await,
         "debug_traceBlockByNumber" => TraceBlockByNumberRequest::call(req, context).await,
+        "debug_traceBlockByHash" => TraceBlockByHashRequest::call(req, context).await,
+        "debug_traceCall" => TraceCallRequest::call(req, context).await,
+        "debug_getBadBlocks" => GetBadBlocksRequest::call(req, context).await,
+        "debug_traceBadBlock" => TraceBadBlockRequest::call(req, context).await,
         unknown_debug_method => Err(RpcErr::MethodNotFound(unknown_debug_method.to_owned())),
     }
 }
 
-pub async fn map_engine_requests(
-    req: &RpcRequest,
-    context: RpcApiContext,
-) -> Result<Value, RpcErr> {
+pub async fn map_trace_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.method.as_str() {
-        "engine_exchangeCapabilities" => ExchangeCapabilitiesRequest::call(req, context).await,
-        "engine_forkchoiceUpdatedV1" => ForkChoiceUpdatedV1::call(req, context).await,
-        "engine_forkchoiceUpdatedV2" => ForkChoiceUpdatedV2::call(req, context).await,
-        "engine_forkchoiceUpdatedV3" => ForkChoiceUpdatedV3::call(req, context).await,
-        "engine_newPayloadV4" => NewPayloadV4Request::call(req, context).await,
-        "engine_newPayloadV3" => NewPayloadV3Request::call(req, context).await,
-        "engine_newPayloadV2" => NewPayloadV2Request::call(req, context).await,
-        "engine_newPayloadV1" => NewPayloadV1Request::call(req, context).await,
-        "engine_exchangeTransitionConfigurationV1" => {
-            ExchangeTransitionConfigV1Req::call(req, context).await
-        }
-        "engine_getPayloadV5" => GetPayloadV5Request::call(req, context).await,
-        "engine_getPayloadV4" => GetPayloadV4Request::call(req, context).await,
-        "engine_getPayloadV3" => GetPayloadV3Request::call(req, context).await,
-        "engine_getPayloadV2" => GetPayloadV2Request::call(req, context).await,
-        "engine_getPayloadV1" => GetPayloadV1Request::call(req, context).await,
-        "engine_getPayloadBodiesByHashV1" => {
-            GetPayloadBodiesByHashV1Request::call(req, context).await
-        }
-        "engine_getPayloadBodiesByRangeV1" => {
-            GetPayloadBodiesByRangeV1Request::call(req, context).await
+        "trace_block" => BlockTracesRequest::call(req, context).await,
+        "trace_transaction" => TransactionTracesRequest::call(req, context).await,
+        "trace_get" => GetTraceRequest::call(req, context).await,
+        "trace_filter" => FilterTracesRequest::call(req, context).await,
+        "trace_call" => CallTracesRequest::call(req, context).await,
+        "trace_replayBlockTransactions" => ReplayBlockTransactionsRequest::call(req, context).await,
+        unknown_trace_method => Err(RpcErr::MethodNotFound(unknown_trace_method.to_owned())),
+    }
+}
+
+pub async fn map_ots_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+    match req.method.as_str() {
+        "ots_getApiLevel" => GetApiLevelRequest::call(req, context).await,
+        "ots_hasCode" => HasCodeRequest::call(req, context).await,
+        "ots_getInternalOperations" => GetInternalOperationsRequest::call(req, context).await,
+        "ots_traceTransaction" => TraceTransactionRequest::call(req, context).await,
+        "ots_getTransactionBySenderAndNonce" => {
+            GetTransactionBySenderAndNonceRequest::call(req, context).await
         }
-        "engine_getBlobsV1" => BlobsV1Request::call(req, context).await,
-        "engine_getBlobsV2" => BlobsV2Request::call(req, context).await,
-        "engine_getBlobsV3" => BlobsV3Request::call(req, context).await,
-        unknown_engine_method => Err(RpcErr::MethodNotFound(unknown_engine_method.to_owned())),
+        "ots_getContractCreator" => GetContractCreatorRequest::call(req, context).await,
+        "ots_searchTransactionsBefore" => SearchTransactionsBeforeRequest::call(req, context).await,
+        "ots_searchTransactionsAfter" => SearchTransactionsAfterRequest::call(req, context).await,
+        unknown_ots_method => Err(RpcErr::MethodNotFound(unknown_ots_method.to_owned())),
     }
 }
 
+/// Defines `map_engine_requests` along with the list of the methods it dispatches, which is the
+/// one advertised by `engine_exchangeCapabilities`, so both can't get out of sync
+macro_rules! engine_methods {
+    ($($method:literal => $handler:ty),* $(,)?) => {
+        /// Engine methods served by the node, besides `engine_exchangeCapabilities`.
+        /// Add new methods to the `engine_methods!` invocation.
+        pub const ENGINE_CAPABILITIES: &[&str] = &[$($method),*];
+
+        pub async fn map_engine_requests(
+            req: &RpcRequest,
+            context: RpcApiContext,
+        ) -> Result<Value, RpcErr> {
+            match req.method.as_str() {
+                "engine_exchangeCapabilities" => {
+                    ExchangeCapabilitiesRequest::call(req, context).await
+                }
+                $($method => <$handler>::call(req, context).await,)*
+                unknown_engine_method => {
+                    Err(RpcErr::MethodNotFound(unknown_engine_method.to_owned()))
+                }
+            }
+        }
+    };
+}
+
+engine_methods! {
+    "engine_forkchoiceUpdatedV1" => ForkChoiceUpdatedV1,
+    "engine_forkchoiceUpdatedV2" => ForkChoiceUpdatedV2,
+    "engine_forkchoiceUpdatedV3" => ForkChoiceUpdatedV3,
+    "engine_newPayloadV1" => NewPayloadV1Request,
+    "engine_newPayloadV2" => NewPayloadV2Request,
+    "engine_newPayloadV3" => NewPayloadV3Request,
+    "engine_newPayloadV4" => NewPayloadV4Request,
+    "engine_getPayloadV1" => GetPayloadV1Request,
+    "engine_getPayloadV2" => GetPayloadV2Request,
+    "engine_getPayloadV3" => GetPayloadV3Request,
+    "engine_getPayloadV4" => GetPayloadV4Request,
+    "engine_getPayloadV5" => GetPayloadV5Request,
+    "engine_exchangeTransitionConfigurationV1" => ExchangeTransitionConfigV1Req,
+    "engine_getPayloadBodiesByHashV1" => GetPayloadBodiesByHashV1Request,
+    "engine_getPayloadBodiesByRangeV1" => GetPayloadBodiesByRangeV1Request,
+    "engine_getBlobsV1" => BlobsV1Request,
+    "engine_getBlobsV2" => BlobsV2Request,
+    "engine_getBlobsV3" => BlobsV3Request,
+}
+
 pub async fn map_admin_requests(
     req: &RpcRequest,
     mut context: RpcApiContext,
@@ -668,6 +945,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::limits::RateLimit;
     use crate::test_utils::default_context_with_storage;
     use ethrex_common::{
         H160,
@@ -750,6 +1028,7 @@ mod tests {
                         "bpo3Time": null,
                         "bpo4Time": null,
                         "bpo5Time": null,
+                        "amsterdamTime": null,
                         "terminalTotalDifficulty": 0,
                         "terminalTotalDifficultyPassed": true,
                         "blobSchedule": blob_schedule,
@@ -963,4 +1242,196 @@ mod tests {
         let expected_response = to_rpc_response_success_value(&json.to_string());
         assert_eq!(rpc_response.to_string(), expected_response.to_string())
     }
+
+    #[tokio::test]
+    async fn engine_capabilities_are_dispatched() {
+        let storage = Store::new("", EngineType::InMemory).expect("Failed to create test DB");
+        let context = default_context_with_storage(storage).await;
+
+        let body =
+            r#"{"jsonrpc":"2.0","method":"engine_exchangeCapabilities","params":[[]],"id":1}"#;
+        let request: RpcRequest = serde_json::from_str(body).unwrap();
+        let result = map_engine_requests(&request, context.clone())
+            .await
+            .unwrap();
+        let capabilities: Vec<String> = serde_json::from_value(result).unwrap();
+        assert_eq!(capabilities, ENGINE_CAPABILITIES);
+        assert!(!capabilities.contains(&"engine_exchangeCapabilities".to_string()));
+
+        for method in capabilities {
+            let request = RpcRequest {
+                method: method.clone(),
+                ..Default::default()
+            };
+            let result = map_engine_requests(&request, context.clone()).await;
+            assert!(
+                !matches!(result, Err(RpcErr::MethodNotFound(_))),
+                "{method} is advertised but not served"
+            );
+        }
+    }
+
+    /// Serves the http rpc server with the given limits on a random local port
+    async fn serve_http(limits: RpcLimits) -> String {
+        let storage = Store::new("", EngineType::InMemory).expect("Failed to create test DB");
+        let context = RpcApiContext {
+            limiter: Arc::new(RpcLimiter::new(limits)),
+            ..default_context_with_storage(storage).await
+        };
+        let router = Router::new()
+            .route("/", post(handle_http_request))
+            .layer(Extension(Arc::new(RpcApiFilter::default())))
+            .with_state(context);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .into_future(),
+        );
+        url
+    }
+
+    async fn post_json(url: &str, body: Value) -> Value {
+        let response = reqwest::Client::new()
+            .post(url)
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        response.json().await.unwrap()
+    }
+
+    fn client_version_request(id: u64) -> Value {
+        serde_json::json!({"jsonrpc": "2.0", "method": "web3_clientVersion", "params": [], "id": id})
+    }
+
+    fn limit_errors(method: &str, error_kind: &str) -> f64 {
+        ethrex_metrics::rpc::METRICS_RPC_REQUEST_OUTCOMES
+            .with_label_values(&["rpc", method, "error", error_kind])
+            .get()
+    }
+
+    #[tokio::test]
+    async fn rate_limited_requests_get_an_error_and_are_recorded() {
+        let url = serve_http(RpcLimits {
+            // Slow enough for the bucket not to refill during the test
+            rate_limit: Some(RateLimit {
+                tokens_per_second: 1,
+                burst: 1,
+            }),
+            ..Default::default()
+        })
+        .await;
+        let errors = limit_errors("web3_clientVersion", "LimitExceeded");
+
+        let response = post_json(&url, client_version_request(1)).await;
+        assert_eq!(response["result"], "ethrex/test");
+
+        let response = post_json(&url, client_version_request(2)).await;
+        assert_eq!(response["id"], 2);
+        assert_eq!(response["error"]["code"], -32005);
+        assert_eq!(
+            limit_errors("web3_clientVersion", "LimitExceeded"),
+            errors + 1.0
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_batches_are_rejected_with_a_null_id() {
+        let url = serve_http(RpcLimits {
+            max_batch_size: 2,
+            ..Default::default()
+        })
+        .await;
+        let errors = limit_errors("batch", "LimitExceeded");
+
+        let batch = (1..=3).map(client_version_request).collect::<Vec<_>>();
+        let response = post_json(&url, Value::Array(batch)).await;
+
+        assert!(response["id"].is_null());
+        assert_eq!(response["error"]["code"], -32005);
+        assert_eq!(limit_errors("batch", "LimitExceeded"), errors + 1.0);
+    }
+
+    #[tokio::test]
+    async fn batches_stop_being_processed_once_the_response_size_is_spent() {
+        let url = serve_http(RpcLimits {
+            // No response fits
+            max_response_size: 1,
+            // Enough for two requests only
+            rate_limit: Some(RateLimit {
+                tokens_per_second: 1,
+                burst: 2,
+            }),
+            ..Default::default()
+        })
+        .await;
+
+        let batch = (1..=3).map(client_version_request).collect::<Vec<_>>();
+        let response = post_json(&url, Value::Array(batch)).await;
+        for (id, response) in (1..=3).zip(response.as_array().unwrap()) {
+            assert_eq!(response["id"], id);
+            assert_eq!(response["error"]["code"], -32003);
+        }
+
+        // Only the first request of the batch was processed, so the client can still send another
+        let response = post_json(&url, client_version_request(4)).await;
+        assert_eq!(response["error"]["code"], -32003);
+    }
+
+    /// Sends a WebSocket upgrade request with the given JWT, if any, returning the response status
+    async fn authrpc_ws_upgrade(url: &str, token: Option<String>) -> reqwest::StatusCode {
+        let mut request = reqwest::Client::new()
+            .get(url)
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==");
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await.unwrap().status()
+    }
+
+    fn jwt(secret: &[u8], iat: u64) -> String {
+        jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &serde_json::json!({ "iat": iat }),
+            &jsonwebtoken::EncodingKey::from_secret(secret),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn authrpc_websocket_upgrades_require_a_fresh_jwt() {
+        let storage = Store::new("", EngineType::InMemory).expect("Failed to create test DB");
+        let mut context = default_context_with_storage(storage).await;
+        context.node_data.jwt_secret = Bytes::from_static(b"jwt secret");
+        let (heartbeat, _) = watch::channel(());
+        let router = authrpc_router(context, Arc::new(heartbeat));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(axum::serve(listener, router).into_future());
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let unauthorized = reqwest::StatusCode::UNAUTHORIZED;
+        assert_eq!(authrpc_ws_upgrade(&url, None).await, unauthorized);
+        let token = jwt(b"another secret", now);
+        assert_eq!(authrpc_ws_upgrade(&url, Some(token)).await, unauthorized);
+        let stale = jwt(b"jwt secret", now - 120);
+        assert_eq!(authrpc_ws_upgrade(&url, Some(stale)).await, unauthorized);
+
+        let token = jwt(b"jwt secret", now);
+        assert_eq!(
+            authrpc_ws_upgrade(&url, Some(token)).await,
+            reqwest::StatusCode::SWITCHING_PROTOCOLS
+        );
+    }
 }
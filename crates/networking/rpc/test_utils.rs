@@ -1,4 +1,5 @@
 use crate::{
+    api_filter::RpcApiFilter,
     eth::gas_tip_estimator::GasTipEstimator,
     rpc::{NodeData, RpcApiContext, start_api, start_block_executor},
 };
@@ -233,8 +234,14 @@ pub async fn start_test_api() -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         start_api(
             http_addr,
+            RpcApiFilter::default(),
             Some(ws_addr),
+            RpcApiFilter::default(),
+            None,
+            None,
+            Default::default(),
             authrpc_addr,
+            None,
             storage,
             blockchain,
             jwt_secret,
@@ -273,6 +280,8 @@ pub async fn default_context_with_storage(storage: Store) -> RpcApiContext {
         log_filter_handler: None,
         gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
         block_worker_channel,
+        limiter: Default::default(),
+        bundle_signer: None,
     }
 }
 
@@ -0,0 +1,601 @@
+//! Parity-style `trace_*` namespace.
+//! https://openethereum.github.io/JSONRPC-trace-module
+
+use bytes::Bytes;
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, H256, U256, serde_utils,
+    tracing::{CallTraceFrame, CallType, StateDiff, VmTrace},
+    types::{Block, BlockHash, BlockNumber, GenericTransaction},
+};
+use ethrex_vm::tracing::{ReplayTrace, ReplayTraceConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    tracing::{DEFAULT_REEXEC, DEFAULT_TIMEOUT},
+    types::block_identifier::BlockIdentifier,
+    utils::{self, RpcErr, expect_params},
+};
+
+/// Max amount of blocks `trace_filter` replays in a single request
+const MAX_FILTER_BLOCK_RANGE: u64 = 100;
+
+pub struct BlockTracesRequest {
+    block: BlockIdentifier,
+}
+
+pub struct TransactionTracesRequest {
+    tx_hash: H256,
+}
+
+pub struct GetTraceRequest {
+    tx_hash: H256,
+    trace_address: Vec<usize>,
+}
+
+pub struct FilterTracesRequest {
+    filter: TraceFilter,
+}
+
+pub struct CallTracesRequest {
+    transaction: GenericTransaction,
+    trace_types: ReplayTraceConfig,
+    with_trace: bool,
+    block: Option<BlockIdentifier>,
+}
+
+pub struct ReplayBlockTransactionsRequest {
+    block: BlockIdentifier,
+    trace_types: ReplayTraceConfig,
+    with_trace: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct TraceFilter {
+    from_block: Option<Value>,
+    to_block: Option<Value>,
+    #[serde(default)]
+    from_address: Vec<Address>,
+    #[serde(default)]
+    to_address: Vec<Address>,
+    after: Option<usize>,
+    count: Option<usize>,
+}
+
+/// Trace of a single call frame, with its position in the call tree given by `trace_address`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct FlatTrace {
+    action: Action,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    result: Option<TraceResult>,
+    subtraces: usize,
+    trace_address: Vec<usize>,
+    #[serde(rename = "type")]
+    trace_type: FlatTraceType,
+    #[serde(flatten)]
+    location: Option<TraceLocation>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+struct TraceLocation {
+    block_hash: BlockHash,
+    block_number: BlockNumber,
+    transaction_hash: H256,
+    transaction_position: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum FlatTraceType {
+    Call,
+    Create,
+    Suicide,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+enum Action {
+    Call(CallAction),
+    Create(CreateAction),
+    Suicide(SuicideAction),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CallAction {
+    call_type: &'static str,
+    from: Address,
+    to: Address,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    gas: u64,
+    #[serde(with = "serde_utils::bytes")]
+    input: Bytes,
+    value: U256,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CreateAction {
+    from: Address,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    gas: u64,
+    #[serde(with = "serde_utils::bytes")]
+    init: Bytes,
+    value: U256,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct SuicideAction {
+    address: Address,
+    refund_address: Address,
+    balance: U256,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+enum TraceResult {
+    Call {
+        #[serde(rename = "gasUsed", with = "serde_utils::u64::hex_str")]
+        gas_used: u64,
+        #[serde(with = "serde_utils::bytes")]
+        output: Bytes,
+    },
+    Create {
+        address: Address,
+        #[serde(with = "serde_utils::bytes")]
+        code: Bytes,
+        #[serde(rename = "gasUsed", with = "serde_utils::u64::hex_str")]
+        gas_used: u64,
+    },
+}
+
+/// Output of `trace_call` and each transaction of `trace_replayBlockTransactions`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct TraceResults {
+    #[serde(with = "serde_utils::bytes")]
+    output: Bytes,
+    state_diff: Option<StateDiff>,
+    trace: Option<Vec<FlatTrace>>,
+    vm_trace: Option<VmTrace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transaction_hash: Option<H256>,
+}
+
+impl FlatTrace {
+    fn involves(&self, filter: &TraceFilter) -> bool {
+        let (from, to) = match (&self.action, &self.result) {
+            (Action::Call(action), _) => (action.from, Some(action.to)),
+            (Action::Create(action), Some(TraceResult::Create { address, .. })) => {
+                (action.from, Some(*address))
+            }
+            (Action::Create(action), _) => (action.from, None),
+            (Action::Suicide(action), _) => (action.address, Some(action.refund_address)),
+        };
+        let from_matches = filter.from_address.is_empty() || filter.from_address.contains(&from);
+        let to_matches =
+            filter.to_address.is_empty() || to.is_some_and(|to| filter.to_address.contains(&to));
+        from_matches && to_matches
+    }
+}
+
+/// Flattens the call trace of a transaction in depth-first order, as Parity outputs it.
+fn flatten_call_trace(
+    call_trace: CallTraceFrame,
+    location: Option<TraceLocation>,
+) -> Vec<FlatTrace> {
+    utils::flatten_call_trace(call_trace, |frame, trace_address, subtraces| {
+        flat_trace(frame, trace_address.to_vec(), subtraces, location)
+    })
+}
+
+fn flat_trace(
+    frame: CallTraceFrame,
+    trace_address: Vec<usize>,
+    subtraces: usize,
+    location: Option<TraceLocation>,
+) -> FlatTrace {
+    let error = frame.error.map(|error| match error.as_str() {
+        "RevertOpcode" => "Reverted".to_string(),
+        _ => error,
+    });
+    let (trace_type, action, result) = match frame.call_type {
+        CallType::CREATE | CallType::CREATE2 => (
+            FlatTraceType::Create,
+            Action::Create(CreateAction {
+                from: frame.from,
+                gas: frame.gas,
+                init: frame.input,
+                value: frame.value,
+            }),
+            TraceResult::Create {
+                address: frame.to,
+                code: frame.output,
+                gas_used: frame.gas_used,
+            },
+        ),
+        CallType::SELFDESTRUCT => {
+            return FlatTrace {
+                action: Action::Suicide(SuicideAction {
+                    address: frame.from,
+                    refund_address: frame.to,
+                    balance: frame.value,
+                }),
+                error: None,
+                result: None,
+                subtraces: 0,
+                trace_address,
+                trace_type: FlatTraceType::Suicide,
+                location,
+            };
+        }
+        call_type => (
+            FlatTraceType::Call,
+            Action::Call(CallAction {
+                call_type: match call_type {
+                    CallType::CALLCODE => "callcode",
+                    CallType::STATICCALL => "staticcall",
+                    CallType::DELEGATECALL => "delegatecall",
+                    _ => "call",
+                },
+                from: frame.from,
+                to: frame.to,
+                gas: frame.gas,
+                input: frame.input,
+                value: frame.value,
+            }),
+            TraceResult::Call {
+                gas_used: frame.gas_used,
+                output: frame.output,
+            },
+        ),
+    };
+
+    FlatTrace {
+        action,
+        result: error.is_none().then_some(result),
+        error,
+        subtraces,
+        trace_address,
+        trace_type,
+        location,
+    }
+}
+
+/// Flattens the call traces of a replayed block, locating each trace in the block.
+fn flatten_block_traces(block: &Block, replay_traces: Vec<(H256, ReplayTrace)>) -> Vec<FlatTrace> {
+    let block_hash = block.hash();
+    replay_traces
+        .into_iter()
+        .enumerate()
+        .flat_map(|(transaction_position, (transaction_hash, replay_trace))| {
+            let location = TraceLocation {
+                block_hash,
+                block_number: block.header.number,
+                transaction_hash,
+                transaction_position,
+            };
+            flatten_call_trace(replay_trace.call_trace, Some(location))
+        })
+        .collect()
+}
+
+fn trace_results(
+    replay_trace: ReplayTrace,
+    with_trace: bool,
+    transaction_hash: Option<H256>,
+) -> TraceResults {
+    let output = replay_trace.call_trace.output.clone();
+    TraceResults {
+        output,
+        state_diff: replay_trace.state_diff,
+        trace: with_trace.then(|| flatten_call_trace(replay_trace.call_trace, None)),
+        vm_trace: replay_trace.vm_trace,
+        transaction_hash,
+    }
+}
+
+/// Parses the `traceTypes` param, returning the traces to output besides the call trace and
+/// whether the call trace was requested.
+fn parse_trace_types(value: &Value) -> Result<(ReplayTraceConfig, bool), RpcErr> {
+    let trace_types: Vec<String> = serde_json::from_value(value.clone())?;
+    let mut config = ReplayTraceConfig::default();
+    let mut with_trace = false;
+    for trace_type in trace_types {
+        match trace_type.as_str() {
+            "trace" => with_trace = true,
+            "stateDiff" => config.state_diff = true,
+            "vmTrace" => config.vm_trace = true,
+            other => return Err(RpcErr::BadParams(format!("Unknown trace type: {other}"))),
+        }
+    }
+    Ok((config, with_trace))
+}
+
+async fn resolve_block(block: &BlockIdentifier, context: &RpcApiContext) -> Result<Block, RpcErr> {
+    let number = block
+        .resolve_block_number(&context.storage)
+        .await?
+        .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+    context
+        .storage
+        .get_block_by_number(number)
+        .await?
+        .ok_or(RpcErr::Internal("Block not Found".to_string()))
+}
+
+async fn replay_block(
+    block: Block,
+    config: ReplayTraceConfig,
+    context: &RpcApiContext,
+) -> Result<Vec<(H256, ReplayTrace)>, RpcErr> {
+    context
+        .blockchain
+        .trace_block_replay(block, DEFAULT_REEXEC, DEFAULT_TIMEOUT, config)
+        .await
+        .map_err(|err| RpcErr::Internal(err.to_string()))
+}
+
+async fn transaction_traces(
+    tx_hash: H256,
+    context: &RpcApiContext,
+) -> Result<Option<Vec<FlatTrace>>, RpcErr> {
+    let Some((block_number, block_hash, tx_index)) =
+        context.storage.get_transaction_location(tx_hash).await?
+    else {
+        return Ok(None);
+    };
+    let replay_trace = context
+        .blockchain
+        .trace_transaction_replay(
+            tx_hash,
+            DEFAULT_REEXEC,
+            DEFAULT_TIMEOUT,
+            ReplayTraceConfig::default(),
+        )
+        .await
+        .map_err(|err| RpcErr::Internal(err.to_string()))?;
+    let location = TraceLocation {
+        block_hash,
+        block_number,
+        transaction_hash: tx_hash,
+        transaction_position: tx_index as usize,
+    };
+    Ok(Some(flatten_call_trace(
+        replay_trace.call_trace,
+        Some(location),
+    )))
+}
+
+/// Resolves a block of the filter, which defaults to the latest one.
+async fn resolve_filter_block(
+    value: &Option<Value>,
+    context: &RpcApiContext,
+) -> Result<BlockNumber, RpcErr> {
+    let block = match value {
+        Some(value) => BlockIdentifier::parse(value.clone(), 0)?,
+        None => BlockIdentifier::default(),
+    };
+    block
+        .resolve_block_number(&context.storage)
+        .await?
+        .ok_or(RpcErr::Internal("Block not Found".to_string()))
+}
+
+impl RpcHandler for BlockTracesRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 1, 1)?;
+        Ok(BlockTracesRequest {
+            block: BlockIdentifier::parse(params[0].clone(), 0)?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block = resolve_block(&self.block, &context).await?;
+        let replay_traces =
+            replay_block(block.clone(), ReplayTraceConfig::default(), &context).await?;
+        Ok(serde_json::to_value(flatten_block_traces(
+            &block,
+            replay_traces,
+        ))?)
+    }
+}
+
+impl RpcHandler for TransactionTracesRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 1, 1)?;
+        Ok(TransactionTracesRequest {
+            tx_hash: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        Ok(serde_json::to_value(
+            transaction_traces(self.tx_hash, &context).await?,
+        )?)
+    }
+}
+
+impl RpcHandler for GetTraceRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 2, 2)?;
+        let indices: Vec<String> = serde_json::from_value(params[1].clone())?;
+        let trace_address = indices
+            .iter()
+            .map(|index| {
+                usize::from_str_radix(index.trim_start_matches("0x"), 16)
+                    .map_err(|_| RpcErr::BadHexFormat(1))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(GetTraceRequest {
+            tx_hash: serde_json::from_value(params[0].clone())?,
+            trace_address,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let trace = transaction_traces(self.tx_hash, &context)
+            .await?
+            .and_then(|traces| {
+                traces
+                    .into_iter()
+                    .find(|trace| trace.trace_address == self.trace_address)
+            });
+        Ok(serde_json::to_value(trace)?)
+    }
+}
+
+impl RpcHandler for FilterTracesRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 1, 1)?;
+        Ok(FilterTracesRequest {
+            filter: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let from_block = resolve_filter_block(&self.filter.from_block, &context).await?;
+        let to_block = resolve_filter_block(&self.filter.to_block, &context).await?;
+        if from_block > to_block {
+            return Err(RpcErr::BadParams(
+                "fromBlock can't be greater than toBlock".to_string(),
+            ));
+        }
+        if to_block - from_block >= MAX_FILTER_BLOCK_RANGE {
+            return Err(RpcErr::BadParams(format!(
+                "Can't trace more than {MAX_FILTER_BLOCK_RANGE} blocks at once"
+            )));
+        }
+
+        let mut traces = Vec::new();
+        for number in from_block..=to_block {
+            let block = resolve_block(&BlockIdentifier::Number(number), &context).await?;
+            let replay_traces =
+                replay_block(block.clone(), ReplayTraceConfig::default(), &context).await?;
+            traces.extend(
+                flatten_block_traces(&block, replay_traces)
+                    .into_iter()
+                    .filter(|trace| trace.involves(&self.filter)),
+            );
+        }
+
+        let traces: Vec<FlatTrace> = traces
+            .into_iter()
+            .skip(self.filter.after.unwrap_or_default())
+            .take(self.filter.count.unwrap_or(usize::MAX))
+            .collect();
+        Ok(serde_json::to_value(traces)?)
+    }
+}
+
+impl RpcHandler for CallTracesRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 2, 3)?;
+        let (trace_types, with_trace) = parse_trace_types(&params[1])?;
+        let block = match params.get(2) {
+            Some(value) => Some(BlockIdentifier::parse(value.clone(), 2)?),
+            None => None,
+        };
+        Ok(CallTracesRequest {
+            transaction: serde_json::from_value(params[0].clone())?,
+            trace_types,
+            with_trace,
+            block,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block = self.block.clone().unwrap_or_default();
+        let header = block
+            .resolve_block_header(&context.storage)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        let vm_db = StoreVmDatabase::new(context.storage.clone(), header.clone())?;
+        let mut vm = context.blockchain.new_evm(vm_db)?;
+        let replay_trace =
+            vm.trace_generic_tx_replay(&self.transaction, &header, self.trace_types)?;
+        Ok(serde_json::to_value(trace_results(
+            replay_trace,
+            self.with_trace,
+            None,
+        ))?)
+    }
+}
+
+impl RpcHandler for ReplayBlockTransactionsRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = expect_params(params, 2, 2)?;
+        let (trace_types, with_trace) = parse_trace_types(&params[1])?;
+        Ok(ReplayBlockTransactionsRequest {
+            block: BlockIdentifier::parse(params[0].clone(), 0)?,
+            trace_types,
+            with_trace,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block = resolve_block(&self.block, &context).await?;
+        let results: Vec<TraceResults> = replay_block(block, self.trace_types, &context)
+            .await?
+            .into_iter()
+            .map(|(tx_hash, replay_trace)| {
+                trace_results(replay_trace, self.with_trace, Some(tx_hash))
+            })
+            .collect();
+        Ok(serde_json::to_value(results)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(call_type: CallType, calls: Vec<CallTraceFrame>) -> CallTraceFrame {
+        CallTraceFrame {
+            call_type,
+            calls,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flattens_call_tree_depth_first() {
+        let call_trace = frame(
+            CallType::CALL,
+            vec![
+                frame(
+                    CallType::DELEGATECALL,
+                    vec![frame(CallType::SELFDESTRUCT, vec![])],
+                ),
+                CallTraceFrame {
+                    error: Some("RevertOpcode".to_string()),
+                    ..frame(CallType::CREATE, vec![])
+                },
+            ],
+        );
+
+        let traces = flatten_call_trace(call_trace, None);
+        let addresses: Vec<_> = traces.iter().map(|t| t.trace_address.clone()).collect();
+        assert_eq!(addresses, vec![vec![], vec![0], vec![0, 0], vec![1]]);
+        assert_eq!(traces[0].subtraces, 2);
+        assert_eq!(traces[2].trace_type, FlatTraceType::Suicide);
+        assert_eq!(traces[3].trace_type, FlatTraceType::Create);
+        assert_eq!(traces[3].error.as_deref(), Some("Reverted"));
+        assert!(traces[3].result.is_none());
+    }
+
+    #[test]
+    fn parses_trace_types() {
+        let (config, with_trace) =
+            parse_trace_types(&serde_json::json!(["trace", "vmTrace"])).unwrap();
+        assert!(with_trace && config.vm_trace && !config.state_diff);
+        assert!(parse_trace_types(&serde_json::json!(["foo"])).is_err());
+    }
+}
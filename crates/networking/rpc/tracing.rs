@@ -1,16 +1,22 @@
 use std::time::Duration;
 
 use ethrex_common::H256;
+use ethrex_common::types::{Block, BlockHash, GenericTransaction, StateOverride};
 use ethrex_common::{serde_utils, tracing::CallTrace, types::BlockNumber};
+use ethrex_rlp::encode::RLPEncode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::{rpc::RpcHandler, utils::RpcErr};
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    types::{block::RpcBlock, block_identifier::BlockIdentifierOrHash},
+    utils::RpcErr,
+};
 
 /// Default max amount of blocks to re-excute if it is not given
-const DEFAULT_REEXEC: u32 = 128;
+pub(crate) const DEFAULT_REEXEC: u32 = 128;
 /// Default max amount of time to spend tracing a transaction (doesn't take into account state rebuild time)
-const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct TraceTransactionRequest {
     tx_hash: H256,
@@ -22,6 +28,24 @@ pub struct TraceBlockByNumberRequest {
     trace_config: TraceConfig,
 }
 
+pub struct TraceBlockByHashRequest {
+    hash: BlockHash,
+    trace_config: TraceConfig,
+}
+
+pub struct TraceBadBlockRequest {
+    hash: BlockHash,
+    trace_config: TraceConfig,
+}
+
+pub struct TraceCallRequest {
+    transaction: GenericTransaction,
+    block: BlockIdentifierOrHash,
+    trace_config: TraceCallConfig,
+}
+
+pub struct GetBadBlocksRequest;
+
 #[derive(Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 struct TraceConfig {
@@ -36,11 +60,34 @@ struct TraceConfig {
     reexec: Option<u32>,
 }
 
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct TraceCallConfig {
+    #[serde(flatten)]
+    trace_config: TraceConfig,
+    #[serde(default)]
+    state_overrides: StateOverride,
+}
+
 #[derive(Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 enum TracerType {
     #[default]
     CallTracer,
+    /// Native tracer registered in the node's `TracerRegistry`
+    #[serde(untagged)]
+    Native(String),
+}
+
+impl TracerType {
+    fn check_registered(&self, context: &crate::rpc::RpcApiContext) -> Result<(), RpcErr> {
+        match self {
+            TracerType::Native(name) if !context.blockchain.tracers.contains(name) => {
+                Err(RpcErr::BadParams(format!("Unknown tracer: {name}")))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 #[derive(Deserialize, Default)]
@@ -96,8 +143,8 @@ impl RpcHandler for TraceTransactionRequest {
     ) -> Result<serde_json::Value, crate::utils::RpcErr> {
         let reexec = self.trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
         let timeout = self.trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
-        // This match will make more sense once we support other tracers
-        match self.trace_config.tracer {
+        self.trace_config.tracer.check_registered(&context)?;
+        match &self.trace_config.tracer {
             TracerType::CallTracer => {
                 // Parse tracer config now that we know the type
                 let config = if let Some(value) = &self.trace_config.tracer_config {
@@ -118,26 +165,96 @@ impl RpcHandler for TraceTransactionRequest {
                     .map_err(|err| RpcErr::Internal(err.to_string()))?;
                 Ok(serde_json::to_value(call_trace)?)
             }
+            TracerType::Native(name) => context
+                .blockchain
+                .trace_transaction_with(
+                    self.tx_hash,
+                    reexec,
+                    timeout,
+                    name.clone(),
+                    self.trace_config.tracer_config.clone(),
+                )
+                .await
+                .map_err(|err| RpcErr::Internal(err.to_string())),
+        }
+    }
+}
+
+/// Parses the params of the `debug_traceBlockBy*` endpoints: the block and an optional trace config
+fn parse_block_trace_params<T: serde::de::DeserializeOwned>(
+    params: &Option<Vec<Value>>,
+) -> Result<(T, TraceConfig), RpcErr> {
+    let params = params
+        .as_ref()
+        .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+    if params.len() != 1 && params.len() != 2 {
+        return Err(RpcErr::BadParams("Expected 1 or 2 params".to_owned()));
+    };
+    let trace_config = if params.len() == 2 {
+        serde_json::from_value(params[1].clone())?
+    } else {
+        TraceConfig::default()
+    };
+    Ok((serde_json::from_value(params[0].clone())?, trace_config))
+}
+
+/// Traces every transaction of the block, which doesn't need to be canonical nor valid
+async fn trace_block(
+    block: Block,
+    trace_config: &TraceConfig,
+    context: RpcApiContext,
+) -> Result<Value, RpcErr> {
+    let reexec = trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
+    let timeout = trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
+    trace_config.tracer.check_registered(&context)?;
+    match &trace_config.tracer {
+        TracerType::CallTracer => {
+            // Parse tracer config now that we know the type
+            let config = if let Some(value) = &trace_config.tracer_config {
+                serde_json::from_value(value.clone())?
+            } else {
+                CallTracerConfig::default()
+            };
+            let call_traces = context
+                .blockchain
+                .trace_block_calls(
+                    block,
+                    reexec,
+                    timeout,
+                    config.only_top_call,
+                    config.with_log,
+                )
+                .await
+                .map_err(|err| RpcErr::Internal(err.to_string()))?;
+            // We need to show transactions from newest to oldest
+            let block_trace: BlockTrace<CallTrace> =
+                call_traces.into_iter().rev().map(Into::into).collect();
+            Ok(serde_json::to_value(block_trace)?)
+        }
+        TracerType::Native(name) => {
+            let traces = context
+                .blockchain
+                .trace_block_with(
+                    block,
+                    reexec,
+                    timeout,
+                    name.clone(),
+                    trace_config.tracer_config.clone(),
+                )
+                .await
+                .map_err(|err| RpcErr::Internal(err.to_string()))?;
+            // We need to show transactions from newest to oldest
+            let block_trace: BlockTrace<Value> = traces.into_iter().rev().map(Into::into).collect();
+            Ok(serde_json::to_value(block_trace)?)
         }
     }
 }
 
 impl RpcHandler for TraceBlockByNumberRequest {
     fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
-        let params = params
-            .as_ref()
-            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
-        if params.len() != 1 && params.len() != 2 {
-            return Err(RpcErr::BadParams("Expected 1 or 2 params".to_owned()));
-        };
-        let trace_config = if params.len() == 2 {
-            serde_json::from_value(params[1].clone())?
-        } else {
-            TraceConfig::default()
-        };
-
+        let (number, trace_config) = parse_block_trace_params(params)?;
         Ok(TraceBlockByNumberRequest {
-            number: serde_json::from_value(params[0].clone())?,
+            number,
             trace_config,
         })
     }
@@ -151,21 +268,88 @@ impl RpcHandler for TraceBlockByNumberRequest {
             .get_block_by_number(self.number)
             .await?
             .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
-        let reexec = self.trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
-        let timeout = self.trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
-        // This match will make more sense once we support other tracers
-        match self.trace_config.tracer {
+        trace_block(block, &self.trace_config, context).await
+    }
+}
+
+impl RpcHandler for TraceBlockByHashRequest {
+    fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        let (hash, trace_config) = parse_block_trace_params(params)?;
+        Ok(TraceBlockByHashRequest { hash, trace_config })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block = context
+            .storage
+            .get_block_by_hash(self.hash)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        trace_block(block, &self.trace_config, context).await
+    }
+}
+
+impl RpcHandler for TraceBadBlockRequest {
+    fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        let (hash, trace_config) = parse_block_trace_params(params)?;
+        Ok(TraceBadBlockRequest { hash, trace_config })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block = context
+            .storage
+            .get_bad_block(self.hash)
+            .await?
+            .ok_or(RpcErr::Internal("Bad block not Found".to_string()))?;
+        trace_block(block, &self.trace_config, context).await
+    }
+}
+
+impl RpcHandler for TraceCallRequest {
+    fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 2 && params.len() != 3 {
+            return Err(RpcErr::BadParams("Expected 2 or 3 params".to_owned()));
+        };
+        let trace_config = match params.get(2) {
+            Some(value) => serde_json::from_value(value.clone())?,
+            None => TraceCallConfig::default(),
+        };
+        Ok(TraceCallRequest {
+            transaction: serde_json::from_value(params[0].clone())?,
+            block: BlockIdentifierOrHash::parse(params[1].clone(), 1)?,
+            trace_config,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block_hash = match &self.block {
+            BlockIdentifierOrHash::Hash(hash) => *hash,
+            BlockIdentifierOrHash::Identifier(identifier) => identifier
+                .resolve_block_header(&context.storage)
+                .await?
+                .ok_or(RpcErr::Internal("Block not Found".to_string()))?
+                .hash(),
+        };
+        let trace_config = &self.trace_config.trace_config;
+        let reexec = trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
+        let timeout = trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let state_override = self.trace_config.state_overrides.clone();
+        trace_config.tracer.check_registered(&context)?;
+        match &trace_config.tracer {
             TracerType::CallTracer => {
-                // Parse tracer config now that we know the type
-                let config = if let Some(value) = &self.trace_config.tracer_config {
+                let config = if let Some(value) = &trace_config.tracer_config {
                     serde_json::from_value(value.clone())?
                 } else {
                     CallTracerConfig::default()
                 };
-                let call_traces = context
+                let call_trace = context
                     .blockchain
-                    .trace_block_calls(
-                        block,
+                    .trace_call_calls(
+                        self.transaction.clone(),
+                        block_hash,
+                        state_override,
                         reexec,
                         timeout,
                         config.only_top_call,
@@ -173,11 +357,215 @@ impl RpcHandler for TraceBlockByNumberRequest {
                     )
                     .await
                     .map_err(|err| RpcErr::Internal(err.to_string()))?;
-                // We need to show transactions from newest to oldest
-                let block_trace: BlockTrace<CallTrace> =
-                    call_traces.into_iter().rev().map(Into::into).collect();
-                Ok(serde_json::to_value(block_trace)?)
+                Ok(serde_json::to_value(call_trace)?)
             }
+            TracerType::Native(name) => context
+                .blockchain
+                .trace_call_with(
+                    self.transaction.clone(),
+                    block_hash,
+                    state_override,
+                    reexec,
+                    timeout,
+                    name.clone(),
+                    trace_config.tracer_config.clone(),
+                )
+                .await
+                .map_err(|err| RpcErr::Internal(err.to_string())),
         }
     }
 }
+
+/// Bad block as returned by geth's `debug_getBadBlocks`
+#[derive(Serialize)]
+struct BadBlock {
+    hash: BlockHash,
+    block: RpcBlock,
+    #[serde(with = "serde_utils::bytes")]
+    rlp: bytes::Bytes,
+}
+
+impl RpcHandler for GetBadBlocksRequest {
+    fn parse(_params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        Ok(GetBadBlocksRequest)
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let bad_blocks = context
+            .storage
+            .get_bad_blocks()
+            .await?
+            .into_iter()
+            .map(|block| {
+                let hash = block.hash();
+                let rlp = block.encode_to_vec().into();
+                let block = RpcBlock::build(block.header, block.body, hash, true)?;
+                Ok(BadBlock { hash, block, rlp })
+            })
+            .collect::<Result<Vec<_>, RpcErr>>()?;
+        Ok(serde_json::to_value(bad_blocks)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::map_http_requests,
+        test_utils::{TEST_GENESIS, default_context_with_storage},
+        utils::RpcRequest,
+    };
+    use bytes::Bytes;
+    use ethrex_common::{
+        Address, U256,
+        types::{
+            BlockBody, BlockHeader, Genesis, GenesisAccount, LegacyTransaction, Transaction, TxKind,
+        },
+        utils::keccak,
+    };
+    use ethrex_rlp::structs::Encoder;
+    use ethrex_storage::{EngineType, Store};
+    use secp256k1::{Message, SECP256K1, SecretKey};
+    use serde_json::json;
+
+    fn sender_key() -> SecretKey {
+        SecretKey::from_slice(&[1; 32]).unwrap()
+    }
+
+    fn sender() -> Address {
+        Address::from_slice(
+            &keccak(&sender_key().public_key(SECP256K1).serialize_uncompressed()[1..])[12..],
+        )
+    }
+
+    fn recipient() -> Address {
+        Address::repeat_byte(0x42)
+    }
+
+    /// Store with the test genesis, funding the sender
+    async fn setup_store() -> Store {
+        let mut genesis: Genesis = serde_json::from_str(TEST_GENESIS).unwrap();
+        genesis.alloc.insert(
+            sender(),
+            GenesisAccount {
+                code: Bytes::new(),
+                storage: Default::default(),
+                balance: u64::MAX.into(),
+                nonce: 0,
+            },
+        );
+        let mut store = Store::new("", EngineType::InMemory).unwrap();
+        store.add_initial_state(genesis).await.unwrap();
+        store
+    }
+
+    /// Child of the genesis block, with a single transfer of 1 wei from the sender to the recipient
+    fn child_of_genesis(store: &Store) -> Block {
+        let parent = store.get_block_header(0).unwrap().unwrap();
+        let mut tx = LegacyTransaction {
+            gas_price: parent.base_fee_per_gas.unwrap_or_default().into(),
+            gas: 21_000,
+            to: TxKind::Call(recipient()),
+            value: U256::one(),
+            ..Default::default()
+        };
+        let mut payload = vec![];
+        Encoder::new(&mut payload)
+            .encode_field(&tx.nonce)
+            .encode_field(&tx.gas_price)
+            .encode_field(&tx.gas)
+            .encode_field(&tx.to)
+            .encode_field(&tx.value)
+            .encode_field(&tx.data)
+            .finish();
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(keccak(&payload).0), &sender_key())
+            .serialize_compact();
+        tx.r = U256::from_big_endian(&signature[..32]);
+        tx.s = U256::from_big_endian(&signature[32..]);
+        tx.v = U256::from(27 + i32::from(recovery_id) as u64);
+
+        let header = BlockHeader {
+            hash: Default::default(),
+            parent_hash: parent.hash(),
+            number: 1,
+            timestamp: parent.timestamp + 12,
+            ..parent
+        };
+        let body = BlockBody {
+            transactions: vec![Transaction::LegacyTransaction(tx)],
+            ommers: Default::default(),
+            withdrawals: Default::default(),
+        };
+        Block::new(header, body)
+    }
+
+    /// Checks the call trace of the block's only transaction, a transfer to the recipient
+    fn assert_transfer_traced(block: &Block, trace: Value) {
+        let [component] = trace.as_array().unwrap().as_slice() else {
+            panic!("expected a single transaction trace, got {trace}");
+        };
+        assert_eq!(
+            component["txHash"],
+            json!(block.body.transactions[0].hash())
+        );
+        let frame = &component["result"][0];
+        assert_eq!(frame["from"], json!(sender()));
+        assert_eq!(frame["to"], json!(recipient()));
+        assert_eq!(frame["value"], json!("0x1"));
+    }
+
+    #[tokio::test]
+    async fn trace_call_applies_state_overrides() {
+        let storage = setup_store().await;
+        let context = default_context_with_storage(storage).await;
+        let call = json!({ "from": sender(), "to": recipient() });
+        // Stores 42 in memory and returns it
+        let code = "0x602a60005260206000f3";
+
+        let request = RpcRequest::new("debug_traceCall", Some(vec![call.clone(), json!("latest")]));
+        let trace = map_http_requests(&request, context.clone()).await.unwrap();
+        assert_eq!(trace[0]["output"], json!("0x"));
+
+        let request = RpcRequest::new(
+            "debug_traceCall",
+            Some(vec![
+                call,
+                json!("latest"),
+                json!({ "stateOverrides": { format!("{:#x}", recipient()): { "code": code } } }),
+            ]),
+        );
+        let trace = map_http_requests(&request, context).await.unwrap();
+        assert_eq!(trace[0]["output"], json!(format!("0x{:064x}", 42)));
+    }
+
+    #[tokio::test]
+    async fn trace_block_by_hash_traces_side_chain_blocks() {
+        let storage = setup_store().await;
+        let block = child_of_genesis(&storage);
+        storage.add_block(block.clone()).await.unwrap();
+        assert!(storage.get_canonical_block_hash(1).await.unwrap().is_none());
+        let context = default_context_with_storage(storage).await;
+
+        let request = RpcRequest::new("debug_traceBlockByHash", Some(vec![json!(block.hash())]));
+        let trace = map_http_requests(&request, context).await.unwrap();
+        assert_transfer_traced(&block, trace);
+    }
+
+    #[tokio::test]
+    async fn trace_bad_block_traces_kept_bad_blocks() {
+        let storage = setup_store().await;
+        let block = child_of_genesis(&storage);
+        let context = default_context_with_storage(storage.clone()).await;
+        let request = RpcRequest::new("debug_traceBadBlock", Some(vec![json!(block.hash())]));
+        assert!(map_http_requests(&request, context.clone()).await.is_err());
+
+        let genesis_hash = block.header.parent_hash;
+        storage
+            .add_bad_block(block.clone(), genesis_hash)
+            .await
+            .unwrap();
+        let trace = map_http_requests(&request, context).await.unwrap();
+        assert_transfer_traced(&block, trace);
+    }
+}
@@ -24,6 +24,16 @@ pub struct StorageProof {
     pub value: U256,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccount {
+    pub balance: U256,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub nonce: u64,
+    pub code_hash: H256,
+    pub storage_root: H256,
+}
+
 pub fn serialize_proofs<S>(value: &Vec<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -1,4 +1,4 @@
-use ethrex_common::U256;
+use ethrex_common::{U256, tracing::CallTraceFrame};
 use ethrex_storage::error::StoreError;
 use ethrex_vm::EvmError;
 use serde::{Deserialize, Serialize};
@@ -39,6 +39,10 @@ pub enum RpcErr {
     InvalidPayloadAttributes(String),
     #[error("Unknown payload: {0}")]
     UnknownPayload(String),
+    #[error("Limit exceeded: {0}")]
+    LimitExceeded(String),
+    #[error("Response too large, the limit is {0} bytes")]
+    ResponseTooLarge(usize),
 }
 
 impl From<RpcErr> for RpcErrorMetadata {
@@ -124,6 +128,12 @@ impl From<RpcErr> for RpcErrorMetadata {
                     data: None,
                     message: "Auth failed: Missing authentication header".to_string(),
                 },
+                AuthenticationError::InvalidBundleSignature => RpcErrorMetadata {
+                    code: -32000,
+                    data: None,
+                    message: "Auth failed: Missing, invalid or unauthorized X-Flashbots-Signature"
+                        .to_string(),
+                },
             },
             RpcErr::InvalidForkChoiceState(data) => RpcErrorMetadata {
                 code: -38002,
@@ -140,6 +150,16 @@ impl From<RpcErr> for RpcErrorMetadata {
                 data: None,
                 message: format!("Unknown payload: {context}"),
             },
+            RpcErr::LimitExceeded(context) => RpcErrorMetadata {
+                code: -32005,
+                data: None,
+                message: format!("Limit exceeded: {context}"),
+            },
+            RpcErr::ResponseTooLarge(limit) => RpcErrorMetadata {
+                code: -32003,
+                data: None,
+                message: format!("Response too large, the limit is {limit} bytes"),
+            },
         }
     }
 }
@@ -167,6 +187,7 @@ impl From<ethrex_common::EcdsaError> for RpcErr {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RpcNamespace {
     Engine,
     Eth,
@@ -175,13 +196,17 @@ pub enum RpcNamespace {
     Web3,
     Net,
     Mempool,
+    Trace,
+    Ots,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum RpcRequestId {
     Number(u64),
     String(String),
+    /// Used to reply to requests whose id couldn't be read
+    Null,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -219,6 +244,8 @@ pub fn resolve_namespace(maybe_namespace: &str, method: String) -> Result<RpcNam
         "debug" => Ok(RpcNamespace::Debug),
         "web3" => Ok(RpcNamespace::Web3),
         "net" => Ok(RpcNamespace::Net),
+        "trace" => Ok(RpcNamespace::Trace),
+        "ots" => Ok(RpcNamespace::Ots),
         // TODO: The namespace is set to match geth's namespace for compatibility, consider changing it in the future
         "txpool" => Ok(RpcNamespace::Mempool),
         _ => Err(RpcErr::MethodNotFound(method)),
@@ -323,3 +350,53 @@ pub fn parse_json_hex(hex: &serde_json::Value) -> Result<u64, String> {
         Err(format!("Could not parse given hex {hex}"))
     }
 }
+
+/// Returns the params of a request if there are between `min` and `max` of them
+pub(crate) fn expect_params(
+    params: &Option<Vec<Value>>,
+    min: usize,
+    max: usize,
+) -> Result<&[Value], RpcErr> {
+    let params = params
+        .as_ref()
+        .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+    if params.len() < min || params.len() > max {
+        let expected = if min == max {
+            min.to_string()
+        } else {
+            format!("between {min} and {max}")
+        };
+        return Err(RpcErr::BadParams(format!(
+            "Expected {expected} params and {} were provided",
+            params.len()
+        )));
+    }
+    Ok(params)
+}
+
+/// Flattens the call trace of a transaction in depth-first order, as flat trace formats list it.
+/// Each frame is converted without its subcalls, given its address in the call tree and its amount
+/// of subcalls instead.
+pub(crate) fn flatten_call_trace<T>(
+    call_trace: CallTraceFrame,
+    mut convert: impl FnMut(CallTraceFrame, &[usize], usize) -> T,
+) -> Vec<T> {
+    let mut entries = Vec::new();
+    flatten_call_frame(call_trace, &mut Vec::new(), &mut convert, &mut entries);
+    entries
+}
+
+fn flatten_call_frame<T>(
+    mut frame: CallTraceFrame,
+    trace_address: &mut Vec<usize>,
+    convert: &mut impl FnMut(CallTraceFrame, &[usize], usize) -> T,
+    entries: &mut Vec<T>,
+) {
+    let calls = std::mem::take(&mut frame.calls);
+    entries.push(convert(frame, trace_address, calls.len()));
+    for (index, call) in calls.into_iter().enumerate() {
+        trace_address.push(index);
+        flatten_call_frame(call, trace_address, convert, entries);
+        trace_address.pop();
+    }
+}
@@ -0,0 +1,108 @@
+//! Indexes of the transactions each address took part in, as sender or as caller, callee, created
+//! contract or self destruct beneficiary of any of its call frames, and of the transactions of each
+//! sender by nonce, used by the `ots_*` endpoints. The addresses are recorded while executing the
+//! blocks, and only when indexing is enabled.
+//!
+//! Entries of every block stored are kept, canonical or not, so lookups have to check the block
+//! hash against the canonical chain.
+
+use std::ops::RangeInclusive;
+
+use ethrex_common::{
+    Address, H256,
+    types::{Block, BlockHash, BlockNumber, Index, TransactionAddresses},
+};
+use ethrex_rlp::encode::RLPEncode;
+
+use crate::error::StoreError;
+
+/// Amount of consecutive blocks sharing a key prefix, those whose numbers only differ in the two
+/// lowest bytes, a lookup takes one seek per bucket
+const BUCKET_SIZE: u64 = 1 << 16;
+/// Bytes of the block number shared by the keys of a bucket
+const BUCKET_PREFIX_LEN: usize = 6;
+
+/// Entries indexing the transactions of a block
+pub(crate) struct BlockEntries {
+    /// `address ++ block_number ++ tx_index ++ block_hash` of each address of each transaction
+    pub address_transactions: Vec<Vec<u8>>,
+    /// `sender ++ nonce ++ block_number ++ block_hash` and hash of each transaction
+    pub sender_nonces: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Entries of the transactions of a block, from the addresses recorded while executing them
+pub(crate) fn block_entries(
+    block: &Block,
+    addresses: &[TransactionAddresses],
+) -> Result<BlockEntries, StoreError> {
+    if addresses.len() != block.body.transactions.len() {
+        return Err(StoreError::Custom(format!(
+            "Addresses of {} transactions given for a block with {}",
+            addresses.len(),
+            block.body.transactions.len()
+        )));
+    }
+    let block_number = block.header.number;
+    let block_hash = block.hash();
+
+    let mut entries = BlockEntries {
+        address_transactions: Vec::new(),
+        sender_nonces: Vec::with_capacity(addresses.len()),
+    };
+    for (index, (tx, tx_addresses)) in block.body.transactions.iter().zip(addresses).enumerate() {
+        for address in &tx_addresses.addresses {
+            let mut key = address.as_bytes().to_vec();
+            key.extend_from_slice(&block_number.to_be_bytes());
+            key.extend_from_slice(&(index as Index).to_be_bytes());
+            key.extend_from_slice(block_hash.as_bytes());
+            entries.address_transactions.push(key);
+        }
+        let mut key = sender_nonce_prefix(tx_addresses.sender, tx.nonce());
+        key.extend_from_slice(&block_number.to_be_bytes());
+        key.extend_from_slice(block_hash.as_bytes());
+        entries.sender_nonces.push((key, tx.hash().encode_to_vec()));
+    }
+    Ok(entries)
+}
+
+/// Buckets spanned by a block range
+pub(crate) fn buckets(blocks: &RangeInclusive<BlockNumber>) -> RangeInclusive<u64> {
+    blocks.start() / BUCKET_SIZE..=blocks.end() / BUCKET_SIZE
+}
+
+/// Prefix of the transactions of an address in a bucket
+pub(crate) fn bucket_prefix(address: Address, bucket: u64) -> Vec<u8> {
+    let first_block = bucket.saturating_mul(BUCKET_SIZE).to_be_bytes();
+    let mut prefix = address.as_bytes().to_vec();
+    prefix.extend_from_slice(&first_block[..BUCKET_PREFIX_LEN]);
+    prefix
+}
+
+/// Block number, transaction index and block hash of an address transactions entry
+pub(crate) fn decode_address_transaction(key: &[u8]) -> Option<(BlockNumber, Index, BlockHash)> {
+    let block_number = key.get(20..28)?.try_into().ok()?;
+    let index = key.get(28..36)?.try_into().ok()?;
+    let block_hash = key.get(36..68)?;
+    Some((
+        BlockNumber::from_be_bytes(block_number),
+        Index::from_be_bytes(index),
+        H256::from_slice(block_hash),
+    ))
+}
+
+/// Prefix of the transactions of a sender with a nonce, one per block including one
+pub(crate) fn sender_nonce_prefix(sender: Address, nonce: u64) -> Vec<u8> {
+    let mut prefix = sender.as_bytes().to_vec();
+    prefix.extend_from_slice(&nonce.to_be_bytes());
+    prefix
+}
+
+/// Block number and hash of a sender nonces entry
+pub(crate) fn decode_sender_nonce(key: &[u8]) -> Option<(BlockNumber, BlockHash)> {
+    let block_number = key.get(28..36)?.try_into().ok()?;
+    let block_hash = key.get(36..68)?;
+    Some((
+        BlockNumber::from_be_bytes(block_number),
+        H256::from_slice(block_hash),
+    ))
+}
@@ -70,6 +70,12 @@ pub const PENDING_BLOCKS: &str = "pending_blocks";
 /// - [`Vec<u8>`] = `BlockHashRLP::from(latest_valid).bytes().clone()`
 pub const INVALID_CHAINS: &str = "invalid_ancestors";
 
+/// Bad blocks column family: [`Vec<u8>`] => [`Vec<u8>`]
+/// Bodies of the blocks in [`INVALID_CHAINS`] that failed execution, kept to debug them.
+/// - [`Vec<u8>`] = `BlockHashRLP::from(bad_block).bytes().clone()`
+/// - [`Vec<u8>`] = `BlockRLP::from(bad_block).bytes().clone()`
+pub const BAD_BLOCKS: &str = "bad_blocks";
+
 /// Block headers downloaded during fullsync column family: [`u8;_`] => [`Vec<u8>`]
 /// - [`u8;_`] = `block_number.to_le_bytes()`
 /// - [`Vec<u8>`] = `BlockHeaderRLP::from(block.header.clone()).bytes().clone()`
@@ -87,7 +93,22 @@ pub const STORAGE_FLATKEYVALUE: &str = "storage_flatkeyvalue";
 
 pub const MISC_VALUES: &str = "misc_values";
 
-pub const TABLES: [&str; 17] = [
+/// Log index column family: [`Vec<u8>`] => empty
+/// One entry per block and distinct address or (position, topic) of its logs, see [`crate::log_index`].
+/// - [`Vec<u8>`] = `tag ++ item ++ (block_number / 4096).to_be_bytes() ++ block_number.to_be_bytes() ++ block_hash`
+pub const LOG_INDEX: &str = "log_index";
+
+/// Address transactions column family: [`Vec<u8>`] => empty
+/// One entry per transaction and address sending it, receiving it or created by it.
+/// - [`Vec<u8>`] = `address ++ block_number.to_be_bytes() ++ tx_index.to_be_bytes() ++ block_hash`
+pub const ADDRESS_TRANSACTIONS: &str = "address_transactions";
+
+/// Sender nonces column family: [`Vec<u8>`] => [`Vec<u8>`]
+/// - [`Vec<u8>`] = `sender ++ nonce.to_be_bytes() ++ block_number.to_be_bytes() ++ block_hash`
+/// - [`Vec<u8>`] = `tx_hash.encode_to_vec()`
+pub const SENDER_NONCES: &str = "sender_nonces";
+
+pub const TABLES: [&str; 21] = [
     CHAIN_DATA,
     ACCOUNT_CODES,
     BODIES,
@@ -99,10 +120,14 @@ pub const TABLES: [&str; 17] = [
     RECEIPTS,
     SNAP_STATE,
     INVALID_CHAINS,
+    BAD_BLOCKS,
     ACCOUNT_TRIE_NODES,
     STORAGE_TRIE_NODES,
     FULLSYNC_HEADERS,
     ACCOUNT_FLATKEYVALUE,
     STORAGE_FLATKEYVALUE,
     MISC_VALUES,
+    LOG_INDEX,
+    ADDRESS_TRANSACTIONS,
+    SENDER_NONCES,
 ];
@@ -0,0 +1,106 @@
+//! Index of the blocks emitting logs from each address and with each topic, so log queries only
+//! read the receipts of the blocks that may have matching logs.
+//!
+//! Every block adds an entry per distinct address and (position, topic) of its logs, keyed
+//! `tag ++ item ++ section ++ block_number ++ block_hash`. Entries of every block stored are kept,
+//! canonical or not, so lookups have to check the block hash against the canonical chain.
+
+use std::{collections::BTreeSet, ops::RangeInclusive};
+
+use ethrex_common::{
+    Address, H256,
+    types::{BlockHash, BlockNumber, Receipt},
+};
+
+/// Amount of consecutive blocks sharing a key prefix, a lookup takes one seek per section
+const SECTION_SIZE: u64 = 4096;
+const ADDRESS_TAG: u8 = 0;
+/// Tag of the first topic of a log, the following positions use the next tags
+const FIRST_TOPIC_TAG: u8 = 1;
+
+/// Logs to look up in the index: those emitted by any of `addresses` and whose topic at each
+/// position is any of the ones at that position of `topics`. An empty list matches anything.
+#[derive(Debug, Clone, Default)]
+pub struct LogIndexQuery {
+    pub addresses: Vec<Address>,
+    pub topics: Vec<Vec<H256>>,
+}
+
+impl LogIndexQuery {
+    /// Items of each criterion of the query, a block is a candidate if it has an entry for any
+    /// item of every criterion
+    pub(crate) fn criteria(&self) -> Vec<Vec<Vec<u8>>> {
+        let addresses = (!self.addresses.is_empty()).then(|| {
+            self.addresses
+                .iter()
+                .map(|address| item_key(ADDRESS_TAG, address.as_bytes()))
+                .collect()
+        });
+        let topics = self
+            .topics
+            .iter()
+            .zip(FIRST_TOPIC_TAG..=u8::MAX)
+            .filter(|(alternatives, _)| !alternatives.is_empty())
+            .map(|(alternatives, tag)| {
+                alternatives
+                    .iter()
+                    .map(|topic| item_key(tag, topic.as_bytes()))
+                    .collect()
+            });
+        addresses.into_iter().chain(topics).collect()
+    }
+}
+
+/// Keys indexing the logs of a block
+pub(crate) fn block_entries(
+    block_number: BlockNumber,
+    block_hash: BlockHash,
+    receipts: &[Receipt],
+) -> Vec<Vec<u8>> {
+    let mut items = BTreeSet::new();
+    for log in receipts.iter().flat_map(|receipt| &receipt.logs) {
+        items.insert(item_key(ADDRESS_TAG, log.address.as_bytes()));
+        for (topic, tag) in log.topics.iter().zip(FIRST_TOPIC_TAG..=u8::MAX) {
+            items.insert(item_key(tag, topic.as_bytes()));
+        }
+    }
+    items
+        .into_iter()
+        .map(|item| {
+            let mut key = section_prefix(&item, block_number / SECTION_SIZE);
+            key.extend_from_slice(&block_number.to_be_bytes());
+            key.extend_from_slice(block_hash.as_bytes());
+            key
+        })
+        .collect()
+}
+
+/// Sections spanned by a block range
+pub(crate) fn sections(blocks: &RangeInclusive<BlockNumber>) -> RangeInclusive<u64> {
+    blocks.start() / SECTION_SIZE..=blocks.end() / SECTION_SIZE
+}
+
+/// Prefix of the entries of an item in a section
+pub(crate) fn section_prefix(item: &[u8], section: u64) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(item.len() + 48);
+    prefix.extend_from_slice(item);
+    prefix.extend_from_slice(&section.to_be_bytes());
+    prefix
+}
+
+/// Block number and hash of an entry
+pub(crate) fn decode_entry(key: &[u8]) -> Option<(BlockNumber, BlockHash)> {
+    let (rest, hash) = key.split_at_checked(key.len().checked_sub(32)?)?;
+    let number = rest.get(rest.len().checked_sub(8)?..)?;
+    Some((
+        BlockNumber::from_be_bytes(number.try_into().ok()?),
+        H256::from_slice(hash),
+    ))
+}
+
+fn item_key(tag: u8, item: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(item.len() + 1);
+    key.push(tag);
+    key.extend_from_slice(item);
+    key
+}
@@ -1,20 +1,22 @@
 #[cfg(feature = "rocksdb")]
 use crate::backend::rocksdb::RocksDBBackend;
 use crate::{
-    STORE_METADATA_FILENAME, STORE_SCHEMA_VERSION,
+    STORE_METADATA_FILENAME, STORE_SCHEMA_VERSION, address_index,
     api::{
         StorageBackend,
         tables::{
-            ACCOUNT_CODES, ACCOUNT_FLATKEYVALUE, ACCOUNT_TRIE_NODES, BLOCK_NUMBERS, BODIES,
-            CANONICAL_BLOCK_HASHES, CHAIN_DATA, FULLSYNC_HEADERS, HEADERS, INVALID_CHAINS,
-            MISC_VALUES, PENDING_BLOCKS, RECEIPTS, SNAP_STATE, STORAGE_FLATKEYVALUE,
-            STORAGE_TRIE_NODES, TRANSACTION_LOCATIONS,
+            ACCOUNT_CODES, ACCOUNT_FLATKEYVALUE, ACCOUNT_TRIE_NODES, ADDRESS_TRANSACTIONS,
+            BAD_BLOCKS, BLOCK_NUMBERS, BODIES, CANONICAL_BLOCK_HASHES, CHAIN_DATA,
+            FULLSYNC_HEADERS, HEADERS, INVALID_CHAINS, LOG_INDEX, MISC_VALUES, PENDING_BLOCKS,
+            RECEIPTS, SENDER_NONCES, SNAP_STATE, STORAGE_FLATKEYVALUE, STORAGE_TRIE_NODES,
+            TRANSACTION_LOCATIONS,
         },
     },
     apply_prefix,
     backend::in_memory::InMemoryBackend,
     error::StoreError,
     layering::{TrieLayerCache, TrieWrapper},
+    log_index::{self, LogIndexQuery},
     rlp::{BlockBodyRLP, BlockHeaderRLP, BlockRLP},
     trie::{BackendTrieDB, BackendTrieDBLocked},
     utils::{ChainDataIndex, SnapStateIndex},
@@ -25,8 +27,8 @@ use ethrex_common::{
     Address, H256, U256,
     types::{
         AccountInfo, AccountState, AccountUpdate, Block, BlockBody, BlockHash, BlockHeader,
-        BlockNumber, ChainConfig, Code, ForkId, Genesis, GenesisAccount, Index, Receipt,
-        Transaction,
+        BlockNumber, ChainConfig, Code, CodeAnalysisCache, ForkId, Genesis, GenesisAccount, Index,
+        Receipt, Transaction, TransactionAddresses,
     },
     utils::keccak,
 };
@@ -41,9 +43,10 @@ use lru::LruCache;
 use rustc_hash::FxBuildHasher;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap, hash_map::Entry},
+    collections::{BTreeMap, BTreeSet, HashMap, hash_map::Entry},
     fmt::Debug,
     io::Write,
+    ops::RangeInclusive,
     path::{Path, PathBuf},
     sync::{
         Arc, Mutex,
@@ -72,6 +75,9 @@ enum FKVGeneratorControlMessage {
     Continue,
 }
 
+/// Maximum amount of bad blocks whose bodies are kept for debugging, the oldest ones are dropped first
+const MAX_BAD_BLOCKS: usize = 10;
+
 // 64mb
 const CODE_CACHE_MAX_SIZE: u64 = 64 * 1024 * 1024;
 
@@ -145,6 +151,14 @@ pub struct Store {
     /// those changes already affect the code hash stored in the account, and only
     /// may result in this cache having useless data.
     account_code_cache: Arc<CodeCache>,
+
+    /// Cache for bytecode analyses, keyed by the bytecode hash. Unlike `account_code_cache`,
+    /// it's shared with the VM so it also covers codes deployed during execution.
+    code_analysis_cache: Arc<CodeAnalysisCache>,
+
+    /// Serializes [`Store::add_bad_block`] calls, since the kept bodies are read to pick the ones
+    /// to evict before writing the new one.
+    bad_blocks_lock: Arc<Mutex<()>>,
 }
 
 pub type StorageTrieNodes = Vec<(H256, Vec<(Nibbles, Vec<u8>)>)>;
@@ -165,6 +179,8 @@ pub struct UpdateBatch {
     pub blocks: Vec<Block>,
     /// Receipts added per block
     pub receipts: Vec<(H256, Vec<Receipt>)>,
+    /// Addresses each transaction took part in per block, only given for indexed blocks
+    pub transaction_addresses: Vec<(H256, Vec<TransactionAddresses>)>,
     /// Code updates
     pub code_updates: Vec<(H256, Code)>,
 }
@@ -594,6 +610,177 @@ impl Store {
             .map_err(StoreError::from)
     }
 
+    /// Canonical blocks in range that may have logs matching the query, in ascending order.
+    /// Blocks stored before the log index was introduced are always included.
+    pub async fn get_log_candidate_blocks(
+        &self,
+        blocks: RangeInclusive<BlockNumber>,
+        query: LogIndexQuery,
+    ) -> Result<Vec<BlockNumber>, StoreError> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || store.log_candidate_blocks(blocks, &query))
+            .await
+            .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
+    fn log_candidate_blocks(
+        &self,
+        blocks: RangeInclusive<BlockNumber>,
+        query: &LogIndexQuery,
+    ) -> Result<Vec<BlockNumber>, StoreError> {
+        let criteria = query.criteria();
+        let txn = self.backend.begin_read()?;
+        let index_start = txn
+            .get(CHAIN_DATA, &chain_data_key(ChainDataIndex::LogIndexStart))?
+            .map(|bytes| -> Result<BlockNumber, StoreError> {
+                let array: [u8; 8] = bytes
+                    .try_into()
+                    .map_err(|_| StoreError::Custom("Invalid BlockNumber bytes".to_string()))?;
+                Ok(BlockNumber::from_le_bytes(array))
+            })
+            .transpose()?;
+        let Some(index_start) = index_start.filter(|_| !criteria.is_empty()) else {
+            return Ok(blocks.collect());
+        };
+
+        let indexed = index_start.max(*blocks.start())..=*blocks.end();
+        let mut candidates: Vec<BlockNumber> = (*blocks.start()..*indexed.start()).collect();
+        if indexed.is_empty() {
+            return Ok(candidates);
+        }
+        // Blocks with an entry for any item of every criterion
+        let mut matches: Option<BTreeSet<(BlockNumber, BlockHash)>> = None;
+        for items in criteria {
+            let mut entries = BTreeSet::new();
+            for section in log_index::sections(&indexed) {
+                for item in &items {
+                    let prefix = log_index::section_prefix(item, section);
+                    for entry in txn.prefix_iterator(LOG_INDEX, &prefix)? {
+                        let (key, _) = entry?;
+                        if !key.starts_with(&prefix) {
+                            break;
+                        }
+                        let (number, hash) = log_index::decode_entry(&key).ok_or_else(|| {
+                            StoreError::Custom("Invalid log index entry".to_string())
+                        })?;
+                        if indexed.contains(&number) {
+                            entries.insert((number, hash));
+                        }
+                    }
+                }
+            }
+            matches = Some(match matches {
+                Some(matches) => matches.intersection(&entries).copied().collect(),
+                None => entries,
+            });
+            if matches.as_ref().is_some_and(BTreeSet::is_empty) {
+                break;
+            }
+        }
+        // Entries of reorged out blocks are kept, so only those of canonical blocks are taken
+        for (number, hash) in matches.unwrap_or_default() {
+            if self.get_canonical_block_hash_sync(number)? == Some(hash) {
+                candidates.push(number);
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Canonical transactions the address sent, received or was created by in the block range, as
+    /// their block number, block hash and index, in ascending or descending order.
+    /// Whole blocks are taken until at least `min_transactions` are found, returning whether the
+    /// range has more transactions of the address.
+    pub async fn get_address_transactions(
+        &self,
+        address: Address,
+        blocks: RangeInclusive<BlockNumber>,
+        descending: bool,
+        min_transactions: usize,
+    ) -> Result<(Vec<(BlockNumber, BlockHash, Index)>, bool), StoreError> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            store.address_transactions(address, blocks, descending, min_transactions)
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
+    fn address_transactions(
+        &self,
+        address: Address,
+        blocks: RangeInclusive<BlockNumber>,
+        descending: bool,
+        min_transactions: usize,
+    ) -> Result<(Vec<(BlockNumber, BlockHash, Index)>, bool), StoreError> {
+        let txn = self.backend.begin_read()?;
+        let buckets = address_index::buckets(&blocks);
+        let buckets: Box<dyn Iterator<Item = u64>> = if descending {
+            Box::new(buckets.rev())
+        } else {
+            Box::new(buckets)
+        };
+        let mut found: Vec<(BlockNumber, BlockHash, Index)> = Vec::new();
+        for bucket in buckets {
+            let prefix = address_index::bucket_prefix(address, bucket);
+            let mut entries = Vec::new();
+            for entry in txn.prefix_iterator(ADDRESS_TRANSACTIONS, &prefix)? {
+                let (key, _) = entry?;
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+                let (number, index, hash) = address_index::decode_address_transaction(&key)
+                    .ok_or_else(|| {
+                        StoreError::Custom("Invalid address transactions entry".to_string())
+                    })?;
+                if blocks.contains(&number) {
+                    entries.push((number, hash, index));
+                }
+            }
+            if descending {
+                entries.reverse();
+            }
+            for (number, hash, index) in entries {
+                if self.get_canonical_block_hash_sync(number)? != Some(hash) {
+                    continue;
+                }
+                if found.len() >= min_transactions
+                    && found.last().is_some_and(|(last, ..)| *last != number)
+                {
+                    return Ok((found, true));
+                }
+                found.push((number, hash, index));
+            }
+        }
+        Ok((found, false))
+    }
+
+    /// Hash of the canonical transaction sent by `sender` with the given nonce
+    pub async fn get_transaction_by_sender_and_nonce(
+        &self,
+        sender: Address,
+        nonce: u64,
+    ) -> Result<Option<H256>, StoreError> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let txn = store.backend.begin_read()?;
+            let prefix = address_index::sender_nonce_prefix(sender, nonce);
+            for entry in txn.prefix_iterator(SENDER_NONCES, &prefix)? {
+                let (key, tx_hash) = entry?;
+                if !key.starts_with(&prefix) {
+                    break;
+                }
+                let (number, hash) = address_index::decode_sender_nonce(&key)
+                    .ok_or_else(|| StoreError::Custom("Invalid sender nonces entry".to_string()))?;
+                if store.get_canonical_block_hash_sync(number)? == Some(hash) {
+                    return Ok(Some(H256::decode(&tx_hash)?));
+                }
+            }
+            Ok(None)
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
     /// Get account code by its hash.
     ///
     /// Check if the code exists in the cache (attribute `account_code_cache`), if not,
@@ -612,14 +799,12 @@ impl Store {
             return Ok(None);
         };
         let bytes = Bytes::from_owner(bytes);
-        let (bytecode_slice, targets) = decode_bytes(&bytes)?;
+        // The stored jump targets are kept for compatibility, the analysis also needs push data
+        // so it's taken from the analysis cache instead.
+        let (bytecode_slice, _targets) = decode_bytes(&bytes)?;
         let bytecode = bytes.slice_ref(bytecode_slice);
 
-        let code = Code {
-            hash: code_hash,
-            bytecode,
-            jump_targets: <Vec<_>>::decode(targets)?,
-        };
+        let code = Code::from_bytecode_cached(bytecode, code_hash, &self.code_analysis_cache);
 
         // insert into cache and evict if needed
         self.account_code_cache.insert(&code)?;
@@ -627,6 +812,11 @@ impl Store {
         Ok(Some(code))
     }
 
+    /// Returns the bytecode analysis cache, shared by every clone of the store.
+    pub fn code_analysis_cache(&self) -> Arc<CodeAnalysisCache> {
+        self.code_analysis_cache.clone()
+    }
+
     /// Add account code
     pub async fn add_account_code(&self, code: Code) -> Result<(), StoreError> {
         let hash_key = code.hash.0.to_vec();
@@ -931,6 +1121,80 @@ impl Store {
             .map_err(StoreError::from)
     }
 
+    /// Marks a block that failed execution as invalid, like [`Self::set_latest_valid_ancestor`], also keeping
+    /// its body so it can be debugged later.
+    ///
+    /// Only the last [`MAX_BAD_BLOCKS`] bodies are kept. Bodies of blocks that are no longer marked
+    /// as invalid are evicted first.
+    pub async fn add_bad_block(
+        &self,
+        bad_block: Block,
+        latest_valid: BlockHash,
+    ) -> Result<(), StoreError> {
+        let backend = self.backend.clone();
+        let bad_blocks_lock = self.bad_blocks_lock.clone();
+        tokio::task::spawn_blocking(move || {
+            let _guard = bad_blocks_lock.lock().map_err(|_| StoreError::LockError)?;
+            let bad_block_hash = bad_block.hash();
+
+            // (still marked as invalid, block number, key) of every kept body but the new one
+            let mut kept = Vec::new();
+            {
+                let read_txn = backend.begin_read()?;
+                for entry in read_txn.prefix_iterator(BAD_BLOCKS, &[])? {
+                    let (key, bytes) = entry?;
+                    if *key == *bad_block_hash.as_bytes() {
+                        continue;
+                    }
+                    let is_invalid = read_txn.get(INVALID_CHAINS, &key)?.is_some();
+                    let block: Block = BlockRLP::from_bytes(bytes.into_vec()).to()?;
+                    kept.push((is_invalid, block.header.number, key));
+                }
+            }
+
+            let mut txn = backend.begin_write()?;
+            txn.put(
+                INVALID_CHAINS,
+                bad_block_hash.as_bytes(),
+                &latest_valid.encode_to_vec(),
+            )?;
+            kept.sort();
+            let evicted = (kept.len() + 1).saturating_sub(MAX_BAD_BLOCKS);
+            for (_, _, key) in kept.iter().take(evicted) {
+                txn.delete(BAD_BLOCKS, key)?;
+            }
+            txn.put(
+                BAD_BLOCKS,
+                bad_block_hash.as_bytes(),
+                &BlockRLP::from(bad_block).into_vec(),
+            )?;
+            txn.commit()
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
+    /// Returns the bad blocks whose bodies are kept, from newest to oldest.
+    pub async fn get_bad_blocks(&self) -> Result<Vec<Block>, StoreError> {
+        let backend = self.backend.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut bad_blocks = read_bad_blocks(backend.as_ref())?;
+            bad_blocks.sort_by_key(|block| std::cmp::Reverse(block.header.number));
+            Ok(bad_blocks)
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
+    /// Returns the bad block with the given hash, if its body is still kept.
+    pub async fn get_bad_block(&self, block_hash: BlockHash) -> Result<Option<Block>, StoreError> {
+        self.read_async(BAD_BLOCKS, block_hash.as_bytes().to_vec())
+            .await?
+            .map(|bytes| BlockRLP::from_bytes(bytes).to())
+            .transpose()
+            .map_err(StoreError::from)
+    }
+
     /// Obtain block number for a given hash
     pub fn get_block_number_sync(
         &self,
@@ -1181,12 +1445,18 @@ impl Store {
         trie_upd_worker_tx.send(trie_update).map_err(|e| {
             StoreError::Custom(format!("failed to read new trie layer notification: {e}"))
         })?;
+        let log_index_start_key = chain_data_key(ChainDataIndex::LogIndexStart);
+        let log_index_started = db.begin_read()?.get(CHAIN_DATA, &log_index_start_key)?;
         let mut tx = db.begin_write()?;
+        let mut block_numbers = HashMap::with_capacity(update_batch.blocks.len());
+        let mut transaction_addresses: HashMap<_, _> =
+            update_batch.transaction_addresses.into_iter().collect();
 
         for block in update_batch.blocks {
             let block_number = block.header.number;
             let block_hash = block.hash();
             let hash_key = block_hash.encode_to_vec();
+            block_numbers.insert(block_hash, block_number);
 
             let header_value_rlp = BlockHeaderRLP::from(block.header.clone());
             tx.put(HEADERS, &hash_key, header_value_rlp.bytes())?;
@@ -1205,9 +1475,27 @@ impl Store {
                 let location_value = (block_number, block_hash, index as u64).encode_to_vec();
                 tx.put(TRANSACTION_LOCATIONS, &composite_key, &location_value)?;
             }
+
+            if let Some(addresses) = transaction_addresses.remove(&block_hash) {
+                let address_entries = address_index::block_entries(&block, &addresses)?;
+                for key in address_entries.address_transactions {
+                    tx.put(ADDRESS_TRANSACTIONS, &key, &[])?;
+                }
+                for (key, tx_hash) in address_entries.sender_nonces {
+                    tx.put(SENDER_NONCES, &key, &tx_hash)?;
+                }
+            }
         }
 
+        let mut log_index_start: Option<BlockNumber> = None;
         for (block_hash, receipts) in update_batch.receipts {
+            if let Some(&block_number) = block_numbers.get(&block_hash) {
+                for key in log_index::block_entries(block_number, block_hash, &receipts) {
+                    tx.put(LOG_INDEX, &key, &[])?;
+                }
+                log_index_start =
+                    Some(log_index_start.map_or(block_number, |start| start.min(block_number)));
+            }
             for (index, receipt) in receipts.into_iter().enumerate() {
                 let key = (block_hash, index as u64).encode_to_vec();
                 let value = receipt.encode_to_vec();
@@ -1215,6 +1503,11 @@ impl Store {
             }
         }
 
+        // Blocks below the first one indexed are searched without the index
+        if let (None, Some(start)) = (log_index_started, log_index_start) {
+            tx.put(CHAIN_DATA, &log_index_start_key, &start.to_le_bytes())?;
+        }
+
         for (code_hash, code) in update_batch.code_updates {
             let buf = encode_code(&code);
             tx.put(ACCOUNT_CODES, code_hash.as_ref(), &buf)?;
@@ -1283,6 +1576,8 @@ impl Store {
             trie_update_worker_tx: trie_upd_tx,
             last_computed_flatkeyvalue: Arc::new(Mutex::new(last_written)),
             account_code_cache: Arc::new(CodeCache::default()),
+            code_analysis_cache: Arc::new(CodeAnalysisCache::default()),
+            bad_blocks_lock: Arc::new(Mutex::new(())),
         };
         let backend_clone = store.backend.clone();
         let last_computed_fkv = store.last_computed_flatkeyvalue.clone();
@@ -1922,11 +2217,10 @@ impl Store {
         address: Address,
         storage_keys: &[H256],
     ) -> Result<Option<AccountProof>, StoreError> {
-        // TODO: check state root
-        // let Some(state_trie) = self.open_state_trie(state_trie)? else {
-        //     return Ok(None);
-        // };
         let state_trie = self.open_state_trie(state_root)?;
+        if !trie_has_root(&state_trie, state_root)? {
+            return Ok(None);
+        }
         let hashed_address = hash_address_fixed(&address);
         let address_path = hashed_address.0.to_vec();
         let proof = state_trie.get_proof(&address_path)?;
@@ -1973,6 +2267,55 @@ impl Store {
         Ok(Some(account_proof))
     }
 
+    /// Reads the state of each of the given accounts and the values of the given slots of its
+    /// storage against a given state, all from the same snapshot of the database.
+    /// Slots are read from the flat key-value tables once they are computed for the account.
+    ///
+    /// Returns `None` if the state trie is missing, otherwise the account, or `None` if it
+    /// doesn't exist, and the slot values in the order they were requested.
+    pub fn get_accounts_storage_at_root(
+        &self,
+        state_root: H256,
+        accounts: &[(Address, Vec<H256>)],
+    ) -> Result<Option<Vec<(Option<AccountState>, Vec<U256>)>>, StoreError> {
+        let state_trie = self.open_locked_state_trie(state_root)?;
+        if !trie_has_root(&state_trie, state_root)? {
+            return Ok(None);
+        }
+        let mut results = Vec::with_capacity(accounts.len());
+        for (address, storage_keys) in accounts {
+            let account = self.get_account_state_from_trie(&state_trie, *address)?;
+            let storage_root = match &account {
+                Some(account) if !storage_keys.is_empty() => account.storage_root,
+                _ => {
+                    results.push((account, vec![U256::zero(); storage_keys.len()]));
+                    continue;
+                }
+            };
+            let account_hash = hash_address_fixed(address);
+            let storage_root = if self.flatkeyvalue_computed(account_hash)? {
+                // We will use FKVs, we don't need the root
+                *EMPTY_TRIE_HASH
+            } else {
+                storage_root
+            };
+            let storage_trie =
+                self.open_locked_storage_trie(account_hash, state_root, storage_root)?;
+            let values = storage_keys
+                .iter()
+                .map(|key| {
+                    storage_trie
+                        .get(&hash_key(key))?
+                        .map(|rlp| U256::decode(&rlp).map_err(StoreError::RLPDecode))
+                        .transpose()
+                        .map(Option::unwrap_or_default)
+                })
+                .collect::<Result<_, StoreError>>()?;
+            results.push((account, values));
+        }
+        Ok(Some(results))
+    }
+
     // Returns an iterator across all accounts in the state trie given by the state_root
     // Does not check that the state_root is valid
     pub fn iter_accounts_from(
@@ -2238,17 +2581,8 @@ impl Store {
     }
 
     pub fn has_state_root(&self, state_root: H256) -> Result<bool, StoreError> {
-        // Empty state trie is always available
-        if state_root == *EMPTY_TRIE_HASH {
-            return Ok(true);
-        }
         let trie = self.open_state_trie(state_root)?;
-        // NOTE: here we hash the root because the trie doesn't check the state root is correct
-        let Some(root) = trie.db().get(Nibbles::default())? else {
-            return Ok(false);
-        };
-        let root_hash = ethrex_trie::Node::decode(&root)?.compute_hash().finalize();
-        Ok(state_root == root_hash)
+        trie_has_root(&trie, state_root)
     }
 
     /// Takes a block hash and returns an iterator to its ancestors. Block headers are returned
@@ -2626,6 +2960,21 @@ fn fkv_check_for_stop_msg(
     Ok(())
 }
 
+/// Checks the root node of a state trie opened at `state_root` is the one of that state, that is,
+/// that the state is still retained
+fn trie_has_root(trie: &Trie, state_root: H256) -> Result<bool, StoreError> {
+    // Empty state trie is always available
+    if state_root == *EMPTY_TRIE_HASH {
+        return Ok(true);
+    }
+    // NOTE: here we hash the root because the trie doesn't check the state root is correct
+    let Some(root) = trie.db().get(Nibbles::default())? else {
+        return Ok(false);
+    };
+    let root_hash = ethrex_trie::Node::decode(&root)?.compute_hash().finalize();
+    Ok(state_root == root_hash)
+}
+
 fn state_trie_locked_backend(
     backend: &dyn StorageBackend,
     last_written: Vec<u8>,
@@ -2700,11 +3049,12 @@ fn snap_state_key(index: SnapStateIndex) -> Vec<u8> {
 }
 
 fn encode_code(code: &Code) -> Vec<u8> {
+    let jump_targets = code.analysis.jump_targets();
     let mut buf = Vec::with_capacity(
-        6 + code.bytecode.len() + std::mem::size_of_val(code.jump_targets.as_slice()),
+        6 + code.bytecode.len() + std::mem::size_of_val(jump_targets.as_slice()),
     );
     code.bytecode.encode(&mut buf);
-    code.jump_targets.encode(&mut buf);
+    jump_targets.encode(&mut buf);
     buf
 }
 
@@ -2783,6 +3133,20 @@ fn dir_is_empty(path: &Path) -> Result<bool, StoreError> {
     Ok(is_empty)
 }
 
+/// Reads the kept bodies of the blocks marked as invalid.
+fn read_bad_blocks(backend: &dyn StorageBackend) -> Result<Vec<Block>, StoreError> {
+    let txn = backend.begin_read()?;
+    let mut bad_blocks = Vec::new();
+    for entry in txn.prefix_iterator(BAD_BLOCKS, &[])? {
+        let (block_hash, bytes) = entry?;
+        // Only blocks still marked as invalid are bad
+        if txn.get(INVALID_CHAINS, &block_hash)?.is_some() {
+            bad_blocks.push(BlockRLP::from_bytes(bytes.into_vec()).to()?);
+        }
+    }
+    Ok(bad_blocks)
+}
+
 #[cfg(test)]
 mod tests {
     use bytes::Bytes;
@@ -2790,7 +3154,7 @@ mod tests {
     use ethrex_common::{
         Bloom, H160,
         constants::EMPTY_KECCACK_HASH,
-        types::{Transaction, TxType},
+        types::{Log, Transaction, TxKind, TxType},
         utils::keccak,
     };
     use ethrex_rlp::decode::RLPDecode;
@@ -2841,6 +3205,230 @@ mod tests {
         run_test(test_genesis_block, engine_type).await;
         run_test(test_iter_accounts, engine_type).await;
         run_test(test_iter_storage, engine_type).await;
+        run_test(test_accounts_storage_at_root, engine_type).await;
+        run_test(test_bad_blocks, engine_type).await;
+        run_test(test_cleared_bad_blocks_are_evicted_first, engine_type).await;
+        run_test(test_log_index, engine_type).await;
+        run_test(test_address_index, engine_type).await;
+    }
+
+    async fn test_address_index(store: Store) {
+        let (mut header, body) = create_block_for_testing();
+        header.number = 1;
+        let block = Block::new(header, body);
+        let block_hash = block.hash();
+        let transactions = block.body.transactions.clone();
+        // The second transaction calls into a contract other than its recipient
+        let inner_callee = H160::from_low_u64_be(0xca11);
+        let transaction_addresses = transactions
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                let sender = tx.sender().unwrap();
+                let TxKind::Call(to) = tx.to() else {
+                    panic!("Expected a call");
+                };
+                let mut addresses = BTreeSet::from([sender, to]);
+                if index == 1 {
+                    addresses.insert(inner_callee);
+                }
+                TransactionAddresses { sender, addresses }
+            })
+            .collect();
+        store
+            .store_block_updates(UpdateBatch {
+                account_updates: vec![],
+                storage_updates: vec![],
+                blocks: vec![block],
+                receipts: vec![],
+                transaction_addresses: vec![(block_hash, transaction_addresses)],
+                code_updates: vec![],
+            })
+            .unwrap();
+        store
+            .forkchoice_update(vec![(1, block_hash)], 1, block_hash, None, None)
+            .await
+            .unwrap();
+
+        // Both transactions are sent to the same address, and the whole block is taken
+        let TxKind::Call(recipient) = transactions[0].to() else {
+            panic!("Expected a call");
+        };
+        let (found, more) = store
+            .get_address_transactions(recipient, 0..=1, true, 1)
+            .await
+            .unwrap();
+        assert_eq!(found, vec![(1, block_hash, 1), (1, block_hash, 0)]);
+        assert!(!more);
+        let (found, _) = store
+            .get_address_transactions(inner_callee, 0..=1, false, 1)
+            .await
+            .unwrap();
+        assert_eq!(found, vec![(1, block_hash, 1)]);
+
+        let sender = transactions[0].sender().unwrap();
+        let (found, _) = store
+            .get_address_transactions(sender, 2..=10, false, 1)
+            .await
+            .unwrap();
+        assert!(found.is_empty());
+        assert_eq!(
+            store
+                .get_transaction_by_sender_and_nonce(sender, transactions[0].nonce())
+                .await
+                .unwrap(),
+            Some(transactions[0].hash())
+        );
+        assert_eq!(
+            store
+                .get_transaction_by_sender_and_nonce(sender, transactions[0].nonce() + 1)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    async fn test_log_index(store: Store) {
+        let (address_a, address_b) = (H160::from_low_u64_be(1), H160::from_low_u64_be(2));
+        let (topic_0, topic_1) = (H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+        let receipt = |address, topics| Receipt {
+            tx_type: TxType::Legacy,
+            succeeded: true,
+            cumulative_gas_used: 0,
+            logs: vec![Log {
+                address,
+                topics,
+                data: Bytes::new(),
+            }],
+        };
+        let block = |number, extra_data: &'static [u8]| {
+            let mut block = Block::default();
+            block.header.number = number;
+            block.header.extra_data = Bytes::from_static(extra_data);
+            block
+        };
+        let blocks = [
+            block(1, b""),
+            block(2, b""),
+            block(2, b"side"),
+            block(3, b""),
+        ];
+        let receipts = vec![
+            (blocks[0].hash(), vec![receipt(address_a, vec![topic_0])]),
+            (
+                blocks[1].hash(),
+                vec![receipt(address_b, vec![topic_0, topic_1])],
+            ),
+            (blocks[2].hash(), vec![receipt(address_a, vec![])]),
+            (blocks[3].hash(), vec![]),
+        ];
+        store
+            .store_block_updates(UpdateBatch {
+                account_updates: vec![],
+                storage_updates: vec![],
+                blocks: blocks.to_vec(),
+                receipts,
+                transaction_addresses: vec![],
+                code_updates: vec![],
+            })
+            .unwrap();
+        // The side block at height 2 isn't canonical
+        let canonical = [&blocks[0], &blocks[1], &blocks[3]]
+            .map(|block| (block.header.number, block.hash()))
+            .to_vec();
+        store
+            .forkchoice_update(canonical, 3, blocks[3].hash(), None, None)
+            .await
+            .unwrap();
+
+        let candidates = |addresses, topics| {
+            store.get_log_candidate_blocks(0..=3, LogIndexQuery { addresses, topics })
+        };
+        // Blocks below the first indexed one are always searched
+        assert_eq!(
+            candidates(vec![address_a], vec![]).await.unwrap(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            candidates(vec![], vec![vec![], vec![topic_1]])
+                .await
+                .unwrap(),
+            vec![0, 2]
+        );
+        assert_eq!(
+            candidates(vec![address_a, address_b], vec![vec![topic_0]])
+                .await
+                .unwrap(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            candidates(vec![address_b], vec![vec![topic_1]])
+                .await
+                .unwrap(),
+            vec![0]
+        );
+        assert_eq!(
+            store
+                .get_log_candidate_blocks(1..=3, LogIndexQuery::default())
+                .await
+                .unwrap(),
+            vec![1, 2, 3]
+        );
+    }
+
+    async fn test_bad_blocks(store: Store) {
+        let latest_valid = H256::random();
+        for number in 0..=MAX_BAD_BLOCKS as u64 {
+            let mut block = Block::default();
+            block.header.number = number;
+            store.add_bad_block(block, latest_valid).await.unwrap();
+        }
+
+        let bad_blocks = store.get_bad_blocks().await.unwrap();
+        assert_eq!(bad_blocks.len(), MAX_BAD_BLOCKS);
+        // The oldest one was dropped, but it's still marked as invalid
+        assert_eq!(bad_blocks[0].header.number, MAX_BAD_BLOCKS as u64);
+        assert_eq!(bad_blocks[MAX_BAD_BLOCKS - 1].header.number, 1);
+
+        let mut oldest = Block::default();
+        oldest.header.number = 0;
+        assert!(store.get_bad_block(oldest.hash()).await.unwrap().is_none());
+        assert_eq!(
+            store
+                .get_latest_valid_ancestor(oldest.hash())
+                .await
+                .unwrap(),
+            Some(latest_valid)
+        );
+        assert_eq!(
+            store.get_bad_block(bad_blocks[0].hash()).await.unwrap(),
+            Some(bad_blocks[0].clone())
+        );
+    }
+
+    async fn test_cleared_bad_blocks_are_evicted_first(store: Store) {
+        let latest_valid = H256::random();
+        let mut cleared = Block::default();
+        cleared.header.number = 100;
+        store
+            .add_bad_block(cleared.clone(), latest_valid)
+            .await
+            .unwrap();
+        store
+            .delete(INVALID_CHAINS, cleared.hash().as_bytes().to_vec())
+            .unwrap();
+
+        for number in 0..MAX_BAD_BLOCKS as u64 {
+            let mut block = Block::default();
+            block.header.number = number;
+            store.add_bad_block(block, latest_valid).await.unwrap();
+        }
+
+        // The cleared body counted towards the limit, and went first even though it's the newest
+        assert!(store.get_bad_block(cleared.hash()).await.unwrap().is_none());
+        let bad_blocks = store.get_bad_blocks().await.unwrap();
+        assert_eq!(bad_blocks.len(), MAX_BAD_BLOCKS);
+        assert_eq!(bad_blocks[MAX_BAD_BLOCKS - 1].header.number, 0);
     }
 
     async fn test_iter_accounts(store: Store) {
@@ -2909,6 +3497,64 @@ mod tests {
         }
     }
 
+    async fn test_accounts_storage_at_root(store: Store) {
+        let address = Address::repeat_byte(0xaa);
+        let account_hash = hash_address_fixed(&address);
+        let slot = H256::from_low_u64_be(1);
+        let mut trie = store
+            .open_direct_storage_trie(account_hash, *EMPTY_TRIE_HASH)
+            .unwrap();
+        trie.insert(hash_key(&slot), U256::from(5).encode_to_vec())
+            .unwrap();
+        let storage_root = trie.hash().unwrap();
+        let account = AccountState {
+            nonce: 1,
+            balance: U256::from(7),
+            storage_root,
+            code_hash: *EMPTY_KECCACK_HASH,
+        };
+        let mut trie = store.open_direct_state_trie(*EMPTY_TRIE_HASH).unwrap();
+        trie.insert(account_hash.0.to_vec(), account.encode_to_vec())
+            .unwrap();
+        let state_root = trie.hash().unwrap();
+
+        assert!(
+            store
+                .get_accounts_storage_at_root(H256::random(), &[(address, vec![slot])])
+                .unwrap()
+                .is_none()
+        );
+
+        // Generate the flat key-values so the slots are read from them
+        let (_control_tx, control_rx) = sync_channel(0);
+        flatkeyvalue_generator(
+            &store.backend,
+            &store.last_computed_flatkeyvalue,
+            &control_rx,
+        )
+        .unwrap();
+        assert!(store.flatkeyvalue_computed(account_hash).unwrap());
+
+        let missing = Address::repeat_byte(0xbb);
+        let results = store
+            .get_accounts_storage_at_root(
+                state_root,
+                &[
+                    (address, vec![slot, H256::from_low_u64_be(2)]),
+                    (missing, vec![slot]),
+                ],
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            results,
+            vec![
+                (Some(account), vec![U256::from(5), U256::zero()]),
+                (None, vec![U256::zero()]),
+            ]
+        );
+    }
+
     async fn test_genesis_block(mut store: Store) {
         const GENESIS_KURTOSIS: &str = include_str!("../../fixtures/genesis/kurtosis.json");
         const GENESIS_HIVE: &str = include_str!("../../fixtures/genesis/hive.json");
@@ -1,6 +1,6 @@
 use ethrex_common::U256 as CoreU256;
 use ethrex_common::constants::EMPTY_KECCACK_HASH;
-use ethrex_common::types::{AccountState, Code};
+use ethrex_common::types::{AccountState, Code, CodeAnalysisCache};
 use ethrex_common::{Address as CoreAddress, H256 as CoreH256};
 use ethrex_levm::db::Database as LevmDatabase;
 
@@ -98,6 +98,10 @@ impl LevmDatabase for DatabaseLogger {
             .map_err(|_| DatabaseError::Custom("Could not lock mutex".to_string()))?
             .get_account_code(code_hash)
     }
+
+    fn code_analysis_cache(&self) -> Option<Arc<CodeAnalysisCache>> {
+        self.store.lock().ok()?.code_analysis_cache()
+    }
 }
 
 impl LevmDatabase for DynVmDatabase {
@@ -135,4 +139,8 @@ impl LevmDatabase for DynVmDatabase {
         <dyn VmDatabase>::get_account_code(self.as_ref(), code_hash)
             .map_err(|e| DatabaseError::Custom(e.to_string()))
     }
+
+    fn code_analysis_cache(&self) -> Option<Arc<CodeAnalysisCache>> {
+        <dyn VmDatabase>::code_analysis_cache(self.as_ref())
+    }
 }
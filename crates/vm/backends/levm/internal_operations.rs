@@ -0,0 +1,138 @@
+use bytes::Bytes;
+use ethrex_common::{
+    Address, U256,
+    tracing::{CallType, InternalOperation, InternalOperationType},
+};
+use ethrex_levm::hooks::Tracer;
+use serde_json::Value;
+
+/// Collects the internal operations of a transaction, as reported by Otterscan's
+/// `ots_getInternalOperations`: value transfers of inner CALLs, contract creations and self
+/// destructs. Operations of call frames that later revert are kept.
+#[derive(Default)]
+pub struct InternalOperationsTracer {
+    depth: usize,
+    operations: Vec<InternalOperation>,
+}
+
+impl InternalOperationsTracer {
+    pub fn take_operations(&mut self) -> Vec<InternalOperation> {
+        std::mem::take(&mut self.operations)
+    }
+}
+
+impl Tracer for InternalOperationsTracer {
+    fn on_call_enter(
+        &mut self,
+        call_type: &CallType,
+        from: Address,
+        to: Address,
+        value: U256,
+        _gas: u64,
+        _input: &Bytes,
+    ) {
+        let is_top_call = self.depth == 0;
+        self.depth = self.depth.saturating_add(1);
+        if is_top_call {
+            return;
+        }
+        let operation_type = match call_type {
+            CallType::CALL if !value.is_zero() => InternalOperationType::Transfer,
+            CallType::CREATE => InternalOperationType::Create,
+            CallType::CREATE2 => InternalOperationType::Create2,
+            CallType::SELFDESTRUCT => InternalOperationType::SelfDestruct,
+            _ => return,
+        };
+        self.operations.push(InternalOperation {
+            operation_type,
+            from,
+            to,
+            value,
+        });
+    }
+
+    fn on_call_exit(&mut self, _gas_used: u64, _output: &Bytes, _error: Option<&str>) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    fn result(&mut self) -> Value {
+        serde_json::to_value(self.take_operations()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enter(tracer: &mut impl Tracer, call_type: CallType, from: u64, to: u64, value: u64) {
+        tracer.on_call_enter(
+            &call_type,
+            Address::from_low_u64_be(from),
+            Address::from_low_u64_be(to),
+            U256::from(value),
+            0,
+            &Bytes::new(),
+        );
+    }
+
+    fn exit(tracer: &mut impl Tracer) {
+        tracer.on_call_exit(0, &Bytes::new(), None);
+    }
+
+    fn operation(
+        operation_type: InternalOperationType,
+        from: u64,
+        to: u64,
+        value: u64,
+    ) -> InternalOperation {
+        InternalOperation {
+            operation_type,
+            from: Address::from_low_u64_be(from),
+            to: Address::from_low_u64_be(to),
+            value: U256::from(value),
+        }
+    }
+
+    #[test]
+    fn records_the_operations_of_inner_calls() {
+        let mut tracer = InternalOperationsTracer::default();
+        // The value sent by the transaction itself isn't an internal operation
+        enter(&mut tracer, CallType::CALL, 1, 2, 10);
+        enter(&mut tracer, CallType::CALL, 2, 3, 5);
+        exit(&mut tracer);
+        // Calls not moving value aren't recorded either
+        enter(&mut tracer, CallType::CALL, 2, 3, 0);
+        enter(&mut tracer, CallType::DELEGATECALL, 3, 4, 0);
+        exit(&mut tracer);
+        exit(&mut tracer);
+        enter(&mut tracer, CallType::CREATE, 2, 5, 1);
+        enter(&mut tracer, CallType::CREATE2, 5, 6, 0);
+        enter(&mut tracer, CallType::SELFDESTRUCT, 6, 7, 2);
+        exit(&mut tracer);
+        exit(&mut tracer);
+        exit(&mut tracer);
+        exit(&mut tracer);
+
+        assert_eq!(
+            tracer.take_operations(),
+            vec![
+                operation(InternalOperationType::Transfer, 2, 3, 5),
+                operation(InternalOperationType::Create, 2, 5, 1),
+                operation(InternalOperationType::Create2, 5, 6, 0),
+                operation(InternalOperationType::SelfDestruct, 6, 7, 2),
+            ]
+        );
+        assert!(tracer.take_operations().is_empty());
+    }
+
+    #[test]
+    fn contract_creation_transactions_are_not_internal_operations() {
+        let mut tracer = InternalOperationsTracer::default();
+        enter(&mut tracer, CallType::CREATE, 1, 2, 10);
+        exit(&mut tracer);
+        // The depth is back at the top for the next transaction traced
+        enter(&mut tracer, CallType::CALL, 1, 3, 10);
+        exit(&mut tracer);
+        assert!(tracer.take_operations().is_empty());
+    }
+}
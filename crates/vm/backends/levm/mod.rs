@@ -1,5 +1,9 @@
 pub mod db;
+mod internal_operations;
+mod parallel;
 mod tracing;
+mod transaction_addresses;
+mod vm_trace;
 
 use super::BlockExecutionResult;
 use crate::system_contracts::{
@@ -14,8 +18,8 @@ use ethrex_common::{
     Address, U256,
     types::{
         AccessList, AccountUpdate, Block, BlockHeader, EIP1559Transaction, Fork, GWEI_TO_WEI,
-        GenericTransaction, INITIAL_BASE_FEE, Receipt, Transaction, TxKind, Withdrawal,
-        requests::Requests,
+        GenericTransaction, INITIAL_BASE_FEE, Receipt, StateOverride, Transaction, TxKind,
+        Withdrawal, block_access_list::BlockAccessIndex, requests::Requests,
     },
 };
 use ethrex_levm::EVMConfig;
@@ -23,6 +27,7 @@ use ethrex_levm::call_frame::Stack;
 use ethrex_levm::constants::{
     POST_OSAKA_GAS_LIMIT_CAP, STACK_LIMIT, SYS_CALL_GAS_LIMIT, TX_BASE_COST,
 };
+use ethrex_levm::db::block_access_list::BlockAccessListRecorder;
 use ethrex_levm::db::gen_db::GeneralizedDatabase;
 use ethrex_levm::errors::{InternalError, TxValidationError};
 use ethrex_levm::tracing::LevmCallTracer;
@@ -33,9 +38,12 @@ use ethrex_levm::{
     errors::{ExecutionReport, TxResult, VMError},
     vm::VM,
 };
+use std::cell::RefCell;
 use std::cmp::min;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::Sender;
+use transaction_addresses::TransactionAddressesTracer;
 
 /// The struct implements the following functions:
 /// [LEVM::execute_block]
@@ -51,14 +59,22 @@ impl LEVM {
         db: &mut GeneralizedDatabase,
         vm_type: VMType,
     ) -> Result<BlockExecutionResult, EvmError> {
+        Self::setup_block_access_list(block, db)?;
         Self::prepare_block(block, db, vm_type)?;
+        db.checkpoint_block_access_list(0);
 
         let mut receipts = Vec::new();
         let mut cumulative_gas_used = 0;
 
-        for (tx, tx_sender) in block.body.get_transactions_with_sender().map_err(|error| {
-            EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
-        })? {
+        for (tx_index, (tx, tx_sender)) in block
+            .body
+            .get_transactions_with_sender()
+            .map_err(|error| {
+                EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+            })?
+            .into_iter()
+            .enumerate()
+        {
             if cumulative_gas_used + tx.gas_limit() > block.header.gas_limit {
                 return Err(EvmError::Transaction(format!(
                     "Gas allowance exceeded. Block gas limit {} can be surpassed by executing transaction with gas limit {}",
@@ -68,6 +84,7 @@ impl LEVM {
             }
 
             let report = Self::execute_tx(tx, tx_sender, &block.header, db, vm_type)?;
+            db.checkpoint_block_access_list(block_access_index(tx_index + 1)?);
 
             cumulative_gas_used += report.gas_used;
             let receipt = Receipt::new(
@@ -91,18 +108,30 @@ impl LEVM {
             VMType::L1 => extract_all_requests_levm(&receipts, db, &block.header, vm_type)?,
             VMType::L2(_) => Default::default(),
         };
-
-        Ok(BlockExecutionResult { receipts, requests })
+        db.checkpoint_block_access_list(block_access_index(receipts.len() + 1)?);
+
+        Ok(BlockExecutionResult {
+            receipts,
+            requests,
+            block_access_list: db
+                .block_access_list
+                .take()
+                .map(BlockAccessListRecorder::build),
+            transaction_addresses: db.transaction_addresses.as_mut().map(std::mem::take),
+        })
     }
 
     pub fn execute_block_pipeline(
         block: &Block,
+        transactions: &[(&Transaction, Address)],
         db: &mut GeneralizedDatabase,
         vm_type: VMType,
         merkleizer: Sender<Vec<AccountUpdate>>,
         queue_length: &AtomicUsize,
     ) -> Result<BlockExecutionResult, EvmError> {
+        Self::setup_block_access_list(block, db)?;
         Self::prepare_block(block, db, vm_type)?;
+        db.checkpoint_block_access_list(0);
 
         let mut shared_stack_pool = Vec::with_capacity(STACK_LIMIT);
 
@@ -113,9 +142,7 @@ impl LEVM {
         // The value itself can be safely changed.
         let mut tx_since_last_flush = 2;
 
-        for (tx, tx_sender) in block.body.get_transactions_with_sender().map_err(|error| {
-            EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
-        })? {
+        for (tx_index, &(tx, tx_sender)) in transactions.iter().enumerate() {
             if cumulative_gas_used + tx.gas_limit() > block.header.gas_limit {
                 return Err(EvmError::Transaction(format!(
                     "Gas allowance exceeded. Block gas limit {} can be surpassed by executing transaction with gas limit {}",
@@ -132,6 +159,7 @@ impl LEVM {
                 vm_type,
                 &mut shared_stack_pool,
             )?;
+            db.checkpoint_block_access_list(block_access_index(tx_index + 1)?);
             if queue_length.load(Ordering::Relaxed) == 0 && tx_since_last_flush > 5 {
                 LEVM::send_state_transitions_tx(&merkleizer, db, queue_length)?;
                 tx_since_last_flush = 0;
@@ -175,9 +203,33 @@ impl LEVM {
             VMType::L1 => extract_all_requests_levm(&receipts, db, &block.header, vm_type)?,
             VMType::L2(_) => Default::default(),
         };
+        db.checkpoint_block_access_list(block_access_index(receipts.len() + 1)?);
         LEVM::send_state_transitions_tx(&merkleizer, db, queue_length)?;
 
-        Ok(BlockExecutionResult { receipts, requests })
+        Ok(BlockExecutionResult {
+            receipts,
+            requests,
+            block_access_list: db
+                .block_access_list
+                .take()
+                .map(BlockAccessListRecorder::build),
+            transaction_addresses: db.transaction_addresses.as_mut().map(std::mem::take),
+        })
+    }
+
+    /// Starts recording the block access list if the block has to commit to it (EIP-7928).
+    pub fn setup_block_access_list(
+        block: &Block,
+        db: &mut GeneralizedDatabase,
+    ) -> Result<(), EvmError> {
+        if db
+            .store
+            .get_chain_config()?
+            .is_amsterdam_activated(block.header.timestamp)
+        {
+            db.enable_block_access_list();
+        }
+        Ok(())
     }
 
     fn send_state_transitions_tx(
@@ -248,9 +300,16 @@ impl LEVM {
         vm_type: VMType,
     ) -> Result<ExecutionReport, EvmError> {
         let env = Self::setup_env(tx, tx_sender, block_header, db, vm_type)?;
+        let tracer = Self::transaction_addresses_tracer(tx, tx_sender, db);
         let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type)?;
+        if let Some(tracer) = &tracer {
+            vm.tracers.push(tracer.clone());
+        }
 
-        vm.execute().map_err(VMError::into)
+        let report = vm.execute().map_err(VMError::into);
+        drop(vm);
+        Self::record_transaction_addresses(db, tracer, &report);
+        report
     }
 
     // Like execute_tx but allows reusing the stack pool
@@ -266,16 +325,51 @@ impl LEVM {
         stack_pool: &mut Vec<Stack>,
     ) -> Result<ExecutionReport, EvmError> {
         let env = Self::setup_env(tx, tx_sender, block_header, db, vm_type)?;
+        let tracer = Self::transaction_addresses_tracer(tx, tx_sender, db);
         let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type)?;
+        if let Some(tracer) = &tracer {
+            vm.tracers.push(tracer.clone());
+        }
 
         std::mem::swap(&mut vm.stack_pool, stack_pool);
         let result = vm.execute().map_err(VMError::into);
         std::mem::swap(&mut vm.stack_pool, stack_pool);
+        drop(vm);
+        Self::record_transaction_addresses(db, tracer, &result);
         result
     }
 
+    /// Tracer collecting the addresses the transaction takes part in, if they are being indexed.
+    fn transaction_addresses_tracer(
+        tx: &Transaction,
+        tx_sender: Address,
+        db: &GeneralizedDatabase,
+    ) -> Option<Rc<RefCell<TransactionAddressesTracer>>> {
+        db.transaction_addresses.as_ref().map(|_| {
+            Rc::new(RefCell::new(TransactionAddressesTracer::new(
+                tx_sender,
+                tx.to(),
+            )))
+        })
+    }
+
+    fn record_transaction_addresses(
+        db: &mut GeneralizedDatabase,
+        tracer: Option<Rc<RefCell<TransactionAddressesTracer>>>,
+        report: &Result<ExecutionReport, EvmError>,
+    ) {
+        if let (Some(tracer), Some(recorded), Ok(_)) =
+            (tracer, db.transaction_addresses.as_mut(), report)
+        {
+            recorded.push(tracer.borrow_mut().take_addresses());
+        }
+    }
+
     pub fn undo_last_tx(db: &mut GeneralizedDatabase) -> Result<(), EvmError> {
         db.undo_last_transaction()?;
+        if let Some(recorded) = db.transaction_addresses.as_mut() {
+            recorded.pop();
+        }
         Ok(())
     }
 
@@ -293,13 +387,59 @@ impl LEVM {
 
         adjust_disabled_base_fee(&mut env);
 
-        let mut vm = vm_from_generic(tx, env, db, vm_type)?;
+        let mut vm = vm_from_generic(tx, env, db, vm_type, LevmCallTracer::disabled())?;
 
         vm.execute()
             .map(|value| value.into())
             .map_err(VMError::into)
     }
 
+    /// Replaces the state of the given accounts before simulating a transaction on `db`.
+    pub fn apply_state_override(
+        db: &mut GeneralizedDatabase,
+        state_override: &StateOverride,
+    ) -> Result<(), EvmError> {
+        for (address, account_override) in state_override {
+            let code = account_override
+                .code
+                .as_ref()
+                .map(|code| db.new_code(code.clone()));
+            let account = db.get_account_mut(*address)?;
+
+            if let Some(balance) = account_override.balance {
+                account.info.balance = balance;
+            }
+            if let Some(nonce) = account_override.nonce {
+                account.info.nonce = nonce;
+            }
+            if let Some(code) = &code {
+                account.info.code_hash = code.hash;
+            }
+            if let Some(state) = &account_override.state {
+                // Slots that aren't overriden must read as zero instead of coming from the database.
+                account.mark_destroyed();
+                account.mark_modified();
+                account.has_storage = !state.is_empty();
+                account.storage = state
+                    .iter()
+                    .map(|(key, value)| (*key, U256::from_big_endian(value.as_bytes())))
+                    .collect();
+            }
+            if let Some(state_diff) = &account_override.state_diff {
+                account.storage.extend(
+                    state_diff
+                        .iter()
+                        .map(|(key, value)| (*key, U256::from_big_endian(value.as_bytes()))),
+                );
+            }
+
+            if let Some(code) = code {
+                db.codes.insert(code.hash, code);
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_state_transitions(
         db: &mut GeneralizedDatabase,
     ) -> Result<Vec<AccountUpdate>, EvmError> {
@@ -447,13 +587,13 @@ impl LEVM {
 
         adjust_disabled_base_fee(&mut env);
 
-        let mut vm = vm_from_generic(&tx, env.clone(), db, vm_type)?;
+        let mut vm = vm_from_generic(&tx, env.clone(), db, vm_type, LevmCallTracer::disabled())?;
 
         vm.stateless_execute()?;
 
         // Execute the tx again, now with the created access list.
         tx.access_list = vm.substate.make_access_list();
-        let mut vm = vm_from_generic(&tx, env, db, vm_type)?;
+        let mut vm = vm_from_generic(&tx, env, db, vm_type, LevmCallTracer::disabled())?;
 
         let report = vm.stateless_execute()?;
 
@@ -507,6 +647,17 @@ pub fn generic_system_contract_levm(
         .current_accounts_state
         .get(&block_header.coinbase)
         .cloned();
+    // Neither the system address nor the coinbase are part of the block access list because of a system call
+    let untracked_accounts: Vec<Address> = db
+        .block_access_list
+        .as_ref()
+        .map(|recorder| {
+            [system_address, block_header.coinbase]
+                .into_iter()
+                .filter(|address| !recorder.is_pending(address))
+                .collect()
+        })
+        .unwrap_or_default();
     let env = Environment {
         origin: system_address,
         // EIPs 2935, 4788, 7002 and 7251 dictate that the system calls have a gas limit of 30 million and they do not use intrinsic gas.
@@ -567,9 +718,24 @@ pub fn generic_system_contract_levm(
         db.current_accounts_state.remove(&block_header.coinbase);
     }
 
+    if let Some(recorder) = db.block_access_list.as_mut() {
+        for address in &untracked_accounts {
+            recorder.forget_account_access(address);
+        }
+    }
+
     Ok(report)
 }
 
+/// Block access index of the execution step at `position`, see [`BlockAccessIndex`].
+pub fn block_access_index(position: usize) -> Result<BlockAccessIndex, EvmError> {
+    BlockAccessIndex::try_from(position).map_err(|_| {
+        EvmError::Custom(format!(
+            "Block access index {position} doesn't fit in the block access list"
+        ))
+    })
+}
+
 #[allow(unreachable_code)]
 #[allow(unused_variables)]
 pub fn extract_all_requests_levm(
@@ -724,6 +890,7 @@ fn vm_from_generic<'a>(
     env: Environment,
     db: &'a mut GeneralizedDatabase,
     vm_type: VMType,
+    tracer: LevmCallTracer,
 ) -> Result<VM<'a>, VMError> {
     let tx = match &tx.authorization_list {
         Some(authorization_list) => Transaction::EIP7702Transaction(EIP7702Transaction {
@@ -760,7 +927,7 @@ fn vm_from_generic<'a>(
     };
 
     let vm_type = adjust_disabled_l2_fees(&env, vm_type);
-    VM::new(env, db, &tx, LevmCallTracer::disabled(), vm_type)
+    VM::new(env, db, &tx, tracer, vm_type)
 }
 
 pub fn get_max_allowed_gas_limit(block_gas_limit: u64, fork: Fork) -> u64 {
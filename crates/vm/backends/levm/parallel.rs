@@ -0,0 +1,643 @@
+//! Optimistic parallel execution of the transactions of a block.
+//!
+//! Every transaction is first executed speculatively against a snapshot of the state right before
+//! the block's transactions, recording the accounts and storage slots it reads. Results are then
+//! committed in block order. A transaction that didn't read anything written by the transactions
+//! committed before it saw exactly the state serial execution would have given it, so its changes
+//! are merged as they are. Any other transaction is re-executed on top of the committed state.
+//! This keeps receipts and the resulting state identical to the ones of serial execution.
+
+use std::cell::RefCell;
+use std::num::NonZeroUsize;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+
+use ethrex_common::constants::EMPTY_TRIE_HASH;
+use ethrex_common::types::{
+    AccountState, AccountUpdate, Block, BlockHeader, ChainConfig, Code, CodeAnalysisCache, Receipt,
+    Transaction,
+};
+use ethrex_common::{Address, H256, U256};
+use ethrex_levm::account::AccountStatus;
+use ethrex_levm::db::Database;
+use ethrex_levm::db::gen_db::{CacheDB, GeneralizedDatabase};
+use ethrex_levm::errors::{DatabaseError, ExecutionReport, InternalError, TxResult};
+use ethrex_levm::hooks::backup_hook::BackupHook;
+use ethrex_levm::tracing::LevmCallTracer;
+use ethrex_levm::vm::{VM, VMType};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::{LEVM, extract_all_requests_levm};
+use crate::{BlockExecutionResult, EvmError};
+
+/// A piece of state a transaction can read or write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum StateKey {
+    /// Balance, nonce, code or existence of an account.
+    Account(Address),
+    Storage(Address, H256),
+}
+
+/// Read-only view of the state of a [`GeneralizedDatabase`] at a given point of the block,
+/// shared by every transaction executed speculatively.
+struct StateSnapshot {
+    store: Arc<dyn Database>,
+    accounts: CacheDB,
+    codes: FxHashMap<H256, Code>,
+}
+
+impl StateSnapshot {
+    fn new(db: &GeneralizedDatabase) -> Self {
+        Self {
+            store: db.store.clone(),
+            accounts: db.current_accounts_state.clone(),
+            codes: db.codes.clone(),
+        }
+    }
+}
+
+impl Database for StateSnapshot {
+    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+        let Some(account) = self.accounts.get(&address) else {
+            return self.store.get_account_state(address);
+        };
+        // The storage root is only used to know whether the account has storage, see `LevmAccount::has_storage`.
+        let storage_root = if account.has_storage {
+            H256::repeat_byte(0xff)
+        } else {
+            *EMPTY_TRIE_HASH
+        };
+        Ok(AccountState {
+            nonce: account.info.nonce,
+            balance: account.info.balance,
+            storage_root,
+            code_hash: account.info.code_hash,
+        })
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        if let Some(account) = self.accounts.get(&address) {
+            if let Some(value) = account.storage.get(&key) {
+                return Ok(*value);
+            }
+            // The storage in the store isn't valid anymore for destroyed accounts
+            if is_destroyed(&account.status) {
+                return Ok(U256::zero());
+            }
+        }
+        self.store.get_storage_value(address, key)
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        self.store.get_block_hash(block_number)
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        self.store.get_chain_config()
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+        match self.codes.get(&code_hash) {
+            Some(code) => Ok(code.clone()),
+            None => self.store.get_account_code(code_hash),
+        }
+    }
+
+    fn code_analysis_cache(&self) -> Option<Arc<CodeAnalysisCache>> {
+        self.store.code_analysis_cache()
+    }
+}
+
+/// Outcome of executing a transaction against a [`StateSnapshot`].
+struct SpeculativeExecution {
+    report: ExecutionReport,
+    /// Database holding the accounts and storage slots read and written by the transaction.
+    db: GeneralizedDatabase,
+    /// Fee for the coinbase, which is paid once the transaction is committed.
+    coinbase_fee: U256,
+}
+
+impl SpeculativeExecution {
+    /// Checks the transaction didn't read anything written by the transactions committed before it.
+    fn is_valid(&self, written: &FxHashSet<StateKey>, coinbase: Address) -> bool {
+        // The deferred coinbase fee is only equivalent to paying it during execution if the transaction
+        // doesn't touch the coinbase itself.
+        if self.db.initial_accounts_state.contains_key(&coinbase) {
+            return false;
+        }
+        self.db
+            .initial_accounts_state
+            .iter()
+            .all(|(address, account)| {
+                !written.contains(&StateKey::Account(*address))
+                    && account
+                        .storage
+                        .keys()
+                        .all(|key| !written.contains(&StateKey::Storage(*address, *key)))
+            })
+    }
+
+    fn commit(
+        self,
+        db: &mut GeneralizedDatabase,
+        coinbase: Address,
+        written: &mut FxHashSet<StateKey>,
+    ) -> Result<ExecutionReport, EvmError> {
+        for (address, account) in &self.db.current_accounts_state {
+            if account.is_unmodified() {
+                continue;
+            }
+            let initial = self.db.initial_accounts_state.get(address);
+            if is_destroyed(&account.status)
+                || initial.is_none_or(|initial| initial.info != account.info)
+            {
+                written.insert(StateKey::Account(*address));
+            }
+            for (key, value) in &account.storage {
+                if initial.and_then(|initial| initial.storage.get(key)) != Some(value) {
+                    written.insert(StateKey::Storage(*address, *key));
+                }
+            }
+        }
+        db.merge_transaction(self.db)?;
+
+        let coinbase_account = db.get_account_mut(coinbase)?;
+        coinbase_account.info.balance = coinbase_account
+            .info
+            .balance
+            .checked_add(self.coinbase_fee)
+            .ok_or(InternalError::Overflow)?;
+        written.insert(StateKey::Account(coinbase));
+
+        Ok(self.report)
+    }
+}
+
+fn is_destroyed(status: &AccountStatus) -> bool {
+    matches!(
+        status,
+        AccountStatus::Destroyed | AccountStatus::DestroyedModified
+    )
+}
+
+fn records_block_access_list(block: &Block, db: &GeneralizedDatabase) -> Result<bool, EvmError> {
+    Ok(db
+        .store
+        .get_chain_config()?
+        .is_amsterdam_activated(block.header.timestamp))
+}
+
+impl LEVM {
+    /// Executes the block like [`LEVM::execute_block`], but running its transactions in parallel.
+    pub fn execute_block_parallel(
+        block: &Block,
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+    ) -> Result<BlockExecutionResult, EvmError> {
+        // L2 hooks pay fees to accounts shared by every transaction, so there's nothing to gain.
+        // Block access lists and indexed addresses are recorded in execution order, which merging
+        // speculative runs doesn't keep.
+        if matches!(vm_type, VMType::L2(_))
+            || records_block_access_list(block, db)?
+            || db.transaction_addresses.is_some()
+        {
+            return Self::execute_block(block, db, vm_type);
+        }
+
+        Self::prepare_block(block, db, vm_type)?;
+
+        let transactions = block.body.get_transactions_with_sender().map_err(|error| {
+            EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+        })?;
+        let receipts =
+            Self::execute_transactions_parallel(block, &transactions, db, vm_type, |_| Ok(()))?;
+
+        if let Some(withdrawals) = &block.body.withdrawals {
+            Self::process_withdrawals(db, withdrawals)?;
+        }
+        let requests = extract_all_requests_levm(&receipts, db, &block.header, vm_type)?;
+
+        Ok(BlockExecutionResult {
+            receipts,
+            requests,
+            block_access_list: None,
+            transaction_addresses: None,
+        })
+    }
+
+    /// Executes the block like [`LEVM::execute_block_pipeline`], but running its transactions in parallel.
+    pub fn execute_block_pipeline_parallel(
+        block: &Block,
+        transactions: &[(&Transaction, Address)],
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+        merkleizer: Sender<Vec<AccountUpdate>>,
+        queue_length: &AtomicUsize,
+    ) -> Result<BlockExecutionResult, EvmError> {
+        if matches!(vm_type, VMType::L2(_))
+            || records_block_access_list(block, db)?
+            || db.transaction_addresses.is_some()
+        {
+            return Self::execute_block_pipeline(
+                block,
+                transactions,
+                db,
+                vm_type,
+                merkleizer,
+                queue_length,
+            );
+        }
+
+        Self::prepare_block(block, db, vm_type)?;
+
+        // Starts at 2 to account for the two precompile calls done in `Self::prepare_block`.
+        let mut tx_since_last_flush = 2;
+        let receipts =
+            Self::execute_transactions_parallel(block, transactions, db, vm_type, |db| {
+                if queue_length.load(Ordering::Relaxed) == 0 && tx_since_last_flush > 5 {
+                    Self::send_state_transitions_tx(&merkleizer, db, queue_length)?;
+                    tx_since_last_flush = 0;
+                } else {
+                    tx_since_last_flush += 1;
+                }
+                Ok(())
+            })?;
+        if queue_length.load(Ordering::Relaxed) == 0 {
+            Self::send_state_transitions_tx(&merkleizer, db, queue_length)?;
+        }
+
+        if let Some(withdrawals) = &block.body.withdrawals {
+            Self::process_withdrawals(db, withdrawals)?;
+        }
+        let requests = extract_all_requests_levm(&receipts, db, &block.header, vm_type)?;
+        Self::send_state_transitions_tx(&merkleizer, db, queue_length)?;
+
+        Ok(BlockExecutionResult {
+            receipts,
+            requests,
+            block_access_list: None,
+            transaction_addresses: None,
+        })
+    }
+
+    /// Executes the transactions of the block, given with their senders, speculatively in parallel and
+    /// then commits them in order, calling `on_commit` after each one of them.
+    fn execute_transactions_parallel(
+        block: &Block,
+        transactions: &[(&Transaction, Address)],
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+        mut on_commit: impl FnMut(&mut GeneralizedDatabase) -> Result<(), EvmError>,
+    ) -> Result<Vec<Receipt>, EvmError> {
+        let snapshot: Arc<dyn Database> = Arc::new(StateSnapshot::new(db));
+        let speculative = execute_speculatively(transactions, &block.header, snapshot, vm_type);
+
+        let coinbase = block.header.coinbase;
+        let mut written = FxHashSet::default();
+        let mut receipts = Vec::new();
+        let mut cumulative_gas_used = 0;
+
+        for (&(tx, tx_sender), execution) in transactions.iter().zip(speculative) {
+            if cumulative_gas_used + tx.gas_limit() > block.header.gas_limit {
+                return Err(EvmError::Transaction(format!(
+                    "Gas allowance exceeded. Block gas limit {} can be surpassed by executing transaction with gas limit {}",
+                    block.header.gas_limit,
+                    tx.gas_limit()
+                )));
+            }
+
+            let report = match execution.filter(|execution| execution.is_valid(&written, coinbase))
+            {
+                Some(execution) => execution.commit(db, coinbase, &mut written)?,
+                None => Self::execute_tx_tracking_writes(
+                    tx,
+                    tx_sender,
+                    &block.header,
+                    db,
+                    vm_type,
+                    &mut written,
+                )?,
+            };
+            on_commit(db)?;
+
+            cumulative_gas_used += report.gas_used;
+            let receipt = Receipt::new(
+                tx.tx_type(),
+                matches!(report.result, TxResult::Success),
+                cumulative_gas_used,
+                report.logs,
+            );
+
+            receipts.push(receipt);
+        }
+
+        Ok(receipts)
+    }
+
+    /// Executes the transaction on top of `db`, adding the state it modified to `written`.
+    fn execute_tx_tracking_writes(
+        tx: &Transaction,
+        tx_sender: Address,
+        block_header: &BlockHeader,
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+        written: &mut FxHashSet<StateKey>,
+    ) -> Result<ExecutionReport, EvmError> {
+        let env = Self::setup_env(tx, tx_sender, block_header, db, vm_type)?;
+        let report = {
+            let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type)?;
+            vm.hooks.push(Rc::new(RefCell::new(BackupHook::default())));
+            vm.execute()?
+        };
+
+        let backup = db.get_tx_backup()?;
+        db.tx_backup = None;
+        for (address, original) in &backup.original_accounts_info {
+            let changed = db
+                .current_accounts_state
+                .get(address)
+                .is_none_or(|account| {
+                    account.info != original.info
+                        || is_destroyed(&account.status) && !is_destroyed(&original.status)
+                });
+            if changed {
+                written.insert(StateKey::Account(*address));
+            }
+        }
+        for (address, slots) in &backup.original_account_storage_slots {
+            let account = db.current_accounts_state.get(address);
+            for (key, original) in slots {
+                if account.and_then(|account| account.storage.get(key)) != Some(original) {
+                    written.insert(StateKey::Storage(*address, *key));
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Executes every transaction against `snapshot` using all the available cores.
+/// Transactions that failed to execute get `None`, they'll be re-executed when committing them.
+fn execute_speculatively(
+    transactions: &[(&Transaction, Address)],
+    block_header: &BlockHeader,
+    snapshot: Arc<dyn Database>,
+    vm_type: VMType,
+) -> Vec<Option<SpeculativeExecution>> {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(transactions.len());
+    let next_tx = AtomicUsize::new(0);
+    let mut executions: Vec<Option<SpeculativeExecution>> = std::iter::repeat_with(|| None)
+        .take(transactions.len())
+        .collect();
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut executed = Vec::new();
+                    loop {
+                        let index = next_tx.fetch_add(1, Ordering::Relaxed);
+                        let Some((tx, tx_sender)) = transactions.get(index) else {
+                            break;
+                        };
+                        let execution = execute_tx_speculatively(
+                            tx,
+                            *tx_sender,
+                            block_header,
+                            snapshot.clone(),
+                            vm_type,
+                        );
+                        executed.push((index, execution.ok()));
+                    }
+                    executed
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            // A worker that panicked just loses its executions, which are redone serially.
+            let Ok(executed) = handle.join() else {
+                continue;
+            };
+            for (index, execution) in executed {
+                if let Some(slot) = executions.get_mut(index) {
+                    *slot = execution;
+                }
+            }
+        }
+    });
+
+    executions
+}
+
+/// Executes the transaction against `snapshot` on a database of its own, deferring the payment to the coinbase.
+fn execute_tx_speculatively(
+    tx: &Transaction,
+    tx_sender: Address,
+    block_header: &BlockHeader,
+    snapshot: Arc<dyn Database>,
+    vm_type: VMType,
+) -> Result<SpeculativeExecution, EvmError> {
+    let mut db = GeneralizedDatabase::new(snapshot);
+    let env = LEVM::setup_env(tx, tx_sender, block_header, &db, vm_type)?;
+    let (report, coinbase_fee) = {
+        let mut vm = VM::new(env, &mut db, tx, LevmCallTracer::disabled(), vm_type)?;
+        vm.deferred_coinbase_fee = Some(U256::zero());
+        let report = vm.execute()?;
+        (report, vm.deferred_coinbase_fee.unwrap_or_default())
+    };
+    Ok(SpeculativeExecution {
+        report,
+        db,
+        coinbase_fee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use ethrex_common::types::{Account, EIP1559Transaction, TxKind};
+
+    use super::*;
+
+    const SENDER: Address = Address::repeat_byte(0x10);
+    const OTHER_SENDER: Address = Address::repeat_byte(0x11);
+    const COUNTER: Address = Address::repeat_byte(0xc1);
+    const OTHER_COUNTER: Address = Address::repeat_byte(0xc2);
+    const COINBASE: Address = Address::repeat_byte(0xcb);
+    /// Paid to the coinbase per unit of gas.
+    const TIP: u64 = 1;
+
+    struct EmptyStore;
+
+    impl Database for EmptyStore {
+        fn get_account_state(&self, _address: Address) -> Result<AccountState, DatabaseError> {
+            Ok(AccountState::default())
+        }
+        fn get_storage_value(&self, _address: Address, _key: H256) -> Result<U256, DatabaseError> {
+            Ok(U256::zero())
+        }
+        fn get_block_hash(&self, _block_number: u64) -> Result<H256, DatabaseError> {
+            Ok(H256::zero())
+        }
+        fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+            Ok(ChainConfig::default())
+        }
+        fn get_account_code(&self, _code_hash: H256) -> Result<Code, DatabaseError> {
+            Ok(Code::default())
+        }
+    }
+
+    /// State with two funded senders and two contracts incrementing their slot 0 when called.
+    fn block_state() -> GeneralizedDatabase {
+        // SSTORE(0, SLOAD(0) + 1), STOP
+        let counter = || {
+            Account::new(
+                U256::zero(),
+                Code::from_bytecode(Bytes::from_static(&[
+                    0x60, 0x00, 0x54, 0x60, 0x01, 0x01, 0x60, 0x00, 0x55, 0x00,
+                ])),
+                1,
+                Default::default(),
+            )
+        };
+        let funded = || Account::new(U256::from(u64::MAX), Code::default(), 0, Default::default());
+        GeneralizedDatabase::new_with_account_state(
+            Arc::new(EmptyStore),
+            FxHashMap::from_iter([
+                (SENDER, funded()),
+                (OTHER_SENDER, funded()),
+                (COUNTER, counter()),
+                (OTHER_COUNTER, counter()),
+            ]),
+        )
+    }
+
+    fn header() -> BlockHeader {
+        BlockHeader {
+            coinbase: COINBASE,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1),
+            ..Default::default()
+        }
+    }
+
+    fn call(nonce: u64, to: Address, value: u64) -> Transaction {
+        Transaction::EIP1559Transaction(EIP1559Transaction {
+            nonce,
+            max_priority_fee_per_gas: TIP,
+            max_fee_per_gas: 1 + TIP,
+            gas_limit: 100_000,
+            to: TxKind::Call(to),
+            value: U256::from(value),
+            ..Default::default()
+        })
+    }
+
+    fn speculate(
+        db: &GeneralizedDatabase,
+        tx: &Transaction,
+        sender: Address,
+    ) -> Result<SpeculativeExecution, EvmError> {
+        let snapshot = Arc::new(StateSnapshot::new(db));
+        execute_tx_speculatively(tx, sender, &header(), snapshot, VMType::L1)
+    }
+
+    fn storage(db: &GeneralizedDatabase, address: Address) -> U256 {
+        db.current_accounts_state[&address].storage[&H256::zero()]
+    }
+
+    #[test]
+    fn transactions_touching_different_state_are_merged() {
+        let mut db = block_state();
+        let first = speculate(&db, &call(0, COUNTER, 0), SENDER).unwrap();
+        let second = speculate(&db, &call(0, OTHER_COUNTER, 0), OTHER_SENDER).unwrap();
+        let fees = U256::from((first.report.gas_used + second.report.gas_used) * TIP);
+
+        let mut written = FxHashSet::default();
+        assert!(first.is_valid(&written, COINBASE));
+        first.commit(&mut db, COINBASE, &mut written).unwrap();
+        assert!(written.contains(&StateKey::Account(SENDER)));
+        assert!(written.contains(&StateKey::Storage(COUNTER, H256::zero())));
+        // Reading the counter account without changing it isn't a write
+        assert!(!written.contains(&StateKey::Account(COUNTER)));
+
+        assert!(second.is_valid(&written, COINBASE));
+        second.commit(&mut db, COINBASE, &mut written).unwrap();
+
+        assert_eq!(storage(&db, COUNTER), U256::one());
+        assert_eq!(storage(&db, OTHER_COUNTER), U256::one());
+        assert_eq!(db.current_accounts_state[&SENDER].info.nonce, 1);
+        assert_eq!(db.current_accounts_state[&OTHER_SENDER].info.nonce, 1);
+        assert_eq!(db.current_accounts_state[&COINBASE].info.balance, fees);
+        // Merged slots keep their value before the block for computing the state transitions
+        assert_eq!(
+            db.initial_accounts_state[&COUNTER].storage[&H256::zero()],
+            U256::zero()
+        );
+    }
+
+    #[test]
+    fn reading_state_written_by_a_previous_transaction_is_a_conflict() {
+        let mut db = block_state();
+        let first = speculate(&db, &call(0, COUNTER, 0), SENDER).unwrap();
+        let second = speculate(&db, &call(0, COUNTER, 0), OTHER_SENDER).unwrap();
+
+        let mut written = FxHashSet::default();
+        first.commit(&mut db, COINBASE, &mut written).unwrap();
+        // It read slot 0 before the first transaction incremented it
+        assert!(!second.is_valid(&written, COINBASE));
+
+        let report = LEVM::execute_tx_tracking_writes(
+            &call(0, COUNTER, 0),
+            OTHER_SENDER,
+            &header(),
+            &mut db,
+            VMType::L1,
+            &mut written,
+        )
+        .unwrap();
+        assert!(report.is_success());
+        assert_eq!(storage(&db, COUNTER), U256::from(2));
+        assert!(written.contains(&StateKey::Account(OTHER_SENDER)));
+        assert!(db.tx_backup.is_none());
+    }
+
+    #[test]
+    fn transactions_of_the_same_sender_are_ordered_by_nonce() {
+        let mut db = block_state();
+        let first = speculate(&db, &call(0, COUNTER, 0), SENDER).unwrap();
+        // Its nonce is only valid once the first transaction is committed
+        assert!(speculate(&db, &call(1, OTHER_COUNTER, 0), SENDER).is_err());
+        // A speculative run with the nonce of the snapshot is stale as soon as the sender is written
+        let stale = speculate(&db, &call(0, OTHER_COUNTER, 0), SENDER).unwrap();
+
+        let mut written = FxHashSet::default();
+        first.commit(&mut db, COINBASE, &mut written).unwrap();
+        assert!(!stale.is_valid(&written, COINBASE));
+
+        LEVM::execute_tx_tracking_writes(
+            &call(1, OTHER_COUNTER, 0),
+            SENDER,
+            &header(),
+            &mut db,
+            VMType::L1,
+            &mut written,
+        )
+        .unwrap();
+        assert_eq!(db.current_accounts_state[&SENDER].info.nonce, 2);
+        assert_eq!(storage(&db, OTHER_COUNTER), U256::one());
+    }
+
+    #[test]
+    fn transactions_touching_the_coinbase_are_re_executed() {
+        let db = block_state();
+        let execution = speculate(&db, &call(0, COINBASE, 1), SENDER).unwrap();
+        assert!(!execution.is_valid(&FxHashSet::default(), COINBASE));
+    }
+}
@@ -1,8 +1,23 @@
-use ethrex_common::types::{Block, Transaction};
+use ethrex_common::tracing::{
+    AccountDiff, CallTraceFrame, Delta, HexBytes, InternalOperation, StateDiff,
+};
+use ethrex_common::types::{Block, GenericTransaction, Transaction};
+use ethrex_common::{H256, U256};
 use ethrex_common::{tracing::CallTrace, types::BlockHeader};
+use ethrex_levm::Environment;
+use ethrex_levm::account::LevmAccount;
+use ethrex_levm::hooks::Tracer;
 use ethrex_levm::vm::VMType;
 use ethrex_levm::{db::gen_db::GeneralizedDatabase, tracing::LevmCallTracer, vm::VM};
+use rustc_hash::FxHashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
 
+use super::{
+    adjust_disabled_base_fee, env_from_generic, internal_operations::InternalOperationsTracer,
+    vm_from_generic, vm_trace::VmTracer,
+};
+use crate::tracing::{ReplayTrace, ReplayTraceConfig};
 use crate::{EvmError, backends::levm::LEVM};
 
 impl LEVM {
@@ -74,4 +89,263 @@ impl LEVM {
         // We only return the top call because a transaction only has one call with subcalls
         Ok(vec![callframe])
     }
+
+    /// Run transaction with the given native tracer attached.
+    pub fn trace_tx_with_tracer(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &Transaction,
+        tracer: Rc<RefCell<dyn Tracer>>,
+        vm_type: VMType,
+    ) -> Result<(), EvmError> {
+        let env = Self::setup_env(
+            tx,
+            tx.sender().map_err(|error| {
+                EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+            })?,
+            block_header,
+            db,
+            vm_type,
+        )?;
+        let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type)?;
+        vm.tracers.push(tracer);
+
+        vm.execute()?;
+
+        Ok(())
+    }
+
+    /// Run transaction collecting its internal operations, as used by `ots_getInternalOperations`.
+    pub fn trace_tx_internal_operations(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &Transaction,
+        vm_type: VMType,
+    ) -> Result<Vec<InternalOperation>, EvmError> {
+        let tracer = Rc::new(RefCell::new(InternalOperationsTracer::default()));
+        Self::trace_tx_with_tracer(db, block_header, tx, tracer.clone(), vm_type)?;
+        let operations = tracer.borrow_mut().take_operations();
+        Ok(operations)
+    }
+
+    /// Run a transaction that isn't signed, like `eth_call`, with callTracer activated.
+    pub fn trace_generic_tx_calls(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &GenericTransaction,
+        only_top_call: bool,
+        with_log: bool,
+        vm_type: VMType,
+    ) -> Result<CallTrace, EvmError> {
+        let env = generic_env(db, block_header, tx)?;
+        let mut vm = vm_from_generic(
+            tx,
+            env,
+            db,
+            vm_type,
+            LevmCallTracer::new(only_top_call, with_log),
+        )?;
+
+        vm.execute()?;
+
+        Ok(vec![vm.get_trace_result()?])
+    }
+
+    /// Run a transaction that isn't signed, like `eth_call`, with the given native tracer attached.
+    pub fn trace_generic_tx_with_tracer(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &GenericTransaction,
+        tracer: Rc<RefCell<dyn Tracer>>,
+        vm_type: VMType,
+    ) -> Result<(), EvmError> {
+        let env = generic_env(db, block_header, tx)?;
+        let mut vm = vm_from_generic(tx, env, db, vm_type, LevmCallTracer::disabled())?;
+        vm.tracers.push(tracer);
+
+        vm.execute()?;
+
+        Ok(())
+    }
+
+    /// Run transaction with the call tracer and the traces enabled in `config`, as used by the
+    /// `trace_*` endpoints.
+    pub fn trace_tx_replay(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &Transaction,
+        config: ReplayTraceConfig,
+        vm_type: VMType,
+    ) -> Result<ReplayTrace, EvmError> {
+        let env = Self::setup_env(
+            tx,
+            tx.sender().map_err(|error| {
+                EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+            })?,
+            block_header,
+            db,
+            vm_type,
+        )?;
+        let vm_tracer = start_replay(db, config)?;
+
+        let mut vm = VM::new(env, db, tx, LevmCallTracer::new(false, false), vm_type)?;
+        let call_trace = run_replay(&mut vm, &vm_tracer)?;
+
+        finish_replay(db, call_trace, vm_tracer, config)
+    }
+
+    /// Same as [`Self::trace_tx_replay`] but for a transaction that isn't signed, like `eth_call`.
+    pub fn trace_generic_tx_replay(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &GenericTransaction,
+        config: ReplayTraceConfig,
+        vm_type: VMType,
+    ) -> Result<ReplayTrace, EvmError> {
+        let env = generic_env(db, block_header, tx)?;
+        let vm_tracer = start_replay(db, config)?;
+
+        let mut vm = vm_from_generic(tx, env, db, vm_type, LevmCallTracer::new(false, false))?;
+        let call_trace = run_replay(&mut vm, &vm_tracer)?;
+
+        finish_replay(db, call_trace, vm_tracer, config)
+    }
+}
+
+/// Environment to simulate an unsigned transaction in, the same way `eth_call` does.
+fn generic_env(
+    db: &GeneralizedDatabase,
+    block_header: &BlockHeader,
+    tx: &GenericTransaction,
+) -> Result<Environment, EvmError> {
+    let mut env = env_from_generic(tx, block_header, db)?;
+    env.block_gas_limit = i64::MAX as u64; // disable block gas limit
+    adjust_disabled_base_fee(&mut env);
+    Ok(env)
+}
+
+fn start_replay(
+    db: &mut GeneralizedDatabase,
+    config: ReplayTraceConfig,
+) -> Result<Option<Rc<RefCell<VmTracer>>>, EvmError> {
+    if config.state_diff {
+        // Sync the initial state with the changes of the previous transactions, so the state
+        // transitions only contain the ones of the traced transaction.
+        LEVM::get_state_transitions_tx(db)?;
+    }
+    Ok(config
+        .vm_trace
+        .then(|| Rc::new(RefCell::new(VmTracer::default()))))
+}
+
+fn run_replay(
+    vm: &mut VM<'_>,
+    vm_tracer: &Option<Rc<RefCell<VmTracer>>>,
+) -> Result<CallTraceFrame, EvmError> {
+    if let Some(vm_tracer) = vm_tracer {
+        vm.tracers.push(vm_tracer.clone());
+    }
+    vm.execute()?;
+    Ok(vm.get_trace_result()?)
+}
+
+fn finish_replay(
+    db: &mut GeneralizedDatabase,
+    call_trace: CallTraceFrame,
+    vm_tracer: Option<Rc<RefCell<VmTracer>>>,
+    config: ReplayTraceConfig,
+) -> Result<ReplayTrace, EvmError> {
+    let state_diff = if config.state_diff {
+        Some(state_diff(db)?)
+    } else {
+        None
+    };
+    let vm_trace = vm_tracer.and_then(|tracer| tracer.borrow_mut().take_trace());
+    Ok(ReplayTrace {
+        call_trace,
+        state_diff,
+        vm_trace,
+    })
+}
+
+/// Diffs the state after the last transaction against the state before it, built from the
+/// account updates of the transaction.
+fn state_diff(db: &mut GeneralizedDatabase) -> Result<StateDiff, EvmError> {
+    // The initial state is overwritten when getting the state transitions, so keep what we need.
+    let pre_state: FxHashMap<_, LevmAccount> = db
+        .current_accounts_state
+        .iter()
+        .filter(|(_, account)| !account.is_unmodified())
+        .filter_map(|(address, _)| {
+            let account = db.initial_accounts_state.get(address)?;
+            Some((*address, account.clone()))
+        })
+        .collect();
+    let account_updates = LEVM::get_state_transitions_tx(db)?;
+
+    let mut diff = StateDiff::new();
+    for update in account_updates {
+        let pre = pre_state.get(&update.address).cloned().unwrap_or_default();
+        let post = db
+            .current_accounts_state
+            .get(&update.address)
+            .cloned()
+            .unwrap_or_default();
+        let post_storage = update
+            .added_storage
+            .iter()
+            .map(|(key, value)| (*key, H256::from(value.to_big_endian())));
+
+        let account_diff = if update.removed {
+            AccountDiff {
+                balance: Delta::Died(pre.info.balance),
+                nonce: Delta::Died(U256::from(pre.info.nonce)),
+                code: Delta::Died(code(db, pre.info.code_hash)?),
+                storage: pre
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(key, value)| (*key, Delta::Died(H256::from(value.to_big_endian()))))
+                    .collect(),
+            }
+        } else if pre.is_empty() {
+            AccountDiff {
+                balance: Delta::Born(post.info.balance),
+                nonce: Delta::Born(U256::from(post.info.nonce)),
+                code: Delta::Born(code(db, post.info.code_hash)?),
+                storage: post_storage
+                    .map(|(key, value)| (key, Delta::Born(value)))
+                    .collect(),
+            }
+        } else {
+            AccountDiff {
+                balance: Delta::new(pre.info.balance, post.info.balance),
+                nonce: Delta::new(U256::from(pre.info.nonce), U256::from(post.info.nonce)),
+                code: if pre.info.code_hash == post.info.code_hash {
+                    Delta::Unchanged
+                } else {
+                    Delta::Changed {
+                        from: code(db, pre.info.code_hash)?,
+                        to: code(db, post.info.code_hash)?,
+                    }
+                },
+                storage: post_storage
+                    .map(|(key, value)| {
+                        let previous = match pre.storage.get(&key) {
+                            Some(previous) if !update.removed_storage => *previous,
+                            _ => U256::zero(),
+                        };
+                        (key, Delta::new(H256::from(previous.to_big_endian()), value))
+                    })
+                    .collect(),
+            }
+        };
+        diff.insert(update.address, account_diff);
+    }
+    Ok(diff)
+}
+
+fn code(db: &mut GeneralizedDatabase, code_hash: H256) -> Result<HexBytes, EvmError> {
+    let code = db.get_code(code_hash)?;
+    Ok(HexBytes(code.bytecode.clone()))
 }
@@ -0,0 +1,98 @@
+use bytes::Bytes;
+use ethrex_common::{
+    Address, U256,
+    tracing::CallType,
+    types::{TransactionAddresses, TxKind},
+};
+use ethrex_levm::hooks::Tracer;
+use serde_json::Value;
+
+/// Collects the addresses a transaction takes part in, as indexed by Erigon for Otterscan: the
+/// caller and callee of every call frame, which includes the contracts created and the
+/// beneficiaries of self destructs. Frames that later revert are kept.
+pub struct TransactionAddressesTracer {
+    addresses: TransactionAddresses,
+}
+
+impl TransactionAddressesTracer {
+    /// The sender and recipient are indexed even if the transaction fails before its first call
+    /// frame is entered.
+    pub fn new(sender: Address, to: TxKind) -> Self {
+        let mut addresses = TransactionAddresses {
+            sender,
+            addresses: [sender].into(),
+        };
+        if let TxKind::Call(to) = to {
+            addresses.addresses.insert(to);
+        }
+        Self { addresses }
+    }
+
+    pub fn take_addresses(&mut self) -> TransactionAddresses {
+        std::mem::take(&mut self.addresses)
+    }
+}
+
+impl Tracer for TransactionAddressesTracer {
+    fn on_call_enter(
+        &mut self,
+        _call_type: &CallType,
+        from: Address,
+        to: Address,
+        _value: U256,
+        _gas: u64,
+        _input: &Bytes,
+    ) {
+        self.addresses.addresses.extend([from, to]);
+    }
+
+    fn result(&mut self) -> Value {
+        serde_json::to_value(std::mem::take(&mut self.addresses.addresses)).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn enter(tracer: &mut impl Tracer, call_type: CallType, from: u64, to: u64) {
+        tracer.on_call_enter(
+            &call_type,
+            Address::from_low_u64_be(from),
+            Address::from_low_u64_be(to),
+            U256::zero(),
+            0,
+            &Bytes::new(),
+        );
+    }
+
+    #[test]
+    fn collects_the_addresses_of_every_call_frame() {
+        let sender = Address::from_low_u64_be(1);
+        let mut tracer =
+            TransactionAddressesTracer::new(sender, TxKind::Call(Address::from_low_u64_be(2)));
+        enter(&mut tracer, CallType::CALL, 1, 2);
+        enter(&mut tracer, CallType::STATICCALL, 2, 3);
+        tracer.on_call_exit(0, &Bytes::new(), None);
+        enter(&mut tracer, CallType::CREATE2, 2, 4);
+        enter(&mut tracer, CallType::SELFDESTRUCT, 4, 5);
+        tracer.on_call_exit(0, &Bytes::new(), Some("reverted"));
+        tracer.on_call_exit(0, &Bytes::new(), None);
+
+        let addresses = tracer.take_addresses();
+        assert_eq!(addresses.sender, sender);
+        assert_eq!(
+            addresses.addresses,
+            BTreeSet::from_iter((1..=5).map(Address::from_low_u64_be))
+        );
+    }
+
+    #[test]
+    fn indexes_the_transaction_without_call_frames() {
+        let sender = Address::from_low_u64_be(1);
+        let mut tracer = TransactionAddressesTracer::new(sender, TxKind::Create);
+        assert_eq!(tracer.take_addresses().addresses, BTreeSet::from([sender]));
+    }
+}
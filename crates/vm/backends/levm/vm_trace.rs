@@ -0,0 +1,245 @@
+use bytes::Bytes;
+use ethrex_common::{
+    Address, U256,
+    tracing::{CallType, MemoryDiff, StorageDiff, VmExecutedOperation, VmOperation, VmTrace},
+};
+use ethrex_levm::{call_frame::CallFrame, hooks::Tracer, opcodes::Opcode, vm::VM};
+use serde_json::Value;
+
+/// Builds Parity's `vmTrace` of a transaction.
+#[derive(Default)]
+pub struct VmTracer {
+    frames: Vec<VmTraceFrame>,
+    trace: Option<VmTrace>,
+}
+
+struct VmTraceFrame {
+    trace: VmTrace,
+    gas: u64,
+    /// Last operation, whose result is known at the next step of the frame or when it exits.
+    pending: Option<PendingOperation>,
+    /// SELFDESTRUCT is reported as a call frame, but doesn't run any code.
+    ignored: bool,
+}
+
+struct PendingOperation {
+    operation: VmOperation,
+    opcode: Opcode,
+    gas_before: u64,
+    pushed: usize,
+    /// Offset and size of the memory written by the operation
+    mem: Option<(usize, usize)>,
+    store: Option<StorageDiff>,
+}
+
+impl VmTracer {
+    /// Returns the trace of the transaction, if it got to be executed.
+    pub fn take_trace(&mut self) -> Option<VmTrace> {
+        self.trace.take()
+    }
+}
+
+impl Tracer for VmTracer {
+    fn on_call_enter(
+        &mut self,
+        call_type: &CallType,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+        gas: u64,
+        input: &Bytes,
+    ) {
+        let code = match call_type {
+            CallType::CREATE | CallType::CREATE2 => input.clone(),
+            // Set at the first step, once the code is loaded.
+            _ => Bytes::new(),
+        };
+        self.frames.push(VmTraceFrame {
+            trace: VmTrace {
+                code,
+                ops: Vec::new(),
+            },
+            gas,
+            pending: None,
+            ignored: matches!(call_type, CallType::SELFDESTRUCT),
+        });
+    }
+
+    fn on_call_exit(&mut self, gas_used: u64, _output: &Bytes, error: Option<&str>) {
+        let Some(mut frame) = self.frames.pop() else {
+            return;
+        };
+        if frame.ignored {
+            return;
+        }
+
+        if let Some(pending) = frame.pending.take() {
+            let gas_after = frame.gas.saturating_sub(gas_used);
+            let halted = error.is_some() && pending.opcode != Opcode::REVERT;
+            frame
+                .trace
+                .ops
+                .push(pending.finish(gas_after, None, halted));
+        }
+
+        match self.frames.last_mut() {
+            Some(parent) => {
+                if let Some(pending) = parent.pending.as_mut() {
+                    pending.operation.sub = Some(frame.trace);
+                }
+            }
+            None => self.trace = Some(frame.trace),
+        }
+    }
+
+    fn on_step(&mut self, vm: &VM<'_>) {
+        let Some(frame) = self.frames.last_mut() else {
+            return;
+        };
+        let call_frame = &vm.current_call_frame;
+        let gas = u64::try_from(call_frame.gas_remaining).unwrap_or_default();
+
+        if frame.trace.ops.is_empty() && frame.pending.is_none() && frame.trace.code.is_empty() {
+            frame.trace.code = call_frame.bytecode.bytecode.clone();
+        }
+        if let Some(pending) = frame.pending.take() {
+            frame
+                .trace
+                .ops
+                .push(pending.finish(gas, Some(call_frame), false));
+        }
+
+        let opcode = Opcode::from(call_frame.next_opcode());
+        frame.pending = Some(PendingOperation {
+            operation: VmOperation {
+                pc: call_frame.pc,
+                ..Default::default()
+            },
+            opcode,
+            gas_before: gas,
+            pushed: pushed_items(opcode),
+            mem: written_memory(opcode, call_frame),
+            store: (opcode == Opcode::SSTORE).then(|| StorageDiff {
+                key: stack_item(call_frame, 0),
+                val: stack_item(call_frame, 1),
+            }),
+        });
+    }
+
+    fn result(&mut self) -> Value {
+        serde_json::to_value(self.take_trace()).unwrap_or_default()
+    }
+}
+
+impl PendingOperation {
+    /// Completes the operation with the state after executing it, which is only available if the
+    /// frame keeps running.
+    fn finish(self, gas_after: u64, call_frame: Option<&CallFrame>, halted: bool) -> VmOperation {
+        let mut operation = self.operation;
+        operation.cost = self.gas_before.saturating_sub(gas_after);
+        if halted {
+            return operation;
+        }
+
+        let mut executed = VmExecutedOperation {
+            used: gas_after,
+            store: self.store,
+            ..Default::default()
+        };
+        if let Some(call_frame) = call_frame {
+            executed.push = (0..self.pushed)
+                .rev()
+                .map(|depth| stack_item(call_frame, depth))
+                .collect();
+            executed.mem = self.mem.map(|(off, size)| {
+                let memory = call_frame.memory.to_vec();
+                let data = memory
+                    .get(off..off.saturating_add(size))
+                    .map(Bytes::copy_from_slice)
+                    .unwrap_or_default();
+                MemoryDiff { off, data }
+            });
+        }
+        operation.ex = Some(executed);
+        operation
+    }
+}
+
+/// Amount of values pushed to the stack by the opcode, as reported by Parity: DUPn and SWAPn
+/// report every stack item they moved.
+fn pushed_items(opcode: Opcode) -> usize {
+    let byte = opcode as u8;
+    match opcode {
+        Opcode::STOP
+        | Opcode::POP
+        | Opcode::MSTORE
+        | Opcode::MSTORE8
+        | Opcode::SSTORE
+        | Opcode::TSTORE
+        | Opcode::JUMP
+        | Opcode::JUMPI
+        | Opcode::JUMPDEST
+        | Opcode::CALLDATACOPY
+        | Opcode::CODECOPY
+        | Opcode::EXTCODECOPY
+        | Opcode::RETURNDATACOPY
+        | Opcode::MCOPY
+        | Opcode::DATACOPY
+        | Opcode::LOG0
+        | Opcode::LOG1
+        | Opcode::LOG2
+        | Opcode::LOG3
+        | Opcode::LOG4
+        | Opcode::RJUMP
+        | Opcode::RJUMPI
+        | Opcode::RJUMPV
+        | Opcode::CALLF
+        | Opcode::RETF
+        | Opcode::JUMPF
+        | Opcode::RETURNCONTRACT
+        | Opcode::RETURN
+        | Opcode::REVERT
+        | Opcode::INVALID
+        | Opcode::SELFDESTRUCT => 0,
+        _ if (Opcode::DUP1 as u8..=Opcode::DUP16 as u8).contains(&byte) => {
+            usize::from(byte - Opcode::DUP1 as u8) + 2
+        }
+        _ if (Opcode::SWAP1 as u8..=Opcode::SWAP16 as u8).contains(&byte) => {
+            usize::from(byte - Opcode::SWAP1 as u8) + 2
+        }
+        _ => 1,
+    }
+}
+
+/// Offset and size of the memory the opcode writes to, read from the stack before executing it.
+fn written_memory(opcode: Opcode, call_frame: &CallFrame) -> Option<(usize, usize)> {
+    let (offset, size) = match opcode {
+        Opcode::MSTORE => (stack_item(call_frame, 0), U256::from(32)),
+        Opcode::MSTORE8 => (stack_item(call_frame, 0), U256::one()),
+        Opcode::CALLDATACOPY
+        | Opcode::CODECOPY
+        | Opcode::RETURNDATACOPY
+        | Opcode::MCOPY
+        | Opcode::DATACOPY => (stack_item(call_frame, 0), stack_item(call_frame, 2)),
+        Opcode::EXTCODECOPY => (stack_item(call_frame, 1), stack_item(call_frame, 3)),
+        Opcode::CALL | Opcode::CALLCODE => (stack_item(call_frame, 5), stack_item(call_frame, 6)),
+        Opcode::DELEGATECALL | Opcode::STATICCALL => {
+            (stack_item(call_frame, 4), stack_item(call_frame, 5))
+        }
+        _ => return None,
+    };
+    if size.is_zero() {
+        return None;
+    }
+    Some((usize::try_from(offset).ok()?, usize::try_from(size).ok()?))
+}
+
+/// Stack item at `depth` from the top, or zero if the stack isn't that deep.
+fn stack_item(call_frame: &CallFrame, depth: usize) -> U256 {
+    call_frame
+        .stack
+        .values
+        .get(call_frame.stack.offset.saturating_add(depth))
+        .copied()
+        .unwrap_or_default()
+}
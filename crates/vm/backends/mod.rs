@@ -4,10 +4,11 @@ use levm::LEVM;
 use crate::db::{DynVmDatabase, VmDatabase};
 use crate::errors::EvmError;
 use crate::execution_result::ExecutionResult;
+use ethrex_common::types::block_access_list::BlockAccessList;
 use ethrex_common::types::requests::Requests;
 use ethrex_common::types::{
-    AccessList, AccountUpdate, Block, BlockHeader, Fork, GenericTransaction, Receipt, Transaction,
-    Withdrawal,
+    AccessList, AccountUpdate, Block, BlockHeader, Fork, GenericTransaction, Receipt,
+    StateOverride, Transaction, TransactionAddresses, Withdrawal,
 };
 use ethrex_common::{Address, types::fee_config::FeeConfig};
 pub use ethrex_levm::call_frame::CallFrameBackup;
@@ -77,6 +78,8 @@ impl Evm {
         LEVM::execute_block(block, &mut self.db, self.vm_type)
     }
 
+    /// `transactions` are the ones of the block with their recovered senders, so callers that also
+    /// need them only recover them once.
     #[instrument(
         level = "trace",
         name = "Block execution",
@@ -86,10 +89,50 @@ impl Evm {
     pub fn execute_block_pipeline(
         &mut self,
         block: &Block,
+        transactions: &[(&Transaction, Address)],
         merkleizer: Sender<Vec<AccountUpdate>>,
         queue_length: &AtomicUsize,
     ) -> Result<BlockExecutionResult, EvmError> {
-        LEVM::execute_block_pipeline(block, &mut self.db, self.vm_type, merkleizer, queue_length)
+        LEVM::execute_block_pipeline(
+            block,
+            transactions,
+            &mut self.db,
+            self.vm_type,
+            merkleizer,
+            queue_length,
+        )
+    }
+
+    /// Like [Evm::execute_block], but executing the block's transactions optimistically in parallel.
+    /// Conflicting transactions are re-executed, so the result is the same as the serial one.
+    pub fn execute_block_parallel(
+        &mut self,
+        block: &Block,
+    ) -> Result<BlockExecutionResult, EvmError> {
+        LEVM::execute_block_parallel(block, &mut self.db, self.vm_type)
+    }
+
+    #[instrument(
+        level = "trace",
+        name = "Block execution",
+        skip_all,
+        fields(namespace = "block_execution")
+    )]
+    pub fn execute_block_pipeline_parallel(
+        &mut self,
+        block: &Block,
+        transactions: &[(&Transaction, Address)],
+        merkleizer: Sender<Vec<AccountUpdate>>,
+        queue_length: &AtomicUsize,
+    ) -> Result<BlockExecutionResult, EvmError> {
+        LEVM::execute_block_pipeline_parallel(
+            block,
+            transactions,
+            &mut self.db,
+            self.vm_type,
+            merkleizer,
+            queue_length,
+        )
     }
 
     /// Wraps [LEVM::execute_tx].
@@ -166,6 +209,11 @@ impl Evm {
         LEVM::simulate_tx_from_generic(tx, header, &mut self.db, self.vm_type)
     }
 
+    /// Replaces the state of the given accounts before simulating transactions.
+    pub fn apply_state_override(&mut self, state_override: &StateOverride) -> Result<(), EvmError> {
+        LEVM::apply_state_override(&mut self.db, state_override)
+    }
+
     pub fn create_access_list(
         &mut self,
         tx: &GenericTransaction,
@@ -205,4 +253,8 @@ impl Evm {
 pub struct BlockExecutionResult {
     pub receipts: Vec<Receipt>,
     pub requests: Vec<Requests>,
+    /// Only recorded for blocks that commit to their access list (EIP-7928).
+    pub block_access_list: Option<BlockAccessList>,
+    /// Only recorded when the addresses each transaction took part in are indexed.
+    pub transaction_addresses: Option<Vec<TransactionAddresses>>,
 }
@@ -2,8 +2,9 @@ use crate::EvmError;
 use dyn_clone::DynClone;
 use ethrex_common::{
     Address, H256, U256,
-    types::{AccountState, ChainConfig, Code},
+    types::{AccountState, ChainConfig, Code, CodeAnalysisCache},
 };
+use std::sync::Arc;
 
 pub trait VmDatabase: Send + Sync + DynClone {
     fn get_account_state(&self, address: Address) -> Result<Option<AccountState>, EvmError>;
@@ -11,6 +12,9 @@ pub trait VmDatabase: Send + Sync + DynClone {
     fn get_block_hash(&self, block_number: u64) -> Result<H256, EvmError>;
     fn get_chain_config(&self) -> Result<ChainConfig, EvmError>;
     fn get_account_code(&self, code_hash: H256) -> Result<Code, EvmError>;
+    fn code_analysis_cache(&self) -> Option<Arc<CodeAnalysisCache>> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(VmDatabase);
@@ -0,0 +1,844 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+use ethrex_common::{Address, H256, U256};
+use ethrex_levm::{
+    call_frame::CallFrame, errors::ContextResult, hooks::StepHook, opcodes::Opcode, vm::VM,
+};
+use serde::{Deserialize, Serialize};
+
+const HELP: &str = "Commands:
+  s, step                     Execute the next opcode, stepping into calls
+  n, next                     Execute the next opcode, stepping over calls
+  o, out                      Run until the current call frame returns
+  c, continue                 Run until a breakpoint is hit
+  b, break pc <pc> [address]  Break at a PC, optionally only in the code of an address
+  b, break opcode <opcode>    Break before executing an opcode
+  b, break slot <key> [address]
+                              Break before an SLOAD or SSTORE of a storage slot
+  breakpoints                 List the breakpoints
+  d, delete <id>              Delete a breakpoint
+  stack                       Print the stack, from top to bottom
+  memory [offset [size]]      Print the memory of the current call frame
+  storage [address]           Print the storage slots loaded so far, of the current contract by default
+  tstorage [address]          Print the transient storage, of the current contract by default
+  bt, backtrace               Print the call frames being executed
+  r, restart                  Run the transaction again from the start, stopping at the first breakpoint
+  q, quit                     Run the transaction to the end without stopping
+  h, help                     Print this message";
+
+/// How the debugger communicates with its user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DebuggerProtocol {
+    /// Human readable commands and output, e.g. `break pc 10`.
+    Repl,
+    /// One JSON object per line for both commands and output, e.g.
+    /// `{"command":"break","breakpoint":{"kind":"pc","pc":10}}`. Meant for editor integrations.
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Breakpoint {
+    Pc {
+        pc: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        address: Option<Address>,
+    },
+    Opcode {
+        opcode: String,
+    },
+    /// Hit before an SLOAD or SSTORE of the slot.
+    Slot {
+        key: U256,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        address: Option<Address>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+enum Command {
+    Step,
+    Next,
+    StepOut,
+    Continue,
+    Break {
+        breakpoint: Breakpoint,
+    },
+    Delete {
+        id: usize,
+    },
+    Breakpoints,
+    Stack,
+    Memory {
+        #[serde(default)]
+        offset: usize,
+        size: Option<usize>,
+    },
+    Storage {
+        address: Option<Address>,
+    },
+    TransientStorage {
+        address: Option<Address>,
+    },
+    Backtrace,
+    Restart,
+    Quit,
+    Help,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Output {
+    Paused {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        breakpoint: Option<usize>,
+        #[serde(flatten)]
+        location: Location,
+    },
+    Finished {
+        success: bool,
+        gas_used: u64,
+        output: String,
+    },
+    /// From top to bottom.
+    Stack {
+        stack: Vec<U256>,
+    },
+    Memory {
+        offset: usize,
+        data: String,
+    },
+    Storage {
+        address: Address,
+        slots: BTreeMap<H256, U256>,
+    },
+    TransientStorage {
+        address: Address,
+        slots: BTreeMap<U256, U256>,
+    },
+    Backtrace {
+        frames: Vec<Location>,
+    },
+    Breakpoints {
+        breakpoints: BTreeMap<usize, Breakpoint>,
+    },
+    BreakpointSet {
+        id: usize,
+        breakpoint: Breakpoint,
+    },
+    BreakpointDeleted {
+        id: usize,
+    },
+    Restarting,
+    Help {
+        message: &'static str,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct Location {
+    depth: usize,
+    address: Address,
+    code_address: Address,
+    pc: usize,
+    opcode: String,
+    gas_remaining: i64,
+}
+
+/// When to stop the execution again.
+#[derive(Debug, Clone, Copy)]
+enum RunMode {
+    Step,
+    /// Stop at the next opcode of a call frame with at most this depth.
+    StepOver(usize),
+    /// Stop at the next opcode of a call frame with less than this depth.
+    StepOut(usize),
+    /// Stop only at breakpoints.
+    Continue,
+    /// Don't stop anymore.
+    Detached,
+}
+
+/// Interactive debugger that stops the execution of a transaction at breakpoints or one opcode at a
+/// time, and lets its user inspect the state of the VM. Commands are read from stdin and output is
+/// written to stdout, following the given [`DebuggerProtocol`].
+pub struct Debugger {
+    protocol: DebuggerProtocol,
+    breakpoints: BTreeMap<usize, Breakpoint>,
+    next_breakpoint_id: usize,
+    mode: RunMode,
+    restart_requested: bool,
+}
+
+impl Debugger {
+    /// Creates a debugger that stops before executing the first opcode.
+    pub fn new(protocol: DebuggerProtocol) -> Self {
+        Self {
+            protocol,
+            breakpoints: BTreeMap::new(),
+            next_breakpoint_id: 1,
+            mode: RunMode::Step,
+            restart_requested: false,
+        }
+    }
+
+    /// Returns whether the user asked to run the transaction again. In that case, the next execution
+    /// stops at the first breakpoint it hits.
+    pub fn take_restart_request(&mut self) -> bool {
+        if !self.restart_requested {
+            return false;
+        }
+        self.restart_requested = false;
+        self.mode = RunMode::Continue;
+        true
+    }
+
+    fn hit_breakpoint(&self, call_frame: &CallFrame) -> Option<usize> {
+        let opcode = call_frame.next_opcode();
+        self.breakpoints
+            .iter()
+            .find(|(_, breakpoint)| match breakpoint {
+                Breakpoint::Pc { pc, address } => {
+                    call_frame.pc == *pc
+                        && address.is_none_or(|address| address == call_frame.code_address)
+                }
+                Breakpoint::Opcode { opcode: name } => name
+                    .parse::<Opcode>()
+                    .is_ok_and(|breakpoint_opcode| u8::from(breakpoint_opcode) == opcode),
+                Breakpoint::Slot { key, address } => {
+                    matches!(Opcode::from(opcode), Opcode::SLOAD | Opcode::SSTORE)
+                        && call_frame.stack.values.get(call_frame.stack.offset) == Some(key)
+                        && address.is_none_or(|address| address == call_frame.to)
+                }
+            })
+            .map(|(id, _)| *id)
+    }
+
+    /// Handles commands until one of them resumes the execution.
+    fn prompt(&mut self, vm: &VM<'_>) {
+        loop {
+            let Some(line) = self.read_line() else {
+                // Nothing else to read, let the execution finish
+                self.mode = RunMode::Detached;
+                return;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let command = match self.protocol {
+                DebuggerProtocol::Repl => parse_command(&line),
+                DebuggerProtocol::Json => {
+                    serde_json::from_str(&line).map_err(|e| format!("Invalid command: {e}"))
+                }
+            };
+            let command = match command {
+                Ok(command) => command,
+                Err(message) => {
+                    self.emit(&Output::Error { message });
+                    continue;
+                }
+            };
+
+            let depth = vm.current_call_frame.depth;
+            let output = match command {
+                Command::Step => {
+                    self.mode = RunMode::Step;
+                    return;
+                }
+                Command::Next => {
+                    self.mode = RunMode::StepOver(depth);
+                    return;
+                }
+                Command::StepOut => {
+                    self.mode = RunMode::StepOut(depth);
+                    return;
+                }
+                Command::Continue => {
+                    self.mode = RunMode::Continue;
+                    return;
+                }
+                Command::Quit => {
+                    self.mode = RunMode::Detached;
+                    return;
+                }
+                Command::Restart => {
+                    self.emit(&Output::Restarting);
+                    self.restart_requested = true;
+                    self.mode = RunMode::Detached;
+                    return;
+                }
+                Command::Break { breakpoint } => self.set_breakpoint(breakpoint),
+                Command::Delete { id } => match self.breakpoints.remove(&id) {
+                    Some(_) => Output::BreakpointDeleted { id },
+                    None => Output::Error {
+                        message: format!("No breakpoint with id {id}"),
+                    },
+                },
+                Command::Breakpoints => Output::Breakpoints {
+                    breakpoints: self.breakpoints.clone(),
+                },
+                Command::Stack => {
+                    let stack = &vm.current_call_frame.stack;
+                    Output::Stack {
+                        stack: stack
+                            .values
+                            .get(stack.offset..)
+                            .unwrap_or_default()
+                            .to_vec(),
+                    }
+                }
+                Command::Memory { offset, size } => {
+                    let memory = vm.current_call_frame.memory.to_vec();
+                    let end = size
+                        .map_or(memory.len(), |size| offset.saturating_add(size))
+                        .min(memory.len());
+                    Output::Memory {
+                        offset,
+                        data: format!(
+                            "0x{}",
+                            hex::encode(memory.get(offset..end).unwrap_or_default())
+                        ),
+                    }
+                }
+                Command::Storage { address } => {
+                    let address = address.unwrap_or(vm.current_call_frame.to);
+                    let slots = vm
+                        .db
+                        .current_accounts_state
+                        .get(&address)
+                        .map(|account| account.storage.iter().map(|(k, v)| (*k, *v)).collect())
+                        .unwrap_or_default();
+                    Output::Storage { address, slots }
+                }
+                Command::TransientStorage { address } => {
+                    let address = address.unwrap_or(vm.current_call_frame.to);
+                    Output::TransientStorage {
+                        address,
+                        slots: vm.substate.get_transient_storage_of(&address),
+                    }
+                }
+                Command::Backtrace => Output::Backtrace {
+                    frames: vm
+                        .call_frames
+                        .iter()
+                        .chain(std::iter::once(&vm.current_call_frame))
+                        .rev()
+                        .map(location)
+                        .collect(),
+                },
+                Command::Help => Output::Help { message: HELP },
+            };
+            self.emit(&output);
+        }
+    }
+
+    fn set_breakpoint(&mut self, mut breakpoint: Breakpoint) -> Output {
+        if let Breakpoint::Opcode { opcode } = &mut breakpoint {
+            *opcode = opcode.to_uppercase();
+            if opcode.parse::<Opcode>().is_err() {
+                return Output::Error {
+                    message: format!("Unknown opcode {opcode}"),
+                };
+            }
+        }
+        let id = self.next_breakpoint_id;
+        self.next_breakpoint_id += 1;
+        self.breakpoints.insert(id, breakpoint.clone());
+        Output::BreakpointSet { id, breakpoint }
+    }
+
+    fn read_line(&self) -> Option<String> {
+        if self.protocol == DebuggerProtocol::Repl {
+            print!("(levm) ");
+            let _ = io::stdout().flush();
+        }
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line),
+        }
+    }
+
+    fn emit(&self, output: &Output) {
+        match self.protocol {
+            DebuggerProtocol::Repl => println!("{output}"),
+            DebuggerProtocol::Json => match serde_json::to_string(output) {
+                Ok(line) => println!("{line}"),
+                Err(e) => println!(r#"{{"event":"error","message":"{e}"}}"#),
+            },
+        }
+    }
+}
+
+impl StepHook for Debugger {
+    fn on_step(&mut self, vm: &VM<'_>) {
+        let call_frame = &vm.current_call_frame;
+        let should_stop = match self.mode {
+            RunMode::Detached => return,
+            RunMode::Step => true,
+            RunMode::StepOver(depth) => call_frame.depth <= depth,
+            RunMode::StepOut(depth) => call_frame.depth < depth,
+            RunMode::Continue => false,
+        };
+        let breakpoint = self.hit_breakpoint(call_frame);
+        if !should_stop && breakpoint.is_none() {
+            return;
+        }
+
+        self.emit(&Output::Paused {
+            breakpoint,
+            location: location(call_frame),
+        });
+        self.prompt(vm);
+    }
+
+    fn on_call_frame_exit(&mut self, vm: &VM<'_>, result: &ContextResult) {
+        if matches!(self.mode, RunMode::Detached) || !vm.call_frames.is_empty() {
+            return;
+        }
+        // Stop once more at the end of the transaction, to allow inspecting its final state
+        self.emit(&Output::Finished {
+            success: result.is_success(),
+            gas_used: result.gas_used,
+            output: format!("0x{}", hex::encode(&result.output)),
+        });
+        self.prompt(vm);
+    }
+}
+
+fn location(call_frame: &CallFrame) -> Location {
+    Location {
+        depth: call_frame.depth,
+        address: call_frame.to,
+        code_address: call_frame.code_address,
+        pc: call_frame.pc,
+        opcode: format!("{:?}", Opcode::from(call_frame.next_opcode())),
+        gas_remaining: call_frame.gas_remaining,
+    }
+}
+
+fn parse_command(line: &str) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let mut next_arg = |arg: &str| {
+        words
+            .next()
+            .ok_or_else(|| format!("Missing {arg}, see `help`"))
+    };
+
+    let command = match name {
+        "s" | "step" => Command::Step,
+        "n" | "next" => Command::Next,
+        "o" | "out" => Command::StepOut,
+        "c" | "continue" => Command::Continue,
+        "b" | "break" => {
+            let breakpoint = match next_arg("breakpoint kind")? {
+                "pc" => Breakpoint::Pc {
+                    pc: parse_number(next_arg("pc")?)?,
+                    address: next_arg("address").ok().map(parse_address).transpose()?,
+                },
+                "opcode" => Breakpoint::Opcode {
+                    opcode: next_arg("opcode")?.to_string(),
+                },
+                "slot" => Breakpoint::Slot {
+                    key: parse_u256(next_arg("slot")?)?,
+                    address: next_arg("address").ok().map(parse_address).transpose()?,
+                },
+                kind => return Err(format!("Unknown breakpoint kind {kind}, see `help`")),
+            };
+            Command::Break { breakpoint }
+        }
+        "breakpoints" => Command::Breakpoints,
+        "d" | "delete" => Command::Delete {
+            id: parse_number(next_arg("breakpoint id")?)?,
+        },
+        "stack" => Command::Stack,
+        "memory" => Command::Memory {
+            offset: next_arg("offset")
+                .ok()
+                .map(parse_number)
+                .transpose()?
+                .unwrap_or_default(),
+            size: next_arg("size").ok().map(parse_number).transpose()?,
+        },
+        "storage" => Command::Storage {
+            address: next_arg("address").ok().map(parse_address).transpose()?,
+        },
+        "tstorage" => Command::TransientStorage {
+            address: next_arg("address").ok().map(parse_address).transpose()?,
+        },
+        "bt" | "backtrace" => Command::Backtrace,
+        "r" | "restart" => Command::Restart,
+        "q" | "quit" => Command::Quit,
+        "h" | "help" => Command::Help,
+        name => return Err(format!("Unknown command {name}, see `help`")),
+    };
+    Ok(command)
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal number.
+fn parse_number(value: &str) -> Result<usize, String> {
+    match value.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .map_err(|_| format!("Invalid number {value}"))
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal word.
+fn parse_u256(value: &str) -> Result<U256, String> {
+    match value.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(value).ok(),
+    }
+    .ok_or_else(|| format!("Invalid word {value}"))
+}
+
+fn parse_address(value: &str) -> Result<Address, String> {
+    Address::from_str(value.trim_start_matches("0x"))
+        .map_err(|_| format!("Invalid address {value}"))
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Output::Paused {
+                breakpoint,
+                location,
+            } => {
+                if let Some(id) = breakpoint {
+                    write!(f, "Breakpoint {id} hit at ")?;
+                }
+                write!(f, "{location}")
+            }
+            Output::Finished {
+                success,
+                gas_used,
+                output,
+            } => {
+                let status = if *success { "succeeded" } else { "reverted" };
+                write!(
+                    f,
+                    "Transaction {status} using {gas_used} gas, output: {output}"
+                )
+            }
+            Output::Stack { stack } => {
+                if stack.is_empty() {
+                    return write!(f, "Empty stack");
+                }
+                for (index, value) in stack.iter().enumerate() {
+                    writeln!(f, "{index:>4}: {value:#x}")?;
+                }
+                Ok(())
+            }
+            Output::Memory { offset, data } => {
+                let data = hex::decode(data.trim_start_matches("0x")).unwrap_or_default();
+                if data.is_empty() {
+                    return write!(f, "Empty memory");
+                }
+                for (index, chunk) in data.chunks(32).enumerate() {
+                    writeln!(f, "{:#06x}: {}", offset + index * 32, hex::encode(chunk))?;
+                }
+                Ok(())
+            }
+            Output::Storage { address, slots } => {
+                writeln!(f, "Storage of {address:#x}:")?;
+                for (key, value) in slots {
+                    writeln!(f, "  {key:#x}: {value:#x}")?;
+                }
+                Ok(())
+            }
+            Output::TransientStorage { address, slots } => {
+                writeln!(f, "Transient storage of {address:#x}:")?;
+                for (key, value) in slots {
+                    writeln!(f, "  {key:#x}: {value:#x}")?;
+                }
+                Ok(())
+            }
+            Output::Backtrace { frames } => {
+                for frame in frames {
+                    writeln!(f, "{frame}")?;
+                }
+                Ok(())
+            }
+            Output::Breakpoints { breakpoints } => {
+                if breakpoints.is_empty() {
+                    return write!(f, "No breakpoints");
+                }
+                for (id, breakpoint) in breakpoints {
+                    writeln!(f, "{id}: {breakpoint}")?;
+                }
+                Ok(())
+            }
+            Output::BreakpointSet { id, breakpoint } => {
+                write!(f, "Breakpoint {id} set on {breakpoint}")
+            }
+            Output::BreakpointDeleted { id } => write!(f, "Breakpoint {id} deleted"),
+            Output::Restarting => write!(f, "Restarting the transaction"),
+            Output::Help { message } => write!(f, "{message}"),
+            Output::Error { message } => write!(f, "Error: {message}"),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[depth {}] {:#x} pc {} ({:#x}): {} (gas remaining: {})",
+            self.depth, self.code_address, self.pc, self.pc, self.opcode, self.gas_remaining
+        )
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Breakpoint::Pc { pc, address } => {
+                write!(f, "pc {pc}")?;
+                if let Some(address) = address {
+                    write!(f, " of {address:#x}")?;
+                }
+                Ok(())
+            }
+            Breakpoint::Opcode { opcode } => write!(f, "opcode {opcode}"),
+            Breakpoint::Slot { key, address } => {
+                write!(f, "slot {key:#x}")?;
+                if let Some(address) = address {
+                    write!(f, " of {address:#x}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use ethrex_common::types::Code;
+    use ethrex_levm::{call_frame::Stack, memory::Memory};
+
+    use super::*;
+
+    const CONTRACT: Address = Address::repeat_byte(0xcc);
+
+    /// A call frame of [`CONTRACT`] about to execute the opcode at `pc` with `stack_top` on the stack.
+    fn call_frame(bytecode: &'static [u8], pc: usize, stack_top: U256) -> CallFrame {
+        let mut stack = Stack::default();
+        stack.push(stack_top).unwrap();
+        let mut call_frame = CallFrame::new(
+            Address::zero(),
+            CONTRACT,
+            CONTRACT,
+            Code::from_bytecode(Bytes::from_static(bytecode)),
+            U256::zero(),
+            Bytes::new(),
+            false,
+            100_000,
+            0,
+            false,
+            false,
+            0,
+            0,
+            stack,
+            Memory::default(),
+        );
+        call_frame.pc = pc;
+        call_frame
+    }
+
+    #[test]
+    fn commands_are_parsed_with_their_aliases() {
+        assert!(matches!(parse_command("s"), Ok(Command::Step)));
+        assert!(matches!(parse_command("next"), Ok(Command::Next)));
+        assert!(matches!(parse_command("o"), Ok(Command::StepOut)));
+        assert!(matches!(
+            parse_command("  continue  \n"),
+            Ok(Command::Continue)
+        ));
+        assert!(matches!(
+            parse_command("d 0x2"),
+            Ok(Command::Delete { id: 2 })
+        ));
+        assert!(matches!(parse_command("bt"), Ok(Command::Backtrace)));
+        assert!(matches!(parse_command("r"), Ok(Command::Restart)));
+        assert!(matches!(parse_command("quit"), Ok(Command::Quit)));
+    }
+
+    #[test]
+    fn breakpoints_are_parsed_with_an_optional_address() {
+        assert!(matches!(
+            parse_command("b pc 0x10"),
+            Ok(Command::Break {
+                breakpoint: Breakpoint::Pc {
+                    pc: 16,
+                    address: None
+                }
+            })
+        ));
+        assert!(matches!(
+            parse_command(&format!("break pc 10 {CONTRACT:#x}")),
+            Ok(Command::Break {
+                breakpoint: Breakpoint::Pc {
+                    pc: 10,
+                    address: Some(CONTRACT)
+                }
+            })
+        ));
+        assert!(matches!(
+            parse_command("b opcode sstore"),
+            Ok(Command::Break {
+                breakpoint: Breakpoint::Opcode { opcode }
+            }) if opcode == "sstore"
+        ));
+        assert!(matches!(
+            parse_command("b slot 0x2a"),
+            Ok(Command::Break {
+                breakpoint: Breakpoint::Slot { key, address: None }
+            }) if key == U256::from(42)
+        ));
+        assert!(matches!(
+            parse_command(&format!("b slot 42 {CONTRACT:#x}")),
+            Ok(Command::Break {
+                breakpoint: Breakpoint::Slot {
+                    key,
+                    address: Some(CONTRACT)
+                }
+            }) if key == U256::from(42)
+        ));
+    }
+
+    #[test]
+    fn memory_arguments_default_to_the_whole_memory() {
+        assert!(matches!(
+            parse_command("memory"),
+            Ok(Command::Memory {
+                offset: 0,
+                size: None
+            })
+        ));
+        assert!(matches!(
+            parse_command("memory 0x20"),
+            Ok(Command::Memory {
+                offset: 32,
+                size: None
+            })
+        ));
+        assert!(matches!(
+            parse_command("memory 0 64"),
+            Ok(Command::Memory {
+                offset: 0,
+                size: Some(64)
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_commands_are_rejected() {
+        let error = |line| parse_command(line).unwrap_err();
+        assert_eq!(error("jump 10"), "Unknown command jump, see `help`");
+        assert_eq!(
+            error("b line 3"),
+            "Unknown breakpoint kind line, see `help`"
+        );
+        assert_eq!(error("b"), "Missing breakpoint kind, see `help`");
+        assert_eq!(error("b pc"), "Missing pc, see `help`");
+        assert_eq!(error("b pc 0xzz"), "Invalid number 0xzz");
+        assert_eq!(error("b pc 1 0x12"), "Invalid address 0x12");
+        assert_eq!(error("b slot -1"), "Invalid word -1");
+        assert_eq!(error("delete"), "Missing breakpoint id, see `help`");
+    }
+
+    #[test]
+    fn json_commands_are_deserialized() {
+        let parse = |line| serde_json::from_str::<Command>(line);
+        assert!(matches!(
+            parse(r#"{"command":"break","breakpoint":{"kind":"pc","pc":10}}"#),
+            Ok(Command::Break {
+                breakpoint: Breakpoint::Pc {
+                    pc: 10,
+                    address: None
+                }
+            })
+        ));
+        assert!(matches!(
+            parse(&format!(
+                r#"{{"command":"break","breakpoint":{{"kind":"slot","key":"0x2a","address":"{CONTRACT:#x}"}}}}"#
+            )),
+            Ok(Command::Break {
+                breakpoint: Breakpoint::Slot {
+                    key,
+                    address: Some(CONTRACT)
+                }
+            }) if key == U256::from(42)
+        ));
+        assert!(matches!(
+            parse(r#"{"command":"step_out"}"#),
+            Ok(Command::StepOut)
+        ));
+        assert!(matches!(
+            parse(r#"{"command":"memory","size":32}"#),
+            Ok(Command::Memory {
+                offset: 0,
+                size: Some(32)
+            })
+        ));
+        assert!(matches!(
+            parse(r#"{"command":"transient_storage"}"#),
+            Ok(Command::TransientStorage { address: None })
+        ));
+        assert!(parse(r#"{"command":"delete"}"#).is_err());
+        assert!(parse(r#"{"command":"jump"}"#).is_err());
+    }
+
+    #[test]
+    fn breakpoints_are_hit_at_their_pc_opcode_or_slot() {
+        // PUSH1 0x2a, SLOAD, STOP
+        let bytecode = &[0x60, 0x2a, 0x54, 0x00];
+        let sload = call_frame(bytecode, 2, U256::from(0x2a));
+        let other_slot = call_frame(bytecode, 2, U256::from(0x2b));
+
+        let mut debugger = Debugger::new(DebuggerProtocol::Repl);
+        assert_eq!(debugger.hit_breakpoint(&sload), None);
+
+        debugger.set_breakpoint(Breakpoint::Pc {
+            pc: 2,
+            address: Some(Address::zero()),
+        });
+        debugger.set_breakpoint(Breakpoint::Slot {
+            key: U256::from(0x2a),
+            address: Some(CONTRACT),
+        });
+        assert_eq!(debugger.hit_breakpoint(&sload), Some(2));
+        assert_eq!(debugger.hit_breakpoint(&other_slot), None);
+
+        // Opcode names are case insensitive
+        debugger.set_breakpoint(Breakpoint::Opcode {
+            opcode: "sload".to_string(),
+        });
+        assert_eq!(debugger.hit_breakpoint(&other_slot), Some(3));
+        assert_eq!(
+            debugger.hit_breakpoint(&call_frame(bytecode, 3, U256::zero())),
+            None
+        );
+
+        debugger.set_breakpoint(Breakpoint::Pc {
+            pc: 3,
+            address: None,
+        });
+        assert_eq!(
+            debugger.hit_breakpoint(&call_frame(bytecode, 3, U256::zero())),
+            Some(4)
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, H256, U256,
+    constants::EMPTY_KECCACK_HASH,
+    types::{AccountState, BlockHeader, ChainConfig, Code},
+};
+use ethrex_levm::{db::Database, errors::DatabaseError};
+use ethrex_rpc::{
+    EthClient,
+    clients::eth::RpcResponse,
+    types::block_identifier::{BlockIdentifier, BlockTag},
+    utils::RpcRequest,
+};
+use ethrex_storage::{EngineType, Store};
+use ethrex_vm::DynVmDatabase;
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::runtime::Runtime;
+use url::Url;
+
+/// Opens the ethrex database in `datadir` to execute in the given block, or the latest one.
+/// The state is the one the block starts from, i.e. the state after its parent, and it's read
+/// lazily as the transaction accesses it. Returns the database together with the block header.
+pub fn datadir_database(
+    datadir: &Path,
+    block_number: Option<u64>,
+) -> Result<(Arc<dyn Database>, BlockHeader), String> {
+    let runtime = new_runtime()?;
+    let store = Store::new(datadir, EngineType::RocksDB)
+        .map_err(|e| format!("Failed to open the database in {}: {e}", datadir.display()))?;
+    runtime
+        .block_on(store.load_initial_state())
+        .map_err(|e| format!("Failed to load the database: {e}"))?;
+
+    let block_number = match block_number {
+        Some(block_number) => block_number,
+        None => runtime
+            .block_on(store.get_latest_block_number())
+            .map_err(|e| format!("Failed to get the latest block number: {e}"))?,
+    };
+    let header = store
+        .get_block_header(block_number)
+        .map_err(|e| format!("Failed to get block {block_number}: {e}"))?
+        .ok_or_else(|| format!("Block {block_number} not found in the database"))?;
+    let parent_number = parent_block_number(&header)?;
+    let parent_header = store
+        .get_block_header_by_hash(header.parent_hash)
+        .map_err(|e| format!("Failed to get block {parent_number}: {e}"))?
+        .ok_or_else(|| format!("Block {parent_number} not found in the database"))?;
+    let vm_db: DynVmDatabase = Box::new(
+        StoreVmDatabase::new(store, parent_header)
+            .map_err(|e| format!("Failed to read the state of block {parent_number}: {e}"))?,
+    );
+
+    Ok((Arc::new(vm_db), header))
+}
+
+/// Reads the state a given block starts from, i.e. the state after its parent, through the
+/// JSON-RPC API of a node, e.g. a local ethrex node.
+///
+/// Accounts are fetched with `eth_getProof` and their code with `eth_getCode`, so the node must be
+/// able to serve them for the block. Everything fetched is cached.
+pub struct RpcDatabase {
+    client: EthClient,
+    runtime: Runtime,
+    block: BlockIdentifier,
+    chain_id: u64,
+    codes: Mutex<FxHashMap<H256, Code>>,
+    block_hashes: Mutex<FxHashMap<u64, H256>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcAccount {
+    balance: U256,
+    code_hash: H256,
+    #[serde(with = "ethrex_common::serde_utils::u64::hex_str")]
+    nonce: u64,
+    storage_hash: H256,
+}
+
+impl RpcDatabase {
+    /// Connects to the node at `url` to execute in the given block, or the latest one.
+    /// Returns the database, which reads the state after the parent block, together with the
+    /// header of the block.
+    pub fn new(url: Url, block_number: Option<u64>) -> Result<(Self, BlockHeader), String> {
+        let runtime = new_runtime()?;
+        let client = EthClient::new(url.clone())
+            .map_err(|e| format!("Failed to create the RPC client for {url}: {e}"))?;
+        let block = match block_number {
+            Some(block_number) => BlockIdentifier::Number(block_number),
+            None => BlockIdentifier::Tag(BlockTag::Latest),
+        };
+        let header = runtime
+            .block_on(client.get_block_by_number(block.clone(), false))
+            .map_err(|e| format!("Failed to get block {block}: {e}"))?
+            .header;
+        let chain_id = runtime
+            .block_on(client.get_chain_id())
+            .map_err(|e| format!("Failed to get the chain id: {e}"))?
+            .as_u64();
+
+        let db = Self {
+            client,
+            runtime,
+            // Pin the parent by number, as the latest one changes while the node is running
+            block: BlockIdentifier::Number(parent_block_number(&header)?),
+            chain_id,
+            codes: Mutex::new(FxHashMap::default()),
+            block_hashes: Mutex::new(FxHashMap::default()),
+        };
+        Ok((db, header))
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        params: Vec<serde_json::Value>,
+    ) -> Result<serde_json::Value, DatabaseError> {
+        let request = RpcRequest::new(method, Some(params));
+        match self.runtime.block_on(self.client.send_request(request)) {
+            Ok(RpcResponse::Success(response)) => Ok(response.result),
+            Ok(RpcResponse::Error(response)) => Err(DatabaseError::Custom(format!(
+                "{method} failed: {}",
+                response.error.message
+            ))),
+            Err(e) => Err(DatabaseError::Custom(format!("{method} failed: {e}"))),
+        }
+    }
+}
+
+impl Database for RpcDatabase {
+    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+        let account = self.request(
+            "eth_getProof",
+            vec![json!(address), json!([]), self.block.clone().into()],
+        )?;
+        let account: RpcAccount = serde_json::from_value(account)
+            .map_err(|e| DatabaseError::Custom(format!("Invalid account of {address:#x}: {e}")))?;
+
+        // Code is fetched by address but requested by hash, so we get it along with the account
+        if account.code_hash != *EMPTY_KECCACK_HASH
+            && !self
+                .codes
+                .lock()
+                .map_err(|_| DatabaseError::Custom("LockError".to_string()))?
+                .contains_key(&account.code_hash)
+        {
+            let code = self
+                .runtime
+                .block_on(self.client.get_code(address, self.block.clone()))
+                .map_err(|e| DatabaseError::Custom(format!("eth_getCode failed: {e}")))?;
+            self.codes
+                .lock()
+                .map_err(|_| DatabaseError::Custom("LockError".to_string()))?
+                .insert(account.code_hash, Code::from_bytecode(code));
+        }
+
+        Ok(AccountState {
+            nonce: account.nonce,
+            balance: account.balance,
+            storage_root: account.storage_hash,
+            code_hash: account.code_hash,
+        })
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        self.runtime
+            .block_on(
+                self.client
+                    .get_storage_at(address, key.into_uint(), self.block.clone()),
+            )
+            .map_err(|e| DatabaseError::Custom(format!("eth_getStorageAt failed: {e}")))
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        let mut block_hashes = self
+            .block_hashes
+            .lock()
+            .map_err(|_| DatabaseError::Custom("LockError".to_string()))?;
+        if let Some(hash) = block_hashes.get(&block_number) {
+            return Ok(*hash);
+        }
+        let hash = self
+            .runtime
+            .block_on(
+                self.client
+                    .get_block_by_number(BlockIdentifier::Number(block_number), false),
+            )
+            .map_err(|e| DatabaseError::Custom(format!("eth_getBlockByNumber failed: {e}")))?
+            .hash;
+        block_hashes.insert(block_number, hash);
+        Ok(hash)
+    }
+
+    /// Only the chain id is known, the fork is set by the runner input.
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        Ok(ChainConfig {
+            chain_id: self.chain_id,
+            ..Default::default()
+        })
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+        if code_hash == *EMPTY_KECCACK_HASH {
+            return Ok(Code::default());
+        }
+        self.codes
+            .lock()
+            .map_err(|_| DatabaseError::Custom("LockError".to_string()))?
+            .get(&code_hash)
+            .cloned()
+            .ok_or_else(|| {
+                DatabaseError::Custom(format!("Code with hash {code_hash:#x} not fetched"))
+            })
+    }
+}
+
+/// Transactions in the genesis block can't be executed, as there's no state before it.
+fn parent_block_number(header: &BlockHeader) -> Result<u64, String> {
+    header
+        .number
+        .checked_sub(1)
+        .ok_or_else(|| "Can't execute in the genesis block, there's no state before it".to_string())
+}
+
+fn new_runtime() -> Result<Runtime, String> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Failed to start the async runtime: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use bytes::Bytes;
+    use ethrex_common::{types::BlockBody, utils::keccak};
+    use ethrex_rpc::types::block::RpcBlock;
+    use serde_json::Value;
+
+    use super::*;
+
+    const LATEST_BLOCK: u64 = 10;
+    const CONTRACT: Address = Address::repeat_byte(0xcc);
+
+    fn header(number: u64) -> BlockHeader {
+        BlockHeader {
+            number,
+            timestamp: 12 * number,
+            parent_hash: H256::from_low_u64_be(number.saturating_sub(1)),
+            ..Default::default()
+        }
+    }
+
+    fn code() -> Bytes {
+        Bytes::from_static(&[0x60, 0x00, 0x56])
+    }
+
+    /// Answers like a node whose state changes on every block: balances and storage values are
+    /// the number of the block they're read at, which tells which state the database reads.
+    fn stub_response(method: &str, params: &[Value], code_requests: &AtomicUsize) -> Value {
+        let block = |param: &Value| match param.as_str() {
+            Some("latest") => LATEST_BLOCK,
+            Some(number) => u64::from_str_radix(number.trim_start_matches("0x"), 16).unwrap(),
+            None => panic!("missing block parameter"),
+        };
+        match method {
+            "eth_chainId" => json!("0x7"),
+            "eth_getBlockByNumber" => {
+                let header = header(block(&params[0]));
+                let hash = header.hash();
+                json!(RpcBlock::build(header, BlockBody::default(), hash, false).unwrap())
+            }
+            "eth_getProof" => json!({
+                "address": params[0],
+                "balance": format!("{:#x}", block(&params[2])),
+                "codeHash": keccak(code()),
+                "nonce": "0x1",
+                "storageHash": H256::zero(),
+                "accountProof": [],
+                "storageProof": [],
+            }),
+            "eth_getCode" => {
+                code_requests.fetch_add(1, Ordering::Relaxed);
+                json!(format!("0x{}", hex::encode(code())))
+            }
+            "eth_getStorageAt" => json!(format!("{:#x}", block(&params[2]))),
+            other => panic!("unexpected method {other}"),
+        }
+    }
+
+    /// Serves the JSON-RPC API of a stub node on a local port, one request per connection.
+    fn stub_node() -> (Url, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let code_requests = Arc::new(AtomicUsize::new(0));
+        let requests = code_requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(&mut stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let request: Value = serde_json::from_slice(&body).unwrap();
+                let params = request["params"].as_array().cloned().unwrap_or_default();
+                let result = stub_response(request["method"].as_str().unwrap(), &params, &requests);
+                let response =
+                    json!({ "id": request["id"], "jsonrpc": "2.0", "result": result }).to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                )
+                .unwrap();
+            }
+        });
+        (url, code_requests)
+    }
+
+    #[test]
+    fn executes_in_the_block_on_top_of_its_parent_state() {
+        let (url, _) = stub_node();
+        let (db, header) = RpcDatabase::new(url, Some(5)).unwrap();
+
+        assert_eq!(header.number, 5);
+        assert_eq!(db.get_chain_config().unwrap().chain_id, 7);
+        assert_eq!(
+            db.get_account_state(CONTRACT).unwrap().balance,
+            U256::from(4)
+        );
+        assert_eq!(
+            db.get_storage_value(CONTRACT, H256::zero()).unwrap(),
+            U256::from(4)
+        );
+    }
+
+    #[test]
+    fn executes_in_the_latest_block_by_default() {
+        let (url, _) = stub_node();
+        let (db, header) = RpcDatabase::new(url, None).unwrap();
+
+        assert_eq!(header.number, LATEST_BLOCK);
+        assert_eq!(
+            db.get_account_state(CONTRACT).unwrap().balance,
+            U256::from(LATEST_BLOCK - 1)
+        );
+    }
+
+    #[test]
+    fn fetches_code_along_with_the_account_once() {
+        let (url, code_requests) = stub_node();
+        let (db, _) = RpcDatabase::new(url, None).unwrap();
+
+        let account = db.get_account_state(CONTRACT).unwrap();
+        db.get_account_state(CONTRACT).unwrap();
+
+        assert_eq!(account.code_hash, keccak(code()));
+        assert_eq!(
+            db.get_account_code(account.code_hash).unwrap().bytecode,
+            code()
+        );
+        assert_eq!(code_requests.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn serves_block_hashes() {
+        let (url, _) = stub_node();
+        let (db, _) = RpcDatabase::new(url, None).unwrap();
+
+        assert_eq!(db.get_block_hash(3).unwrap(), header(3).hash());
+    }
+
+    #[test]
+    fn genesis_block_cannot_be_forked() {
+        let (url, _) = stub_node();
+
+        assert!(RpcDatabase::new(url, Some(0)).is_err());
+    }
+}
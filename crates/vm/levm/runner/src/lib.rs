@@ -1 +1,5 @@
+pub mod debugger;
+pub mod fork;
 pub mod input;
+pub mod profiler;
+pub mod sourcemap;
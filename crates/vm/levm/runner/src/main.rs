@@ -10,7 +10,8 @@ use ethrex_common::{
 use ethrex_levm::{
     EVMConfig, Environment,
     account::LevmAccount,
-    db::gen_db::GeneralizedDatabase,
+    db::{Database, gen_db::GeneralizedDatabase},
+    hooks::StepHook,
     opcodes::Opcode,
     tracing::LevmCallTracer,
     vm::{VM, VMType},
@@ -20,14 +21,27 @@ use ethrex_vm::DynVmDatabase;
 use log::{debug, error, info};
 use num_bigint::BigUint;
 use num_traits::Num;
-use runner::input::{InputAccount, InputTransaction, RunnerInput};
+use runner::{
+    debugger::{Debugger, DebuggerProtocol},
+    fork::{RpcDatabase, datadir_database},
+    input::{InputAccount, InputTransaction, RunnerInput},
+    profiler::GasProfiler,
+    sourcemap::SourceMap,
+};
 use rustc_hash::FxHashMap;
-use std::{collections::BTreeMap, io::Write};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 use std::{
     fs::{self, File},
     io::BufReader,
     sync::Arc,
 };
+use url::Url;
 
 const COINBASE: H160 = H160([0x77; 20]);
 
@@ -48,6 +62,54 @@ struct Cli {
         help = "Converts mnemonics file into a bytecode file"
     )]
     emit_bytes: Option<String>,
+
+    #[arg(
+        long,
+        help = "Profile the gas and time spent per opcode, call frame and PC, writing a summary and flamegraph stacks to the given directory"
+    )]
+    profile: Option<String>,
+
+    #[arg(
+        long,
+        requires = "profile",
+        help = "Path to the Solidity source map of the bytecode, to map the profile to source lines"
+    )]
+    sourcemap: Option<String>,
+
+    #[arg(
+        long,
+        requires = "sourcemap",
+        help = "Path to a source file of the source map. Repeat it in the order of the compiler's source indices"
+    )]
+    source: Vec<String>,
+
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "repl",
+        conflicts_with = "profile",
+        help = "Debug the transaction interactively, reading commands from stdin"
+    )]
+    debug: Option<DebuggerProtocol>,
+
+    #[arg(
+        long,
+        conflicts_with = "rpc_url",
+        help = "Path to the datadir of an ethrex node, to execute on top of its state"
+    )]
+    datadir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "URL of the JSON-RPC API of a node, e.g. a local ethrex node, to execute on top of its state"
+    )]
+    rpc_url: Option<Url>,
+
+    #[arg(
+        long,
+        help = "Block to execute in when using --datadir or --rpc-url, on top of the state of its parent. Defaults to the latest one"
+    )]
+    block: Option<u64>,
 }
 
 fn main() {
@@ -125,9 +187,36 @@ fn main() {
         Bytes::new()
     };
 
-    // Now we want to initialize the VM, so we set up the environment and database.
+    // Now we want to initialize the VM, so we set up the database and environment.
+    // DB, either forked from a node's state or empty
+    let (store, fork_header): (Arc<dyn Database>, _) = if let Some(datadir) = &cli.datadir {
+        let (store, header) = datadir_database(datadir, cli.block)
+            .unwrap_or_else(|e| panic!("Failed to fork the state: {e}"));
+        (store, Some(header))
+    } else if let Some(rpc_url) = &cli.rpc_url {
+        let (store, header) = RpcDatabase::new(rpc_url.clone(), cli.block)
+            .unwrap_or_else(|e| panic!("Failed to fork the state: {e}"));
+        (Arc::new(store), Some(header))
+    } else {
+        let in_memory_db = Store::new("", ethrex_storage::EngineType::InMemory).unwrap();
+        let header = BlockHeader {
+            state_root: *EMPTY_TRIE_HASH,
+            ..Default::default()
+        };
+        let store: DynVmDatabase = Box::new(StoreVmDatabase::new(in_memory_db, header).unwrap());
+        (Arc::new(store), None)
+    };
+    if let Some(header) = &fork_header {
+        info!(
+            "Forking the state of block {} to execute in block {} ({:#x})",
+            header.number - 1,
+            header.number,
+            header.hash()
+        );
+    }
+
     // Env
-    let env = Environment {
+    let mut env = Environment {
         origin: runner_input.transaction.sender,
         gas_limit: runner_input.transaction.gas_limit,
         gas_price: runner_input.transaction.gas_price,
@@ -139,16 +228,89 @@ fn main() {
         coinbase: COINBASE,
         ..Default::default()
     };
+    // When forking, the transaction is executed as part of the block
+    if let Some(header) = &fork_header {
+        env.block_number = header.number.into();
+        env.coinbase = header.coinbase;
+        env.timestamp = header.timestamp.into();
+        env.prev_randao = Some(header.prev_randao);
+        env.difficulty = header.difficulty;
+        env.chain_id = store
+            .get_chain_config()
+            .expect("Failed to get the chain config")
+            .chain_id
+            .into();
+        env.base_fee_per_gas = header.base_fee_per_gas.unwrap_or_default().into();
+        env.block_gas_limit = header.gas_limit;
+    }
 
-    // DB
-    let initial_state = setup_initial_state(&mut runner_input, bytecode);
-    let in_memory_db = Store::new("", ethrex_storage::EngineType::InMemory).unwrap();
-    let header = BlockHeader {
-        state_root: *EMPTY_TRIE_HASH,
-        ..Default::default()
+    // Profiler
+    let profiler = cli.profile.as_ref().map(|_| {
+        let mut profiler = GasProfiler::default();
+        if let Some(sourcemap_path) = &cli.sourcemap {
+            let sourcemap =
+                fs::read_to_string(sourcemap_path).expect("Failed to read source map file");
+            let source_map = SourceMap::new(&sourcemap, &bytecode, &cli.source)
+                .unwrap_or_else(|e| panic!("Failed to load source map: {e}"));
+            profiler =
+                profiler.with_source_map(Code::from_bytecode(bytecode.clone()).hash, source_map);
+        }
+        Rc::new(RefCell::new(profiler))
+    });
+
+    // Debugger
+    let debugger = cli
+        .debug
+        .map(|protocol| Rc::new(RefCell::new(Debugger::new(protocol))));
+
+    let step_hook: Option<Rc<RefCell<dyn StepHook>>> = match (&profiler, &debugger) {
+        (Some(profiler), _) => Some(profiler.clone()),
+        (_, Some(debugger)) => Some(debugger.clone()),
+        _ => None,
     };
-    let store: DynVmDatabase = Box::new(StoreVmDatabase::new(in_memory_db, header).unwrap());
-    let mut db = GeneralizedDatabase::new_with_account_state(Arc::new(store), initial_state);
+
+    // Execute, again if the debugger asks for it
+    let initial_state = setup_initial_state(&mut runner_input, bytecode, fork_header.is_none());
+    let gas_used = loop {
+        let gas_used = run_transaction(
+            env.clone(),
+            store.clone(),
+            &runner_input,
+            initial_state.clone(),
+            step_hook.clone(),
+        );
+        if !debugger
+            .as_ref()
+            .is_some_and(|debugger| debugger.borrow_mut().take_restart_request())
+        {
+            break gas_used;
+        }
+    };
+    drop(step_hook);
+
+    // Write the profile
+    if let (Some(profiler), Some(profile_dir)) = (profiler, &cli.profile) {
+        let profiler = Rc::try_unwrap(profiler)
+            .unwrap_or_else(|_| panic!("Profiler is still in use"))
+            .into_inner();
+        profiler
+            .write_output(Path::new(profile_dir), gas_used)
+            .expect("Failed to write profile");
+        info!("Profile written to: {}", profile_dir);
+    }
+}
+
+/// Executes the transaction of the input on top of `initial_state`, printing its result, final stack
+/// and memory and state diff. Returns the gas used by the transaction.
+fn run_transaction(
+    env: Environment,
+    store: Arc<dyn Database>,
+    runner_input: &RunnerInput,
+    initial_state: FxHashMap<Address, Account>,
+    step_hook: Option<Rc<RefCell<dyn StepHook>>>,
+) -> u64 {
+    // DB
+    let mut db = GeneralizedDatabase::new_with_account_state(store, initial_state);
 
     // Initialize VM
     let mut vm = VM::new(
@@ -163,8 +325,8 @@ fn main() {
     // Set initial stack and memory
     info!("Setting initial stack: {:?}", runner_input.initial_stack);
     let stack = &mut vm.current_call_frame.stack;
-    for elem in runner_input.initial_stack {
-        stack.push(elem).expect("Stack Overflow");
+    for elem in &runner_input.initial_stack {
+        stack.push(*elem).expect("Stack Overflow");
     }
     info!(
         "Setting initial memory: 0x{:x}",
@@ -175,15 +337,24 @@ fn main() {
         .memory
         .store_data(0, &runner_input.initial_memory);
 
+    vm.step_hook = step_hook;
+
     // Execute Transaction
     let result = vm.execute();
+    vm.step_hook = None;
 
     // Print execution result
     info!("\n\nResult:");
-    match result {
-        Ok(report) => info!(" {:?}\n", report),
-        Err(e) => error!(" Error: {}\n", e),
-    }
+    let gas_used = match result {
+        Ok(report) => {
+            info!(" {:?}\n", report);
+            report.gas_used
+        }
+        Err(e) => {
+            error!(" Error: {}\n", e);
+            0
+        }
+    };
 
     // Print final stack and memory
     let callframe = vm.current_call_frame;
@@ -204,6 +375,8 @@ fn main() {
         db.current_accounts_state,
         &runner_input.transaction,
     );
+
+    gas_used
 }
 
 /// Prints on screen difference between initial state and current one.
@@ -266,7 +439,8 @@ fn compare_initial_and_current_accounts(
 }
 
 /// ## Sets up the initial state
-/// - Inserts sender account into state with some balance for sending the transaction
+/// - Inserts sender account into state with some balance for sending the transaction, unless
+///   `with_default_sender` is false because the state is forked and already has the sender
 /// - Takes all accounts defined in the `pre` field of the json and inserts them in the state
 /// - Assigns the code to the corresponding place:
 ///   - Call to a contract: Sets contract's code
@@ -274,12 +448,16 @@ fn compare_initial_and_current_accounts(
 fn setup_initial_state(
     runner_input: &mut RunnerInput,
     bytecode: Bytes,
+    with_default_sender: bool,
 ) -> FxHashMap<Address, Account> {
     // Default state has sender with some balance to send Tx, it can be overwritten though.
-    let mut initial_state = FxHashMap::from_iter(vec![(
-        runner_input.transaction.sender,
-        Account::from(InputAccount::default()),
-    )]);
+    let mut initial_state = FxHashMap::default();
+    if with_default_sender {
+        initial_state.insert(
+            runner_input.transaction.sender,
+            Account::from(InputAccount::default()),
+        );
+    }
     let input_pre_state: BTreeMap<Address, Account> = runner_input
         .pre
         .iter()
@@ -0,0 +1,525 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use ethrex_common::{Address, H256};
+use ethrex_levm::{
+    call_frame::CallFrame, errors::ContextResult, hooks::StepHook, opcodes::Opcode, vm::VM,
+};
+use serde::Serialize;
+
+use crate::sourcemap::{SourceLocation, SourceMap};
+
+/// Attributes the gas and the time spent executing a transaction to opcodes, call frames and PCs.
+///
+/// The cost of an opcode is measured as the gas (and time) elapsed until the next opcode of the same
+/// call frame starts, minus what its child call frames used. So opcodes that call other contracts only
+/// account for their own cost, and the gas of their calls is attributed to the opcodes executed in them.
+#[derive(Default)]
+pub struct GasProfiler {
+    /// Source map of the bytecode with the given hash.
+    source_map: Option<(H256, SourceMap)>,
+    /// Call frames being executed, from the outermost one.
+    frames: Vec<FrameProfile>,
+    opcodes: HashMap<u8, Stats>,
+    contracts: HashMap<Address, ContractStats>,
+    pcs: HashMap<(Address, usize), PcStats>,
+    source_lines: HashMap<SourceLocation, Stats>,
+    /// Collapsed stacks, as used by flamegraph tools, with the gas spent in each of them.
+    gas_stacks: BTreeMap<String, u64>,
+    /// Collapsed stacks with the nanoseconds spent in each of them.
+    time_stacks: BTreeMap<String, u128>,
+}
+
+struct FrameProfile {
+    depth: usize,
+    code_address: Address,
+    /// Collapsed stack of the call frames up to this one.
+    stack: String,
+    is_source_mapped: bool,
+    gas_remaining_at_start: i64,
+    started_at: Instant,
+    current_step: Option<Step>,
+    /// Gas and time used by the child call frames of the current step.
+    children_gas: u64,
+    children_time: Duration,
+}
+
+struct Step {
+    pc: usize,
+    opcode: u8,
+    gas_remaining: i64,
+    started_at: Instant,
+}
+
+#[derive(Default, Serialize)]
+struct Stats {
+    count: u64,
+    gas: u64,
+    time_ns: u128,
+}
+
+#[derive(Default, Serialize)]
+struct ContractStats {
+    calls: u64,
+    gas: u64,
+    self_gas: u64,
+    time_ns: u128,
+    self_time_ns: u128,
+}
+
+#[derive(Serialize)]
+struct PcStats {
+    opcode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(flatten)]
+    stats: Stats,
+}
+
+#[derive(Serialize)]
+struct ProfileSummary {
+    tx_gas_used: u64,
+    execution_gas: u64,
+    execution_time_ns: u128,
+    opcodes: Vec<OpcodeSummary>,
+    contracts: Vec<ContractSummary>,
+    pcs: Vec<PcSummary>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    source_lines: Vec<SourceLineSummary>,
+}
+
+#[derive(Serialize)]
+struct OpcodeSummary {
+    opcode: String,
+    #[serde(flatten)]
+    stats: Stats,
+}
+
+#[derive(Serialize)]
+struct ContractSummary {
+    address: Address,
+    #[serde(flatten)]
+    stats: ContractStats,
+}
+
+#[derive(Serialize)]
+struct PcSummary {
+    address: Address,
+    pc: usize,
+    #[serde(flatten)]
+    stats: PcStats,
+}
+
+#[derive(Serialize)]
+struct SourceLineSummary {
+    source: String,
+    #[serde(flatten)]
+    stats: Stats,
+}
+
+impl GasProfiler {
+    /// Maps the PCs of the bytecode with hash `code_hash` to source lines using `source_map`.
+    pub fn with_source_map(mut self, code_hash: H256, source_map: SourceMap) -> Self {
+        self.source_map = Some((code_hash, source_map));
+        self
+    }
+
+    /// Writes to `output_dir`:
+    /// - `profile.json`: a summary with the gas and time spent per opcode, contract, PC and source line.
+    /// - `gas.folded` and `time.folded`: collapsed stacks that can be turned into flamegraphs with
+    ///   tools like `inferno-flamegraph` or `flamegraph.pl`.
+    pub fn write_output(self, output_dir: &Path, tx_gas_used: u64) -> io::Result<()> {
+        fs::create_dir_all(output_dir)?;
+
+        let mut gas_file = fs::File::create(output_dir.join("gas.folded"))?;
+        for (stack, gas) in self.gas_stacks.iter().filter(|(_, gas)| **gas > 0) {
+            writeln!(gas_file, "{stack} {gas}")?;
+        }
+        let mut time_file = fs::File::create(output_dir.join("time.folded"))?;
+        for (stack, time) in self.time_stacks.iter().filter(|(_, time)| **time > 0) {
+            writeln!(time_file, "{stack} {time}")?;
+        }
+
+        let summary = self.into_summary(tx_gas_used);
+        let summary_file = fs::File::create(output_dir.join("profile.json"))?;
+        serde_json::to_writer_pretty(summary_file, &summary).map_err(io::Error::other)
+    }
+
+    fn into_summary(self, tx_gas_used: u64) -> ProfileSummary {
+        let mut opcodes: Vec<_> = self
+            .opcodes
+            .into_iter()
+            .map(|(opcode, stats)| OpcodeSummary {
+                opcode: opcode_name(opcode),
+                stats,
+            })
+            .collect();
+        opcodes.sort_by(|a, b| b.stats.gas.cmp(&a.stats.gas));
+
+        let mut contracts: Vec<_> = self
+            .contracts
+            .into_iter()
+            .map(|(address, stats)| ContractSummary { address, stats })
+            .collect();
+        contracts.sort_by(|a, b| b.stats.self_gas.cmp(&a.stats.self_gas));
+
+        let mut pcs: Vec<_> = self
+            .pcs
+            .into_iter()
+            .map(|((address, pc), stats)| PcSummary { address, pc, stats })
+            .collect();
+        pcs.sort_by(|a, b| b.stats.stats.gas.cmp(&a.stats.stats.gas));
+
+        let mut source_lines: Vec<_> = self
+            .source_lines
+            .into_iter()
+            .map(|(location, stats)| SourceLineSummary {
+                source: location.to_string(),
+                stats,
+            })
+            .collect();
+        source_lines.sort_by(|a, b| b.stats.gas.cmp(&a.stats.gas));
+
+        ProfileSummary {
+            tx_gas_used,
+            execution_gas: opcodes.iter().map(|opcode| opcode.stats.gas).sum(),
+            execution_time_ns: opcodes.iter().map(|opcode| opcode.stats.time_ns).sum(),
+            opcodes,
+            contracts,
+            pcs,
+            source_lines,
+        }
+    }
+
+    fn enter_call_frame(&mut self, call_frame: &CallFrame, now: Instant) {
+        let label = format!("{:#x}", call_frame.code_address);
+        let stack = match self.frames.last() {
+            Some(parent) => format!("{};{label}", parent.stack),
+            None => label,
+        };
+        let is_source_mapped = self
+            .source_map
+            .as_ref()
+            .is_some_and(|(code_hash, _)| *code_hash == call_frame.bytecode.hash);
+
+        self.frames.push(FrameProfile {
+            depth: call_frame.depth,
+            code_address: call_frame.code_address,
+            stack,
+            is_source_mapped,
+            gas_remaining_at_start: call_frame.gas_remaining,
+            started_at: now,
+            current_step: None,
+            children_gas: 0,
+            children_time: Duration::ZERO,
+        });
+    }
+
+    /// Attributes to the current step of the innermost call frame the cost of executing it,
+    /// which ended leaving `gas_remaining` in the call frame.
+    fn finish_step(&mut self, gas_remaining: i64, now: Instant) {
+        let Some(frame) = self.frames.last_mut() else {
+            return;
+        };
+        let Some(step) = frame.current_step.take() else {
+            return;
+        };
+
+        let gas = gas_difference(step.gas_remaining, gas_remaining)
+            .saturating_sub(std::mem::take(&mut frame.children_gas));
+        let time = now
+            .duration_since(step.started_at)
+            .saturating_sub(std::mem::take(&mut frame.children_time));
+        let time_ns = time.as_nanos();
+
+        let location = frame
+            .is_source_mapped
+            .then(|| {
+                self.source_map
+                    .as_ref()
+                    .and_then(|(_, source_map)| source_map.location(step.pc))
+            })
+            .flatten();
+        let opcode = opcode_name(step.opcode);
+
+        self.opcodes
+            .entry(step.opcode)
+            .or_default()
+            .add(gas, time_ns);
+
+        let contract = self.contracts.entry(frame.code_address).or_default();
+        contract.self_gas += gas;
+        contract.self_time_ns += time_ns;
+
+        self.pcs
+            .entry((frame.code_address, step.pc))
+            .or_insert_with(|| PcStats {
+                opcode: opcode.clone(),
+                source: location.as_ref().map(ToString::to_string),
+                stats: Stats::default(),
+            })
+            .stats
+            .add(gas, time_ns);
+
+        let stack = match &location {
+            Some(location) => format!("{};{location};{opcode}", frame.stack),
+            None => format!("{};{opcode}", frame.stack),
+        };
+        if let Some(location) = location {
+            self.source_lines
+                .entry(location)
+                .or_default()
+                .add(gas, time_ns);
+        }
+        *self.gas_stacks.entry(stack.clone()).or_default() += gas;
+        *self.time_stacks.entry(stack).or_default() += time_ns;
+    }
+}
+
+impl StepHook for GasProfiler {
+    fn on_step(&mut self, vm: &VM<'_>) {
+        let now = Instant::now();
+        let call_frame = &vm.current_call_frame;
+
+        if self
+            .frames
+            .last()
+            .is_none_or(|frame| frame.depth < call_frame.depth)
+        {
+            self.enter_call_frame(call_frame, now);
+        } else {
+            self.finish_step(call_frame.gas_remaining, now);
+        }
+
+        if let Some(frame) = self.frames.last_mut() {
+            frame.current_step = Some(Step {
+                pc: call_frame.pc,
+                opcode: call_frame.next_opcode(),
+                gas_remaining: call_frame.gas_remaining,
+                started_at: now,
+            });
+        }
+    }
+
+    fn on_call_frame_exit(&mut self, vm: &VM<'_>, _result: &ContextResult) {
+        let now = Instant::now();
+        let call_frame = &vm.current_call_frame;
+
+        // Call frames that didn't execute any opcode aren't profiled, their cost goes to their caller
+        if self
+            .frames
+            .last()
+            .is_none_or(|frame| frame.depth != call_frame.depth)
+        {
+            return;
+        }
+        self.finish_step(call_frame.gas_remaining, now);
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+
+        let gas = gas_difference(frame.gas_remaining_at_start, call_frame.gas_remaining);
+        let time = now.duration_since(frame.started_at);
+        let contract = self.contracts.entry(frame.code_address).or_default();
+        contract.calls += 1;
+        contract.gas += gas;
+        contract.time_ns += time.as_nanos();
+
+        if let Some(parent) = self.frames.last_mut() {
+            parent.children_gas += gas;
+            parent.children_time += time;
+        }
+    }
+}
+
+impl Stats {
+    fn add(&mut self, gas: u64, time_ns: u128) {
+        self.count += 1;
+        self.gas += gas;
+        self.time_ns += time_ns;
+    }
+}
+
+fn gas_difference(before: i64, after: i64) -> u64 {
+    u64::try_from(before.saturating_sub(after)).unwrap_or_default()
+}
+
+fn opcode_name(opcode: u8) -> String {
+    match Opcode::from(opcode) {
+        Opcode::INVALID => format!("INVALID(0x{opcode:02x})"),
+        opcode => format!("{opcode:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+    use bytes::Bytes;
+    use ethrex_common::{
+        U256,
+        types::{
+            Account, AccountState, ChainConfig, Code, EIP1559Transaction, Fork, Transaction, TxKind,
+        },
+    };
+    use ethrex_levm::{
+        EVMConfig, Environment,
+        db::{Database, gen_db::GeneralizedDatabase},
+        errors::DatabaseError,
+        tracing::LevmCallTracer,
+        vm::VMType,
+    };
+    use rustc_hash::FxHashMap;
+
+    use super::*;
+
+    const SENDER: Address = Address::repeat_byte(0x10);
+    const CALLER: Address = Address::repeat_byte(0xaa);
+    const CALLEE: Address = Address::repeat_byte(0xbb);
+
+    struct EmptyStore;
+
+    impl Database for EmptyStore {
+        fn get_account_state(&self, _address: Address) -> Result<AccountState, DatabaseError> {
+            Ok(AccountState::default())
+        }
+        fn get_storage_value(&self, _address: Address, _key: H256) -> Result<U256, DatabaseError> {
+            Ok(U256::zero())
+        }
+        fn get_block_hash(&self, _block_number: u64) -> Result<H256, DatabaseError> {
+            Ok(H256::zero())
+        }
+        fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+            Ok(ChainConfig::default())
+        }
+        fn get_account_code(&self, _code_hash: H256) -> Result<Code, DatabaseError> {
+            Ok(Code::default())
+        }
+    }
+
+    /// PUSH1 1, PUSH1 2, ADD, POP, STOP
+    fn callee_code() -> Bytes {
+        Bytes::from_static(&[0x60, 0x01, 0x60, 0x02, 0x01, 0x50, 0x00])
+    }
+
+    /// Profiles a transaction to a contract that CALLs the callee and stops.
+    fn profile(profiler: GasProfiler) -> GasProfiler {
+        // CALL(gas, CALLEE, 0, 0, 0, 0, 0), POP, STOP
+        let mut caller_code = hex::decode("60006000600060006000").unwrap();
+        caller_code.push(0x73);
+        caller_code.extend_from_slice(CALLEE.as_bytes());
+        caller_code.extend_from_slice(&[0x5a, 0xf1, 0x50, 0x00]);
+        let contract = |code| {
+            Account::new(
+                U256::zero(),
+                Code::from_bytecode(code),
+                1,
+                Default::default(),
+            )
+        };
+        let accounts = FxHashMap::from_iter([
+            (
+                SENDER,
+                Account::new(U256::MAX, Code::default(), 0, Default::default()),
+            ),
+            (CALLER, contract(Bytes::from(caller_code))),
+            (CALLEE, contract(callee_code())),
+        ]);
+        let mut db = GeneralizedDatabase::new_with_account_state(Arc::new(EmptyStore), accounts);
+
+        let gas_limit = 1_000_000;
+        let env = Environment {
+            origin: SENDER,
+            gas_limit,
+            block_gas_limit: gas_limit,
+            config: EVMConfig::new(Fork::Prague, EVMConfig::canonical_values(Fork::Prague)),
+            ..Default::default()
+        };
+        let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+            gas_limit,
+            to: TxKind::Call(CALLER),
+            ..Default::default()
+        });
+
+        let profiler = Rc::new(RefCell::new(profiler));
+        let mut vm = VM::new(env, &mut db, &tx, LevmCallTracer::disabled(), VMType::L1).unwrap();
+        vm.step_hook = Some(profiler.clone());
+        assert!(vm.execute().unwrap().is_success());
+        drop(vm);
+
+        profiler.take()
+    }
+
+    #[test]
+    fn gas_of_calls_goes_to_the_opcodes_executed_in_them() {
+        let profiler = profile(GasProfiler::default());
+
+        let caller = &profiler.contracts[&CALLER];
+        let callee = &profiler.contracts[&CALLEE];
+        assert_eq!((caller.calls, callee.calls), (1, 1));
+        // PUSH1, PUSH1, ADD, POP, STOP
+        assert_eq!((callee.gas, callee.self_gas), (11, 11));
+        assert_eq!(caller.gas, caller.self_gas + callee.gas);
+
+        let add = &profiler.opcodes[&u8::from(Opcode::ADD)];
+        assert_eq!((add.count, add.gas), (1, 3));
+        assert_eq!(profiler.opcodes[&u8::from(Opcode::PUSH1)].count, 7);
+        assert_eq!(profiler.opcodes[&u8::from(Opcode::CALL)].count, 1);
+        assert_eq!(profiler.pcs[&(CALLEE, 4)].stats.gas, 3);
+
+        let callee_stack = format!("{CALLER:#x};{CALLEE:#x}");
+        assert_eq!(profiler.gas_stacks[&format!("{callee_stack};ADD")], 3);
+        let callee_stacks_gas: u64 = profiler
+            .gas_stacks
+            .iter()
+            .filter(|(stack, _)| stack.starts_with(&callee_stack))
+            .map(|(_, gas)| gas)
+            .sum();
+        assert_eq!(callee_stacks_gas, 11);
+
+        let (caller_self_gas, callee_self_gas) = (caller.self_gas, callee.self_gas);
+        let summary = profiler.into_summary(21_000);
+        assert_eq!(summary.execution_gas, caller_self_gas + callee_self_gas);
+        assert_eq!(summary.contracts[0].address, CALLER);
+    }
+
+    #[test]
+    fn source_mapped_opcodes_are_aggregated_per_line() {
+        let dir = std::env::temp_dir().join(format!("levm-profiler-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Callee.sol");
+        fs::write(&path, "a\nb\nc\nd\ne\n").unwrap();
+        // One instruction per line
+        let source_map = SourceMap::new(
+            "0:1:0;2:1:0;4:1:0;6:1:0;8:1:0",
+            &callee_code(),
+            &[path.to_string_lossy().into_owned()],
+        )
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        let code_hash = Code::from_bytecode(callee_code()).hash;
+
+        let profiler = profile(GasProfiler::default().with_source_map(code_hash, source_map));
+
+        let line = |line| SourceLocation {
+            file: "Callee.sol".to_string(),
+            line,
+        };
+        assert_eq!(profiler.source_lines.len(), 5);
+        let add = &profiler.source_lines[&line(3)];
+        assert_eq!((add.count, add.gas), (1, 3));
+        assert_eq!(
+            profiler.pcs[&(CALLEE, 4)].source.as_deref(),
+            Some("Callee.sol:3")
+        );
+        let stack = format!("{CALLER:#x};{CALLEE:#x};Callee.sol:3;ADD");
+        assert_eq!(profiler.gas_stacks[&stack], 3);
+        // The caller isn't source mapped
+        assert!(profiler.pcs[&(CALLER, 0)].source.is_none());
+    }
+}
@@ -0,0 +1,210 @@
+use std::{fmt, fs, path::Path};
+
+use ethrex_levm::opcodes::Opcode;
+
+/// Solidity source map of a bytecode, as output by `solc --combined-json srcmap-runtime` or found in
+/// the `deployedBytecode.sourceMap` field of compiler artifacts.
+/// See https://docs.soliditylang.org/en/latest/internals/source_mappings.html
+pub struct SourceMap {
+    /// Source range of each instruction, indexed by instruction number (not by PC).
+    entries: Vec<SourceMapEntry>,
+    /// Instruction number of each PC of the bytecode, `None` for bytes that are PUSH data.
+    pc_to_instruction: Vec<Option<usize>>,
+    sources: Vec<SourceFile>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct SourceMapEntry {
+    offset: usize,
+    file_index: Option<usize>,
+}
+
+struct SourceFile {
+    name: String,
+    /// Byte offset at which each line starts.
+    line_starts: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    pub file: String,
+    /// 1-based.
+    pub line: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+impl SourceMap {
+    /// `source_paths` must be sorted by the source index the compiler assigned to each file.
+    pub fn new(sourcemap: &str, bytecode: &[u8], source_paths: &[String]) -> Result<Self, String> {
+        let sources = source_paths
+            .iter()
+            .map(|path| {
+                let content = fs::read_to_string(path)
+                    .map_err(|e| format!("Failed to read source file '{path}': {e}"))?;
+                let name = Path::new(path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.clone());
+                Ok(SourceFile::new(name, &content))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            entries: parse_entries(sourcemap.trim())?,
+            pc_to_instruction: instruction_numbers(bytecode),
+            sources,
+        })
+    }
+
+    /// Source location of the instruction at `pc`, if it maps to one of the given source files.
+    pub fn location(&self, pc: usize) -> Option<SourceLocation> {
+        let instruction = (*self.pc_to_instruction.get(pc)?)?;
+        let entry = self.entries.get(instruction)?;
+        let source = self.sources.get(entry.file_index?)?;
+        Some(SourceLocation {
+            file: source.name.clone(),
+            line: source.line(entry.offset),
+        })
+    }
+}
+
+impl SourceFile {
+    fn new(name: String, content: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { name, line_starts }
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        match self.line_starts.binary_search(&offset) {
+            Ok(line) => line + 1,
+            Err(next_line) => next_line,
+        }
+    }
+}
+
+/// Decompresses the source map: empty fields take the value of the previous entry.
+fn parse_entries(sourcemap: &str) -> Result<Vec<SourceMapEntry>, String> {
+    let mut entries = Vec::new();
+    let mut previous = SourceMapEntry::default();
+
+    for raw_entry in sourcemap.split(';') {
+        let mut fields = raw_entry.split(':');
+        let mut entry = previous;
+        if let Some(offset) = fields.next().filter(|field| !field.is_empty()) {
+            entry.offset = offset
+                .parse()
+                .map_err(|_| format!("Invalid source map offset '{offset}'"))?;
+        }
+        // The length of the range isn't needed to find its line
+        fields.next();
+        if let Some(file_index) = fields.next().filter(|field| !field.is_empty()) {
+            let file_index: i64 = file_index
+                .parse()
+                .map_err(|_| format!("Invalid source map file index '{file_index}'"))?;
+            // -1 is used for code that doesn't map to any source, e.g. generated by the compiler
+            entry.file_index = usize::try_from(file_index).ok();
+        }
+        entries.push(entry);
+        previous = entry;
+    }
+
+    Ok(entries)
+}
+
+fn instruction_numbers(bytecode: &[u8]) -> Vec<Option<usize>> {
+    let mut numbers = vec![None; bytecode.len()];
+    let mut pc = 0;
+    let mut instruction = 0;
+
+    while let Some(&byte) = bytecode.get(pc) {
+        if let Some(number) = numbers.get_mut(pc) {
+            *number = Some(instruction);
+        }
+        let opcode = Opcode::from(byte);
+        let push_size = if (Opcode::PUSH1..=Opcode::PUSH32).contains(&opcode) {
+            usize::from(byte - u8::from(Opcode::PUSH1) + 1)
+        } else {
+            0
+        };
+        pc += 1 + push_size;
+        instruction += 1;
+    }
+
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(entries: &[SourceMapEntry]) -> Vec<(usize, Option<usize>)> {
+        entries
+            .iter()
+            .map(|entry| (entry.offset, entry.file_index))
+            .collect()
+    }
+
+    #[test]
+    fn empty_fields_take_the_value_of_the_previous_entry() {
+        let entries = parse_entries("10:5:0;;20;:3:1;::-1").unwrap();
+        assert_eq!(
+            fields(&entries),
+            [
+                (10, Some(0)),
+                (10, Some(0)),
+                (20, Some(0)),
+                (20, Some(1)),
+                (20, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn jump_markers_and_modifier_depths_are_ignored() {
+        let entries = parse_entries("10:5:0:i;20:4:0:o:1;:::-;30:1:1:-:0").unwrap();
+        assert_eq!(
+            fields(&entries),
+            [(10, Some(0)), (20, Some(0)), (20, Some(0)), (30, Some(1))]
+        );
+    }
+
+    #[test]
+    fn invalid_entries_are_rejected() {
+        assert!(parse_entries("a:1:0").is_err());
+        assert!(parse_entries("1:1:b").is_err());
+    }
+
+    #[test]
+    fn push_data_is_not_numbered_as_an_instruction() {
+        // PUSH1 0x01, PUSH2 0x0002, ADD
+        let numbers = instruction_numbers(&[0x60, 0x01, 0x61, 0x00, 0x02, 0x01]);
+        assert_eq!(numbers, [Some(0), None, Some(1), None, None, Some(2)]);
+    }
+
+    #[test]
+    fn pcs_are_mapped_to_the_line_of_their_instruction() {
+        let source_map = SourceMap {
+            // The second instruction isn't mapped to any source
+            entries: parse_entries("0:1:0;:::-1;4:2:0").unwrap(),
+            pc_to_instruction: instruction_numbers(&[0x60, 0x01, 0x60, 0x02, 0x01]),
+            sources: vec![SourceFile::new("A.sol".to_string(), "a\nbb\nccc\n")],
+        };
+        let line = |line| SourceLocation {
+            file: "A.sol".to_string(),
+            line,
+        };
+
+        assert_eq!(source_map.location(0), Some(line(1)));
+        assert_eq!(source_map.location(1), None);
+        assert_eq!(source_map.location(2), None);
+        assert_eq!(source_map.location(4), Some(line(2)));
+        assert_eq!(source_map.location(5), None);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     account::LevmAccount,
     constants::STACK_LIMIT,
+    eof::{EofContainer, EofFrame, is_eof},
     errors::{ExceptionalHalt, InternalError, VMError},
     memory::Memory,
     utils::restore_cache_state,
@@ -161,6 +162,33 @@ impl Stack {
         self.offset = STACK_LIMIT;
     }
 
+    /// Index in `values` of the value at the given depth, 0 being the top of the stack.
+    fn index_at_depth(&self, depth: usize) -> Result<usize, ExceptionalHalt> {
+        self.offset
+            .checked_add(depth)
+            .filter(|index| *index < self.values.len())
+            .ok_or(ExceptionalHalt::StackUnderflow)
+    }
+
+    /// Pushes a copy of the value at the given depth, which unlike in [`dup`](Self::dup) is only
+    /// known at runtime.
+    pub fn dup_n(&mut self, depth: usize) -> Result<(), ExceptionalHalt> {
+        let index = self.index_at_depth(depth)?;
+        let value = *self
+            .values
+            .get(index)
+            .ok_or(ExceptionalHalt::StackUnderflow)?;
+        self.push(value)
+    }
+
+    /// Swaps the values at the given depths.
+    pub fn exchange(&mut self, depth_a: usize, depth_b: usize) -> Result<(), ExceptionalHalt> {
+        let index_a = self.index_at_depth(depth_a)?;
+        let index_b = self.index_at_depth(depth_b)?;
+        self.values.swap(index_a, index_b);
+        Ok(())
+    }
+
     /// Pushes a copy of the value at depth N
     #[inline]
     pub fn dup<const N: usize>(&mut self) -> Result<(), ExceptionalHalt> {
@@ -268,6 +296,8 @@ pub struct CallFrame {
     pub ret_size: usize,
     /// If true then transfer value from caller to callee
     pub should_transfer_value: bool,
+    /// Set when executing an EOF container, see [`load_eof`](Self::load_eof).
+    pub eof: Option<Box<EofFrame>>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
@@ -353,6 +383,7 @@ impl CallFrame {
             output: Bytes::default(),
             pc: 0,
             sub_return_data: Bytes::default(),
+            eof: None,
         }
     }
 
@@ -390,6 +421,23 @@ impl CallFrame {
         self.bytecode = code;
         Ok(())
     }
+
+    /// Starts executing the bytecode as an EOF container if it is one, from its first code section.
+    ///
+    /// Containers are validated when deployed, so a header that doesn't parse can only come from
+    /// code that wasn't deployed as EOF. It's then executed as legacy code, halting at its 0xEF.
+    pub fn load_eof(&mut self) {
+        if !is_eof(&self.bytecode.bytecode) {
+            return;
+        }
+        let Ok(container) = EofContainer::parse(&self.bytecode.bytecode) else {
+            return;
+        };
+        if let Some(first_section) = container.code_sections.first() {
+            self.pc = first_section.start;
+            self.eof = Some(Box::new(EofFrame::new(container)));
+        }
+    }
 }
 
 impl<'a> VM<'a> {
@@ -11,6 +11,15 @@ pub const WORD_SIZE_IN_BYTES_U64: u64 = 32;
 
 pub const SUCCESS: U256 = U256::one();
 pub const FAIL: U256 = U256::zero();
+// Status codes pushed by EXTCALL, EXTDELEGATECALL and EXTSTATICCALL
+pub const EXTCALL_SUCCESS: U256 = U256::zero();
+pub const EXTCALL_REVERT: U256 = U256::one();
+pub const EXTCALL_FAILURE: U256 = U256([2, 0, 0, 0]);
+// Hash of the code seen by legacy code when inspecting an EOF account, keccak256(0xEF00)
+pub const EOF_CODE_HASH: H256 = H256([
+    0x9d, 0xbf, 0x36, 0x48, 0xdb, 0x82, 0x10, 0x55, 0x2e, 0x9c, 0x4f, 0x75, 0xc6, 0xa1, 0xc3, 0x05,
+    0x7c, 0x0c, 0xa4, 0x32, 0x04, 0x3b, 0xd6, 0x48, 0xbe, 0x15, 0xfe, 0x7b, 0xe0, 0x56, 0x46, 0xf5,
+]);
 pub const WORD_SIZE: usize = 32;
 
 pub const STACK_LIMIT: usize = 1024;
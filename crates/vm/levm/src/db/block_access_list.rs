@@ -0,0 +1,200 @@
+use ethrex_common::types::block_access_list::{
+    BlockAccessIndex, BlockAccessList, BlockAccessListBuilder,
+};
+use ethrex_common::types::{AccountInfo, Code};
+use ethrex_common::{Address, H256, U256};
+use rustc_hash::FxHashMap;
+
+use super::gen_db::CacheDB;
+
+/// Records the accounts and storage slots accessed while executing a block to build its
+/// [EIP-7928](https://eips.ethereum.org/EIPS/eip-7928) access list.
+///
+/// Accesses are kept as pending, together with the value they had when first accessed, until the
+/// execution step that made them ends and the block executor calls [`Self::checkpoint`] with its
+/// block access index. At that point the pending values are compared with the current state to tell
+/// apart changes from plain reads.
+#[derive(Debug, Clone, Default)]
+pub struct BlockAccessListRecorder {
+    /// Accounts accessed in the current execution step, with their info before it.
+    pending_accounts: FxHashMap<Address, AccountInfo>,
+    /// Storage slots accessed in the current execution step, with their value before it.
+    pending_storage: FxHashMap<(Address, H256), U256>,
+    builder: BlockAccessListBuilder,
+}
+
+impl BlockAccessListRecorder {
+    pub fn record_account_access(&mut self, address: Address, info: &AccountInfo) {
+        self.pending_accounts
+            .entry(address)
+            .or_insert_with(|| info.clone());
+    }
+
+    pub fn record_storage_access(&mut self, address: Address, key: H256, value: U256) {
+        self.pending_storage.entry((address, key)).or_insert(value);
+    }
+
+    pub fn is_pending(&self, address: &Address) -> bool {
+        self.pending_accounts.contains_key(address)
+    }
+
+    /// Forgets an account access of the current execution step, for accounts that system calls touch
+    /// only as part of their execution environment.
+    pub fn forget_account_access(&mut self, address: &Address) {
+        self.pending_accounts.remove(address);
+    }
+
+    /// Forgets the accesses of the current execution step, e.g. when a transaction is left out of the
+    /// block being built because it's invalid.
+    pub fn discard_pending(&mut self) {
+        self.pending_accounts.clear();
+        self.pending_storage.clear();
+    }
+
+    /// Ends the current execution step, recording in the access list its accesses and the values
+    /// they were left with in `accounts`.
+    pub fn checkpoint(
+        &mut self,
+        index: BlockAccessIndex,
+        accounts: &CacheDB,
+        codes: &FxHashMap<H256, Code>,
+    ) {
+        for (address, pre_info) in self.pending_accounts.drain() {
+            self.builder.touch_account(address);
+            let Some(post_info) = accounts.get(&address).map(|account| &account.info) else {
+                continue;
+            };
+            if post_info.balance != pre_info.balance {
+                self.builder
+                    .record_balance_change(address, index, post_info.balance);
+            }
+            if post_info.nonce != pre_info.nonce {
+                self.builder
+                    .record_nonce_change(address, index, post_info.nonce);
+            }
+            if post_info.code_hash != pre_info.code_hash {
+                let code = codes
+                    .get(&post_info.code_hash)
+                    .map(|code| code.bytecode.clone())
+                    .unwrap_or_default();
+                self.builder.record_code_change(address, index, code);
+            }
+        }
+
+        for ((address, key), pre_value) in self.pending_storage.drain() {
+            // Slots missing from an accessed account were cleared when it was destroyed
+            let post_value = accounts
+                .get(&address)
+                .and_then(|account| account.storage.get(&key))
+                .copied()
+                .unwrap_or_default();
+            if post_value != pre_value {
+                self.builder
+                    .record_storage_change(address, key, index, post_value);
+            } else {
+                self.builder.record_storage_read(address, key);
+            }
+        }
+    }
+
+    pub fn build(self) -> BlockAccessList {
+        self.builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing)]
+
+    use bytes::Bytes;
+    use ethrex_common::types::block_access_list::StorageChange;
+
+    use super::*;
+    use crate::account::LevmAccount;
+
+    fn account(balance: u64, nonce: u64, code_hash: H256) -> LevmAccount {
+        LevmAccount {
+            info: AccountInfo {
+                balance: U256::from(balance),
+                nonce,
+                code_hash,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn checkpoint_tells_apart_changes_from_reads() {
+        let sender = Address::from_low_u64_be(1);
+        let contract = Address::from_low_u64_be(2);
+        let read_slot = H256::from_low_u64_be(3);
+        let written_slot = H256::from_low_u64_be(4);
+        let code = Code::from_bytecode(Bytes::from_static(&[0x60, 0x00]));
+        let mut recorder = BlockAccessListRecorder::default();
+
+        recorder.record_account_access(sender, &account(100, 0, H256::zero()).info);
+        recorder.record_account_access(contract, &account(0, 0, H256::zero()).info);
+        recorder.record_storage_access(contract, read_slot, U256::from(5));
+        recorder.record_storage_access(contract, written_slot, U256::zero());
+        // Only the first access of the step keeps its value
+        recorder.record_storage_access(contract, written_slot, U256::from(1));
+
+        let mut accounts = CacheDB::default();
+        accounts.insert(sender, account(79, 1, H256::zero()));
+        let mut deployed = account(0, 1, code.hash);
+        deployed.storage.insert(read_slot, U256::from(5));
+        deployed.storage.insert(written_slot, U256::from(7));
+        accounts.insert(contract, deployed);
+        let mut codes = FxHashMap::default();
+        codes.insert(code.hash, code.clone());
+        recorder.checkpoint(1, &accounts, &codes);
+
+        let block_access_list = recorder.build();
+        assert_eq!(block_access_list.accounts.len(), 2);
+        let sender_changes = &block_access_list.accounts[0];
+        assert_eq!(sender_changes.address, sender);
+        assert_eq!(
+            sender_changes.balance_changes[0].post_balance,
+            U256::from(79)
+        );
+        assert_eq!(sender_changes.nonce_changes[0].post_nonce, 1);
+        assert!(sender_changes.code_changes.is_empty());
+
+        let contract_changes = &block_access_list.accounts[1];
+        assert_eq!(contract_changes.storage_reads, vec![U256::from(3)]);
+        assert_eq!(contract_changes.storage_changes.len(), 1);
+        assert_eq!(contract_changes.storage_changes[0].slot, U256::from(4));
+        assert_eq!(
+            contract_changes.storage_changes[0].changes[0],
+            StorageChange {
+                block_access_index: 1,
+                post_value: U256::from(7),
+            }
+        );
+        assert_eq!(contract_changes.code_changes[0].new_code, code.bytecode);
+        assert!(contract_changes.balance_changes.is_empty());
+    }
+
+    #[test]
+    fn discarded_accesses_are_left_out() {
+        let kept = Address::from_low_u64_be(1);
+        let discarded = Address::from_low_u64_be(2);
+        let accounts = CacheDB::default();
+        let codes = FxHashMap::default();
+        let mut recorder = BlockAccessListRecorder::default();
+
+        recorder.record_account_access(kept, &AccountInfo::default());
+        recorder.checkpoint(1, &accounts, &codes);
+        recorder.record_account_access(discarded, &AccountInfo::default());
+        recorder.record_storage_access(discarded, H256::zero(), U256::zero());
+        assert!(recorder.is_pending(&discarded));
+        recorder.discard_pending();
+        assert!(!recorder.is_pending(&discarded));
+        recorder.checkpoint(2, &accounts, &codes);
+
+        let block_access_list = recorder.build();
+        assert_eq!(block_access_list.accounts.len(), 1);
+        assert_eq!(block_access_list.accounts[0].address, kept);
+        assert!(block_access_list.accounts[0].storage_reads.is_empty());
+    }
+}
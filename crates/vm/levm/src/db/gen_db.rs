@@ -1,13 +1,18 @@
 use std::sync::Arc;
 
+use bytes::Bytes;
 use ethrex_common::Address;
 use ethrex_common::H256;
 use ethrex_common::U256;
 use ethrex_common::types::Account;
 use ethrex_common::types::Code;
+use ethrex_common::types::TransactionAddresses;
+use ethrex_common::types::block_access_list::BlockAccessIndex;
 use ethrex_common::utils::ZERO_U256;
+use ethrex_common::utils::keccak;
 
 use super::Database;
+use super::block_access_list::BlockAccessListRecorder;
 use crate::account::AccountStatus;
 use crate::account::LevmAccount;
 use crate::call_frame::CallFrameBackup;
@@ -29,6 +34,10 @@ pub struct GeneralizedDatabase {
     pub initial_accounts_state: CacheDB,
     pub codes: FxHashMap<H256, Code>,
     pub tx_backup: Option<CallFrameBackup>,
+    /// Set by the block executor when the block must commit to its access list (EIP-7928).
+    pub block_access_list: Option<BlockAccessListRecorder>,
+    /// Set by the block executor when the addresses each transaction took part in are indexed.
+    pub transaction_addresses: Option<Vec<TransactionAddresses>>,
 }
 
 impl GeneralizedDatabase {
@@ -38,6 +47,8 @@ impl GeneralizedDatabase {
             current_accounts_state: Default::default(),
             initial_accounts_state: Default::default(),
             tx_backup: None,
+            block_access_list: None,
+            transaction_addresses: None,
             codes: Default::default(),
         }
     }
@@ -61,6 +72,8 @@ impl GeneralizedDatabase {
             current_accounts_state: levm_accounts.clone(),
             initial_accounts_state: levm_accounts,
             tx_backup: None,
+            block_access_list: None,
+            transaction_addresses: None,
             codes,
         }
     }
@@ -69,15 +82,19 @@ impl GeneralizedDatabase {
     /// Loads account
     /// If it's the first time it's loaded store it in `initial_accounts_state` and also cache it in `current_accounts_state` for making changes to it
     fn load_account(&mut self, address: Address) -> Result<&mut LevmAccount, InternalError> {
-        match self.current_accounts_state.entry(address) {
-            Entry::Occupied(entry) => Ok(entry.into_mut()),
+        let account = match self.current_accounts_state.entry(address) {
+            Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => {
                 let state = self.store.get_account_state(address)?;
                 let account = LevmAccount::from(state);
                 self.initial_accounts_state.insert(address, account.clone());
-                Ok(entry.insert(account))
+                entry.insert(account)
             }
+        };
+        if let Some(recorder) = self.block_access_list.as_mut() {
+            recorder.record_account_access(address, &account.info);
         }
+        Ok(account)
     }
 
     /// Gets reference of an account
@@ -134,6 +151,105 @@ impl GeneralizedDatabase {
         Ok(value)
     }
 
+    /// Gets storage value of an account, caching it if not already cached.
+    /// The account must have been loaded before. Opcodes should use `vm.get_storage_value` instead.
+    pub fn get_storage_value(
+        &mut self,
+        address: Address,
+        key: H256,
+    ) -> Result<U256, InternalError> {
+        let Some(account) = self.current_accounts_state.get(&address) else {
+            return Err(InternalError::AccountNotFound);
+        };
+        let value = match account.storage.get(&key) {
+            Some(value) => *value,
+            // If the account was destroyed and then created then we cannot rely on the DB to obtain storage values
+            None if account.status == AccountStatus::DestroyedModified => U256::zero(),
+            None => {
+                let value = self.get_value_from_database(address, key)?;
+                if let Some(account) = self.current_accounts_state.get_mut(&address) {
+                    account.storage.insert(key, value);
+                }
+                value
+            }
+        };
+        if let Some(recorder) = self.block_access_list.as_mut() {
+            recorder.record_storage_access(address, key, value);
+        }
+        Ok(value)
+    }
+
+    /// Applies on top of this database the changes of a transaction that was executed on `tx_db`.
+    /// `tx_db` must have been executed against a view of this database's current state, which is what the
+    /// parallel block executor guarantees before merging, otherwise the resulting state is meaningless.
+    pub fn merge_transaction(&mut self, tx_db: GeneralizedDatabase) -> Result<(), InternalError> {
+        for (address, tx_account) in tx_db.current_accounts_state {
+            if tx_account.is_unmodified() {
+                continue;
+            }
+
+            // The transaction destroyed the account, so its storage in the cache is all that's left of it.
+            if matches!(
+                tx_account.status,
+                AccountStatus::Destroyed | AccountStatus::DestroyedModified
+            ) {
+                *self.load_account(address)? = tx_account;
+                continue;
+            }
+
+            let account = self.load_account(address)?;
+            let storage_cleared = matches!(
+                account.status,
+                AccountStatus::Destroyed | AccountStatus::DestroyedModified
+            );
+            // Slots that weren't cached yet need their original value in `initial_accounts_state` for computing state transitions.
+            let missing_keys: Vec<H256> = if storage_cleared {
+                Vec::new()
+            } else {
+                tx_account
+                    .storage
+                    .keys()
+                    .filter(|key| !account.storage.contains_key(key))
+                    .copied()
+                    .collect()
+            };
+            for key in missing_keys {
+                self.get_value_from_database(address, key)?;
+            }
+
+            let account = self.load_account(address)?;
+            account.info = tx_account.info;
+            account.storage.extend(tx_account.storage);
+            account.mark_modified();
+        }
+        self.codes.extend(tx_db.codes);
+        Ok(())
+    }
+
+    /// Starts recording the block access list. Any access made before this isn't part of it.
+    pub fn enable_block_access_list(&mut self) {
+        self.block_access_list = Some(BlockAccessListRecorder::default());
+    }
+
+    /// Ends the execution step at `index` of the block access list being recorded, if any.
+    pub fn checkpoint_block_access_list(&mut self, index: BlockAccessIndex) {
+        if let Some(recorder) = self.block_access_list.as_mut() {
+            recorder.checkpoint(index, &self.current_accounts_state, &self.codes);
+        }
+    }
+
+    /// Forgets the accesses made since the last checkpoint of the block access list being recorded, if any.
+    pub fn discard_pending_block_access_list(&mut self) {
+        if let Some(recorder) = self.block_access_list.as_mut() {
+            recorder.discard_pending();
+        }
+    }
+
+    /// Starts recording the addresses each transaction executed from now on takes part in.
+    pub fn enable_address_index(&mut self) {
+        self.transaction_addresses = Some(Vec::new());
+    }
+
     /// Gets the transaction backup, if it exists.
     /// It only works if the `BackupHook` was enabled during the transaction execution.
     pub fn get_tx_backup(&self) -> Result<CallFrameBackup, InternalError> {
@@ -387,11 +503,12 @@ impl<'a> VM<'a> {
         increase: U256,
     ) -> Result<(), InternalError> {
         let account = self.get_account_mut(address)?;
-        account.info.balance = account
-            .info
-            .balance
+        let previous = account.info.balance;
+        account.info.balance = previous
             .checked_add(increase)
             .ok_or(InternalError::Overflow)?;
+        let new = account.info.balance;
+        self.trace(|tracer| tracer.on_balance_change(address, previous, new));
         Ok(())
     }
 
@@ -401,11 +518,12 @@ impl<'a> VM<'a> {
         decrease: U256,
     ) -> Result<(), InternalError> {
         let account = self.get_account_mut(address)?;
-        account.info.balance = account
-            .info
-            .balance
+        let previous = account.info.balance;
+        account.info.balance = previous
             .checked_sub(decrease)
             .ok_or(InternalError::Underflow)?;
+        let new = account.info.balance;
+        self.trace(|tracer| tracer.on_balance_change(address, previous, new));
         Ok(())
     }
 
@@ -423,6 +541,16 @@ impl<'a> VM<'a> {
         Ok(())
     }
 
+    /// Builds a code deployed during execution, reusing the analysis of an identical code seen
+    /// before if the store keeps a cache of them.
+    pub fn new_code(&self, bytecode: Bytes) -> Code {
+        let code_hash = keccak(&bytecode);
+        match self.store.code_analysis_cache() {
+            Some(cache) => Code::from_bytecode_cached(bytecode, code_hash, &cache),
+            None => Code::from_bytecode_unchecked(bytecode, code_hash),
+        }
+    }
+
     /// Updates bytecode of given account.
     pub fn update_account_bytecode(
         &mut self,
@@ -487,6 +615,15 @@ impl<'a> VM<'a> {
         address: Address,
         key: H256,
     ) -> Result<U256, InternalError> {
+        let value = self.load_storage_value(address, key)?;
+        if let Some(recorder) = self.db.block_access_list.as_mut() {
+            recorder.record_storage_access(address, key, value);
+        }
+        Ok(value)
+    }
+
+    #[inline(always)]
+    fn load_storage_value(&mut self, address: Address, key: H256) -> Result<U256, InternalError> {
         if let Some(account) = self.db.current_accounts_state.get(&address) {
             if let Some(value) = account.storage.get(&key) {
                 return Ok(*value);
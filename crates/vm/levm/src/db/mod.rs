@@ -1,9 +1,11 @@
 use crate::errors::DatabaseError;
 use ethrex_common::{
     Address, H256, U256,
-    types::{AccountState, ChainConfig, Code},
+    types::{AccountState, ChainConfig, Code, CodeAnalysisCache},
 };
+use std::sync::Arc;
 
+pub mod block_access_list;
 pub mod gen_db;
 
 pub trait Database: Send + Sync {
@@ -12,4 +14,8 @@ pub trait Database: Send + Sync {
     fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError>;
     fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError>;
     fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError>;
+    /// Bytecode analysis cache kept across blocks, used for the codes deployed during execution.
+    fn code_analysis_cache(&self) -> Option<Arc<CodeAnalysisCache>> {
+        None
+    }
 }
@@ -1,6 +1,6 @@
 use ethrex_common::{
     Address, H256, U256,
-    types::{BlockHeader, ChainConfig, Fork, ForkBlobSchedule},
+    types::{BlockHeader, ChainConfig, Fork, ForkBlobSchedule, L2Precompiles},
 };
 
 use crate::constants::{
@@ -54,6 +54,10 @@ pub struct Environment {
 pub struct EVMConfig {
     pub fork: Fork,
     pub blob_schedule: ForkBlobSchedule,
+    /// Whether EOF containers are validated and executed, which isn't tied to any fork.
+    pub eof_enabled: bool,
+    /// Extra precompiles of the chain, only available when executing L2 blocks.
+    pub l2_precompiles: L2Precompiles,
 }
 
 impl EVMConfig {
@@ -61,6 +65,8 @@ impl EVMConfig {
         EVMConfig {
             fork,
             blob_schedule,
+            eof_enabled: false,
+            l2_precompiles: L2Precompiles::default(),
         }
     }
 
@@ -71,7 +77,11 @@ impl EVMConfig {
             .get_fork_blob_schedule(block_header.timestamp)
             .unwrap_or_else(|| EVMConfig::canonical_values(fork));
 
-        EVMConfig::new(fork, blob_schedule)
+        EVMConfig {
+            eof_enabled: chain_config.is_eof_activated(block_header.timestamp),
+            l2_precompiles: chain_config.l2_precompiles,
+            ..EVMConfig::new(fork, blob_schedule)
+        }
     }
 
     /// This function is used for running the EF tests. If you don't
@@ -124,6 +134,8 @@ impl Default for EVMConfig {
         EVMConfig {
             fork,
             blob_schedule: Self::canonical_values(fork),
+            eof_enabled: false,
+            l2_precompiles: L2Precompiles::default(),
         }
     }
 }
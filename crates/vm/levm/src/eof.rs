@@ -0,0 +1,1115 @@
+//! EVM Object Format (EOF) containers, as specified by [EIP-7692](https://eips.ethereum.org/EIPS/eip-7692).
+//!
+//! EOF isn't scheduled for any fork, so it's enabled by the `eof_time` of the chain config.
+//! Containers are validated once, when they are deployed, so executing them only requires
+//! parsing their header. The EOF opcodes are implemented in [`crate::opcode_handlers::eof`].
+
+use std::ops::Range;
+
+use crate::{
+    constants::{INIT_CODE_MAX_SIZE, STACK_LIMIT},
+    opcodes::Opcode,
+};
+
+pub const EOF_MAGIC: [u8; 2] = [0xEF, 0x00];
+pub const EOF_VERSION: u8 = 0x01;
+
+const KIND_TYPES: u8 = 0x01;
+const KIND_CODE: u8 = 0x02;
+const KIND_CONTAINER: u8 = 0x03;
+const KIND_DATA: u8 = 0xFF;
+const TERMINATOR: u8 = 0x00;
+
+const MAX_CODE_SECTIONS: usize = 1024;
+const MAX_CONTAINER_SECTIONS: usize = 256;
+/// Size of the entry of each code section in the types section.
+const TYPE_SIZE: usize = 4;
+/// Maximum number of inputs and outputs of a code section.
+const MAX_SECTION_IO: u8 = 0x7F;
+/// Outputs of the code sections that never return to their caller.
+pub const NON_RETURNING_SECTION: u8 = 0x80;
+/// Maximum stack height that a code section can declare.
+pub const MAX_STACK_HEIGHT: usize = 1023;
+/// Maximum number of nested CALLFs.
+pub const MAX_RETURN_STACK_DEPTH: usize = 1024;
+/// Containers can't be bigger than the max initcode size, wherever they come from.
+pub const MAX_CONTAINER_SIZE: usize = INIT_CODE_MAX_SIZE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum EofError {
+    #[error("Invalid magic")]
+    InvalidMagic,
+    #[error("Invalid version")]
+    InvalidVersion,
+    #[error("Missing or incomplete section header")]
+    InvalidHeader,
+    #[error("Types section size doesn't match the number of code sections")]
+    InvalidTypesSectionSize,
+    #[error("Invalid number of code sections")]
+    InvalidCodeSectionCount,
+    #[error("Invalid number of container sections")]
+    InvalidContainerSectionCount,
+    #[error("Section with size zero")]
+    ZeroSectionSize,
+    #[error("Section bodies don't match the sizes declared in the header")]
+    InvalidSectionBodiesSize,
+    #[error("Data section of a top level container is truncated")]
+    TopLevelContainerTruncated,
+    #[error("Container is bigger than the max initcode size")]
+    ContainerSizeAboveLimit,
+    #[error("First code section must have no inputs and be non-returning")]
+    InvalidFirstSectionType,
+    #[error("Code section inputs or outputs above limit")]
+    InputsOutputsAboveLimit,
+    #[error("Code section max stack height above limit")]
+    MaxStackHeightAboveLimit,
+    #[error("Undefined instruction")]
+    UndefinedInstruction,
+    #[error("Truncated instruction immediate")]
+    TruncatedImmediate,
+    #[error("Invalid relative jump destination")]
+    InvalidRjumpDestination,
+    #[error("Invalid code section index")]
+    InvalidCodeSectionIndex,
+    #[error("Invalid container section index")]
+    InvalidContainerSectionIndex,
+    #[error("DATALOADN reads past the data section")]
+    InvalidDataloadnIndex,
+    #[error("CALLF to a non-returning code section")]
+    CallfToNonReturning,
+    #[error("JUMPF to a code section with incompatible outputs")]
+    JumpfDestinationIncompatibleOutputs,
+    #[error("Non-returning code section returns")]
+    NonReturningSectionReturns,
+    #[error("Returning code section never returns")]
+    ReturningSectionNeverReturns,
+    #[error("Code section doesn't end with a terminating instruction")]
+    MissingStopOpcode,
+    #[error("Stack underflow")]
+    StackUnderflow,
+    #[error("Stack overflow")]
+    StackOverflow,
+    #[error("Stack height mismatch")]
+    StackHeightMismatch,
+    #[error("Max stack height doesn't match the declared one")]
+    InvalidMaxStackHeight,
+    #[error("Unreachable instructions")]
+    UnreachableInstructions,
+    #[error("Unreachable code sections")]
+    UnreachableCodeSections,
+    #[error("Instruction not allowed in this kind of container")]
+    IncompatibleContainerKind,
+    #[error("Subcontainer referenced both as initcode and runtime code")]
+    AmbiguousContainerKind,
+    #[error("Subcontainer never referenced")]
+    OrphanSubcontainer,
+    #[error("EOFCREATE of a container with a truncated data section")]
+    EofcreateWithTruncatedContainer,
+}
+
+/// How a container is meant to be executed, which determines how it can end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerKind {
+    /// Executed on creation, it can only end deploying a subcontainer with RETURNCONTRACT.
+    Initcode,
+    /// Deployed code, it can't use RETURNCONTRACT.
+    Runtime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeType {
+    pub inputs: u8,
+    pub outputs: u8,
+    pub max_stack_increase: u16,
+}
+
+impl CodeType {
+    pub fn is_returning(&self) -> bool {
+        self.outputs != NON_RETURNING_SECTION
+    }
+
+    /// Max stack height reached executing the section, counting its inputs.
+    pub fn max_stack_height(&self) -> usize {
+        usize::from(self.inputs).saturating_add(usize::from(self.max_stack_increase))
+    }
+}
+
+/// Layout of an EOF container. Sections are referenced by their position in the container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EofContainer {
+    pub types: Vec<CodeType>,
+    pub code_sections: Vec<Range<usize>>,
+    pub container_sections: Vec<Range<usize>>,
+    /// Position in the header of the data section size, which is updated when data is appended on deployment.
+    pub data_size_position: usize,
+    /// Start of the data section.
+    pub data_offset: usize,
+    /// Size of the data section declared in the header. Subcontainers can have less data,
+    /// which is completed when deploying them.
+    pub data_size: usize,
+}
+
+/// State of a call frame executing an EOF container.
+///
+/// Its PC is an offset in the whole container rather than in the current code section, so relative
+/// jumps and immediates work the same as in legacy code.
+#[derive(Debug)]
+pub struct EofFrame {
+    pub container: EofContainer,
+    /// Code section being executed.
+    pub code_section: usize,
+    /// Code sections and PCs to go back to with RETF, pushed by CALLF.
+    pub return_stack: Vec<(usize, usize)>,
+}
+
+impl EofFrame {
+    pub fn new(container: EofContainer) -> Self {
+        Self {
+            container,
+            code_section: 0,
+            return_stack: Vec::new(),
+        }
+    }
+
+    pub fn current_type(&self) -> Option<&CodeType> {
+        self.container.types.get(self.code_section)
+    }
+}
+
+/// Returns whether the code is an EOF container, even an invalid one.
+pub fn is_eof(code: &[u8]) -> bool {
+    code.starts_with(&EOF_MAGIC)
+}
+
+struct Reader<'a> {
+    code: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], EofError> {
+        let end = self
+            .position
+            .checked_add(N)
+            .ok_or(EofError::InvalidHeader)?;
+        let bytes = self
+            .code
+            .get(self.position..end)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(EofError::InvalidHeader)?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, EofError> {
+        Ok(u8::from_be_bytes(self.bytes()?))
+    }
+
+    fn u16(&mut self) -> Result<usize, EofError> {
+        Ok(usize::from(u16::from_be_bytes(self.bytes()?)))
+    }
+
+    fn u32(&mut self) -> Result<usize, EofError> {
+        usize::try_from(u32::from_be_bytes(self.bytes()?)).map_err(|_| EofError::InvalidHeader)
+    }
+
+    fn kind(&mut self, kind: u8) -> Result<(), EofError> {
+        if self.u8()? != kind {
+            return Err(EofError::InvalidHeader);
+        }
+        Ok(())
+    }
+
+    fn section_sizes(
+        &mut self,
+        max_sections: usize,
+        count_error: EofError,
+        read_size: fn(&mut Self) -> Result<usize, EofError>,
+    ) -> Result<Vec<usize>, EofError> {
+        let count = self.u16()?;
+        if count == 0 || count > max_sections {
+            return Err(count_error);
+        }
+        (0..count)
+            .map(|_| match read_size(self)? {
+                0 => Err(EofError::ZeroSectionSize),
+                size => Ok(size),
+            })
+            .collect()
+    }
+}
+
+impl EofContainer {
+    /// Parses the header and the types section of a container.
+    ///
+    /// The container may be followed by more bytes, e.g. the calldata in creation transactions,
+    /// and its data section may be truncated. See [`EofContainer::size`].
+    pub fn parse(code: &[u8]) -> Result<Self, EofError> {
+        let mut reader = Reader { code, position: 0 };
+
+        if reader.bytes::<2>().ok() != Some(EOF_MAGIC) {
+            return Err(EofError::InvalidMagic);
+        }
+        if reader.u8()? != EOF_VERSION {
+            return Err(EofError::InvalidVersion);
+        }
+
+        reader.kind(KIND_TYPES)?;
+        let types_size = reader.u16()?;
+        reader.kind(KIND_CODE)?;
+        let code_sizes = reader.section_sizes(
+            MAX_CODE_SECTIONS,
+            EofError::InvalidCodeSectionCount,
+            Reader::u16,
+        )?;
+        if Some(types_size) != code_sizes.len().checked_mul(TYPE_SIZE) {
+            return Err(EofError::InvalidTypesSectionSize);
+        }
+
+        let mut kind = reader.u8()?;
+        let container_sizes = if kind == KIND_CONTAINER {
+            let sizes = reader.section_sizes(
+                MAX_CONTAINER_SECTIONS,
+                EofError::InvalidContainerSectionCount,
+                Reader::u32,
+            )?;
+            kind = reader.u8()?;
+            sizes
+        } else {
+            Vec::new()
+        };
+        if kind != KIND_DATA {
+            return Err(EofError::InvalidHeader);
+        }
+        let data_size_position = reader.position;
+        let data_size = reader.u16()?;
+        if reader.u8()? != TERMINATOR {
+            return Err(EofError::InvalidHeader);
+        }
+
+        let types = (0..code_sizes.len())
+            .map(|_| {
+                let [inputs, outputs, max_stack_increase @ ..] = reader.bytes::<TYPE_SIZE>()?;
+                Ok(CodeType {
+                    inputs,
+                    outputs,
+                    max_stack_increase: u16::from_be_bytes(max_stack_increase),
+                })
+            })
+            .collect::<Result<Vec<_>, EofError>>()
+            .map_err(|_| EofError::InvalidSectionBodiesSize)?;
+
+        let mut position = reader.position;
+        let mut sections = |sizes: Vec<usize>| {
+            sizes
+                .into_iter()
+                .map(|size| {
+                    let start = position;
+                    position = start
+                        .checked_add(size)
+                        .ok_or(EofError::InvalidSectionBodiesSize)?;
+                    Ok(start..position)
+                })
+                .collect::<Result<Vec<_>, EofError>>()
+        };
+        let code_sections = sections(code_sizes)?;
+        let container_sections = sections(container_sizes)?;
+        if position > code.len() {
+            return Err(EofError::InvalidSectionBodiesSize);
+        }
+
+        Ok(Self {
+            types,
+            code_sections,
+            container_sections,
+            data_size_position,
+            data_offset: position,
+            data_size,
+        })
+    }
+
+    /// Size of the container, counting the whole data section declared in its header.
+    pub fn size(&self) -> usize {
+        self.data_offset.saturating_add(self.data_size)
+    }
+
+    /// The data section of the container, which may be shorter than the declared size.
+    pub fn data<'a>(&self, code: &'a [u8]) -> &'a [u8] {
+        code.get(self.data_offset..).unwrap_or_default()
+    }
+}
+
+/// Validates a container and all its subcontainers, as done before deploying it.
+pub fn validate_container(code: &[u8], kind: ContainerKind) -> Result<EofContainer, EofError> {
+    if code.len() > MAX_CONTAINER_SIZE {
+        return Err(EofError::ContainerSizeAboveLimit);
+    }
+
+    let (container, subcontainers) =
+        validate_single_container(code, kind, Some(EofError::TopLevelContainerTruncated))?;
+    let mut pending = subcontainers;
+    while let Some((code, kind)) = pending.pop() {
+        // Only the runtime code is completed with data on deployment
+        let truncation_error = match kind {
+            ContainerKind::Initcode => Some(EofError::EofcreateWithTruncatedContainer),
+            ContainerKind::Runtime => None,
+        };
+        let (_, subcontainers) = validate_single_container(code, kind, truncation_error)?;
+        pending.extend(subcontainers);
+    }
+
+    Ok(container)
+}
+
+/// Validates a container, returning its subcontainers with the kind they are used as.
+fn validate_single_container(
+    code: &[u8],
+    kind: ContainerKind,
+    truncation_error: Option<EofError>,
+) -> Result<(EofContainer, Vec<(&[u8], ContainerKind)>), EofError> {
+    let container = EofContainer::parse(code)?;
+    let data_len = container.data(code).len();
+    if data_len > container.data_size {
+        return Err(EofError::InvalidSectionBodiesSize);
+    }
+    if let Some(error) = truncation_error
+        && data_len < container.data_size
+    {
+        return Err(error);
+    }
+
+    let first_type = container.types.first().ok_or(EofError::InvalidHeader)?;
+    if first_type.inputs != 0 || first_type.is_returning() {
+        return Err(EofError::InvalidFirstSectionType);
+    }
+    for code_type in &container.types {
+        if code_type.inputs > MAX_SECTION_IO || code_type.outputs > NON_RETURNING_SECTION {
+            return Err(EofError::InputsOutputsAboveLimit);
+        }
+        if code_type.max_stack_height() > MAX_STACK_HEIGHT {
+            return Err(EofError::MaxStackHeightAboveLimit);
+        }
+    }
+
+    // Code sections are only validated when reached from the first one, as unreachable ones are invalid
+    let mut reached_sections = vec![false; container.code_sections.len()];
+    let mut subcontainer_kinds = vec![None; container.container_sections.len()];
+    let mut pending = vec![0];
+    while let Some(index) = pending.pop() {
+        if reached_sections.get(index) != Some(&false) {
+            continue;
+        }
+        if let Some(reached) = reached_sections.get_mut(index) {
+            *reached = true;
+        }
+
+        let references = validate_code_section(code, &container, index, kind)?;
+        pending.extend(references.code_sections);
+        for (subcontainer, subcontainer_kind) in references.subcontainers {
+            let entry = subcontainer_kinds
+                .get_mut(subcontainer)
+                .ok_or(EofError::InvalidContainerSectionIndex)?;
+            match entry {
+                Some(kind) if *kind != subcontainer_kind => {
+                    return Err(EofError::AmbiguousContainerKind);
+                }
+                _ => *entry = Some(subcontainer_kind),
+            }
+        }
+    }
+    if reached_sections.contains(&false) {
+        return Err(EofError::UnreachableCodeSections);
+    }
+
+    let subcontainers = container
+        .container_sections
+        .iter()
+        .zip(subcontainer_kinds)
+        .map(|(section, kind)| {
+            let kind = kind.ok_or(EofError::OrphanSubcontainer)?;
+            let code = code
+                .get(section.clone())
+                .ok_or(EofError::InvalidSectionBodiesSize)?;
+            Ok((code, kind))
+        })
+        .collect::<Result<_, EofError>>()?;
+
+    Ok((container, subcontainers))
+}
+
+#[derive(Default)]
+struct SectionReferences {
+    /// Targets of CALLF and JUMPF.
+    code_sections: Vec<usize>,
+    /// Subcontainers used by EOFCREATE and RETURNCONTRACT.
+    subcontainers: Vec<(usize, ContainerKind)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Instruction {
+    inputs: u8,
+    outputs: u8,
+    immediate_size: u8,
+    is_terminating: bool,
+}
+
+const fn instruction(inputs: u8, outputs: u8) -> Option<Instruction> {
+    Some(Instruction {
+        inputs,
+        outputs,
+        immediate_size: 0,
+        is_terminating: false,
+    })
+}
+
+const fn with_immediate(inputs: u8, outputs: u8, immediate_size: u8) -> Option<Instruction> {
+    Some(Instruction {
+        inputs,
+        outputs,
+        immediate_size,
+        is_terminating: false,
+    })
+}
+
+const fn terminating(inputs: u8, immediate_size: u8) -> Option<Instruction> {
+    Some(Instruction {
+        inputs,
+        outputs: 0,
+        immediate_size,
+        is_terminating: true,
+    })
+}
+
+/// Instructions allowed in EOF code with their stack inputs and outputs. Those of CALLF, RETF, JUMPF,
+/// DUPN, SWAPN and EXCHANGE depend on their immediates, and RJUMPV has a variable immediate size.
+///
+/// Legacy jumps, and the opcodes that observe gas or code are not allowed.
+#[allow(
+    clippy::as_conversions,
+    clippy::indexing_slicing,
+    clippy::arithmetic_side_effects
+)]
+const INSTRUCTIONS: [Option<Instruction>; 256] = {
+    let mut table = [None; 256];
+
+    table[Opcode::STOP as usize] = terminating(0, 0);
+    table[Opcode::ADD as usize] = instruction(2, 1);
+    table[Opcode::MUL as usize] = instruction(2, 1);
+    table[Opcode::SUB as usize] = instruction(2, 1);
+    table[Opcode::DIV as usize] = instruction(2, 1);
+    table[Opcode::SDIV as usize] = instruction(2, 1);
+    table[Opcode::MOD as usize] = instruction(2, 1);
+    table[Opcode::SMOD as usize] = instruction(2, 1);
+    table[Opcode::ADDMOD as usize] = instruction(3, 1);
+    table[Opcode::MULMOD as usize] = instruction(3, 1);
+    table[Opcode::EXP as usize] = instruction(2, 1);
+    table[Opcode::SIGNEXTEND as usize] = instruction(2, 1);
+    table[Opcode::LT as usize] = instruction(2, 1);
+    table[Opcode::GT as usize] = instruction(2, 1);
+    table[Opcode::SLT as usize] = instruction(2, 1);
+    table[Opcode::SGT as usize] = instruction(2, 1);
+    table[Opcode::EQ as usize] = instruction(2, 1);
+    table[Opcode::ISZERO as usize] = instruction(1, 1);
+    table[Opcode::AND as usize] = instruction(2, 1);
+    table[Opcode::OR as usize] = instruction(2, 1);
+    table[Opcode::XOR as usize] = instruction(2, 1);
+    table[Opcode::NOT as usize] = instruction(1, 1);
+    table[Opcode::BYTE as usize] = instruction(2, 1);
+    table[Opcode::SHL as usize] = instruction(2, 1);
+    table[Opcode::SHR as usize] = instruction(2, 1);
+    table[Opcode::SAR as usize] = instruction(2, 1);
+    table[Opcode::KECCAK256 as usize] = instruction(2, 1);
+    table[Opcode::ADDRESS as usize] = instruction(0, 1);
+    table[Opcode::BALANCE as usize] = instruction(1, 1);
+    table[Opcode::ORIGIN as usize] = instruction(0, 1);
+    table[Opcode::CALLER as usize] = instruction(0, 1);
+    table[Opcode::CALLVALUE as usize] = instruction(0, 1);
+    table[Opcode::CALLDATALOAD as usize] = instruction(1, 1);
+    table[Opcode::CALLDATASIZE as usize] = instruction(0, 1);
+    table[Opcode::CALLDATACOPY as usize] = instruction(3, 0);
+    table[Opcode::GASPRICE as usize] = instruction(0, 1);
+    table[Opcode::RETURNDATASIZE as usize] = instruction(0, 1);
+    table[Opcode::RETURNDATACOPY as usize] = instruction(3, 0);
+    table[Opcode::BLOCKHASH as usize] = instruction(1, 1);
+    table[Opcode::COINBASE as usize] = instruction(0, 1);
+    table[Opcode::TIMESTAMP as usize] = instruction(0, 1);
+    table[Opcode::NUMBER as usize] = instruction(0, 1);
+    table[Opcode::PREVRANDAO as usize] = instruction(0, 1);
+    table[Opcode::GASLIMIT as usize] = instruction(0, 1);
+    table[Opcode::CHAINID as usize] = instruction(0, 1);
+    table[Opcode::SELFBALANCE as usize] = instruction(0, 1);
+    table[Opcode::BASEFEE as usize] = instruction(0, 1);
+    table[Opcode::BLOBHASH as usize] = instruction(1, 1);
+    table[Opcode::BLOBBASEFEE as usize] = instruction(0, 1);
+    table[Opcode::POP as usize] = instruction(1, 0);
+    table[Opcode::MLOAD as usize] = instruction(1, 1);
+    table[Opcode::MSTORE as usize] = instruction(2, 0);
+    table[Opcode::MSTORE8 as usize] = instruction(2, 0);
+    table[Opcode::SLOAD as usize] = instruction(1, 1);
+    table[Opcode::SSTORE as usize] = instruction(2, 0);
+    table[Opcode::MSIZE as usize] = instruction(0, 1);
+    // JUMPDEST is a no-op in EOF code
+    table[Opcode::JUMPDEST as usize] = instruction(0, 0);
+    table[Opcode::TLOAD as usize] = instruction(1, 1);
+    table[Opcode::TSTORE as usize] = instruction(2, 0);
+    table[Opcode::MCOPY as usize] = instruction(3, 0);
+    table[Opcode::PUSH0 as usize] = instruction(0, 1);
+
+    let mut n = 1;
+    while n <= 32 {
+        table[Opcode::PUSH1 as usize + n - 1] = with_immediate(0, 1, n as u8);
+        n += 1;
+    }
+    let mut n = 1;
+    while n <= 16 {
+        table[Opcode::DUP1 as usize + n - 1] = instruction(n as u8, n as u8 + 1);
+        table[Opcode::SWAP1 as usize + n - 1] = instruction(n as u8 + 1, n as u8 + 1);
+        n += 1;
+    }
+    let mut n = 0;
+    while n <= 4 {
+        table[Opcode::LOG0 as usize + n] = instruction(n as u8 + 2, 0);
+        n += 1;
+    }
+
+    table[Opcode::DATALOAD as usize] = instruction(1, 1);
+    table[Opcode::DATALOADN as usize] = with_immediate(0, 1, 2);
+    table[Opcode::DATASIZE as usize] = instruction(0, 1);
+    table[Opcode::DATACOPY as usize] = instruction(3, 0);
+    table[Opcode::RJUMP as usize] = with_immediate(0, 0, 2);
+    table[Opcode::RJUMPI as usize] = with_immediate(1, 0, 2);
+    table[Opcode::RJUMPV as usize] = with_immediate(1, 0, 1);
+    table[Opcode::CALLF as usize] = with_immediate(0, 0, 2);
+    table[Opcode::RETF as usize] = terminating(0, 0);
+    table[Opcode::JUMPF as usize] = terminating(0, 2);
+    table[Opcode::DUPN as usize] = with_immediate(0, 0, 1);
+    table[Opcode::SWAPN as usize] = with_immediate(0, 0, 1);
+    table[Opcode::EXCHANGE as usize] = with_immediate(0, 0, 1);
+    table[Opcode::EOFCREATE as usize] = with_immediate(4, 1, 1);
+    table[Opcode::RETURNCONTRACT as usize] = terminating(2, 1);
+    table[Opcode::RETURN as usize] = terminating(2, 0);
+    table[Opcode::RETURNDATALOAD as usize] = instruction(1, 1);
+    table[Opcode::EXTCALL as usize] = instruction(4, 1);
+    table[Opcode::EXTDELEGATECALL as usize] = instruction(3, 1);
+    table[Opcode::EXTSTATICCALL as usize] = instruction(3, 1);
+    table[Opcode::REVERT as usize] = terminating(2, 0);
+    table[Opcode::INVALID as usize] = terminating(0, 0);
+
+    table
+};
+
+fn read_u16(code: &[u8], position: usize) -> Option<u16> {
+    let bytes = code.get(position..position.checked_add(2)?)?;
+    Some(u16::from_be_bytes(bytes.try_into().ok()?))
+}
+
+/// Relative jump offsets of RJUMP, RJUMPI and RJUMPV, which start at `immediate`.
+fn relative_jump_offsets(code: &[u8], opcode: Opcode, immediate: usize) -> Vec<i16> {
+    let read_offset = |position| read_u16(code, position).map(|offset| offset.cast_signed());
+    match opcode {
+        Opcode::RJUMP | Opcode::RJUMPI => read_offset(immediate).into_iter().collect(),
+        Opcode::RJUMPV => {
+            let count = code
+                .get(immediate)
+                .map_or(0, |max_index| usize::from(*max_index).saturating_add(1));
+            (0..count)
+                .filter_map(|index| {
+                    read_offset(
+                        immediate
+                            .saturating_add(1)
+                            .saturating_add(index.saturating_mul(2)),
+                    )
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Size of the instruction at `pc`, counting its immediate.
+fn instruction_size(code: &[u8], pc: usize, opcode: Opcode, instruction: &Instruction) -> usize {
+    let immediate_size = match opcode {
+        // The jump table has `max_index + 1` entries of 2 bytes
+        Opcode::RJUMPV => code.get(pc.saturating_add(1)).map_or(1, |max_index| {
+            usize::from(*max_index)
+                .saturating_add(1)
+                .saturating_mul(2)
+                .saturating_add(1)
+        }),
+        _ => usize::from(instruction.immediate_size),
+    };
+    immediate_size.saturating_add(1)
+}
+
+fn validate_code_section(
+    code: &[u8],
+    container: &EofContainer,
+    index: usize,
+    kind: ContainerKind,
+) -> Result<SectionReferences, EofError> {
+    let section = container
+        .code_sections
+        .get(index)
+        .and_then(|section| code.get(section.clone()))
+        .ok_or(EofError::InvalidCodeSectionIndex)?;
+    let section_type = container
+        .types
+        .get(index)
+        .ok_or(EofError::InvalidCodeSectionIndex)?;
+
+    let mut references = SectionReferences::default();
+    let mut is_instruction_start = vec![false; section.len()];
+    let mut returns = false;
+    let mut pc = 0;
+    while let Some(byte) = section.get(pc) {
+        let opcode = Opcode::from(*byte);
+        let instruction = INSTRUCTIONS
+            .get(usize::from(*byte))
+            .copied()
+            .flatten()
+            .ok_or(EofError::UndefinedInstruction)?;
+        let next_pc = pc.saturating_add(instruction_size(section, pc, opcode, &instruction));
+        if next_pc > section.len() {
+            return Err(EofError::TruncatedImmediate);
+        }
+        if let Some(is_start) = is_instruction_start.get_mut(pc) {
+            *is_start = true;
+        }
+
+        let immediate = pc.saturating_add(1);
+        let immediate_u16 = || read_u16(section, immediate).map(usize::from);
+        let immediate_u8 = || section.get(immediate).copied().map(usize::from);
+        match opcode {
+            Opcode::CALLF | Opcode::JUMPF => {
+                let target = immediate_u16().ok_or(EofError::TruncatedImmediate)?;
+                let target_type = container
+                    .types
+                    .get(target)
+                    .ok_or(EofError::InvalidCodeSectionIndex)?;
+                if opcode == Opcode::CALLF && !target_type.is_returning() {
+                    return Err(EofError::CallfToNonReturning);
+                }
+                if opcode == Opcode::JUMPF && target_type.is_returning() {
+                    if !section_type.is_returning() {
+                        return Err(EofError::JumpfDestinationIncompatibleOutputs);
+                    }
+                    returns = true;
+                }
+                references.code_sections.push(target);
+            }
+            Opcode::RETF => {
+                if !section_type.is_returning() {
+                    return Err(EofError::NonReturningSectionReturns);
+                }
+                returns = true;
+            }
+            Opcode::DATALOADN => {
+                let offset = immediate_u16().ok_or(EofError::TruncatedImmediate)?;
+                if offset.saturating_add(32) > container.data_size {
+                    return Err(EofError::InvalidDataloadnIndex);
+                }
+            }
+            Opcode::EOFCREATE | Opcode::RETURNCONTRACT => {
+                let subcontainer = immediate_u8().ok_or(EofError::TruncatedImmediate)?;
+                if subcontainer >= container.container_sections.len() {
+                    return Err(EofError::InvalidContainerSectionIndex);
+                }
+                let subcontainer_kind = if opcode == Opcode::EOFCREATE {
+                    ContainerKind::Initcode
+                } else if kind == ContainerKind::Runtime {
+                    return Err(EofError::IncompatibleContainerKind);
+                } else {
+                    ContainerKind::Runtime
+                };
+                references
+                    .subcontainers
+                    .push((subcontainer, subcontainer_kind));
+            }
+            Opcode::STOP | Opcode::RETURN if kind == ContainerKind::Initcode => {
+                return Err(EofError::IncompatibleContainerKind);
+            }
+            _ => {}
+        }
+        pc = next_pc;
+    }
+
+    if section_type.is_returning() && !returns {
+        return Err(EofError::ReturningSectionNeverReturns);
+    }
+    validate_stack(section, container, section_type, &is_instruction_start)?;
+
+    Ok(references)
+}
+
+/// Validates the stack heights of a code section, as specified by
+/// [EIP-5450](https://eips.ethereum.org/EIPS/eip-5450).
+///
+/// Instructions are visited in order, so jumps can only go backwards to instructions whose stack
+/// height is already known, and it must be the same from every path.
+fn validate_stack(
+    section: &[u8],
+    container: &EofContainer,
+    section_type: &CodeType,
+    is_instruction_start: &[bool],
+) -> Result<(), EofError> {
+    // Min and max stack heights at the start of each instruction
+    let mut heights: Vec<Option<(usize, usize)>> = vec![None; section.len()];
+    let inputs = usize::from(section_type.inputs);
+    if let Some(height) = heights.first_mut() {
+        *height = Some((inputs, inputs));
+    }
+    let mut max_height = inputs;
+
+    let mut pc = 0;
+    while let Some(byte) = section.get(pc) {
+        let opcode = Opcode::from(*byte);
+        let instruction = INSTRUCTIONS
+            .get(usize::from(*byte))
+            .copied()
+            .flatten()
+            .ok_or(EofError::UndefinedInstruction)?;
+        let (min, max) = heights
+            .get(pc)
+            .copied()
+            .flatten()
+            .ok_or(EofError::UnreachableInstructions)?;
+        let next_pc = pc.saturating_add(instruction_size(section, pc, opcode, &instruction));
+        let immediate = pc.saturating_add(1);
+        let immediate_u8 = usize::from(section.get(immediate).copied().unwrap_or_default());
+        let target_type = || {
+            read_u16(section, immediate)
+                .and_then(|target| container.types.get(usize::from(target)))
+                .ok_or(EofError::InvalidCodeSectionIndex)
+        };
+
+        let (required, outputs) = match opcode {
+            Opcode::CALLF | Opcode::JUMPF => {
+                let target_type = target_type()?;
+                if max.saturating_add(usize::from(target_type.max_stack_increase)) > STACK_LIMIT {
+                    return Err(EofError::StackOverflow);
+                }
+                if opcode == Opcode::JUMPF && target_type.is_returning() {
+                    // The target returns to our caller, so it must leave exactly our outputs
+                    let expected = usize::from(section_type.outputs)
+                        .checked_add(usize::from(target_type.inputs))
+                        .and_then(|height| height.checked_sub(usize::from(target_type.outputs)))
+                        .ok_or(EofError::JumpfDestinationIncompatibleOutputs)?;
+                    if min != max || min != expected {
+                        return Err(EofError::StackHeightMismatch);
+                    }
+                }
+                (
+                    usize::from(target_type.inputs),
+                    usize::from(target_type.outputs),
+                )
+            }
+            Opcode::RETF => {
+                let outputs = usize::from(section_type.outputs);
+                if min != max || min != outputs {
+                    return Err(EofError::StackHeightMismatch);
+                }
+                (outputs, outputs)
+            }
+            Opcode::DUPN => (
+                immediate_u8.saturating_add(1),
+                immediate_u8.saturating_add(2),
+            ),
+            Opcode::SWAPN => (
+                immediate_u8.saturating_add(2),
+                immediate_u8.saturating_add(2),
+            ),
+            Opcode::EXCHANGE => {
+                let n = (immediate_u8 >> 4).saturating_add(1);
+                let m = (immediate_u8 & 0x0F).saturating_add(1);
+                let required = n.saturating_add(m).saturating_add(1);
+                (required, required)
+            }
+            _ => (
+                usize::from(instruction.inputs),
+                usize::from(instruction.outputs),
+            ),
+        };
+        if min < required {
+            return Err(EofError::StackUnderflow);
+        }
+        // Terminating instructions don't leave anything for a next one
+        if instruction.is_terminating {
+            pc = next_pc;
+            continue;
+        }
+
+        let next_min = min.saturating_sub(required).saturating_add(outputs);
+        let next_max = max.saturating_sub(required).saturating_add(outputs);
+        if next_max > STACK_LIMIT {
+            return Err(EofError::StackOverflow);
+        }
+        max_height = max_height.max(next_max);
+
+        let mut successors = Vec::new();
+        if opcode != Opcode::RJUMP {
+            successors.push(next_pc);
+        }
+        for offset in relative_jump_offsets(section, opcode, immediate) {
+            let target = next_pc
+                .checked_add_signed(isize::from(offset))
+                .filter(|target| is_instruction_start.get(*target) == Some(&true))
+                .ok_or(EofError::InvalidRjumpDestination)?;
+            if target <= pc {
+                if heights.get(target).copied().flatten() != Some((next_min, next_max)) {
+                    return Err(EofError::StackHeightMismatch);
+                }
+            } else {
+                successors.push(target);
+            }
+        }
+
+        for successor in successors {
+            let height = heights
+                .get_mut(successor)
+                .ok_or(EofError::MissingStopOpcode)?;
+            *height = Some(match *height {
+                Some((min, max)) => (min.min(next_min), max.max(next_max)),
+                None => (next_min, next_max),
+            });
+        }
+        pc = next_pc;
+    }
+
+    if max_height != section_type.max_stack_height() {
+        return Err(EofError::InvalidMaxStackHeight);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing, clippy::arithmetic_side_effects)]
+    use super::*;
+
+    /// Builds a container with the given code sections as `(inputs, outputs, max_stack_increase, code)`.
+    fn container(
+        sections: &[(u8, u8, u16, &[u8])],
+        subcontainers: &[&[u8]],
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut header = vec![0xEF, 0x00, EOF_VERSION, KIND_TYPES];
+        header.extend(u16::try_from(sections.len() * 4).unwrap().to_be_bytes());
+        header.push(KIND_CODE);
+        header.extend(u16::try_from(sections.len()).unwrap().to_be_bytes());
+        for (_, _, _, code) in sections {
+            header.extend(u16::try_from(code.len()).unwrap().to_be_bytes());
+        }
+        if !subcontainers.is_empty() {
+            header.push(KIND_CONTAINER);
+            header.extend(u16::try_from(subcontainers.len()).unwrap().to_be_bytes());
+            for subcontainer in subcontainers {
+                header.extend(u32::try_from(subcontainer.len()).unwrap().to_be_bytes());
+            }
+        }
+        header.push(KIND_DATA);
+        header.extend(u16::try_from(data.len()).unwrap().to_be_bytes());
+        header.push(TERMINATOR);
+
+        for (inputs, outputs, max_stack_increase, _) in sections {
+            header.extend([*inputs, *outputs]);
+            header.extend(max_stack_increase.to_be_bytes());
+        }
+        for (_, _, _, code) in sections {
+            header.extend(*code);
+        }
+        for subcontainer in subcontainers {
+            header.extend(*subcontainer);
+        }
+        header.extend(data);
+        header
+    }
+
+    const STOP: u8 = 0x00;
+    const PUSH1: u8 = 0x60;
+
+    #[test]
+    fn validates_minimal_container() {
+        let code = container(&[(0, 0x80, 0, &[STOP])], &[], &[]);
+        let container = validate_container(&code, ContainerKind::Runtime).unwrap();
+        assert_eq!(container.code_sections, vec![19..20]);
+        assert_eq!(container.data_offset, 20);
+    }
+
+    #[test]
+    fn rejects_invalid_header() {
+        let code = container(&[(0, 0x80, 0, &[STOP])], &[], &[]);
+
+        let mut invalid_magic = code.clone();
+        invalid_magic[1] = 0x01;
+        assert_eq!(
+            validate_container(&invalid_magic, ContainerKind::Runtime),
+            Err(EofError::InvalidMagic)
+        );
+
+        let mut invalid_version = code.clone();
+        invalid_version[2] = 0x02;
+        assert_eq!(
+            validate_container(&invalid_version, ContainerKind::Runtime),
+            Err(EofError::InvalidVersion)
+        );
+
+        let mut trailing_bytes = code;
+        trailing_bytes.push(0x00);
+        assert_eq!(
+            validate_container(&trailing_bytes, ContainerKind::Runtime),
+            Err(EofError::InvalidSectionBodiesSize)
+        );
+    }
+
+    #[test]
+    fn rejects_legacy_and_undefined_opcodes() {
+        // JUMP
+        let code = container(&[(0, 0x80, 1, &[PUSH1, 0x00, 0x56])], &[], &[]);
+        assert_eq!(
+            validate_container(&code, ContainerKind::Runtime),
+            Err(EofError::UndefinedInstruction)
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_immediates_and_fall_through() {
+        let truncated = container(&[(0, 0x80, 1, &[PUSH1])], &[], &[]);
+        assert_eq!(
+            validate_container(&truncated, ContainerKind::Runtime),
+            Err(EofError::TruncatedImmediate)
+        );
+
+        let fall_through = container(&[(0, 0x80, 1, &[PUSH1, 0x00, 0x50])], &[], &[]);
+        assert_eq!(
+            validate_container(&fall_through, ContainerKind::Runtime),
+            Err(EofError::MissingStopOpcode)
+        );
+    }
+
+    #[test]
+    fn validates_relative_jumps() {
+        // PUSH1 1, RJUMPI +1, STOP, STOP
+        let valid = container(
+            &[(0, 0x80, 1, &[PUSH1, 0x01, 0xE1, 0x00, 0x01, STOP, STOP])],
+            &[],
+            &[],
+        );
+        assert!(validate_container(&valid, ContainerKind::Runtime).is_ok());
+
+        // RJUMP into its own immediate
+        let into_immediate = container(&[(0, 0x80, 0, &[0xE0, 0xFF, 0xFF])], &[], &[]);
+        assert_eq!(
+            validate_container(&into_immediate, ContainerKind::Runtime),
+            Err(EofError::InvalidRjumpDestination)
+        );
+
+        // Infinite loop: RJUMP -3
+        let infinite_loop = container(&[(0, 0x80, 0, &[0xE0, 0xFF, 0xFD])], &[], &[]);
+        assert!(validate_container(&infinite_loop, ContainerKind::Runtime).is_ok());
+    }
+
+    #[test]
+    fn validates_stack_heights() {
+        // POP on an empty stack
+        let underflow = container(&[(0, 0x80, 0, &[0x50, STOP])], &[], &[]);
+        assert_eq!(
+            validate_container(&underflow, ContainerKind::Runtime),
+            Err(EofError::StackUnderflow)
+        );
+
+        let wrong_max_stack = container(&[(0, 0x80, 2, &[PUSH1, 0x00, STOP])], &[], &[]);
+        assert_eq!(
+            validate_container(&wrong_max_stack, ContainerKind::Runtime),
+            Err(EofError::InvalidMaxStackHeight)
+        );
+
+        // Loop that pushes on every iteration: PUSH1 0, RJUMP -5
+        let growing_loop = container(&[(0, 0x80, 1, &[PUSH1, 0x00, 0xE0, 0xFF, 0xFB])], &[], &[]);
+        assert_eq!(
+            validate_container(&growing_loop, ContainerKind::Runtime),
+            Err(EofError::StackHeightMismatch)
+        );
+    }
+
+    #[test]
+    fn validates_code_sections() {
+        // CALLF 1, STOP | PUSH1 0, RETF
+        let valid = container(
+            &[
+                (0, 0x80, 1, &[0xE3, 0x00, 0x01, STOP]),
+                (0, 1, 1, &[PUSH1, 0x00, 0xE4]),
+            ],
+            &[],
+            &[],
+        );
+        assert!(validate_container(&valid, ContainerKind::Runtime).is_ok());
+
+        let unreachable = container(
+            &[(0, 0x80, 0, &[STOP]), (0, 1, 1, &[PUSH1, 0x00, 0xE4])],
+            &[],
+            &[],
+        );
+        assert_eq!(
+            validate_container(&unreachable, ContainerKind::Runtime),
+            Err(EofError::UnreachableCodeSections)
+        );
+
+        let callf_to_non_returning = container(
+            &[
+                (0, 0x80, 0, &[0xE3, 0x00, 0x01, STOP]),
+                (0, 0x80, 0, &[STOP]),
+            ],
+            &[],
+            &[],
+        );
+        assert_eq!(
+            validate_container(&callf_to_non_returning, ContainerKind::Runtime),
+            Err(EofError::CallfToNonReturning)
+        );
+    }
+
+    #[test]
+    fn validates_subcontainer_kinds() {
+        let runtime = container(&[(0, 0x80, 0, &[STOP])], &[], &[]);
+        // PUSH1 0, PUSH1 0, RETURNCONTRACT 0
+        let initcode = container(
+            &[(0, 0x80, 2, &[PUSH1, 0x00, PUSH1, 0x00, 0xEE, 0x00])],
+            &[&runtime],
+            &[],
+        );
+        assert!(validate_container(&initcode, ContainerKind::Initcode).is_ok());
+        assert_eq!(
+            validate_container(&initcode, ContainerKind::Runtime),
+            Err(EofError::IncompatibleContainerKind)
+        );
+
+        // The runtime code can't be the initcode of a creation
+        assert_eq!(
+            validate_container(&runtime, ContainerKind::Initcode),
+            Err(EofError::IncompatibleContainerKind)
+        );
+
+        let orphan = container(&[(0, 0x80, 0, &[STOP])], &[&runtime], &[]);
+        assert_eq!(
+            validate_container(&orphan, ContainerKind::Runtime),
+            Err(EofError::OrphanSubcontainer)
+        );
+    }
+
+    #[test]
+    fn allows_truncated_data_only_in_deployed_subcontainers() {
+        let mut truncated = container(&[(0, 0x80, 0, &[STOP])], &[], &[0xAA; 4]);
+        truncated.truncate(truncated.len() - 2);
+        assert_eq!(
+            validate_container(&truncated, ContainerKind::Runtime),
+            Err(EofError::TopLevelContainerTruncated)
+        );
+
+        let initcode = container(
+            &[(0, 0x80, 2, &[PUSH1, 0x00, PUSH1, 0x00, 0xEE, 0x00])],
+            &[&truncated],
+            &[],
+        );
+        assert!(validate_container(&initcode, ContainerKind::Initcode).is_ok());
+    }
+}
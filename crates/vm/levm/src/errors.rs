@@ -72,6 +72,8 @@ pub enum ExceptionalHalt {
     AddressAlreadyOccupied,
     #[error("Contract Output Too Big")]
     ContractOutputTooBig,
+    #[error("Invalid EOF Data Section Size")]
+    InvalidDataSectionSize,
     #[error("Offset out of bounds")]
     OutOfBounds,
     #[error("Out Of Gas")]
@@ -6,7 +6,6 @@ use crate::{
 };
 
 use bytes::Bytes;
-use ethrex_common::types::Code;
 
 impl<'a> VM<'a> {
     pub fn handle_precompile_result(
@@ -64,8 +63,8 @@ impl<'a> VM<'a> {
 
             // Set bytecode to the newly created contract.
             let contract_address = self.current_call_frame.to;
-            let code = self.current_call_frame.output.clone();
-            self.update_account_bytecode(contract_address, Code::from_bytecode(code))?;
+            let code = self.db.new_code(self.current_call_frame.output.clone());
+            self.update_account_bytecode(contract_address, code)?;
         }
 
         #[expect(clippy::as_conversions, reason = "remaining gas conversion")]
@@ -141,8 +140,9 @@ impl<'a> VM<'a> {
             .ok_or(InternalError::Overflow)?;
 
         // Revert Scenarios
-        // 1. If the first byte of code is 0xEF
-        if code.first().is_some_and(|v| v == &EOF_PREFIX) {
+        // 1. If the first byte of code is 0xEF, unless it's an EOF container deployed with
+        //    RETURNCONTRACT, which was already validated.
+        if callframe.eof.is_none() && code.first().is_some_and(|v| v == &EOF_PREFIX) {
             return Err(ExceptionalHalt::InvalidContractPrefix.into());
         }
 
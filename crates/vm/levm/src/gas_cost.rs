@@ -90,6 +90,25 @@ pub const CODECOPY_DYNAMIC_BASE: u64 = 3;
 pub const GASPRICE: u64 = 2;
 pub const CLZ: u64 = 5;
 
+// EOF opcodes, see https://eips.ethereum.org/EIPS/eip-7692
+pub const DATALOAD: u64 = 4;
+pub const DATALOADN: u64 = 3;
+pub const DATASIZE: u64 = 2;
+pub const DATACOPY_STATIC: u64 = 3;
+pub const DATACOPY_DYNAMIC_BASE: u64 = 3;
+pub const RJUMP: u64 = 2;
+pub const RJUMPI: u64 = 4;
+pub const RJUMPV: u64 = 4;
+pub const CALLF: u64 = 5;
+pub const RETF: u64 = 3;
+pub const JUMPF: u64 = 5;
+pub const EXCHANGE: u64 = 3;
+pub const RETURNDATALOAD: u64 = 3;
+pub const EOFCREATE: u64 = 32000;
+// Gas that EXTCALL, EXTDELEGATECALL and EXTSTATICCALL keep in the caller and give to the callee, at least
+pub const EXTCALL_MIN_RETAINED_GAS: u64 = 5000;
+pub const EXTCALL_MIN_CALLEE_GAS: u64 = 2300;
+
 pub const SELFDESTRUCT_STATIC: u64 = 5000;
 pub const SELFDESTRUCT_DYNAMIC: u64 = 25000;
 pub const SELFDESTRUCT_REFUND: u64 = 24000;
@@ -325,6 +344,20 @@ fn copy_behavior(
         .ok_or(OutOfGas.into())
 }
 
+pub fn datacopy(
+    new_memory_size: usize,
+    current_memory_size: usize,
+    size: usize,
+) -> Result<u64, VMError> {
+    copy_behavior(
+        new_memory_size,
+        current_memory_size,
+        size,
+        DATACOPY_DYNAMIC_BASE,
+        DATACOPY_STATIC,
+    )
+}
+
 pub fn keccak256(
     new_memory_size: usize,
     current_memory_size: usize,
@@ -688,6 +721,36 @@ pub fn call(
     )
 }
 
+/// Cost of EXTCALL, EXTDELEGATECALL and EXTSTATICCALL, not counting the gas given to the callee.
+pub fn extcall(
+    new_memory_size: usize,
+    current_memory_size: usize,
+    address_was_cold: bool,
+    address_is_empty: bool,
+    value_to_transfer: U256,
+) -> Result<u64, VMError> {
+    let memory_expansion_cost = memory::expansion_cost(new_memory_size, current_memory_size)?;
+    let address_access_cost = address_access_cost(
+        address_was_cold,
+        CALL_STATIC,
+        CALL_COLD_DYNAMIC,
+        CALL_WARM_DYNAMIC,
+    )?;
+    let value_cost = match (value_to_transfer.is_zero(), address_is_empty) {
+        (true, _) => 0,
+        (false, false) => CALL_POSITIVE_VALUE,
+        (false, true) => CALL_POSITIVE_VALUE
+            .checked_add(CALL_TO_EMPTY_ACCOUNT)
+            .ok_or(OutOfGas)?,
+    };
+
+    memory_expansion_cost
+        .checked_add(address_access_cost)
+        .ok_or(OutOfGas)?
+        .checked_add(value_cost)
+        .ok_or(OutOfGas.into())
+}
+
 pub fn callcode(
     new_memory_size: usize,
     current_memory_size: usize,
@@ -1,6 +1,7 @@
 use crate::{
     account::LevmAccount,
     constants::*,
+    eof::{ContainerKind, EofContainer, is_eof, validate_container},
     errors::{ContextResult, InternalError, TxValidationError, VMError},
     gas_cost::{self, STANDARD_TOKEN_COST, TOTAL_COST_FLOOR_PER_TOKEN},
     hooks::hook::Hook,
@@ -223,6 +224,13 @@ pub fn pay_coinbase(vm: &mut VM<'_>, gas_to_pay: u64) -> Result<(), VMError> {
         .checked_mul(priority_fee_per_gas)
         .ok_or(InternalError::Overflow)?;
 
+    if let Some(deferred_fee) = vm.deferred_coinbase_fee.as_mut() {
+        *deferred_fee = deferred_fee
+            .checked_add(coinbase_fee)
+            .ok_or(InternalError::Overflow)?;
+        return Ok(());
+    }
+
     vm.increase_account_balance(vm.env.coinbase, coinbase_fee)?;
 
     Ok(())
@@ -493,7 +501,19 @@ pub fn set_bytecode_and_code_address(vm: &mut VM<'_>) -> Result<(), VMError> {
     // Get bytecode and code_address for assigning those values to the callframe.
     let (bytecode, code_address) = if vm.is_create()? {
         // Here bytecode is the calldata and the code_address is just the created contract address.
-        let calldata = std::mem::take(&mut vm.current_call_frame.calldata);
+        let mut calldata = std::mem::take(&mut vm.current_call_frame.calldata);
+
+        // [EIP-7698] - An EOF initcontainer is followed by the calldata for its execution.
+        if vm.env.config.eof_enabled
+            && is_eof(&calldata)
+            && let Ok(container) = EofContainer::parse(&calldata)
+            && calldata.len() >= container.size()
+        {
+            let initcontainer = calldata.split_to(container.size());
+            vm.current_call_frame.calldata = calldata;
+            calldata = initcontainer;
+        }
+
         (
             // SAFETY: we don't need the hash for the initcode
             Code::from_bytecode_unchecked(calldata, H256::zero()),
@@ -512,5 +532,14 @@ pub fn set_bytecode_and_code_address(vm: &mut VM<'_>) -> Result<(), VMError> {
     vm.current_call_frame.code_address = code_address;
     vm.current_call_frame.set_code(bytecode)?;
 
+    // Deployed containers were validated when created, but initcontainers sent in a transaction
+    // are validated here. An invalid one is executed as legacy code, failing at its first byte.
+    if vm.env.config.eof_enabled {
+        let bytecode = &vm.current_call_frame.bytecode.bytecode;
+        if !vm.is_create()? || validate_container(bytecode, ContainerKind::Initcode).is_ok() {
+            vm.current_call_frame.load_eof();
+        }
+    }
+
     Ok(())
 }
@@ -2,6 +2,10 @@ pub mod backup_hook;
 pub mod default_hook;
 pub mod hook;
 pub mod l2_hook;
+pub mod step_hook;
+pub mod tracer;
 
 pub use default_hook::DefaultHook;
 pub use l2_hook::L2Hook;
+pub use step_hook::StepHook;
+pub use tracer::Tracer;
@@ -0,0 +1,13 @@
+use crate::{errors::ContextResult, vm::VM};
+
+/// Observes the execution of a transaction one opcode at a time, e.g. for profiling or debugging.
+/// Unlike [`super::hook::Hook`], it's never set by the VM itself, so it costs nothing unless a user of the VM
+/// sets `VM::step_hook`.
+pub trait StepHook {
+    /// Called before executing the opcode at `vm.current_call_frame.pc`.
+    fn on_step(&mut self, vm: &VM<'_>);
+
+    /// Called when a call frame finishes executing, before returning to its parent if there is one.
+    /// `vm.current_call_frame` is still the finished call frame.
+    fn on_call_frame_exit(&mut self, _vm: &VM<'_>, _result: &ContextResult) {}
+}
@@ -0,0 +1,264 @@
+use bytes::Bytes;
+use ethrex_common::{Address, H256, U256, tracing::CallType, types::Log};
+use serde_json::Value;
+
+use crate::{errors::ExecutionReport, vm::VM};
+
+/// Native tracer plugged into the VM through `VM::tracers`, e.g. to follow token flows or detect
+/// MEV without changing LEVM.
+///
+/// Every method has an empty default so tracers only implement the events they need. Like
+/// [`super::StepHook`], tracers are never set by the VM itself, so they cost nothing unless a user
+/// of the VM adds one.
+///
+/// Events are reported as they happen, so changes made by call frames that later revert are
+/// reported too; the `on_call_exit` of the reverting frame carries its error.
+pub trait Tracer {
+    /// Called before validating the transaction. If it turns out to be invalid, the top-level call
+    /// exits with the validation error and `on_tx_end` isn't called.
+    fn on_tx_start(&mut self, _vm: &VM<'_>) {}
+
+    /// Called once the transaction finished executing, after every fee was paid.
+    fn on_tx_end(&mut self, _vm: &VM<'_>, _report: &ExecutionReport) {}
+
+    /// Called when a call frame is entered, starting with the one of the transaction, which is
+    /// entered before fees and value are moved. For DELEGATECALL and CALLCODE, `to` is the address
+    /// whose code is executed.
+    fn on_call_enter(
+        &mut self,
+        _call_type: &CallType,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+        _gas: u64,
+        _input: &Bytes,
+    ) {
+    }
+
+    /// Called when the last entered call frame finishes. `error` is set if it reverted or halted.
+    fn on_call_exit(&mut self, _gas_used: u64, _output: &Bytes, _error: Option<&str>) {}
+
+    /// Called before executing the opcode at `vm.current_call_frame.pc`.
+    fn on_step(&mut self, _vm: &VM<'_>) {}
+
+    /// Called when SLOAD reads a storage slot.
+    fn on_storage_read(&mut self, _address: Address, _key: H256, _value: U256) {}
+
+    /// Called when SSTORE changes the value of a storage slot.
+    fn on_storage_write(&mut self, _address: Address, _key: H256, _previous: U256, _new: U256) {}
+
+    /// Called when a LOG opcode emits a log.
+    fn on_log(&mut self, _log: &Log) {}
+
+    /// Called when the balance of an account changes, including fees and value transfers.
+    fn on_balance_change(&mut self, _address: Address, _previous: U256, _new: U256) {}
+
+    /// Result of the trace, returned as is by the `debug_trace*` endpoints.
+    fn result(&mut self) -> Value {
+        Value::Null
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{
+        db::{Database, gen_db::GeneralizedDatabase},
+        environment::{EVMConfig, Environment},
+        errors::DatabaseError,
+        tracing::LevmCallTracer,
+        vm::VMType,
+    };
+    use ethrex_common::types::{
+        Account, AccountState, ChainConfig, Code, EIP1559Transaction, Fork, Transaction, TxKind,
+    };
+    use rustc_hash::FxHashMap;
+    use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+    const SENDER: u64 = 0x1000;
+    const CALLER: u64 = 0x2000;
+    const CALLEE: u64 = 0x3000;
+    const BENEFICIARY: u64 = 0x4000;
+
+    struct EmptyStore;
+
+    impl Database for EmptyStore {
+        fn get_account_state(&self, _address: Address) -> Result<AccountState, DatabaseError> {
+            Ok(AccountState::default())
+        }
+        fn get_storage_value(&self, _address: Address, _key: H256) -> Result<U256, DatabaseError> {
+            Ok(U256::zero())
+        }
+        fn get_block_hash(&self, _block_number: u64) -> Result<H256, DatabaseError> {
+            Ok(H256::zero())
+        }
+        fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+            Ok(ChainConfig::default())
+        }
+        fn get_account_code(&self, _code_hash: H256) -> Result<Code, DatabaseError> {
+            Ok(Code::default())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        TxStart,
+        Enter(String, Address),
+        Exit(Option<String>),
+        Step(Address),
+        Balance(Address),
+        TxEnd,
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<Event>,
+    }
+
+    impl Tracer for Recorder {
+        fn on_tx_start(&mut self, _vm: &VM<'_>) {
+            self.events.push(Event::TxStart);
+        }
+        fn on_tx_end(&mut self, _vm: &VM<'_>, _report: &ExecutionReport) {
+            self.events.push(Event::TxEnd);
+        }
+        fn on_call_enter(
+            &mut self,
+            call_type: &CallType,
+            _from: Address,
+            to: Address,
+            _value: U256,
+            _gas: u64,
+            _input: &Bytes,
+        ) {
+            self.events.push(Event::Enter(format!("{call_type:?}"), to));
+        }
+        fn on_call_exit(&mut self, _gas_used: u64, _output: &Bytes, error: Option<&str>) {
+            self.events.push(Event::Exit(error.map(str::to_owned)));
+        }
+        fn on_step(&mut self, vm: &VM<'_>) {
+            self.events.push(Event::Step(vm.current_call_frame.to));
+        }
+        fn on_balance_change(&mut self, address: Address, _previous: U256, _new: U256) {
+            self.events.push(Event::Balance(address));
+        }
+    }
+
+    /// Runs a transaction sending 1 wei to a contract that CALLs another contract, CREATEs an
+    /// empty one and SELFDESTRUCTs, returning the events it reported.
+    fn trace(nonce: u64) -> Vec<Event> {
+        let address = Address::from_low_u64_be;
+        // CALL(gas, CALLEE, 0, 0, 0, 0, 0), CREATE(0, 0, 0), SELFDESTRUCT(BENEFICIARY)
+        let bytecode = hex::decode(
+            "60006000600060006000613000".to_owned() + "5af150" + "600060006000f050" + "614000ff",
+        )
+        .unwrap();
+        let accounts = FxHashMap::from_iter([
+            (
+                address(SENDER),
+                Account::new(U256::MAX, Code::default(), 0, Default::default()),
+            ),
+            (
+                address(CALLER),
+                Account::new(
+                    U256::zero(),
+                    Code::from_bytecode(Bytes::from(bytecode)),
+                    1,
+                    Default::default(),
+                ),
+            ),
+            (
+                address(CALLEE),
+                Account::new(
+                    U256::zero(),
+                    Code::from_bytecode(Bytes::from_static(&[0x00])),
+                    1,
+                    Default::default(),
+                ),
+            ),
+        ]);
+        let mut db = GeneralizedDatabase::new_with_account_state(Arc::new(EmptyStore), accounts);
+
+        let gas_limit = 1_000_000;
+        let env = Environment {
+            origin: address(SENDER),
+            gas_limit,
+            block_gas_limit: gas_limit,
+            config: EVMConfig::new(Fork::Prague, EVMConfig::canonical_values(Fork::Prague)),
+            ..Default::default()
+        };
+        let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+            nonce,
+            gas_limit,
+            to: TxKind::Call(address(CALLER)),
+            value: U256::one(),
+            ..Default::default()
+        });
+
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+        let mut vm = VM::new(env, &mut db, &tx, LevmCallTracer::disabled(), VMType::L1).unwrap();
+        vm.tracers.push(recorder.clone());
+        let _ = vm.execute();
+        drop(vm);
+
+        recorder.take().events
+    }
+
+    #[test]
+    fn top_call_is_entered_before_moving_balances() {
+        let events = trace(0);
+
+        assert_eq!(events.first(), Some(&Event::TxStart));
+        assert_eq!(
+            events.get(1),
+            Some(&Event::Enter(
+                "CALL".to_owned(),
+                Address::from_low_u64_be(CALLER)
+            ))
+        );
+        assert_eq!(events.last(), Some(&Event::TxEnd));
+        assert!(events.contains(&Event::Balance(Address::from_low_u64_be(SENDER))));
+    }
+
+    #[test]
+    fn calls_are_nested_and_steps_run_inside_them() {
+        let events = trace(0);
+
+        let mut frames = Vec::new();
+        let mut calls = Vec::new();
+        for event in &events {
+            match event {
+                Event::Enter(call_type, to) => {
+                    frames.push(*to);
+                    calls.push(call_type.clone());
+                }
+                Event::Exit(error) => {
+                    assert_eq!(error, &None);
+                    assert!(frames.pop().is_some(), "exit without a matching enter");
+                }
+                Event::Step(to) => assert_eq!(frames.last(), Some(to)),
+                Event::Balance(_) => assert!(!frames.is_empty()),
+                Event::TxStart | Event::TxEnd => assert!(frames.is_empty()),
+            }
+        }
+        assert!(frames.is_empty());
+        assert_eq!(calls, ["CALL", "CALL", "CREATE", "SELFDESTRUCT"]);
+        assert!(events.contains(&Event::Step(Address::from_low_u64_be(CALLEE))));
+        assert!(events.contains(&Event::Enter(
+            "SELFDESTRUCT".to_owned(),
+            Address::from_low_u64_be(BENEFICIARY)
+        )));
+    }
+
+    #[test]
+    fn invalid_transaction_exits_the_top_call_with_the_error() {
+        let events = trace(5);
+
+        assert!(matches!(
+            events.as_slice(),
+            [Event::TxStart, Event::Enter(..), Event::Exit(Some(_))]
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+//! Precompiles that L2 chains can enable through their chain config, on top of the ones of their
+//! fork. Since they're part of the chain config, the guest program executes them the same way.
+
+use bytes::Bytes;
+use ed25519_dalek::{Signature, VerifyingKey};
+use ethrex_common::{
+    Address, U256,
+    types::{L1BlockHashPrecompileConfig, L2PrecompileConfig, L2Precompiles},
+    utils::keccak,
+};
+use lambdaworks_crypto::hash::poseidon::{Poseidon, starknet::PoseidonCairoStark252};
+use lambdaworks_math::{
+    field::{
+        element::FieldElement, fields::fft_friendly::stark_252_prime_field::Stark252PrimeField,
+    },
+    traits::ByteConversion,
+};
+
+use crate::{
+    db::gen_db::GeneralizedDatabase,
+    errors::{PrecompileError, VMError},
+    precompiles::increase_precompile_consumed_gas,
+};
+
+type StarkFieldElement = FieldElement<Stark252PrimeField>;
+
+const ED25519_PUBLIC_KEY_LENGTH: usize = 32;
+const ED25519_SIGNATURE_LENGTH: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum L2Precompile {
+    /// Poseidon hash over the Stark252 field, as used by Starknet.
+    Poseidon(L2PrecompileConfig),
+    /// Ed25519 signature verification.
+    Ed25519Verify(L2PrecompileConfig),
+    /// L1 block hashes, read from the contract the sequencer keeps updated.
+    L1BlockHash(L1BlockHashPrecompileConfig),
+}
+
+impl L2Precompile {
+    fn config(&self) -> &L2PrecompileConfig {
+        match self {
+            L2Precompile::Poseidon(config) | L2Precompile::Ed25519Verify(config) => config,
+            L2Precompile::L1BlockHash(config) => &config.precompile,
+        }
+    }
+}
+
+/// Returns the L2 precompile enabled at `address`, if any.
+pub fn find_l2_precompile(precompiles: &L2Precompiles, address: Address) -> Option<L2Precompile> {
+    if let Some(config) = precompiles.poseidon.filter(|c| c.address == address) {
+        return Some(L2Precompile::Poseidon(config));
+    }
+    if let Some(config) = precompiles.ed25519_verify.filter(|c| c.address == address) {
+        return Some(L2Precompile::Ed25519Verify(config));
+    }
+    precompiles
+        .l1_block_hash
+        .filter(|c| c.precompile.address == address)
+        .map(L2Precompile::L1BlockHash)
+}
+
+pub fn execute_l2_precompile(
+    precompile: L2Precompile,
+    calldata: &Bytes,
+    gas_remaining: &mut u64,
+    db: &mut GeneralizedDatabase,
+) -> Result<Bytes, VMError> {
+    let gas_cost = l2_precompile_gas_cost(precompile.config(), calldata.len())?;
+    increase_precompile_consumed_gas(gas_cost, gas_remaining)?;
+
+    match precompile {
+        L2Precompile::Poseidon(_) => poseidon(calldata),
+        L2Precompile::Ed25519Verify(_) => ed25519_verify(calldata),
+        L2Precompile::L1BlockHash(config) => l1_block_hash(calldata, config.source, db),
+    }
+}
+
+/// `base_gas + word_gas * ceil(len / 32)`
+fn l2_precompile_gas_cost(config: &L2PrecompileConfig, len: usize) -> Result<u64, VMError> {
+    let words = u64::try_from(len.div_ceil(32)).map_err(|_| PrecompileError::NotEnoughGas)?;
+    config
+        .word_gas
+        .checked_mul(words)
+        .and_then(|words_gas| words_gas.checked_add(config.base_gas))
+        .ok_or(PrecompileError::NotEnoughGas.into())
+}
+
+/// Hashes the input, a sequence of 32-byte big-endian Stark252 field elements, returning the
+/// resulting element in the same encoding.
+fn poseidon(calldata: &Bytes) -> Result<Bytes, VMError> {
+    if !calldata.len().is_multiple_of(32) {
+        return Err(PrecompileError::ParsingInputError.into());
+    }
+
+    let inputs = calldata
+        .chunks_exact(32)
+        .map(|chunk| {
+            let element = StarkFieldElement::from_bytes_be(chunk)
+                .map_err(|_| PrecompileError::ParsingInputError)?;
+            // Elements are reduced when parsed, so values over the modulus don't round-trip.
+            if element.to_bytes_be() != chunk {
+                return Err(PrecompileError::ParsingInputError);
+            }
+            Ok(element)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let hash = PoseidonCairoStark252::hash_many(&inputs);
+    Ok(Bytes::from(hash.to_bytes_be()))
+}
+
+/// Input is `public_key (32) || signature (64) || message`. Like P256VERIFY, returns 1 as a
+/// 32-byte word if the signature is valid and empty output otherwise.
+fn ed25519_verify(calldata: &Bytes) -> Result<Bytes, VMError> {
+    let Some((public_key, rest)) = calldata.split_first_chunk::<ED25519_PUBLIC_KEY_LENGTH>() else {
+        return Ok(Bytes::new());
+    };
+    let Some((signature, message)) = rest.split_first_chunk::<ED25519_SIGNATURE_LENGTH>() else {
+        return Ok(Bytes::new());
+    };
+
+    let Ok(verifying_key) = VerifyingKey::from_bytes(public_key) else {
+        return Ok(Bytes::new());
+    };
+    let signature = Signature::from_bytes(signature);
+
+    if verifying_key.verify_strict(message, &signature).is_err() {
+        return Ok(Bytes::new());
+    }
+
+    Ok(Bytes::from(U256::one().to_big_endian().to_vec()))
+}
+
+/// Input is the 32-byte L1 block number, output its hash as stored in the source contract's
+/// `mapping(uint256 => bytes32)` at slot 0, or zero if it wasn't relayed yet.
+fn l1_block_hash(
+    calldata: &Bytes,
+    source: Address,
+    db: &mut GeneralizedDatabase,
+) -> Result<Bytes, VMError> {
+    if calldata.len() != 32 {
+        return Err(PrecompileError::ParsingInputError.into());
+    }
+
+    // Solidity mapping slot: keccak(key || mapping slot)
+    let slot = keccak([calldata.as_ref(), &[0u8; 32]].concat());
+
+    // Goes through the cache so that the writes of the block and transaction are seen
+    db.get_account(source)?;
+    let hash = db.get_storage_value(source, slot)?;
+    Ok(Bytes::from(hash.to_big_endian().to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::indexing_slicing, clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{
+        db::Database,
+        environment::{EVMConfig, Environment},
+        errors::DatabaseError,
+        tracing::LevmCallTracer,
+        vm::{VM, VMType},
+    };
+    use ed25519_dalek::{Signer, SigningKey};
+    use ethrex_common::{
+        H256,
+        types::{
+            Account, AccountState, ChainConfig, Code, EIP1559Transaction, Fork, Transaction, TxKind,
+        },
+    };
+    use rustc_hash::FxHashMap;
+    use std::sync::Arc;
+
+    fn config(base_gas: u64, word_gas: u64) -> L2PrecompileConfig {
+        L2PrecompileConfig {
+            address: Address::from_low_u64_be(0xa01),
+            base_gas,
+            word_gas,
+        }
+    }
+
+    #[test]
+    fn gas_cost_rounds_words_up() {
+        assert_eq!(l2_precompile_gas_cost(&config(200, 20), 0).unwrap(), 200);
+        assert_eq!(l2_precompile_gas_cost(&config(200, 20), 33).unwrap(), 240);
+        assert!(l2_precompile_gas_cost(&config(1, u64::MAX), 64).is_err());
+    }
+
+    #[test]
+    fn finds_precompiles_by_address() {
+        let precompiles = L2Precompiles {
+            poseidon: Some(config(200, 20)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            find_l2_precompile(&precompiles, Address::from_low_u64_be(0xa01)),
+            Some(L2Precompile::Poseidon(_))
+        ));
+        assert!(find_l2_precompile(&precompiles, Address::from_low_u64_be(0xa02)).is_none());
+    }
+
+    #[test]
+    fn poseidon_rejects_non_canonical_elements() {
+        assert!(poseidon(&Bytes::from(vec![0xff; 32])).is_err());
+        assert!(poseidon(&Bytes::from(vec![0; 31])).is_err());
+        assert_eq!(poseidon(&Bytes::from(vec![0; 64])).unwrap().len(), 32);
+    }
+
+    #[test]
+    fn ed25519_verifies_signatures() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let message = b"ethrex";
+        let signature = signing_key.sign(message);
+
+        let mut input = signing_key.verifying_key().to_bytes().to_vec();
+        input.extend_from_slice(&signature.to_bytes());
+        input.extend_from_slice(message);
+        let output = ed25519_verify(&Bytes::from(input.clone())).unwrap();
+        assert_eq!(U256::from_big_endian(&output), U256::one());
+
+        *input.last_mut().unwrap() ^= 1;
+        assert!(ed25519_verify(&Bytes::from(input)).unwrap().is_empty());
+    }
+
+    const SOURCE: u64 = 0xfff0;
+    const L1_BLOCK_HASH: u64 = 0xa03;
+
+    /// Serves the hash of L1 block 7 from the source contract, and empty accounts otherwise.
+    struct L1BlockHashStore;
+
+    impl Database for L1BlockHashStore {
+        fn get_account_state(&self, _address: Address) -> Result<AccountState, DatabaseError> {
+            Ok(AccountState::default())
+        }
+        fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+            let slot = keccak([U256::from(7).to_big_endian(), [0; 32]].concat());
+            if address == Address::from_low_u64_be(SOURCE) && key == slot {
+                return Ok(U256::from(0xb10c));
+            }
+            Ok(U256::zero())
+        }
+        fn get_block_hash(&self, _block_number: u64) -> Result<H256, DatabaseError> {
+            Ok(H256::zero())
+        }
+        fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+            Ok(ChainConfig::default())
+        }
+        fn get_account_code(&self, _code_hash: H256) -> Result<Code, DatabaseError> {
+            Ok(Code::default())
+        }
+    }
+
+    #[test]
+    fn l1_block_hash_is_callable_from_a_transaction() {
+        let sender = Address::from_low_u64_be(0x1000);
+        let caller = Address::from_low_u64_be(0x2000);
+        // STATICCALLs the precompile with block number 7 and returns its output
+        let bytecode = Bytes::from(
+            hex::decode("6007600052602060006020600061".to_owned() + "0a03" + "5afa5060206000f3")
+                .unwrap(),
+        );
+        let accounts = FxHashMap::from_iter([
+            (
+                sender,
+                Account::new(U256::MAX, Code::default(), 0, Default::default()),
+            ),
+            (
+                caller,
+                Account::new(
+                    U256::zero(),
+                    Code::from_bytecode(bytecode),
+                    1,
+                    Default::default(),
+                ),
+            ),
+        ]);
+        let mut db =
+            GeneralizedDatabase::new_with_account_state(Arc::new(L1BlockHashStore), accounts);
+
+        let gas_limit = 100_000;
+        let l2_precompiles = L2Precompiles {
+            l1_block_hash: Some(L1BlockHashPrecompileConfig {
+                precompile: L2PrecompileConfig {
+                    address: Address::from_low_u64_be(L1_BLOCK_HASH),
+                    base_gas: 2100,
+                    word_gas: 0,
+                },
+                source: Address::from_low_u64_be(SOURCE),
+            }),
+            ..Default::default()
+        };
+        let env = Environment {
+            origin: sender,
+            gas_limit,
+            block_gas_limit: gas_limit,
+            config: EVMConfig {
+                l2_precompiles,
+                ..EVMConfig::new(Fork::Prague, EVMConfig::canonical_values(Fork::Prague))
+            },
+            ..Default::default()
+        };
+        let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+            gas_limit,
+            to: TxKind::Call(caller),
+            ..Default::default()
+        });
+
+        let mut vm = VM::new(
+            env,
+            &mut db,
+            &tx,
+            LevmCallTracer::disabled(),
+            VMType::L2(Default::default()),
+        )
+        .unwrap();
+        assert!(
+            vm.substate
+                .is_address_accessed(&Address::from_low_u64_be(L1_BLOCK_HASH))
+        );
+        let report = vm.execute().unwrap();
+
+        assert!(report.is_success());
+        assert_eq!(U256::from_big_endian(&report.output), U256::from(0xb10c));
+    }
+}
@@ -3,10 +3,12 @@ pub mod constants;
 pub mod db;
 pub mod debug;
 pub mod environment;
+pub mod eof;
 pub mod errors;
 pub mod execution_handlers;
 pub mod gas_cost;
 pub mod hooks;
+pub mod l2_precompiles;
 pub mod memory;
 pub mod opcode_handlers;
 pub mod opcodes;
@@ -1,7 +1,10 @@
 use crate::{
+    constants::EOF_CODE_HASH,
+    eof::{EOF_MAGIC, is_eof},
     errors::{ExceptionalHalt, InternalError, OpcodeResult, VMError},
     gas_cost::{self},
     memory::calculate_memory_size,
+    opcode_handlers::eof::copy_padded,
     utils::{size_offset_to_usize, u256_to_usize, word_to_address},
     vm::VM,
 };
@@ -287,7 +290,13 @@ impl<'a> VM<'a> {
         let address = word_to_address(self.current_call_frame.stack.pop1()?);
         let address_was_cold = !self.substate.add_accessed_address(address);
         // FIXME: a bit wasteful to fetch the whole code just to get the length.
-        let account_code_length = self.db.get_account_code(address)?.bytecode.len().into();
+        let account_code = &self.db.get_account_code(address)?.bytecode;
+        // Legacy code sees EOF code as just its magic.
+        let account_code_length = if self.env.config.eof_enabled && is_eof(account_code) {
+            EOF_MAGIC.len().into()
+        } else {
+            account_code.len().into()
+        };
 
         let current_call_frame = &mut self.current_call_frame;
 
@@ -325,14 +334,20 @@ impl<'a> VM<'a> {
 
         // If the bytecode is a delegation designation, it will copy the marker (0xef0100) || address.
         // https://eips.ethereum.org/EIPS/eip-7702#delegation-designation
-        let bytecode = self.db.get_account_code(address)?;
+        // Legacy code sees EOF code as just its magic.
+        let bytecode = &self.db.get_account_code(address)?.bytecode;
+        let bytecode: &[u8] = if self.env.config.eof_enabled && is_eof(bytecode) {
+            &EOF_MAGIC
+        } else {
+            bytecode
+        };
 
         // Happiest fast path, copy without an intermediate buffer because there is no need to pad 0s and also size doesn't overflow.
         if let Some(offset_end) = offset.checked_add(size)
-            && offset_end <= bytecode.bytecode.len()
+            && offset_end <= bytecode.len()
         {
             #[expect(unsafe_code, reason = "bounds checked beforehand")]
-            let slice = unsafe { bytecode.bytecode.get_unchecked(offset..offset_end) };
+            let slice = unsafe { bytecode.get_unchecked(offset..offset_end) };
             self.current_call_frame
                 .memory
                 .store_data(dest_offset, slice)?;
@@ -341,15 +356,15 @@ impl<'a> VM<'a> {
         }
 
         let mut data = vec![0u8; size];
-        if offset < bytecode.bytecode.len() {
-            let diff = bytecode.bytecode.len().wrapping_sub(offset);
+        if offset < bytecode.len() {
+            let diff = bytecode.len().wrapping_sub(offset);
             let final_size = size.min(diff);
             let end = offset.wrapping_add(final_size);
 
             #[expect(unsafe_code, reason = "bounds checked beforehand")]
             unsafe {
                 data.get_unchecked_mut(..final_size)
-                    .copy_from_slice(bytecode.bytecode.get_unchecked(offset..end));
+                    .copy_from_slice(bytecode.get_unchecked(offset..end));
             }
         }
 
@@ -378,8 +393,12 @@ impl<'a> VM<'a> {
         let [dest_offset, returndata_offset, size] = *current_call_frame.stack.pop()?;
 
         let (size, dest_offset) = size_offset_to_usize(size, dest_offset)?;
-        let returndata_offset =
-            u256_to_usize(returndata_offset).map_err(|_| ExceptionalHalt::OutOfBounds)?;
+        let returndata_offset = match u256_to_usize(returndata_offset) {
+            Ok(returndata_offset) => returndata_offset,
+            // EOF code reads out of bounds return data as zeros.
+            Err(_) if current_call_frame.eof.is_some() => usize::MAX,
+            Err(_) => return Err(ExceptionalHalt::OutOfBounds.into()),
+        };
 
         let new_memory_size = calculate_memory_size(dest_offset, size)?;
 
@@ -395,6 +414,13 @@ impl<'a> VM<'a> {
 
         let sub_return_data_len = current_call_frame.sub_return_data.len();
 
+        // EOF code reads out of bounds return data as zeros, like the rest of the copy opcodes.
+        if current_call_frame.eof.is_some() {
+            let data = copy_padded(&current_call_frame.sub_return_data, returndata_offset, size);
+            current_call_frame.memory.store_data(dest_offset, &data)?;
+            return Ok(OpcodeResult::Continue);
+        }
+
         let copy_limit = returndata_offset
             .checked_add(size)
             .ok_or(ExceptionalHalt::VeryLargeNumber)?;
@@ -421,6 +447,14 @@ impl<'a> VM<'a> {
         let account = self.db.get_account(address)?;
         let account_is_empty = account.is_empty();
         let account_code_hash = account.info.code_hash.0;
+        // Legacy code sees EOF code as just its magic.
+        let account_code_hash = if self.env.config.eof_enabled
+            && is_eof(&self.db.get_account_code(address)?.bytecode)
+        {
+            EOF_CODE_HASH.0
+        } else {
+            account_code_hash
+        };
         let current_call_frame = &mut self.current_call_frame;
 
         current_call_frame.increase_consumed_gas(gas_cost::extcodehash(address_was_cold)?)?;
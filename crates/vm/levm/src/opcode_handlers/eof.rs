@@ -0,0 +1,684 @@
+use crate::{
+    call_frame::CallFrame,
+    constants::{EXTCALL_REVERT, FAIL, STACK_LIMIT, WORD_SIZE},
+    eof::{EofContainer, EofFrame, MAX_RETURN_STACK_DEPTH, is_eof},
+    errors::{ExceptionalHalt, InternalError, OpcodeResult, VMError},
+    gas_cost::{self, max_message_call_gas},
+    memory::{calculate_memory_size, expansion_cost},
+    utils::{word_to_address, *},
+    vm::VM,
+};
+use bytes::Bytes;
+use ethrex_common::{
+    H256, U256,
+    tracing::CallType::{self, CALL, DELEGATECALL, STATICCALL},
+    types::Code,
+    utils::u256_from_big_endian_const,
+};
+use std::ops::Range;
+
+// EOF Operations (19)
+// Opcodes: DATALOAD, DATALOADN, DATASIZE, DATACOPY, RJUMP, RJUMPI, RJUMPV, CALLF, RETF, JUMPF,
+// DUPN, SWAPN, EXCHANGE, EOFCREATE, RETURNCONTRACT, RETURNDATALOAD, EXTCALL, EXTDELEGATECALL,
+// EXTSTATICCALL
+//
+// These opcodes are only part of the opcode table when EOF is enabled, and they are only valid
+// inside EOF containers. Legacy code executing them halts as with any undefined opcode.
+
+impl<'a> VM<'a> {
+    // DATALOAD operation
+    pub fn op_dataload(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::DATALOAD)?;
+
+        let offset = u256_to_usize(call_frame.stack.pop1()?).unwrap_or(usize::MAX);
+        let word = word_from_padded(data_section(call_frame)?, offset);
+        call_frame.stack.push(word)?;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // DATALOADN operation
+    pub fn op_dataloadn(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::DATALOADN)?;
+
+        let offset = usize::from(u16::from_be_bytes(read_immediate(call_frame)?));
+        let word = word_from_padded(data_section(call_frame)?, offset);
+        call_frame.stack.push(word)?;
+
+        call_frame.pc = call_frame.pc.wrapping_add(2);
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // DATASIZE operation
+    pub fn op_datasize(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::DATASIZE)?;
+
+        let data_size = data_section(call_frame)?.len();
+        call_frame.stack.push(data_size.into())?;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // DATACOPY operation
+    pub fn op_datacopy(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        let [mem_offset, offset, size] = *call_frame.stack.pop()?;
+
+        let (size, mem_offset) = size_offset_to_usize(size, mem_offset)?;
+        let offset = u256_to_usize(offset).unwrap_or(usize::MAX);
+        let new_memory_size = calculate_memory_size(mem_offset, size)?;
+
+        call_frame.increase_consumed_gas(gas_cost::datacopy(
+            new_memory_size,
+            call_frame.memory.len(),
+            size,
+        )?)?;
+
+        if size == 0 {
+            return Ok(OpcodeResult::Continue);
+        }
+
+        let data = copy_padded(data_section(call_frame)?, offset, size);
+        call_frame.memory.store_data(mem_offset, &data)?;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // RJUMP operation
+    pub fn op_rjump(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::RJUMP)?;
+
+        let offset = i16::from_be_bytes(read_immediate(call_frame)?);
+        relative_jump(call_frame, 2, offset)?;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // RJUMPI operation
+    pub fn op_rjumpi(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::RJUMPI)?;
+
+        let offset = i16::from_be_bytes(read_immediate(call_frame)?);
+        let condition = call_frame.stack.pop1()?;
+        relative_jump(call_frame, 2, if condition.is_zero() { 0 } else { offset })?;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // RJUMPV operation
+    pub fn op_rjumpv(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::RJUMPV)?;
+
+        let [max_index] = read_immediate(call_frame)?;
+        let case = call_frame.stack.pop1()?;
+
+        // The immediate is the max index followed by a 2 byte offset for each case.
+        let table_size = usize::from(max_index)
+            .checked_add(1)
+            .and_then(|cases| cases.checked_mul(2))
+            .ok_or(InternalError::Overflow)?;
+        let immediate_size = table_size.checked_add(1).ok_or(InternalError::Overflow)?;
+
+        let offset = match u8::try_from(case) {
+            Ok(case) if case <= max_index => {
+                let position = usize::from(case)
+                    .checked_mul(2)
+                    .and_then(|position| position.checked_add(call_frame.pc)?.checked_add(1))
+                    .ok_or(InternalError::Overflow)?;
+                let offset = call_frame
+                    .bytecode
+                    .bytecode
+                    .get(position..position.wrapping_add(2))
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or(InternalError::Slicing)?;
+                i16::from_be_bytes(offset)
+            }
+            _ => 0,
+        };
+        relative_jump(call_frame, immediate_size, offset)?;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // CALLF operation
+    pub fn op_callf(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::CALLF)?;
+
+        let target = usize::from(u16::from_be_bytes(read_immediate(call_frame)?));
+        let return_pc = call_frame
+            .pc
+            .checked_add(2)
+            .ok_or(InternalError::Overflow)?;
+        let target_start = check_section_stack_overflow(call_frame, target)?;
+
+        let eof = call_frame
+            .eof
+            .as_mut()
+            .ok_or(ExceptionalHalt::InvalidOpcode)?;
+        if eof.return_stack.len() >= MAX_RETURN_STACK_DEPTH {
+            return Err(ExceptionalHalt::StackOverflow.into());
+        }
+        let code_section = eof.code_section;
+        eof.return_stack.push((code_section, return_pc));
+        eof.code_section = target;
+        call_frame.pc = target_start;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // RETF operation
+    pub fn op_retf(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::RETF)?;
+
+        let eof = call_frame
+            .eof
+            .as_mut()
+            .ok_or(ExceptionalHalt::InvalidOpcode)?;
+        // Validation ensures only sections called with CALLF can return.
+        let (code_section, pc) = eof
+            .return_stack
+            .pop()
+            .ok_or_else(|| InternalError::msg("RETF with an empty return stack"))?;
+        eof.code_section = code_section;
+        call_frame.pc = pc;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // JUMPF operation
+    pub fn op_jumpf(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::JUMPF)?;
+
+        let target = usize::from(u16::from_be_bytes(read_immediate(call_frame)?));
+        let target_start = check_section_stack_overflow(call_frame, target)?;
+
+        let eof = call_frame
+            .eof
+            .as_mut()
+            .ok_or(ExceptionalHalt::InvalidOpcode)?;
+        eof.code_section = target;
+        call_frame.pc = target_start;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // DUPN operation
+    pub fn op_dupn(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::DUPN)?;
+
+        let [n] = read_immediate(call_frame)?;
+        call_frame.stack.dup_n(usize::from(n))?;
+
+        call_frame.pc = call_frame.pc.wrapping_add(1);
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // SWAPN operation
+    pub fn op_swapn(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::SWAPN)?;
+
+        let [n] = read_immediate(call_frame)?;
+        call_frame
+            .stack
+            .exchange(0, usize::from(n).wrapping_add(1))?;
+
+        call_frame.pc = call_frame.pc.wrapping_add(1);
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // EXCHANGE operation
+    pub fn op_exchange(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::EXCHANGE)?;
+
+        // The high nibble is the depth of the first value and the low one the distance to the
+        // second, both minus one.
+        let [immediate] = read_immediate(call_frame)?;
+        let n = usize::from(immediate >> 4).wrapping_add(1);
+        let m = usize::from(immediate & 0x0F).wrapping_add(1);
+        call_frame.stack.exchange(n, n.wrapping_add(m))?;
+
+        call_frame.pc = call_frame.pc.wrapping_add(1);
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // EOFCREATE operation
+    pub fn op_eofcreate(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        if call_frame.is_static {
+            return Err(ExceptionalHalt::OpcodeNotAllowedInStaticContext.into());
+        }
+
+        let [initcontainer_index] = read_immediate(call_frame)?;
+        let [value, salt, input_offset, input_size] = *call_frame.stack.pop()?;
+        let (input_size, input_offset) = size_offset_to_usize(input_size, input_offset)?;
+        let new_memory_size = calculate_memory_size(input_offset, input_size)?;
+
+        call_frame.increase_consumed_gas(
+            gas_cost::EOFCREATE
+                .checked_add(expansion_cost(new_memory_size, call_frame.memory.len())?)
+                .ok_or(ExceptionalHalt::OutOfGas)?,
+        )?;
+
+        let initcontainer_range = container_section(call_frame, initcontainer_index)?;
+        let initcontainer = call_frame.bytecode.bytecode.slice(initcontainer_range);
+        let input = call_frame.memory.load_range(input_offset, input_size)?;
+
+        call_frame.pc = call_frame.pc.wrapping_add(1);
+
+        // Clear callframe subreturn data
+        call_frame.sub_return_data = Bytes::new();
+
+        // Reserve gas for subcall
+        let gas_limit = max_message_call_gas(call_frame)?;
+        call_frame.increase_consumed_gas(gas_limit)?;
+
+        let deployer = call_frame.to;
+        let (deployer_balance, deployer_nonce) = {
+            let deployer_account = self.db.get_account(deployer)?;
+            (deployer_account.info.balance, deployer_account.info.nonce)
+        };
+
+        let new_address = calculate_eofcreate_address(deployer, salt)?;
+        self.substate.add_accessed_address(new_address);
+
+        self.trace_call_enter(
+            CallType::CREATE2,
+            deployer,
+            new_address,
+            value,
+            gas_limit,
+            &initcontainer,
+        );
+
+        let new_depth = self
+            .current_call_frame
+            .depth
+            .checked_add(1)
+            .ok_or(InternalError::Overflow)?;
+
+        // Same light failures as CREATE and CREATE2, which push 0 and return the reserved gas.
+        let checks = [
+            (deployer_balance < value, "OutOfFund"),
+            (new_depth > 1024, "MaxDepth"),
+            (deployer_nonce == u64::MAX, "MaxNonce"),
+        ];
+        for (condition, reason) in checks {
+            if condition {
+                self.early_revert_message_call(gas_limit, FAIL, reason.to_string())?;
+                return Ok(OpcodeResult::Continue);
+            }
+        }
+
+        self.increment_account_nonce(deployer)?;
+
+        let new_account = self.get_account_mut(new_address)?;
+        if new_account.create_would_collide() {
+            self.current_call_frame.stack.push(FAIL)?;
+            self.trace_call_exit_early(gas_limit, Some("CreateAccExists".to_string()))?;
+            return Ok(OpcodeResult::Continue);
+        }
+
+        let mut stack = self.stack_pool.pop().unwrap_or_default();
+        stack.clear();
+
+        let next_memory = self.current_call_frame.memory.next_memory();
+
+        let mut new_call_frame = CallFrame::new(
+            deployer,
+            new_address,
+            new_address,
+            // SAFETY: init code hash is never used
+            Code::from_bytecode_unchecked(initcontainer, H256::zero()),
+            value,
+            input,
+            false,
+            gas_limit,
+            new_depth,
+            true,
+            true,
+            0,
+            0,
+            stack,
+            next_memory,
+        );
+        // The initcontainer was validated along with the container deploying it.
+        new_call_frame.load_eof();
+        self.add_callframe(new_call_frame);
+
+        // Changes that revert in case the creation fails.
+        self.increment_account_nonce(new_address)?; // 0 -> 1
+        self.transfer(deployer, new_address, value)?;
+
+        self.substate.push_backup();
+        self.substate.add_created_account(new_address);
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // RETURNCONTRACT operation
+    pub fn op_returncontract(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        // Validation only allows RETURNCONTRACT in initcontainers, which are only executed by
+        // creations.
+        if !call_frame.is_create {
+            return Err(ExceptionalHalt::InvalidOpcode.into());
+        }
+
+        let [container_index] = read_immediate(call_frame)?;
+        let [aux_data_offset, aux_data_size] = *call_frame.stack.pop()?;
+        let (aux_data_size, aux_data_offset) =
+            size_offset_to_usize(aux_data_size, aux_data_offset)?;
+        let new_memory_size = calculate_memory_size(aux_data_offset, aux_data_size)?;
+
+        call_frame.increase_consumed_gas(gas_cost::exit_opcode(
+            new_memory_size,
+            call_frame.memory.len(),
+        )?)?;
+
+        let container_range = container_section(call_frame, container_index)?;
+        let aux_data = call_frame
+            .memory
+            .load_range(aux_data_offset, aux_data_size)?;
+        let container = call_frame
+            .bytecode
+            .bytecode
+            .get(container_range)
+            .ok_or(InternalError::Slicing)?;
+
+        // The subcontainer was validated, so its header can be parsed.
+        let header = EofContainer::parse(container)
+            .map_err(|_| InternalError::msg("Invalid container deployed by RETURNCONTRACT"))?;
+
+        // The data section of the deployed container is completed with the auxiliary data, and
+        // its size in the header updated to the final one.
+        let data_size = container
+            .len()
+            .saturating_sub(header.data_offset)
+            .checked_add(aux_data.len())
+            .ok_or(ExceptionalHalt::InvalidDataSectionSize)?;
+        if data_size < header.data_size {
+            return Err(ExceptionalHalt::InvalidDataSectionSize.into());
+        }
+        let data_size =
+            u16::try_from(data_size).map_err(|_| ExceptionalHalt::InvalidDataSectionSize)?;
+
+        let mut deployed = [container, &aux_data[..]].concat();
+        deployed
+            .get_mut(header.data_size_position..header.data_size_position.wrapping_add(2))
+            .ok_or(InternalError::Slicing)?
+            .copy_from_slice(&data_size.to_be_bytes());
+
+        call_frame.output = deployed.into();
+
+        Ok(OpcodeResult::Halt)
+    }
+
+    // RETURNDATALOAD operation
+    pub fn op_returndataload(&mut self) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        call_frame.increase_consumed_gas(gas_cost::RETURNDATALOAD)?;
+
+        let offset = u256_to_usize(call_frame.stack.pop1()?).unwrap_or(usize::MAX);
+        let word = word_from_padded(&call_frame.sub_return_data, offset);
+        call_frame.stack.push(word)?;
+
+        Ok(OpcodeResult::Continue)
+    }
+
+    // EXTCALL operation
+    pub fn op_extcall(&mut self) -> Result<OpcodeResult, VMError> {
+        self.generic_extcall(CALL)
+    }
+
+    // EXTDELEGATECALL operation
+    pub fn op_extdelegatecall(&mut self) -> Result<OpcodeResult, VMError> {
+        self.generic_extcall(DELEGATECALL)
+    }
+
+    // EXTSTATICCALL operation
+    pub fn op_extstaticcall(&mut self) -> Result<OpcodeResult, VMError> {
+        self.generic_extcall(STATICCALL)
+    }
+
+    /// Common behavior for EXTCALL, EXTDELEGATECALL and EXTSTATICCALL, identified by the call type
+    /// used when tracing them.
+    ///
+    /// Unlike the legacy calls, they don't take the gas to give to the callee nor the memory to
+    /// write its output to.
+    #[expect(clippy::as_conversions, reason = "remaining gas conversion")]
+    fn generic_extcall(&mut self, call_type: CallType) -> Result<OpcodeResult, VMError> {
+        let call_frame = &mut self.current_call_frame;
+        eof_frame(call_frame)?;
+        let is_delegatecall = matches!(call_type, DELEGATECALL);
+
+        let [target, input_offset, input_size] = *call_frame.stack.pop()?;
+        let value = match call_type {
+            CALL => call_frame.stack.pop1()?,
+            _ => U256::zero(),
+        };
+
+        // VALIDATIONS
+        if target.bits() > 160 {
+            return Err(ExceptionalHalt::VeryLargeNumber.into());
+        }
+        if call_frame.is_static && !value.is_zero() {
+            return Err(ExceptionalHalt::OpcodeNotAllowedInStaticContext.into());
+        }
+        let target = word_to_address(target);
+        let (input_size, input_offset) = size_offset_to_usize(input_size, input_offset)?;
+        let new_memory_size = calculate_memory_size(input_offset, input_size)?;
+        let current_memory_size = call_frame.memory.len();
+
+        // CHECK EIP7702
+        let (is_delegation_7702, eip7702_gas_consumed, code_address, bytecode) =
+            eip7702_get_code(self.db, &mut self.substate, target)?;
+
+        // GAS
+        let address_was_cold = !self.substate.add_accessed_address(target);
+        let address_is_empty = self.db.get_account(target)?.is_empty();
+        let cost = gas_cost::extcall(
+            new_memory_size,
+            current_memory_size,
+            address_was_cold,
+            address_is_empty,
+            value,
+        )?;
+
+        let call_frame = &mut self.current_call_frame;
+        call_frame.increase_consumed_gas(
+            cost.checked_add(eip7702_gas_consumed)
+                .ok_or(ExceptionalHalt::OutOfGas)?,
+        )?;
+        call_frame.memory.resize(new_memory_size)?;
+
+        // The callee gets all the gas but the 1/64th of it, always keeping some in the caller.
+        let gas_left = call_frame.gas_remaining as u64;
+        let gas_limit =
+            gas_left.saturating_sub((gas_left / 64).max(gas_cost::EXTCALL_MIN_RETAINED_GAS));
+        call_frame.increase_consumed_gas(gas_limit)?;
+
+        // OPERATION
+        let (from, to, value, should_transfer_value, is_static) = match call_type {
+            DELEGATECALL => (
+                call_frame.msg_sender,
+                call_frame.to,
+                call_frame.msg_value,
+                false,
+                call_frame.is_static,
+            ),
+            STATICCALL => (call_frame.to, target, value, true, true),
+            _ => (call_frame.to, target, value, true, call_frame.is_static),
+        };
+        let data = call_frame.memory.load_range(input_offset, input_size)?;
+
+        match call_type {
+            // In this trace the `from` is the current contract, like in DELEGATECALL.
+            DELEGATECALL => {
+                self.trace_call_enter(call_type, to, code_address, value, gas_limit, &data)
+            }
+            _ => self.trace_call_enter(call_type, from, to, value, gas_limit, &data),
+        }
+
+        // Light failures, which push 1 and return the reserved gas besides the ones checked when
+        // making any call.
+        let checks = [
+            (gas_limit < gas_cost::EXTCALL_MIN_CALLEE_GAS, "OutOfGas"),
+            (
+                is_delegatecall && !is_eof(&bytecode.bytecode),
+                "DelegateToLegacy",
+            ),
+        ];
+        for (condition, reason) in checks {
+            if condition {
+                self.current_call_frame.sub_return_data.clear();
+                self.early_revert_message_call(gas_limit, EXTCALL_REVERT, reason.to_string())?;
+                return Ok(OpcodeResult::Continue);
+            }
+        }
+
+        self.generic_call(
+            gas_limit,
+            value,
+            from,
+            to,
+            code_address,
+            should_transfer_value,
+            is_static,
+            data,
+            0,
+            0,
+            bytecode,
+            is_delegation_7702,
+        )
+    }
+}
+
+/// Returns the EOF state of the call frame, halting if it isn't executing an EOF container.
+fn eof_frame(call_frame: &CallFrame) -> Result<&EofFrame, ExceptionalHalt> {
+    call_frame
+        .eof
+        .as_deref()
+        .ok_or(ExceptionalHalt::InvalidOpcode)
+}
+
+/// Returns the data section of the container being executed.
+fn data_section(call_frame: &CallFrame) -> Result<&[u8], ExceptionalHalt> {
+    Ok(eof_frame(call_frame)?
+        .container
+        .data(&call_frame.bytecode.bytecode))
+}
+
+/// Returns where the given subcontainer is in the container being executed.
+fn container_section(call_frame: &CallFrame, index: u8) -> Result<Range<usize>, VMError> {
+    eof_frame(call_frame)?
+        .container
+        .container_sections
+        .get(usize::from(index))
+        .cloned()
+        .ok_or(InternalError::Slicing.into())
+}
+
+/// Reads the N byte immediate of the current opcode, without advancing the PC.
+fn read_immediate<const N: usize>(call_frame: &CallFrame) -> Result<[u8; N], VMError> {
+    let end = call_frame
+        .pc
+        .checked_add(N)
+        .ok_or(InternalError::Overflow)?;
+    call_frame
+        .bytecode
+        .bytecode
+        .get(call_frame.pc..end)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(InternalError::Slicing.into())
+}
+
+/// Moves the PC past the immediate of the current opcode, plus the given relative offset.
+fn relative_jump(
+    call_frame: &mut CallFrame,
+    immediate_size: usize,
+    offset: i16,
+) -> Result<(), VMError> {
+    call_frame.pc = call_frame
+        .pc
+        .checked_add(immediate_size)
+        .and_then(|pc| pc.checked_add_signed(isize::from(offset)))
+        .ok_or(InternalError::Overflow)?;
+    Ok(())
+}
+
+/// Checks that the stack has room for the code section CALLF or JUMPF is about to execute,
+/// returning where that section starts.
+fn check_section_stack_overflow(call_frame: &CallFrame, section: usize) -> Result<usize, VMError> {
+    let eof = eof_frame(call_frame)?;
+    let section_type = eof
+        .container
+        .types
+        .get(section)
+        .ok_or(InternalError::Slicing)?;
+    let max_stack_size = call_frame
+        .stack
+        .len()
+        .checked_add(usize::from(section_type.max_stack_increase))
+        .ok_or(InternalError::Overflow)?;
+    if max_stack_size > STACK_LIMIT {
+        return Err(ExceptionalHalt::StackOverflow.into());
+    }
+    eof.container
+        .code_sections
+        .get(section)
+        .map(|range| range.start)
+        .ok_or(InternalError::Slicing.into())
+}
+
+/// Copies `size` bytes of the source from the offset, padding with zeros what is out of bounds.
+pub(crate) fn copy_padded(source: &[u8], offset: usize, size: usize) -> Vec<u8> {
+    let mut data = vec![0u8; size];
+    if let Some(available) = source.get(offset..) {
+        let copied = available.len().min(size);
+        if let (Some(destination), Some(source)) = (data.get_mut(..copied), available.get(..copied))
+        {
+            destination.copy_from_slice(source);
+        }
+    }
+    data
+}
+
+/// Reads a word from the offset of the source, padding with zeros what is out of bounds.
+fn word_from_padded(source: &[u8], offset: usize) -> U256 {
+    let word: [u8; WORD_SIZE] = copy_padded(source, offset, WORD_SIZE)
+        .try_into()
+        .unwrap_or([0; WORD_SIZE]);
+    u256_from_big_endian_const(word)
+}
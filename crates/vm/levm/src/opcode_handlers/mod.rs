@@ -3,6 +3,7 @@ pub mod bitwise_comparison;
 pub mod block;
 pub mod dup;
 pub mod environment;
+pub mod eof;
 pub mod exchange;
 pub mod keccak;
 pub mod logging;
@@ -137,6 +137,7 @@ impl<'a> VM<'a> {
         let storage_slot_key = u256_to_h256(storage_slot_key);
 
         let (value, storage_slot_was_cold) = self.access_storage_slot(address, storage_slot_key)?;
+        self.trace(|tracer| tracer.on_storage_read(address, storage_slot_key, value));
 
         let current_call_frame = &mut self.current_call_frame;
 
@@ -222,6 +223,9 @@ impl<'a> VM<'a> {
 
         if new_storage_slot_value != current_value {
             self.update_account_storage(to, key, new_storage_slot_value, current_value)?;
+            self.trace(|tracer| {
+                tracer.on_storage_write(to, key, current_value, new_storage_slot_value)
+            });
         }
 
         Ok(OpcodeResult::Continue)
@@ -292,11 +296,8 @@ impl<'a> VM<'a> {
     ///   - Ensuring the byte is not blacklisted. In other words, the 0x5B value is not part of a
     ///     constant associated with a push instruction.
     fn target_address_is_valid(call_frame: &CallFrame, jump_address: u32) -> bool {
-        call_frame
-            .bytecode
-            .jump_targets
-            .binary_search(&jump_address)
-            .is_ok()
+        usize::try_from(jump_address)
+            .is_ok_and(|jump_address| call_frame.bytecode.analysis.is_jumpdest(jump_address))
     }
 
     /// JUMP* family (`JUMP` and `JUMP` ATTOW [DEC 2024]) helper
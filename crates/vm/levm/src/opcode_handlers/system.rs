@@ -1,6 +1,9 @@
 use crate::{
     call_frame::CallFrame,
-    constants::{FAIL, INIT_CODE_MAX_SIZE, SUCCESS},
+    constants::{
+        EXTCALL_FAILURE, EXTCALL_REVERT, EXTCALL_SUCCESS, FAIL, INIT_CODE_MAX_SIZE, SUCCESS,
+    },
+    eof::is_eof,
     errors::{ContextResult, ExceptionalHalt, InternalError, OpcodeResult, TxResult, VMError},
     gas_cost::{self, max_message_call_gas},
     memory::calculate_memory_size,
@@ -104,7 +107,7 @@ impl<'a> VM<'a> {
         let is_static = callframe.is_static;
         let data = self.get_calldata(args_offset, args_size)?;
 
-        self.tracer.enter(CALL, from, to, value, gas_limit, &data);
+        self.trace_call_enter(CALL, from, to, value, gas_limit, &data);
 
         self.generic_call(
             gas_limit,
@@ -201,8 +204,7 @@ impl<'a> VM<'a> {
         let is_static = callframe.is_static;
         let data = self.get_calldata(args_offset, args_size)?;
 
-        self.tracer
-            .enter(CALLCODE, from, code_address, value, gas_limit, &data);
+        self.trace_call_enter(CALLCODE, from, code_address, value, gas_limit, &data);
 
         self.generic_call(
             gas_limit,
@@ -319,8 +321,14 @@ impl<'a> VM<'a> {
         let data = self.get_calldata(args_offset, args_size)?;
 
         // In this trace the `from` is the current contract, we don't want the `from` to be, for example, the EOA that sent the transaction
-        self.tracer
-            .enter(DELEGATECALL, to, code_address, value, gas_limit, &data);
+        self.trace_call_enter(DELEGATECALL, to, code_address, value, gas_limit, &data);
+
+        // EOF code can only be delegated to from EOF code, with EXTDELEGATECALL.
+        if self.env.config.eof_enabled && is_eof(&bytecode.bytecode) {
+            self.current_call_frame.sub_return_data.clear();
+            self.early_revert_message_call(gas_limit, FAIL, "DelegateToEof".to_string())?;
+            return Ok(OpcodeResult::Continue);
+        }
 
         self.generic_call(
             gas_limit,
@@ -414,8 +422,7 @@ impl<'a> VM<'a> {
         let to = address; // In this case address and the sub-context account are the same. Unlike CALLCODE or DELEGATECODE.
         let data = self.get_calldata(args_offset, args_size)?;
 
-        self.tracer
-            .enter(STATICCALL, from, to, value, gas_limit, &data);
+        self.trace_call_enter(STATICCALL, from, to, value, gas_limit, &data);
 
         self.generic_call(
             gas_limit,
@@ -563,21 +570,22 @@ impl<'a> VM<'a> {
             // Selfdestruct is executed in the same transaction as the contract was created
             if self.substate.is_account_created(&to) {
                 // If target is the same as the contract calling, Ether will be burnt.
-                self.get_account_mut(to)?.info.balance = U256::zero();
+                let remaining_balance = self.get_account_mut(to)?.info.balance;
+                self.decrease_account_balance(to, remaining_balance)?;
 
                 self.substate.add_selfdestruct(to);
             }
         } else {
             self.increase_account_balance(beneficiary, balance)?;
-            self.get_account_mut(to)?.info.balance = U256::zero();
+            let remaining_balance = self.get_account_mut(to)?.info.balance;
+            self.decrease_account_balance(to, remaining_balance)?;
 
             self.substate.add_selfdestruct(to);
         }
 
-        self.tracer
-            .enter(SELFDESTRUCT, to, beneficiary, balance, 0, &Bytes::new());
+        self.trace_call_enter(SELFDESTRUCT, to, beneficiary, balance, 0, &Bytes::new());
 
-        self.tracer.exit_early(0, None)?;
+        self.trace_call_exit_early(0, None)?;
 
         Ok(OpcodeResult::Halt)
     }
@@ -636,8 +644,7 @@ impl<'a> VM<'a> {
             Some(_) => CallType::CREATE2,
             None => CallType::CREATE,
         };
-        self.tracer
-            .enter(call_type, deployer, new_address, value, gas_limit, &code);
+        self.trace_call_enter(call_type, deployer, new_address, value, gas_limit, &code);
 
         let new_depth = self
             .current_call_frame
@@ -656,7 +663,7 @@ impl<'a> VM<'a> {
         ];
         for (condition, reason) in checks {
             if condition {
-                self.early_revert_message_call(gas_limit, reason.to_string())?;
+                self.early_revert_message_call(gas_limit, FAIL, reason.to_string())?;
                 return Ok(OpcodeResult::Continue);
             }
         }
@@ -668,8 +675,7 @@ impl<'a> VM<'a> {
         let new_account = self.get_account_mut(new_address)?;
         if new_account.create_would_collide() {
             self.current_call_frame.stack.push(FAIL)?;
-            self.tracer
-                .exit_early(gas_limit, Some("CreateAccExists".to_string()))?;
+            self.trace_call_exit_early(gas_limit, Some("CreateAccExists".to_string()))?;
             return Ok(OpcodeResult::Continue);
         }
 
@@ -738,7 +744,8 @@ impl<'a> VM<'a> {
         if should_transfer_value && !value.is_zero() {
             let sender_balance = self.db.get_account(msg_sender)?.info.balance;
             if sender_balance < value {
-                self.early_revert_message_call(gas_limit, "OutOfFund".to_string())?;
+                let status = call_failure_status(&self.current_call_frame);
+                self.early_revert_message_call(gas_limit, status, "OutOfFund".to_string())?;
                 return Ok(OpcodeResult::Continue);
             }
         }
@@ -750,21 +757,32 @@ impl<'a> VM<'a> {
             .checked_add(1)
             .ok_or(InternalError::Overflow)?;
         if new_depth > 1024 {
-            self.early_revert_message_call(gas_limit, "MaxDepth".to_string())?;
+            let status = call_failure_status(&self.current_call_frame);
+            self.early_revert_message_call(gas_limit, status, "MaxDepth".to_string())?;
             return Ok(OpcodeResult::Continue);
         }
 
-        if precompiles::is_precompile(&code_address, self.env.config.fork, self.vm_type)
+        let l2_precompile = self.l2_precompile(code_address);
+        if (l2_precompile.is_some()
+            || precompiles::is_precompile(&code_address, self.env.config.fork, self.vm_type))
             && !is_delegation_7702
         {
             let mut gas_remaining = gas_limit;
-            let ctx_result = Self::execute_precompile(
-                code_address,
-                &calldata,
-                gas_limit,
-                &mut gas_remaining,
-                self.env.config.fork,
-            )?;
+            let ctx_result = match l2_precompile {
+                Some(precompile) => self.execute_l2_precompile(
+                    precompile,
+                    &calldata,
+                    gas_limit,
+                    &mut gas_remaining,
+                )?,
+                None => Self::execute_precompile(
+                    code_address,
+                    &calldata,
+                    gas_limit,
+                    &mut gas_remaining,
+                    self.env.config.fork,
+                )?,
+            };
 
             let call_frame = &mut self.current_call_frame;
 
@@ -796,24 +814,22 @@ impl<'a> VM<'a> {
             call_frame.sub_return_data = ctx_result.output.clone();
 
             // What to do, depending on TxResult
-            call_frame.stack.push(match &ctx_result.result {
-                TxResult::Success => SUCCESS,
-                TxResult::Revert(_) => FAIL,
-            })?;
+            let status = call_result_status(call_frame, &ctx_result.result);
+            call_frame.stack.push(status)?;
 
             // Transfer value from caller to callee.
             if should_transfer_value && ctx_result.is_success() {
                 self.transfer(msg_sender, to, value)?;
             }
 
-            self.tracer.exit_context(&ctx_result, false)?;
+            self.trace_call_exit(&ctx_result, false)?;
         } else {
             let mut stack = self.stack_pool.pop().unwrap_or_default();
             stack.clear();
 
             let next_memory = self.current_call_frame.memory.next_memory();
 
-            let new_call_frame = CallFrame::new(
+            let mut new_call_frame = CallFrame::new(
                 msg_sender,
                 to,
                 code_address,
@@ -830,6 +846,9 @@ impl<'a> VM<'a> {
                 stack,
                 next_memory,
             );
+            if self.env.config.eof_enabled {
+                new_call_frame.load_eof();
+            }
             self.add_callframe(new_call_frame);
 
             // Transfer value from caller to callee.
@@ -915,17 +934,13 @@ impl<'a> VM<'a> {
         parent_call_frame.sub_return_data = ctx_result.output.clone();
 
         // What to do, depending on TxResult
-        match &ctx_result.result {
-            TxResult::Success => {
-                self.current_call_frame.stack.push(SUCCESS)?;
-                self.merge_call_frame_backup_with_parent(&executed_call_frame.call_frame_backup)?;
-            }
-            TxResult::Revert(_) => {
-                self.current_call_frame.stack.push(FAIL)?;
-            }
-        };
+        let status = call_result_status(parent_call_frame, &ctx_result.result);
+        parent_call_frame.stack.push(status)?;
+        if ctx_result.is_success() {
+            self.merge_call_frame_backup_with_parent(&executed_call_frame.call_frame_backup)?;
+        }
 
-        self.tracer.exit_context(ctx_result, false)?;
+        self.trace_call_exit(ctx_result, false)?;
 
         let mut stack = executed_call_frame.stack;
         stack.clear();
@@ -977,7 +992,7 @@ impl<'a> VM<'a> {
             }
         };
 
-        self.tracer.exit_context(ctx_result, false)?;
+        self.trace_call_exit(ctx_result, false)?;
 
         let mut stack = executed_call_frame.stack;
         stack.clear();
@@ -1025,8 +1040,14 @@ impl<'a> VM<'a> {
         self.current_call_frame.memory.load_range(offset, size)
     }
 
+    /// Ends a message call that couldn't be made, pushing the given failure status.
     #[expect(clippy::as_conversions, reason = "remaining gas conversion")]
-    fn early_revert_message_call(&mut self, gas_limit: u64, reason: String) -> Result<(), VMError> {
+    pub(crate) fn early_revert_message_call(
+        &mut self,
+        gas_limit: u64,
+        status: U256,
+        reason: String,
+    ) -> Result<(), VMError> {
         let callframe = &mut self.current_call_frame;
 
         // Return gas_limit to callframe.
@@ -1034,9 +1055,30 @@ impl<'a> VM<'a> {
             .gas_remaining
             .checked_add(gas_limit as i64)
             .ok_or(InternalError::Overflow)?;
-        callframe.stack.push(FAIL)?; // It's the same as revert for CREATE
+        callframe.stack.push(status)?;
 
-        self.tracer.exit_early(0, Some(reason))?;
+        self.trace_call_exit_early(0, Some(reason))?;
         Ok(())
     }
 }
+
+/// Value pushed to the caller's stack with the result of a message call. EOF code makes calls
+/// with EXTCALL, EXTDELEGATECALL and EXTSTATICCALL, which push a status code rather than a flag.
+fn call_result_status(caller: &CallFrame, result: &TxResult) -> U256 {
+    match (caller.eof.is_some(), result) {
+        (false, TxResult::Success) => SUCCESS,
+        (false, TxResult::Revert(_)) => FAIL,
+        (true, TxResult::Success) => EXTCALL_SUCCESS,
+        (true, TxResult::Revert(err)) if err.is_revert_opcode() => EXTCALL_REVERT,
+        (true, TxResult::Revert(_)) => EXTCALL_FAILURE,
+    }
+}
+
+/// Value pushed to the caller's stack when a message call can't be made.
+fn call_failure_status(caller: &CallFrame) -> U256 {
+    if caller.eof.is_some() {
+        EXTCALL_REVERT
+    } else {
+        FAIL
+    }
+}
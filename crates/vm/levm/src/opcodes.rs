@@ -165,6 +165,25 @@ pub enum Opcode {
     LOG2 = 0xA2,
     LOG3 = 0xA3,
     LOG4 = 0xA4,
+    // EOF Data Section Access Operations
+    DATALOAD = 0xD0,
+    DATALOADN = 0xD1,
+    DATASIZE = 0xD2,
+    DATACOPY = 0xD3,
+    // EOF Flow Operations
+    RJUMP = 0xE0,
+    RJUMPI = 0xE1,
+    RJUMPV = 0xE2,
+    CALLF = 0xE3,
+    RETF = 0xE4,
+    JUMPF = 0xE5,
+    // EOF Stack Operations
+    DUPN = 0xE6,
+    SWAPN = 0xE7,
+    EXCHANGE = 0xE8,
+    // EOF Contract Creation Operations
+    EOFCREATE = 0xEC,
+    RETURNCONTRACT = 0xEE,
     // // System Operations
     CREATE = 0xF0,
     CALL = 0xF1,
@@ -172,7 +191,11 @@ pub enum Opcode {
     RETURN = 0xF3,
     DELEGATECALL = 0xF4,
     CREATE2 = 0xF5,
+    RETURNDATALOAD = 0xF7,
+    EXTCALL = 0xF8,
+    EXTDELEGATECALL = 0xF9,
     STATICCALL = 0xFA,
+    EXTSTATICCALL = 0xFB,
     REVERT = 0xFD,
     INVALID = 0xFE,
     SELFDESTRUCT = 0xFF,
@@ -333,6 +356,25 @@ impl From<u8> for Opcode {
             table[0xF4] = Opcode::DELEGATECALL;
             table[0xFA] = Opcode::STATICCALL;
             table[0xFD] = Opcode::REVERT;
+            table[0xD0] = Opcode::DATALOAD;
+            table[0xD1] = Opcode::DATALOADN;
+            table[0xD2] = Opcode::DATASIZE;
+            table[0xD3] = Opcode::DATACOPY;
+            table[0xE0] = Opcode::RJUMP;
+            table[0xE1] = Opcode::RJUMPI;
+            table[0xE2] = Opcode::RJUMPV;
+            table[0xE3] = Opcode::CALLF;
+            table[0xE4] = Opcode::RETF;
+            table[0xE5] = Opcode::JUMPF;
+            table[0xE6] = Opcode::DUPN;
+            table[0xE7] = Opcode::SWAPN;
+            table[0xE8] = Opcode::EXCHANGE;
+            table[0xEC] = Opcode::EOFCREATE;
+            table[0xEE] = Opcode::RETURNCONTRACT;
+            table[0xF7] = Opcode::RETURNDATALOAD;
+            table[0xF8] = Opcode::EXTCALL;
+            table[0xF9] = Opcode::EXTDELEGATECALL;
+            table[0xFB] = Opcode::EXTSTATICCALL;
             table[0xFF] = Opcode::SELFDESTRUCT;
 
             table
@@ -369,12 +411,13 @@ impl<'a> OpCodeFn<'a> {
 }
 
 impl<'a> VM<'a> {
-    /// Setups the opcode lookup function pointer table, configured according the given fork.
+    /// Setups the opcode lookup function pointer table, configured according the given fork
+    /// and whether EOF is enabled.
     ///
     /// This is faster than a conventional match.
     #[allow(clippy::as_conversions, clippy::indexing_slicing)]
-    pub(crate) fn build_opcode_table(fork: Fork) -> [OpCodeFn<'a>; 256] {
-        if fork >= Fork::Osaka {
+    pub(crate) fn build_opcode_table(fork: Fork, eof_enabled: bool) -> [OpCodeFn<'a>; 256] {
+        let opcode_table = if fork >= Fork::Osaka {
             Self::build_opcode_table_osaka()
         } else if fork >= Fork::Cancun {
             Self::build_opcode_table_pre_osaka()
@@ -382,6 +425,12 @@ impl<'a> VM<'a> {
             Self::build_opcode_table_pre_cancun()
         } else {
             Self::build_opcode_table_pre_shanghai()
+        };
+
+        if eof_enabled {
+            Self::add_eof_opcodes(opcode_table)
+        } else {
+            opcode_table
         }
     }
 
@@ -577,6 +626,31 @@ impl<'a> VM<'a> {
         opcode_table
     }
 
+    /// [EIP-7692] - EOF opcodes, which aren't tied to a fork. They halt when executed in legacy code.
+    #[allow(clippy::as_conversions, clippy::indexing_slicing)]
+    const fn add_eof_opcodes(mut opcode_table: [OpCodeFn<'a>; 256]) -> [OpCodeFn<'a>; 256] {
+        opcode_table[Opcode::DATALOAD as usize] = OpCodeFn(VM::op_dataload);
+        opcode_table[Opcode::DATALOADN as usize] = OpCodeFn(VM::op_dataloadn);
+        opcode_table[Opcode::DATASIZE as usize] = OpCodeFn(VM::op_datasize);
+        opcode_table[Opcode::DATACOPY as usize] = OpCodeFn(VM::op_datacopy);
+        opcode_table[Opcode::RJUMP as usize] = OpCodeFn(VM::op_rjump);
+        opcode_table[Opcode::RJUMPI as usize] = OpCodeFn(VM::op_rjumpi);
+        opcode_table[Opcode::RJUMPV as usize] = OpCodeFn(VM::op_rjumpv);
+        opcode_table[Opcode::CALLF as usize] = OpCodeFn(VM::op_callf);
+        opcode_table[Opcode::RETF as usize] = OpCodeFn(VM::op_retf);
+        opcode_table[Opcode::JUMPF as usize] = OpCodeFn(VM::op_jumpf);
+        opcode_table[Opcode::DUPN as usize] = OpCodeFn(VM::op_dupn);
+        opcode_table[Opcode::SWAPN as usize] = OpCodeFn(VM::op_swapn);
+        opcode_table[Opcode::EXCHANGE as usize] = OpCodeFn(VM::op_exchange);
+        opcode_table[Opcode::EOFCREATE as usize] = OpCodeFn(VM::op_eofcreate);
+        opcode_table[Opcode::RETURNCONTRACT as usize] = OpCodeFn(VM::op_returncontract);
+        opcode_table[Opcode::RETURNDATALOAD as usize] = OpCodeFn(VM::op_returndataload);
+        opcode_table[Opcode::EXTCALL as usize] = OpCodeFn(VM::op_extcall);
+        opcode_table[Opcode::EXTDELEGATECALL as usize] = OpCodeFn(VM::op_extdelegatecall);
+        opcode_table[Opcode::EXTSTATICCALL as usize] = OpCodeFn(VM::op_extstaticcall);
+        opcode_table
+    }
+
     /// Used within the opcode table for invalid opcodes.
     pub fn on_invalid_opcode(&mut self) -> Result<OpcodeResult, VMError> {
         Err(ExceptionalHalt::InvalidOpcode.into())
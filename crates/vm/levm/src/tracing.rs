@@ -1,5 +1,6 @@
 use crate::{
     errors::{ContextResult, InternalError, TxResult, VMError},
+    hooks::Tracer,
     vm::VM,
 };
 use bytes::Bytes;
@@ -200,4 +201,58 @@ impl<'a> VM<'a> {
             .pop()
             .ok_or(InternalError::CallFrame.into())
     }
+
+    /// Reports an event to every tracer in `VM::tracers`.
+    #[inline(always)]
+    pub(crate) fn trace(&self, mut event: impl FnMut(&mut dyn Tracer)) {
+        for tracer in &self.tracers {
+            event(&mut *tracer.borrow_mut());
+        }
+    }
+
+    pub(crate) fn trace_call_enter(
+        &mut self,
+        call_type: CallType,
+        from: Address,
+        to: Address,
+        value: U256,
+        gas: u64,
+        input: &Bytes,
+    ) {
+        self.trace(|tracer| tracer.on_call_enter(&call_type, from, to, value, gas, input));
+        self.tracer.enter(call_type, from, to, value, gas, input);
+    }
+
+    pub(crate) fn trace_call_exit(
+        &mut self,
+        ctx_result: &ContextResult,
+        is_top_call: bool,
+    ) -> Result<(), InternalError> {
+        if !self.tracers.is_empty() {
+            let error = match &ctx_result.result {
+                TxResult::Success => None,
+                TxResult::Revert(err) => Some(err.to_string()),
+            };
+            self.trace(|tracer| {
+                tracer.on_call_exit(ctx_result.gas_used, &ctx_result.output, error.as_deref())
+            });
+        }
+        self.tracer.exit_context(ctx_result, is_top_call)
+    }
+
+    /// Exits the trace call when CALL or CREATE opcodes return early or in case SELFDESTRUCT is
+    /// called.
+    pub(crate) fn trace_call_exit_early(
+        &mut self,
+        gas_used: u64,
+        error: Option<String>,
+    ) -> Result<(), InternalError> {
+        self.trace(|tracer| tracer.on_call_exit(gas_used, &Bytes::new(), error.as_deref()));
+        self.tracer.exit_early(gas_used, error)
+    }
+
+    pub(crate) fn trace_log(&mut self, log: &Log) -> Result<(), InternalError> {
+        self.trace(|tracer| tracer.on_log(log));
+        self.tracer.log(log)
+    }
 }
@@ -66,6 +66,31 @@ pub fn calculate_create2_address(
     Ok(generated_address)
 }
 
+/// Calculates the address of a new contract using the EOFCREATE opcode as follows
+///
+/// address = keccak256(0xff || sender_address || salt)[12:]
+///
+/// Where the sender address is left padded to 32 bytes.
+pub fn calculate_eofcreate_address(
+    sender_address: Address,
+    salt: U256,
+) -> Result<Address, InternalError> {
+    let generated_address = Address::from_slice(
+        keccak(
+            [
+                &[0xff],
+                &address_to_word(sender_address).to_big_endian()[..],
+                &salt.to_big_endian(),
+            ]
+            .concat(),
+        )
+        .as_bytes()
+        .get(12..)
+        .ok_or(InternalError::Slicing)?,
+    );
+    Ok(generated_address)
+}
+
 // ================== Backup related functions =======================
 
 /// Restore the state of the cache to the state it in the callframe backup.
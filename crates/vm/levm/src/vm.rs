@@ -8,7 +8,10 @@ use crate::{
     hooks::{
         backup_hook::BackupHook,
         hook::{Hook, get_hooks},
+        step_hook::StepHook,
+        tracer::Tracer,
     },
+    l2_precompiles::{L2Precompile, execute_l2_precompile, find_l2_precompile},
     memory::Memory,
     opcodes::OpCodeFn,
     precompiles::{
@@ -276,6 +279,22 @@ impl Substate {
             })
     }
 
+    /// Return all the transient storage entries of an address.
+    pub fn get_transient_storage_of(&self, to: &Address) -> BTreeMap<U256, U256> {
+        let mut entries = self
+            .parent
+            .as_ref()
+            .map(|parent| parent.get_transient_storage_of(to))
+            .unwrap_or_default();
+        entries.extend(
+            self.transient_storage
+                .iter()
+                .filter(|((address, _), _)| address == to)
+                .map(|((_, key), value)| (*key, *value)),
+        );
+        entries
+    }
+
     /// Return the data associated with a transient storage entry, or zero if not present.
     pub fn set_transient(&mut self, to: &Address, key: &U256, value: U256) {
         self.transient_storage.insert((*to, *key), value);
@@ -323,6 +342,13 @@ pub struct VM<'a> {
     /// A pool of stacks to avoid reallocating too much when creating new call frames.
     pub stack_pool: Vec<Stack>,
     pub vm_type: VMType,
+    /// When set, the coinbase fee is accumulated here instead of being paid to the coinbase.
+    /// Used by the parallel block executor, so that transactions don't conflict on the coinbase balance.
+    pub deferred_coinbase_fee: Option<U256>,
+    /// Observes every executed opcode when set. See [`StepHook`].
+    pub step_hook: Option<Rc<RefCell<dyn StepHook>>>,
+    /// Native tracers following the execution. See [`Tracer`].
+    pub tracers: Vec<Rc<RefCell<dyn Tracer>>>,
 
     /// The opcode table mapping opcodes to opcode handlers for fast lookup.
     /// Build dynamically according to the given fork config.
@@ -339,11 +365,12 @@ impl<'a> VM<'a> {
     ) -> Result<Self, VMError> {
         db.tx_backup = None; // If BackupHook is enabled, it will contain backup at the end of tx execution.
 
-        let mut substate = Substate::initialize(&env, tx)?;
+        let mut substate = Substate::initialize(&env, tx, vm_type)?;
 
         let (callee, is_create) = Self::get_tx_callee(tx, db, &env, &mut substate)?;
 
         let fork = env.config.fork;
+        let eof_enabled = env.config.eof_enabled;
 
         let mut vm = Self {
             call_frames: Vec::new(),
@@ -357,6 +384,9 @@ impl<'a> VM<'a> {
             debug_mode: DebugMode::disabled(),
             stack_pool: Vec::new(),
             vm_type,
+            deferred_coinbase_fee: None,
+            step_hook: None,
+            tracers: Vec::new(),
             current_call_frame: CallFrame::new(
                 env.origin,
                 callee,
@@ -375,7 +405,7 @@ impl<'a> VM<'a> {
                 Memory::default(),
             ),
             env,
-            opcode_table: VM::build_opcode_table(fork),
+            opcode_table: VM::build_opcode_table(fork, eof_enabled),
         };
 
         let call_type = if is_create {
@@ -407,7 +437,31 @@ impl<'a> VM<'a> {
 
     /// Executes a whole external transaction. Performing validations at the beginning.
     pub fn execute(&mut self) -> Result<ExecutionReport, VMError> {
+        self.trace(|tracer| tracer.on_tx_start(self));
+        // The top-level call is entered before prepare_execution so that the balance changes it
+        // makes (fees and value transfer) are reported inside of it.
+        if !self.tracers.is_empty() {
+            let call_type = if self.current_call_frame.is_create {
+                CallType::CREATE
+            } else {
+                CallType::CALL
+            };
+            let call_frame = &self.current_call_frame;
+            self.trace(|tracer| {
+                tracer.on_call_enter(
+                    &call_type,
+                    call_frame.msg_sender,
+                    call_frame.to,
+                    call_frame.msg_value,
+                    call_frame.gas_limit,
+                    &call_frame.calldata,
+                )
+            });
+        }
+
         if let Err(e) = self.prepare_execution() {
+            let error = e.to_string();
+            self.trace(|tracer| tracer.on_call_exit(0, &Bytes::new(), Some(&error)));
             // Restore cache to state previous to this Tx execution because this Tx is invalid.
             self.restore_cache_state()?;
             return Err(e);
@@ -435,6 +489,22 @@ impl<'a> VM<'a> {
 
     /// Main execution loop.
     pub fn run_execution(&mut self) -> Result<ContextResult, VMError> {
+        if let Some(precompile) = self.l2_precompile(self.current_call_frame.to) {
+            let calldata = self.current_call_frame.calldata.clone();
+            let gas_limit = self.current_call_frame.gas_limit;
+            #[expect(clippy::as_conversions, reason = "remaining gas conversion")]
+            let mut gas_remaining = self.current_call_frame.gas_remaining as u64;
+            let result =
+                self.execute_l2_precompile(precompile, &calldata, gas_limit, &mut gas_remaining);
+
+            #[expect(clippy::as_conversions, reason = "remaining gas conversion")]
+            {
+                self.current_call_frame.gas_remaining = gas_remaining as i64;
+            }
+
+            return result;
+        }
+
         #[expect(clippy::as_conversions, reason = "remaining gas conversion")]
         if precompiles::is_precompile(
             &self.current_call_frame.to,
@@ -457,7 +527,16 @@ impl<'a> VM<'a> {
             return result;
         }
 
+        // Tracers can't be added mid-execution, so this keeps the per-opcode check cheap.
+        let tracing = !self.tracers.is_empty();
         loop {
+            if let Some(step_hook) = &self.step_hook {
+                step_hook.borrow_mut().on_step(self);
+            }
+            if tracing {
+                self.trace(|tracer| tracer.on_step(self));
+            }
+
             let opcode = self.current_call_frame.next_opcode();
             self.advance_pc(1)?;
 
@@ -472,6 +551,10 @@ impl<'a> VM<'a> {
                 Err(error) => self.handle_opcode_error(error)?,
             };
 
+            if let Some(step_hook) = &self.step_hook {
+                step_hook.borrow_mut().on_call_frame_exit(self, &result);
+            }
+
             // Return the ExecutionReport if the executed callframe was the first one.
             if self.is_initial_call_frame() {
                 self.handle_state_backup(&result)?;
@@ -500,6 +583,27 @@ impl<'a> VM<'a> {
         )
     }
 
+    /// Returns the L2 precompile the chain config enables at `address`, if executing an L2 block.
+    pub fn l2_precompile(&self, address: Address) -> Option<L2Precompile> {
+        match self.vm_type {
+            VMType::L2(_) => find_l2_precompile(&self.env.config.l2_precompiles, address),
+            VMType::L1 => None,
+        }
+    }
+
+    /// Executes an L2 precompile and handles its output like the one of any other precompile.
+    pub fn execute_l2_precompile(
+        &mut self,
+        precompile: L2Precompile,
+        calldata: &Bytes,
+        gas_limit: u64,
+        gas_remaining: &mut u64,
+    ) -> Result<ContextResult, VMError> {
+        let result = execute_l2_precompile(precompile, calldata, gas_remaining, self.db);
+
+        Self::handle_precompile_result(result, gas_limit, *gas_remaining)
+    }
+
     /// True if external transaction is a contract creation
     pub fn is_create(&self) -> Result<bool, InternalError> {
         Ok(self.current_call_frame.is_create)
@@ -532,7 +636,7 @@ impl<'a> VM<'a> {
                 .finalize_execution(self, &mut ctx_result)?;
         }
 
-        self.tracer.exit_context(&ctx_result, true)?;
+        self.trace_call_exit(&ctx_result, true)?;
 
         let report = ExecutionReport {
             result: ctx_result.result.clone(),
@@ -542,13 +646,19 @@ impl<'a> VM<'a> {
             logs: self.substate.extract_logs(),
         };
 
+        self.trace(|tracer| tracer.on_tx_end(self, &report));
+
         Ok(report)
     }
 }
 
 impl Substate {
     /// Initializes the VM substate, mainly adding addresses to the "accessed_addresses" field and the same with storage slots
-    pub fn initialize(env: &Environment, tx: &Transaction) -> Result<Substate, VMError> {
+    pub fn initialize(
+        env: &Environment,
+        tx: &Transaction,
+        vm_type: VMType,
+    ) -> Result<Substate, VMError> {
         // Add sender and recipient to accessed accounts [https://www.evm.codes/about#access_list]
         let mut initial_accessed_addresses = HashSet::new();
         let mut initial_accessed_storage_slots: BTreeMap<Address, BTreeSet<H256>> = BTreeMap::new();
@@ -578,6 +688,11 @@ impl Substate {
             initial_accessed_addresses.insert(Address::from_low_u64_be(0x100));
         }
 
+        // Add the precompiles enabled by the L2 chain config, which are warm like any other
+        if let VMType::L2(_) = vm_type {
+            initial_accessed_addresses.extend(env.config.l2_precompiles.addresses());
+        }
+
         // Add access lists contents to accessed accounts and accessed storage slots.
         for (address, keys) in tx.access_list().clone() {
             initial_accessed_addresses.insert(address);
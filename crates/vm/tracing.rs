@@ -1,9 +1,98 @@
 use crate::backends::levm::LEVM;
-use ethrex_common::tracing::CallTrace;
-use ethrex_common::types::Block;
+use ethrex_common::tracing::{CallTrace, CallTraceFrame, InternalOperation, StateDiff, VmTrace};
+use ethrex_common::types::{Block, BlockHeader, GenericTransaction};
+use ethrex_levm::hooks::Tracer;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
 
 use crate::{Evm, EvmError};
 
+/// Builds a tracer from the `tracerConfig` given by the user, if any.
+pub type TracerFactory =
+    Arc<dyn Fn(Option<Value>) -> Result<Rc<RefCell<dyn Tracer>>, EvmError> + Send + Sync>;
+
+/// Native tracers available by name, e.g. to the `debug_trace*` endpoints.
+/// Clones share the same registry, so tracers can be registered after handing it out.
+#[derive(Clone, Default)]
+pub struct TracerRegistry {
+    factories: Arc<RwLock<BTreeMap<String, TracerFactory>>>,
+}
+
+impl std::fmt::Debug for TracerRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TracerRegistry")
+            .field("tracers", &self.names())
+            .finish()
+    }
+}
+
+impl TracerRegistry {
+    /// Registers a tracer under `name`, replacing any tracer previously registered with it.
+    pub fn register(
+        &self,
+        name: impl Into<String>,
+        factory: impl Fn(Option<Value>) -> Result<Rc<RefCell<dyn Tracer>>, EvmError>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Result<(), EvmError> {
+        self.factories
+            .write()
+            .map_err(|_| EvmError::Custom("Tracer registry lock poisoned".to_string()))?
+            .insert(name.into(), Arc::new(factory));
+        Ok(())
+    }
+
+    /// Builds a new instance of the tracer registered under `name`.
+    pub fn create(
+        &self,
+        name: &str,
+        config: Option<Value>,
+    ) -> Result<Rc<RefCell<dyn Tracer>>, EvmError> {
+        let factory = self
+            .factories
+            .read()
+            .map_err(|_| EvmError::Custom("Tracer registry lock poisoned".to_string()))?
+            .get(name)
+            .cloned()
+            .ok_or_else(|| EvmError::Custom(format!("Unknown tracer: {name}")))?;
+        factory(config)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories
+            .read()
+            .is_ok_and(|factories| factories.contains_key(name))
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.factories
+            .read()
+            .map(|factories| factories.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Traces to output besides the call trace when replaying a transaction, as in the `traceTypes`
+/// of Parity's `trace_replay*` endpoints.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReplayTraceConfig {
+    pub state_diff: bool,
+    pub vm_trace: bool,
+}
+
+/// Traces of a replayed transaction.
+#[derive(Debug)]
+pub struct ReplayTrace {
+    /// Call trace of the transaction, including every sub-call
+    pub call_trace: CallTraceFrame,
+    pub state_diff: Option<StateDiff>,
+    pub vm_trace: Option<VmTrace>,
+}
+
 impl Evm {
     /// Runs a single tx with the call tracer and outputs its trace.
     /// Assumes that the received state already contains changes from previous blocks and other
@@ -34,6 +123,127 @@ impl Evm {
         )
     }
 
+    /// Runs a single tx with the given native tracer attached.
+    /// Assumes that the received state already contains changes from previous blocks and other
+    /// transactions within its block.
+    pub fn trace_tx_with_tracer(
+        &mut self,
+        block: &Block,
+        tx_index: usize,
+        tracer: Rc<RefCell<dyn Tracer>>,
+    ) -> Result<(), EvmError> {
+        let tx = block
+            .body
+            .transactions
+            .get(tx_index)
+            .ok_or(EvmError::Custom(
+                "Missing Transaction for Trace".to_string(),
+            ))?;
+
+        LEVM::trace_tx_with_tracer(&mut self.db, &block.header, tx, tracer, self.vm_type)
+    }
+
+    /// Runs a single tx collecting its value transfers, contract creations and self destructs
+    /// besides its top call.
+    /// Assumes that the received state already contains changes from previous blocks and other
+    /// transactions within its block.
+    pub fn trace_tx_internal_operations(
+        &mut self,
+        block: &Block,
+        tx_index: usize,
+    ) -> Result<Vec<InternalOperation>, EvmError> {
+        let tx = block
+            .body
+            .transactions
+            .get(tx_index)
+            .ok_or(EvmError::Custom(
+                "Missing Transaction for Trace".to_string(),
+            ))?;
+
+        LEVM::trace_tx_internal_operations(&mut self.db, &block.header, tx, self.vm_type)
+    }
+
+    /// Runs a single tx with the tracer registered under `tracer_name` and outputs its result.
+    pub fn trace_tx(
+        &mut self,
+        block: &Block,
+        tx_index: usize,
+        tracers: &TracerRegistry,
+        tracer_name: &str,
+        tracer_config: Option<Value>,
+    ) -> Result<Value, EvmError> {
+        let tracer = tracers.create(tracer_name, tracer_config)?;
+        self.trace_tx_with_tracer(block, tx_index, tracer.clone())?;
+        let result = tracer.borrow_mut().result();
+        Ok(result)
+    }
+
+    /// Runs a transaction that isn't part of the chain on top of the current state with the call
+    /// tracer, like `eth_call` would.
+    pub fn trace_generic_tx_calls(
+        &mut self,
+        tx: &GenericTransaction,
+        header: &BlockHeader,
+        only_top_call: bool,
+        with_log: bool,
+    ) -> Result<CallTrace, EvmError> {
+        LEVM::trace_generic_tx_calls(
+            &mut self.db,
+            header,
+            tx,
+            only_top_call,
+            with_log,
+            self.vm_type,
+        )
+    }
+
+    /// Runs a transaction that isn't part of the chain on top of the current state with the
+    /// tracer registered under `tracer_name` and outputs its result.
+    pub fn trace_generic_tx(
+        &mut self,
+        tx: &GenericTransaction,
+        header: &BlockHeader,
+        tracers: &TracerRegistry,
+        tracer_name: &str,
+        tracer_config: Option<Value>,
+    ) -> Result<Value, EvmError> {
+        let tracer = tracers.create(tracer_name, tracer_config)?;
+        LEVM::trace_generic_tx_with_tracer(&mut self.db, header, tx, tracer.clone(), self.vm_type)?;
+        let result = tracer.borrow_mut().result();
+        Ok(result)
+    }
+
+    /// Runs a single tx with the call tracer and the traces enabled in `config`.
+    /// Assumes that the received state already contains changes from previous blocks and other
+    /// transactions within its block.
+    pub fn trace_tx_replay(
+        &mut self,
+        block: &Block,
+        tx_index: usize,
+        config: ReplayTraceConfig,
+    ) -> Result<ReplayTrace, EvmError> {
+        let tx = block
+            .body
+            .transactions
+            .get(tx_index)
+            .ok_or(EvmError::Custom(
+                "Missing Transaction for Trace".to_string(),
+            ))?;
+
+        LEVM::trace_tx_replay(&mut self.db, &block.header, tx, config, self.vm_type)
+    }
+
+    /// Runs a transaction that isn't part of the chain on top of the current state, with the call
+    /// tracer and the traces enabled in `config`.
+    pub fn trace_generic_tx_replay(
+        &mut self,
+        tx: &GenericTransaction,
+        header: &BlockHeader,
+        config: ReplayTraceConfig,
+    ) -> Result<ReplayTrace, EvmError> {
+        LEVM::trace_generic_tx_replay(&mut self.db, header, tx, config, self.vm_type)
+    }
+
     /// Reruns the given block, saving the changes on the state, doesn't output any results or receipts.
     /// If the optional argument `stop_index` is set, the run will stop just before executing the transaction at that index
     /// and won't process the withdrawals afterwards.
@@ -46,3 +256,74 @@ impl Evm {
         LEVM::rerun_block(&mut self.db, block, stop_index, self.vm_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Returns the config it was built with as its result.
+    struct EchoTracer(Value);
+
+    impl Tracer for EchoTracer {
+        fn result(&mut self) -> Value {
+            self.0.clone()
+        }
+    }
+
+    fn echo(config: Option<Value>) -> Result<Rc<RefCell<dyn Tracer>>, EvmError> {
+        Ok(Rc::new(RefCell::new(EchoTracer(
+            config.unwrap_or(Value::Null),
+        ))))
+    }
+
+    #[test]
+    fn creates_registered_tracers_with_their_config() {
+        let registry = TracerRegistry::default();
+        registry.register("echo", echo).unwrap();
+
+        let tracer = registry.create("echo", Some(json!({"depth": 2}))).unwrap();
+        assert_eq!(tracer.borrow_mut().result(), json!({"depth": 2}));
+        let tracer = registry.create("echo", None).unwrap();
+        assert_eq!(tracer.borrow_mut().result(), Value::Null);
+    }
+
+    #[test]
+    fn unknown_tracers_are_rejected() {
+        let registry = TracerRegistry::default();
+        registry.register("echo", echo).unwrap();
+
+        assert!(!registry.contains("callTracer"));
+        let Err(EvmError::Custom(error)) = registry.create("callTracer", None) else {
+            panic!("expected an unknown tracer error");
+        };
+        assert_eq!(error, "Unknown tracer: callTracer");
+    }
+
+    #[test]
+    fn registering_a_name_again_replaces_the_tracer() {
+        let registry = TracerRegistry::default();
+        registry.register("echo", echo).unwrap();
+        registry
+            .register("echo", |_| {
+                Ok(Rc::new(RefCell::new(EchoTracer(json!("replaced")))))
+            })
+            .unwrap();
+
+        let tracer = registry.create("echo", Some(json!(1))).unwrap();
+        assert_eq!(tracer.borrow_mut().result(), json!("replaced"));
+        assert_eq!(registry.names(), ["echo"]);
+    }
+
+    #[test]
+    fn clones_share_the_registered_tracers() {
+        let registry = TracerRegistry::default();
+        let handed_out = registry.clone();
+        registry.register("zeta", echo).unwrap();
+        registry.register("alpha", echo).unwrap();
+
+        assert!(handed_out.contains("alpha"));
+        assert_eq!(handed_out.names(), ["alpha", "zeta"]);
+        assert!(handed_out.create("zeta", None).is_ok());
+    }
+}
@@ -92,6 +92,11 @@ lazy_static! {
         ..*OSAKA_CONFIG
     };
 
+    pub static ref AMSTERDAM_CONFIG: ChainConfig = ChainConfig {
+        amsterdam_time: Some(0),
+        ..*OSAKA_CONFIG
+    };
+
 }
 
 /// Most of the fork variants are just for parsing the tests
@@ -131,6 +136,7 @@ pub enum Fork {
     BPO2ToBPO3AtTime15k,
     BPO3ToBPO4AtTime15k,
     BPO4ToBPO5AtTime15k,
+    Amsterdam,
 }
 
 impl Fork {
@@ -150,6 +156,7 @@ impl Fork {
             Fork::BPO2ToBPO3AtTime15k => &BPO2_TO_BPO3_AT_15K_CONFIG,
             Fork::BPO3ToBPO4AtTime15k => &BPO3_TO_BPO4_AT_15K_CONFIG,
             Fork::BPO4ToBPO5AtTime15k => &BPO4_TO_BPO5_AT_15K_CONFIG,
+            Fork::Amsterdam => &AMSTERDAM_CONFIG,
             _ => {
                 panic!("Ethrex doesn't support pre-Merge forks: {self:?}")
             }
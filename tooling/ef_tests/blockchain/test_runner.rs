@@ -76,7 +76,13 @@ pub async fn run_ef_test(
     check_prestate_against_db(test_key, test, &store);
 
     // Blockchain EF tests are meant for L1.
-    let blockchain = Blockchain::new(store.clone(), BlockchainOptions::default());
+    let blockchain = Blockchain::new(
+        store.clone(),
+        BlockchainOptions {
+            parallel_execution: cfg!(feature = "parallel-execution"),
+            ..Default::default()
+        },
+    );
 
     // Early return if the exception is in the rlp decoding of the block
     for bf in &test.blocks {
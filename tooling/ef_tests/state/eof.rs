@@ -0,0 +1,139 @@
+//! Runner for the EOF validation tests (`EOFTests` in [ethereum/tests](https://github.com/ethereum/tests)).
+//!
+//! These tests don't execute anything, they only check whether LEVM accepts each container. The
+//! EOF state tests are run by the state runner instead, with its `--eof` flag.
+
+use crate::{deserialize::deserialize_hex_bytes, parser::EFTestParseError};
+use bytes::Bytes;
+use colored::Colorize;
+use ethrex_levm::eof::{ContainerKind, validate_container};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+// Kept apart from the state tests vectors, since the state tests parser reads every file there.
+const EOF_VECTORS_DIR_NAME: &str = "eof_vectors";
+// The EOF state tests live next to these, in `state_tests`, and are run by the state runner.
+const EOF_VALIDATION_TESTS_DIR_NAME: &str = "EOFTests";
+
+#[derive(Debug, Deserialize)]
+pub struct EofTest {
+    pub vectors: BTreeMap<String, EofTestVector>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EofTestVector {
+    #[serde(deserialize_with = "deserialize_hex_bytes")]
+    pub code: Bytes,
+    #[serde(default)]
+    pub container_kind: Option<String>,
+    /// Expected result for each fork.
+    pub results: BTreeMap<String, EofTestResult>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EofTestResult {
+    pub result: bool,
+    #[serde(default)]
+    pub exception: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct EofTestsReport {
+    pub passed: usize,
+    /// Name of the failing vectors, with the reason why they failed.
+    pub failed: Vec<(String, String)>,
+}
+
+/// Runs every EOF validation test in the vectors directory, optionally only the ones whose path
+/// contains one of the given filters.
+pub fn run_eof_tests(filters: &[String]) -> Result<EofTestsReport, EFTestParseError> {
+    let vectors_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join(EOF_VECTORS_DIR_NAME)
+        .join(EOF_VALIDATION_TESTS_DIR_NAME);
+    let mut test_files = Vec::new();
+    collect_test_files(&vectors_dir, &mut test_files)?;
+    test_files.sort();
+
+    let mut report = EofTestsReport::default();
+    for test_file in test_files {
+        let path = test_file.display().to_string();
+        if !filters.is_empty() && !filters.iter().any(|filter| path.contains(filter)) {
+            continue;
+        }
+        let content = std::fs::read_to_string(&test_file)
+            .map_err(|err| EFTestParseError::FailedToReadFile(format!("{path}: {err}")))?;
+        let tests: BTreeMap<String, EofTest> = serde_json::from_str(&content)
+            .map_err(|err| EFTestParseError::FailedToParseTestFile(format!("{path}: {err}")))?;
+
+        for (test_name, test) in tests {
+            for (vector_name, vector) in test.vectors {
+                for (fork, result) in run_vector(&vector) {
+                    let name = format!("{test_name}::{vector_name}::{fork}");
+                    match result {
+                        Ok(()) => report.passed += 1,
+                        Err(reason) => report.failed.push((name, reason)),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Checks the validation result against the expected one of each fork, reporting every fork on
+/// its own so that a mismatch in one doesn't hide the others.
+fn run_vector(vector: &EofTestVector) -> Vec<(&str, Result<(), String>)> {
+    let kind = match vector.container_kind.as_deref() {
+        Some("INITCODE") => ContainerKind::Initcode,
+        _ => ContainerKind::Runtime,
+    };
+    let validation = validate_container(&vector.code, kind);
+
+    vector
+        .results
+        .iter()
+        .map(|(fork, expected)| {
+            let result = match (&validation, expected.result) {
+                (Ok(_), true) | (Err(_), false) => Ok(()),
+                (Ok(_), false) => Err(format!(
+                    "expected {}, container was valid",
+                    expected.exception.as_deref().unwrap_or("an exception")
+                )),
+                (Err(err), true) => Err(format!("expected valid container, {err}")),
+            };
+            (fork.as_str(), result)
+        })
+        .collect()
+}
+
+fn collect_test_files(dir: &Path, test_files: &mut Vec<PathBuf>) -> Result<(), EFTestParseError> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|err| EFTestParseError::FailedToReadDirectory(format!("{dir:?}: {err}")))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_test_files(&path, test_files)?;
+        } else if path.extension().is_some_and(|ext| ext == "json") {
+            test_files.push(path);
+        }
+    }
+    Ok(())
+}
+
+impl EofTestsReport {
+    pub fn print(&self) {
+        for (name, reason) in &self.failed {
+            println!("{} {name}: {reason}", "FAILED".red().bold());
+        }
+        println!(
+            "EOF validation tests: {} passed, {} failed",
+            self.passed.to_string().green(),
+            self.failed.len().to_string().red()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 mod deserialize;
+pub mod eof;
 pub mod parser;
 mod report;
 pub mod runner;
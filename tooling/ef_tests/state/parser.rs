@@ -36,11 +36,20 @@ pub const SPECIFIC_IGNORED_TESTS: [&str; 1] = [
 
 // This constant is used as the reference from which to keep the relative path of the tests.
 const START_DIR_NAME: &str = "vectors";
+// Same as above, for the EOF state tests which are kept apart from the rest.
+const EOF_START_DIR_NAME: &str = "eof_vectors";
+const EOF_STATE_TESTS_DIR_NAME: &str = "state_tests";
 
 pub fn parse_ef_tests(opts: &EFTestRunnerOptions) -> Result<Vec<EFTest>, EFTestParseError> {
     let parsing_time = std::time::Instant::now();
     let cargo_manifest_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    let ef_general_state_tests_path = cargo_manifest_dir.join("vectors");
+    let ef_general_state_tests_path = if opts.eof {
+        cargo_manifest_dir
+            .join(EOF_START_DIR_NAME)
+            .join(EOF_STATE_TESTS_DIR_NAME)
+    } else {
+        cargo_manifest_dir.join(START_DIR_NAME)
+    };
     println!("{}", "Parsing EF Tests".bold().cyan());
 
     let mut tests = Vec::new();
@@ -188,6 +197,7 @@ pub fn parse_ef_test_dir(
 
                 for test in tests.0.iter_mut() {
                     test.dir = test_dir.path().to_str().unwrap().to_string();
+                    test.eof = opts.eof;
                 }
 
                 // We only want to include tests that have post states from the specified forks in EFTestsRunnerOptions.
@@ -235,18 +245,17 @@ pub fn parse_ef_test_dir(
     Ok(directory_tests)
 }
 
-/// Given the full path of a json test file, returns its path relative to the vectors directory.
-/// Panics if the file is not in the vectors directory.
+/// Given the full path of a json test file, returns its path relative to the vectors directory
+/// (or the EOF one). Panics if the file is not in either of them.
 pub fn get_test_relative_path(full_path: PathBuf) -> String {
     let mut path_prefix = PathBuf::new();
 
     for dir in full_path.components() {
-        if dir.as_os_str().to_str().unwrap() == START_DIR_NAME {
+        path_prefix.push(dir);
+        if [START_DIR_NAME, EOF_START_DIR_NAME].contains(&dir.as_os_str().to_str().unwrap()) {
             break;
         }
-        path_prefix.push(dir);
     }
-    path_prefix.push(PathBuf::from(START_DIR_NAME));
 
     full_path
         .strip_prefix(path_prefix)
@@ -273,6 +282,7 @@ fn parse_ef_test_file(
     })?;
     for test in tests_in_file.0.iter_mut() {
         test.dir = full_path.to_str().unwrap().to_string();
+        test.eof = opts.eof;
     }
 
     // We only want to include tests that have post states from the specified forks in EFTestsRunnerOptions.
@@ -166,7 +166,10 @@ pub fn prepare_vm_for_tx<'a>(
     });
 
     let blob_schedule = EVMConfig::canonical_values(*fork);
-    let config = EVMConfig::new(*fork, blob_schedule);
+    let config = EVMConfig {
+        eof_enabled: test.eof,
+        ..EVMConfig::new(*fork, blob_schedule)
+    };
 
     let tx = match authorization_list {
         Some(list) => Transaction::EIP7702Transaction(EIP7702Transaction {
@@ -87,6 +87,14 @@ pub struct EFTestRunnerOptions {
     /// For running particular tests that have their specified paths listed with the tests flag.
     #[arg(long, value_name = "PATHS", default_value = "false")]
     pub paths: bool,
+    /// For running the EOF state tests, from the EOF vectors directory and with EOF enabled.
+    #[arg(
+        long,
+        value_name = "EOF",
+        default_value = "false",
+        conflicts_with = "revm"
+    )]
+    pub eof: bool,
 }
 
 fn parse_fork(value: &str) -> Result<SpecId, String> {
@@ -106,7 +114,8 @@ pub async fn run_ef_tests(
             run_with_levm(&mut reports, &ef_tests, opts).await?;
         }
     }
-    if opts.summary {
+    // REVM doesn't support EOF, so failing EOF tests can't be re-run with it.
+    if opts.summary || opts.eof {
         if reports.iter().any(|r| !r.passed()) {
             println!(
                 "{}",
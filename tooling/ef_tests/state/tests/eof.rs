@@ -0,0 +1,13 @@
+use ef_tests_state::eof::run_eof_tests;
+use std::error::Error;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Any argument filters the tests to run by their path.
+    let filters: Vec<String> = std::env::args().skip(1).collect();
+    let report = run_eof_tests(&filters)?;
+    report.print();
+    if !report.failed.is_empty() {
+        return Err(format!("{} EOF validation tests failed", report.failed.len()).into());
+    }
+    Ok(())
+}
@@ -24,6 +24,8 @@ pub struct EFTests(pub Vec<EFTest>);
 pub struct EFTest {
     pub name: String,
     pub dir: String,
+    /// Whether the test runs with EOF enabled, which the test itself doesn't say.
+    pub eof: bool,
     pub _info: EFTestInfo,
     pub env: EFTestEnv,
     pub post: EFTestPost,
@@ -139,6 +139,7 @@ pub async fn run_test(test: &Test, test_case: &TestCase) -> Result<(), RunnerErr
         excess_blob_gas,
         parent_beacon_block_root,
         requests_hash,
+        block_access_list_hash: None,
     };
     let block = Block::new(header, body);
 